@@ -18,6 +18,7 @@ use move_core_types::{
     vm_status::StatusCode,
 };
 use move_vm_runtime::{
+    native_extensions::SnapshotExtension,
     native_functions,
     native_functions::{NativeContext, NativeFunction, NativeFunctionTable},
 };
@@ -199,7 +200,29 @@ impl<'a> NativeTableContext<'a> {
     }
 }
 
+impl<'a> SnapshotExtension for NativeTableContext<'a> {
+    fn snapshot(&self) -> PartialVMResult<Self> {
+        Ok(Self {
+            resolver: self.resolver,
+            txn_hash: self.txn_hash,
+            table_data: RefCell::new(self.table_data.borrow().copy_value()?),
+        })
+    }
+}
+
 impl TableData {
+    fn copy_value(&self) -> PartialVMResult<Self> {
+        Ok(Self {
+            new_tables: self.new_tables.clone(),
+            removed_tables: self.removed_tables.clone(),
+            tables: self
+                .tables
+                .iter()
+                .map(|(handle, table)| Ok((*handle, table.copy_value()?)))
+                .collect::<PartialVMResult<_>>()?,
+        })
+    }
+
     /// Gets or creates a new table in the TableData. This initializes information about
     /// the table, like the type layout for keys and values.
     fn get_or_create_table(
@@ -227,6 +250,19 @@ impl TableData {
 }
 
 impl Table {
+    fn copy_value(&self) -> PartialVMResult<Self> {
+        Ok(Self {
+            handle: self.handle,
+            key_layout: self.key_layout.clone(),
+            value_layout: self.value_layout.clone(),
+            content: self
+                .content
+                .iter()
+                .map(|(key, gv)| Ok((key.clone(), gv.copy_value()?)))
+                .collect::<PartialVMResult<_>>()?,
+        })
+    }
+
     fn get_or_create_global_value(
         &mut self,
         context: &NativeTableContext,
@@ -402,13 +438,25 @@ fn native_add_box(
 
     let key_bytes = serialize(&table.key_layout, &key)?;
     cost += gas_params.per_byte_serialized * NumBytes::new(key_bytes.len() as u64);
+    let memory_key = entry_memory_key(handle, &key_bytes);
 
     let (gv, loaded) = table.get_or_create_global_value(table_context, key_bytes)?;
     cost += common_gas_params.calculate_load_cost(loaded);
 
-    match gv.move_to(val) {
-        Ok(_) => Ok(NativeResult::ok(cost, smallvec![])),
-        Err(_) => Ok(NativeResult::err(cost, ALREADY_EXISTS)),
+    let existing = held_value(gv)?;
+    let added = match gv.move_to(val) {
+        Ok(_) => held_value(gv)?,
+        Err(_) => None,
+    };
+    drop(table_data);
+
+    context.use_global_memory(memory_key, existing.as_ref().map(|r| r.value_view()))?;
+    match added {
+        Some(added) => {
+            context.use_memory(added.value_view())?;
+            Ok(NativeResult::ok(cost, smallvec![]))
+        }
+        None => Ok(NativeResult::err(cost, ALREADY_EXISTS)),
     }
 }
 
@@ -451,11 +499,17 @@ fn native_borrow_box(
 
     let key_bytes = serialize(&table.key_layout, &key)?;
     cost += gas_params.per_byte_serialized * NumBytes::new(key_bytes.len() as u64);
+    let memory_key = entry_memory_key(handle, &key_bytes);
 
     let (gv, loaded) = table.get_or_create_global_value(table_context, key_bytes)?;
     cost += common_gas_params.calculate_load_cost(loaded);
 
-    match gv.borrow_global() {
+    let existing = held_value(gv)?;
+    let res = gv.borrow_global();
+    drop(table_data);
+
+    context.use_global_memory(memory_key, existing.as_ref().map(|r| r.value_view()))?;
+    match res {
         Ok(ref_val) => Ok(NativeResult::ok(cost, smallvec![ref_val])),
         Err(_) => Ok(NativeResult::err(cost, NOT_FOUND)),
     }
@@ -500,11 +554,16 @@ fn native_contains_box(
 
     let key_bytes = serialize(&table.key_layout, &key)?;
     cost += gas_params.per_byte_serialized * NumBytes::new(key_bytes.len() as u64);
+    let memory_key = entry_memory_key(handle, &key_bytes);
 
     let (gv, loaded) = table.get_or_create_global_value(table_context, key_bytes)?;
     cost += common_gas_params.calculate_load_cost(loaded);
 
-    let exists = Value::bool(gv.exists()?);
+    let existing = held_value(gv)?;
+    drop(table_data);
+
+    context.use_global_memory(memory_key, existing.as_ref().map(|r| r.value_view()))?;
+    let exists = Value::bool(existing.is_some());
 
     Ok(NativeResult::ok(cost, smallvec![exists]))
 }
@@ -548,13 +607,26 @@ fn native_remove_box(
 
     let key_bytes = serialize(&table.key_layout, &key)?;
     cost += gas_params.per_byte_serialized * NumBytes::new(key_bytes.len() as u64);
+    let memory_key = entry_memory_key(handle, &key_bytes);
 
     let (gv, loaded) = table.get_or_create_global_value(table_context, key_bytes)?;
     cost += common_gas_params.calculate_load_cost(loaded);
 
-    match gv.move_from() {
-        Ok(val) => Ok(NativeResult::ok(cost, smallvec![val])),
-        Err(_) => Ok(NativeResult::err(cost, NOT_FOUND)),
+    // The entry cannot be borrowed while moving from it, so the removed value is the one
+    // accounted for on a first access
+    let res = gv.move_from();
+    drop(table_data);
+
+    match res {
+        Ok(val) => {
+            context.use_global_memory(memory_key, Some(&val))?;
+            context.release_memory(&val)?;
+            Ok(NativeResult::ok(cost, smallvec![val]))
+        }
+        Err(_) => {
+            context.use_global_memory(memory_key, None::<&Value>)?;
+            Ok(NativeResult::err(cost, NOT_FOUND))
+        }
     }
 }
 
@@ -683,6 +755,19 @@ fn get_table_handle(table: &StructRef) -> PartialVMResult<TableHandle> {
     Ok(TableHandle(handle))
 }
 
+/// Identifies the entry `key_bytes` of the table `handle` for memory accounting.
+fn entry_memory_key(handle: TableHandle, key_bytes: &[u8]) -> Vec<u8> {
+    [b"table".as_slice(), handle.0.as_ref(), key_bytes].concat()
+}
+
+/// Returns a reference to the value the table entry `gv` holds, if any, for memory accounting.
+fn held_value(gv: &GlobalValue) -> PartialVMResult<Option<Reference>> {
+    if !gv.exists()? {
+        return Ok(None);
+    }
+    Ok(Some(gv.borrow_global()?.value_as::<Reference>()?))
+}
+
 fn serialize(layout: &MoveTypeLayout, val: &Value) -> PartialVMResult<Vec<u8>> {
     val.simple_serialize(layout)
         .ok_or_else(|| partial_extension_error("cannot serialize table key or value"))
@@ -11,7 +11,10 @@ use move_compiler::parser::{
     lexer::{Lexer, Tok},
 };
 use move_symbol_pool::Symbol;
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
 /// Constructs an `lsp_types::CompletionItem` with the given `label` and `kind`.
 fn completion_item(label: &str, kind: CompletionItemKind) -> CompletionItem {
@@ -60,6 +63,16 @@ fn builtins() -> Vec<CompletionItem> {
         .collect()
 }
 
+/// Return a list of completion items corresponding to the functions that can be called with the
+/// method call syntax on the identifier ending at the given column (exclusive) of the given line.
+fn methods(symbols: &Symbols, path: &Path, line: u32, col_end: u32) -> Vec<CompletionItem> {
+    symbols
+        .method_names(path, line, col_end)
+        .iter()
+        .map(|name| completion_item(name.as_str(), CompletionItemKind::Method))
+        .collect()
+}
+
 /// Lexes the Move source file at the given path and returns a list of completion items
 /// corresponding to the non-keyword identifiers therein.
 ///
@@ -173,9 +186,19 @@ pub fn on_completion_request(context: &Context, request: &Request, symbols: &Sym
         Some(Tok::Colon) => {
             items.extend_from_slice(&primitive_types());
         }
-        Some(Tok::Period) | Some(Tok::ColonColon) => {
-            // `.` or `::` must be followed by identifiers, which are added to the completion items
-            // below.
+        Some(Tok::Period) => {
+            // `.` may be followed by a method call on the identifier that precedes it. Other
+            // identifiers, such as field names, are added to the completion items below.
+            let position = &parameters.text_document_position.position;
+            items.extend_from_slice(&methods(
+                symbols,
+                &path,
+                position.line,
+                position.character - 1,
+            ));
+        }
+        Some(Tok::ColonColon) => {
+            // `::` must be followed by identifiers, which are added to the completion items below.
         }
         _ => {
             // If the user's cursor is positioned anywhere other than following a `.`, `:`, or `::`,
@@ -77,7 +77,9 @@ use url::Url;
 use move_command_line_common::files::FileHash;
 use move_compiler::{
     expansion::ast::{Address, Fields, ModuleIdent, ModuleIdent_},
-    naming::ast::{StructDefinition, StructFields, TParam, Type, TypeName_, Type_},
+    naming::ast::{
+        BuiltinTypeName_, StructDefinition, StructFields, TParam, Type, TypeName_, Type_,
+    },
     parser::ast::StructName,
     shared::Identifier,
     typing::ast::{
@@ -86,6 +88,7 @@ use move_compiler::{
     },
     PASS_TYPING,
 };
+use move_core_types::account_address::AccountAddress;
 use move_ir_types::location::*;
 use move_package::compilation::build_plan::BuildPlan;
 use move_symbol_pool::Symbol;
@@ -342,6 +345,11 @@ fn type_to_ide_string(sp!(_, t): &Type) -> String {
                 )
             }
         },
+        Type_::Fun(args, result) => format!(
+            "|{}|{}",
+            type_list_to_ide_string(args),
+            type_to_ide_string(result)
+        ),
         Type_::Anything => "_".to_string(),
         Type_::Var(_) => "invalid type (var)".to_string(),
         Type_::UnresolvedError => "invalid type (unresolved)".to_string(),
@@ -606,6 +614,69 @@ impl Symbols {
     pub fn file_mods(&self) -> &BTreeMap<PathBuf, BTreeSet<ModuleDefs>> {
         &self.file_mods
     }
+
+    /// Returns the functions that can be called with the method call syntax on the identifier
+    /// ending at the given column (exclusive) of the given line, i.e. the functions of the module
+    /// defining the identifier's type whose first parameter is of this type or a reference to it.
+    pub fn method_names(&self, path: &Path, line: u32, col_end: u32) -> Vec<Symbol> {
+        let use_type = self
+            .file_use_defs
+            .get(path)
+            .and_then(|mod_symbols| mod_symbols.get(line))
+            .and_then(|uses| uses.into_iter().find(|u| u.col_end == col_end))
+            .map(|u| u.use_type);
+        let receiver_tn = match &use_type {
+            Some(IdentType::RegularType(ty)) => match receiver_type_name(ty) {
+                Some(tn) => tn,
+                None => return vec![],
+            },
+            _ => return vec![],
+        };
+        self.file_mods
+            .values()
+            .flatten()
+            .filter(|mod_defs| match receiver_tn {
+                TypeName_::ModuleType(sp!(_, m), _) => &mod_defs.name == m,
+                _ => is_vector_module(&mod_defs.name),
+            })
+            .flat_map(|mod_defs| mod_defs.functions.values())
+            .filter_map(|fdef| match &fdef.ident_type {
+                IdentType::FunctionType(_, _, _, _, arg_types, _, _)
+                    if arg_types.first().and_then(receiver_type_name) == Some(receiver_tn) =>
+                {
+                    Some(fdef.name)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// The name of the type that a method can be called on for a receiver of the given type, i.e. the
+/// name of the type itself or of the type it refers to. Only struct types and vectors have methods.
+fn receiver_type_name(sp!(_, t): &Type) -> Option<&TypeName_> {
+    match t {
+        Type_::Ref(_, inner) => receiver_type_name(inner),
+        Type_::Apply(_, sp!(_, tn @ TypeName_::ModuleType(_, _)), _) => Some(tn),
+        Type_::Apply(_, sp!(_, tn @ TypeName_::Builtin(sp!(_, BuiltinTypeName_::Vector))), _) => {
+            Some(tn)
+        }
+        _ => None,
+    }
+}
+
+/// Whether the module is `std::vector`, which defines the methods on vectors
+fn is_vector_module(m: &ModuleIdent_) -> bool {
+    let is_std = match &m.address {
+        Address::Numerical(Some(sp!(_, n)), _) | Address::NamedUnassigned(sp!(_, n))
+            if n.as_str() == "std" =>
+        {
+            true
+        }
+        Address::Numerical(_, sp!(_, a)) => a.into_inner() == AccountAddress::ONE,
+        _ => false,
+    };
+    is_std && m.module.value().as_str() == "vector"
 }
 
 impl Symbolicator {
@@ -1287,6 +1358,12 @@ impl Symbolicator {
                     define, ident, name, tparams, fields, scope, references, use_defs,
                 );
             }
+            LValue_::UnpackVariant(_, _, _, _, fields)
+            | LValue_::BorrowUnpackVariant(_, _, _, _, _, fields) => {
+                for (_, _, (_, (_, lvalue))) in fields {
+                    self.lvalue_symbols(define, lvalue, scope, references, use_defs);
+                }
+            }
             LValue_::Ignore => (),
         }
     }
@@ -1382,6 +1459,17 @@ impl Symbolicator {
                 exp.ty.clone(),
             ),
             E::ModuleCall(mod_call) => self.mod_call_symbols(mod_call, scope, references, use_defs),
+            E::VarCall(var, args) => {
+                self.add_local_use_def(
+                    &var.value(),
+                    &var.loc(),
+                    references,
+                    scope,
+                    use_defs,
+                    exp.ty.clone(),
+                );
+                self.exp_symbols(args, scope, references, use_defs);
+            }
             E::Builtin(builtin_fun, exp) => {
                 use BuiltinFunction_ as BF;
                 match &builtin_fun.value {
@@ -1410,6 +1498,16 @@ impl Symbolicator {
             E::Loop { has_break: _, body } => {
                 self.exp_symbols(body, scope, references, use_defs);
             }
+            E::Match(subject, arms) => {
+                self.exp_symbols(subject, scope, references, use_defs);
+                for arm in arms {
+                    let (pattern, rhs) = &arm.value;
+                    // each arm is a new var scope
+                    let mut new_scope = scope.clone();
+                    self.lvalue_symbols(true, pattern, &mut new_scope, references, use_defs);
+                    self.exp_symbols(rhs, &mut new_scope, references, use_defs);
+                }
+            }
             E::Block(sequence) => {
                 // a block is a new var scope
                 let mut new_scope = scope.clone();
@@ -1417,6 +1515,12 @@ impl Symbolicator {
                     self.seq_item_symbols(&mut new_scope, seq_item, references, use_defs);
                 }
             }
+            E::Lambda(lvalues, body) => {
+                // a lambda is a new var scope
+                let mut new_scope = scope.clone();
+                self.lvalue_list_symbols(true, lvalues, &mut new_scope, references, use_defs);
+                self.exp_symbols(body, &mut new_scope, references, use_defs);
+            }
             E::Assign(lvalues, opt_types, e) => {
                 self.lvalue_list_symbols(false, lvalues, scope, references, use_defs);
                 for opt_t in opt_types {
@@ -1450,6 +1554,14 @@ impl Symbolicator {
             E::Pack(ident, name, tparams, fields) => {
                 self.pack_symbols(ident, name, tparams, fields, scope, references, use_defs);
             }
+            E::PackVariant(_, _, _, _, fields) => {
+                for (_, _, (_, (_, exp))) in fields {
+                    self.exp_symbols(exp, scope, references, use_defs);
+                }
+            }
+            E::TestVariant(_, _, _, _, exp) => {
+                self.exp_symbols(exp, scope, references, use_defs);
+            }
             E::ExpList(list_items) => {
                 for item in list_items {
                     let exp = match item {
@@ -3677,3 +3789,45 @@ fn symbols_test() {
         None,
     );
 }
+
+#[test]
+/// Tests if method calls are symbolicated and if methods are found for receivers of struct and
+/// vector types.
+fn method_test() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) = Symbolicator::get_symbols(path.as_path()).unwrap();
+    let symbols = symbols_opt.unwrap();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M8.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+
+    let mod_symbols = symbols.file_use_defs.get(&cpath).unwrap();
+
+    // method name
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        1,
+        10,
+        10,
+        5,
+        15,
+        "M8.move",
+        "fun Symbols::M8::incr(c: &mut Symbols::M8::Counter)",
+        None,
+    );
+
+    // methods of a struct
+    let methods = symbols.method_names(&cpath, 10, 9);
+    assert!(methods == vec![Symbol::from("count"), Symbol::from("incr")]);
+
+    // methods of a vector
+    let methods = symbols.method_names(&cpath, 11, 21);
+    assert!(methods.contains(&Symbol::from("length")));
+    assert!(methods.contains(&Symbol::from("push_back")));
+    assert!(!methods.contains(&Symbol::from("incr")));
+}
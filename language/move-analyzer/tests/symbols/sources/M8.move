@@ -0,0 +1,14 @@
+module Symbols::M8 {
+
+    struct Counter has drop { count: u64 }
+
+    public fun count(c: &Counter): u64 { c.count }
+    public fun incr(c: &mut Counter) { c.count = c.count + 1 }
+    public fun new(count: u64): Counter { Counter { count: count } }
+
+    fun methods(v: vector<u64>): u64 {
+        let c = new(0);
+        c.incr();
+        c.count() + v.length()
+    }
+}
@@ -8,12 +8,14 @@ use crate::{
     errors::{PartialVMError, PartialVMResult},
     file_format::{
         AbilitySet, AddressIdentifierIndex, CodeUnit, CompiledScript, Constant, ConstantPoolIndex,
+        EnumDefInstantiation, EnumDefInstantiationIndex, EnumDefinition, EnumDefinitionIndex,
         FieldHandle, FieldHandleIndex, FieldInstantiation, FieldInstantiationIndex,
         FunctionDefinition, FunctionDefinitionIndex, FunctionHandle, FunctionHandleIndex,
         FunctionInstantiation, FunctionInstantiationIndex, IdentifierIndex, ModuleHandle,
         ModuleHandleIndex, Signature, SignatureIndex, SignatureToken, StructDefInstantiation,
         StructDefInstantiationIndex, StructDefinition, StructDefinitionIndex, StructHandle,
-        StructHandleIndex,
+        StructHandleIndex, VariantDefinition, VariantHandle, VariantHandleIndex,
+        VariantInstantiationHandle, VariantInstantiationHandleIndex, VariantTag,
     },
     CompiledModule,
 };
@@ -246,6 +248,90 @@ impl<'a> BinaryIndexedView<'a> {
         }
     }
 
+    pub fn enum_defs(&self) -> Option<&[EnumDefinition]> {
+        match self {
+            BinaryIndexedView::Module(module) => Some(module.enum_defs()),
+            BinaryIndexedView::Script(_) => None,
+        }
+    }
+
+    pub fn enum_def_at(&self, idx: EnumDefinitionIndex) -> PartialVMResult<&EnumDefinition> {
+        match self {
+            BinaryIndexedView::Module(module) => Ok(module.enum_def_at(idx)),
+            BinaryIndexedView::Script(_) => {
+                Err(PartialVMError::new(StatusCode::INVALID_OPERATION_IN_SCRIPT))
+            }
+        }
+    }
+
+    /// Returns the definition of variant `variant` of the enum at `enum_def`
+    pub fn variant_def_at(
+        &self,
+        enum_def: EnumDefinitionIndex,
+        variant: VariantTag,
+    ) -> PartialVMResult<&VariantDefinition> {
+        self.enum_def_at(enum_def)?.variant(variant).ok_or_else(|| {
+            PartialVMError::new(StatusCode::INDEX_OUT_OF_BOUNDS).with_message(format!(
+                "variant {} of enum {} not found",
+                variant, enum_def
+            ))
+        })
+    }
+
+    pub fn enum_instantiations(&self) -> Option<&[EnumDefInstantiation]> {
+        match self {
+            BinaryIndexedView::Module(module) => Some(module.enum_instantiations()),
+            BinaryIndexedView::Script(_) => None,
+        }
+    }
+
+    pub fn enum_instantiation_at(
+        &self,
+        idx: EnumDefInstantiationIndex,
+    ) -> PartialVMResult<&EnumDefInstantiation> {
+        match self {
+            BinaryIndexedView::Module(module) => Ok(module.enum_instantiation_at(idx)),
+            BinaryIndexedView::Script(_) => {
+                Err(PartialVMError::new(StatusCode::INVALID_OPERATION_IN_SCRIPT))
+            }
+        }
+    }
+
+    pub fn variant_handles(&self) -> Option<&[VariantHandle]> {
+        match self {
+            BinaryIndexedView::Module(module) => Some(module.variant_handles()),
+            BinaryIndexedView::Script(_) => None,
+        }
+    }
+
+    pub fn variant_handle_at(&self, idx: VariantHandleIndex) -> PartialVMResult<&VariantHandle> {
+        match self {
+            BinaryIndexedView::Module(module) => Ok(module.variant_handle_at(idx)),
+            BinaryIndexedView::Script(_) => {
+                Err(PartialVMError::new(StatusCode::INVALID_OPERATION_IN_SCRIPT))
+            }
+        }
+    }
+
+    pub fn variant_instantiation_handles(&self) -> Option<&[VariantInstantiationHandle]> {
+        match self {
+            BinaryIndexedView::Module(module) => Some(module.variant_instantiation_handles()),
+            BinaryIndexedView::Script(_) => None,
+        }
+    }
+
+    pub fn variant_instantiation_handle_at(
+        &self,
+        idx: VariantInstantiationHandleIndex,
+    ) -> PartialVMResult<&VariantInstantiationHandle> {
+        match self {
+            BinaryIndexedView::Module(module) => Ok(module.variant_instantiation_handle_at(idx)),
+            BinaryIndexedView::Script(_) => {
+                Err(PartialVMError::new(StatusCode::INVALID_OPERATION_IN_SCRIPT))
+            }
+        }
+    }
+
     // Return the `AbilitySet` of a `SignatureToken` given a context.
     // A `TypeParameter` has the abilities of its `constraints`.
     // `StructInstantiation` abilities are predicated on the particular instantiation
@@ -10,9 +10,11 @@ use crate::{
     },
     file_format::{
         AbilitySet, Bytecode, CodeOffset, CodeUnit, CompiledModule, CompiledScript, Constant,
-        FieldHandle, FieldInstantiation, FunctionDefinition, FunctionDefinitionIndex,
-        FunctionHandle, FunctionInstantiation, LocalIndex, ModuleHandle, Signature, SignatureToken,
-        StructDefInstantiation, StructDefinition, StructFieldInformation, StructHandle, TableIndex,
+        EnumDefInstantiation, EnumDefinition, EnumDefinitionIndex, FieldHandle, FieldInstantiation,
+        FunctionDefinition, FunctionDefinitionIndex, FunctionHandle, FunctionInstantiation,
+        LocalIndex, ModuleHandle, Signature, SignatureToken, StructDefInstantiation,
+        StructDefinition, StructFieldInformation, StructHandle, TableIndex,
+        VariantInstantiationHandle, VariantTag,
     },
     internals::ModuleIndex,
     IndexKind,
@@ -90,6 +92,10 @@ impl<'a> BoundsChecker<'a> {
         self.check_function_instantiations()?;
         self.check_field_instantiations()?;
         self.check_struct_defs()?;
+        self.check_enum_defs()?;
+        self.check_enum_instantiations()?;
+        self.check_variant_handles()?;
+        self.check_variant_instantiation_handles()?;
         self.check_function_defs()
     }
 
@@ -170,6 +176,40 @@ impl<'a> BoundsChecker<'a> {
         Ok(())
     }
 
+    fn check_enum_defs(&self) -> PartialVMResult<()> {
+        for enum_def in self.view.enum_defs().into_iter().flatten() {
+            self.check_enum_def(enum_def)?
+        }
+        Ok(())
+    }
+
+    fn check_enum_instantiations(&self) -> PartialVMResult<()> {
+        for enum_instantiation in self.view.enum_instantiations().into_iter().flatten() {
+            self.check_enum_instantiation(enum_instantiation)?
+        }
+        Ok(())
+    }
+
+    fn check_variant_handles(&self) -> PartialVMResult<()> {
+        for variant_handle in self.view.variant_handles().into_iter().flatten() {
+            check_bounds_impl_opt(&self.view.enum_defs(), variant_handle.enum_def)?;
+            self.check_variant_tag(variant_handle.enum_def, variant_handle.variant)?
+        }
+        Ok(())
+    }
+
+    fn check_variant_instantiation_handles(&self) -> PartialVMResult<()> {
+        for variant_inst in self
+            .view
+            .variant_instantiation_handles()
+            .into_iter()
+            .flatten()
+        {
+            self.check_variant_instantiation_handle(variant_inst)?
+        }
+        Ok(())
+    }
+
     fn check_function_defs(&mut self) -> PartialVMResult<()> {
         let view = self.view;
         for (function_def_idx, function_def) in
@@ -257,6 +297,45 @@ impl<'a> BoundsChecker<'a> {
         check_bounds_impl(self.view.signatures(), struct_instantiation.type_parameters)
     }
 
+    fn check_enum_instantiation(
+        &self,
+        enum_instantiation: &EnumDefInstantiation,
+    ) -> PartialVMResult<()> {
+        check_bounds_impl_opt(&self.view.enum_defs(), enum_instantiation.def)?;
+        check_bounds_impl(self.view.signatures(), enum_instantiation.type_parameters)
+    }
+
+    fn check_variant_instantiation_handle(
+        &self,
+        variant_inst: &VariantInstantiationHandle,
+    ) -> PartialVMResult<()> {
+        check_bounds_impl_opt(&self.view.enum_instantiations(), variant_inst.enum_def)?;
+        if let Some(enum_inst) = self
+            .view
+            .enum_instantiations()
+            .and_then(|i| i.get(variant_inst.enum_def.into_index()))
+        {
+            self.check_variant_tag(enum_inst.def, variant_inst.variant)?
+        }
+        Ok(())
+    }
+
+    // the variant tag must be in bounds for the enum, if the enum definition exists
+    fn check_variant_tag(&self, def: EnumDefinitionIndex, tag: VariantTag) -> PartialVMResult<()> {
+        if let Some(enum_def) = self.view.enum_defs().and_then(|d| d.get(def.into_index())) {
+            let variant_count = enum_def.variants.len();
+            if tag as usize >= variant_count {
+                return Err(bounds_error(
+                    StatusCode::INDEX_OUT_OF_BOUNDS,
+                    IndexKind::VariantHandle,
+                    tag,
+                    variant_count,
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn check_function_instantiation(
         &self,
         function_instantiation: &FunctionInstantiation,
@@ -306,6 +385,24 @@ impl<'a> BoundsChecker<'a> {
         Ok(())
     }
 
+    fn check_enum_def(&self, enum_def: &EnumDefinition) -> PartialVMResult<()> {
+        check_bounds_impl(self.view.struct_handles(), enum_def.enum_handle)?;
+        let type_param_count = self
+            .view
+            .struct_handles()
+            .get(enum_def.enum_handle.into_index())
+            .map_or(0, |sh| sh.type_parameters.len());
+        for variant in &enum_def.variants {
+            check_bounds_impl(self.view.identifiers(), variant.name)?;
+            for field in &variant.fields {
+                check_bounds_impl(self.view.identifiers(), field.name)?;
+                self.check_type(&field.signature.0)?;
+                self.check_type_parameter(&field.signature.0, type_param_count)?;
+            }
+        }
+        Ok(())
+    }
+
     fn check_function_def(
         &mut self,
         function_def_idx: usize,
@@ -477,6 +574,43 @@ impl<'a> BoundsChecker<'a> {
                         }
                     }
                 }
+                PackVariant(idx)
+                | UnpackVariant(idx)
+                | UnpackVariantImmRef(idx)
+                | UnpackVariantMutRef(idx)
+                | TestVariant(idx) => self.check_code_unit_bounds_impl_opt(
+                    &self.view.variant_handles(),
+                    *idx,
+                    bytecode_offset,
+                )?,
+                PackVariantGeneric(idx)
+                | UnpackVariantGeneric(idx)
+                | UnpackVariantImmRefGeneric(idx)
+                | UnpackVariantMutRefGeneric(idx)
+                | TestVariantGeneric(idx) => {
+                    self.check_code_unit_bounds_impl_opt(
+                        &self.view.variant_instantiation_handles(),
+                        *idx,
+                        bytecode_offset,
+                    )?;
+                    // check type parameters in variant operations are bound to the function type
+                    // parameters
+                    if let Some(sig) = self
+                        .view
+                        .variant_instantiation_handles()
+                        .and_then(|v| v.get(idx.into_index()))
+                        .and_then(|v| {
+                            self.view
+                                .enum_instantiations()
+                                .and_then(|e| e.get(v.enum_def.into_index()))
+                        })
+                        .and_then(|e| self.view.signatures().get(e.type_parameters.into_index()))
+                    {
+                        for ty in &sig.0 {
+                            self.check_type_parameter(ty, type_param_count)?
+                        }
+                    }
+                }
                 // Instructions that refer to this code block.
                 BrTrue(offset) | BrFalse(offset) | Branch(offset) => {
                     let offset = *offset as usize;
@@ -128,6 +128,32 @@ impl Compatibility {
         // we can remove/change a friend function if the function is not used by any module in the
         // friend list. But for simplicity, we decided to go to the more restrictive form now and
         // we may revisit this in the future.
+        // old module's enums are a subset of the new module's enums, with the same checks as
+        // for structs
+        for (name, old_enum) in &old_module.enums {
+            let new_enum = match new_module.enums.get(name) {
+                Some(new_enum) => new_enum,
+                None => {
+                    struct_and_pub_function_linking = false;
+                    struct_layout = false;
+                    break;
+                }
+            };
+
+            if !struct_abilities_compatibile(old_enum.abilities, new_enum.abilities)
+                || !struct_type_parameters_compatibile(
+                    &old_enum.type_parameters,
+                    &new_enum.type_parameters,
+                )
+            {
+                struct_and_pub_function_linking = false;
+            }
+            if new_enum.variants != old_enum.variants {
+                // Variants or their fields changed. Stored values may no longer be readable
+                struct_layout = false
+            }
+        }
+
         for (name, old_func) in &old_module.exposed_functions {
             let new_func = match new_module.exposed_functions.get(name) {
                 Some(new_func) => new_func,
@@ -239,6 +239,40 @@ fn load_struct_def_inst_index(
     )?))
 }
 
+fn load_enum_def_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<EnumDefinitionIndex> {
+    Ok(EnumDefinitionIndex(read_uleb_internal(
+        cursor,
+        ENUM_DEF_INDEX_MAX,
+    )?))
+}
+
+fn load_enum_def_inst_index(
+    cursor: &mut VersionedCursor,
+) -> BinaryLoaderResult<EnumDefInstantiationIndex> {
+    Ok(EnumDefInstantiationIndex(read_uleb_internal(
+        cursor,
+        ENUM_DEF_INST_INDEX_MAX,
+    )?))
+}
+
+fn load_variant_handle_index(
+    cursor: &mut VersionedCursor,
+) -> BinaryLoaderResult<VariantHandleIndex> {
+    Ok(VariantHandleIndex(read_uleb_internal(
+        cursor,
+        VARIANT_HANDLE_INDEX_MAX,
+    )?))
+}
+
+fn load_variant_inst_handle_index(
+    cursor: &mut VersionedCursor,
+) -> BinaryLoaderResult<VariantInstantiationHandleIndex> {
+    Ok(VariantInstantiationHandleIndex(read_uleb_internal(
+        cursor,
+        VARIANT_INST_HANDLE_INDEX_MAX,
+    )?))
+}
+
 fn load_constant_pool_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<ConstantPoolIndex> {
     Ok(ConstantPoolIndex(read_uleb_internal(
         cursor,
@@ -262,6 +296,14 @@ fn load_field_count(cursor: &mut VersionedCursor) -> BinaryLoaderResult<u64> {
     read_uleb_internal(cursor, FIELD_COUNT_MAX)
 }
 
+fn load_variant_count(cursor: &mut VersionedCursor) -> BinaryLoaderResult<u64> {
+    read_uleb_internal(cursor, VARIANT_COUNT_MAX)
+}
+
+fn load_variant_tag(cursor: &mut VersionedCursor) -> BinaryLoaderResult<VariantTag> {
+    read_uleb_internal(cursor, VARIANT_COUNT_MAX)
+}
+
 fn load_type_parameter_count(cursor: &mut VersionedCursor) -> BinaryLoaderResult<usize> {
     read_uleb_internal(cursor, TYPE_PARAMETER_COUNT_MAX)
 }
@@ -605,6 +647,21 @@ fn build_common_tables(
             | TableType::STRUCT_DEF_INST
             | TableType::FIELD_HANDLE
             | TableType::FIELD_INST => continue,
+            TableType::ENUM_DEFS
+            | TableType::ENUM_DEF_INST
+            | TableType::VARIANT_HANDLES
+            | TableType::VARIANT_INST_HANDLES => {
+                // enums do not exist before VERSION_7
+                if binary.version() < VERSION_7 {
+                    return Err(
+                        PartialVMError::new(StatusCode::MALFORMED).with_message(format!(
+                            "Enum declarations not applicable in bytecode version {}",
+                            binary.version()
+                        )),
+                    );
+                }
+                continue;
+            }
             TableType::FRIEND_DECLS => {
                 // friend declarations do not exist before VERSION_2
                 if binary.version() < VERSION_2 {
@@ -645,6 +702,22 @@ fn build_module_tables(
             TableType::FRIEND_DECLS => {
                 load_module_handles(binary, table, &mut module.friend_decls)?;
             }
+            TableType::ENUM_DEFS => {
+                load_enum_defs(binary, table, &mut module.enum_defs)?;
+            }
+            TableType::ENUM_DEF_INST => {
+                load_enum_instantiations(binary, table, &mut module.enum_def_instantiations)?;
+            }
+            TableType::VARIANT_HANDLES => {
+                load_variant_handles(binary, table, &mut module.variant_handles)?;
+            }
+            TableType::VARIANT_INST_HANDLES => {
+                load_variant_instantiation_handles(
+                    binary,
+                    table,
+                    &mut module.variant_instantiation_handles,
+                )?;
+            }
             TableType::MODULE_HANDLES
             | TableType::STRUCT_HANDLES
             | TableType::FUNCTION_HANDLES
@@ -685,7 +758,11 @@ fn build_script_tables(
             | TableType::FUNCTION_DEFS
             | TableType::FIELD_INST
             | TableType::FIELD_HANDLE
-            | TableType::FRIEND_DECLS => {
+            | TableType::FRIEND_DECLS
+            | TableType::ENUM_DEFS
+            | TableType::ENUM_DEF_INST
+            | TableType::VARIANT_HANDLES
+            | TableType::VARIANT_INST_HANDLES => {
                 return Err(PartialVMError::new(StatusCode::MALFORMED)
                     .with_message("Bad table in Script".to_string()));
             }
@@ -1328,6 +1405,86 @@ fn load_field_instantiations(
     Ok(())
 }
 
+/// Builds the `EnumDefinition` table.
+fn load_enum_defs(
+    binary: &VersionedBinary,
+    table: &Table,
+    enum_defs: &mut Vec<EnumDefinition>,
+) -> BinaryLoaderResult<()> {
+    let start = table.offset as usize;
+    let end = start + table.count as usize;
+    let mut cursor = binary.new_cursor(start, end);
+    while cursor.position() < u64::from(table.count) {
+        let enum_handle = load_struct_handle_index(&mut cursor)?;
+        let variant_count = load_variant_count(&mut cursor)?;
+        let mut variants = vec![];
+        for _ in 0..variant_count {
+            let name = load_identifier_index(&mut cursor)?;
+            let fields = load_field_defs(&mut cursor)?;
+            variants.push(VariantDefinition { name, fields });
+        }
+        enum_defs.push(EnumDefinition {
+            enum_handle,
+            variants,
+        });
+    }
+    Ok(())
+}
+
+/// Builds the `EnumDefInstantiation` table.
+fn load_enum_instantiations(
+    binary: &VersionedBinary,
+    table: &Table,
+    enum_insts: &mut Vec<EnumDefInstantiation>,
+) -> BinaryLoaderResult<()> {
+    let start = table.offset as usize;
+    let end = start + table.count as usize;
+    let mut cursor = binary.new_cursor(start, end);
+    while cursor.position() < u64::from(table.count) {
+        let def = load_enum_def_index(&mut cursor)?;
+        let type_parameters = load_signature_index(&mut cursor)?;
+        enum_insts.push(EnumDefInstantiation {
+            def,
+            type_parameters,
+        });
+    }
+    Ok(())
+}
+
+/// Builds the `VariantHandle` table.
+fn load_variant_handles(
+    binary: &VersionedBinary,
+    table: &Table,
+    variant_handles: &mut Vec<VariantHandle>,
+) -> BinaryLoaderResult<()> {
+    let start = table.offset as usize;
+    let end = start + table.count as usize;
+    let mut cursor = binary.new_cursor(start, end);
+    while cursor.position() < u64::from(table.count) {
+        let enum_def = load_enum_def_index(&mut cursor)?;
+        let variant = load_variant_tag(&mut cursor)?;
+        variant_handles.push(VariantHandle { enum_def, variant });
+    }
+    Ok(())
+}
+
+/// Builds the `VariantInstantiationHandle` table.
+fn load_variant_instantiation_handles(
+    binary: &VersionedBinary,
+    table: &Table,
+    variant_insts: &mut Vec<VariantInstantiationHandle>,
+) -> BinaryLoaderResult<()> {
+    let start = table.offset as usize;
+    let end = start + table.count as usize;
+    let mut cursor = binary.new_cursor(start, end);
+    while cursor.position() < u64::from(table.count) {
+        let enum_def = load_enum_def_inst_index(&mut cursor)?;
+        let variant = load_variant_tag(&mut cursor)?;
+        variant_insts.push(VariantInstantiationHandle { enum_def, variant });
+    }
+    Ok(())
+}
+
 /// Deserializes a `FunctionDefinition`.
 fn load_function_def(cursor: &mut VersionedCursor) -> BinaryLoaderResult<FunctionDefinition> {
     let function = load_function_handle_index(cursor)?;
@@ -1475,6 +1632,25 @@ fn load_code(cursor: &mut VersionedCursor, code: &mut Vec<Bytecode>) -> BinaryLo
                     )),
                 );
             }
+            Opcodes::PACK_VARIANT
+            | Opcodes::PACK_VARIANT_GENERIC
+            | Opcodes::UNPACK_VARIANT
+            | Opcodes::UNPACK_VARIANT_GENERIC
+            | Opcodes::UNPACK_VARIANT_IMM_REF
+            | Opcodes::UNPACK_VARIANT_IMM_REF_GENERIC
+            | Opcodes::UNPACK_VARIANT_MUT_REF
+            | Opcodes::UNPACK_VARIANT_MUT_REF_GENERIC
+            | Opcodes::TEST_VARIANT
+            | Opcodes::TEST_VARIANT_GENERIC
+                if (cursor.version() < VERSION_7) =>
+            {
+                return Err(
+                    PartialVMError::new(StatusCode::MALFORMED).with_message(format!(
+                        "Enum operations not supported in bytecode version {}",
+                        cursor.version()
+                    )),
+                );
+            }
             _ => (),
         };
 
@@ -1594,6 +1770,30 @@ fn load_code(cursor: &mut VersionedCursor, code: &mut Vec<Bytecode>) -> BinaryLo
             Opcodes::CAST_U16 => Bytecode::CastU16,
             Opcodes::CAST_U32 => Bytecode::CastU32,
             Opcodes::CAST_U256 => Bytecode::CastU256,
+            Opcodes::PACK_VARIANT => Bytecode::PackVariant(load_variant_handle_index(cursor)?),
+            Opcodes::PACK_VARIANT_GENERIC => {
+                Bytecode::PackVariantGeneric(load_variant_inst_handle_index(cursor)?)
+            }
+            Opcodes::UNPACK_VARIANT => Bytecode::UnpackVariant(load_variant_handle_index(cursor)?),
+            Opcodes::UNPACK_VARIANT_GENERIC => {
+                Bytecode::UnpackVariantGeneric(load_variant_inst_handle_index(cursor)?)
+            }
+            Opcodes::UNPACK_VARIANT_IMM_REF => {
+                Bytecode::UnpackVariantImmRef(load_variant_handle_index(cursor)?)
+            }
+            Opcodes::UNPACK_VARIANT_IMM_REF_GENERIC => {
+                Bytecode::UnpackVariantImmRefGeneric(load_variant_inst_handle_index(cursor)?)
+            }
+            Opcodes::UNPACK_VARIANT_MUT_REF => {
+                Bytecode::UnpackVariantMutRef(load_variant_handle_index(cursor)?)
+            }
+            Opcodes::UNPACK_VARIANT_MUT_REF_GENERIC => {
+                Bytecode::UnpackVariantMutRefGeneric(load_variant_inst_handle_index(cursor)?)
+            }
+            Opcodes::TEST_VARIANT => Bytecode::TestVariant(load_variant_handle_index(cursor)?),
+            Opcodes::TEST_VARIANT_GENERIC => {
+                Bytecode::TestVariantGeneric(load_variant_inst_handle_index(cursor)?)
+            }
         };
         code.push(bytecode);
     }
@@ -1618,6 +1818,10 @@ impl TableType {
             0xE => Ok(TableType::FIELD_INST),
             0xF => Ok(TableType::FRIEND_DECLS),
             0x10 => Ok(TableType::METADATA),
+            0x11 => Ok(TableType::ENUM_DEFS),
+            0x12 => Ok(TableType::ENUM_DEF_INST),
+            0x13 => Ok(TableType::VARIANT_HANDLES),
+            0x14 => Ok(TableType::VARIANT_INST_HANDLES),
             _ => Err(PartialVMError::new(StatusCode::UNKNOWN_TABLE_TYPE)),
         }
     }
@@ -1774,6 +1978,16 @@ impl Opcodes {
             0x4B => Ok(Opcodes::CAST_U16),
             0x4C => Ok(Opcodes::CAST_U32),
             0x4D => Ok(Opcodes::CAST_U256),
+            0x4E => Ok(Opcodes::PACK_VARIANT),
+            0x4F => Ok(Opcodes::PACK_VARIANT_GENERIC),
+            0x50 => Ok(Opcodes::UNPACK_VARIANT),
+            0x51 => Ok(Opcodes::UNPACK_VARIANT_GENERIC),
+            0x52 => Ok(Opcodes::UNPACK_VARIANT_IMM_REF),
+            0x53 => Ok(Opcodes::UNPACK_VARIANT_IMM_REF_GENERIC),
+            0x54 => Ok(Opcodes::UNPACK_VARIANT_MUT_REF),
+            0x55 => Ok(Opcodes::UNPACK_VARIANT_MUT_REF_GENERIC),
+            0x56 => Ok(Opcodes::TEST_VARIANT),
+            0x57 => Ok(Opcodes::TEST_VARIANT_GENERIC),
             _ => Err(PartialVMError::new(StatusCode::UNKNOWN_OPCODE)),
         }
     }
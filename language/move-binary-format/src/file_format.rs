@@ -159,6 +159,26 @@ define_index! {
     kind: FunctionDefinition,
     doc: "Index into the `FunctionDefinition` table.",
 }
+define_index! {
+    name: EnumDefinitionIndex,
+    kind: EnumDefinition,
+    doc: "Index into the `EnumDefinition` table.",
+}
+define_index! {
+    name: EnumDefInstantiationIndex,
+    kind: EnumDefInstantiation,
+    doc: "Index into the `EnumDefInstantiation` table.",
+}
+define_index! {
+    name: VariantHandleIndex,
+    kind: VariantHandle,
+    doc: "Index into the `VariantHandle` table.",
+}
+define_index! {
+    name: VariantInstantiationHandleIndex,
+    kind: VariantInstantiationHandle,
+    doc: "Index into the `VariantInstantiationHandle` table.",
+}
 
 /// Index of a local variable in a function.
 ///
@@ -166,6 +186,8 @@ define_index! {
 pub type LocalIndex = u8;
 /// Max number of fields in a `StructDefinition`.
 pub type MemberCount = u16;
+/// The position of a variant in its `EnumDefinition`. It is the tag of the variant at runtime.
+pub type VariantTag = u16;
 /// Index into the code stream for a jump. The offset is relative to the beginning of
 /// the instruction stream.
 pub type CodeOffset = u16;
@@ -307,6 +329,26 @@ pub struct FieldHandle {
     pub field: MemberCount,
 }
 
+/// A variant access info (owner type and tag)
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct VariantHandle {
+    pub enum_def: EnumDefinitionIndex,
+    pub variant: VariantTag,
+}
+
+/// A variant access info for a (possibly partially) instantiated generic enum
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct VariantInstantiationHandle {
+    pub enum_def: EnumDefInstantiationIndex,
+    pub variant: VariantTag,
+}
+
 // DEFINITIONS:
 // Definitions are the module code. So the set of types and functions in the module.
 
@@ -338,6 +380,16 @@ pub struct StructDefInstantiation {
     pub type_parameters: SignatureIndex,
 }
 
+/// A complete or partial instantiation of a generic enum
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct EnumDefInstantiation {
+    pub def: EnumDefinitionIndex,
+    pub type_parameters: SignatureIndex,
+}
+
 /// A complete or partial instantiation of a function
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
@@ -397,6 +449,39 @@ impl StructDefinition {
     }
 }
 
+/// An `EnumDefinition` is the definition of a tagged union type. A value of the type is exactly
+/// one of its variants, identified at runtime by its `VariantTag`, the position of the variant
+/// in `variants`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct EnumDefinition {
+    /// The `StructHandle` for this `EnumDefinition`. Enums share handles with structs, as
+    /// handles only carry the name and the abilities of the type.
+    pub enum_handle: StructHandleIndex,
+    /// The variants of the enum. There must be at least one.
+    pub variants: Vec<VariantDefinition>,
+}
+
+impl EnumDefinition {
+    pub fn variant(&self, tag: VariantTag) -> Option<&VariantDefinition> {
+        self.variants.get(tag as usize)
+    }
+}
+
+/// A `VariantDefinition` is the definition of a variant of an enum: its name and its fields.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+#[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub struct VariantDefinition {
+    /// The name of the variant.
+    pub name: IdentifierIndex,
+    /// The fields of the variant, possibly none.
+    pub fields: Vec<FieldDefinition>,
+}
+
 /// A `FieldDefinition` is the definition of a field: its name and the field type.
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
@@ -1635,6 +1720,49 @@ pub enum Bytecode {
     ///
     /// ```..., integer_value -> ..., u256_value```
     CastU256,
+    /// Create an instance of the variant specified by the handle and push it on the stack.
+    /// The values of the fields of the variant, in the order they appear in the variant
+    /// declaration, must be pushed on the stack. All fields must be provided.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., field(1)_value, field(2)_value, ..., field(n)_value -> ..., instance_value```
+    PackVariant(VariantHandleIndex),
+    PackVariantGeneric(VariantInstantiationHandleIndex),
+    /// Destroy an instance of the variant specified by the handle and push the values bound
+    /// to each field on the stack. Abort the execution if the value is a different variant.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., instance_value -> ..., field(1)_value, field(2)_value, ..., field(n)_value```
+    UnpackVariant(VariantHandleIndex),
+    UnpackVariantGeneric(VariantInstantiationHandleIndex),
+    /// Consume a reference to an instance of the variant specified by the handle and push an
+    /// immutable reference to each of its fields on the stack. Abort the execution if the value
+    /// is a different variant.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., reference -> ..., field(1)_reference, ..., field(n)_reference```
+    UnpackVariantImmRef(VariantHandleIndex),
+    UnpackVariantImmRefGeneric(VariantInstantiationHandleIndex),
+    /// Consume a mutable reference to an instance of the variant specified by the handle and
+    /// push a mutable reference to each of its fields on the stack. Abort the execution if the
+    /// value is a different variant.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., reference -> ..., field(1)_reference, ..., field(n)_reference```
+    UnpackVariantMutRef(VariantHandleIndex),
+    UnpackVariantMutRefGeneric(VariantInstantiationHandleIndex),
+    /// Consume a reference to an enum value and push whether it is the variant specified by the
+    /// handle.
+    ///
+    /// Stack transition:
+    ///
+    /// ```..., reference -> ..., bool_value```
+    TestVariant(VariantHandleIndex),
+    TestVariantGeneric(VariantInstantiationHandleIndex),
 }
 
 impl ::std::fmt::Debug for Bytecode {
@@ -1717,6 +1845,20 @@ impl ::std::fmt::Debug for Bytecode {
             Bytecode::VecPopBack(a) => write!(f, "VecPopBack({})", a),
             Bytecode::VecUnpack(a, n) => write!(f, "VecUnpack({}, {})", a, n),
             Bytecode::VecSwap(a) => write!(f, "VecSwap({})", a),
+            Bytecode::PackVariant(a) => write!(f, "PackVariant({})", a),
+            Bytecode::PackVariantGeneric(a) => write!(f, "PackVariantGeneric({})", a),
+            Bytecode::UnpackVariant(a) => write!(f, "UnpackVariant({})", a),
+            Bytecode::UnpackVariantGeneric(a) => write!(f, "UnpackVariantGeneric({})", a),
+            Bytecode::UnpackVariantImmRef(a) => write!(f, "UnpackVariantImmRef({})", a),
+            Bytecode::UnpackVariantImmRefGeneric(a) => {
+                write!(f, "UnpackVariantImmRefGeneric({})", a)
+            }
+            Bytecode::UnpackVariantMutRef(a) => write!(f, "UnpackVariantMutRef({})", a),
+            Bytecode::UnpackVariantMutRefGeneric(a) => {
+                write!(f, "UnpackVariantMutRefGeneric({})", a)
+            }
+            Bytecode::TestVariant(a) => write!(f, "TestVariant({})", a),
+            Bytecode::TestVariantGeneric(a) => write!(f, "TestVariantGeneric({})", a),
         }
     }
 }
@@ -1876,6 +2018,15 @@ pub struct CompiledModule {
     pub struct_defs: Vec<StructDefinition>,
     /// Function defined in this module.
     pub function_defs: Vec<FunctionDefinition>,
+
+    /// Enums defined in this module. Only present in binaries of version 7 or later.
+    pub enum_defs: Vec<EnumDefinition>,
+    /// Enum instantiations.
+    pub enum_def_instantiations: Vec<EnumDefInstantiation>,
+    /// Handles to variants.
+    pub variant_handles: Vec<VariantHandle>,
+    /// Handles to variants of instantiated enums.
+    pub variant_instantiation_handles: Vec<VariantInstantiationHandle>,
 }
 
 // Need a custom implementation of Arbitrary because as of proptest-derive 0.1.1, the derivation
@@ -1986,6 +2137,10 @@ impl Arbitrary for CompiledModule {
                         metadata: vec![],
                         struct_defs,
                         function_defs,
+                        enum_defs: vec![],
+                        enum_def_instantiations: vec![],
+                        variant_handles: vec![],
+                        variant_instantiation_handles: vec![],
                     }
                 },
             )
@@ -2019,6 +2174,10 @@ impl CompiledModule {
             IndexKind::Identifier => self.identifiers.len(),
             IndexKind::AddressIdentifier => self.address_identifiers.len(),
             IndexKind::ConstantPool => self.constant_pool.len(),
+            IndexKind::EnumDefinition => self.enum_defs.len(),
+            IndexKind::EnumDefInstantiation => self.enum_def_instantiations.len(),
+            IndexKind::VariantHandle => self.variant_handles.len(),
+            IndexKind::VariantInstantiationHandle => self.variant_instantiation_handles.len(),
             // XXX these two don't seem to belong here
             other @ IndexKind::LocalPool
             | other @ IndexKind::CodeDefinition
@@ -2040,6 +2199,14 @@ impl CompiledModule {
     pub fn self_id(&self) -> ModuleId {
         self.module_id_for_handle(self.self_handle())
     }
+
+    /// Returns whether `self` defines or uses enums, which need at least bytecode version 7
+    pub fn has_enums(&self) -> bool {
+        !self.enum_defs.is_empty()
+            || !self.enum_def_instantiations.is_empty()
+            || !self.variant_handles.is_empty()
+            || !self.variant_instantiation_handles.is_empty()
+    }
 }
 
 /// Return the simplest module that will pass the bounds checker
@@ -2065,6 +2232,10 @@ pub fn empty_module() -> CompiledModule {
         function_instantiations: vec![],
         field_instantiations: vec![],
         signatures: vec![Signature(vec![])],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     }
 }
 
@@ -51,6 +51,10 @@ pub const FUNCTION_INST_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const FIELD_HANDLE_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const FIELD_INST_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const STRUCT_DEF_INST_INDEX_MAX: u64 = TABLE_INDEX_MAX;
+pub const ENUM_DEF_INDEX_MAX: u64 = TABLE_INDEX_MAX;
+pub const ENUM_DEF_INST_INDEX_MAX: u64 = TABLE_INDEX_MAX;
+pub const VARIANT_HANDLE_INDEX_MAX: u64 = TABLE_INDEX_MAX;
+pub const VARIANT_INST_HANDLE_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const CONSTANT_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 
 pub const BYTECODE_COUNT_MAX: u64 = 65535;
@@ -72,6 +76,8 @@ pub const ACQUIRES_COUNT_MAX: u64 = 255;
 pub const FIELD_COUNT_MAX: u64 = 255;
 pub const FIELD_OFFSET_MAX: u64 = 255;
 
+pub const VARIANT_COUNT_MAX: u64 = 127;
+
 pub const TYPE_PARAMETER_COUNT_MAX: u64 = 255;
 pub const TYPE_PARAMETER_INDEX_MAX: u64 = 65536;
 
@@ -101,6 +107,10 @@ pub enum TableType {
     FIELD_INST              = 0xE,
     FRIEND_DECLS            = 0xF,
     METADATA                = 0x10,
+    ENUM_DEFS               = 0x11,
+    ENUM_DEF_INST           = 0x12,
+    VARIANT_HANDLES         = 0x13,
+    VARIANT_INST_HANDLES    = 0x14,
 }
 
 /// Constants for signature blob values.
@@ -218,6 +228,16 @@ pub enum Opcodes {
     CAST_U16                    = 0x4B,
     CAST_U32                    = 0x4C,
     CAST_U256                   = 0x4D,
+    PACK_VARIANT                = 0x4E,
+    PACK_VARIANT_GENERIC        = 0x4F,
+    UNPACK_VARIANT              = 0x50,
+    UNPACK_VARIANT_GENERIC      = 0x51,
+    UNPACK_VARIANT_IMM_REF      = 0x52,
+    UNPACK_VARIANT_IMM_REF_GENERIC = 0x53,
+    UNPACK_VARIANT_MUT_REF      = 0x54,
+    UNPACK_VARIANT_MUT_REF_GENERIC = 0x55,
+    TEST_VARIANT                = 0x56,
+    TEST_VARIANT_GENERIC        = 0x57,
 }
 
 /// Upper limit on the binary size
@@ -405,8 +425,17 @@ pub const VERSION_5: u32 = 5;
 ///  + u16, u32, u256 integers and corresponding Ld, Cast bytecodes
 pub const VERSION_6: u32 = 6;
 
+/// Version 7: changes compared with version 6
+///  + enum definitions, variant handles and their instantiations
+///  + bytecode for packing, unpacking and testing enum variants
+pub const VERSION_7: u32 = 7;
+
 // Mark which version is the latest version
-pub const VERSION_MAX: u32 = VERSION_6;
+pub const VERSION_MAX: u32 = VERSION_7;
+
+// Mark which version is emitted when none is requested. Modules using enums are emitted at
+// VERSION_7 instead, so binaries without them stay loadable by VMs that only support VERSION_6.
+pub const VERSION_DEFAULT: u32 = VERSION_6;
 
 // Mark which oldest version is supported.
 // TODO(#145): finish v4 compatibility; as of now, only metadata is implemented
@@ -625,6 +654,16 @@ pub fn instruction_key(instruction: &Bytecode) -> u8 {
         CastU16 => Opcodes::CAST_U16,
         CastU32 => Opcodes::CAST_U32,
         CastU256 => Opcodes::CAST_U256,
+        PackVariant(_) => Opcodes::PACK_VARIANT,
+        PackVariantGeneric(_) => Opcodes::PACK_VARIANT_GENERIC,
+        UnpackVariant(_) => Opcodes::UNPACK_VARIANT,
+        UnpackVariantGeneric(_) => Opcodes::UNPACK_VARIANT_GENERIC,
+        UnpackVariantImmRef(_) => Opcodes::UNPACK_VARIANT_IMM_REF,
+        UnpackVariantImmRefGeneric(_) => Opcodes::UNPACK_VARIANT_IMM_REF_GENERIC,
+        UnpackVariantMutRef(_) => Opcodes::UNPACK_VARIANT_MUT_REF,
+        UnpackVariantMutRefGeneric(_) => Opcodes::UNPACK_VARIANT_MUT_REF_GENERIC,
+        TestVariant(_) => Opcodes::TEST_VARIANT,
+        TestVariantGeneric(_) => Opcodes::TEST_VARIANT_GENERIC,
     };
     opcode as u8
 }
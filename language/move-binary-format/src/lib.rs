@@ -51,6 +51,10 @@ pub enum IndexKind {
     CodeDefinition,
     TypeParameter,
     MemberCount,
+    EnumDefinition,
+    EnumDefInstantiation,
+    VariantHandle,
+    VariantInstantiationHandle,
 }
 
 impl IndexKind {
@@ -77,6 +81,10 @@ impl IndexKind {
             CodeDefinition,
             TypeParameter,
             MemberCount,
+            EnumDefinition,
+            EnumDefInstantiation,
+            VariantHandle,
+            VariantInstantiationHandle,
         ]
     }
 }
@@ -105,6 +113,10 @@ impl fmt::Display for IndexKind {
             CodeDefinition => "code definition pool",
             TypeParameter => "type parameter",
             MemberCount => "field offset",
+            EnumDefinition => "enum definition",
+            EnumDefInstantiation => "enum instantiation",
+            VariantHandle => "variant handle",
+            VariantInstantiationHandle => "variant instantiation handle",
         };
 
         f.write_str(desc)
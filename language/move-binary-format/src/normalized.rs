@@ -5,9 +5,9 @@
 use crate::{
     access::ModuleAccess,
     file_format::{
-        AbilitySet, CompiledModule, FieldDefinition, FunctionDefinition, SignatureToken,
-        StructDefinition, StructFieldInformation, StructTypeParameter, TypeParameterIndex,
-        Visibility,
+        AbilitySet, CompiledModule, EnumDefinition, FieldDefinition, FunctionDefinition,
+        SignatureToken, StructDefinition, StructFieldInformation, StructTypeParameter,
+        TypeParameterIndex, VariantDefinition, Visibility,
     },
 };
 use move_core_types::{
@@ -81,6 +81,23 @@ pub struct Struct {
     pub fields: Vec<Field>,
 }
 
+/// Normalized version of a `VariantDefinition`. As with fields, the name is included so that
+/// reordering or renaming variants is marked as incompatible.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Variant {
+    pub name: Identifier,
+    pub fields: Vec<Field>,
+}
+
+/// Normalized version of an `EnumDefinition`. Not safe to compare without an associated
+/// `ModuleId` or `Module`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Enum {
+    pub abilities: AbilitySet,
+    pub type_parameters: Vec<StructTypeParameter>,
+    pub variants: Vec<Variant>,
+}
+
 /// Normalized version of a `FunctionDefinition`. Not safe to compare without an associated
 /// `ModuleId` or `Module`.
 #[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
@@ -92,8 +109,8 @@ pub struct Function {
     pub return_: Vec<Type>,
 }
 
-/// Normalized version of a `CompiledModule`: its address, name, struct declarations, and public
-/// function declarations.
+/// Normalized version of a `CompiledModule`: its address, name, struct and enum declarations, and
+/// public function declarations.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Module {
     pub file_format_version: u32,
@@ -101,6 +118,8 @@ pub struct Module {
     pub name: Identifier,
     pub friends: Vec<ModuleId>,
     pub structs: BTreeMap<Identifier, Struct>,
+    #[serde(default)]
+    pub enums: BTreeMap<Identifier, Enum>,
     pub exposed_functions: BTreeMap<Identifier, Function>,
 }
 
@@ -111,6 +130,7 @@ impl Module {
     pub fn new(m: &CompiledModule) -> Self {
         let friends = m.immediate_friends();
         let structs = m.struct_defs().iter().map(|d| Struct::new(m, d)).collect();
+        let enums = m.enum_defs().iter().map(|d| Enum::new(m, d)).collect();
         let exposed_functions = m
             .function_defs()
             .iter()
@@ -131,6 +151,7 @@ impl Module {
             name: m.name().to_owned(),
             friends,
             structs,
+            enums,
             exposed_functions,
         }
     }
@@ -318,6 +339,30 @@ impl Struct {
     }
 }
 
+impl Variant {
+    /// Create a `Variant` for `VariantDefinition` `v` in module `m`.
+    pub fn new(m: &CompiledModule, v: &VariantDefinition) -> Self {
+        Variant {
+            name: m.identifier_at(v.name).to_owned(),
+            fields: v.fields.iter().map(|f| Field::new(m, f)).collect(),
+        }
+    }
+}
+
+impl Enum {
+    /// Create an `Enum` for `EnumDefinition` `def` in module `m`.
+    pub fn new(m: &CompiledModule, def: &EnumDefinition) -> (Identifier, Self) {
+        let handle = m.struct_handle_at(def.enum_handle);
+        let name = m.identifier_at(handle.name).to_owned();
+        let e = Enum {
+            abilities: handle.abilities,
+            type_parameters: handle.type_parameters.clone(),
+            variants: def.variants.iter().map(|v| Variant::new(m, v)).collect(),
+        };
+        (name, e)
+    }
+}
+
 impl Function {
     /// Create a `FunctionSignature` for `FunctionHandle` `f` in module `m`.
     pub fn new(m: &CompiledModule, def: &FunctionDefinition) -> (Identifier, Self) {
@@ -28,13 +28,13 @@ impl CompiledScript {
         self.serialize_for_version(None, binary)
     }
 
-    /// Serialize into binary, at given version.
+    /// Serialize into binary, at given version or `VERSION_DEFAULT` if none is given.
     pub fn serialize_for_version(
         &self,
         bytecode_version: Option<u32>,
         binary: &mut Vec<u8>,
     ) -> Result<()> {
-        let version = bytecode_version.unwrap_or(VERSION_MAX);
+        let version = bytecode_version.unwrap_or(VERSION_DEFAULT);
         validate_version(version)?;
         let mut binary_data = BinaryData::from(binary.clone());
         let mut ser = ScriptSerializer::new(version);
@@ -132,6 +132,28 @@ fn serialize_struct_def_inst_index(
     write_as_uleb128(binary, idx.0, STRUCT_DEF_INST_INDEX_MAX)
 }
 
+fn serialize_enum_def_index(binary: &mut BinaryData, idx: &EnumDefinitionIndex) -> Result<()> {
+    write_as_uleb128(binary, idx.0, ENUM_DEF_INDEX_MAX)
+}
+
+fn serialize_enum_def_inst_index(
+    binary: &mut BinaryData,
+    idx: &EnumDefInstantiationIndex,
+) -> Result<()> {
+    write_as_uleb128(binary, idx.0, ENUM_DEF_INST_INDEX_MAX)
+}
+
+fn serialize_variant_handle_index(binary: &mut BinaryData, idx: &VariantHandleIndex) -> Result<()> {
+    write_as_uleb128(binary, idx.0, VARIANT_HANDLE_INDEX_MAX)
+}
+
+fn serialize_variant_inst_handle_index(
+    binary: &mut BinaryData,
+    idx: &VariantInstantiationHandleIndex,
+) -> Result<()> {
+    write_as_uleb128(binary, idx.0, VARIANT_INST_HANDLE_INDEX_MAX)
+}
+
 fn seiralize_table_offset(binary: &mut BinaryData, offset: u32) -> Result<()> {
     write_as_uleb128(binary, offset, TABLE_OFFSET_MAX)
 }
@@ -172,6 +194,14 @@ fn serialize_field_offset(binary: &mut BinaryData, offset: u16) -> Result<()> {
     write_as_uleb128(binary, offset, FIELD_OFFSET_MAX)
 }
 
+fn serialize_variant_count(binary: &mut BinaryData, len: usize) -> Result<()> {
+    write_as_uleb128(binary, len as u64, VARIANT_COUNT_MAX)
+}
+
+fn serialize_variant_tag(binary: &mut BinaryData, tag: VariantTag) -> Result<()> {
+    write_as_uleb128(binary, tag, VARIANT_COUNT_MAX)
+}
+
 fn serialize_acquires_count(binary: &mut BinaryData, len: usize) -> Result<()> {
     write_as_uleb128(binary, len as u64, ACQUIRES_COUNT_MAX)
 }
@@ -220,13 +250,18 @@ impl CompiledModule {
         self.serialize_for_version(None, binary)
     }
 
-    /// Serialize into binary, at given version.
+    /// Serialize into binary, at given version. If none is given, modules using enums are
+    /// serialized at `VERSION_7` and all others at `VERSION_DEFAULT`.
     pub fn serialize_for_version(
         &self,
         bytecode_version: Option<u32>,
         binary: &mut Vec<u8>,
     ) -> Result<()> {
-        let version = bytecode_version.unwrap_or(VERSION_MAX);
+        let version = bytecode_version.unwrap_or(if self.has_enums() {
+            VERSION_7
+        } else {
+            VERSION_DEFAULT
+        });
         validate_version(version)?;
         let mut binary_data = BinaryData::from(binary.clone());
         let mut ser = ModuleSerializer::new(version);
@@ -283,6 +318,10 @@ struct ModuleSerializer {
     field_handles: (u32, u32),
     field_instantiations: (u32, u32),
     friend_decls: (u32, u32),
+    enum_defs: (u32, u32),
+    enum_def_instantiations: (u32, u32),
+    variant_handles: (u32, u32),
+    variant_instantiation_handles: (u32, u32),
 }
 
 /// Holds data to compute the header of a transaction script binary.
@@ -609,6 +648,49 @@ fn serialize_field_instantiation(
     Ok(())
 }
 
+/// Serializes an `EnumDefinition`.
+///
+/// An `EnumDefinition` gets serialized as follows:
+/// - `EnumDefinition.enum_handle` as a ULEB128 (index into the `StructHandle` table)
+/// - `EnumDefinition.variants` as a ULEB128 count followed by the variants, each serialized as
+///   its name (a ULEB128 index into the `IdentifierPool`) and its field definitions
+fn serialize_enum_definition(
+    binary: &mut BinaryData,
+    enum_definition: &EnumDefinition,
+) -> Result<()> {
+    serialize_struct_handle_index(binary, &enum_definition.enum_handle)?;
+    serialize_variant_count(binary, enum_definition.variants.len())?;
+    for variant in &enum_definition.variants {
+        serialize_identifier_index(binary, &variant.name)?;
+        serialize_field_definitions(binary, &variant.fields)?;
+    }
+    Ok(())
+}
+
+fn serialize_enum_def_instantiation(
+    binary: &mut BinaryData,
+    enum_inst: &EnumDefInstantiation,
+) -> Result<()> {
+    serialize_enum_def_index(binary, &enum_inst.def)?;
+    serialize_signature_index(binary, &enum_inst.type_parameters)?;
+    Ok(())
+}
+
+fn serialize_variant_handle(binary: &mut BinaryData, variant_handle: &VariantHandle) -> Result<()> {
+    serialize_enum_def_index(binary, &variant_handle.enum_def)?;
+    serialize_variant_tag(binary, variant_handle.variant)?;
+    Ok(())
+}
+
+fn serialize_variant_instantiation_handle(
+    binary: &mut BinaryData,
+    variant_inst: &VariantInstantiationHandle,
+) -> Result<()> {
+    serialize_enum_def_inst_index(binary, &variant_inst.enum_def)?;
+    serialize_variant_tag(binary, variant_inst.variant)?;
+    Ok(())
+}
+
 /// Serializes a `Vec<StructDefinitionIndex>`.
 fn serialize_acquires(binary: &mut BinaryData, indices: &[StructDefinitionIndex]) -> Result<()> {
     serialize_acquires_count(binary, indices.len())?;
@@ -748,6 +830,23 @@ fn serialize_instruction_inner(
                 major_version
             ));
         }
+        Bytecode::PackVariant(_)
+        | Bytecode::PackVariantGeneric(_)
+        | Bytecode::UnpackVariant(_)
+        | Bytecode::UnpackVariantGeneric(_)
+        | Bytecode::UnpackVariantImmRef(_)
+        | Bytecode::UnpackVariantImmRefGeneric(_)
+        | Bytecode::UnpackVariantMutRef(_)
+        | Bytecode::UnpackVariantMutRefGeneric(_)
+        | Bytecode::TestVariant(_)
+        | Bytecode::TestVariantGeneric(_)
+            if (major_version < VERSION_7) =>
+        {
+            return Err(anyhow!(
+                "Enum operations not supported in bytecode version {}",
+                major_version
+            ));
+        }
         _ => (),
     };
 
@@ -960,6 +1059,46 @@ fn serialize_instruction_inner(
         Bytecode::CastU16 => binary.push(Opcodes::CAST_U16 as u8),
         Bytecode::CastU32 => binary.push(Opcodes::CAST_U32 as u8),
         Bytecode::CastU256 => binary.push(Opcodes::CAST_U256 as u8),
+        Bytecode::PackVariant(idx) => {
+            binary.push(Opcodes::PACK_VARIANT as u8)?;
+            serialize_variant_handle_index(binary, idx)
+        }
+        Bytecode::PackVariantGeneric(idx) => {
+            binary.push(Opcodes::PACK_VARIANT_GENERIC as u8)?;
+            serialize_variant_inst_handle_index(binary, idx)
+        }
+        Bytecode::UnpackVariant(idx) => {
+            binary.push(Opcodes::UNPACK_VARIANT as u8)?;
+            serialize_variant_handle_index(binary, idx)
+        }
+        Bytecode::UnpackVariantGeneric(idx) => {
+            binary.push(Opcodes::UNPACK_VARIANT_GENERIC as u8)?;
+            serialize_variant_inst_handle_index(binary, idx)
+        }
+        Bytecode::UnpackVariantImmRef(idx) => {
+            binary.push(Opcodes::UNPACK_VARIANT_IMM_REF as u8)?;
+            serialize_variant_handle_index(binary, idx)
+        }
+        Bytecode::UnpackVariantImmRefGeneric(idx) => {
+            binary.push(Opcodes::UNPACK_VARIANT_IMM_REF_GENERIC as u8)?;
+            serialize_variant_inst_handle_index(binary, idx)
+        }
+        Bytecode::UnpackVariantMutRef(idx) => {
+            binary.push(Opcodes::UNPACK_VARIANT_MUT_REF as u8)?;
+            serialize_variant_handle_index(binary, idx)
+        }
+        Bytecode::UnpackVariantMutRefGeneric(idx) => {
+            binary.push(Opcodes::UNPACK_VARIANT_MUT_REF_GENERIC as u8)?;
+            serialize_variant_inst_handle_index(binary, idx)
+        }
+        Bytecode::TestVariant(idx) => {
+            binary.push(Opcodes::TEST_VARIANT as u8)?;
+            serialize_variant_handle_index(binary, idx)
+        }
+        Bytecode::TestVariantGeneric(idx) => {
+            binary.push(Opcodes::TEST_VARIANT_GENERIC as u8)?;
+            serialize_variant_inst_handle_index(binary, idx)
+        }
     };
     res?;
     Ok(())
@@ -1257,6 +1396,10 @@ impl ModuleSerializer {
             field_handles: (0, 0),
             field_instantiations: (0, 0),
             friend_decls: (0, 0),
+            enum_defs: (0, 0),
+            enum_def_instantiations: (0, 0),
+            variant_handles: (0, 0),
+            variant_instantiation_handles: (0, 0),
         }
     }
 
@@ -1267,7 +1410,8 @@ impl ModuleSerializer {
         self.serialize_function_definitions(binary, &module.function_defs)?;
         self.serialize_field_handles(binary, &module.field_handles)?;
         self.serialize_field_instantiations(binary, &module.field_instantiations)?;
-        self.serialize_friend_declarations(binary, &module.friend_decls)
+        self.serialize_friend_declarations(binary, &module.friend_decls)?;
+        self.serialize_enum_tables(binary, module)
     }
 
     fn serialize_table_indices(&mut self, binary: &mut BinaryData) -> Result<()> {
@@ -1308,6 +1452,30 @@ impl ModuleSerializer {
             self.friend_decls.0,
             self.friend_decls.1,
         )?;
+        serialize_table_index(
+            binary,
+            TableType::ENUM_DEFS,
+            self.enum_defs.0,
+            self.enum_defs.1,
+        )?;
+        serialize_table_index(
+            binary,
+            TableType::ENUM_DEF_INST,
+            self.enum_def_instantiations.0,
+            self.enum_def_instantiations.1,
+        )?;
+        serialize_table_index(
+            binary,
+            TableType::VARIANT_HANDLES,
+            self.variant_handles.0,
+            self.variant_handles.1,
+        )?;
+        serialize_table_index(
+            binary,
+            TableType::VARIANT_INST_HANDLES,
+            self.variant_instantiation_handles.0,
+            self.variant_instantiation_handles.1,
+        )?;
         Ok(())
     }
 
@@ -1455,6 +1623,53 @@ impl ModuleSerializer {
         }
         Ok(())
     }
+
+    /// Serializes the tables for enums, which were introduced in v7.
+    fn serialize_enum_tables(
+        &mut self,
+        binary: &mut BinaryData,
+        module: &CompiledModule,
+    ) -> Result<()> {
+        if module.has_enums() && self.common.major_version < VERSION_7 {
+            bail!(
+                "Enums not supported in bytecode version {}",
+                self.common.major_version
+            )
+        }
+        self.enum_defs =
+            self.serialize_table(binary, &module.enum_defs, serialize_enum_definition)?;
+        self.enum_def_instantiations = self.serialize_table(
+            binary,
+            &module.enum_def_instantiations,
+            serialize_enum_def_instantiation,
+        )?;
+        self.variant_handles =
+            self.serialize_table(binary, &module.variant_handles, serialize_variant_handle)?;
+        self.variant_instantiation_handles = self.serialize_table(
+            binary,
+            &module.variant_instantiation_handles,
+            serialize_variant_instantiation_handle,
+        )?;
+        Ok(())
+    }
+
+    /// Serializes the entries of a table, returning its offset and size.
+    fn serialize_table<T>(
+        &mut self,
+        binary: &mut BinaryData,
+        entries: &[T],
+        serialize_entry: impl Fn(&mut BinaryData, &T) -> Result<()>,
+    ) -> Result<(u32, u32)> {
+        if entries.is_empty() {
+            return Ok((0, 0));
+        }
+        self.common.table_count += 1;
+        let start = check_index_in_binary(binary.len())?;
+        for entry in entries {
+            serialize_entry(binary, entry)?;
+        }
+        Ok((start, checked_calculate_table_size(binary, start)?))
+    }
 }
 
 impl ScriptSerializer {
@@ -3,10 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    file_format::{CompiledModule, CompiledScript},
+    file_format::{
+        basic_test_module, AbilitySet, CompiledModule, CompiledScript, EnumDefinition,
+        EnumDefinitionIndex, FieldDefinition, IdentifierIndex, ModuleHandleIndex, SignatureToken,
+        StructHandle, StructHandleIndex, TypeSignature, VariantDefinition, VariantHandle,
+    },
     file_format_common::*,
 };
-use move_core_types::vm_status::StatusCode;
+use move_core_types::{identifier::Identifier, vm_status::StatusCode};
 
 fn malformed_simple_versioned_test(version: u32) {
     // bad uleb (more than allowed for table count)
@@ -257,3 +261,69 @@ fn deserialize_invalid_script_no_signature() {
         StatusCode::INDEX_OUT_OF_BOUNDS
     );
 }
+
+// Add `enum E { A { x: u64 }, B }` to the basic test module
+fn enum_test_module() -> CompiledModule {
+    let mut m = basic_test_module();
+    let enum_handle = StructHandleIndex(m.struct_handles.len() as u16);
+    m.struct_handles.push(StructHandle {
+        module: ModuleHandleIndex(0),
+        name: IdentifierIndex(m.identifiers.len() as u16),
+        abilities: AbilitySet::EMPTY,
+        type_parameters: vec![],
+    });
+    m.identifiers.push(Identifier::new("E").unwrap());
+    let variant_names = ["A", "B"].map(|n| {
+        m.identifiers.push(Identifier::new(n).unwrap());
+        IdentifierIndex(m.identifiers.len() as u16 - 1)
+    });
+    m.enum_defs.push(EnumDefinition {
+        enum_handle,
+        variants: vec![
+            VariantDefinition {
+                name: variant_names[0],
+                fields: vec![FieldDefinition {
+                    // reuse the field name of `Bar`
+                    name: IdentifierIndex(2),
+                    signature: TypeSignature(SignatureToken::U64),
+                }],
+            },
+            VariantDefinition {
+                name: variant_names[1],
+                fields: vec![],
+            },
+        ],
+    });
+    m.variant_handles.push(VariantHandle {
+        enum_def: EnumDefinitionIndex(0),
+        variant: 1,
+    });
+    m
+}
+
+#[test]
+fn enum_round_trip() {
+    let m = enum_test_module();
+    let mut binary = vec![];
+    m.serialize(&mut binary).expect("module should serialize");
+    let deserialized = CompiledModule::deserialize(&binary).expect("module should deserialize");
+    assert_eq!(m, deserialized);
+}
+
+#[test]
+fn enum_not_supported_before_v7() {
+    let m = enum_test_module();
+    let mut binary = vec![];
+    m.serialize_for_version(Some(VERSION_6), &mut binary)
+        .expect_err("enums should not serialize in VERSION_6");
+
+    // A VERSION_7 binary with enums is rejected when the max version is VERSION_6
+    let mut binary = vec![];
+    m.serialize(&mut binary).expect("module should serialize");
+    assert_eq!(
+        CompiledModule::deserialize_with_max_version(&binary, VERSION_6)
+            .unwrap_err()
+            .major_status(),
+        StatusCode::UNKNOWN_VERSION
+    );
+}
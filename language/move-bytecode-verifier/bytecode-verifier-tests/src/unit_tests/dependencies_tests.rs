@@ -80,6 +80,10 @@ fn mk_script_function_module() -> CompiledModule {
         struct_def_instantiations: vec![],
         function_instantiations: vec![],
         field_instantiations: vec![],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     };
     move_bytecode_verifier::verify_module(&m).unwrap();
     m
@@ -172,6 +176,10 @@ fn mk_invoking_module(use_generic: bool, valid: bool) -> CompiledModule {
         friend_decls: vec![],
         struct_def_instantiations: vec![],
         field_instantiations: vec![],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     };
     move_bytecode_verifier::verify_module(&m).unwrap();
     m
@@ -238,6 +238,10 @@ fn big_vec_unpacks() {
                 code,
             }),
         }],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     };
 
     // save module and verify that it can ser/de
@@ -24,6 +24,7 @@ pub mod negative_stack_size_tests;
 pub mod reference_safety_tests;
 pub mod signature_tests;
 pub mod struct_defs_tests;
+pub mod variant_tests;
 pub mod vec_pack_tests;
 
 /// Configuration used in production.
@@ -123,6 +123,10 @@ fn no_verify_locals_good() {
                 }),
             },
         ],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     };
     assert!(verify_module(&compiled_module_good).is_ok());
 }
@@ -206,6 +210,10 @@ fn big_signature_test() {
                 code,
             }),
         }],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     };
 
     // save module and verify that it can ser/de
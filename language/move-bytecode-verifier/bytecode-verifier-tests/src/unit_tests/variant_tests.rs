@@ -0,0 +1,334 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::unit_tests::production_config;
+use move_binary_format::{
+    check_bounds::BoundsChecker,
+    file_format::{
+        empty_module, Ability, AbilitySet, Bytecode, CodeUnit, CompiledModule, EnumDefinition,
+        EnumDefinitionIndex, FieldDefinition, FunctionDefinition, FunctionHandle,
+        FunctionHandleIndex, IdentifierIndex, ModuleHandleIndex, Signature, SignatureIndex,
+        SignatureToken, StructHandle, StructHandleIndex, TypeSignature, VariantDefinition,
+        VariantHandle, VariantHandleIndex, Visibility,
+    },
+};
+use move_core_types::{identifier::Identifier, vm_status::StatusCode};
+
+const E_A: VariantHandleIndex = VariantHandleIndex(0);
+const E_B: VariantHandleIndex = VariantHandleIndex(1);
+const F_C: VariantHandleIndex = VariantHandleIndex(2);
+
+fn identifier(m: &mut CompiledModule, name: &str) -> IdentifierIndex {
+    m.identifiers.push(Identifier::new(name).unwrap());
+    IdentifierIndex((m.identifiers.len() - 1) as u16)
+}
+
+fn signature(m: &mut CompiledModule, tokens: Vec<SignatureToken>) -> SignatureIndex {
+    let signature = Signature(tokens);
+    let idx = match m.signatures.iter().position(|s| *s == signature) {
+        Some(idx) => idx,
+        None => {
+            m.signatures.push(signature);
+            m.signatures.len() - 1
+        }
+    };
+    SignatureIndex(idx as u16)
+}
+
+/// Creates the following module, where `E_A`, `E_B` and `F_C` are the handles of the variants:
+/// // module <SELF> {
+/// //     enum E has drop { A { x: u64 }, B }
+/// //     enum F has drop { C }
+/// //     fun foo(<parameters>) { <locals> <code> }
+/// // }
+fn enum_module(
+    parameters: Vec<SignatureToken>,
+    locals: Vec<SignatureToken>,
+    code: Vec<Bytecode>,
+) -> CompiledModule {
+    let mut m = empty_module();
+
+    for (enum_name, variants) in [
+        ("E", vec![("A", true), ("B", false)]),
+        ("F", vec![("C", false)]),
+    ] {
+        let enum_handle = StructHandleIndex(m.struct_handles.len() as u16);
+        let name = identifier(&mut m, enum_name);
+        m.struct_handles.push(StructHandle {
+            module: ModuleHandleIndex(0),
+            name,
+            abilities: AbilitySet::singleton(Ability::Drop),
+            type_parameters: vec![],
+        });
+
+        let enum_def = EnumDefinitionIndex(m.enum_defs.len() as u16);
+        let variants = variants
+            .into_iter()
+            .enumerate()
+            .map(|(tag, (variant_name, has_field))| {
+                m.variant_handles.push(VariantHandle {
+                    enum_def,
+                    variant: tag as u16,
+                });
+                let fields = if has_field {
+                    vec![FieldDefinition {
+                        name: identifier(&mut m, "x"),
+                        signature: TypeSignature(SignatureToken::U64),
+                    }]
+                } else {
+                    vec![]
+                };
+                VariantDefinition {
+                    name: identifier(&mut m, variant_name),
+                    fields,
+                }
+            })
+            .collect();
+        m.enum_defs.push(EnumDefinition {
+            enum_handle,
+            variants,
+        });
+    }
+
+    let name = identifier(&mut m, "foo");
+    let parameters = signature(&mut m, parameters);
+    m.function_handles.push(FunctionHandle {
+        module: ModuleHandleIndex(0),
+        name,
+        parameters,
+        return_: SignatureIndex(0),
+        type_parameters: vec![],
+    });
+    let locals = signature(&mut m, locals);
+    m.function_defs.push(FunctionDefinition {
+        function: FunctionHandleIndex(0),
+        visibility: Visibility::Private,
+        is_entry: false,
+        acquires_global_resources: vec![],
+        code: Some(CodeUnit { locals, code }),
+    });
+
+    m
+}
+
+fn e() -> SignatureToken {
+    SignatureToken::Struct(StructHandleIndex(0))
+}
+
+fn f() -> SignatureToken {
+    SignatureToken::Struct(StructHandleIndex(1))
+}
+
+fn verify(m: &CompiledModule) -> Result<(), StatusCode> {
+    move_bytecode_verifier::verify_module_with_config_for_test(
+        "variant_tests",
+        &production_config(),
+        m,
+    )
+    .map_err(|e| e.major_status())
+}
+
+#[test]
+fn valid_variant_instructions() {
+    // Builds `E::A { x: 0 }`, tests it through a reference, then moves its field out.
+    let m = enum_module(
+        vec![],
+        vec![e()],
+        vec![
+            Bytecode::LdU64(0),
+            Bytecode::PackVariant(E_A),
+            Bytecode::StLoc(0),
+            Bytecode::ImmBorrowLoc(0),
+            Bytecode::TestVariant(E_B),
+            Bytecode::Pop,
+            Bytecode::MutBorrowLoc(0),
+            Bytecode::UnpackVariantMutRef(E_A),
+            Bytecode::Pop,
+            Bytecode::MoveLoc(0),
+            Bytecode::UnpackVariant(E_A),
+            Bytecode::Pop,
+            Bytecode::PackVariant(F_C),
+            Bytecode::UnpackVariant(F_C),
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(verify(&m), Ok(()));
+}
+
+#[test]
+fn variant_tag_out_of_range() {
+    let mut m = enum_module(vec![], vec![], vec![Bytecode::Ret]);
+    m.variant_handles.push(VariantHandle {
+        enum_def: EnumDefinitionIndex(0),
+        variant: 2,
+    });
+    assert_eq!(
+        BoundsChecker::verify_module(&m).unwrap_err().major_status(),
+        StatusCode::INDEX_OUT_OF_BOUNDS
+    );
+}
+
+#[test]
+fn variant_handle_out_of_range() {
+    let m = enum_module(
+        vec![],
+        vec![],
+        vec![Bytecode::PackVariant(VariantHandleIndex(3)), Bytecode::Ret],
+    );
+    assert_eq!(
+        BoundsChecker::verify_module(&m).unwrap_err().major_status(),
+        StatusCode::INDEX_OUT_OF_BOUNDS
+    );
+}
+
+#[test]
+fn pack_variant_wrong_field_type() {
+    let m = enum_module(
+        vec![],
+        vec![],
+        vec![
+            Bytecode::LdTrue,
+            Bytecode::PackVariant(E_A),
+            Bytecode::Pop,
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(verify(&m), Err(StatusCode::PACK_TYPE_MISMATCH_ERROR));
+}
+
+#[test]
+fn unpack_variant_of_other_enum() {
+    let m = enum_module(
+        vec![],
+        vec![],
+        vec![
+            Bytecode::PackVariant(F_C),
+            Bytecode::UnpackVariant(E_B),
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(verify(&m), Err(StatusCode::UNPACK_TYPE_MISMATCH_ERROR));
+}
+
+#[test]
+fn unpack_variant_mut_ref_through_imm_ref() {
+    let m = enum_module(
+        vec![SignatureToken::Reference(Box::new(e()))],
+        vec![],
+        vec![
+            Bytecode::MoveLoc(0),
+            Bytecode::UnpackVariantMutRef(E_A),
+            Bytecode::Pop,
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(verify(&m), Err(StatusCode::UNPACK_TYPE_MISMATCH_ERROR));
+}
+
+#[test]
+fn test_variant_of_other_enum() {
+    let m = enum_module(
+        vec![SignatureToken::Reference(Box::new(f()))],
+        vec![],
+        vec![
+            Bytecode::MoveLoc(0),
+            Bytecode::TestVariant(E_A),
+            Bytecode::Pop,
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(
+        verify(&m),
+        Err(StatusCode::TEST_VARIANT_TYPE_MISMATCH_ERROR)
+    );
+}
+
+#[test]
+fn test_variant_of_value() {
+    let m = enum_module(
+        vec![e()],
+        vec![],
+        vec![
+            Bytecode::MoveLoc(0),
+            Bytecode::TestVariant(E_A),
+            Bytecode::Pop,
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(
+        verify(&m),
+        Err(StatusCode::TEST_VARIANT_TYPE_MISMATCH_ERROR)
+    );
+}
+
+#[test]
+fn test_variant_with_mutably_borrowed_field() {
+    let m = enum_module(
+        vec![SignatureToken::MutableReference(Box::new(e()))],
+        vec![SignatureToken::MutableReference(Box::new(
+            SignatureToken::U64,
+        ))],
+        vec![
+            Bytecode::CopyLoc(0),
+            Bytecode::UnpackVariantMutRef(E_A),
+            Bytecode::StLoc(1),
+            Bytecode::MoveLoc(0),
+            Bytecode::TestVariant(E_A),
+            Bytecode::Pop,
+            Bytecode::MoveLoc(1),
+            Bytecode::Pop,
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(
+        verify(&m),
+        Err(StatusCode::READREF_EXISTS_MUTABLE_BORROW_ERROR)
+    );
+}
+
+#[test]
+fn unpack_variant_imm_ref_with_mutably_borrowed_field() {
+    let m = enum_module(
+        vec![SignatureToken::MutableReference(Box::new(e()))],
+        vec![SignatureToken::MutableReference(Box::new(
+            SignatureToken::U64,
+        ))],
+        vec![
+            Bytecode::CopyLoc(0),
+            Bytecode::UnpackVariantMutRef(E_A),
+            Bytecode::StLoc(1),
+            Bytecode::MoveLoc(0),
+            Bytecode::UnpackVariantImmRef(E_A),
+            Bytecode::Pop,
+            Bytecode::MoveLoc(1),
+            Bytecode::Pop,
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(
+        verify(&m),
+        Err(StatusCode::BORROWFIELD_EXISTS_MUTABLE_BORROW_ERROR)
+    );
+}
+
+#[test]
+fn unpack_variant_mut_ref_with_copied_reference() {
+    let m = enum_module(
+        vec![SignatureToken::MutableReference(Box::new(e()))],
+        vec![SignatureToken::MutableReference(Box::new(e()))],
+        vec![
+            Bytecode::CopyLoc(0),
+            Bytecode::StLoc(1),
+            Bytecode::MoveLoc(0),
+            Bytecode::UnpackVariantMutRef(E_A),
+            Bytecode::Pop,
+            Bytecode::MoveLoc(1),
+            Bytecode::Pop,
+            Bytecode::Ret,
+        ],
+    );
+    assert_eq!(
+        verify(&m),
+        Err(StatusCode::BORROWFIELD_EXISTS_MUTABLE_BORROW_ERROR)
+    );
+}
@@ -8,7 +8,7 @@ use move_binary_format::{
         Bytecode, CodeOffset, CompiledModule, ConstantPoolIndex, FieldHandleIndex,
         FieldInstantiationIndex, FunctionDefinitionIndex, FunctionHandleIndex,
         FunctionInstantiationIndex, LocalIndex, SignatureIndex, StructDefInstantiationIndex,
-        StructDefinitionIndex, TableIndex,
+        StructDefinitionIndex, TableIndex, VariantHandleIndex, VariantInstantiationHandleIndex,
     },
     internals::ModuleIndex,
     IndexKind,
@@ -199,6 +199,8 @@ impl<'a> ApplyCodeUnitBoundsContext<'a> {
         let function_inst_len = self.module.function_instantiations.len();
         let field_inst_len = self.module.field_instantiations.len();
         let signature_pool_len = self.module.signatures.len();
+        let variant_handle_len = self.module.variant_handles.len();
+        let variant_inst_len = self.module.variant_instantiation_handles.len();
 
         mutations
             .iter()
@@ -475,6 +477,86 @@ impl<'a> ApplyCodeUnitBoundsContext<'a> {
                         SignatureIndex,
                         VecSwap
                     ),
+                    PackVariant(_) => new_bytecode!(
+                        variant_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantHandleIndex,
+                        PackVariant
+                    ),
+                    PackVariantGeneric(_) => new_bytecode!(
+                        variant_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantInstantiationHandleIndex,
+                        PackVariantGeneric
+                    ),
+                    UnpackVariant(_) => new_bytecode!(
+                        variant_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantHandleIndex,
+                        UnpackVariant
+                    ),
+                    UnpackVariantGeneric(_) => new_bytecode!(
+                        variant_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantInstantiationHandleIndex,
+                        UnpackVariantGeneric
+                    ),
+                    UnpackVariantImmRef(_) => new_bytecode!(
+                        variant_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantHandleIndex,
+                        UnpackVariantImmRef
+                    ),
+                    UnpackVariantImmRefGeneric(_) => new_bytecode!(
+                        variant_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantInstantiationHandleIndex,
+                        UnpackVariantImmRefGeneric
+                    ),
+                    UnpackVariantMutRef(_) => new_bytecode!(
+                        variant_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantHandleIndex,
+                        UnpackVariantMutRef
+                    ),
+                    UnpackVariantMutRefGeneric(_) => new_bytecode!(
+                        variant_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantInstantiationHandleIndex,
+                        UnpackVariantMutRefGeneric
+                    ),
+                    TestVariant(_) => new_bytecode!(
+                        variant_handle_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantHandleIndex,
+                        TestVariant
+                    ),
+                    TestVariantGeneric(_) => new_bytecode!(
+                        variant_inst_len,
+                        current_fdef,
+                        bytecode_idx,
+                        offset,
+                        VariantInstantiationHandleIndex,
+                        TestVariantGeneric
+                    ),
 
                     // List out the other options explicitly so there's a compile error if a new
                     // bytecode gets added.
@@ -535,7 +617,17 @@ fn is_interesting(bytecode: &Bytecode) -> bool {
         | VecPushBack(_)
         | VecPopBack(_)
         | VecUnpack(..)
-        | VecSwap(_) => true,
+        | VecSwap(_)
+        | PackVariant(_)
+        | PackVariantGeneric(_)
+        | UnpackVariant(_)
+        | UnpackVariantGeneric(_)
+        | UnpackVariantImmRef(_)
+        | UnpackVariantImmRefGeneric(_)
+        | UnpackVariantMutRef(_)
+        | UnpackVariantMutRefGeneric(_)
+        | TestVariant(_)
+        | TestVariantGeneric(_) => true,
 
         // List out the other options explicitly so there's a compile error if a new
         // bytecode gets added.
@@ -3,12 +3,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! This module implements a checker for verifying that all of the struct's fields satisfy the
-//! abilities required by the struct's abilities
+//! abilities required by the struct's abilities. The same holds for the fields of each variant of
+//! an enum
 use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
     errors::{verification_error, Location, PartialVMResult, VMResult},
-    file_format::{AbilitySet, CompiledModule, StructFieldInformation, TableIndex},
+    file_format::{
+        AbilitySet, CompiledModule, FieldDefinition, StructFieldInformation, StructHandle,
+        TableIndex,
+    },
     IndexKind,
 };
 use move_core_types::vm_status::StatusCode;
@@ -25,24 +29,21 @@ fn verify_module_impl(module: &CompiledModule) -> PartialVMResult<()> {
             StructFieldInformation::Native => continue,
             StructFieldInformation::Declared(fields) => fields,
         };
-        let required_abilities = sh
-            .abilities
-            .into_iter()
-            .map(|a| a.requires())
-            .fold(AbilitySet::EMPTY, |acc, required| acc | required);
-        // Assume type parameters have all abilities, as the struct's abilities will be dependent on
-        // them
-        let type_parameter_abilities = sh
-            .type_parameters
-            .iter()
-            .map(|_| AbilitySet::ALL)
-            .collect::<Vec<_>>();
-        for field in fields {
-            let field_abilities = view.abilities(&field.signature.0, &type_parameter_abilities)?;
-            if !required_abilities.is_subset(field_abilities) {
+        if !fields_satisfy_requirements(&view, sh, fields)? {
+            return Err(verification_error(
+                StatusCode::FIELD_MISSING_TYPE_ABILITY,
+                IndexKind::StructDefinition,
+                idx as TableIndex,
+            ));
+        }
+    }
+    for (idx, enum_def) in module.enum_defs().iter().enumerate() {
+        let sh = module.struct_handle_at(enum_def.enum_handle);
+        for variant in &enum_def.variants {
+            if !fields_satisfy_requirements(&view, sh, &variant.fields)? {
                 return Err(verification_error(
                     StatusCode::FIELD_MISSING_TYPE_ABILITY,
-                    IndexKind::StructDefinition,
+                    IndexKind::EnumDefinition,
                     idx as TableIndex,
                 ));
             }
@@ -50,3 +51,29 @@ fn verify_module_impl(module: &CompiledModule) -> PartialVMResult<()> {
     }
     Ok(())
 }
+
+fn fields_satisfy_requirements(
+    view: &BinaryIndexedView,
+    sh: &StructHandle,
+    fields: &[FieldDefinition],
+) -> PartialVMResult<bool> {
+    let required_abilities = sh
+        .abilities
+        .into_iter()
+        .map(|a| a.requires())
+        .fold(AbilitySet::EMPTY, |acc, required| acc | required);
+    // Assume type parameters have all abilities, as the struct's abilities will be dependent on
+    // them
+    let type_parameter_abilities = sh
+        .type_parameters
+        .iter()
+        .map(|_| AbilitySet::ALL)
+        .collect::<Vec<_>>();
+    for field in fields {
+        let field_abilities = view.abilities(&field.signature.0, &type_parameter_abilities)?;
+        if !required_abilities.is_subset(field_abilities) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
@@ -133,6 +133,16 @@ impl<'a> AcquiresVerifier<'a> {
             | Bytecode::PackGeneric(_)
             | Bytecode::Unpack(_)
             | Bytecode::UnpackGeneric(_)
+            | Bytecode::PackVariant(_)
+            | Bytecode::PackVariantGeneric(_)
+            | Bytecode::UnpackVariant(_)
+            | Bytecode::UnpackVariantGeneric(_)
+            | Bytecode::UnpackVariantImmRef(_)
+            | Bytecode::UnpackVariantImmRefGeneric(_)
+            | Bytecode::UnpackVariantMutRef(_)
+            | Bytecode::UnpackVariantMutRefGeneric(_)
+            | Bytecode::TestVariant(_)
+            | Bytecode::TestVariantGeneric(_)
             | Bytecode::ReadRef
             | Bytecode::WriteRef
             | Bytecode::CastU8
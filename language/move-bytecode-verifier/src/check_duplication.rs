@@ -6,8 +6,8 @@
 //! distinct values. Successful verification implies that an index in vector can be used to
 //! uniquely name the entry at that index. Additionally, the checker also verifies the
 //! following:
-//! - struct and field definitions are consistent
-//! - the handles in struct and function definitions point to the self module index
+//! - struct, enum, and field definitions are consistent
+//! - the handles in struct, enum, and function definitions point to the self module index
 //! - all struct and function handles pointing to the self module index have a definition
 use move_binary_format::{
     access::{ModuleAccess, ScriptAccess},
@@ -49,7 +49,10 @@ impl<'a> DuplicationChecker<'a> {
         checker.check_field_instantiations()?;
         checker.check_function_defintions()?;
         checker.check_struct_definitions()?;
-        checker.check_struct_instantiations()
+        checker.check_struct_instantiations()?;
+        checker.check_enum_definitions()?;
+        checker.check_enum_instantiations()?;
+        checker.check_variant_handles()
     }
 
     pub fn verify_script(module: &'a CompiledScript) -> VMResult<()> {
@@ -238,12 +241,14 @@ impl<'a> DuplicationChecker<'a> {
                 idx as TableIndex,
             ));
         }
-        // Check that each struct handle in self module is implemented (has a declaration)
+        // Check that each struct handle in self module is implemented (has a declaration), either
+        // by a struct or by an enum
         let implemented_struct_handles: HashSet<StructHandleIndex> = self
             .module
             .struct_defs()
             .iter()
             .map(|x| x.struct_handle)
+            .chain(self.module.enum_defs().iter().map(|x| x.enum_handle))
             .collect();
         if let Some(idx) = (0..self.module.struct_handles().len()).position(|x| {
             let y = StructHandleIndex::new(x as u16);
@@ -259,6 +264,100 @@ impl<'a> DuplicationChecker<'a> {
         Ok(())
     }
 
+    fn check_enum_definitions(&self) -> PartialVMResult<()> {
+        // EnumDefinition - contained StructHandle defines uniqueness, also with respect to
+        // struct definitions
+        let struct_handles = self.module.struct_defs().iter().map(|x| x.struct_handle);
+        let enum_handles = self.module.enum_defs().iter().map(|x| x.enum_handle);
+        if let Some(idx) = Self::first_duplicate_element(struct_handles.chain(enum_handles)) {
+            return Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::EnumDefinition,
+                idx - self.module.struct_defs().len() as TableIndex,
+            ));
+        }
+        for (enum_idx, enum_def) in self.module.enum_defs().iter().enumerate() {
+            let enum_handle = self.module.struct_handle_at(enum_def.enum_handle);
+            // Check that each enum definition is pointing to the self module
+            if enum_handle.module != self.module.self_handle_idx() {
+                return Err(verification_error(
+                    StatusCode::INVALID_MODULE_HANDLE,
+                    IndexKind::EnumDefinition,
+                    enum_idx as TableIndex,
+                ));
+            }
+            // Enums are never stored directly in global storage
+            if enum_handle.abilities.has_key() {
+                return Err(verification_error(
+                    StatusCode::ENUM_WITH_KEY_ABILITY,
+                    IndexKind::EnumDefinition,
+                    enum_idx as TableIndex,
+                ));
+            }
+            if enum_def.variants.is_empty() {
+                return Err(verification_error(
+                    StatusCode::ZERO_VARIANT_ENUM,
+                    IndexKind::EnumDefinition,
+                    enum_idx as TableIndex,
+                ));
+            }
+            // Variant names must be unique, as must field names within a variant. Unlike structs,
+            // variants may have no fields
+            if let Some(idx) =
+                Self::first_duplicate_element(enum_def.variants.iter().map(|x| x.name))
+            {
+                return Err(verification_error(
+                    StatusCode::DUPLICATE_ELEMENT,
+                    IndexKind::VariantHandle,
+                    idx,
+                ));
+            }
+            for variant in &enum_def.variants {
+                if let Some(idx) =
+                    Self::first_duplicate_element(variant.fields.iter().map(|x| x.name))
+                {
+                    return Err(verification_error(
+                        StatusCode::DUPLICATE_ELEMENT,
+                        IndexKind::FieldDefinition,
+                        idx,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_enum_instantiations(&self) -> PartialVMResult<()> {
+        match Self::first_duplicate_element(self.module.enum_instantiations()) {
+            Some(idx) => Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::EnumDefInstantiation,
+                idx,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn check_variant_handles(&self) -> PartialVMResult<()> {
+        if let Some(idx) = Self::first_duplicate_element(self.module.variant_handles()) {
+            return Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::VariantHandle,
+                idx,
+            ));
+        }
+        if let Some(idx) =
+            Self::first_duplicate_element(self.module.variant_instantiation_handles())
+        {
+            return Err(verification_error(
+                StatusCode::DUPLICATE_ELEMENT,
+                IndexKind::VariantInstantiationHandle,
+                idx,
+            ));
+        }
+        Ok(())
+    }
+
     fn check_function_defintions(&self) -> PartialVMResult<()> {
         // FunctionDefinition - contained FunctionHandle defines uniqueness
         if let Some(idx) =
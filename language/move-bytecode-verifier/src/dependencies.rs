@@ -84,14 +84,18 @@ impl<'a, 'b> Context<'a, 'b> {
         for (module_id, module) in &context.dependency_map {
             let friend_module_ids: BTreeSet<_> = module.immediate_friends().into_iter().collect();
 
-            // Module::StructName -> def handle idx
-            for struct_def in module.struct_defs() {
-                let struct_handle = module.struct_handle_at(struct_def.struct_handle);
+            // Module::StructName -> def handle idx, for both structs and enums
+            let defined_handles = module
+                .struct_defs()
+                .iter()
+                .map(|def| def.struct_handle)
+                .chain(module.enum_defs().iter().map(|def| def.enum_handle));
+            for sh_idx in defined_handles {
+                let struct_handle = module.struct_handle_at(sh_idx);
                 let struct_name = module.identifier_at(struct_handle.name);
-                context.struct_id_to_handle_map.insert(
-                    (module_id.clone(), struct_name.to_owned()),
-                    struct_def.struct_handle,
-                );
+                context
+                    .struct_id_to_handle_map
+                    .insert((module_id.clone(), struct_name.to_owned()), sh_idx);
             }
             // Module::FuncName -> def handle idx
             for func_def in module.function_defs() {
@@ -11,8 +11,9 @@ use move_binary_format::{
     binary_views::BinaryIndexedView,
     errors::{Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
-        Bytecode, CodeOffset, CodeUnit, CompiledModule, CompiledScript, FieldHandleIndex,
-        FunctionDefinitionIndex, FunctionHandleIndex, StructDefinitionIndex, TableIndex,
+        Bytecode, CodeOffset, CodeUnit, CompiledModule, CompiledScript, EnumDefinitionIndex,
+        FieldHandleIndex, FunctionDefinitionIndex, FunctionHandleIndex, StructDefinitionIndex,
+        TableIndex, VariantHandleIndex,
     },
 };
 use move_core_types::vm_status::StatusCode;
@@ -132,6 +133,22 @@ impl<'a> InstructionConsistency<'a> {
                     let struct_inst = self.resolver.struct_instantiation_at(*idx)?;
                     self.check_type_op(offset, struct_inst.def, /* generic */ true)?;
                 }
+                PackVariant(idx)
+                | UnpackVariant(idx)
+                | UnpackVariantImmRef(idx)
+                | UnpackVariantMutRef(idx)
+                | TestVariant(idx) => {
+                    self.check_variant_op(offset, *idx, /* generic */ false)?;
+                }
+                PackVariantGeneric(idx)
+                | UnpackVariantGeneric(idx)
+                | UnpackVariantImmRefGeneric(idx)
+                | UnpackVariantMutRefGeneric(idx)
+                | TestVariantGeneric(idx) => {
+                    let handle = self.resolver.variant_instantiation_handle_at(*idx)?;
+                    let enum_inst = self.resolver.enum_instantiation_at(handle.enum_def)?;
+                    self.check_enum_op(offset, enum_inst.def, /* generic */ true)?;
+                }
                 VecPack(_, num) | VecUnpack(_, num) => {
                     if *num > u16::MAX as u64 {
                         return Err(PartialVMError::new(StatusCode::CONSTRAINT_NOT_SATISFIED)
@@ -191,6 +208,33 @@ impl<'a> InstructionConsistency<'a> {
         Ok(())
     }
 
+    fn check_variant_op(
+        &self,
+        offset: usize,
+        variant_handle_index: VariantHandleIndex,
+        generic: bool,
+    ) -> PartialVMResult<()> {
+        let variant_handle = self.resolver.variant_handle_at(variant_handle_index)?;
+        self.check_enum_op(offset, variant_handle.enum_def, generic)
+    }
+
+    fn check_enum_op(
+        &self,
+        offset: usize,
+        enum_def_index: EnumDefinitionIndex,
+        generic: bool,
+    ) -> PartialVMResult<()> {
+        let enum_def = self.resolver.enum_def_at(enum_def_index)?;
+        let enum_handle = self.resolver.struct_handle_at(enum_def.enum_handle);
+        if enum_handle.type_parameters.is_empty() == generic {
+            return Err(
+                PartialVMError::new(StatusCode::GENERIC_MEMBER_OPCODE_MISMATCH)
+                    .at_code_offset(self.current_function(), offset as CodeOffset),
+            );
+        }
+        Ok(())
+    }
+
     fn check_function_op(
         &self,
         offset: usize,
@@ -103,6 +103,13 @@ impl<'a> LimitsVerifier<'a> {
                 }
             }
         }
+        if let Some(edefs) = self.resolver.enum_defs() {
+            for edef in edefs {
+                for fdef in edef.variants.iter().flat_map(|variant| &variant.fields) {
+                    self.verify_type_node(config, &fdef.signature.0)?
+                }
+            }
+        }
         Ok(())
     }
 
@@ -168,6 +175,27 @@ impl<'a> LimitsVerifier<'a> {
                 }
             }
         }
+        // Enums count towards the struct definition limit, and each of their variants towards the
+        // field limit
+        if let Some(defs) = self.resolver.enum_defs() {
+            let struct_def_count = self.resolver.struct_defs().map_or(0, |defs| defs.len());
+            if let Some(max_struct_definitions) = config.max_struct_definitions {
+                if struct_def_count + defs.len() > max_struct_definitions {
+                    return Err(PartialVMError::new(
+                        StatusCode::MAX_STRUCT_DEFINITIONS_REACHED,
+                    ));
+                }
+            }
+            if let Some(max_fields_in_struct) = config.max_fields_in_struct {
+                for variant in defs.iter().flat_map(|def| &def.variants) {
+                    if variant.fields.len() > max_fields_in_struct {
+                        return Err(PartialVMError::new(
+                            StatusCode::MAX_FIELD_DEFINITIONS_REACHED,
+                        ));
+                    }
+                }
+            }
+        }
         Ok(())
     }
 }
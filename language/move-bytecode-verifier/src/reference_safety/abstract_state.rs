@@ -11,8 +11,8 @@ use move_binary_format::{
     binary_views::FunctionView,
     errors::{PartialVMError, PartialVMResult},
     file_format::{
-        CodeOffset, FieldHandleIndex, FunctionDefinitionIndex, LocalIndex, Signature,
-        SignatureToken, StructDefinitionIndex,
+        CodeOffset, EnumDefinitionIndex, FieldHandleIndex, FunctionDefinitionIndex, LocalIndex,
+        MemberCount, Signature, SignatureToken, StructDefinitionIndex, VariantTag,
     },
     safe_unwrap,
 };
@@ -59,6 +59,7 @@ enum Label {
     Local(LocalIndex),
     Global(StructDefinitionIndex),
     Field(FieldHandleIndex),
+    VariantField(EnumDefinitionIndex, VariantTag, MemberCount),
 }
 
 // Needed for debugging with the borrow graph
@@ -68,6 +69,7 @@ impl std::fmt::Display for Label {
             Label::Local(i) => write!(f, "local#{}", i),
             Label::Global(i) => write!(f, "resource@{}", i),
             Label::Field(i) => write!(f, "field#{}", i),
+            Label::VariantField(e, v, i) => write!(f, "variant_field#{}::{}::{}", e, v, i),
         }
     }
 }
@@ -177,6 +179,11 @@ impl AbstractState {
             .add_strong_field_borrow((), parent, Label::Field(field), child)
     }
 
+    fn add_variant_field_borrow(&mut self, parent: RefID, label: Label, child: RefID) {
+        self.borrow_graph
+            .add_strong_field_borrow((), parent, label, child)
+    }
+
     fn add_local_borrow(&mut self, local: LocalIndex, id: RefID) {
         self.borrow_graph
             .add_strong_field_borrow((), self.frame_root(), Label::Local(local), id)
@@ -248,15 +255,15 @@ impl AbstractState {
     /// checks if `id` is freezable
     /// - Mutable references are freezable if there are no consistent mutable borrows
     /// - Immutable references are not freezable by the typing rules
-    fn is_freezable(&self, id: RefID, at_field_opt: Option<FieldHandleIndex>) -> bool {
+    fn is_freezable(&self, id: RefID, at_field_opt: Option<Label>) -> bool {
         assert!(self.borrow_graph.is_mutable(id));
-        !self.has_consistent_mutable_borrows(id, at_field_opt.map(Label::Field))
+        !self.has_consistent_mutable_borrows(id, at_field_opt)
     }
 
     /// checks if `id` is readable
     /// - Mutable references are readable if they are freezable
     /// - Immutable references are always readable
-    fn is_readable(&self, id: RefID, at_field_opt: Option<FieldHandleIndex>) -> bool {
+    fn is_readable(&self, id: RefID, at_field_opt: Option<Label>) -> bool {
         let is_mutable = self.borrow_graph.is_mutable(id);
         !is_mutable || self.is_freezable(id, at_field_opt)
     }
@@ -439,7 +446,8 @@ impl AbstractState {
         let is_mut_borrow_with_full_borrows = || mut_ && self.has_full_borrows(id);
         // For new immutable borrow, the reference must be readable at that field
         // This means that there could exist a mutable borrow on some other field
-        let is_imm_borrow_with_mut_borrows = || !mut_ && !self.is_readable(id, Some(field));
+        let is_imm_borrow_with_mut_borrows =
+            || !mut_ && !self.is_readable(id, Some(Label::Field(field)));
 
         if is_mut_borrow_with_full_borrows() || is_imm_borrow_with_mut_borrows() {
             // TODO improve error for mutable case
@@ -452,6 +460,58 @@ impl AbstractState {
         Ok(AbstractValue::Reference(field_borrow_id))
     }
 
+    /// Unpacks the reference `id` to a variant of enum `enum_def` into references to each of its
+    /// `field_count` fields
+    pub fn unpack_variant_ref(
+        &mut self,
+        offset: CodeOffset,
+        mut_: bool,
+        id: RefID,
+        enum_def: EnumDefinitionIndex,
+        variant: VariantTag,
+        field_count: MemberCount,
+    ) -> PartialVMResult<Vec<AbstractValue>> {
+        let labels: Vec<_> = (0..field_count)
+            .map(|i| Label::VariantField(enum_def, variant, i))
+            .collect();
+        // As with fields of structs, any field borrows will be factored out in the mutable case
+        // and each field must be readable in the immutable case
+        let is_mut_borrow_with_full_borrows = || mut_ && self.has_full_borrows(id);
+        let is_imm_borrow_with_mut_borrows = || {
+            !mut_
+                && labels
+                    .iter()
+                    .any(|label| !self.is_readable(id, Some(label.clone())))
+        };
+        if is_mut_borrow_with_full_borrows() || is_imm_borrow_with_mut_borrows() {
+            return Err(self.error(StatusCode::BORROWFIELD_EXISTS_MUTABLE_BORROW_ERROR, offset));
+        }
+
+        let field_refs = labels
+            .into_iter()
+            .map(|label| {
+                let field_borrow_id = self.new_ref(mut_);
+                self.add_variant_field_borrow(id, label, field_borrow_id);
+                AbstractValue::Reference(field_borrow_id)
+            })
+            .collect();
+        self.release(id);
+        Ok(field_refs)
+    }
+
+    /// Reads the tag of the variant behind `id`
+    pub fn test_variant(
+        &mut self,
+        offset: CodeOffset,
+        id: RefID,
+    ) -> PartialVMResult<AbstractValue> {
+        if !self.is_readable(id, None) {
+            return Err(self.error(StatusCode::READREF_EXISTS_MUTABLE_BORROW_ERROR, offset));
+        }
+        self.release(id);
+        Ok(AbstractValue::NonReference)
+    }
+
     pub fn borrow_global(
         &mut self,
         offset: CodeOffset,
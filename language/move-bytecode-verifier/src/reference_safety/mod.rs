@@ -22,8 +22,9 @@ use move_binary_format::{
     binary_views::{BinaryIndexedView, FunctionView},
     errors::{PartialVMError, PartialVMResult},
     file_format::{
-        Bytecode, CodeOffset, FunctionDefinitionIndex, FunctionHandle, IdentifierIndex,
-        SignatureIndex, SignatureToken, StructDefinition, StructFieldInformation,
+        Bytecode, CodeOffset, EnumDefinitionIndex, FunctionDefinitionIndex, FunctionHandle,
+        IdentifierIndex, SignatureIndex, SignatureToken, StructDefinition, StructFieldInformation,
+        VariantHandleIndex, VariantInstantiationHandleIndex, VariantTag,
     },
     safe_assert, safe_unwrap,
 };
@@ -130,6 +131,63 @@ fn unpack(
     Ok(())
 }
 
+/// Resolves a variant handle to its enum, its tag, and its number of fields
+fn variant(
+    verifier: &ReferenceSafetyAnalysis,
+    idx: VariantHandleIndex,
+) -> PartialVMResult<(EnumDefinitionIndex, VariantTag, usize)> {
+    let handle = verifier.resolver.variant_handle_at(idx)?;
+    let variant_def = verifier
+        .resolver
+        .variant_def_at(handle.enum_def, handle.variant)?;
+    Ok((handle.enum_def, handle.variant, variant_def.fields.len()))
+}
+
+fn variant_generic(
+    verifier: &ReferenceSafetyAnalysis,
+    idx: VariantInstantiationHandleIndex,
+) -> PartialVMResult<(EnumDefinitionIndex, VariantTag, usize)> {
+    let handle = verifier.resolver.variant_instantiation_handle_at(idx)?;
+    let enum_inst = verifier.resolver.enum_instantiation_at(handle.enum_def)?;
+    let variant_def = verifier
+        .resolver
+        .variant_def_at(enum_inst.def, handle.variant)?;
+    Ok((enum_inst.def, handle.variant, variant_def.fields.len()))
+}
+
+fn pack_variant(verifier: &mut ReferenceSafetyAnalysis, field_count: usize) -> PartialVMResult<()> {
+    for _ in 0..field_count {
+        safe_assert!(safe_unwrap!(verifier.stack.pop()).is_value())
+    }
+    verifier.stack.push(AbstractValue::NonReference);
+    Ok(())
+}
+
+fn unpack_variant(
+    verifier: &mut ReferenceSafetyAnalysis,
+    field_count: usize,
+) -> PartialVMResult<()> {
+    safe_assert!(safe_unwrap!(verifier.stack.pop()).is_value());
+    for _ in 0..field_count {
+        verifier.stack.push(AbstractValue::NonReference)
+    }
+    Ok(())
+}
+
+fn unpack_variant_ref(
+    verifier: &mut ReferenceSafetyAnalysis,
+    state: &mut AbstractState,
+    offset: CodeOffset,
+    mut_: bool,
+    (enum_def, tag, field_count): (EnumDefinitionIndex, VariantTag, usize),
+) -> PartialVMResult<()> {
+    let id = safe_unwrap!(safe_unwrap!(verifier.stack.pop()).ref_id());
+    let field_refs =
+        state.unpack_variant_ref(offset, mut_, id, enum_def, tag, field_count as u16)?;
+    verifier.stack.extend(field_refs);
+    Ok(())
+}
+
 fn vec_element_type(
     verifier: &mut ReferenceSafetyAnalysis,
     idx: SignatureIndex,
@@ -361,6 +419,44 @@ fn execute_inner(
             unpack(verifier, struct_def)?
         }
 
+        Bytecode::PackVariant(idx) => {
+            let (_, _, field_count) = variant(verifier, *idx)?;
+            pack_variant(verifier, field_count)?
+        }
+        Bytecode::PackVariantGeneric(idx) => {
+            let (_, _, field_count) = variant_generic(verifier, *idx)?;
+            pack_variant(verifier, field_count)?
+        }
+        Bytecode::UnpackVariant(idx) => {
+            let (_, _, field_count) = variant(verifier, *idx)?;
+            unpack_variant(verifier, field_count)?
+        }
+        Bytecode::UnpackVariantGeneric(idx) => {
+            let (_, _, field_count) = variant_generic(verifier, *idx)?;
+            unpack_variant(verifier, field_count)?
+        }
+        Bytecode::UnpackVariantImmRef(idx) => {
+            let v = variant(verifier, *idx)?;
+            unpack_variant_ref(verifier, state, offset, false, v)?
+        }
+        Bytecode::UnpackVariantImmRefGeneric(idx) => {
+            let v = variant_generic(verifier, *idx)?;
+            unpack_variant_ref(verifier, state, offset, false, v)?
+        }
+        Bytecode::UnpackVariantMutRef(idx) => {
+            let v = variant(verifier, *idx)?;
+            unpack_variant_ref(verifier, state, offset, true, v)?
+        }
+        Bytecode::UnpackVariantMutRefGeneric(idx) => {
+            let v = variant_generic(verifier, *idx)?;
+            unpack_variant_ref(verifier, state, offset, true, v)?
+        }
+        Bytecode::TestVariant(_) | Bytecode::TestVariantGeneric(_) => {
+            let id = safe_unwrap!(safe_unwrap!(verifier.stack.pop()).ref_id());
+            let value = state.test_variant(offset, id)?;
+            verifier.stack.push(value)
+        }
+
         Bytecode::VecPack(idx, num) => {
             for _ in 0..*num {
                 safe_assert!(safe_unwrap!(verifier.stack.pop()).is_value())
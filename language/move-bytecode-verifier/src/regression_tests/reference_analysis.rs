@@ -152,6 +152,10 @@ fn too_many_locals() {
                 code: vec![CopyLoc(2), StLoc(33), Branch(0)],
             }),
         }],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     };
 
     let res = crate::verify_module(&module);
@@ -203,6 +207,10 @@ fn borrow_graph() {
                 code: vec![MoveLoc(0), MoveLoc(1), StLoc(0), StLoc(1), Branch(0)],
             }),
         }],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     };
 
     let res = crate::verify_module(&module);
@@ -10,9 +10,9 @@ use move_binary_format::{
     binary_views::BinaryIndexedView,
     errors::{Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
-        AbilitySet, Bytecode, CodeUnit, CompiledModule, CompiledScript, FunctionDefinition,
-        FunctionHandle, Signature, SignatureIndex, SignatureToken, StructDefinition,
-        StructFieldInformation, StructTypeParameter, TableIndex,
+        AbilitySet, Bytecode, CodeUnit, CompiledModule, CompiledScript, EnumDefinition,
+        FieldDefinition, FunctionDefinition, FunctionHandle, Signature, SignatureIndex,
+        SignatureToken, StructDefinition, StructFieldInformation, StructTypeParameter, TableIndex,
     },
     file_format_common::VERSION_6,
     IndexKind,
@@ -35,6 +35,7 @@ impl<'a> SignatureChecker<'a> {
         sig_check.verify_signature_pool(module.signatures())?;
         sig_check.verify_function_signatures(module.function_handles())?;
         sig_check.verify_fields(module.struct_defs())?;
+        sig_check.verify_variant_fields(module.enum_defs())?;
         sig_check.verify_code_units(module.function_defs())
     }
 
@@ -93,25 +94,47 @@ impl<'a> SignatureChecker<'a> {
                 err.at_index(IndexKind::FieldDefinition, idx as TableIndex)
                     .at_index(IndexKind::StructDefinition, struct_def_idx as TableIndex)
             };
-            for (field_offset, field_def) in fields.iter().enumerate() {
-                self.check_signature_token(&field_def.signature.0)
-                    .map_err(|err| err_handler(err, field_offset))?;
-                let type_param_constraints: Vec<_> =
-                    struct_handle.type_param_constraints().collect();
-                self.check_type_instantiation(&field_def.signature.0, &type_param_constraints)
-                    .map_err(|err| err_handler(err, field_offset))?;
+            self.check_fields(fields, &struct_handle.type_parameters)
+                .map_err(|(err, field_offset)| err_handler(err, field_offset))?;
+        }
+        Ok(())
+    }
 
-                self.check_phantom_params(
-                    &field_def.signature.0,
-                    false,
-                    &struct_handle.type_parameters,
-                )
-                .map_err(|err| err_handler(err, field_offset))?;
+    fn verify_variant_fields(&self, enum_defs: &[EnumDefinition]) -> PartialVMResult<()> {
+        for (enum_def_idx, enum_def) in enum_defs.iter().enumerate() {
+            let enum_handle = self.resolver.struct_handle_at(enum_def.enum_handle);
+            for variant in &enum_def.variants {
+                self.check_fields(&variant.fields, &enum_handle.type_parameters)
+                    .map_err(|(err, field_offset)| {
+                        err.at_index(IndexKind::FieldDefinition, field_offset as TableIndex)
+                            .at_index(IndexKind::EnumDefinition, enum_def_idx as TableIndex)
+                    })?;
             }
         }
         Ok(())
     }
 
+    /// Checks the field types of a struct or of an enum variant, returning the offset of the
+    /// offending field on failure
+    fn check_fields(
+        &self,
+        fields: &[FieldDefinition],
+        type_parameters: &[StructTypeParameter],
+    ) -> Result<(), (PartialVMError, usize)> {
+        let type_param_constraints: Vec<_> =
+            type_parameters.iter().map(|p| p.constraints).collect();
+        for (field_offset, field_def) in fields.iter().enumerate() {
+            self.check_signature_token(&field_def.signature.0)
+                .map_err(|err| (err, field_offset))?;
+            self.check_type_instantiation(&field_def.signature.0, &type_param_constraints)
+                .map_err(|err| (err, field_offset))?;
+
+            self.check_phantom_params(&field_def.signature.0, false, type_parameters)
+                .map_err(|err| (err, field_offset))?;
+        }
+        Ok(())
+    }
+
     fn verify_code_units(&self, function_defs: &[FunctionDefinition]) -> PartialVMResult<()> {
         for (func_def_idx, func_def) in function_defs.iter().enumerate() {
             // skip native functions
@@ -179,6 +202,23 @@ impl<'a> SignatureChecker<'a> {
                         type_parameters,
                     )
                 }
+                PackVariantGeneric(idx)
+                | UnpackVariantGeneric(idx)
+                | UnpackVariantImmRefGeneric(idx)
+                | UnpackVariantMutRefGeneric(idx)
+                | TestVariantGeneric(idx) => {
+                    let variant_inst = self.resolver.variant_instantiation_handle_at(*idx)?;
+                    let enum_inst = self.resolver.enum_instantiation_at(variant_inst.enum_def)?;
+                    let enum_def = self.resolver.enum_def_at(enum_inst.def)?;
+                    let enum_handle = self.resolver.struct_handle_at(enum_def.enum_handle);
+                    let type_arguments = &self.resolver.signature_at(enum_inst.type_parameters).0;
+                    self.check_signature_tokens(type_arguments)?;
+                    self.check_generic_instance(
+                        type_arguments,
+                        enum_handle.type_param_constraints(),
+                        type_parameters,
+                    )
+                }
                 VecPack(idx, _)
                 | VecLen(idx)
                 | VecImmBorrow(idx)
@@ -202,14 +242,70 @@ impl<'a> SignatureChecker<'a> {
 
                 // List out the other options explicitly so there's a compile error if a new
                 // bytecode gets added.
-                Pop | Ret | Branch(_) | BrTrue(_) | BrFalse(_) | LdU8(_) | LdU16(_) | LdU32(_)
-                | LdU64(_) | LdU128(_) | LdU256(_) | LdConst(_) | CastU8 | CastU16 | CastU32
-                | CastU64 | CastU128 | CastU256 | LdTrue | LdFalse | Call(_) | Pack(_)
-                | Unpack(_) | ReadRef | WriteRef | FreezeRef | Add | Sub | Mul | Mod | Div
-                | BitOr | BitAnd | Xor | Shl | Shr | Or | And | Not | Eq | Neq | Lt | Gt | Le
-                | Ge | CopyLoc(_) | MoveLoc(_) | StLoc(_) | MutBorrowLoc(_) | ImmBorrowLoc(_)
-                | MutBorrowField(_) | ImmBorrowField(_) | MutBorrowGlobal(_)
-                | ImmBorrowGlobal(_) | Exists(_) | MoveTo(_) | MoveFrom(_) | Abort | Nop => Ok(()),
+                Pop
+                | Ret
+                | Branch(_)
+                | BrTrue(_)
+                | BrFalse(_)
+                | LdU8(_)
+                | LdU16(_)
+                | LdU32(_)
+                | LdU64(_)
+                | LdU128(_)
+                | LdU256(_)
+                | LdConst(_)
+                | CastU8
+                | CastU16
+                | CastU32
+                | CastU64
+                | CastU128
+                | CastU256
+                | LdTrue
+                | LdFalse
+                | Call(_)
+                | Pack(_)
+                | Unpack(_)
+                | ReadRef
+                | WriteRef
+                | FreezeRef
+                | Add
+                | Sub
+                | Mul
+                | Mod
+                | Div
+                | BitOr
+                | BitAnd
+                | Xor
+                | Shl
+                | Shr
+                | Or
+                | And
+                | Not
+                | Eq
+                | Neq
+                | Lt
+                | Gt
+                | Le
+                | Ge
+                | CopyLoc(_)
+                | MoveLoc(_)
+                | StLoc(_)
+                | MutBorrowLoc(_)
+                | ImmBorrowLoc(_)
+                | MutBorrowField(_)
+                | ImmBorrowField(_)
+                | MutBorrowGlobal(_)
+                | ImmBorrowGlobal(_)
+                | Exists(_)
+                | MoveTo(_)
+                | MoveFrom(_)
+                | Abort
+                | Nop
+                | PackVariant(_)
+                | UnpackVariant(_)
+                | UnpackVariantImmRef(_)
+                | UnpackVariantMutRef(_)
+                | TestVariant(_) => Ok(()),
             };
             result.map_err(|err| {
                 err.append_message_with_separator(' ', format!("at offset {} ", offset))
@@ -160,7 +160,9 @@ impl<'a> StackUsageVerifier<'a> {
             | Bytecode::CastU128
             | Bytecode::CastU256
             | Bytecode::VecLen(_)
-            | Bytecode::VecPopBack(_) => (1, 1),
+            | Bytecode::VecPopBack(_)
+            | Bytecode::TestVariant(_)
+            | Bytecode::TestVariantGeneric(_) => (1, 1),
 
             // Binary operations (pop twice and push once)
             Bytecode::Add
@@ -263,6 +265,44 @@ impl<'a> StackUsageVerifier<'a> {
                 };
                 (1, field_count as u64)
             }
+
+            // PackVariant performs `num_fields` pops and one push
+            Bytecode::PackVariant(idx) => {
+                let handle = self.resolver.variant_handle_at(*idx)?;
+                let variant = self
+                    .resolver
+                    .variant_def_at(handle.enum_def, handle.variant)?;
+                (variant.fields.len() as u64, 1)
+            }
+            Bytecode::PackVariantGeneric(idx) => {
+                let handle = self.resolver.variant_instantiation_handle_at(*idx)?;
+                let enum_inst = self.resolver.enum_instantiation_at(handle.enum_def)?;
+                let variant = self
+                    .resolver
+                    .variant_def_at(enum_inst.def, handle.variant)?;
+                (variant.fields.len() as u64, 1)
+            }
+
+            // UnpackVariant and its reference forms perform one pop and `num_fields` pushes
+            Bytecode::UnpackVariant(idx)
+            | Bytecode::UnpackVariantImmRef(idx)
+            | Bytecode::UnpackVariantMutRef(idx) => {
+                let handle = self.resolver.variant_handle_at(*idx)?;
+                let variant = self
+                    .resolver
+                    .variant_def_at(handle.enum_def, handle.variant)?;
+                (1, variant.fields.len() as u64)
+            }
+            Bytecode::UnpackVariantGeneric(idx)
+            | Bytecode::UnpackVariantImmRefGeneric(idx)
+            | Bytecode::UnpackVariantMutRefGeneric(idx) => {
+                let handle = self.resolver.variant_instantiation_handle_at(*idx)?;
+                let enum_inst = self.resolver.enum_instantiation_at(handle.enum_def)?;
+                let variant = self
+                    .resolver
+                    .variant_def_at(enum_inst.def, handle.variant)?;
+                (1, variant.fields.len() as u64)
+            }
         })
     }
 
@@ -2,17 +2,17 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-//! This module provides a checker for verifying that struct definitions in a module are not
-//! recursive. Since the module dependency graph is acylic by construction, applying this checker to
+//! This module provides a checker for verifying that struct and enum definitions in a module are
+//! not recursive. Since the module dependency graph is acylic by construction, applying this checker to
 //! each module in isolation guarantees that there is no structural recursion globally.
 use move_binary_format::{
     access::ModuleAccess,
     errors::{verification_error, Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
-        CompiledModule, SignatureToken, StructDefinitionIndex, StructHandleIndex, TableIndex,
+        CompiledModule, FieldDefinition, SignatureToken, StructFieldInformation, StructHandleIndex,
+        TableIndex,
     },
     internals::ModuleIndex,
-    views::StructDefinitionView,
     IndexKind,
 };
 use move_core_types::vm_status::StatusCode;
@@ -36,44 +36,71 @@ impl<'a> RecursiveStructDefChecker<'a> {
         // the iterative solution here as this code may be dealing with untrusted data.
         match toposort(&graph, None) {
             Ok(_) => Ok(()),
-            Err(cycle) => Err(verification_error(
-                StatusCode::RECURSIVE_STRUCT_DEFINITION,
-                IndexKind::StructDefinition,
-                cycle.node_id().into_index() as TableIndex,
-            )),
+            Err(cycle) => {
+                let sh_idx = cycle.node_id();
+                let (kind, idx) = match module
+                    .struct_defs()
+                    .iter()
+                    .position(|def| def.struct_handle == sh_idx)
+                {
+                    Some(idx) => (IndexKind::StructDefinition, idx),
+                    None => (
+                        IndexKind::EnumDefinition,
+                        module
+                            .enum_defs()
+                            .iter()
+                            .position(|def| def.enum_handle == sh_idx)
+                            .unwrap_or_else(|| sh_idx.into_index()),
+                    ),
+                };
+                Err(verification_error(
+                    StatusCode::RECURSIVE_STRUCT_DEFINITION,
+                    kind,
+                    idx as TableIndex,
+                ))
+            }
         }
     }
 }
 
-/// Given a module, build a graph of struct definitions. This is useful when figuring out whether
-/// the struct definitions in module form a cycle.
+/// Given a module, build a graph of struct and enum definitions, identified by their handles. This
+/// is useful when figuring out whether the definitions in module form a cycle.
 struct StructDefGraphBuilder<'a> {
     module: &'a CompiledModule,
-    /// Used to follow field definitions' signatures' struct handles to their struct definitions.
-    handle_to_def: BTreeMap<StructHandleIndex, StructDefinitionIndex>,
+    /// The handles of the structs and enums defined in the module. Used to follow field
+    /// definitions' signatures' struct handles to their definitions.
+    defined_handles: BTreeSet<StructHandleIndex>,
 }
 
 impl<'a> StructDefGraphBuilder<'a> {
     fn new(module: &'a CompiledModule) -> Self {
-        let mut handle_to_def = BTreeMap::new();
-        // the mapping from struct definitions to struct handles is already checked to be 1-1 by
-        // DuplicationChecker
-        for (idx, struct_def) in module.struct_defs().iter().enumerate() {
-            let sh_idx = struct_def.struct_handle;
-            handle_to_def.insert(sh_idx, StructDefinitionIndex(idx as TableIndex));
-        }
+        // the mapping from struct and enum definitions to struct handles is already checked to be
+        // 1-1 by DuplicationChecker
+        let defined_handles = module
+            .struct_defs()
+            .iter()
+            .map(|def| def.struct_handle)
+            .chain(module.enum_defs().iter().map(|def| def.enum_handle))
+            .collect();
 
         Self {
             module,
-            handle_to_def,
+            defined_handles,
         }
     }
 
-    fn build(self) -> PartialVMResult<DiGraphMap<StructDefinitionIndex, ()>> {
+    fn build(self) -> PartialVMResult<DiGraphMap<StructHandleIndex, ()>> {
         let mut neighbors = BTreeMap::new();
-        for idx in 0..self.module.struct_defs().len() {
-            let sd_idx = StructDefinitionIndex::new(idx as TableIndex);
-            self.add_struct_defs(&mut neighbors, sd_idx)?
+        for struct_def in self.module.struct_defs() {
+            let fields = match &struct_def.field_information {
+                StructFieldInformation::Native => [].iter(),
+                StructFieldInformation::Declared(fields) => fields.iter(),
+            };
+            self.add_fields(&mut neighbors, struct_def.struct_handle, fields)?
+        }
+        for enum_def in self.module.enum_defs() {
+            let fields = enum_def.variants.iter().flat_map(|variant| &variant.fields);
+            self.add_fields(&mut neighbors, enum_def.enum_handle, fields)?
         }
 
         let edges = neighbors
@@ -82,25 +109,22 @@ impl<'a> StructDefGraphBuilder<'a> {
         Ok(DiGraphMap::from_edges(edges))
     }
 
-    fn add_struct_defs(
+    fn add_fields<'f>(
         &self,
-        neighbors: &mut BTreeMap<StructDefinitionIndex, BTreeSet<StructDefinitionIndex>>,
-        idx: StructDefinitionIndex,
+        neighbors: &mut BTreeMap<StructHandleIndex, BTreeSet<StructHandleIndex>>,
+        idx: StructHandleIndex,
+        fields: impl Iterator<Item = &'f FieldDefinition>,
     ) -> PartialVMResult<()> {
-        let struct_def = self.module.struct_def_at(idx);
-        let struct_def = StructDefinitionView::new(self.module, struct_def);
-        // The fields iterator is an option in the case of native structs. Flatten makes an empty
-        // iterator for that case
-        for field in struct_def.fields().into_iter().flatten() {
-            self.add_signature_token(neighbors, idx, field.signature_token())?
+        for field in fields {
+            self.add_signature_token(neighbors, idx, &field.signature.0)?
         }
         Ok(())
     }
 
     fn add_signature_token(
         &self,
-        neighbors: &mut BTreeMap<StructDefinitionIndex, BTreeSet<StructDefinitionIndex>>,
-        cur_idx: StructDefinitionIndex,
+        neighbors: &mut BTreeMap<StructHandleIndex, BTreeSet<StructHandleIndex>>,
+        cur_idx: StructHandleIndex,
         token: &SignatureToken,
     ) -> PartialVMResult<()> {
         use SignatureToken as T;
@@ -123,19 +147,19 @@ impl<'a> StructDefGraphBuilder<'a> {
             }
             T::Vector(inner) => self.add_signature_token(neighbors, cur_idx, inner)?,
             T::Struct(sh_idx) => {
-                if let Some(struct_def_idx) = self.handle_to_def.get(sh_idx) {
+                if self.defined_handles.contains(sh_idx) {
                     neighbors
                         .entry(cur_idx)
                         .or_insert_with(BTreeSet::new)
-                        .insert(*struct_def_idx);
+                        .insert(*sh_idx);
                 }
             }
             T::StructInstantiation(sh_idx, inners) => {
-                if let Some(struct_def_idx) = self.handle_to_def.get(sh_idx) {
+                if self.defined_handles.contains(sh_idx) {
                     neighbors
                         .entry(cur_idx)
                         .or_insert_with(BTreeSet::new)
-                        .insert(*struct_def_idx);
+                        .insert(*sh_idx);
                 }
                 for t in inners {
                     self.add_signature_token(neighbors, cur_idx, t)?
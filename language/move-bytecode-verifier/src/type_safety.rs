@@ -11,9 +11,10 @@ use move_binary_format::{
     control_flow_graph::ControlFlowGraph,
     errors::{PartialVMError, PartialVMResult},
     file_format::{
-        AbilitySet, Bytecode, CodeOffset, FieldHandleIndex, FunctionDefinitionIndex,
-        FunctionHandle, LocalIndex, Signature, SignatureToken, SignatureToken as ST,
-        StructDefinition, StructDefinitionIndex, StructFieldInformation, StructHandleIndex,
+        AbilitySet, Bytecode, CodeOffset, EnumDefinitionIndex, FieldHandleIndex,
+        FunctionDefinitionIndex, FunctionHandle, LocalIndex, Signature, SignatureToken,
+        SignatureToken as ST, StructDefinition, StructDefinitionIndex, StructFieldInformation,
+        StructHandleIndex, VariantTag,
     },
     safe_unwrap,
 };
@@ -318,6 +319,93 @@ fn unpack(
     Ok(())
 }
 
+fn pack_variant(
+    verifier: &mut TypeSafetyChecker,
+    meter: &mut impl Meter,
+    offset: CodeOffset,
+    enum_def: EnumDefinitionIndex,
+    variant: VariantTag,
+    type_args: &Signature,
+) -> PartialVMResult<()> {
+    let enum_handle = verifier.resolver.enum_def_at(enum_def)?.enum_handle;
+    let variant_def = verifier.resolver.variant_def_at(enum_def, variant)?;
+    let field_sig: Vec<_> = variant_def
+        .fields
+        .iter()
+        .map(|field_def| instantiate(&field_def.signature.0, type_args))
+        .collect();
+    for sig in field_sig.iter().rev() {
+        let arg = safe_unwrap!(verifier.stack.pop());
+        if &arg != sig {
+            return Err(verifier.error(StatusCode::PACK_TYPE_MISMATCH_ERROR, offset));
+        }
+    }
+
+    verifier.push(meter, materialize_type(enum_handle, type_args))?;
+    Ok(())
+}
+
+/// Helper for `UnpackVariant` and its reference forms. `ref_kind` is `None` when unpacking by
+/// value, and `Some(mut_)` when unpacking through a reference
+fn unpack_variant(
+    verifier: &mut TypeSafetyChecker,
+    meter: &mut impl Meter,
+    offset: CodeOffset,
+    ref_kind: Option<bool>,
+    enum_def: EnumDefinitionIndex,
+    variant: VariantTag,
+    type_args: &Signature,
+) -> PartialVMResult<()> {
+    let enum_handle = verifier.resolver.enum_def_at(enum_def)?.enum_handle;
+    let enum_type = materialize_type(enum_handle, type_args);
+
+    let arg = safe_unwrap!(verifier.stack.pop());
+    let matches = match (ref_kind, arg) {
+        (None, arg) => arg == enum_type,
+        (Some(false), ST::Reference(inner) | ST::MutableReference(inner))
+        | (Some(true), ST::MutableReference(inner)) => *inner == enum_type,
+        (Some(_), _) => false,
+    };
+    if !matches {
+        return Err(verifier.error(StatusCode::UNPACK_TYPE_MISMATCH_ERROR, offset));
+    }
+
+    let variant_def = verifier.resolver.variant_def_at(enum_def, variant)?;
+    let field_sig: Vec<_> = variant_def
+        .fields
+        .iter()
+        .map(|field_def| {
+            let field_type = instantiate(&field_def.signature.0, type_args);
+            match ref_kind {
+                None => field_type,
+                Some(false) => ST::Reference(Box::new(field_type)),
+                Some(true) => ST::MutableReference(Box::new(field_type)),
+            }
+        })
+        .collect();
+    for sig in field_sig {
+        verifier.push(meter, sig)?
+    }
+    Ok(())
+}
+
+fn test_variant(
+    verifier: &mut TypeSafetyChecker,
+    meter: &mut impl Meter,
+    offset: CodeOffset,
+    enum_def: EnumDefinitionIndex,
+    type_args: &Signature,
+) -> PartialVMResult<()> {
+    let enum_handle = verifier.resolver.enum_def_at(enum_def)?.enum_handle;
+    let enum_type = materialize_type(enum_handle, type_args);
+    match safe_unwrap!(verifier.stack.pop()) {
+        ST::Reference(inner) | ST::MutableReference(inner) if *inner == enum_type => (),
+        _ => return Err(verifier.error(StatusCode::TEST_VARIANT_TYPE_MISMATCH_ERROR, offset)),
+    }
+    verifier.push(meter, ST::Bool)?;
+    Ok(())
+}
+
 fn exists(
     verifier: &mut TypeSafetyChecker,
     meter: &mut impl Meter,
@@ -623,6 +711,91 @@ fn verify_instr(
             unpack(verifier, meter, offset, struct_def, type_args)?
         }
 
+        Bytecode::PackVariant(idx) => {
+            let handle = verifier.resolver.variant_handle_at(*idx)?;
+            let (enum_def, variant) = (handle.enum_def, handle.variant);
+            pack_variant(
+                verifier,
+                meter,
+                offset,
+                enum_def,
+                variant,
+                &Signature(vec![]),
+            )?
+        }
+
+        Bytecode::PackVariantGeneric(idx) => {
+            let handle = verifier.resolver.variant_instantiation_handle_at(*idx)?;
+            let enum_inst = verifier.resolver.enum_instantiation_at(handle.enum_def)?;
+            let type_args = verifier.resolver.signature_at(enum_inst.type_parameters);
+            verifier.charge_tys(meter, &type_args.0)?;
+            pack_variant(
+                verifier,
+                meter,
+                offset,
+                enum_inst.def,
+                handle.variant,
+                type_args,
+            )?
+        }
+
+        Bytecode::UnpackVariant(idx)
+        | Bytecode::UnpackVariantImmRef(idx)
+        | Bytecode::UnpackVariantMutRef(idx) => {
+            let ref_kind = match bytecode {
+                Bytecode::UnpackVariantImmRef(_) => Some(false),
+                Bytecode::UnpackVariantMutRef(_) => Some(true),
+                _ => None,
+            };
+            let handle = verifier.resolver.variant_handle_at(*idx)?;
+            let (enum_def, variant) = (handle.enum_def, handle.variant);
+            unpack_variant(
+                verifier,
+                meter,
+                offset,
+                ref_kind,
+                enum_def,
+                variant,
+                &Signature(vec![]),
+            )?
+        }
+
+        Bytecode::UnpackVariantGeneric(idx)
+        | Bytecode::UnpackVariantImmRefGeneric(idx)
+        | Bytecode::UnpackVariantMutRefGeneric(idx) => {
+            let ref_kind = match bytecode {
+                Bytecode::UnpackVariantImmRefGeneric(_) => Some(false),
+                Bytecode::UnpackVariantMutRefGeneric(_) => Some(true),
+                _ => None,
+            };
+            let handle = verifier.resolver.variant_instantiation_handle_at(*idx)?;
+            let enum_inst = verifier.resolver.enum_instantiation_at(handle.enum_def)?;
+            let type_args = verifier.resolver.signature_at(enum_inst.type_parameters);
+            verifier.charge_tys(meter, &type_args.0)?;
+            unpack_variant(
+                verifier,
+                meter,
+                offset,
+                ref_kind,
+                enum_inst.def,
+                handle.variant,
+                type_args,
+            )?
+        }
+
+        Bytecode::TestVariant(idx) => {
+            let enum_def = verifier.resolver.variant_handle_at(*idx)?.enum_def;
+            test_variant(verifier, meter, offset, enum_def, &Signature(vec![]))?
+        }
+
+        Bytecode::TestVariantGeneric(idx) => {
+            let handle = verifier.resolver.variant_instantiation_handle_at(*idx)?;
+            let enum_inst = verifier.resolver.enum_instantiation_at(handle.enum_def)?;
+            let type_args = verifier.resolver.signature_at(enum_inst.type_parameters);
+            verifier.charge_tys(meter, &type_args.0)?;
+            test_variant(verifier, meter, offset, enum_inst.def, type_args)?
+        }
+
         Bytecode::ReadRef => {
             let operand = safe_unwrap!(verifier.stack.pop());
             match operand {
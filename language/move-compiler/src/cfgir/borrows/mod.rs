@@ -155,12 +155,25 @@ fn lvalue(context: &mut Context, sp!(loc, l_): &LValue, value: Value) {
             let diags = context.borrow_state.assign_local(*loc, v, value);
             context.add_diags(diags)
         }
-        L::Unpack(_, _, fields) => {
+        L::Unpack(_, _, fields) | L::UnpackVariant(_, _, UnpackType::ByValue, _, fields) => {
             assert!(!value.is_ref());
             fields
                 .iter()
                 .for_each(|(_, l)| lvalue(context, l, Value::NonRef))
         }
+        L::UnpackVariant(_, _, unpack_type, _, fields) => {
+            let mut_ = matches!(unpack_type, UnpackType::ByMutRef);
+            let field_names = fields.iter().map(|(f, _)| *f).collect::<Vec<_>>();
+            let (diags, field_values) =
+                context
+                    .borrow_state
+                    .borrow_variant_fields(*loc, mut_, value, &field_names);
+            context.add_diags(diags);
+            fields
+                .iter()
+                .zip(field_values)
+                .for_each(|((_, l), fvalue)| lvalue(context, l, fvalue))
+        }
     }
 }
 
@@ -281,7 +294,7 @@ fn exp(context: &mut Context, parent_e: &Exp) -> Values {
             assert!(!v2.is_ref());
             svalue()
         }
-        E::Pack(_, _, fields) => {
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
             fields.iter().for_each(|(_, _, e)| {
                 let arg = exp(context, e);
                 assert!(!assert_single_value(arg).is_ref());
@@ -289,6 +302,14 @@ fn exp(context: &mut Context, parent_e: &Exp) -> Values {
             svalue()
         }
 
+        E::TestVariant(_, _, _, e) => {
+            let evalue = assert_single_value(exp(context, e));
+            let (diags, value) = context.borrow_state.dereference(*eloc, evalue);
+            context.add_diags(diags);
+            assert!(!value.is_ref());
+            svalue()
+        }
+
         E::ExpList(es) => es
             .iter()
             .flat_map(|item| exp_list_item(context, item))
@@ -714,6 +714,55 @@ impl BorrowState {
         (diags, Value::Ref(field_borrow_id))
     }
 
+    pub fn borrow_variant_fields(
+        &mut self,
+        loc: Loc,
+        mut_: bool,
+        rvalue: Value,
+        fields: &[Field],
+    ) -> (Diagnostics, Values) {
+        let id = match rvalue {
+            Value::NonRef => {
+                assert!(
+                    self.prev_had_errors,
+                    "ICE borrow checking failed {:#?}",
+                    loc
+                );
+                return (
+                    Diagnostics::new(),
+                    fields.iter().map(|_| Value::NonRef).collect(),
+                );
+            }
+            Value::Ref(id) => id,
+        };
+
+        let mut diags = Diagnostics::new();
+        let mut values = vec![];
+        for field in fields {
+            if mut_ {
+                let msg = || format!("Invalid mutable borrow at field '{}'.", field);
+                let (full_borrows, _field_borrows) = self.borrows.borrowed_by(id);
+                // Any field borrows will be factored out
+                diags.add_opt(Self::borrow_error(
+                    &self.borrows,
+                    loc,
+                    &full_borrows,
+                    &BTreeMap::new(),
+                    ReferenceSafety::MutOwns,
+                    msg,
+                ))
+            } else {
+                let msg = || format!("Invalid immutable borrow at field '{}'.", field);
+                diags.extend(self.readable(loc, ReferenceSafety::RefTrans, msg, id, Some(field)))
+            }
+            let field_borrow_id = self.declare_new_ref(mut_);
+            self.add_field_borrow(loc, id, *field, field_borrow_id);
+            values.push(Value::Ref(field_borrow_id))
+        }
+        self.release(id);
+        (diags, values)
+    }
+
     pub fn borrow_global(&mut self, loc: Loc, mut_: bool, t: &BaseType) -> (Diagnostics, Value) {
         let new_id = self.declare_new_ref(mut_);
         let resource = match &t.value {
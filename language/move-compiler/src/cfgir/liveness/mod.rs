@@ -113,7 +113,9 @@ fn lvalue(state: &mut LivenessState, sp!(_, l_): &LValue) {
         L::Var(v, _) => {
             state.0.remove(v);
         }
-        L::Unpack(_, _, fields) => fields.iter().for_each(|(_, l)| lvalue(state, l)),
+        L::Unpack(_, _, fields) | L::UnpackVariant(_, _, _, _, fields) => {
+            fields.iter().for_each(|(_, l)| lvalue(state, l))
+        }
     }
 }
 
@@ -137,6 +139,7 @@ fn exp(state: &mut LivenessState, parent_e: &Exp) {
         | E::Dereference(e)
         | E::UnaryExp(_, e)
         | E::Borrow(_, e, _)
+        | E::TestVariant(_, _, _, e)
         | E::Cast(e, _) => exp(state, e),
 
         E::BinopExp(e1, _, e2) => {
@@ -144,7 +147,9 @@ fn exp(state: &mut LivenessState, parent_e: &Exp) {
             exp(state, e2)
         }
 
-        E::Pack(_, _, fields) => fields.iter().for_each(|(_, _, e)| exp(state, e)),
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+            fields.iter().for_each(|(_, _, e)| exp(state, e))
+        }
 
         E::ExpList(es) => es.iter().for_each(|item| exp_list_item(state, item)),
 
@@ -316,7 +321,9 @@ mod last_usage {
                     }
                 }
             }
-            L::Unpack(_, _, fields) => fields.iter_mut().for_each(|(_, l)| lvalue(context, l)),
+            L::Unpack(_, _, fields) | L::UnpackVariant(_, _, _, _, fields) => {
+                fields.iter_mut().for_each(|(_, l)| lvalue(context, l))
+            }
         }
     }
 
@@ -358,6 +365,7 @@ mod last_usage {
             | E::Dereference(e)
             | E::UnaryExp(_, e)
             | E::Borrow(_, e, _)
+            | E::TestVariant(_, _, _, e)
             | E::Cast(e, _) => exp(context, e),
 
             E::BinopExp(e1, _, e2) => {
@@ -365,7 +373,7 @@ mod last_usage {
                 exp(context, e1)
             }
 
-            E::Pack(_, _, fields) => fields
+            E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => fields
                 .iter_mut()
                 .rev()
                 .for_each(|(_, _, e)| exp(context, e)),
@@ -9,7 +9,7 @@ use crate::{
         TypeName, TypeName_, UnannotatedExp_, Value, Value_,
     },
     naming::ast::{BuiltinTypeName, BuiltinTypeName_},
-    parser::ast::{BinOp, BinOp_, UnaryOp, UnaryOp_, Var},
+    parser::ast::{BinOp, BinOp_, ConstantName, UnaryOp, UnaryOp_, Var},
     shared::unique_map::UniqueMap,
 };
 use move_ir_types::location::*;
@@ -19,6 +19,7 @@ use std::convert::TryFrom;
 pub fn optimize(
     _signature: &FunctionSignature,
     _locals: &UniqueMap<Var, SingleType>,
+    constants: &UniqueMap<ConstantName, Value>,
     cfg: &mut BlockCFG,
 ) -> bool {
     let mut changed = false;
@@ -26,7 +27,7 @@ pub fn optimize(
         let block = std::mem::take(block_ref);
         *block_ref = block
             .into_iter()
-            .filter_map(|mut cmd| match optimize_cmd(&mut cmd) {
+            .filter_map(|mut cmd| match optimize_cmd(constants, &mut cmd) {
                 None => {
                     changed = true;
                     None
@@ -47,18 +48,23 @@ pub fn optimize(
 
 // Some(changed) to keep
 // None to remove the cmd
-fn optimize_cmd(sp!(_, cmd_): &mut Command) -> Option<bool> {
+fn optimize_cmd(
+    consts: &UniqueMap<ConstantName, Value>,
+    sp!(_, cmd_): &mut Command,
+) -> Option<bool> {
     use Command_ as C;
     Some(match cmd_ {
-        C::Assign(_ls, e) => optimize_exp(e),
+        C::Assign(_ls, e) => optimize_exp(consts, e),
         C::Mutate(el, er) => {
-            let c1 = optimize_exp(er);
-            let c2 = optimize_exp(el);
+            let c1 = optimize_exp(consts, er);
+            let c2 = optimize_exp(consts, el);
             c1 || c2
         }
-        C::Return { exp: e, .. } | C::Abort(e) | C::JumpIf { cond: e, .. } => optimize_exp(e),
+        C::Return { exp: e, .. } | C::Abort(e) | C::JumpIf { cond: e, .. } => {
+            optimize_exp(consts, e)
+        }
         C::IgnoreAndPop { exp: e, .. } => {
-            let c = optimize_exp(e);
+            let c = optimize_exp(consts, e);
             match foldable_exps(e) {
                 // All values, so the command can be removed
                 Some(_) => return None,
@@ -71,7 +77,7 @@ fn optimize_cmd(sp!(_, cmd_): &mut Command) -> Option<bool> {
     })
 }
 
-fn optimize_exp(e: &mut Exp) -> bool {
+fn optimize_exp(consts: &UniqueMap<ConstantName, Value>, e: &mut Exp) -> bool {
     use UnannotatedExp_ as E;
     match &mut e.exp.value {
         //************************************
@@ -79,7 +85,6 @@ fn optimize_exp(e: &mut Exp) -> bool {
         //************************************
         E::Unit { .. }
         | E::Value(_)
-        | E::Constant(_)
         | E::UnresolvedError
         | E::Spec(_, _)
         | E::BorrowLocal(_, _)
@@ -87,25 +92,45 @@ fn optimize_exp(e: &mut Exp) -> bool {
         | E::Copy { .. }
         | E::Unreachable => false,
 
-        E::ModuleCall(mcall) => optimize_exp(&mut mcall.arguments),
-        E::Builtin(_, e) | E::Freeze(e) | E::Dereference(e) | E::Borrow(_, e, _) => optimize_exp(e),
+        E::ModuleCall(mcall) => optimize_exp(consts, &mut mcall.arguments),
+        E::Builtin(_, e)
+        | E::Freeze(e)
+        | E::Dereference(e)
+        | E::Borrow(_, e, _)
+        | E::TestVariant(_, _, _, e) => optimize_exp(consts, e),
 
-        E::Pack(_, _, fields) => fields
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => fields
             .iter_mut()
-            .map(|(_, _, e)| optimize_exp(e))
+            .map(|(_, _, e)| optimize_exp(consts, e))
             .any(|changed| changed),
 
-        E::ExpList(es) => es.iter_mut().map(optimize_exp_item).any(|changed| changed),
+        E::ExpList(es) => es
+            .iter_mut()
+            .map(|item| optimize_exp_item(consts, item))
+            .any(|changed| changed),
 
         //************************************
         // Foldable cases
         //************************************
+        e_ @ E::Constant(_) => {
+            let c = match e_ {
+                E::Constant(c) => c,
+                _ => unreachable!(),
+            };
+            let v = match consts.get(c) {
+                Some(sp!(_, v_)) => v_.clone(),
+                None => return false,
+            };
+            *e_ = evalue_(e.exp.loc, v);
+            true
+        }
+
         e_ @ E::UnaryExp(_, _) => {
             let (op, er) = match e_ {
                 E::UnaryExp(op, er) => (op, er),
                 _ => unreachable!(),
             };
-            let changed = optimize_exp(er);
+            let changed = optimize_exp(consts, er);
             let v = match foldable_exp(er) {
                 Some(v) => v,
                 None => return changed,
@@ -119,8 +144,8 @@ fn optimize_exp(e: &mut Exp) -> bool {
                 E::BinopExp(e1, op, e2) => (e1, op, e2),
                 _ => unreachable!(),
             };
-            let changed1 = optimize_exp(e1);
-            let changed2 = optimize_exp(e2);
+            let changed1 = optimize_exp(consts, e1);
+            let changed2 = optimize_exp(consts, e2);
             let changed = changed1 || changed2;
             let (v1, v2) = match (foldable_exp(e1), foldable_exp(e2)) {
                 (Some(v1), Some(v2)) => (v1, v2),
@@ -140,7 +165,7 @@ fn optimize_exp(e: &mut Exp) -> bool {
                 E::Cast(e, bt) => (e, bt),
                 _ => unreachable!(),
             };
-            let changed = optimize_exp(e);
+            let changed = optimize_exp(consts, e);
             let v = match foldable_exp(e) {
                 Some(v) => v,
                 None => return changed,
@@ -159,7 +184,7 @@ fn optimize_exp(e: &mut Exp) -> bool {
                 E::Vector(_, n, ty, eargs) => (*n, ty, eargs),
                 _ => unreachable!(),
             };
-            let changed = optimize_exp(eargs);
+            let changed = optimize_exp(consts, eargs);
             if !is_valid_const_type(ty) {
                 return changed;
             }
@@ -174,9 +199,9 @@ fn optimize_exp(e: &mut Exp) -> bool {
     }
 }
 
-fn optimize_exp_item(item: &mut ExpListItem) -> bool {
+fn optimize_exp_item(consts: &UniqueMap<ConstantName, Value>, item: &mut ExpListItem) -> bool {
     match item {
-        ExpListItem::Single(e, _) | ExpListItem::Splat(_, e, _) => optimize_exp(e),
+        ExpListItem::Single(e, _) | ExpListItem::Splat(_, e, _) => optimize_exp(consts, e),
     }
 }
 
@@ -344,6 +369,14 @@ fn fold_binary_op(
         (B::Ge, V::U128(u1), V::U128(u2)) => V::Bool(u1 >= u2),
         (B::Ge, V::U256(u1), V::U256(u2)) => V::Bool(u1 >= u2),
 
+        //************************************
+        // Byte string concatenation, only in constants
+        //************************************
+        (B::Add, V::Vector(ty, mut vs1), V::Vector(_, vs2)) => {
+            vs1.extend(vs2);
+            V::Vector(ty, vs1)
+        }
+
         (B::Eq, v1, v2) => V::Bool(v1 == v2),
         (B::Neq, v1, v2) => V::Bool(v1 != v2),
 
@@ -7,8 +7,8 @@ use crate::{
         ast::remap_labels,
         cfg::{BlockCFG, CFG},
     },
-    hlir::ast::{BasicBlocks, Command_, FunctionSignature, Label, SingleType},
-    parser::ast::Var,
+    hlir::ast::{BasicBlocks, Command_, FunctionSignature, Label, SingleType, Value},
+    parser::ast::{ConstantName, Var},
     shared::unique_map::UniqueMap,
 };
 use std::collections::{BTreeMap, BTreeSet};
@@ -17,6 +17,7 @@ use std::collections::{BTreeMap, BTreeSet};
 pub fn optimize(
     _signature: &FunctionSignature,
     _locals: &UniqueMap<Var, SingleType>,
+    _constants: &UniqueMap<ConstantName, Value>,
     cfg: &mut BlockCFG,
 ) -> bool {
     let changed = optimize_(cfg.start_block(), cfg.blocks_mut());
@@ -6,9 +6,19 @@ mod eliminate_locals;
 mod inline_blocks;
 mod simplify_jumps;
 
-use crate::{cfgir::cfg::BlockCFG, hlir::ast::*, parser::ast::Var, shared::unique_map::UniqueMap};
+use crate::{
+    cfgir::cfg::BlockCFG,
+    hlir::ast::*,
+    parser::ast::{ConstantName, Var},
+    shared::unique_map::UniqueMap,
+};
 
-pub type Optimization = fn(&FunctionSignature, &UniqueMap<Var, SingleType>, &mut BlockCFG) -> bool;
+pub type Optimization = fn(
+    &FunctionSignature,
+    &UniqueMap<Var, SingleType>,
+    &UniqueMap<ConstantName, Value>,
+    &mut BlockCFG,
+) -> bool;
 
 const OPTIMIZATIONS: &[Optimization] = &[
     eliminate_locals::optimize,
@@ -17,9 +27,11 @@ const OPTIMIZATIONS: &[Optimization] = &[
     inline_blocks::optimize,
 ];
 
+// The values of the given constants are substituted for any uses of them
 pub fn optimize(
     signature: &FunctionSignature,
     locals: &UniqueMap<Var, SingleType>,
+    constants: &UniqueMap<ConstantName, Value>,
     cfg: &mut BlockCFG,
 ) {
     let mut count = 0;
@@ -32,7 +44,7 @@ pub fn optimize(
         }
 
         // reset the count if something has changed
-        if optimization(signature, locals, cfg) {
+        if optimization(signature, locals, constants, cfg) {
             count = 0
         } else {
             count += 1
@@ -4,8 +4,10 @@
 
 use crate::{
     cfgir::cfg::BlockCFG,
-    hlir::ast::{Command, Command_, Exp, FunctionSignature, SingleType, UnannotatedExp_, Value_},
-    parser::ast::Var,
+    hlir::ast::{
+        Command, Command_, Exp, FunctionSignature, SingleType, UnannotatedExp_, Value, Value_,
+    },
+    parser::ast::{ConstantName, Var},
     shared::unique_map::UniqueMap,
 };
 
@@ -13,6 +15,7 @@ use crate::{
 pub fn optimize(
     _signature: &FunctionSignature,
     _locals: &UniqueMap<Var, SingleType>,
+    _constants: &UniqueMap<ConstantName, Value>,
     cfg: &mut BlockCFG,
 ) -> bool {
     let mut changed = false;
@@ -9,16 +9,18 @@ use crate::{
         cfg::BlockCFG,
     },
     diag,
-    expansion::ast::{AbilitySet, ModuleIdent},
+    diagnostics::Diagnostic,
+    expansion::ast::{self as E, AbilitySet, ModuleIdent},
     hlir::ast::{self as H, Label, Value, Value_},
-    parser::ast::{ConstantName, FunctionName, StructName, Var},
-    shared::{unique_map::UniqueMap, CompilationEnv},
+    parser::ast::{BinOp_, ConstantName, FunctionName, StructName, Var},
+    shared::{shortest_cycle, unique_map::UniqueMap, CompilationEnv},
     FullyCompiledProgram,
 };
 use cfgir::ast::LoopInfo;
 use move_core_types::{account_address::AccountAddress as MoveAddress, value::MoveValue};
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use petgraph::{algo::tarjan_scc as petgraph_scc, graphmap::DiGraphMap};
 use std::{
     collections::{BTreeMap, BTreeSet},
     mem,
@@ -193,7 +195,7 @@ fn module(
         constants: hconstants,
     } = mdef;
 
-    let constants = hconstants.map(|name, c| constant(context, name, c));
+    let constants = constants(context, hconstants);
     let functions = hfunctions.map(|name, f| function(context, name, f));
     (
         module_ident,
@@ -229,7 +231,7 @@ fn script(context: &mut Context, hscript: H::Script) -> G::Script {
         function_name,
         function: hfunction,
     } = hscript;
-    let constants = hconstants.map(|name, c| constant(context, name, c));
+    let constants = constants(context, hconstants);
     let function = function(context, function_name, hfunction);
     G::Script {
         package_name,
@@ -242,45 +244,132 @@ fn script(context: &mut Context, hscript: H::Script) -> G::Script {
 }
 
 //**************************************************************************************************
-// Functions
+// Constants
 //**************************************************************************************************
 
-fn constant(context: &mut Context, _name: ConstantName, c: H::Constant) -> G::Constant {
+// A constant after its value has been translated into a CFG, ready to be folded
+struct ConstantCFG {
+    attributes: E::Attributes,
+    loc: Loc,
+    signature: H::BaseType,
+    locals: UniqueMap<Var, H::SingleType>,
+    start: Label,
+    blocks: BasicBlocks,
+    block_info: Vec<(Label, BlockInfo)>,
+}
+
+fn constants(
+    context: &mut Context,
+    hconstants: UniqueMap<ConstantName, H::Constant>,
+) -> UniqueMap<ConstantName, G::Constant> {
+    let mut cfgs = hconstants.map(|_, c| constant_cfg(context, c));
+
+    // Constants can use other constants of the same module. So the constants are folded in
+    // dependency order, substituting the values of the constants folded before them
+    let used_constants = cfgs
+        .key_cloned_iter()
+        .map(|(name, cfg)| (name, used_constants(&cfg.blocks)))
+        .collect::<BTreeMap<_, _>>();
+    let mut graph = DiGraphMap::new();
+    for (name, used) in &used_constants {
+        graph.add_node(name);
+        for used_name in used.keys() {
+            graph.add_edge(name, used_name, ());
+        }
+    }
+
+    let mut values = UniqueMap::new();
+    let mut constants = UniqueMap::new();
+    // The strongly connected components are in reverse topological order, so the used constants
+    // come first
+    for scc in petgraph_scc(&graph) {
+        let is_cycle = scc.len() > 1 || graph.contains_edge(scc[0], scc[0]);
+        if is_cycle {
+            context
+                .env
+                .add_diag(cycle_error(&used_constants, &graph, scc[0]));
+        }
+        for name in scc {
+            let cfg = cfgs.remove(name).unwrap();
+            let (constant, value_opt) = constant(context, &values, cfg, is_cycle);
+            if let Some(value) = value_opt {
+                values.add(*name, value).unwrap();
+            }
+            constants.add(*name, constant).unwrap();
+        }
+    }
+    constants
+}
+
+fn constant_cfg(context: &mut Context, c: H::Constant) -> ConstantCFG {
     let H::Constant {
         attributes,
         loc,
         signature,
         value: (locals, block),
     } = c;
-
-    let final_value = constant_(context, loc, signature.clone(), locals, block);
-    let value = final_value.and_then(move_value_from_exp);
-
-    G::Constant {
+    initial_block(context, block);
+    let (start, blocks, block_info) = context.finish_blocks();
+    ConstantCFG {
         attributes,
         loc,
         signature,
-        value,
+        locals,
+        start,
+        blocks,
+        block_info,
     }
 }
 
+fn constant(
+    context: &mut Context,
+    values: &UniqueMap<ConstantName, Value>,
+    mut cfg: ConstantCFG,
+    is_cycle: bool,
+) -> (G::Constant, Option<Value>) {
+    // The cycle has already been reported, so the value is not computed
+    let value = if is_cycle {
+        None
+    } else {
+        constant_(context, values, &mut cfg)
+    };
+    let ConstantCFG {
+        attributes,
+        loc,
+        signature,
+        ..
+    } = cfg;
+    let constant = G::Constant {
+        attributes,
+        loc,
+        signature,
+        value: value.clone().map(move_value_from_value),
+    };
+    (constant, value)
+}
+
 const CANNOT_FOLD: &str =
     "Invalid expression in 'const'. This expression could not be evaluated to a value";
 
 fn constant_(
     context: &mut Context,
-    full_loc: Loc,
-    signature: H::BaseType,
-    locals: UniqueMap<Var, H::SingleType>,
-    block: H::Block,
-) -> Option<H::Exp> {
+    values: &UniqueMap<ConstantName, Value>,
+    constant_cfg: &mut ConstantCFG,
+) -> Option<Value> {
     use H::Command_ as C;
+    use H::UnannotatedExp_ as E;
     const ICE_MSG: &str = "ICE invalid constant should have been blocked in typing";
 
-    initial_block(context, block);
-    let (start, mut blocks, block_info) = context.finish_blocks();
-
-    let (mut cfg, infinite_loop_starts, errors) = BlockCFG::new(start, &mut blocks, &block_info);
+    let ConstantCFG {
+        loc: full_loc,
+        signature,
+        locals,
+        start,
+        blocks,
+        block_info,
+        ..
+    } = constant_cfg;
+    let (mut cfg, infinite_loop_starts, errors) = BlockCFG::new(*start, blocks, block_info);
     assert!(infinite_loop_starts.is_empty(), "{}", ICE_MSG);
     assert!(errors.is_empty(), "{}", ICE_MSG);
 
@@ -288,7 +377,7 @@ fn constant_(
     let fake_signature = H::FunctionSignature {
         type_parameters: vec![],
         parameters: vec![],
-        return_type: H::Type_::base(signature),
+        return_type: H::Type_::base(signature.clone()),
     };
     let fake_acquires = BTreeMap::new();
     let fake_infinite_loop_starts = BTreeSet::new();
@@ -297,7 +386,7 @@ fn constant_(
         &context.struct_declared_abilities,
         &fake_signature,
         &fake_acquires,
-        &locals,
+        locals,
         &mut cfg,
         &fake_infinite_loop_starts,
     );
@@ -306,17 +395,18 @@ fn constant_(
         "{}",
         ICE_MSG
     );
-    cfgir::optimize(&fake_signature, &locals, &mut cfg);
+    cfgir::optimize(&fake_signature, locals, values, &mut cfg);
 
     if blocks.len() != 1 {
         context.env.add_diag(diag!(
             BytecodeGeneration::UnfoldableConstant,
-            (full_loc, CANNOT_FOLD)
+            (*full_loc, CANNOT_FOLD)
         ));
         return None;
     }
-    let mut optimized_block = blocks.remove(&start).unwrap();
+    let mut optimized_block = blocks.remove(start).unwrap();
     let return_cmd = optimized_block.pop_back().unwrap();
+    let mut is_value = true;
     for sp!(cloc, cmd_) in &optimized_block {
         let e = match cmd_ {
             C::IgnoreAndPop { exp, .. } => exp,
@@ -325,39 +415,189 @@ fn constant_(
                     BytecodeGeneration::UnfoldableConstant,
                     (*cloc, CANNOT_FOLD)
                 ));
+                is_value = false;
                 continue;
             }
         };
-        check_constant_value(context, e)
+        is_value = check_constant_value(context, e) && is_value;
     }
 
     let result = match return_cmd.value {
         C::Return { exp: e, .. } => e,
         _ => unreachable!(),
     };
-    check_constant_value(context, &result);
-    Some(result)
+    is_value = check_constant_value(context, &result) && is_value;
+    match result.exp.value {
+        E::Value(v) if is_value => Some(v),
+        _ => None,
+    }
 }
 
-fn check_constant_value(context: &mut Context, e: &H::Exp) {
+// Returns true if the expression was folded to a value, otherwise reports why it could not be
+fn check_constant_value(context: &mut Context, e: &H::Exp) -> bool {
     use H::UnannotatedExp_ as E;
     match &e.exp.value {
-        E::Value(_) => (),
-        _ => context.env.add_diag(diag!(
-            BytecodeGeneration::UnfoldableConstant,
-            (e.exp.loc, CANNOT_FOLD)
-        )),
+        E::Value(_) => true,
+        _ => {
+            if let Some((loc, msg)) = unfoldable_reason(e) {
+                context
+                    .env
+                    .add_diag(diag!(BytecodeGeneration::UnfoldableConstant, (loc, msg)))
+            }
+            false
+        }
     }
 }
 
-fn move_value_from_exp(e: H::Exp) -> Option<MoveValue> {
+// Finds the innermost operation that could not be folded, e.g. an arithmetic operation that
+// overflows. Returns None for uses of constants without a value, as those already have errors
+fn unfoldable_reason(e: &H::Exp) -> Option<(Loc, String)> {
+    use BinOp_ as B;
     use H::UnannotatedExp_ as E;
-    match e.exp.value {
-        E::Value(v) => Some(move_value_from_value(v)),
-        _ => None,
+    let loc = e.exp.loc;
+    let msg = |reason: String| Some((loc, format!("Invalid expression in 'const'. {}", reason)));
+    match &e.exp.value {
+        E::Value(_) => None,
+        E::Constant(_) => None,
+        E::UnaryExp(_, e) => unfoldable_reason(e),
+        E::Cast(e, bt) => match &e.exp.value {
+            E::Value(v) => msg(format!(
+                "The value '{}' does not fit in the type '{}'",
+                value_string(v),
+                bt
+            )),
+            _ => unfoldable_reason(e),
+        },
+        E::BinopExp(e1, sp!(_, op_), e2) => match (&e1.exp.value, &e2.exp.value) {
+            (E::Value(v1), E::Value(v2)) => {
+                let reason = match op_ {
+                    B::Add | B::Mul => "overflows",
+                    B::Sub => "underflows",
+                    B::Div | B::Mod => "divides by zero",
+                    B::Shl | B::Shr => "shifts by at least the bit width of the type",
+                    _ => return msg(CANNOT_FOLD.to_string()),
+                };
+                msg(format!(
+                    "'{} {} {}' {}",
+                    value_string(v1),
+                    op_,
+                    value_string(v2),
+                    reason
+                ))
+            }
+            (E::Value(_), _) => unfoldable_reason(e2),
+            _ => unfoldable_reason(e1),
+        },
+        E::Vector(_, _, _, e) => unfoldable_reason(e),
+        E::ExpList(items) => items.iter().find_map(|item| match item {
+            H::ExpListItem::Single(e, _) | H::ExpListItem::Splat(_, e, _) => unfoldable_reason(e),
+        }),
+        _ => Some((loc, CANNOT_FOLD.to_string())),
     }
 }
 
+fn value_string(sp!(_, v_): &Value) -> String {
+    use Value_ as V;
+    match v_ {
+        V::Address(a) => format!("@{}", a),
+        V::U8(u) => format!("{}", u),
+        V::U16(u) => format!("{}", u),
+        V::U32(u) => format!("{}", u),
+        V::U64(u) => format!("{}", u),
+        V::U128(u) => format!("{}", u),
+        V::U256(u) => format!("{}", u),
+        V::Bool(b) => format!("{}", b),
+        V::Vector(_, vs) => format!(
+            "vector[{}]",
+            vs.iter().map(value_string).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+// The constants used by a constant's value, with the location of their first use
+fn used_constants(blocks: &BasicBlocks) -> BTreeMap<ConstantName, Loc> {
+    use H::Command_ as C;
+    let mut used = BTreeMap::new();
+    for sp!(_, cmd_) in blocks.values().flatten() {
+        match cmd_ {
+            C::Assign(_, e) => exp_used_constants(&mut used, e),
+            C::Mutate(el, er) => {
+                exp_used_constants(&mut used, el);
+                exp_used_constants(&mut used, er)
+            }
+            C::Abort(e)
+            | C::Return { exp: e, .. }
+            | C::IgnoreAndPop { exp: e, .. }
+            | C::JumpIf { cond: e, .. } => exp_used_constants(&mut used, e),
+            C::Break | C::Continue | C::Jump { .. } => (),
+        }
+    }
+    used
+}
+
+fn exp_used_constants(used: &mut BTreeMap<ConstantName, Loc>, e: &H::Exp) {
+    use H::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Constant(c) => {
+            used.entry(*c).or_insert(e.exp.loc);
+        }
+        E::ModuleCall(call) => exp_used_constants(used, &call.arguments),
+        E::Builtin(_, e)
+        | E::Freeze(e)
+        | E::Vector(_, _, _, e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::TestVariant(_, _, _, e)
+        | E::Borrow(_, e, _)
+        | E::Cast(e, _) => exp_used_constants(used, e),
+        E::BinopExp(e1, _, e2) => {
+            exp_used_constants(used, e1);
+            exp_used_constants(used, e2)
+        }
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => fields
+            .iter()
+            .for_each(|(_, _, e)| exp_used_constants(used, e)),
+        E::ExpList(items) => items.iter().for_each(|item| match item {
+            H::ExpListItem::Single(e, _) | H::ExpListItem::Splat(_, e, _) => {
+                exp_used_constants(used, e)
+            }
+        }),
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::BorrowLocal(_, _)
+        | E::Unreachable
+        | E::Spec(_, _)
+        | E::UnresolvedError => (),
+    }
+}
+
+fn cycle_error(
+    used_constants: &BTreeMap<ConstantName, BTreeMap<ConstantName, Loc>>,
+    graph: &DiGraphMap<&ConstantName, ()>,
+    cycle_node: &ConstantName,
+) -> Diagnostic {
+    let cycle = shortest_cycle(graph, cycle_node);
+    let cycle_strings = cycle
+        .iter()
+        .map(|c| format!("'{}'", c))
+        .collect::<Vec<_>>()
+        .join(" uses ");
+    let (user, used) = (cycle[0], cycle[1]);
+    let used_loc = used_constants[user][used];
+    let msg = format!(
+        "Invalid use of constant '{}' in constant '{}'. The value of '{}' depends on itself",
+        used, user, user
+    );
+    let cycle_msg = format!("Using this constant creates a cycle: {}", cycle_strings);
+    diag!(
+        BytecodeGeneration::UnfoldableConstant,
+        (used_loc, msg),
+        (used_loc, cycle_msg),
+    )
+}
+
 pub(crate) fn move_value_from_value(sp!(_, v_): Value) -> MoveValue {
     move_value_from_value_(v_)
 }
@@ -439,7 +679,9 @@ fn function_body(
             );
             // do not optimize if there are errors, warnings are okay
             if !context.env.has_errors() {
-                cfgir::optimize(signature, &locals, &mut cfg);
+                // Uses of constants are not folded, their values are loaded from the constant
+                // pool
+                cfgir::optimize(signature, &locals, &UniqueMap::new(), &mut cfg);
             }
 
             let loop_heads = block_info
@@ -8,7 +8,7 @@ use crate::{
     compiled_unit,
     compiled_unit::AnnotatedCompiledUnit,
     diagnostics::{codes::Severity, *},
-    expansion, hlir, interface_generator, naming, parser,
+    expansion, hlir, interface_generator, linters, naming, parser,
     parser::{comments::*, *},
     shared::{
         CompilationEnv, Flags, IndexedPackagePath, NamedAddressMap, NamedAddressMaps,
@@ -230,8 +230,9 @@ impl<'a> Compiler<'a> {
     }
 
     pub fn check_and_report(self) -> anyhow::Result<FilesSourceText> {
+        let format = self.flags.diagnostics_format();
         let (files, res) = self.check()?;
-        unwrap_or_report_diagnostics(&files, res);
+        unwrap_or_report_diagnostics_with_format(&files, res, format);
         Ok(files)
     }
 
@@ -249,9 +250,10 @@ impl<'a> Compiler<'a> {
     }
 
     pub fn build_and_report(self) -> anyhow::Result<(FilesSourceText, Vec<AnnotatedCompiledUnit>)> {
+        let format = self.flags.diagnostics_format();
         let (files, units_res) = self.build()?;
-        let (units, warnings) = unwrap_or_report_diagnostics(&files, units_res);
-        report_warnings(&files, warnings);
+        let (units, warnings) = unwrap_or_report_diagnostics_with_format(&files, units_res, format);
+        report_warnings_with_format(&files, warnings, format);
         Ok((files, units))
     }
 }
@@ -365,17 +367,20 @@ macro_rules! ast_stepped_compilers {
                 }
 
                 pub fn check_and_report(self, files: &FilesSourceText)  {
+                    let format = self.compilation_env.flags().diagnostics_format();
                     let errors_result = self.check();
-                    unwrap_or_report_diagnostics(&files, errors_result);
+                    unwrap_or_report_diagnostics_with_format(&files, errors_result, format);
                 }
 
                 pub fn build_and_report(
                     self,
                     files: &FilesSourceText,
                 ) -> Vec<AnnotatedCompiledUnit> {
+                    let format = self.compilation_env.flags().diagnostics_format();
                     let units_result = self.build();
-                    let (units, warnings) = unwrap_or_report_diagnostics(&files, units_result);
-                    report_warnings(&files, warnings);
+                    let (units, warnings) =
+                        unwrap_or_report_diagnostics_with_format(&files, units_result, format);
+                    report_warnings_with_format(&files, warnings, format);
                     units
                 }
             }
@@ -754,6 +759,7 @@ fn run(
     match cur {
         PassResult::Parser(prog) => {
             let prog = parser::merge_spec_modules::program(compilation_env, prog);
+            let prog = parser::filter::program(compilation_env, prog);
             let prog = unit_test::filter_test_members::program(compilation_env, prog);
             let prog = verification::ast_filter::program(compilation_env, prog);
             let eprog = expansion::translate::program(compilation_env, pre_compiled_lib, prog);
@@ -780,6 +786,7 @@ fn run(
         PassResult::Naming(nprog) => {
             let tprog = typing::translate::program(compilation_env, pre_compiled_lib, nprog);
             compilation_env.check_diags_at_or_above_severity(Severity::BlockingError)?;
+            linters::typing_program(compilation_env, &tprog);
             run(
                 compilation_env,
                 pre_compiled_lib,
@@ -788,7 +795,9 @@ fn run(
                 result_check,
             )
         }
-        PassResult::Typing(tprog) => {
+        PassResult::Typing(mut tprog) => {
+            typing::inlining::program(compilation_env, pre_compiled_lib, &mut tprog);
+            compilation_env.check_diags_at_or_above_severity(Severity::BlockingError)?;
             let hprog = hlir::translate::program(compilation_env, pre_compiled_lib, tprog);
             compilation_env.check_diags_at_or_above_severity(Severity::Bug)?;
             run(
@@ -802,6 +811,7 @@ fn run(
         PassResult::HLIR(hprog) => {
             let cprog = cfgir::translate::program(compilation_env, pre_compiled_lib, hprog);
             compilation_env.check_diags_at_or_above_severity(Severity::NonblockingError)?;
+            linters::cfgir_program(compilation_env, &cprog);
             run(
                 compilation_env,
                 pre_compiled_lib,
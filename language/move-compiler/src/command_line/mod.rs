@@ -30,6 +30,10 @@ pub const FLAVOR: &str = "flavor";
 
 pub const BYTECODE_VERSION: &str = "bytecode-version";
 
+pub const DIAGNOSTICS_FORMAT: &str = "diagnostics-format";
+
+pub const LINT: &str = "lint";
+
 pub const COLOR_MODE_ENV_VAR: &str = "COLOR_MODE";
 
 pub const MOVE_COMPILED_INTERFACES_DIR: &str = "mv_interfaces";
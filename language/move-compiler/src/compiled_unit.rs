@@ -14,10 +14,11 @@ use move_binary_format::file_format as F;
 use move_bytecode_source_map::source_map::SourceMap;
 use move_core_types::{
     account_address::AccountAddress, identifier::Identifier as MoveCoreIdentifier,
-    language_storage::ModuleId,
+    language_storage::ModuleId, metadata::Metadata,
 };
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 //**************************************************************************************************
@@ -95,6 +96,52 @@ pub enum CompiledUnitEnum<TModule: TargetModule, TScript: TargetScript> {
 pub type CompiledUnit = CompiledUnitEnum<NamedCompiledModule, NamedCompiledScript>;
 pub type AnnotatedCompiledUnit = CompiledUnitEnum<AnnotatedCompiledModule, AnnotatedCompiledScript>;
 
+//**************************************************************************************************
+// Metadata
+//**************************************************************************************************
+
+/// The key of the module metadata that records the items marked with `#[deprecated]`
+pub const DEPRECATION_METADATA_KEY: &[u8] = b"move_deprecation";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeprecationMetadata {
+    // Set if the module itself is deprecated
+    pub module: Option<DeprecationInfo>,
+    pub structs: BTreeMap<String, DeprecationInfo>,
+    pub functions: BTreeMap<String, DeprecationInfo>,
+    pub constants: BTreeMap<String, DeprecationInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeprecationInfo {
+    pub note: Option<String>,
+}
+
+impl DeprecationMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.module.is_none()
+            && self.structs.is_empty()
+            && self.functions.is_empty()
+            && self.constants.is_empty()
+    }
+
+    pub fn to_metadata(&self) -> Metadata {
+        Metadata {
+            key: DEPRECATION_METADATA_KEY.to_vec(),
+            value: bcs::to_bytes(self).unwrap(),
+        }
+    }
+
+    /// The deprecation metadata of the module, if it has any deprecated items
+    pub fn from_module(module: &F::CompiledModule) -> Option<Self> {
+        module
+            .metadata
+            .iter()
+            .find(|metadata| metadata.key == DEPRECATION_METADATA_KEY)
+            .and_then(|metadata| bcs::from_bytes(&metadata.value).ok())
+    }
+}
+
 impl AnnotatedCompiledModule {
     pub fn module_ident(&self) -> ModuleIdent {
         use crate::expansion::ast::Address;
@@ -114,6 +114,9 @@ codes!(
         SpecContextRestricted:
             { msg: "syntax item restricted to spec contexts", severity: BlockingError },
         InvalidSpecBlockMember: { msg: "invalid spec block member", severity: NonblockingError },
+        InvalidPattern: { msg: "invalid match pattern", severity: NonblockingError },
+        InvalidStructUpdate: { msg: "invalid struct update", severity: NonblockingError },
+        InvalidString: { msg: "invalid string literal", severity: NonblockingError },
     ],
     // errors for any rules around declaration items
     Declarations: [
@@ -151,6 +154,8 @@ codes!(
         UnboundField: { msg: "unbound field", severity: BlockingError },
         ReservedName: { msg: "invalid use of reserved name", severity: BlockingError },
         UnboundMacro: { msg: "unbound macro", severity: BlockingError },
+        UnboundVariant: { msg: "unbound variant", severity: BlockingError },
+        DeprecatedUsage: { msg: "use of deprecated item", severity: Warning },
     ],
     // errors for typing rules. mostly typing/translate
     TypeSafety: [
@@ -182,6 +187,10 @@ codes!(
                 (NOTE: this may become an error in the future)",
             severity: Warning
         },
+        NonExhaustiveMatch: { msg: "non-exhaustive match", severity: BlockingError },
+        InvalidLambda: { msg: "invalid use of lambda", severity: BlockingError },
+        InvalidInline: { msg: "invalid 'inline' function", severity: BlockingError },
+        InvalidMethodCall: { msg: "invalid method call", severity: BlockingError },
     ],
     // errors for ability rules. mostly typing/translate
     AbilitySafety: [
@@ -238,7 +247,16 @@ codes!(
     ],
     Derivation: [
         DeriveFailed: { msg: "attribute derivation failed", severity: BlockingError }
-    ]
+    ],
+    // warnings for the optional lints in `linters`. The severity is adjusted per the lint level
+    Lint: [
+        SelfAssignment: { msg: "self assignment", severity: Warning },
+        RedundantCopy: { msg: "redundant copy", severity: Warning },
+        ConstantComparison: { msg: "comparison is always true or false", severity: Warning },
+        NeedlessMutBorrow: { msg: "needless mutable borrow", severity: Warning },
+        ShiftOverflow: { msg: "shift amount exceeds bit width", severity: Warning },
+        WhileTrue: { msg: "'while (true)' instead of 'loop'", severity: Warning },
+    ],
 );
 
 //**************************************************************************************************
@@ -268,9 +286,21 @@ impl DiagnosticInfo {
         self.message
     }
 
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+
     pub fn severity(&self) -> Severity {
         self.severity
     }
+
+    pub fn set_severity(self, severity: Severity) -> Self {
+        Self { severity, ..self }
+    }
 }
 
 impl Severity {
@@ -0,0 +1,491 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Machine readable renderings of `Diagnostics`, for consumption by CI bots and editor plugins.
+//! Two formats are supported:
+//! - JSON lines, where each diagnostic is a single JSON object on its own line
+//! - SARIF 2.1.0, where all diagnostics are reported as the results of a single run
+
+use crate::diagnostics::{codes::Severity, Diagnostic, FileId, FileMapping};
+use codespan_reporting::files::{Files, SimpleFiles};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+//**************************************************************************************************
+// JSON lines
+//**************************************************************************************************
+
+#[derive(Debug, Serialize)]
+pub struct JsonDiagnostic {
+    /// The rendered code, e.g. `E03001`
+    pub code: String,
+    pub severity: &'static str,
+    /// The name of the category from `codes.rs`, e.g. `NameResolution`
+    pub category: String,
+    pub category_id: u8,
+    pub code_id: u8,
+    pub message: &'static str,
+    pub primary_label: JsonLabel,
+    pub secondary_labels: Vec<JsonLabel>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonLabel {
+    pub file: String,
+    pub start: JsonPosition,
+    pub end: JsonPosition,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonPosition {
+    /// Byte offset into the file, starting at 0
+    pub byte: usize,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column number (in characters), starting at 1
+    pub column: usize,
+}
+
+pub(super) fn render_json_lines(
+    files: &SimpleFiles<Symbol, &str>,
+    file_mapping: &FileMapping,
+    diags: Vec<Diagnostic>,
+) -> String {
+    let mut out = String::new();
+    for diag in diags {
+        let json_diag = json_diagnostic(files, file_mapping, diag);
+        out.push_str(&serde_json::to_string(&json_diag).unwrap());
+        out.push('\n');
+    }
+    out
+}
+
+fn json_diagnostic(
+    files: &SimpleFiles<Symbol, &str>,
+    file_mapping: &FileMapping,
+    diag: Diagnostic,
+) -> JsonDiagnostic {
+    let Diagnostic {
+        info,
+        primary_label,
+        secondary_labels,
+        notes,
+    } = diag;
+    let mk_lbl = |(loc, message): (Loc, String)| json_label(files, file_mapping, loc, message);
+    let category = format!("{:?}", info.category());
+    let category_id = info.category() as u8;
+    let code_id = info.code();
+    let severity = severity_name(info.severity());
+    let (code, message) = info.render();
+    JsonDiagnostic {
+        code,
+        severity,
+        category,
+        category_id,
+        code_id,
+        message,
+        primary_label: mk_lbl(primary_label),
+        secondary_labels: secondary_labels.into_iter().map(mk_lbl).collect(),
+        notes,
+    }
+}
+
+fn json_label(
+    files: &SimpleFiles<Symbol, &str>,
+    file_mapping: &FileMapping,
+    loc: Loc,
+    message: String,
+) -> JsonLabel {
+    let id = *file_mapping.get(&loc.file_hash()).unwrap();
+    JsonLabel {
+        file: files.name(id).unwrap().to_string(),
+        start: json_position(files, id, loc.start() as usize),
+        end: json_position(files, id, loc.end() as usize),
+        message,
+    }
+}
+
+fn json_position(files: &SimpleFiles<Symbol, &str>, id: FileId, byte: usize) -> JsonPosition {
+    let location = files.location(id, byte).unwrap();
+    JsonPosition {
+        byte,
+        line: location.line_number,
+        column: location.column_number,
+    }
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Warning => "warning",
+        Severity::NonblockingError | Severity::BlockingError => "error",
+        Severity::Bug => "bug",
+    }
+}
+
+//**************************************************************************************************
+// SARIF
+//**************************************************************************************************
+
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_TOOL_NAME: &str = "move-compiler";
+
+#[derive(Debug, Serialize)]
+struct SarifLog {
+    version: &'static str,
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: String,
+    name: String,
+    short_description: SarifMessage,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    level: &'static str,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+    related_locations: Vec<SarifLocation>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+    message: SarifMessage,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: usize,
+    byte_length: usize,
+}
+
+pub(super) fn render_sarif(
+    files: &SimpleFiles<Symbol, &str>,
+    file_mapping: &FileMapping,
+    diags: Vec<Diagnostic>,
+) -> String {
+    let mut rules = BTreeMap::new();
+    let mut results = vec![];
+    for diag in diags {
+        let level = sarif_level(diag.info.severity());
+        let JsonDiagnostic {
+            code,
+            severity: _,
+            category,
+            category_id: _,
+            code_id: _,
+            message,
+            primary_label,
+            secondary_labels,
+            notes,
+        } = json_diagnostic(files, file_mapping, diag);
+        rules.entry(code.clone()).or_insert_with(|| SarifRule {
+            id: code.clone(),
+            name: category,
+            short_description: SarifMessage {
+                text: message.to_string(),
+            },
+        });
+        let mut text = format!("{}: {}", message, primary_label.message);
+        for note in notes {
+            text.push_str("\nNote: ");
+            text.push_str(&note);
+        }
+        results.push(SarifResult {
+            rule_id: code,
+            level,
+            message: SarifMessage { text },
+            locations: vec![sarif_location(primary_label)],
+            related_locations: secondary_labels.into_iter().map(sarif_location).collect(),
+        })
+    }
+    let log = SarifLog {
+        version: SARIF_VERSION,
+        schema: SARIF_SCHEMA,
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: SARIF_TOOL_NAME,
+                    rules: rules.into_values().collect(),
+                },
+            },
+            results,
+        }],
+    };
+    let mut out = serde_json::to_string_pretty(&log).unwrap();
+    out.push('\n');
+    out
+}
+
+fn sarif_location(label: JsonLabel) -> SarifLocation {
+    let JsonLabel {
+        file,
+        start,
+        end,
+        message,
+    } = label;
+    SarifLocation {
+        physical_location: SarifPhysicalLocation {
+            artifact_location: SarifArtifactLocation { uri: file },
+            region: SarifRegion {
+                start_line: start.line,
+                start_column: start.column,
+                end_line: end.line,
+                end_column: end.column,
+                byte_offset: start.byte,
+                byte_length: end.byte - start.byte,
+            },
+        },
+        message: SarifMessage { text: message },
+    }
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Warning => "warning",
+        Severity::NonblockingError | Severity::BlockingError | Severity::Bug => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diag,
+        diagnostics::{
+            report_diagnostics_to_buffer_with_format, Diagnostics, DiagnosticsFormat,
+            FilesSourceText,
+        },
+    };
+    use move_command_line_common::files::FileHash;
+    use move_ir_types::location::Loc;
+    use serde_json::{json, Value};
+
+    const FILE_NAME: &str = "sources/m.move";
+    const SOURCE: &str = "module 0x1::m {\n    fun f(x: u64) {\n        M::g()\n    }\n}\n";
+
+    /// The location of the first occurrence of `text` in `SOURCE`
+    fn loc_of(text: &str) -> Loc {
+        let start = SOURCE.find(text).unwrap() as u32;
+        Loc::new(FileHash::new(SOURCE), start, start + text.len() as u32)
+    }
+
+    fn render(format: DiagnosticsFormat) -> String {
+        let files: FilesSourceText = [(
+            FileHash::new(SOURCE),
+            (FILE_NAME.into(), SOURCE.to_string()),
+        )]
+        .into_iter()
+        .collect();
+
+        let mut diags = Diagnostics::new();
+        let mut unbound = diag!(
+            NameResolution::UnboundModule,
+            (loc_of("M::g"), "Unbound module 'M'"),
+            (loc_of("0x1::m"), "In this module"),
+        );
+        unbound.add_note("Modules must be declared before use");
+        diags.add(unbound);
+        diags.add(diag!(
+            UnusedItem::Variable,
+            (loc_of("x: u64"), "Unused parameter 'x'"),
+        ));
+
+        String::from_utf8(report_diagnostics_to_buffer_with_format(
+            &files, diags, format,
+        ))
+        .unwrap()
+    }
+
+    fn position(byte: usize, line: usize, column: usize) -> Value {
+        json!({ "byte": byte, "line": line, "column": column })
+    }
+
+    #[test]
+    fn json_lines() {
+        let out = render(DiagnosticsFormat::Json);
+        let diags: Vec<Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(diags.len(), 2, "{}", out);
+
+        // Diagnostics are sorted by location
+        let unused = &diags[0];
+        assert_eq!(unused["code"], "W09002");
+        assert_eq!(unused["severity"], "warning");
+        assert_eq!(unused["category"], "UnusedItem");
+        assert_eq!(unused["message"], "unused variable");
+        assert_eq!(
+            unused["primary_label"],
+            json!({
+                "file": FILE_NAME,
+                "start": position(26, 2, 11),
+                "end": position(32, 2, 17),
+                "message": "Unused parameter 'x'",
+            })
+        );
+        assert_eq!(unused["secondary_labels"], json!([]));
+        assert_eq!(unused["notes"], json!([]));
+
+        let unbound = &diags[1];
+        assert_eq!(unbound["code"], "E03002");
+        assert_eq!(unbound["severity"], "error");
+        assert_eq!(unbound["category"], "NameResolution");
+        assert_eq!(unbound["category_id"], 3);
+        assert_eq!(unbound["code_id"], 2);
+        assert_eq!(unbound["message"], "unbound module");
+        assert_eq!(
+            unbound["primary_label"],
+            json!({
+                "file": FILE_NAME,
+                "start": position(44, 3, 9),
+                "end": position(48, 3, 13),
+                "message": "Unbound module 'M'",
+            })
+        );
+        assert_eq!(
+            unbound["secondary_labels"],
+            json!([{
+                "file": FILE_NAME,
+                "start": position(7, 1, 8),
+                "end": position(13, 1, 14),
+                "message": "In this module",
+            }])
+        );
+        assert_eq!(
+            unbound["notes"],
+            json!(["Modules must be declared before use"])
+        );
+    }
+
+    #[test]
+    fn sarif() {
+        let out = render(DiagnosticsFormat::Sarif);
+        let log: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(
+            log["$schema"],
+            "https://json.schemastore.org/sarif-2.1.0.json"
+        );
+
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "move-compiler");
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([
+                {
+                    "id": "E03002",
+                    "name": "NameResolution",
+                    "shortDescription": { "text": "unbound module" },
+                },
+                {
+                    "id": "W09002",
+                    "name": "UnusedItem",
+                    "shortDescription": { "text": "unused variable" },
+                },
+            ])
+        );
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2, "{}", out);
+        assert_eq!(results[0]["ruleId"], "W09002");
+        assert_eq!(results[0]["level"], "warning");
+        assert_eq!(results[0]["relatedLocations"], json!([]));
+
+        let unbound = &results[1];
+        assert_eq!(unbound["ruleId"], "E03002");
+        assert_eq!(unbound["level"], "error");
+        assert_eq!(
+            unbound["message"]["text"],
+            "unbound module: Unbound module 'M'\nNote: Modules must be declared before use"
+        );
+        assert_eq!(
+            unbound["locations"],
+            json!([{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": FILE_NAME },
+                    "region": {
+                        "startLine": 3,
+                        "startColumn": 9,
+                        "endLine": 3,
+                        "endColumn": 13,
+                        "byteOffset": 44,
+                        "byteLength": 4,
+                    },
+                },
+                "message": { "text": "Unbound module 'M'" },
+            }])
+        );
+        assert_eq!(
+            unbound["relatedLocations"],
+            json!([{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": FILE_NAME },
+                    "region": {
+                        "startLine": 1,
+                        "startColumn": 8,
+                        "endLine": 1,
+                        "endColumn": 14,
+                        "byteOffset": 7,
+                        "byteLength": 6,
+                    },
+                },
+                "message": { "text": "In this module" },
+            }])
+        );
+    }
+}
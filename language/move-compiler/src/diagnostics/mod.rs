@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod codes;
+mod json;
 
 use crate::{
     command_line::COLOR_MODE_ENV_VAR,
@@ -20,10 +21,14 @@ use codespan_reporting::{
 use move_command_line_common::{env::read_env_var, files::FileHash};
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    io::Write,
     iter::FromIterator,
     ops::Range,
+    str::FromStr,
 };
 
 //**************************************************************************************************
@@ -51,44 +56,95 @@ pub struct Diagnostics {
     severity_count: BTreeMap<Severity, usize>,
 }
 
+/// The format used when reporting diagnostics
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Default, Serialize, Deserialize,
+)]
+pub enum DiagnosticsFormat {
+    /// Human readable output, rendered by codespan
+    #[default]
+    Human,
+    /// One JSON object per diagnostic, one per line
+    Json,
+    /// A single SARIF 2.1.0 log
+    Sarif,
+}
+
 //**************************************************************************************************
 // Reporting
 //**************************************************************************************************
 
 pub fn report_diagnostics(files: &FilesSourceText, diags: Diagnostics) -> ! {
+    report_diagnostics_with_format(files, diags, DiagnosticsFormat::Human)
+}
+
+pub fn report_diagnostics_with_format(
+    files: &FilesSourceText,
+    diags: Diagnostics,
+    format: DiagnosticsFormat,
+) -> ! {
     let should_exit = true;
-    report_diagnostics_impl(files, diags, should_exit);
+    report_diagnostics_impl(files, diags, format, should_exit);
     std::process::exit(1)
 }
 
 pub fn report_warnings(files: &FilesSourceText, warnings: Diagnostics) {
+    report_warnings_with_format(files, warnings, DiagnosticsFormat::Human)
+}
+
+pub fn report_warnings_with_format(
+    files: &FilesSourceText,
+    warnings: Diagnostics,
+    format: DiagnosticsFormat,
+) {
     if warnings.is_empty() {
         return;
     }
     debug_assert!(warnings.max_severity().unwrap() == Severity::Warning);
-    report_diagnostics_impl(files, warnings, false)
+    report_diagnostics_impl(files, warnings, format, false)
 }
 
-fn report_diagnostics_impl(files: &FilesSourceText, diags: Diagnostics, should_exit: bool) {
-    let color_choice = match read_env_var(COLOR_MODE_ENV_VAR).as_str() {
-        "NONE" => ColorChoice::Never,
-        "ANSI" => ColorChoice::AlwaysAnsi,
-        "ALWAYS" => ColorChoice::Always,
-        _ => ColorChoice::Auto,
-    };
-    let mut writer = StandardStream::stderr(color_choice);
-    output_diagnostics(&mut writer, files, diags);
+fn report_diagnostics_impl(
+    files: &FilesSourceText,
+    diags: Diagnostics,
+    format: DiagnosticsFormat,
+    should_exit: bool,
+) {
+    match format {
+        DiagnosticsFormat::Human => {
+            let color_choice = match read_env_var(COLOR_MODE_ENV_VAR).as_str() {
+                "NONE" => ColorChoice::Never,
+                "ANSI" => ColorChoice::AlwaysAnsi,
+                "ALWAYS" => ColorChoice::Always,
+                _ => ColorChoice::Auto,
+            };
+            let mut writer = StandardStream::stderr(color_choice);
+            output_diagnostics(&mut writer, files, diags);
+        }
+        DiagnosticsFormat::Json | DiagnosticsFormat::Sarif => {
+            let buffer = report_diagnostics_to_buffer_with_format(files, diags, format);
+            std::io::stderr().write_all(&buffer).unwrap();
+        }
+    }
     if should_exit {
         std::process::exit(1);
     }
 }
 
 pub fn unwrap_or_report_diagnostics<T>(files: &FilesSourceText, res: Result<T, Diagnostics>) -> T {
+    unwrap_or_report_diagnostics_with_format(files, res, DiagnosticsFormat::Human)
+}
+
+pub fn unwrap_or_report_diagnostics_with_format<T>(
+    files: &FilesSourceText,
+    res: Result<T, Diagnostics>,
+    format: DiagnosticsFormat,
+) -> T {
     match res {
         Ok(t) => t,
         Err(diags) => {
             assert!(!diags.is_empty());
-            report_diagnostics(files, diags)
+            report_diagnostics_with_format(files, diags, format)
         }
     }
 }
@@ -105,17 +161,42 @@ pub fn report_diagnostics_to_color_buffer(files: &FilesSourceText, diags: Diagno
     writer.into_inner()
 }
 
-fn output_diagnostics<W: WriteColor>(
-    writer: &mut W,
-    sources: &FilesSourceText,
+/// Renders the diagnostics in the given format. For `DiagnosticsFormat::Human`, the output is
+/// colored in the same way as `report_diagnostics_to_color_buffer`
+pub fn report_diagnostics_to_buffer_with_format(
+    files: &FilesSourceText,
     diags: Diagnostics,
-) {
+    format: DiagnosticsFormat,
+) -> Vec<u8> {
+    match format {
+        DiagnosticsFormat::Human => report_diagnostics_to_color_buffer(files, diags),
+        DiagnosticsFormat::Json => {
+            let (files, file_mapping) = make_files(files);
+            json::render_json_lines(&files, &file_mapping, sorted_unique(diags)).into_bytes()
+        }
+        DiagnosticsFormat::Sarif => {
+            let (files, file_mapping) = make_files(files);
+            json::render_sarif(&files, &file_mapping, sorted_unique(diags)).into_bytes()
+        }
+    }
+}
+
+fn make_files(sources: &FilesSourceText) -> (SimpleFiles<Symbol, &str>, FileMapping) {
     let mut files = SimpleFiles::new();
     let mut file_mapping = HashMap::new();
     for (fhash, (fname, source)) in sources {
         let id = files.add(*fname, source.as_str());
         file_mapping.insert(*fhash, id);
     }
+    (files, file_mapping)
+}
+
+fn output_diagnostics<W: WriteColor>(
+    writer: &mut W,
+    sources: &FilesSourceText,
+    diags: Diagnostics,
+) {
+    let (files, file_mapping) = make_files(sources);
     render_diagnostics(writer, &files, &file_mapping, diags);
 }
 
@@ -123,22 +204,31 @@ fn render_diagnostics(
     writer: &mut dyn WriteColor,
     files: &SimpleFiles<Symbol, &str>,
     file_mapping: &FileMapping,
-    mut diags: Diagnostics,
+    diags: Diagnostics,
 ) {
+    for diag in sorted_unique(diags) {
+        let rendered = render_diagnostic(file_mapping, diag);
+        emit(writer, &Config::default(), files, &rendered).unwrap()
+    }
+}
+
+/// Sorts the diagnostics by their primary location, removing any duplicates
+fn sorted_unique(mut diags: Diagnostics) -> Vec<Diagnostic> {
     diags.diagnostics.sort_by(|e1, e2| {
         let loc1: &Loc = &e1.primary_label.0;
         let loc2: &Loc = &e2.primary_label.0;
         loc1.cmp(loc2)
     });
     let mut seen: HashSet<Diagnostic> = HashSet::new();
+    let mut unique = vec![];
     for diag in diags.diagnostics {
         if seen.contains(&diag) {
             continue;
         }
         seen.insert(diag.clone());
-        let rendered = render_diagnostic(file_mapping, diag);
-        emit(writer, &Config::default(), files, &rendered).unwrap()
+        unique.push(diag)
     }
+    unique
 }
 
 fn convert_loc(file_mapping: &FileMapping, loc: Loc) -> (FileId, Range<usize>) {
@@ -182,6 +272,12 @@ fn render_diagnostic(
 // impls
 //**************************************************************************************************
 
+impl DiagnosticsFormat {
+    pub fn variants() -> [&'static str; 3] {
+        ["human", "json", "sarif"]
+    }
+}
+
 impl Diagnostics {
     pub fn new() -> Self {
         Self {
@@ -285,6 +381,11 @@ impl Diagnostic {
         self
     }
 
+    pub fn set_severity(mut self, severity: Severity) -> Self {
+        self.info = self.info.set_severity(severity);
+        self
+    }
+
     #[allow(unused)]
     pub fn add_secondary_labels(
         &mut self,
@@ -344,6 +445,33 @@ macro_rules! diag {
 // traits
 //**************************************************************************************************
 
+impl fmt::Display for DiagnosticsFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Human => write!(f, "human"),
+            Self::Json => write!(f, "json"),
+            Self::Sarif => write!(f, "sarif"),
+        }
+    }
+}
+
+impl FromStr for DiagnosticsFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s {
+            "human" => Self::Human,
+            "json" => Self::Json,
+            "sarif" => Self::Sarif,
+            _ => anyhow::bail!(
+                "Unrecognized diagnostics format '{}'. Expected one of: {}",
+                s,
+                Self::variants().join(", ")
+            ),
+        })
+    }
+}
+
 impl FromIterator<Diagnostic> for Diagnostics {
     fn from_iter<I: IntoIterator<Item = Diagnostic>>(iter: I) -> Self {
         let diagnostics = iter.into_iter().collect::<Vec<_>>();
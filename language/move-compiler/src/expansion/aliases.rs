@@ -154,6 +154,10 @@ impl AliasMap {
         self.unused.len()
     }
 
+    pub fn module_alias_exists(&self, n: &Name) -> bool {
+        self.modules.contains_key(n)
+    }
+
     pub fn module_alias_get(&mut self, n: &Name) -> Option<ModuleIdent> {
         match self.modules.get_mut(n) {
             None => None,
@@ -5,7 +5,8 @@
 use crate::{
     parser::ast::{
         self as P, Ability, Ability_, BinOp, ConstantName, Field, FunctionName, ModuleName,
-        QuantKind, SpecApplyPattern, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        QuantKind, SpecApplyPattern, StructName, UnaryOp, Var, VariantName, ENTRY_MODIFIER,
+        INLINE_MODIFIER,
     },
     shared::{
         ast_debug::*, known_attributes::KnownAttribute, unique_map::UniqueMap,
@@ -165,8 +166,11 @@ pub struct StructDefinition {
 pub enum StructFields {
     Defined(Fields<Type>),
     Native(Loc),
+    Variants(Variants<Type>),
 }
 
+pub type Variants<T> = UniqueMap<VariantName, (usize, Fields<T>)>;
+
 //**************************************************************************************************
 // Functions
 //**************************************************************************************************
@@ -201,6 +205,7 @@ pub struct Function {
     pub loc: Loc,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: Option<Loc>,
     pub signature: FunctionSignature,
     pub acquires: Vec<ModuleAccess>,
     pub body: FunctionBody,
@@ -358,6 +363,16 @@ pub type LValue = Spanned<LValue_>;
 pub type LValueList_ = Vec<LValue>;
 pub type LValueList = Spanned<LValueList_>;
 
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchPattern_ {
+    Variant(ModuleAccess, VariantName, Option<Vec<Type>>, Fields<LValue>),
+    Binder(LValue),
+}
+pub type MatchPattern = Spanned<MatchPattern_>;
+pub type MatchArm_ = (MatchPattern, Exp);
+pub type MatchArm = Spanned<MatchArm_>;
+
 pub type LValueWithRange_ = (LValue, Exp);
 pub type LValueWithRange = Spanned<LValueWithRange_>;
 pub type LValueWithRangeList_ = Vec<LValueWithRange>;
@@ -411,11 +426,15 @@ pub enum Exp_ {
         Spanned<Vec<Exp>>,
     ),
     Pack(ModuleAccess, Option<Vec<Type>>, Fields<Exp>),
+    // struct update, the fields not given are taken from the base value
+    PackUpdate(ModuleAccess, Option<Vec<Type>>, Fields<Exp>, Box<Exp>),
+    PackVariant(ModuleAccess, VariantName, Option<Vec<Type>>, Fields<Exp>),
     Vector(Loc, Option<Vec<Type>>, Spanned<Vec<Exp>>),
 
     IfElse(Box<Exp>, Box<Exp>, Box<Exp>),
     While(Box<Exp>, Box<Exp>),
     Loop(Box<Exp>),
+    Match(Box<Exp>, Vec<MatchArm>),
     Block(Sequence),
     Lambda(LValueList, Box<Exp>), // spec only
     Quant(
@@ -446,6 +465,7 @@ pub enum Exp_ {
 
     Borrow(bool, Box<Exp>),
     ExpDotted(Box<ExpDotted>),
+    MethodCall(Box<ExpDotted>, Name, Option<Vec<Type>>, Spanned<Vec<Exp>>),
     Index(Box<Exp>, Box<Exp>), // spec only (no mutation needed right now)
 
     Cast(Box<Exp>, Type),
@@ -1023,18 +1043,38 @@ impl AstDebug for (StructName, &StructDefinition) {
             w.write("native ");
         }
 
-        w.write(&format!("struct {}", name));
+        let kind = match fields {
+            StructFields::Variants(_) => "enum",
+            StructFields::Defined(_) | StructFields::Native(_) => "struct",
+        };
+        w.write(&format!("{} {}", kind, name));
         type_parameters.ast_debug(w);
         ability_modifiers_ast_debug(w, abilities);
-        if let StructFields::Defined(fields) = fields {
-            w.block(|w| {
+        match fields {
+            StructFields::Defined(fields) => w.block(|w| {
                 w.list(fields, ",", |w, (_, f, idx_st)| {
                     let (idx, st) = idx_st;
                     w.write(&format!("{}#{}: ", idx, f));
                     st.ast_debug(w);
                     true
                 });
-            })
+            }),
+            StructFields::Variants(variants) => w.block(|w| {
+                w.list(variants, ",", |w, (_, v, idx_fields)| {
+                    let (idx, fields) = idx_fields;
+                    w.write(&format!("{}#{} ", idx, v));
+                    w.block(|w| {
+                        w.list(fields, ",", |w, (_, f, idx_st)| {
+                            let (idx, st) = idx_st;
+                            w.write(&format!("{}#{}: ", idx, f));
+                            st.ast_debug(w);
+                            true
+                        });
+                    });
+                    true
+                });
+            }),
+            StructFields::Native(_) => (),
         }
     }
 }
@@ -1234,6 +1274,7 @@ impl AstDebug for (FunctionName, &Function) {
                 loc: _loc,
                 visibility,
                 entry,
+                inline,
                 signature,
                 acquires,
                 body,
@@ -1245,6 +1286,9 @@ impl AstDebug for (FunctionName, &Function) {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if inline.is_some() {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -1503,6 +1547,42 @@ impl AstDebug for Exp_ {
                 });
                 w.write("}");
             }
+            E::PackUpdate(ma, tys_opt, fields, base) => {
+                ma.ast_debug(w);
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_e)| {
+                    let (idx, e) = idx_e;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    e.ast_debug(w);
+                });
+                if !fields.is_empty() {
+                    w.write(", ");
+                }
+                w.write("..");
+                base.ast_debug(w);
+                w.write("}");
+            }
+            E::PackVariant(ma, v, tys_opt, fields) => {
+                ma.ast_debug(w);
+                w.write(&format!("::{}", v));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_e)| {
+                    let (idx, e) = idx_e;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    e.ast_debug(w);
+                });
+                w.write("}");
+            }
             E::Vector(_loc, tys_opt, sp!(_, elems)) => {
                 w.write("vector");
                 if let Some(ss) = tys_opt {
@@ -1532,6 +1612,18 @@ impl AstDebug for Exp_ {
                 w.write("loop ");
                 e.ast_debug(w);
             }
+            E::Match(e, arms) => {
+                w.write("match (");
+                e.ast_debug(w);
+                w.write(") ");
+                w.block(|w| {
+                    w.comma(arms, |w, sp!(_, (pat, arm))| {
+                        pat.ast_debug(w);
+                        w.write(" => ");
+                        arm.ast_debug(w);
+                    })
+                })
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
             E::Lambda(sp!(_, bs), e) => {
                 w.write("fun ");
@@ -1608,6 +1700,18 @@ impl AstDebug for Exp_ {
                 e.ast_debug(w);
             }
             E::ExpDotted(ed) => ed.ast_debug(w),
+            E::MethodCall(ed, n, tys_opt, sp!(_, rhs)) => {
+                ed.ast_debug(w);
+                w.write(&format!(".{}", n));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("(");
+                w.comma(rhs, |w, e| e.ast_debug(w));
+                w.write(")");
+            }
             E::Cast(e, ty) => {
                 w.write("(");
                 e.ast_debug(w);
@@ -1698,6 +1802,30 @@ impl AstDebug for LValue_ {
     }
 }
 
+impl AstDebug for MatchPattern_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        match self {
+            MatchPattern_::Variant(ma, v, tys_opt, fields) => {
+                ma.ast_debug(w);
+                w.write(&format!("::{}", v));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_b)| {
+                    let (idx, b) = idx_b;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    b.ast_debug(w);
+                });
+                w.write("}");
+            }
+            MatchPattern_::Binder(b) => b.ast_debug(w),
+        }
+    }
+}
+
 impl AstDebug for Vec<LValueWithRange> {
     fn ast_debug(&self, w: &mut AstWriter) {
         let parens = self.len() != 1;
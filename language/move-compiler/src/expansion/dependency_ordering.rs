@@ -338,8 +338,14 @@ fn function_acquires(context: &mut Context, acqs: &[E::ModuleAccess]) {
 //**************************************************************************************************
 
 fn struct_def(context: &mut Context, sdef: &E::StructDefinition) {
-    if let E::StructFields::Defined(fields) = &sdef.fields {
-        fields.iter().for_each(|(_, _, (_, bt))| type_(context, bt));
+    match &sdef.fields {
+        E::StructFields::Defined(fields) => {
+            fields.iter().for_each(|(_, _, (_, bt))| type_(context, bt))
+        }
+        E::StructFields::Variants(variants) => variants.iter().for_each(|(_, _, (_, fields))| {
+            fields.iter().for_each(|(_, _, (_, bt))| type_(context, bt))
+        }),
+        E::StructFields::Native(_) => (),
     }
 }
 
@@ -425,7 +431,7 @@ fn lvalue(context: &mut Context, sp!(_loc, a_): &E::LValue) {
 }
 
 fn exp(context: &mut Context, sp!(_loc, e_): &E::Exp) {
-    use crate::expansion::ast::{Exp_ as E, Value_ as V};
+    use crate::expansion::ast::{Exp_ as E, MatchPattern_ as P, Value_ as V};
     match e_ {
         E::Value(sp!(_, V::Address(a))) => context.add_address_usage(*a),
 
@@ -447,11 +453,17 @@ fn exp(context: &mut Context, sp!(_loc, e_): &E::Exp) {
             types_opt(context, tys_opt);
             args_.iter().for_each(|e| exp(context, e))
         }
-        E::Pack(ma, tys_opt, fields) => {
+        E::Pack(ma, tys_opt, fields) | E::PackVariant(ma, _, tys_opt, fields) => {
             module_access(context, ma);
             types_opt(context, tys_opt);
             fields.iter().for_each(|(_, _, (_, e))| exp(context, e))
         }
+        E::PackUpdate(ma, tys_opt, fields, base) => {
+            module_access(context, ma);
+            types_opt(context, tys_opt);
+            fields.iter().for_each(|(_, _, (_, e))| exp(context, e));
+            exp(context, base)
+        }
         E::Vector(_vec_loc, tys_opt, sp!(_, args_)) => {
             types_opt(context, tys_opt);
             args_.iter().for_each(|e| exp(context, e))
@@ -467,6 +479,19 @@ fn exp(context: &mut Context, sp!(_loc, e_): &E::Exp) {
             exp(context, e1);
             exp(context, e2)
         }
+        E::Match(e, arms) => {
+            exp(context, e);
+            for sp!(_, (pat, arm)) in arms {
+                match &pat.value {
+                    P::Variant(ma, _, tys_opt, _) => {
+                        module_access(context, ma);
+                        types_opt(context, tys_opt);
+                    }
+                    P::Binder(_) => (),
+                }
+                exp(context, arm)
+            }
+        }
         E::Block(seq) => sequence(context, seq),
         E::Assign(al, e) => {
             lvalues(context, &al.value);
@@ -487,6 +512,11 @@ fn exp(context: &mut Context, sp!(_loc, e_): &E::Exp) {
         E::ExpList(es) => es.iter().for_each(|e| exp(context, e)),
 
         E::ExpDotted(edotted) => exp_dotted(context, edotted),
+        E::MethodCall(edotted, _, tys_opt, sp!(_, args_)) => {
+            exp_dotted(context, edotted);
+            types_opt(context, tys_opt);
+            args_.iter().for_each(|e| exp(context, e))
+        }
 
         E::Cast(e, ty) | E::Annotate(e, ty) => {
             exp(context, e);
@@ -11,16 +11,19 @@ use crate::{
         byte_string, hex_string,
     },
     parser::ast::{
-        self as P, Ability, ConstantName, Field, FunctionName, ModuleName, StructName, Var,
+        self as P, Ability, Ability_, ConstantName, Field, FunctionName, ModuleName, StructName,
+        Var, VariantName,
     },
     shared::{known_attributes::AttributePosition, unique_map::UniqueMap, *},
     FullyCompiledProgram,
 };
 use move_command_line_common::parser::{parse_u16, parse_u256, parse_u32};
+use move_core_types::identifier::{is_positional_struct, IdentStr, POSITIONAL_FIELD_PREFIX};
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt,
     iter::IntoIterator,
 };
 
@@ -440,7 +443,8 @@ fn module_(
             P::ModuleMember::Use(_) => unreachable!(),
             P::ModuleMember::Friend(f) => friend(context, &mut friends, f),
             P::ModuleMember::Function(mut f) => {
-                if !context.is_source_definition {
+                // The bodies of inline functions are needed to expand their calls
+                if !context.is_source_definition && f.inline.is_none() {
                     f.body.value = P::FunctionBody_::Native
                 }
                 function(context, &mut functions, f)
@@ -1061,6 +1065,21 @@ fn struct_def_(
         .shadow_for_type_parameters(type_parameters.iter().map(|tp| &tp.name));
     let abilities = ability_set(context, "modifier", abilities_vec);
     let fields = struct_fields(context, &name, pfields);
+    if let E::StructFields::Variants(_) = &fields {
+        if let Some(key) = abilities.iter().find(|a| a.value == Ability_::Key) {
+            context.env.add_diag(diag!(
+                Declarations::InvalidStruct,
+                (
+                    key.loc,
+                    format!(
+                        "Invalid enum declaration. Enums cannot have the '{}' ability, as \
+                         they cannot be stored in global storage",
+                        Ability_::KEY
+                    ),
+                )
+            ));
+        }
+    }
     let sdef = E::StructDefinition {
         attributes,
         loc,
@@ -1080,9 +1099,22 @@ fn struct_fields(
     let pfields_vec = match pfields {
         P::StructFields::Native(loc) => return E::StructFields::Native(loc),
         P::StructFields::Defined(v) => v,
+        P::StructFields::Variants(pvariants) => {
+            return E::StructFields::Variants(variants(context, sname, pvariants))
+        }
     };
+    E::StructFields::Defined(field_decls(context, sname, "struct", pfields_vec))
+}
+
+fn field_decls(
+    context: &mut Context,
+    name: &impl fmt::Display,
+    case: &str,
+    pfields: Vec<(Field, P::Type)>,
+) -> E::Fields<E::Type> {
+    check_field_names(context, name, case, &pfields);
     let mut field_map = UniqueMap::new();
-    for (idx, (field, pt)) in pfields_vec.into_iter().enumerate() {
+    for (idx, (field, pt)) in pfields.into_iter().enumerate() {
         let t = type_(context, pt);
         if let Err((field, old_loc)) = field_map.add(field, (idx, t)) {
             context.env.add_diag(diag!(
@@ -1090,15 +1122,85 @@ fn struct_fields(
                 (
                     field.loc(),
                     format!(
-                        "Duplicate definition for field '{}' in struct '{}'",
-                        field, sname
+                        "Duplicate definition for field '{}' in {} '{}'",
+                        field, case, name
                     ),
                 ),
                 (old_loc, "Field previously defined here"),
             ));
         }
     }
-    E::StructFields::Defined(field_map)
+    field_map
+}
+
+// Positional fields are named `pos0`, `pos1`, ... in the binary format, and readers of the
+// bytecode treat a struct as positional exactly when its fields are named this way, in order.
+// Named fields may use these names, as long as they do not form that sequence.
+fn check_field_names(
+    context: &mut Context,
+    name: &impl fmt::Display,
+    case: &str,
+    pfields: &[(Field, P::Type)],
+) {
+    let (first, _) = match pfields.first() {
+        Some(first) if !first.0.is_positional() => first,
+        _ => return,
+    };
+    let field_names = pfields
+        .iter()
+        .map(|(field, _)| IdentStr::new(field.0.value.as_str()))
+        .collect::<Result<Vec<_>, _>>();
+    let is_ambiguous = match field_names {
+        Ok(names) => is_positional_struct(names),
+        Err(_) => false,
+    };
+    if is_ambiguous {
+        let msg = format!(
+            "Invalid field names for {} '{}'. Named fields cannot be exactly '{}0', '{}1', ... in \
+             order, as these are the names of the fields of positional {}s",
+            case, name, POSITIONAL_FIELD_PREFIX, POSITIONAL_FIELD_PREFIX, case,
+        );
+        context
+            .env
+            .add_diag(diag!(Declarations::InvalidName, (first.loc(), msg)));
+    }
+}
+
+fn variants(
+    context: &mut Context,
+    sname: &StructName,
+    pvariants: Vec<(VariantName, Vec<(Field, P::Type)>)>,
+) -> E::Variants<E::Type> {
+    if pvariants.is_empty() {
+        context.env.add_diag(diag!(
+            Declarations::InvalidStruct,
+            (
+                sname.loc(),
+                format!(
+                    "Invalid enum declaration. Enum '{}' must have at least one variant",
+                    sname
+                ),
+            )
+        ));
+    }
+    let mut variant_map = UniqueMap::new();
+    for (idx, (variant, pfields)) in pvariants.into_iter().enumerate() {
+        let fields = field_decls(context, &variant, "variant", pfields);
+        if let Err((variant, old_loc)) = variant_map.add(variant, (idx, fields)) {
+            context.env.add_diag(diag!(
+                Declarations::DuplicateItem,
+                (
+                    variant.loc(),
+                    format!(
+                        "Duplicate definition for variant '{}' in enum '{}'",
+                        variant, sname
+                    ),
+                ),
+                (old_loc, "Variant previously defined here"),
+            ));
+        }
+    }
+    variant_map
 }
 
 //**************************************************************************************************
@@ -1201,6 +1303,7 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
         name,
         visibility: pvisibility,
         entry,
+        inline,
         signature: psignature,
         body: pbody,
         acquires,
@@ -1208,7 +1311,7 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
     assert!(context.exp_specs.is_empty());
     let attributes = flatten_attributes(context, AttributePosition::Function, pattributes);
     let visibility = visibility(context, pvisibility);
-    let (old_aliases, signature) = function_signature(context, psignature);
+    let (old_aliases, signature) = function_signature(context, inline.is_some(), psignature);
     let acquires = acquires
         .into_iter()
         .flat_map(|a| name_access_chain(context, Access::Type, a))
@@ -1220,6 +1323,7 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
         loc,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -1243,6 +1347,7 @@ fn visibility(context: &mut Context, pvisibility: P::Visibility) -> E::Visibilit
 
 fn function_signature(
     context: &mut Context,
+    is_inline: bool,
     psignature: P::FunctionSignature,
 ) -> (OldAliasMap, E::FunctionSignature) {
     let P::FunctionSignature {
@@ -1256,7 +1361,7 @@ fn function_signature(
         .shadow_for_type_parameters(type_parameters.iter().map(|(name, _)| name));
     let parameters = pparams
         .into_iter()
-        .map(|(v, t)| (v, type_(context, t)))
+        .map(|(v, t)| (v, parameter_type(context, is_inline, t)))
         .collect::<Vec<_>>();
     for (v, _) in &parameters {
         check_valid_local_name(context, v)
@@ -1270,6 +1375,19 @@ fn function_signature(
     (old_aliases, signature)
 }
 
+// Parameters of inline functions may have function types, as their arguments are substituted
+// directly into the body at each call site
+fn parameter_type(context: &mut Context, is_inline: bool, pty: P::Type) -> E::Type {
+    match pty {
+        sp!(loc, P::Type_::Fun(args, result)) if is_inline && !context.in_spec_context => {
+            let args = types(context, args);
+            let result = type_(context, *result);
+            sp(loc, E::Type_::Fun(args, Box::new(result)))
+        }
+        pty => type_(context, pty),
+    }
+}
+
 fn function_body(context: &mut Context, sp!(loc, pbody_): P::FunctionBody) -> E::FunctionBody {
     use E::FunctionBody_ as EF;
     use P::FunctionBody_ as PF;
@@ -1329,7 +1447,7 @@ fn spec_target(context: &mut Context, sp!(loc, pt): P::SpecBlockTarget) -> E::Sp
         PT::Member(name, signature_opt) => ET::Member(
             name,
             signature_opt.map(|s| {
-                let (old_aliases, signature) = function_signature(context, *s);
+                let (old_aliases, signature) = function_signature(context, false, *s);
                 context.set_to_outer_scope(old_aliases);
                 Box::new(signature)
             }),
@@ -1421,7 +1539,7 @@ fn spec_member(context: &mut Context, sp!(loc, pm): P::SpecBlockMember) -> E::Sp
             signature,
             body,
         } => {
-            let (old_aliases, signature) = function_signature(context, signature);
+            let (old_aliases, signature) = function_signature(context, false, signature);
             let body = function_body(context, body);
             context.set_to_outer_scope(old_aliases);
             EM::Function {
@@ -1595,7 +1713,11 @@ fn type_(context: &mut Context, sp!(loc, pt_): P::Type) -> E::Type {
             } else {
                 context.env.add_diag(diag!(
                     Syntax::SpecContextRestricted,
-                    (loc, "`|_|_` function type only allowed in specifications")
+                    (
+                        loc,
+                        "`|_|_` function type only allowed in specifications and as parameters \
+                         of 'inline' functions"
+                    )
                 ));
                 ET::UnresolvedError
             }
@@ -1668,6 +1790,34 @@ fn name_access_chain(
     Some(sp(loc, tn_))
 }
 
+// `Enum::Variant` is a variant access if `Enum` is not a module alias (or `Self`). Enum names are
+// always valid struct names, so anything else is left to `name_access_chain` to report
+fn is_variant_access_chain(context: &mut Context, sp!(_, ptn_): &P::NameAccessChain) -> bool {
+    use P::{LeadingNameAccess_ as LN, NameAccessChain_ as PN};
+    match ptn_ {
+        PN::Two(sp!(_, LN::Name(n1)), _) => {
+            n1.value.as_str() != ModuleName::SELF_NAME
+                && !context.aliases.module_alias_exists(n1)
+                && is_valid_struct_constant_or_schema_name(n1.value.as_str())
+        }
+        _ => false,
+    }
+}
+
+fn variant_access_chain(
+    context: &mut Context,
+    sp!(_, ptn_): P::NameAccessChain,
+) -> Option<(E::ModuleAccess, VariantName)> {
+    use P::{LeadingNameAccess_ as LN, NameAccessChain_ as PN};
+    match ptn_ {
+        PN::Two(sp!(_, LN::Name(n1)), n2) => {
+            let en = name_access_chain(context, Access::ApplyNamed, sp(n1.loc, PN::One(n1)))?;
+            Some((en, VariantName(n2)))
+        }
+        _ => panic!("ICE variant_access_chain with something other than `Enum::Variant`"),
+    }
+}
+
 fn name_access_chain_to_module_ident(
     context: &mut Context,
     sp!(loc, pn_): P::NameAccessChain,
@@ -1814,6 +1964,9 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
         },
         PE::Move(v) => EE::Move(v),
         PE::Copy(v) => EE::Copy(v),
+        PE::Name(pn, ptys_opt) if is_variant_access_chain(context, &pn) => {
+            pack_variant(context, loc, pn, ptys_opt, vec![])
+        }
         PE::Name(_, Some(_)) if !context.in_spec_context => {
             context.env.add_diag(diag!(
                 Syntax::SpecContextRestricted,
@@ -1848,6 +2001,9 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
                 }
             }
         }
+        PE::Pack(pn, ptys_opt, pfields) if is_variant_access_chain(context, &pn) => {
+            pack_variant(context, loc, pn, ptys_opt, pfields)
+        }
         PE::Pack(pn, ptys_opt, pfields) => {
             let en_opt = name_access_chain(context, Access::ApplyNamed, pn);
             let tys_opt = optional_types(context, ptys_opt);
@@ -1864,6 +2020,9 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
                 }
             }
         }
+        PE::PackUpdate(pn, ptys_opt, pfields, pbase) => {
+            pack_update(context, loc, pn, ptys_opt, pfields, pbase)
+        }
         PE::Vector(vec_loc, ptys_opt, sp!(args_loc, pargs_)) => {
             let tys_opt = optional_types(context, ptys_opt);
             let args = sp(args_loc, exps(context, pargs_));
@@ -1880,23 +2039,16 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
         }
         PE::While(pb, ploop) => EE::While(exp(context, *pb), exp(context, *ploop)),
         PE::Loop(ploop) => EE::Loop(exp(context, *ploop)),
+        PE::Match(pe, parms) => match_(context, *pe, parms),
         PE::Block(seq) => EE::Block(sequence(context, loc, seq)),
         PE::Lambda(pbs, pe) => {
-            if !context.in_spec_context {
-                context.env.add_diag(diag!(
-                    Syntax::SpecContextRestricted,
-                    (loc, "lambda expression only allowed in specifications"),
-                ));
-                EE::UnresolvedError
-            } else {
-                let bs_opt = bind_list(context, pbs);
-                let e = exp_(context, *pe);
-                match bs_opt {
-                    Some(bs) => EE::Lambda(bs, Box::new(e)),
-                    None => {
-                        assert!(context.env.has_errors());
-                        EE::UnresolvedError
-                    }
+            let bs_opt = bind_list(context, pbs);
+            let e = exp_(context, *pe);
+            match bs_opt {
+                Some(bs) => EE::Lambda(bs, Box::new(e)),
+                None => {
+                    assert!(context.env.has_errors());
+                    EE::UnresolvedError
                 }
             }
         }
@@ -1976,6 +2128,9 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
                 EE::UnresolvedError
             }
         },
+        PE::DotCall(pdotted, n, ptys_opt, sp!(rloc, prs)) => {
+            method_call(context, *pdotted, n, ptys_opt, rloc, prs)
+        }
         PE::Cast(e, ty) => EE::Cast(exp(context, *e), type_(context, ty)),
         PE::Index(e, i) => {
             if context.in_spec_context {
@@ -2005,6 +2160,71 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
     sp(loc, e_)
 }
 
+fn method_call(
+    context: &mut Context,
+    pdotted: P::Exp,
+    n: Name,
+    ptys_opt: Option<Vec<P::Type>>,
+    rloc: Loc,
+    prs: Vec<P::Exp>,
+) -> E::Exp_ {
+    if context.in_spec_context {
+        let msg = "Method calls are not supported in specifications";
+        context
+            .env
+            .add_diag(diag!(Syntax::SpecContextRestricted, (n.loc, msg)));
+        return E::Exp_::UnresolvedError;
+    }
+    let tys_opt = optional_types(context, ptys_opt);
+    let ers = sp(rloc, exps(context, prs));
+    match exp_dotted(context, pdotted) {
+        Some(edotted) => E::Exp_::MethodCall(Box::new(edotted), n, tys_opt, ers),
+        None => {
+            assert!(context.env.has_errors());
+            E::Exp_::UnresolvedError
+        }
+    }
+}
+
+fn pack_update(
+    context: &mut Context,
+    loc: Loc,
+    pn: P::NameAccessChain,
+    ptys_opt: Option<Vec<P::Type>>,
+    pfields: Vec<(Field, P::Exp)>,
+    pbase: Box<P::Exp>,
+) -> E::Exp_ {
+    if context.in_spec_context {
+        let msg = "Struct updates are not supported in specifications";
+        context
+            .env
+            .add_diag(diag!(Syntax::SpecContextRestricted, (loc, msg)));
+        return E::Exp_::UnresolvedError;
+    }
+    if is_variant_access_chain(context, &pn) {
+        let msg = "Enum variants cannot be updated from a base value";
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidStructUpdate, (loc, msg)));
+        return E::Exp_::UnresolvedError;
+    }
+    let en_opt = name_access_chain(context, Access::ApplyNamed, pn);
+    let tys_opt = optional_types(context, ptys_opt);
+    let efields_vec = pfields
+        .into_iter()
+        .map(|(f, pe)| (f, exp_(context, pe)))
+        .collect();
+    let efields = fields(context, loc, "construction", "argument", efields_vec);
+    let ebase = exp(context, *pbase);
+    match en_opt {
+        Some(en) => E::Exp_::PackUpdate(en, tys_opt, efields, ebase),
+        None => {
+            assert!(context.env.has_errors());
+            E::Exp_::UnresolvedError
+        }
+    }
+}
+
 fn exp_dotted(context: &mut Context, sp!(loc, pdotted_): P::Exp) -> Option<E::ExpDotted> {
     use E::ExpDotted_ as EE;
     use P::Exp_ as PE;
@@ -2187,6 +2407,96 @@ fn bind(context: &mut Context, sp!(loc, pb_): P::Bind) -> Option<E::LValue> {
     Some(sp(loc, b_))
 }
 
+// A variant without fields can be constructed without braces, so `pfields` may come from either
+// a `P::Exp_::Name` or a `P::Exp_::Pack`
+fn pack_variant(
+    context: &mut Context,
+    loc: Loc,
+    pn: P::NameAccessChain,
+    ptys_opt: Option<Vec<P::Type>>,
+    pfields: Vec<(Field, P::Exp)>,
+) -> E::Exp_ {
+    use E::Exp_ as EE;
+    let ev_opt = variant_access_chain(context, pn);
+    let tys_opt = optional_types(context, ptys_opt);
+    let efields_vec = pfields
+        .into_iter()
+        .map(|(f, pe)| (f, exp_(context, pe)))
+        .collect();
+    let efields = fields(context, loc, "construction", "argument", efields_vec);
+    match ev_opt {
+        Some((en, v)) => EE::PackVariant(en, v, tys_opt, efields),
+        None => {
+            assert!(context.env.has_errors());
+            EE::UnresolvedError
+        }
+    }
+}
+
+fn match_(context: &mut Context, pe: P::Exp, parms: Vec<P::MatchArm>) -> E::Exp_ {
+    use E::Exp_ as EE;
+    let e = exp(context, pe);
+    let arms_opt: Option<Vec<E::MatchArm>> = parms
+        .into_iter()
+        .map(|sp!(aloc, (pb, parm))| {
+            let pat_opt = match_pattern(context, pb);
+            let arm = exp_(context, parm);
+            Some(sp(aloc, (pat_opt?, arm)))
+        })
+        .collect();
+    match arms_opt {
+        Some(arms) => EE::Match(e, arms),
+        None => {
+            assert!(context.env.has_errors());
+            EE::UnresolvedError
+        }
+    }
+}
+
+fn match_pattern(context: &mut Context, sp!(loc, pb_): P::Bind) -> Option<E::MatchPattern> {
+    use E::MatchPattern_ as EP;
+    use P::Bind_ as PB;
+    let pat_ = match pb_ {
+        PB::Var(_) => EP::Binder(bind(context, sp(loc, pb_))?),
+        PB::Unpack(ptn, ptys_opt, pfields) if is_variant_access_chain(context, &ptn) => {
+            let ev_opt = variant_access_chain(context, *ptn);
+            let tys_opt = optional_types(context, ptys_opt);
+            let vfields: Option<Vec<(Field, E::LValue)>> = pfields
+                .into_iter()
+                .map(|(f, pb)| match pb.value {
+                    PB::Var(_) => Some((f, bind(context, pb)?)),
+                    PB::Unpack(..) => {
+                        context.env.add_diag(diag!(
+                            Syntax::InvalidPattern,
+                            (
+                                pb.loc,
+                                "Invalid match pattern. Nested patterns are not supported, \
+                                 bind the field to a variable instead",
+                            )
+                        ));
+                        None
+                    }
+                })
+                .collect();
+            let fields = fields(context, loc, "match pattern", "binding", vfields?);
+            let (en, v) = ev_opt?;
+            EP::Variant(en, v, tys_opt, fields)
+        }
+        PB::Unpack(..) => {
+            context.env.add_diag(diag!(
+                Syntax::InvalidPattern,
+                (
+                    loc,
+                    "Invalid match pattern. Expected a variant of the form 'Enum::Variant' or \
+                     a variable",
+                )
+            ));
+            return None;
+        }
+    };
+    Some(sp(loc, pat_))
+}
+
 enum LValue {
     Assigns(E::LValueList),
     FieldMutate(Box<E::ExpDotted>),
@@ -2280,6 +2590,18 @@ fn assign(context: &mut Context, sp!(loc, e_): P::Exp) -> Option<E::LValue> {
             let efields = assign_unpack_fields(context, loc, pfields)?;
             EL::Unpack(en, tys_opt, efields)
         }
+        // Deconstructing assignment of a positional struct, `S(x, y) = e`
+        PE::Call(pn, false, ptys_opt, sp!(_, pargs)) => {
+            let en = name_access_chain(context, Access::ApplyNamed, pn)?;
+            let tys_opt = optional_types(context, ptys_opt);
+            let pfields = pargs
+                .into_iter()
+                .enumerate()
+                .map(|(idx, e)| (Field::positional(e.loc, idx), e))
+                .collect();
+            let efields = assign_unpack_fields(context, loc, pfields)?;
+            EL::Unpack(en, tys_opt, efields)
+        }
         _ => {
             context.env.add_diag(diag!(
                 Syntax::InvalidLValue,
@@ -2366,7 +2688,13 @@ fn unbound_names_exp(unbound: &mut BTreeSet<Name>, sp!(_, e_): &E::Exp) {
         EE::Call(_, _, _, sp!(_, es_)) | EE::Vector(_, _, sp!(_, es_)) => {
             unbound_names_exps(unbound, es_)
         }
-        EE::Pack(_, _, es) => unbound_names_exps(unbound, es.iter().map(|(_, _, (_, e))| e)),
+        EE::Pack(_, _, es) | EE::PackVariant(_, _, _, es) => {
+            unbound_names_exps(unbound, es.iter().map(|(_, _, (_, e))| e))
+        }
+        EE::PackUpdate(_, _, es, ebase) => {
+            unbound_names_exp(unbound, ebase);
+            unbound_names_exps(unbound, es.iter().map(|(_, _, (_, e))| e))
+        }
         EE::IfElse(econd, et, ef) => {
             unbound_names_exp(unbound, ef);
             unbound_names_exp(unbound, et);
@@ -2377,6 +2705,19 @@ fn unbound_names_exp(unbound: &mut BTreeSet<Name>, sp!(_, e_): &E::Exp) {
             unbound_names_exp(unbound, econd)
         }
         EE::Loop(eloop) => unbound_names_exp(unbound, eloop),
+        EE::Match(esubject, arms) => {
+            for sp!(_, (pat, earm)) in arms.iter().rev() {
+                unbound_names_exp(unbound, earm);
+                // remove anything bound by the pattern
+                match &pat.value {
+                    E::MatchPattern_::Variant(_, _, _, lfields) => lfields
+                        .iter()
+                        .for_each(|(_, _, (_, l))| unbound_names_bind(unbound, l)),
+                    E::MatchPattern_::Binder(l) => unbound_names_bind(unbound, l),
+                }
+            }
+            unbound_names_exp(unbound, esubject)
+        }
 
         EE::Block(seq) => unbound_names_sequence(unbound, seq),
         EE::Lambda(ls, er) => {
@@ -2417,6 +2758,10 @@ fn unbound_names_exp(unbound: &mut BTreeSet<Name>, sp!(_, e_): &E::Exp) {
         }
         EE::ExpList(es) => unbound_names_exps(unbound, es),
         EE::ExpDotted(ed) => unbound_names_dotted(unbound, ed),
+        EE::MethodCall(ed, _, _, sp!(_, es_)) => {
+            unbound_names_exps(unbound, es_);
+            unbound_names_dotted(unbound, ed)
+        }
         EE::Index(el, ei) => {
             unbound_names_exp(unbound, ei);
             unbound_names_exp(unbound, el)
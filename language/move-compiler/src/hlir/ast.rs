@@ -9,7 +9,8 @@ use crate::{
     },
     naming::ast::{BuiltinTypeName, BuiltinTypeName_, StructTypeParameter, TParam},
     parser::ast::{
-        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, VariantName,
+        ENTRY_MODIFIER,
     },
     shared::{ast_debug::*, unique_map::UniqueMap, NumericalAddress},
 };
@@ -78,6 +79,8 @@ pub struct StructDefinition {
 pub enum StructFields {
     Defined(Vec<(Field, BaseType)>),
     Native(Loc),
+    // Variants in order of their tag
+    Variants(Vec<(VariantName, Vec<(Field, BaseType)>)>),
 }
 
 //**************************************************************************************************
@@ -231,9 +234,23 @@ pub enum LValue_ {
     Ignore,
     Var(Var, Box<SingleType>),
     Unpack(StructName, Vec<BaseType>, Vec<(Field, LValue)>),
+    UnpackVariant(
+        StructName,
+        VariantName,
+        UnpackType,
+        Vec<BaseType>,
+        Vec<(Field, LValue)>,
+    ),
 }
 pub type LValue = Spanned<LValue_>;
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UnpackType {
+    ByValue,
+    ByImmRef,
+    ByMutRef,
+}
+
 //**************************************************************************************************
 // Expressions
 //**************************************************************************************************
@@ -323,6 +340,13 @@ pub enum UnannotatedExp_ {
     BinopExp(Box<Exp>, BinOp, Box<Exp>),
 
     Pack(StructName, Vec<BaseType>, Vec<(Field, BaseType, Exp)>),
+    PackVariant(
+        StructName,
+        VariantName,
+        Vec<BaseType>,
+        Vec<(Field, BaseType, Exp)>,
+    ),
+    TestVariant(StructName, VariantName, Vec<BaseType>, Box<Exp>),
     ExpList(Vec<ExpListItem>),
 
     Borrow(bool, Box<Exp>, Field),
@@ -730,17 +754,35 @@ impl AstDebug for (StructName, &StructDefinition) {
             w.write("native ");
         }
 
-        w.write(&format!("struct {}", name));
+        let kind = match fields {
+            StructFields::Variants(_) => "enum",
+            StructFields::Native(_) | StructFields::Defined(_) => "struct",
+        };
+        w.write(&format!("{} {}", kind, name));
         type_parameters.ast_debug(w);
         ability_modifiers_ast_debug(w, abilities);
-        if let StructFields::Defined(fields) = fields {
-            w.block(|w| {
+        match fields {
+            StructFields::Native(_) => (),
+            StructFields::Defined(fields) => w.block(|w| {
                 w.list(fields, ";", |w, (f, bt)| {
                     w.write(&format!("{}: ", f));
                     bt.ast_debug(w);
                     true
                 })
-            })
+            }),
+            StructFields::Variants(variants) => w.block(|w| {
+                w.list(variants, ",", |w, (v, fields)| {
+                    w.write(&format!("{}", v));
+                    w.block(|w| {
+                        w.list(fields, ";", |w, (f, bt)| {
+                            w.write(&format!("{}: ", f));
+                            bt.ast_debug(w);
+                            true
+                        })
+                    });
+                    true
+                })
+            }),
         }
     }
 }
@@ -1125,6 +1167,28 @@ impl AstDebug for UnannotatedExp_ {
                 });
                 w.write("}");
             }
+            E::PackVariant(s, v, tys, fields) => {
+                w.write(&format!("{}::{}", s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (f, bt, e)| {
+                    w.annotate(|w| w.write(&format!("{}", f)), bt);
+                    w.write(": ");
+                    e.ast_debug(w);
+                });
+                w.write("}");
+            }
+            E::TestVariant(s, v, tys, e) => {
+                w.write(&format!("test_variant {}::{}", s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("(");
+                e.ast_debug(w);
+                w.write(")");
+            }
 
             E::ExpList(es) => {
                 w.write("(");
@@ -1275,6 +1339,23 @@ impl AstDebug for LValue_ {
                 });
                 w.write("}");
             }
+            L::UnpackVariant(s, v, unpack_type, tys, fields) => {
+                match unpack_type {
+                    UnpackType::ByValue => (),
+                    UnpackType::ByImmRef => w.write("&"),
+                    UnpackType::ByMutRef => w.write("&mut "),
+                }
+                w.write(&format!("{}::{}", s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (f, l)| {
+                    w.write(&format!("{}: ", f));
+                    l.ast_debug(w)
+                });
+                w.write("}");
+            }
         }
     }
 }
@@ -7,7 +7,7 @@ use crate::{
     expansion::ast::{self as E, AbilitySet, Fields, ModuleIdent},
     hlir::ast::{self as H, Block, MoveOpAnnotation},
     naming::ast as N,
-    parser::ast::{BinOp_, ConstantName, Field, FunctionName, StructName, Var},
+    parser::ast::{BinOp_, ConstantName, Field, FunctionName, StructName, Var, VariantName},
     shared::{unique_map::UniqueMap, *},
     typing::ast as T,
     FullyCompiledProgram,
@@ -60,9 +60,13 @@ pub fn display_var(s: Symbol) -> DisplayVar {
 // Context
 //**************************************************************************************************
 
+type FieldIndices = UniqueMap<Field, usize>;
+type VariantFieldIndices = UniqueMap<VariantName, FieldIndices>;
+
 struct Context<'env> {
     env: &'env mut CompilationEnv,
-    structs: UniqueMap<ModuleIdent, UniqueMap<StructName, UniqueMap<Field, usize>>>,
+    structs: UniqueMap<ModuleIdent, UniqueMap<StructName, FieldIndices>>,
+    variants: UniqueMap<ModuleIdent, UniqueMap<StructName, VariantFieldIndices>>,
     function_locals: UniqueMap<Var, H::SingleType>,
     local_scope: UniqueMap<Var, Var>,
     used_locals: BTreeSet<Var>,
@@ -76,39 +80,54 @@ impl<'env> Context<'env> {
         pre_compiled_lib_opt: Option<&FullyCompiledProgram>,
         prog: &T::Program,
     ) -> Self {
+        fn field_indices(field_map: &Fields<N::Type>) -> FieldIndices {
+            let mut fields = UniqueMap::new();
+            for (field, (idx, _)) in field_map.key_cloned_iter() {
+                fields.add(field, *idx).unwrap();
+            }
+            fields
+        }
+
         fn add_struct_fields(
-            structs: &mut UniqueMap<ModuleIdent, UniqueMap<StructName, UniqueMap<Field, usize>>>,
+            structs: &mut UniqueMap<ModuleIdent, UniqueMap<StructName, FieldIndices>>,
+            variants: &mut UniqueMap<ModuleIdent, UniqueMap<StructName, VariantFieldIndices>>,
             mident: ModuleIdent,
             struct_defs: &UniqueMap<StructName, N::StructDefinition>,
         ) {
             let mut cur_structs = UniqueMap::new();
+            let mut cur_variants = UniqueMap::new();
             for (sname, sdef) in struct_defs.key_cloned_iter() {
-                let mut fields = UniqueMap::new();
-                let field_map = match &sdef.fields {
-                    N::StructFields::Native(_) => continue,
-                    N::StructFields::Defined(m) => m,
-                };
-                for (field, (idx, _)) in field_map.key_cloned_iter() {
-                    fields.add(field, *idx).unwrap();
+                match &sdef.fields {
+                    N::StructFields::Native(_) => (),
+                    N::StructFields::Defined(m) => {
+                        cur_structs.add(sname, field_indices(m)).unwrap();
+                    }
+                    N::StructFields::Variants(vs) => {
+                        let variant_fields = vs.ref_map(|_, (_, m)| field_indices(m));
+                        cur_variants.add(sname, variant_fields).unwrap();
+                    }
                 }
-                cur_structs.add(sname, fields).unwrap();
             }
             structs.remove(&mident);
             structs.add(mident, cur_structs).unwrap();
+            variants.remove(&mident);
+            variants.add(mident, cur_variants).unwrap();
         }
 
         let mut structs = UniqueMap::new();
+        let mut variants = UniqueMap::new();
         if let Some(pre_compiled_lib) = pre_compiled_lib_opt {
             for (mident, mdef) in pre_compiled_lib.typing.modules.key_cloned_iter() {
-                add_struct_fields(&mut structs, mident, &mdef.structs)
+                add_struct_fields(&mut structs, &mut variants, mident, &mdef.structs)
             }
         }
         for (mident, mdef) in prog.modules.key_cloned_iter() {
-            add_struct_fields(&mut structs, mident, &mdef.structs)
+            add_struct_fields(&mut structs, &mut variants, mident, &mdef.structs)
         }
         Context {
             env,
             structs,
+            variants,
             function_locals: UniqueMap::new(),
             local_scope: UniqueMap::new(),
             used_locals: BTreeSet::new(),
@@ -170,6 +189,23 @@ impl<'env> Context<'env> {
         fields
     }
 
+    pub fn variant_fields(
+        &self,
+        module: &ModuleIdent,
+        enum_name: &StructName,
+        variant: &VariantName,
+    ) -> Option<&UniqueMap<Field, usize>> {
+        let fields = self
+            .variants
+            .get(module)
+            .and_then(|enums| enums.get(enum_name))
+            .and_then(|variants| variants.get(variant));
+        // variants are only constructed and deconstructed in the enum's own module, so the fields
+        // should always be present if there are no errors
+        assert!(fields.is_some() || self.env.has_errors());
+        fields
+    }
+
     fn counter_next(&mut self) -> usize {
         self.tmp_counter += 1;
         self.tmp_counter
@@ -225,7 +261,9 @@ fn module(
     let structs = tstructs.map(|name, s| struct_def(context, name, s));
 
     let constants = tconstants.map(|name, c| constant(context, name, c));
-    let functions = tfunctions.map(|name, f| function(context, name, f));
+    // Inline functions have already been substituted at their call sites
+    let functions =
+        tfunctions.filter_map(|name, f| f.inline.is_none().then(|| function(context, name, f)));
     (
         module_ident,
         H::ModuleDefinition {
@@ -283,6 +321,7 @@ fn function(context: &mut Context, _name: FunctionName, f: T::Function) -> H::Fu
         attributes,
         visibility,
         entry,
+        inline: _,
         signature,
         acquires,
         body,
@@ -424,16 +463,27 @@ fn struct_def(
 }
 
 fn struct_fields(context: &mut Context, tfields: N::StructFields) -> H::StructFields {
-    let tfields_map = match tfields {
-        N::StructFields::Native(loc) => return H::StructFields::Native(loc),
-        N::StructFields::Defined(m) => m,
-    };
+    match tfields {
+        N::StructFields::Native(loc) => H::StructFields::Native(loc),
+        N::StructFields::Defined(m) => H::StructFields::Defined(field_decls(context, m)),
+        N::StructFields::Variants(variants) => {
+            let mut indexed_variants = variants
+                .into_iter()
+                .map(|(v, (tag, m))| (tag, (v, field_decls(context, m))))
+                .collect::<Vec<_>>();
+            indexed_variants.sort_by(|(tag1, _), (tag2, _)| tag1.cmp(tag2));
+            H::StructFields::Variants(indexed_variants.into_iter().map(|(_, v)| v).collect())
+        }
+    }
+}
+
+fn field_decls(context: &mut Context, tfields_map: Fields<N::Type>) -> Vec<(Field, H::BaseType)> {
     let mut indexed_fields = tfields_map
         .into_iter()
         .map(|(f, (idx, t))| (idx, (f, base_type(context, t))))
         .collect::<Vec<_>>();
     indexed_fields.sort_by(|(idx1, _), (idx2, _)| idx1.cmp(idx2));
-    H::StructFields::Defined(indexed_fields.into_iter().map(|(_, f_ty)| f_ty).collect())
+    indexed_fields.into_iter().map(|(_, f_ty)| f_ty).collect()
 }
 
 //**************************************************************************************************
@@ -481,7 +531,7 @@ fn base_type(context: &Context, sp!(loc, nb_): N::Type) -> H::BaseType {
         NT::Param(tp) => HB::Param(tp),
         NT::UnresolvedError => HB::UnresolvedError,
         NT::Anything => HB::Unreachable,
-        NT::Ref(_, _) | NT::Unit => {
+        NT::Ref(_, _) | NT::Unit | NT::Fun(_, _) => {
             panic!(
                 "ICE type constraints failed {}:{}-{}",
                 loc.file_hash(),
@@ -668,7 +718,10 @@ fn declare_bind(context: &mut Context, sp!(_, bind_): &T::LValue) {
             let st = single_type(context, *ty.clone());
             context.bind_local(*v, st)
         }
-        L::Unpack(_, _, _, fields) | L::BorrowUnpack(_, _, _, _, fields) => fields
+        L::Unpack(_, _, _, fields)
+        | L::BorrowUnpack(_, _, _, _, fields)
+        | L::UnpackVariant(_, _, _, _, fields)
+        | L::BorrowUnpackVariant(_, _, _, _, _, fields) => fields
             .iter()
             .for_each(|(_, _, (_, (_, b)))| declare_bind(context, b)),
     }
@@ -716,7 +769,7 @@ fn assign(
             let bs = base_types(context, tbs);
 
             let mut fields = vec![];
-            for (decl_idx, f, bt, tfa) in assign_fields(context, &m, &s, tfields) {
+            for (decl_idx, f, bt, tfa) in assign_fields(context, &m, &s, None, tfields) {
                 assert!(fields.len() == decl_idx);
                 let st = &H::SingleType_::base(bt);
                 let (fa, mut fafter) = assign(context, tfa, st);
@@ -734,7 +787,7 @@ fn assign(
                 };
                 H::exp(H::Type_::single(rvalue_ty.clone()), sp(loc, copy_tmp_))
             };
-            let fields = assign_fields(context, &m, &s, tfields)
+            let fields = assign_fields(context, &m, &s, None, tfields)
                 .into_iter()
                 .enumerate();
             for (idx, (decl_idx, f, bt, tfa)) in fields {
@@ -747,17 +800,60 @@ fn assign(
             }
             L::Var(tmp, Box::new(rvalue_ty.clone()))
         }
+        A::UnpackVariant(m, s, v, tbs, tfields) => {
+            let unpack_type = H::UnpackType::ByValue;
+            assign_variant(context, &mut after, unpack_type, m, s, v, tbs, tfields)
+        }
+        A::BorrowUnpackVariant(mut_, m, s, v, tbs, tfields) => {
+            let unpack_type = if mut_ {
+                H::UnpackType::ByMutRef
+            } else {
+                H::UnpackType::ByImmRef
+            };
+            assign_variant(context, &mut after, unpack_type, m, s, v, tbs, tfields)
+        }
     };
     (sp(loc, l_), after)
 }
 
+#[allow(clippy::too_many_arguments)]
+fn assign_variant(
+    context: &mut Context,
+    after: &mut Block,
+    unpack_type: H::UnpackType,
+    m: ModuleIdent,
+    s: StructName,
+    v: VariantName,
+    tbs: Vec<N::Type>,
+    tfields: Fields<(N::Type, T::LValue)>,
+) -> H::LValue_ {
+    let bs = base_types(context, tbs);
+    let mut fields = vec![];
+    for (decl_idx, f, bt, tfa) in assign_fields(context, &m, &s, Some(&v), tfields) {
+        assert!(fields.len() == decl_idx);
+        let st = match unpack_type {
+            H::UnpackType::ByValue => H::SingleType_::base(bt),
+            H::UnpackType::ByImmRef => sp(tfa.loc, H::SingleType_::Ref(false, bt)),
+            H::UnpackType::ByMutRef => sp(tfa.loc, H::SingleType_::Ref(true, bt)),
+        };
+        let (fa, mut fafter) = assign(context, tfa, &st);
+        after.append(&mut fafter);
+        fields.push((f, fa))
+    }
+    H::LValue_::UnpackVariant(s, v, unpack_type, bs, fields)
+}
+
 fn assign_fields(
     context: &Context,
     m: &ModuleIdent,
     s: &StructName,
+    v_opt: Option<&VariantName>,
     tfields: Fields<(N::Type, T::LValue)>,
 ) -> Vec<(usize, Field, H::BaseType, T::LValue)> {
-    let decl_fields = context.fields(m, s);
+    let decl_fields = match v_opt {
+        None => context.fields(m, s),
+        Some(v) => context.variant_fields(m, s, v),
+    };
     let mut count = 0;
     let mut decl_field = |f: &Field| -> usize {
         match decl_fields {
@@ -858,6 +954,13 @@ fn exp_<'env>(
         use T::UnannotatedExp_ as TE;
 
         let (tty, sp!(loc, cur_)) = (cur.ty, cur.exp);
+        let cur_ = match cur_ {
+            TE::Match(subject, arms) => {
+                let block = match_to_if_else(stack.context, loc, tty, *subject, arms);
+                return exp_loop(stack, result, cur_expected_type_opt, Box::new(block));
+            }
+            cur_ => cur_,
+        };
         let ty = type_(stack.context, tty);
         match cur_ {
             //***********************************************
@@ -1045,6 +1148,87 @@ fn exp_<'env>(
     e_res
 }
 
+// Lowers a match to a block that binds the subject to a temporary, followed by a chain of
+// if-else expressions that test the variant of each arm in turn. The last arm is left untested,
+// as typing guarantees that the match is exhaustive
+fn match_to_if_else(
+    context: &mut Context,
+    loc: Loc,
+    result_ty: N::Type,
+    subject: T::Exp,
+    arms: Vec<T::MatchArm>,
+) -> T::Exp {
+    use T::{LValue_ as L, UnannotatedExp_ as TE};
+    let subject_ty = subject.ty.clone();
+    let vsubject = Var(sp(loc, new_temp_name(context)));
+    let by_ref = matches!(&subject_ty, sp!(_, N::Type_::Ref(_, _)));
+
+    let mut stmts = VecDeque::new();
+    let bind_list = sp(
+        loc,
+        vec![sp(loc, L::Var(vsubject, Box::new(subject_ty.clone())))],
+    );
+    let tys = vec![Some(subject_ty.clone())];
+    let bind = T::SequenceItem_::Bind(bind_list, tys, Box::new(subject));
+    stmts.push_back(sp(loc, bind));
+
+    let use_subject = |ploc| {
+        let from_user = false;
+        let e_ = if by_ref {
+            TE::Copy {
+                from_user,
+                var: vsubject,
+            }
+        } else {
+            TE::Move {
+                from_user,
+                var: vsubject,
+            }
+        };
+        T::exp(subject_ty.clone(), sp(ploc, e_))
+    };
+    let arm_block = |sp!(aloc, (pat, earm)): T::MatchArm| {
+        let ploc = pat.loc;
+        let tys = vec![Some(subject_ty.clone())];
+        let bind = T::SequenceItem_::Bind(sp(ploc, vec![pat]), tys, Box::new(use_subject(ploc)));
+        let stmts = VecDeque::from([
+            sp(ploc, bind),
+            sp(aloc, T::SequenceItem_::Seq(Box::new(earm))),
+        ]);
+        T::exp(result_ty.clone(), sp(aloc, TE::Block(stmts)))
+    };
+
+    let mut arms = arms.into_iter().rev();
+    let mut lowered = match arms.next() {
+        Some(last) => arm_block(last),
+        None => {
+            assert!(context.env.has_errors(), "ICE match without arms");
+            T::exp(result_ty.clone(), sp(loc, TE::UnresolvedError))
+        }
+    };
+    for arm in arms {
+        let ploc = arm.value.0.loc;
+        let (m, s, v, tys) = match &arm.value.0.value {
+            L::UnpackVariant(m, s, v, tys, _) | L::BorrowUnpackVariant(_, m, s, v, tys, _) => {
+                (*m, *s, *v, tys.clone())
+            }
+            _ => panic!("ICE only the last arm of a match can match all values"),
+        };
+        let subject_ref = if by_ref {
+            use_subject(ploc)
+        } else {
+            let ref_ty = sp(ploc, N::Type_::Ref(false, Box::new(subject_ty.clone())));
+            T::exp(ref_ty, sp(ploc, TE::BorrowLocal(false, vsubject)))
+        };
+        let test_ = TE::TestVariant(m, s, v, tys, Box::new(subject_ref));
+        let test = T::exp(N::Type_::bool(ploc), sp(ploc, test_));
+        let if_else_ = TE::IfElse(Box::new(test), Box::new(arm_block(arm)), Box::new(lowered));
+        lowered = T::exp(result_ty.clone(), sp(loc, if_else_));
+    }
+    stmts.push_back(sp(loc, T::SequenceItem_::Seq(Box::new(lowered))));
+    T::exp(result_ty, sp(loc, TE::Block(stmts)))
+}
+
 enum TmpItem {
     Single(Box<H::SingleType>),
     Splat(Loc, Vec<H::SingleType>),
@@ -1216,78 +1400,20 @@ fn exp_impl(
 
         TE::Pack(m, s, tbs, tfields) => {
             let bs = base_types(context, tbs);
-
-            let decl_fields = context.fields(&m, &s);
-            let mut count = 0;
-            let mut decl_field = |f: &Field| -> usize {
-                match decl_fields {
-                    Some(field_map) => *field_map.get(f).unwrap(),
-                    None => {
-                        // none can occur with errors in typing
-                        let i = count;
-                        count += 1;
-                        i
-                    }
-                }
-            };
-
-            let mut texp_fields: Vec<(usize, Field, usize, N::Type, T::Exp)> = tfields
-                .into_iter()
-                .map(|(f, (exp_idx, (bt, tf)))| (decl_field(&f), f, exp_idx, bt, tf))
-                .collect();
-            texp_fields.sort_by(|(_, _, eidx1, _, _), (_, _, eidx2, _, _)| eidx1.cmp(eidx2));
-
-            let bind_all_fields = texp_fields
-                .iter()
-                .any(|(decl_idx, _, exp_idx, _, _)| decl_idx != exp_idx);
-            let fields = if !bind_all_fields {
-                let mut fs = vec![];
-                let tes = texp_fields
-                    .into_iter()
-                    .map(|(_, f, _, bt, te)| {
-                        let bt = base_type(context, bt);
-                        fs.push((f, bt.clone()));
-                        let t = H::Type_::base(bt);
-                        (te, Some(t))
-                    })
-                    .collect();
-                let es = exp_evaluation_order(context, result, tes);
-                assert!(
-                    fs.len() == es.len(),
-                    "ICE exp_evaluation_order changed arity"
-                );
-                es.into_iter()
-                    .zip(fs)
-                    .map(|(e, (f, bt))| (f, bt, e))
-                    .collect()
-            } else {
-                let num_fields = decl_fields.as_ref().map(|m| m.len()).unwrap_or(0);
-                let mut fields = (0..num_fields).map(|_| None).collect::<Vec<_>>();
-                for (decl_idx, f, _exp_idx, bt, tf) in texp_fields {
-                    // Might have too many arguments, there will be an error from typing
-                    if decl_idx >= fields.len() {
-                        debug_assert!(context.env.has_errors());
-                        break;
-                    }
-                    let bt = base_type(context, bt);
-                    let t = H::Type_::base(bt.clone());
-                    let ef = exp_(context, result, Some(&t), tf);
-                    assert!(fields.get(decl_idx).unwrap().is_none());
-                    let move_tmp = bind_exp(context, result, ef);
-                    fields[decl_idx] = Some((f, bt, move_tmp))
-                }
-                // Might have too few arguments, there will be an error from typing if so
-                fields
-                    .into_iter()
-                    .filter_map(|o| {
-                        // if o is None, context should have errors
-                        debug_assert!(o.is_some() || context.env.has_errors());
-                        o
-                    })
-                    .collect()
-            };
+            let fields = pack_fields(context, result, &m, &s, None, tfields);
             HE::Pack(s, bs, fields)
         }
+        TE::PackVariant(m, s, v, tbs, tfields) => {
+            let bs = base_types(context, tbs);
+            let fields = pack_fields(context, result, &m, &s, Some(&v), tfields);
+            HE::PackVariant(s, v, bs, fields)
+        }
+        TE::TestVariant(_m, s, v, tbs, te) => {
+            let bs = base_types(context, tbs);
+            let e = exp(context, result, None, *te);
+            HE::TestVariant(s, v, bs, e)
+        }
+        TE::Match(_, _) => panic!("ICE match should have been desugared"),
         TE::ExpList(titems) => {
             assert!(!titems.is_empty());
             let mut tmp_items = vec![];
@@ -1369,12 +1495,95 @@ fn exp_impl(
             assert!(context.env.has_errors());
             HE::UnresolvedError
         }
+        TE::VarCall(_, _) | TE::Lambda(_, _) => panic!("ICE lambdas should have been inlined"),
 
         TE::IfElse(..) | TE::BinopExp(..) => unreachable!(),
     };
     H::exp(ty, sp(eloc, res))
 }
 
+fn pack_fields(
+    context: &mut Context,
+    result: &mut Block,
+    m: &ModuleIdent,
+    s: &StructName,
+    v_opt: Option<&VariantName>,
+    tfields: Fields<(N::Type, T::Exp)>,
+) -> Vec<(Field, H::BaseType, H::Exp)> {
+    let decl_fields = match v_opt {
+        None => context.fields(m, s),
+        Some(v) => context.variant_fields(m, s, v),
+    };
+    let mut count = 0;
+    let mut decl_field = |f: &Field| -> usize {
+        match decl_fields {
+            Some(field_map) => *field_map.get(f).unwrap(),
+            None => {
+                // none can occur with errors in typing
+                let i = count;
+                count += 1;
+                i
+            }
+        }
+    };
+
+    let mut texp_fields: Vec<(usize, Field, usize, N::Type, T::Exp)> = tfields
+        .into_iter()
+        .map(|(f, (exp_idx, (bt, tf)))| (decl_field(&f), f, exp_idx, bt, tf))
+        .collect();
+    texp_fields.sort_by(|(_, _, eidx1, _, _), (_, _, eidx2, _, _)| eidx1.cmp(eidx2));
+
+    let bind_all_fields = texp_fields
+        .iter()
+        .any(|(decl_idx, _, exp_idx, _, _)| decl_idx != exp_idx);
+    if !bind_all_fields {
+        let mut fs = vec![];
+        let tes = texp_fields
+            .into_iter()
+            .map(|(_, f, _, bt, te)| {
+                let bt = base_type(context, bt);
+                fs.push((f, bt.clone()));
+                let t = H::Type_::base(bt);
+                (te, Some(t))
+            })
+            .collect();
+        let es = exp_evaluation_order(context, result, tes);
+        assert!(
+            fs.len() == es.len(),
+            "ICE exp_evaluation_order changed arity"
+        );
+        es.into_iter()
+            .zip(fs)
+            .map(|(e, (f, bt))| (f, bt, e))
+            .collect()
+    } else {
+        let num_fields = decl_fields.as_ref().map(|m| m.len()).unwrap_or(0);
+        let mut fields = (0..num_fields).map(|_| None).collect::<Vec<_>>();
+        for (decl_idx, f, _exp_idx, bt, tf) in texp_fields {
+            // Might have too many arguments, there will be an error from typing
+            if decl_idx >= fields.len() {
+                debug_assert!(context.env.has_errors());
+                break;
+            }
+            let bt = base_type(context, bt);
+            let t = H::Type_::base(bt.clone());
+            let ef = exp_(context, result, Some(&t), tf);
+            assert!(fields.get(decl_idx).unwrap().is_none());
+            let move_tmp = bind_exp(context, result, ef);
+            fields[decl_idx] = Some((f, bt, move_tmp))
+        }
+        // Might have too few arguments, there will be an error from typing if so
+        fields
+            .into_iter()
+            .filter_map(|o| {
+                // if o is None, context should have errors
+                debug_assert!(o.is_some() || context.env.has_errors());
+                o
+            })
+            .collect()
+    }
+}
+
 fn exp_evaluation_order(
     context: &mut Context,
     result: &mut Block,
@@ -1730,17 +1939,22 @@ fn bind_for_short_circuit(e: &T::Exp) -> bool {
         | TE::UnaryExp(_, _)
         | TE::Borrow(_, _, _)
         | TE::TempBorrow(_, _)
-        | TE::BinopExp(_, _, _, _) => true,
+        | TE::BinopExp(_, _, _, _)
+        | TE::Match(_, _)
+        | TE::TestVariant(_, _, _, _, _) => true,
 
         TE::Unit { .. }
         | TE::Spec(_, _)
         | TE::Assign(_, _, _)
         | TE::Mutate(_, _)
         | TE::Pack(_, _, _, _)
+        | TE::PackVariant(_, _, _, _, _)
         | TE::Vector(_, _, _, _)
         | TE::BorrowLocal(_, _)
         | TE::ExpList(_)
-        | TE::Cast(_, _) => panic!("ICE unexpected exp in short circuit check: {:?}", e),
+        | TE::Cast(_, _)
+        | TE::VarCall(_, _)
+        | TE::Lambda(_, _) => panic!("ICE unexpected exp in short circuit check: {:?}", e),
     }
 }
 
@@ -2016,7 +2230,7 @@ fn remove_unused_bindings_lvalue(unused: &BTreeSet<Var>, sp!(_, l_): &mut H::LVa
     match l_ {
         HL::Var(v, _) if unused.contains(v) => *l_ = HL::Ignore,
         HL::Var(_, _) | HL::Ignore => (),
-        HL::Unpack(_, _, fields) => fields
+        HL::Unpack(_, _, fields) | HL::UnpackVariant(_, _, _, _, fields) => fields
             .iter_mut()
             .for_each(|(_, l)| remove_unused_bindings_lvalue(unused, l)),
     }
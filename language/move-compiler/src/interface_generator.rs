@@ -12,7 +12,7 @@ use move_binary_format::{
         TypeParameterIndex, Visibility,
     },
 };
-use move_core_types::language_storage::ModuleId;
+use move_core_types::{identifier::is_positional_struct, language_storage::ModuleId};
 use std::{collections::BTreeMap, fs};
 
 pub const NATIVE_INTERFACE: &str = "native_interface";
@@ -185,6 +185,27 @@ fn write_struct_def(ctx: &mut Context, sdef: &StructDefinition) -> String {
 
     let shandle = ctx.module.struct_handle_at(sdef.struct_handle);
 
+    if let StructFieldInformation::Declared(fields) = &sdef.field_information {
+        let names = fields.iter().map(|f| ctx.module.identifier_at(f.name));
+        if is_positional_struct(names) {
+            let tys = fields
+                .iter()
+                .map(|f| write_signature_token(ctx, &f.signature.0))
+                .collect::<Vec<_>>();
+            push!(
+                out,
+                format!(
+                    "    struct {}{}({}){};",
+                    ctx.module.identifier_at(shandle.name),
+                    write_struct_type_parameters(&shandle.type_parameters),
+                    tys.join(", "),
+                    write_ability_modifiers(shandle.abilities),
+                )
+            );
+            return out;
+        }
+    }
+
     push_line!(
         out,
         format!(
@@ -0,0 +1,103 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Flags comparisons whose result does not depend on the values being compared, e.g. `x == x`,
+//! `x >= 0`, or `x > 255u8`
+
+use super::{used_var, Lint};
+use crate::{
+    diag,
+    diagnostics::Diagnostics,
+    expansion::ast::Value_,
+    parser::ast::BinOp_,
+    typing::ast::{Exp, UnannotatedExp_},
+};
+use move_core_types::u256::U256;
+
+pub struct ConstantComparison;
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Min,
+    Max,
+}
+
+impl Lint for ConstantComparison {
+    fn name(&self) -> &'static str {
+        "constant_comparison"
+    }
+
+    fn visit_typing_exp(&self, diags: &mut Diagnostics, e: &Exp) {
+        let (lhs, sp!(_, op_), rhs) = match &e.exp.value {
+            UnannotatedExp_::BinopExp(lhs, op, _, rhs) => (lhs, op, rhs),
+            _ => return,
+        };
+        let result = match (used_var(lhs), used_var(rhs)) {
+            (Some(v1), Some(v2)) if v1 == v2 => match op_ {
+                BinOp_::Eq | BinOp_::Le | BinOp_::Ge => Some(true),
+                BinOp_::Neq | BinOp_::Lt | BinOp_::Gt => Some(false),
+                _ => None,
+            },
+            _ => match (bound(lhs), bound(rhs)) {
+                (_, Some(b)) => compare_with_bound(*op_, b),
+                (Some(b), None) => flip(*op_).and_then(|op_| compare_with_bound(op_, b)),
+                (None, None) => None,
+            },
+        };
+        if let Some(result) = result {
+            let msg = format!("This comparison is always '{}'", result);
+            diags.add(diag!(Lint::ConstantComparison, (e.exp.loc, msg)))
+        }
+    }
+}
+
+/// If the expression is a literal equal to the minimum or maximum value of its integer type
+fn bound(e: &Exp) -> Option<Bound> {
+    let v_ = match &e.exp.value {
+        UnannotatedExp_::Value(sp!(_, v_)) => v_,
+        UnannotatedExp_::Annotate(e, _) => return bound(e),
+        _ => return None,
+    };
+    macro_rules! bound_of {
+        ($n:expr, $min:expr, $max:expr) => {
+            if $n == $min {
+                Some(Bound::Min)
+            } else if $n == $max {
+                Some(Bound::Max)
+            } else {
+                None
+            }
+        };
+    }
+    match v_ {
+        Value_::U8(n) => bound_of!(*n, u8::MIN, u8::MAX),
+        Value_::U16(n) => bound_of!(*n, u16::MIN, u16::MAX),
+        Value_::U32(n) => bound_of!(*n, u32::MIN, u32::MAX),
+        Value_::U64(n) => bound_of!(*n, u64::MIN, u64::MAX),
+        Value_::U128(n) => bound_of!(*n, u128::MIN, u128::MAX),
+        Value_::U256(n) => bound_of!(*n, U256::zero(), U256::max_value()),
+        Value_::Address(_) | Value_::InferredNum(_) | Value_::Bool(_) | Value_::Bytearray(_) => {
+            None
+        }
+    }
+}
+
+/// The result of `x op bound`, if it is the same for all `x`
+fn compare_with_bound(op_: BinOp_, bound: Bound) -> Option<bool> {
+    match (op_, bound) {
+        (BinOp_::Ge, Bound::Min) | (BinOp_::Le, Bound::Max) => Some(true),
+        (BinOp_::Lt, Bound::Min) | (BinOp_::Gt, Bound::Max) => Some(false),
+        _ => None,
+    }
+}
+
+/// The operator `op2` such that `a op b` is equivalent to `b op2 a`
+fn flip(op_: BinOp_) -> Option<BinOp_> {
+    Some(match op_ {
+        BinOp_::Lt => BinOp_::Gt,
+        BinOp_::Gt => BinOp_::Lt,
+        BinOp_::Le => BinOp_::Ge,
+        BinOp_::Ge => BinOp_::Le,
+        _ => return None,
+    })
+}
@@ -0,0 +1,450 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional lints, run over the typing and CFGIR ASTs of the source modules and scripts.
+//! Lints are only run when enabled in the `Flags`. Each lint has a level, `allow`, `warn`, or
+//! `deny`, which can be set in the `Flags` (e.g. from the `[lints]` table of a `Move.toml`).
+//! A lint can be silenced for a single module, constant, or function with `#[allow(<lint>)]`.
+
+mod constant_comparison;
+mod needless_mut_borrow;
+mod redundant_copy;
+mod self_assignment;
+mod shift_overflow;
+mod while_true;
+
+use crate::{
+    cfgir::ast as G,
+    diag,
+    diagnostics::{codes::Severity, Diagnostics},
+    expansion::ast::{self as E, AttributeName_, Attribute_},
+    hlir::ast as H,
+    parser::ast::Var,
+    shared::{
+        known_attributes::{KnownAttribute, LintAttribute},
+        CompilationEnv,
+    },
+    typing::ast as T,
+};
+use move_symbol_pool::Symbol;
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+//**************************************************************************************************
+// Lints
+//**************************************************************************************************
+
+/// A single lint. Each `visit_*` function is called on every node of the corresponding kind, for
+/// each function and constant where the lint is not allowed.
+pub trait Lint {
+    /// The name used to refer to the lint in `#[allow(..)]` and in the `Flags`
+    fn name(&self) -> &'static str;
+
+    fn visit_typing_exp(&self, _diags: &mut Diagnostics, _e: &T::Exp) {}
+
+    fn visit_cfgir_exp(&self, _diags: &mut Diagnostics, _e: &H::Exp) {}
+
+    fn visit_cfgir_function(&self, _diags: &mut Diagnostics, _function: &G::Function) {}
+}
+
+const LINTS: &[&dyn Lint] = &[
+    &self_assignment::SelfAssignment,
+    &redundant_copy::RedundantCopy,
+    &constant_comparison::ConstantComparison,
+    &needless_mut_borrow::NeedlessMutBorrow,
+    &shift_overflow::ShiftOverflow,
+    &while_true::WhileTrue,
+];
+
+/// The names of all lints
+pub fn lint_names() -> impl Iterator<Item = &'static str> {
+    LINTS.iter().map(|lint| lint.name())
+}
+
+pub fn is_lint_name(name: &str) -> bool {
+    lint_names().any(|lint_name| lint_name == name)
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Default)]
+pub enum LintLevel {
+    /// The lint is not run
+    Allow,
+    /// The lint is reported as a warning
+    #[default]
+    Warn,
+    /// The lint is reported as an error
+    Deny,
+}
+
+impl LintLevel {
+    pub fn variants() -> [&'static str; 3] {
+        ["allow", "warn", "deny"]
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Warn => write!(f, "warn"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s {
+            "allow" => Self::Allow,
+            "warn" => Self::Warn,
+            "deny" => Self::Deny,
+            _ => anyhow::bail!(
+                "Unrecognized lint level '{}'. Expected one of: {}",
+                s,
+                Self::variants().join(", ")
+            ),
+        })
+    }
+}
+
+//**************************************************************************************************
+// Context
+//**************************************************************************************************
+
+struct Context<'env> {
+    env: &'env mut CompilationEnv,
+}
+
+impl<'env> Context<'env> {
+    fn new(env: &'env mut CompilationEnv) -> Self {
+        Self { env }
+    }
+
+    /// Runs `visit` for each lint that is not allowed, given the lints allowed by the attributes
+    /// in scope
+    fn run(
+        &mut self,
+        allowed: &BTreeSet<Symbol>,
+        mut visit: impl FnMut(&dyn Lint, &mut Diagnostics),
+    ) {
+        for lint in LINTS {
+            let level = if allowed.contains(&Symbol::from(lint.name())) {
+                LintLevel::Allow
+            } else {
+                self.env.flags().lint_level(lint.name())
+            };
+            let severity = match level {
+                LintLevel::Allow => continue,
+                LintLevel::Warn => Severity::Warning,
+                LintLevel::Deny => Severity::NonblockingError,
+            };
+            let mut diags = Diagnostics::new();
+            visit(*lint, &mut diags);
+            for diag in diags.into_vec() {
+                self.env.add_diag(diag.set_severity(severity))
+            }
+        }
+    }
+}
+
+/// The lints named in any `#[allow(..)]` in the given attributes
+fn allowed_lints(attributes: &E::Attributes) -> BTreeSet<Symbol> {
+    match attributes.get_(&AttributeName_::Known(KnownAttribute::Lint(
+        LintAttribute::Allow,
+    ))) {
+        Some(sp!(_, Attribute_::Parameterized(_, names))) => names
+            .key_cloned_iter()
+            .filter_map(|(sp!(_, name_), _)| match name_ {
+                AttributeName_::Unknown(name) => Some(name),
+                AttributeName_::Known(_) => None,
+            })
+            .collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+fn check_allow_attribute(context: &mut Context, attributes: &E::Attributes) {
+    let allow = AttributeName_::Known(KnownAttribute::Lint(LintAttribute::Allow));
+    let sp!(loc, attr_) = match attributes.get_(&allow) {
+        None => return,
+        Some(attr) => attr,
+    };
+    let names = match attr_ {
+        Attribute_::Parameterized(_, names) => names,
+        Attribute_::Name(_) | Attribute_::Assigned(_, _) => {
+            let msg = format!(
+                "Expected a list of lints, e.g. '#[{}(<lint>)]'",
+                LintAttribute::ALLOW
+            );
+            context
+                .env
+                .add_diag(diag!(Attributes::InvalidValue, (*loc, msg)));
+            return;
+        }
+    };
+    for (sp!(nloc, name_), sp!(_, attr_)) in names.key_cloned_iter() {
+        match (name_, attr_) {
+            (AttributeName_::Unknown(name), Attribute_::Name(_)) if is_lint_name(&name) => (),
+            (AttributeName_::Unknown(name), Attribute_::Name(_)) => {
+                let msg = format!(
+                    "Unknown lint '{}'. Expected one of: {}",
+                    name,
+                    lint_names().collect::<Vec<_>>().join(", ")
+                );
+                context
+                    .env
+                    .add_diag(diag!(Attributes::ValueWarning, (nloc, msg)));
+            }
+            _ => {
+                let msg = "Expected the name of a lint";
+                context
+                    .env
+                    .add_diag(diag!(Attributes::InvalidValue, (nloc, msg)));
+            }
+        }
+    }
+}
+
+fn with_allowed(outer: &BTreeSet<Symbol>, attributes: &E::Attributes) -> BTreeSet<Symbol> {
+    let mut allowed = outer.clone();
+    allowed.extend(allowed_lints(attributes));
+    allowed
+}
+
+//**************************************************************************************************
+// Typing
+//**************************************************************************************************
+
+/// The local used by the expression, if it is just a use of a local
+fn used_var(e: &T::Exp) -> Option<&Var> {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) => Some(var),
+        E::Annotate(e, _) => used_var(e),
+        _ => None,
+    }
+}
+
+pub fn typing_program(env: &mut CompilationEnv, prog: &T::Program) {
+    if !env.flags().lint() {
+        return;
+    }
+    let context = &mut Context::new(env);
+    for (_, _, mdef) in prog.modules.iter() {
+        if !mdef.is_source_module {
+            continue;
+        }
+        check_allow_attribute(context, &mdef.attributes);
+        let allowed = allowed_lints(&mdef.attributes);
+        for (_, _, sdef) in mdef.structs.iter() {
+            check_allow_attribute(context, &sdef.attributes);
+        }
+        for (_, _, cdef) in mdef.constants.iter() {
+            typing_constant(context, &allowed, cdef)
+        }
+        for (_, _, fdef) in mdef.functions.iter() {
+            typing_function(context, &allowed, fdef)
+        }
+    }
+    for script in prog.scripts.values() {
+        check_allow_attribute(context, &script.attributes);
+        let allowed = allowed_lints(&script.attributes);
+        for (_, _, cdef) in script.constants.iter() {
+            typing_constant(context, &allowed, cdef)
+        }
+        typing_function(context, &allowed, &script.function)
+    }
+}
+
+fn typing_constant(context: &mut Context, allowed: &BTreeSet<Symbol>, cdef: &T::Constant) {
+    check_allow_attribute(context, &cdef.attributes);
+    let allowed = with_allowed(allowed, &cdef.attributes);
+    context.run(&allowed, |lint, diags| typing_exp(lint, diags, &cdef.value))
+}
+
+fn typing_function(context: &mut Context, allowed: &BTreeSet<Symbol>, fdef: &T::Function) {
+    check_allow_attribute(context, &fdef.attributes);
+    let seq = match &fdef.body.value {
+        T::FunctionBody_::Defined(seq) => seq,
+        T::FunctionBody_::Native => return,
+    };
+    let allowed = with_allowed(allowed, &fdef.attributes);
+    context.run(&allowed, |lint, diags| typing_seq(lint, diags, seq))
+}
+
+fn typing_seq(lint: &dyn Lint, diags: &mut Diagnostics, seq: &T::Sequence) {
+    use T::SequenceItem_ as S;
+    for sp!(_, item_) in seq {
+        match item_ {
+            S::Seq(e) | S::Bind(_, _, e) => typing_exp(lint, diags, e),
+            S::Declare(_) => (),
+        }
+    }
+}
+
+fn typing_exp(lint: &dyn Lint, diags: &mut Diagnostics, e: &T::Exp) {
+    use T::UnannotatedExp_ as E;
+    lint.visit_typing_exp(diags, e);
+    match &e.exp.value {
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::Use(_)
+        | E::Constant(_, _)
+        | E::Break
+        | E::Continue
+        | E::BorrowLocal(_, _)
+        | E::Spec(_, _)
+        | E::UnresolvedError => (),
+
+        E::ModuleCall(call) => typing_exp(lint, diags, &call.arguments),
+        E::Builtin(_, e)
+        | E::Vector(_, _, _, e)
+        | E::Loop { body: e, .. }
+        | E::Assign(_, _, e)
+        | E::Return(e)
+        | E::Abort(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _)
+        | E::TempBorrow(_, e)
+        | E::TestVariant(_, _, _, _, e)
+        | E::VarCall(_, e)
+        | E::Lambda(_, e)
+        | E::Cast(e, _)
+        | E::Annotate(e, _) => typing_exp(lint, diags, e),
+        E::While(e1, e2) | E::Mutate(e1, e2) | E::BinopExp(e1, _, _, e2) => {
+            typing_exp(lint, diags, e1);
+            typing_exp(lint, diags, e2)
+        }
+        E::IfElse(e1, e2, e3) => {
+            typing_exp(lint, diags, e1);
+            typing_exp(lint, diags, e2);
+            typing_exp(lint, diags, e3)
+        }
+        E::Match(e, arms) => {
+            typing_exp(lint, diags, e);
+            for sp!(_, (_, arm)) in arms {
+                typing_exp(lint, diags, arm)
+            }
+        }
+        E::Block(seq) => typing_seq(lint, diags, seq),
+        E::Pack(_, _, _, fields) | E::PackVariant(_, _, _, _, fields) => {
+            for (_, _, (_, (_, e))) in fields.iter() {
+                typing_exp(lint, diags, e)
+            }
+        }
+        E::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        typing_exp(lint, diags, e)
+                    }
+                }
+            }
+        }
+    }
+}
+
+//**************************************************************************************************
+// CFGIR
+//**************************************************************************************************
+
+pub fn cfgir_program(env: &mut CompilationEnv, prog: &G::Program) {
+    if !env.flags().lint() {
+        return;
+    }
+    let context = &mut Context::new(env);
+    for (_, _, mdef) in prog.modules.iter() {
+        if !mdef.is_source_module {
+            continue;
+        }
+        let allowed = allowed_lints(&mdef.attributes);
+        for (_, _, fdef) in mdef.functions.iter() {
+            cfgir_function(context, &allowed, fdef)
+        }
+    }
+    for script in prog.scripts.values() {
+        let allowed = allowed_lints(&script.attributes);
+        cfgir_function(context, &allowed, &script.function)
+    }
+}
+
+fn cfgir_function(context: &mut Context, allowed: &BTreeSet<Symbol>, fdef: &G::Function) {
+    let blocks = match &fdef.body.value {
+        G::FunctionBody_::Defined { blocks, .. } => blocks,
+        G::FunctionBody_::Native => return,
+    };
+    let allowed = with_allowed(allowed, &fdef.attributes);
+    context.run(&allowed, |lint, diags| {
+        lint.visit_cfgir_function(diags, fdef);
+        for block in blocks.values() {
+            for cmd in block {
+                cfgir_command(lint, diags, cmd)
+            }
+        }
+    })
+}
+
+fn cfgir_command(lint: &dyn Lint, diags: &mut Diagnostics, sp!(_, cmd_): &H::Command) {
+    use H::Command_ as C;
+    match cmd_ {
+        C::Assign(_, e) => cfgir_exp(lint, diags, e),
+        C::Abort(e)
+        | C::Return { exp: e, .. }
+        | C::IgnoreAndPop { exp: e, .. }
+        | C::JumpIf { cond: e, .. } => cfgir_exp(lint, diags, e),
+        C::Mutate(el, er) => {
+            cfgir_exp(lint, diags, el);
+            cfgir_exp(lint, diags, er)
+        }
+        C::Jump { .. } => (),
+        C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+    }
+}
+
+fn cfgir_exp(lint: &dyn Lint, diags: &mut Diagnostics, e: &H::Exp) {
+    use H::UnannotatedExp_ as E;
+    lint.visit_cfgir_exp(diags, e);
+    match &e.exp.value {
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::Constant(_)
+        | E::BorrowLocal(_, _)
+        | E::Unreachable
+        | E::Spec(_, _)
+        | E::UnresolvedError => (),
+
+        E::ModuleCall(call) => cfgir_exp(lint, diags, &call.arguments),
+        E::Builtin(_, e)
+        | E::Freeze(e)
+        | E::Vector(_, _, _, e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _)
+        | E::TestVariant(_, _, _, e)
+        | E::Cast(e, _) => cfgir_exp(lint, diags, e),
+        E::BinopExp(e1, _, e2) => {
+            cfgir_exp(lint, diags, e1);
+            cfgir_exp(lint, diags, e2)
+        }
+        E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+            for (_, _, e) in fields {
+                cfgir_exp(lint, diags, e)
+            }
+        }
+        E::ExpList(items) => {
+            for item in items {
+                match item {
+                    H::ExpListItem::Single(e, _) | H::ExpListItem::Splat(_, e, _) => {
+                        cfgir_exp(lint, diags, e)
+                    }
+                }
+            }
+        }
+    }
+}
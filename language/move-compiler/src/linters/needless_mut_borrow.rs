@@ -0,0 +1,35 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Flags mutable borrows that are immediately frozen, e.g. passing `&mut x` where a `&` is
+//! expected
+
+use super::Lint;
+use crate::{
+    diag,
+    diagnostics::Diagnostics,
+    hlir::ast::{Exp, UnannotatedExp_},
+};
+
+pub struct NeedlessMutBorrow;
+
+impl Lint for NeedlessMutBorrow {
+    fn name(&self) -> &'static str {
+        "needless_mut_borrow"
+    }
+
+    fn visit_cfgir_exp(&self, diags: &mut Diagnostics, e: &Exp) {
+        let borrowed = match &e.exp.value {
+            UnannotatedExp_::Freeze(borrowed) => borrowed,
+            _ => return,
+        };
+        match &borrowed.exp.value {
+            UnannotatedExp_::BorrowLocal(true, _) | UnannotatedExp_::Borrow(true, _, _) => {
+                let msg = "This mutable borrow is only used as an immutable reference. \
+                    Consider using '&' instead of '&mut'";
+                diags.add(diag!(Lint::NeedlessMutBorrow, (borrowed.exp.loc, msg)))
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Flags explicit `copy`s of a local that is not used afterwards, where the value could have been
+//! moved instead. Locals that are ever borrowed are not considered, as the `copy` might be needed
+//! to keep the borrow valid
+
+use super::Lint;
+use crate::{
+    cfgir::ast::{BasicBlock, Function, FunctionBody_},
+    diag,
+    diagnostics::Diagnostics,
+    hlir::{
+        ast::{Command, Command_, Exp, ExpListItem, LValue, LValue_, Label, UnannotatedExp_},
+        translate::{display_var, DisplayVar},
+    },
+    parser::ast::Var,
+    shared::Identifier,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub struct RedundantCopy;
+
+impl Lint for RedundantCopy {
+    fn name(&self) -> &'static str {
+        "redundant_copy"
+    }
+
+    fn visit_cfgir_function(&self, diags: &mut Diagnostics, function: &Function) {
+        let blocks = match &function.body.value {
+            FunctionBody_::Defined { blocks, .. } => blocks,
+            FunctionBody_::Native => return,
+        };
+        // compute the locals live at the start of each block, iterating to a fixed point
+        let mut borrowed = BTreeSet::new();
+        let mut live_in: BTreeMap<Label, BTreeSet<Var>> = BTreeMap::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (lbl, block) in blocks.iter().rev() {
+                let mut context = Context::new(&mut borrowed, None);
+                let live = context.block(&live_in, block);
+                if live_in.get(lbl) != Some(&live) {
+                    live_in.insert(*lbl, live);
+                    changed = true;
+                }
+            }
+        }
+        // report any copy after which the local is dead
+        for block in blocks.values() {
+            Context::new(&mut borrowed, Some(diags)).block(&live_in, block);
+        }
+    }
+}
+
+struct Context<'a> {
+    live: BTreeSet<Var>,
+    borrowed: &'a mut BTreeSet<Var>,
+    diags: Option<&'a mut Diagnostics>,
+}
+
+impl<'a> Context<'a> {
+    fn new(borrowed: &'a mut BTreeSet<Var>, diags: Option<&'a mut Diagnostics>) -> Self {
+        Self {
+            live: BTreeSet::new(),
+            borrowed,
+            diags,
+        }
+    }
+
+    /// Returns the locals live at the start of the block
+    fn block(
+        &mut self,
+        live_in: &BTreeMap<Label, BTreeSet<Var>>,
+        block: &BasicBlock,
+    ) -> BTreeSet<Var> {
+        let last_cmd = block.back().unwrap();
+        self.live = last_cmd
+            .value
+            .successors()
+            .iter()
+            .filter_map(|succ| live_in.get(succ))
+            .flatten()
+            .copied()
+            .collect();
+        for cmd in block.iter().rev() {
+            self.command(cmd)
+        }
+        std::mem::take(&mut self.live)
+    }
+
+    // commands and expressions are visited in the reverse of their evaluation order
+
+    fn command(&mut self, sp!(_, cmd_): &Command) {
+        use Command_ as C;
+        match cmd_ {
+            C::Assign(ls, e) => {
+                ls.iter().for_each(|l| self.lvalue(l));
+                self.exp(e)
+            }
+            C::Mutate(el, er) => {
+                self.exp(el);
+                self.exp(er)
+            }
+            C::Return { exp: e, .. }
+            | C::Abort(e)
+            | C::IgnoreAndPop { exp: e, .. }
+            | C::JumpIf { cond: e, .. } => self.exp(e),
+            C::Jump { .. } => (),
+            C::Break | C::Continue => panic!("ICE break/continue not translated to jumps"),
+        }
+    }
+
+    fn lvalue(&mut self, sp!(_, l_): &LValue) {
+        match l_ {
+            LValue_::Ignore => (),
+            LValue_::Var(v, _) => {
+                self.live.remove(v);
+            }
+            LValue_::Unpack(_, _, fields) | LValue_::UnpackVariant(_, _, _, _, fields) => {
+                fields.iter().for_each(|(_, l)| self.lvalue(l))
+            }
+        }
+    }
+
+    fn exp(&mut self, e: &Exp) {
+        use UnannotatedExp_ as E;
+        match &e.exp.value {
+            E::Unit { .. } | E::Value(_) | E::Constant(_) | E::UnresolvedError => (),
+            E::Unreachable => (),
+
+            E::Move { var, .. } => {
+                self.live.insert(*var);
+            }
+            E::BorrowLocal(_, var) => {
+                self.borrowed.insert(*var);
+                self.live.insert(*var);
+            }
+            E::Spec(_, used_locals) => self.live.extend(used_locals.keys().copied()),
+            E::Copy { var, from_user } => {
+                let is_last_use = !self.live.contains(var) && !self.borrowed.contains(var);
+                match (&mut self.diags, display_var(var.value())) {
+                    (Some(diags), DisplayVar::Orig(v_str)) if *from_user && is_last_use => {
+                        let msg = format!(
+                            "Local '{}' is not used after this copy. Consider removing the \
+                             'copy' so the value is moved",
+                            v_str
+                        );
+                        diags.add(diag!(Lint::RedundantCopy, (e.exp.loc, msg)))
+                    }
+                    _ => (),
+                }
+                self.live.insert(*var);
+            }
+
+            E::ModuleCall(call) => self.exp(&call.arguments),
+            E::Builtin(_, e)
+            | E::Freeze(e)
+            | E::Vector(_, _, _, e)
+            | E::Dereference(e)
+            | E::UnaryExp(_, e)
+            | E::Borrow(_, e, _)
+            | E::TestVariant(_, _, _, e)
+            | E::Cast(e, _) => self.exp(e),
+            E::BinopExp(e1, _, e2) => {
+                self.exp(e2);
+                self.exp(e1)
+            }
+            E::Pack(_, _, fields) | E::PackVariant(_, _, _, fields) => {
+                fields.iter().rev().for_each(|(_, _, e)| self.exp(e))
+            }
+            E::ExpList(items) => items.iter().rev().for_each(|item| match item {
+                ExpListItem::Single(e, _) | ExpListItem::Splat(_, e, _) => self.exp(e),
+            }),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Flags assignments of a local to itself, e.g. `x = x` or `(x, y) = (x, y)`
+
+use super::{used_var, Lint};
+use crate::{
+    diag,
+    diagnostics::Diagnostics,
+    typing::ast::{Exp, ExpListItem, LValue_, UnannotatedExp_},
+};
+
+pub struct SelfAssignment;
+
+impl Lint for SelfAssignment {
+    fn name(&self) -> &'static str {
+        "self_assignment"
+    }
+
+    fn visit_typing_exp(&self, diags: &mut Diagnostics, e: &Exp) {
+        let (lvalues, rhs) = match &e.exp.value {
+            UnannotatedExp_::Assign(sp!(_, lvalues), _, rhs) => (lvalues, rhs),
+            _ => return,
+        };
+        let rhs_exps = match &rhs.exp.value {
+            UnannotatedExp_::ExpList(items) => items
+                .iter()
+                .map(|item| match item {
+                    ExpListItem::Single(e, _) => Some(e),
+                    ExpListItem::Splat(_, _, _) => None,
+                })
+                .collect::<Option<Vec<_>>>(),
+            _ => Some(vec![&**rhs]),
+        };
+        let rhs_exps = match rhs_exps {
+            Some(rhs_exps) if rhs_exps.len() == lvalues.len() => rhs_exps,
+            _ => return,
+        };
+        for (lvalue, rhs_exp) in lvalues.iter().zip(rhs_exps) {
+            match (&lvalue.value, used_var(rhs_exp)) {
+                (LValue_::Var(lhs_var, _), Some(rhs_var)) if lhs_var == rhs_var => {
+                    let msg = format!("Variable '{}' is assigned to itself", lhs_var);
+                    diags.add(diag!(Lint::SelfAssignment, (lvalue.loc, msg)))
+                }
+                _ => (),
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Flags shifts by a constant amount that is not less than the bit width of the shifted value.
+//! Such shifts always abort
+
+use super::Lint;
+use crate::{
+    diag,
+    diagnostics::Diagnostics,
+    expansion::ast::Value_,
+    naming::ast::BuiltinTypeName_,
+    parser::ast::BinOp_,
+    typing::ast::{Exp, UnannotatedExp_},
+};
+
+pub struct ShiftOverflow;
+
+impl Lint for ShiftOverflow {
+    fn name(&self) -> &'static str {
+        "shift_overflow"
+    }
+
+    fn visit_typing_exp(&self, diags: &mut Diagnostics, e: &Exp) {
+        let (lhs, rhs) = match &e.exp.value {
+            UnannotatedExp_::BinopExp(lhs, sp!(_, BinOp_::Shl | BinOp_::Shr), _, rhs) => (lhs, rhs),
+            _ => return,
+        };
+        let amount = match &rhs.exp.value {
+            UnannotatedExp_::Value(sp!(_, Value_::U8(amount))) => *amount,
+            _ => return,
+        };
+        let sp!(_, bt_) = match lhs.ty.value.builtin_name() {
+            Some(bt) => bt,
+            None => return,
+        };
+        let bits = match bt_ {
+            BuiltinTypeName_::U8 => 8,
+            BuiltinTypeName_::U16 => 16,
+            BuiltinTypeName_::U32 => 32,
+            BuiltinTypeName_::U64 => 64,
+            BuiltinTypeName_::U128 => 128,
+            // every u8 shift amount is less than 256
+            _ => return,
+        };
+        if (amount as u16) >= bits {
+            let msg = format!(
+                "Shifting a '{}' by {} bits will always abort. \
+                The shift amount must be less than {}",
+                bt_, amount, bits
+            );
+            diags.add(diag!(Lint::ShiftOverflow, (rhs.exp.loc, msg)))
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Flags `while (true)`, which should be written as `loop`
+
+use super::Lint;
+use crate::{
+    diag,
+    diagnostics::Diagnostics,
+    expansion::ast::Value_,
+    typing::ast::{Exp, UnannotatedExp_},
+};
+
+pub struct WhileTrue;
+
+impl Lint for WhileTrue {
+    fn name(&self) -> &'static str {
+        "while_true"
+    }
+
+    fn visit_typing_exp(&self, diags: &mut Diagnostics, e: &Exp) {
+        let cond = match &e.exp.value {
+            UnannotatedExp_::While(cond, _) => cond,
+            _ => return,
+        };
+        if matches!(
+            &cond.exp.value,
+            UnannotatedExp_::Value(sp!(_, Value_::Bool(true)))
+        ) {
+            let msg = "Use 'loop' instead of 'while (true)'";
+            diags.add(diag!(Lint::WhileTrue, (cond.exp.loc, msg)))
+        }
+    }
+}
@@ -5,10 +5,11 @@
 use crate::{
     expansion::ast::{
         ability_constraints_ast_debug, ability_modifiers_ast_debug, AbilitySet, Attributes, Fields,
-        Friend, ModuleIdent, SpecId, Value, Value_, Visibility,
+        Friend, ModuleIdent, SpecId, Value, Value_, Variants, Visibility,
     },
     parser::ast::{
-        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, VariantName,
+        ENTRY_MODIFIER, INLINE_MODIFIER,
     },
     shared::{ast_debug::*, unique_map::UniqueMap, *},
 };
@@ -86,6 +87,7 @@ pub struct StructTypeParameter {
 pub enum StructFields {
     Defined(Fields<Type>),
     Native(Loc),
+    Variants(Variants<Type>),
 }
 
 //**************************************************************************************************
@@ -111,6 +113,7 @@ pub struct Function {
     pub attributes: Attributes,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: Option<Loc>,
     pub signature: FunctionSignature,
     pub acquires: BTreeMap<StructName, Loc>,
     pub body: FunctionBody,
@@ -187,6 +190,8 @@ pub enum Type_ {
     Ref(bool, Box<Type>),
     Param(TParam),
     Apply(Option<AbilitySet>, TypeName, Vec<Type>),
+    // |t1, ..., tn| tr, only for parameters of inline functions
+    Fun(Vec<Type>, Box<Type>),
     Var(TVar),
     Anything,
     UnresolvedError,
@@ -208,6 +213,22 @@ pub type LValue = Spanned<LValue_>;
 pub type LValueList_ = Vec<LValue>;
 pub type LValueList = Spanned<LValueList_>;
 
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum MatchPattern_ {
+    Variant(
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Option<Vec<Type>>,
+        Fields<LValue>,
+    ),
+    Binder(LValue),
+}
+pub type MatchPattern = Spanned<MatchPattern_>;
+pub type MatchArm_ = (MatchPattern, Exp);
+pub type MatchArm = Spanned<MatchArm_>;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ExpDotted_ {
     Exp(Box<Exp>),
@@ -242,13 +263,17 @@ pub enum Exp_ {
         Option<Vec<Type>>,
         Spanned<Vec<Exp>>,
     ),
+    // call of a local with a function type, i.e. a lambda parameter of an inline function
+    VarCall(Var, Spanned<Vec<Exp>>),
     Builtin(BuiltinFunction, Spanned<Vec<Exp>>),
     Vector(Loc, Option<Type>, Spanned<Vec<Exp>>),
 
     IfElse(Box<Exp>, Box<Exp>, Box<Exp>),
     While(Box<Exp>, Box<Exp>),
     Loop(Box<Exp>),
+    Match(Box<Exp>, Vec<MatchArm>),
     Block(Sequence),
+    Lambda(LValueList, Box<Exp>),
 
     Assign(LValueList, Box<Exp>),
     FieldMutate(ExpDotted, Box<Exp>),
@@ -264,6 +289,20 @@ pub enum Exp_ {
     BinopExp(Box<Exp>, BinOp, Box<Exp>),
 
     Pack(ModuleIdent, StructName, Option<Vec<Type>>, Fields<Exp>),
+    PackUpdate(
+        ModuleIdent,
+        StructName,
+        Option<Vec<Type>>,
+        Fields<Exp>,
+        Box<Exp>,
+    ),
+    PackVariant(
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Option<Vec<Type>>,
+        Fields<Exp>,
+    ),
     ExpList(Vec<Exp>),
     Unit {
         trailing: bool,
@@ -271,6 +310,8 @@ pub enum Exp_ {
 
     DerefBorrow(ExpDotted),
     Borrow(bool, ExpDotted),
+    // e.f(args), resolved during typing based on the type of `e`
+    MethodCall(ExpDotted, Name, Option<Vec<Type>>, Spanned<Vec<Exp>>),
 
     Cast(Box<Exp>, Type),
     Annotate(Box<Exp>, Type),
@@ -710,18 +751,38 @@ impl AstDebug for (StructName, &StructDefinition) {
         if let StructFields::Native(_) = fields {
             w.write("native ");
         }
-        w.write(&format!("struct {}", name));
+        let kind = match fields {
+            StructFields::Variants(_) => "enum",
+            StructFields::Defined(_) | StructFields::Native(_) => "struct",
+        };
+        w.write(&format!("{} {}", kind, name));
         type_parameters.ast_debug(w);
         ability_modifiers_ast_debug(w, abilities);
-        if let StructFields::Defined(fields) = fields {
-            w.block(|w| {
+        match fields {
+            StructFields::Defined(fields) => w.block(|w| {
                 w.list(fields, ",", |w, (_, f, idx_st)| {
                     let (idx, st) = idx_st;
                     w.write(&format!("{}#{}: ", idx, f));
                     st.ast_debug(w);
                     true
                 })
-            })
+            }),
+            StructFields::Variants(variants) => w.block(|w| {
+                w.list(variants, ",", |w, (_, v, idx_fields)| {
+                    let (idx, fields) = idx_fields;
+                    w.write(&format!("{}#{} ", idx, v));
+                    w.block(|w| {
+                        w.list(fields, ",", |w, (_, f, idx_st)| {
+                            let (idx, st) = idx_st;
+                            w.write(&format!("{}#{}: ", idx, f));
+                            st.ast_debug(w);
+                            true
+                        })
+                    });
+                    true
+                })
+            }),
+            StructFields::Native(_) => (),
         }
     }
 }
@@ -734,6 +795,7 @@ impl AstDebug for (FunctionName, &Function) {
                 attributes,
                 visibility,
                 entry,
+                inline,
                 signature,
                 acquires,
                 body,
@@ -744,6 +806,9 @@ impl AstDebug for (FunctionName, &Function) {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if inline.is_some() {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -904,6 +969,12 @@ impl AstDebug for Type_ {
                     }),
                 }
             }
+            Type_::Fun(args, result) => {
+                w.write("(");
+                w.comma(args, |w, ty| ty.ast_debug(w));
+                w.write("):");
+                result.ast_debug(w);
+            }
             Type_::Var(tv) => w.write(&format!("#{}", tv.0)),
             Type_::Anything => w.write("_"),
             Type_::UnresolvedError => w.write("_|_"),
@@ -970,6 +1041,11 @@ impl AstDebug for Exp_ {
                 w.comma(rhs, |w, e| e.ast_debug(w));
                 w.write(")");
             }
+            E::VarCall(v, sp!(_, rhs)) => {
+                w.write(&format!("{}(", v));
+                w.comma(rhs, |w, e| e.ast_debug(w));
+                w.write(")");
+            }
             E::Builtin(bf, sp!(_, rhs)) => {
                 bf.ast_debug(w);
                 w.write("(");
@@ -1002,6 +1078,41 @@ impl AstDebug for Exp_ {
                 });
                 w.write("}");
             }
+            E::PackUpdate(m, s, tys_opt, fields, base) => {
+                w.write(&format!("{}::{}", m, s));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_e)| {
+                    let (idx, e) = idx_e;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    e.ast_debug(w);
+                });
+                if !fields.is_empty() {
+                    w.write(", ");
+                }
+                w.write("..");
+                base.ast_debug(w);
+                w.write("}");
+            }
+            E::PackVariant(m, s, v, tys_opt, fields) => {
+                w.write(&format!("{}::{}::{}", m, s, v));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_e)| {
+                    let (idx, e) = idx_e;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    e.ast_debug(w);
+                });
+                w.write("}");
+            }
             E::IfElse(b, t, f) => {
                 w.write("if (");
                 b.ast_debug(w);
@@ -1020,7 +1131,25 @@ impl AstDebug for Exp_ {
                 w.write("loop ");
                 e.ast_debug(w);
             }
+            E::Match(e, arms) => {
+                w.write("match (");
+                e.ast_debug(w);
+                w.write(") ");
+                w.block(|w| {
+                    w.comma(arms, |w, sp!(_, (pat, arm))| {
+                        pat.ast_debug(w);
+                        w.write(" => ");
+                        arm.ast_debug(w);
+                    })
+                })
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
+            E::Lambda(sp!(_, bs), e) => {
+                w.write("fun ");
+                bs.ast_debug(w);
+                w.write(" ");
+                e.ast_debug(w);
+            }
             E::ExpList(es) => {
                 w.write("(");
                 w.comma(es, |w, e| e.ast_debug(w));
@@ -1081,6 +1210,18 @@ impl AstDebug for Exp_ {
                 w.write("(&*)");
                 ed.ast_debug(w)
             }
+            E::MethodCall(ed, n, tys_opt, sp!(_, rhs)) => {
+                ed.ast_debug(w);
+                w.write(&format!(".{}", n));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("(");
+                w.comma(rhs, |w, e| e.ast_debug(w));
+                w.write(")");
+            }
             E::Cast(e, ty) => {
                 w.write("(");
                 e.ast_debug(w);
@@ -1179,3 +1320,26 @@ impl AstDebug for LValue_ {
         }
     }
 }
+
+impl AstDebug for MatchPattern_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        match self {
+            MatchPattern_::Variant(m, s, v, tys_opt, fields) => {
+                w.write(&format!("{}::{}::{}", m, s, v));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_b)| {
+                    let (idx, b) = idx_b;
+                    w.write(&format!("{}#{}: ", idx, f));
+                    b.ast_debug(w);
+                });
+                w.write("}");
+            }
+            MatchPattern_::Binder(b) => b.ast_debug(w),
+        }
+    }
+}
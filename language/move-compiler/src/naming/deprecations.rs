@@ -0,0 +1,199 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module tracks the items marked with `#[deprecated]`. Uses of those items from other
+//! modules are reported as warnings during naming, and the compiled module keeps a record of
+//! them in its metadata (see `compiled_unit::DeprecationMetadata`)
+
+use crate::{
+    diag,
+    expansion::ast::{self as E, AttributeName_, AttributeValue_, Attribute_, ModuleIdent},
+    shared::{
+        known_attributes::{DeprecationAttribute, KnownAttribute},
+        CompilationEnv, Identifier,
+    },
+    FullyCompiledProgram,
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use std::{collections::BTreeMap, fmt};
+
+pub const DEPRECATED_ATTR: AttributeName_ = AttributeName_::Known(KnownAttribute::Deprecation(
+    DeprecationAttribute::Deprecated,
+));
+
+#[derive(Debug, Clone)]
+pub struct Deprecation {
+    // The location of the attribute
+    pub loc: Loc,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemberKind {
+    Struct,
+    Function,
+    Constant,
+}
+
+pub struct Deprecations {
+    modules: BTreeMap<ModuleIdent, Deprecation>,
+    members: BTreeMap<(ModuleIdent, MemberKind, Symbol), Deprecation>,
+}
+
+impl fmt::Display for MemberKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemberKind::Struct => write!(f, "struct"),
+            MemberKind::Function => write!(f, "function"),
+            MemberKind::Constant => write!(f, "constant"),
+        }
+    }
+}
+
+impl Deprecations {
+    /// Collects the deprecated items of the program and of the pre-compiled library. Malformed
+    /// `#[deprecated]` attributes are reported for the modules of the program
+    pub fn new(
+        env: &mut CompilationEnv,
+        pre_compiled_lib: Option<&FullyCompiledProgram>,
+        prog: &E::Program,
+    ) -> Self {
+        let mut deprecations = Self {
+            modules: BTreeMap::new(),
+            members: BTreeMap::new(),
+        };
+        for (mident, mdef) in prog.modules.key_cloned_iter() {
+            deprecations.add_module(Some(&mut *env), mident, mdef)
+        }
+        let pre_compiled_modules = pre_compiled_lib
+            .iter()
+            .flat_map(|pre_compiled| pre_compiled.expansion.modules.key_cloned_iter())
+            .filter(|(mident, _)| !prog.modules.contains_key(mident));
+        for (mident, mdef) in pre_compiled_modules {
+            deprecations.add_module(None, mident, mdef)
+        }
+        deprecations
+    }
+
+    fn add_module(
+        &mut self,
+        mut env: Option<&mut CompilationEnv>,
+        mident: ModuleIdent,
+        mdef: &E::ModuleDefinition,
+    ) {
+        let mut deprecation = |attributes: &E::Attributes| match deprecation(attributes) {
+            Ok(deprecation_opt) => deprecation_opt,
+            Err(invalid) => {
+                if let Some(env) = env.as_mut() {
+                    env.add_diag(diag!(Attributes::InvalidValue, invalid))
+                }
+                None
+            }
+        };
+        if let Some(d) = deprecation(&mdef.attributes) {
+            self.modules.insert(mident, d);
+        }
+        let members = mdef
+            .structs
+            .key_cloned_iter()
+            .map(|(s, sdef)| (MemberKind::Struct, s.value(), &sdef.attributes))
+            .chain(
+                mdef.functions
+                    .key_cloned_iter()
+                    .map(|(f, fdef)| (MemberKind::Function, f.value(), &fdef.attributes)),
+            )
+            .chain(
+                mdef.constants
+                    .key_cloned_iter()
+                    .map(|(c, cdef)| (MemberKind::Constant, c.value(), &cdef.attributes)),
+            );
+        for (kind, name, attributes) in members {
+            if let Some(d) = deprecation(attributes) {
+                self.members.insert((mident, kind, name), d);
+            }
+        }
+    }
+
+    /// Reports a use of the member `m::n` if it, or its module, is deprecated and the use is
+    /// not from within the module `m` itself
+    pub fn check_use(
+        &self,
+        env: &mut CompilationEnv,
+        current_module: Option<ModuleIdent>,
+        loc: Loc,
+        m: &ModuleIdent,
+        kind: MemberKind,
+        n: Symbol,
+    ) {
+        if current_module.as_ref() == Some(m) {
+            return;
+        }
+        let (msg, deprecation) = match (self.members.get(&(*m, kind, n)), self.modules.get(m)) {
+            (Some(d), _) => (format!("Use of deprecated {} '{}::{}'", kind, m, n), d),
+            (None, Some(d)) => (
+                format!(
+                    "Use of {} '{}::{}' from deprecated module '{}'",
+                    kind, m, n, m
+                ),
+                d,
+            ),
+            (None, None) => return,
+        };
+        let mut diag = diag!(
+            NameResolution::DeprecatedUsage,
+            (loc, msg),
+            (deprecation.loc, "Marked as deprecated here"),
+        );
+        if let Some(note) = &deprecation.note {
+            diag.add_note(note);
+        }
+        env.add_diag(diag)
+    }
+}
+
+/// The deprecation of an item, if it is marked as `#[deprecated]` or
+/// `#[deprecated(note = b"<note>")]`. Returns the location and message of the error for a
+/// malformed attribute
+pub fn deprecation(attributes: &E::Attributes) -> Result<Option<Deprecation>, (Loc, String)> {
+    let sp!(loc, attr_) = match attributes.get_(&DEPRECATED_ATTR) {
+        None => return Ok(None),
+        Some(attr) => attr,
+    };
+    let loc = *loc;
+    let invalid = |loc| {
+        let msg = format!(
+            "Expected '#[{0}]' or '#[{0}({1} = b\"<note>\")]'",
+            DeprecationAttribute::DEPRECATED,
+            DeprecationAttribute::NOTE,
+        );
+        (loc, msg)
+    };
+    let params = match attr_ {
+        Attribute_::Name(_) => return Ok(Some(Deprecation { loc, note: None })),
+        Attribute_::Assigned(_, _) => return Err(invalid(loc)),
+        Attribute_::Parameterized(_, params) => params,
+    };
+    let mut note = None;
+    for (sp!(nloc, name_), sp!(_, param_)) in params.key_cloned_iter() {
+        let bytes = match (name_, param_) {
+            (AttributeName_::Unknown(name), Attribute_::Assigned(_, value))
+                if name.as_str() == DeprecationAttribute::NOTE =>
+            {
+                match &value.value {
+                    AttributeValue_::Value(sp!(_, E::Value_::Bytearray(bytes))) => bytes,
+                    _ => return Err(invalid(value.loc)),
+                }
+            }
+            _ => return Err(invalid(nloc)),
+        };
+        match std::str::from_utf8(bytes) {
+            Ok(s) => note = Some(s.to_owned()),
+            Err(_) => {
+                let msg = "Invalid deprecation note. Expected a UTF-8 encoded byte string";
+                return Err((nloc, msg.to_owned()));
+            }
+        }
+    }
+    Ok(Some(Deprecation { loc, note }))
+}
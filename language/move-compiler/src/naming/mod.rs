@@ -3,5 +3,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod ast;
+pub(crate) mod deprecations;
 pub(crate) mod fake_natives;
 pub(crate) mod translate;
@@ -18,7 +18,10 @@ use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use std::collections::BTreeMap;
 
-use super::fake_natives;
+use super::{
+    deprecations::{Deprecations, MemberKind},
+    fake_natives,
+};
 
 //**************************************************************************************************
 // Context
@@ -50,6 +53,7 @@ struct Context<'env> {
     scoped_functions: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
     unscoped_constants: BTreeMap<Symbol, Loc>,
     scoped_constants: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
+    deprecations: Deprecations,
 }
 
 impl<'env> Context<'env> {
@@ -105,6 +109,7 @@ impl<'env> Context<'env> {
                 (mident, mems)
             })
             .collect();
+        let deprecations = Deprecations::new(compilation_env, pre_compiled_lib, prog);
         let unscoped_types = N::BuiltinTypeName_::all_names()
             .iter()
             .map(|s| (*s, RT::BuiltinType))
@@ -117,9 +122,15 @@ impl<'env> Context<'env> {
             scoped_constants,
             unscoped_types,
             unscoped_constants: BTreeMap::new(),
+            deprecations,
         }
     }
 
+    fn check_deprecated_use(&mut self, loc: Loc, m: &ModuleIdent, kind: MemberKind, n: &Name) {
+        self.deprecations
+            .check_use(self.env, self.current_module, loc, m, kind, n.value)
+    }
+
     fn resolve_module(&mut self, m: &ModuleIdent) -> bool {
         // NOTE: piggybacking on `scoped_functions` to provide a set of modules in the context。
         // TODO: a better solution would be to have a single `BTreeMap<ModuleIdent, ModuleInfo>`
@@ -134,6 +145,19 @@ impl<'env> Context<'env> {
         resolved
     }
 
+    // A call of a struct name, rather than a function name, is a positional pack
+    fn is_positional_pack(&self, m: &ModuleIdent, n: &Name) -> bool {
+        let is_type = matches!(
+            self.scoped_types.get(m),
+            Some(types) if types.contains_key(&n.value)
+        );
+        let is_function = matches!(
+            self.scoped_functions.get(m),
+            Some(functions) if functions.contains_key(&n.value)
+        );
+        is_type && !is_function
+    }
+
     fn resolve_module_type(
         &mut self,
         loc: Loc,
@@ -161,7 +185,9 @@ impl<'env> Context<'env> {
                 None
             }
             Some((decl_loc, _, abilities, arity)) => {
-                Some((*decl_loc, StructName(*n), abilities.clone(), *arity))
+                let resolved = (*decl_loc, StructName(*n), abilities.clone(), *arity);
+                self.check_deprecated_use(loc, m, MemberKind::Struct, n);
+                Some(resolved)
             }
         }
     }
@@ -192,7 +218,10 @@ impl<'env> Context<'env> {
                     .add_diag(diag!(NameResolution::UnboundModuleMember, (loc, msg)));
                 None
             }
-            Some(_) => Some(FunctionName(*n)),
+            Some(_) => {
+                self.check_deprecated_use(loc, m, MemberKind::Function, n);
+                Some(FunctionName(*n))
+            }
         }
     }
 
@@ -222,7 +251,10 @@ impl<'env> Context<'env> {
                     .add_diag(diag!(NameResolution::UnboundModuleMember, (loc, msg)));
                 None
             }
-            Some(_) => Some(ConstantName(n)),
+            Some(_) => {
+                self.check_deprecated_use(loc, m, MemberKind::Constant, &n);
+                Some(ConstantName(n))
+            }
         }
     }
 
@@ -490,6 +522,7 @@ fn function(
         loc: _,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -502,6 +535,7 @@ fn function(
         attributes,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -656,6 +690,9 @@ fn struct_fields(context: &mut Context, efields: E::StructFields) -> N::StructFi
         E::StructFields::Defined(em) => {
             N::StructFields::Defined(em.map(|_f, (idx, t)| (idx, type_(context, t))))
         }
+        E::StructFields::Variants(ev) => N::StructFields::Variants(
+            ev.map(|_v, (idx, em)| (idx, em.map(|_f, (idx, t)| (idx, type_(context, t))))),
+        ),
     }
 }
 
@@ -794,7 +831,11 @@ fn type_(context: &mut Context, sp!(loc, ety_): E::Type) -> N::Type {
                 }
             }
         }
-        ET::Fun(_, _) => panic!("ICE only allowed in spec context"),
+        ET::Fun(args, result) => {
+            let args = types(context, args);
+            let result = type_(context, *result);
+            NT::Fun(args, Box::new(result))
+        }
     };
     sp(loc, ty_)
 }
@@ -908,7 +949,9 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
         }
         EE::While(eb, el) => NE::While(exp(context, *eb), exp(context, *el)),
         EE::Loop(el) => NE::Loop(exp(context, *el)),
+        EE::Match(esubject, earms) => match_(context, *esubject, earms),
         EE::Block(seq) => NE::Block(sequence(context, seq)),
+        EE::Lambda(ebs, ebody) => lambda(context, ebs, *ebody),
 
         EE::Assign(a, e) => {
             let na_opt = assign_list(context, a);
@@ -961,6 +1004,24 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                 ),
             }
         }
+        EE::PackUpdate(tn, etys_opt, efields, ebase) => {
+            pack_update(context, eloc, tn, etys_opt, efields, ebase)
+        }
+        EE::PackVariant(tn, v, etys_opt, efields) => {
+            match context.resolve_struct_name(eloc, "variant construction", tn, etys_opt) {
+                None => {
+                    assert!(context.env.has_errors());
+                    NE::UnresolvedError
+                }
+                Some((m, sn, tys_opt)) => NE::PackVariant(
+                    m,
+                    sn,
+                    v,
+                    tys_opt,
+                    efields.map(|_, (idx, e)| (idx, exp_(context, e))),
+                ),
+            }
+        }
         EE::ExpList(es) => {
             assert!(es.len() > 1);
             NE::ExpList(exps(context, es))
@@ -987,6 +1048,7 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
             }
             Some(d) => NE::DerefBorrow(d),
         },
+        EE::MethodCall(edot, n, tys_opt, rhs) => method_call(context, *edot, n, tys_opt, rhs),
 
         EE::Cast(e, t) => NE::Cast(exp(context, *e), type_(context, t)),
         EE::Annotate(e, t) => NE::Annotate(exp(context, *e), type_(context, t)),
@@ -1009,37 +1071,7 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                 }
             }
         }
-        EE::Call(sp!(mloc, ma_), false, tys_opt, rhs) => {
-            use E::ModuleAccess_ as EA;
-            let ty_args = tys_opt.map(|tys| types(context, tys));
-            let nes = call_args(context, rhs);
-            match ma_ {
-                EA::Name(n) if N::BuiltinFunction_::all_names().contains(&n.value) => {
-                    match resolve_builtin_function(context, eloc, &n, ty_args) {
-                        None => {
-                            assert!(context.env.has_errors());
-                            NE::UnresolvedError
-                        }
-                        Some(f) => NE::Builtin(sp(mloc, f), nes),
-                    }
-                }
-
-                EA::Name(n) => {
-                    context.env.add_diag(diag!(
-                        NameResolution::UnboundUnscopedName,
-                        (n.loc, format!("Unbound function '{}' in current scope", n)),
-                    ));
-                    NE::UnresolvedError
-                }
-                EA::ModuleAccess(m, n) => match context.resolve_module_function(mloc, &m, &n) {
-                    None => {
-                        assert!(context.env.has_errors());
-                        NE::UnresolvedError
-                    }
-                    Some(_) => NE::ModuleCall(m, FunctionName(n), ty_args, nes),
-                },
-            }
-        }
+        EE::Call(ma, false, tys_opt, rhs) => call(context, eloc, ma, tys_opt, rhs),
         EE::Vector(vec_loc, tys_opt, rhs) => {
             let ty_args = tys_opt.map(|tys| types(context, tys));
             let nes = call_args(context, rhs);
@@ -1068,7 +1100,7 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
             NE::UnresolvedError
         }
         // `Name` matches name variants only allowed in specs (we handle the allowed ones above)
-        EE::Index(..) | EE::Lambda(..) | EE::Quant(..) | EE::Name(_, Some(_)) => {
+        EE::Index(..) | EE::Quant(..) | EE::Name(_, Some(_)) => {
             panic!("ICE unexpected specification construct")
         }
     };
@@ -1085,6 +1117,119 @@ fn access_constant(context: &mut Context, ma: E::ModuleAccess) -> N::Exp_ {
     }
 }
 
+fn pack_update(
+    context: &mut Context,
+    eloc: Loc,
+    tn: E::ModuleAccess,
+    etys_opt: Option<Vec<E::Type>>,
+    efields: E::Fields<E::Exp>,
+    ebase: Box<E::Exp>,
+) -> N::Exp_ {
+    match context.resolve_struct_name(eloc, "construction", tn, etys_opt) {
+        None => {
+            assert!(context.env.has_errors());
+            N::Exp_::UnresolvedError
+        }
+        Some((m, sn, tys_opt)) => N::Exp_::PackUpdate(
+            m,
+            sn,
+            tys_opt,
+            efields.map(|_, (idx, e)| (idx, exp_(context, e))),
+            exp(context, *ebase),
+        ),
+    }
+}
+
+fn call(
+    context: &mut Context,
+    eloc: Loc,
+    sp!(mloc, ma_): E::ModuleAccess,
+    tys_opt: Option<Vec<E::Type>>,
+    rhs: Spanned<Vec<E::Exp>>,
+) -> N::Exp_ {
+    use E::ModuleAccess_ as EA;
+    let ty_args = tys_opt.map(|tys| types(context, tys));
+    let nes = call_args(context, rhs);
+    match ma_ {
+        EA::Name(n) if N::BuiltinFunction_::all_names().contains(&n.value) => {
+            match resolve_builtin_function(context, eloc, &n, ty_args) {
+                None => {
+                    assert!(context.env.has_errors());
+                    N::Exp_::UnresolvedError
+                }
+                Some(f) => N::Exp_::Builtin(sp(mloc, f), nes),
+            }
+        }
+
+        // Possibly a call of a lambda parameter, resolved during typing
+        EA::Name(n) if ty_args.is_none() => N::Exp_::VarCall(Var(n), nes),
+        EA::Name(n) => {
+            context.env.add_diag(diag!(
+                NameResolution::UnboundUnscopedName,
+                (n.loc, format!("Unbound function '{}' in current scope", n)),
+            ));
+            N::Exp_::UnresolvedError
+        }
+        EA::ModuleAccess(m, n) if context.is_positional_pack(&m, &n) => {
+            positional_pack(context, eloc, m, n, ty_args, nes)
+        }
+        EA::ModuleAccess(m, n) => match context.resolve_module_function(mloc, &m, &n) {
+            None => {
+                assert!(context.env.has_errors());
+                N::Exp_::UnresolvedError
+            }
+            Some(_) => N::Exp_::ModuleCall(m, FunctionName(n), ty_args, nes),
+        },
+    }
+}
+
+// `S(e0, e1)` packs the positional struct `S`, with its fields named `0`, `1`, ...
+fn positional_pack(
+    context: &mut Context,
+    eloc: Loc,
+    m: ModuleIdent,
+    n: Name,
+    ty_args: Option<Vec<N::Type>>,
+    sp!(_, args): Spanned<Vec<N::Exp>>,
+) -> N::Exp_ {
+    match context.resolve_module_type(eloc, &m, &n) {
+        None => {
+            assert!(context.env.has_errors());
+            N::Exp_::UnresolvedError
+        }
+        Some((_, sn, _, arity)) => {
+            let tys_opt = ty_args.map(|tys| {
+                let name_f = || format!("{}::{}", &m, &n);
+                check_type_argument_arity(context, eloc, name_f, tys, arity)
+            });
+            let fields = args
+                .into_iter()
+                .enumerate()
+                .map(|(idx, e)| (Field::positional(e.loc, idx), (idx, e)));
+            let fields = UniqueMap::maybe_from_iter(fields).unwrap();
+            N::Exp_::Pack(m, sn, tys_opt, fields)
+        }
+    }
+}
+
+fn method_call(
+    context: &mut Context,
+    edot: E::ExpDotted,
+    n: Name,
+    tys_opt: Option<Vec<E::Type>>,
+    sp!(rloc, rhs): Spanned<Vec<E::Exp>>,
+) -> N::Exp_ {
+    let ty_args = tys_opt.map(|tys| types(context, tys));
+    let nes = sp(rloc, exps(context, rhs));
+    match dotted(context, edot) {
+        None => {
+            assert!(context.env.has_errors());
+            N::Exp_::UnresolvedError
+        }
+        Some(d) => N::Exp_::MethodCall(d, n, ty_args, nes),
+    }
+}
+
 fn dotted(context: &mut Context, edot: E::ExpDotted) -> Option<N::ExpDotted> {
     let sp!(loc, edot_) = edot;
     let nedot_ = match edot_ {
@@ -1142,6 +1287,65 @@ fn lvalue(context: &mut Context, case: LValueCase, sp!(loc, l_): E::LValue) -> O
     Some(sp(loc, nl_))
 }
 
+fn lambda(context: &mut Context, ebs: E::LValueList, ebody: E::Exp) -> N::Exp_ {
+    let nbs_opt = bind_list(context, ebs);
+    let nbody = exp(context, ebody);
+    match nbs_opt {
+        None => {
+            assert!(context.env.has_errors());
+            N::Exp_::UnresolvedError
+        }
+        Some(nbs) => N::Exp_::Lambda(nbs, nbody),
+    }
+}
+
+fn match_(context: &mut Context, esubject: E::Exp, earms: Vec<E::MatchArm>) -> N::Exp_ {
+    use N::Exp_ as NE;
+    let nsubject = exp(context, esubject);
+    let narms_opt: Option<Vec<N::MatchArm>> = earms
+        .into_iter()
+        .map(|sp!(aloc, (epat, earm))| {
+            let npat_opt = match_pattern(context, epat);
+            let narm = exp_(context, earm);
+            Some(sp(aloc, (npat_opt?, narm)))
+        })
+        .collect();
+    match narms_opt {
+        None => {
+            assert!(context.env.has_errors());
+            NE::UnresolvedError
+        }
+        Some(narms) => NE::Match(nsubject, narms),
+    }
+}
+
+fn match_pattern(
+    context: &mut Context,
+    sp!(loc, pat_): E::MatchPattern,
+) -> Option<N::MatchPattern> {
+    use E::MatchPattern_ as EP;
+    use N::MatchPattern_ as NP;
+    let npat_ = match pat_ {
+        EP::Variant(tn, v, etys_opt, efields) => {
+            let (m, sn, tys_opt) =
+                context.resolve_struct_name(loc, "match pattern", tn, etys_opt)?;
+            let nfields =
+                UniqueMap::maybe_from_opt_iter(efields.into_iter().map(|(k, (idx, inner))| {
+                    Some((k, (idx, lvalue(context, LValueCase::Bind, inner)?)))
+                }))?;
+            NP::Variant(
+                m,
+                sn,
+                v,
+                tys_opt,
+                nfields.expect("ICE fields were already unique"),
+            )
+        }
+        EP::Binder(l) => NP::Binder(lvalue(context, LValueCase::Bind, l)?),
+    };
+    Some(sp(loc, npat_))
+}
+
 fn bind_list(context: &mut Context, ls: E::LValueList) -> Option<N::LValueList> {
     lvalue_list(context, LValueCase::Bind, ls)
 }
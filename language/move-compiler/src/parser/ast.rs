@@ -198,9 +198,12 @@ pub struct FriendDecl {
 
 new_name!(Field);
 new_name!(StructName);
+new_name!(VariantName);
 
 pub type ResourceLoc = Option<Loc>;
 
+pub const ENUM_IDENT: &str = "enum";
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct StructTypeParameter {
     pub is_phantom: bool,
@@ -220,8 +223,11 @@ pub struct StructDefinition {
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum StructFields {
+    // struct S { f1: t1, ... } or, for positional fields, struct S(t1, ...)
     Defined(Vec<(Field, Type)>),
     Native(Loc),
+    // enum N { V1 { f1: t1, ... }, ..., Vn }
+    Variants(Vec<(VariantName, Vec<(Field, Type)>)>),
 }
 
 //**************************************************************************************************
@@ -232,6 +238,7 @@ new_name!(FunctionName);
 
 pub const NATIVE_MODIFIER: &str = "native";
 pub const ENTRY_MODIFIER: &str = "entry";
+pub const INLINE_MODIFIER: &str = "inline";
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct FunctionSignature {
@@ -265,6 +272,7 @@ pub struct Function {
     pub loc: Loc,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: Option<Loc>,
     pub signature: FunctionSignature,
     pub acquires: Vec<NameAccessChain>,
     pub name: FunctionName,
@@ -459,6 +467,8 @@ pub type Type = Spanned<Type_>;
 
 new_name!(Var);
 
+pub const MATCH_IDENT: &str = "match";
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Bind_ {
     // x
@@ -574,6 +584,13 @@ pub enum Exp_ {
 
     // tn {f1: e1, ... , f_n: e_n }
     Pack(NameAccessChain, Option<Vec<Type>>, Vec<(Field, Exp)>),
+    // tn {f1: e1, ... , f_n: e_n, ..ebase }
+    PackUpdate(
+        NameAccessChain,
+        Option<Vec<Type>>,
+        Vec<(Field, Exp)>,
+        Box<Exp>,
+    ),
 
     // vector [ e1, ..., e_n ]
     // vector<t> [e1, ..., en ]
@@ -589,11 +606,13 @@ pub enum Exp_ {
     While(Box<Exp>, Box<Exp>),
     // loop eloop
     Loop(Box<Exp>),
+    // match (e) { b1 => e1, ..., bn => en }
+    Match(Box<Exp>, Vec<MatchArm>),
 
     // { seq }
     Block(Sequence),
     // fun (x1, ..., xn) e
-    Lambda(BindList, Box<Exp>), // spec only
+    Lambda(BindList, Box<Exp>),
     // forall/exists x1 : e1, ..., xn [{ t1, .., tk } *] [where cond]: en.
     Quant(
         QuantKind,
@@ -632,6 +651,8 @@ pub enum Exp_ {
 
     // e.f
     Dot(Box<Exp>, Name),
+    // e.f<t1, ... tn>(e1, ..., em)
+    DotCall(Box<Exp>, Name, Option<Vec<Type>>, Spanned<Vec<Exp>>),
     // e[e']
     Index(Box<Exp>, Box<Exp>), // spec only
 
@@ -649,6 +670,9 @@ pub enum Exp_ {
 }
 pub type Exp = Spanned<Exp_>;
 
+// b => e
+pub type MatchArm = Spanned<(Bind, Exp)>;
+
 // { e1; ... ; en }
 // { e1; ... ; en; }
 // The Loc field holds the source location of the final semicolon, if there is one.
@@ -741,6 +765,17 @@ impl ModuleName {
     pub const SELF_NAME: &'static str = "Self";
 }
 
+impl Field {
+    /// Fields of positional structs, e.g. `struct S(u64, bool)`, are named by their index
+    pub fn positional(loc: Loc, idx: usize) -> Self {
+        Field(sp(loc, format!("{}", idx).into()))
+    }
+
+    pub fn is_positional(&self) -> bool {
+        self.0.value.starts_with(|c: char| c.is_ascii_digit())
+    }
+}
+
 impl Var {
     pub fn is_underscore(&self) -> bool {
         self.0.value.as_str() == "_"
@@ -1232,15 +1267,39 @@ impl AstDebug for StructDefinition {
             w.write("native ");
         }
 
-        w.write(&format!("struct {}", name));
-        type_parameters.ast_debug(w);
-        if let StructFields::Defined(fields) = fields {
-            w.block(|w| {
-                w.semicolon(fields, |w, (f, st)| {
-                    w.write(&format!("{}: ", f));
-                    st.ast_debug(w);
-                });
-            })
+        match fields {
+            StructFields::Variants(variants) => {
+                w.write(&format!("enum {}", name));
+                type_parameters.ast_debug(w);
+                w.block(|w| {
+                    w.comma(variants, |w, (v, fields)| {
+                        w.write(&format!("{}", v));
+                        if !fields.is_empty() {
+                            w.write(" ");
+                            w.block(|w| {
+                                w.comma(fields, |w, (f, st)| {
+                                    w.write(&format!("{}: ", f));
+                                    st.ast_debug(w);
+                                });
+                            })
+                        }
+                    });
+                })
+            }
+            StructFields::Defined(fields) => {
+                w.write(&format!("struct {}", name));
+                type_parameters.ast_debug(w);
+                w.block(|w| {
+                    w.semicolon(fields, |w, (f, st)| {
+                        w.write(&format!("{}: ", f));
+                        st.ast_debug(w);
+                    });
+                })
+            }
+            StructFields::Native(_) => {
+                w.write(&format!("struct {}", name));
+                type_parameters.ast_debug(w);
+            }
         }
     }
 }
@@ -1462,6 +1521,7 @@ impl AstDebug for Function {
             loc: _loc,
             visibility,
             entry,
+            inline,
             signature,
             acquires,
             name,
@@ -1472,6 +1532,9 @@ impl AstDebug for Function {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if inline.is_some() {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -1730,6 +1793,25 @@ impl AstDebug for Exp_ {
                 });
                 w.write("}");
             }
+            E::PackUpdate(ma, tys_opt, fields, base) => {
+                ma.ast_debug(w);
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("{");
+                w.comma(fields, |w, (f, e)| {
+                    w.write(&format!("{}: ", f));
+                    e.ast_debug(w);
+                });
+                if !fields.is_empty() {
+                    w.write(", ");
+                }
+                w.write("..");
+                base.ast_debug(w);
+                w.write("}");
+            }
             E::Vector(_loc, tys_opt, sp!(_, elems)) => {
                 w.write("vector");
                 if let Some(ss) = tys_opt {
@@ -1761,6 +1843,18 @@ impl AstDebug for Exp_ {
                 w.write("loop ");
                 e.ast_debug(w);
             }
+            E::Match(e, arms) => {
+                w.write("match (");
+                e.ast_debug(w);
+                w.write(") ");
+                w.block(|w| {
+                    w.comma(arms, |w, sp!(_, (b, arm))| {
+                        b.ast_debug(w);
+                        w.write(" => ");
+                        arm.ast_debug(w);
+                    })
+                })
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
             E::Lambda(sp!(_, bs), e) => {
                 w.write("fun ");
@@ -1830,6 +1924,18 @@ impl AstDebug for Exp_ {
                 e.ast_debug(w);
                 w.write(&format!(".{}", n));
             }
+            E::DotCall(e, n, tys_opt, sp!(_, rhs)) => {
+                e.ast_debug(w);
+                w.write(&format!(".{}", n));
+                if let Some(ss) = tys_opt {
+                    w.write("<");
+                    ss.ast_debug(w);
+                    w.write(">");
+                }
+                w.write("(");
+                w.comma(rhs, |w, e| e.ast_debug(w));
+                w.write(")");
+            }
             E::Cast(e, ty) => {
                 w.write("(");
                 e.ast_debug(w);
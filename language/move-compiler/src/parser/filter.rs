@@ -2,12 +2,20 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use move_ir_types::location::sp;
+use move_ir_types::location::{sp, Loc};
+use move_symbol_pool::Symbol;
 
-use crate::parser::ast as P;
+use crate::{
+    diag,
+    parser::ast as P,
+    shared::{known_attributes::CfgAttribute, CompilationEnv},
+};
 
 /// A trait that decides whether to include a parsed element in the compilation
 pub trait FilterContext {
+    /// Called before filtering the definitions of each package
+    fn enter_package(&mut self, _package: Option<Symbol>) {}
+
     /// Attribute-based node removal
     fn should_remove_by_attributes(
         &mut self,
@@ -138,6 +146,7 @@ pub fn filter_program<T: FilterContext>(context: &mut T, prog: P::Program) -> P:
                  named_address_map,
                  def,
              }| {
+                context.enter_package(package);
                 Some(P::PackageDefinition {
                     package,
                     named_address_map,
@@ -155,6 +164,7 @@ pub fn filter_program<T: FilterContext>(context: &mut T, prog: P::Program) -> P:
                  named_address_map,
                  def,
              }| {
+                context.enter_package(package);
                 Some(P::PackageDefinition {
                     package,
                     named_address_map,
@@ -320,3 +330,198 @@ fn filter_module_member<T: FilterContext>(
             .map(PM::Constant),
     }
 }
+
+//***************************************************************************
+// Filtering of conditionally compiled module members
+//***************************************************************************
+
+struct CfgContext<'env> {
+    env: &'env mut CompilationEnv,
+    package: Option<Symbol>,
+}
+
+impl FilterContext for CfgContext<'_> {
+    fn enter_package(&mut self, package: Option<Symbol>) {
+        self.package = package;
+    }
+
+    fn should_remove_by_attributes(
+        &mut self,
+        attrs: &[P::Attributes],
+        _is_source_def: bool,
+    ) -> bool {
+        let mut holds = true;
+        for attr in attrs.iter().flat_map(|attrs| &attrs.value) {
+            if attr.value.attribute_name().value.as_str() != CfgAttribute::CFG {
+                continue;
+            }
+            match &attr.value {
+                P::Attribute_::Parameterized(_, sp!(_, predicates)) if predicates.len() == 1 => {
+                    // Keep the node if its predicate is invalid: the error has been reported
+                    holds &= self.eval_predicate(&predicates[0]).unwrap_or(true);
+                }
+                _ => self.invalid_predicate(
+                    attr.loc,
+                    "Expected a single predicate, e.g. '#[cfg(feature = \"name\")]'",
+                ),
+            }
+        }
+        !holds
+    }
+
+    fn filter_map_address(
+        &mut self,
+        mut address_def: P::AddressDefinition,
+        is_source_def: bool,
+    ) -> Option<P::AddressDefinition> {
+        self.keep(&mut address_def.attributes, is_source_def)
+            .then_some(address_def)
+    }
+
+    fn filter_map_module(
+        &mut self,
+        mut module_def: P::ModuleDefinition,
+        is_source_def: bool,
+    ) -> Option<P::ModuleDefinition> {
+        self.keep(&mut module_def.attributes, is_source_def)
+            .then_some(module_def)
+    }
+
+    fn filter_map_script(
+        &mut self,
+        mut script_def: P::Script,
+        is_source_def: bool,
+    ) -> Option<P::Script> {
+        self.keep(&mut script_def.attributes, is_source_def)
+            .then_some(script_def)
+    }
+
+    fn filter_map_function(
+        &mut self,
+        mut function_def: P::Function,
+        is_source_def: bool,
+    ) -> Option<P::Function> {
+        self.keep(&mut function_def.attributes, is_source_def)
+            .then_some(function_def)
+    }
+
+    fn filter_map_struct(
+        &mut self,
+        mut struct_def: P::StructDefinition,
+        is_source_def: bool,
+    ) -> Option<P::StructDefinition> {
+        self.keep(&mut struct_def.attributes, is_source_def)
+            .then_some(struct_def)
+    }
+
+    fn filter_map_spec(
+        &mut self,
+        mut spec: P::SpecBlock_,
+        is_source_def: bool,
+    ) -> Option<P::SpecBlock_> {
+        self.keep(&mut spec.attributes, is_source_def)
+            .then_some(spec)
+    }
+
+    fn filter_map_use(
+        &mut self,
+        mut use_decl: P::UseDecl,
+        is_source_def: bool,
+    ) -> Option<P::UseDecl> {
+        self.keep(&mut use_decl.attributes, is_source_def)
+            .then_some(use_decl)
+    }
+
+    fn filter_map_friend(
+        &mut self,
+        mut friend_decl: P::FriendDecl,
+        is_source_def: bool,
+    ) -> Option<P::FriendDecl> {
+        self.keep(&mut friend_decl.attributes, is_source_def)
+            .then_some(friend_decl)
+    }
+
+    fn filter_map_constant(
+        &mut self,
+        mut constant: P::Constant,
+        is_source_def: bool,
+    ) -> Option<P::Constant> {
+        self.keep(&mut constant.attributes, is_source_def)
+            .then_some(constant)
+    }
+}
+
+impl CfgContext<'_> {
+    /// Whether to keep the node annotated with `attrs`, whose `cfg` attributes are removed as they
+    /// have no meaning past this point (sparing their predicates from checks on attributes).
+    fn keep(&mut self, attrs: &mut [P::Attributes], is_source_def: bool) -> bool {
+        if self.should_remove_by_attributes(attrs, is_source_def) {
+            return false;
+        }
+        for attrs in attrs {
+            attrs
+                .value
+                .retain(|attr| attr.value.attribute_name().value.as_str() != CfgAttribute::CFG);
+        }
+        true
+    }
+
+    /// Evaluates a `cfg` predicate, returning `None` if it is malformed
+    fn eval_predicate(&mut self, sp!(loc, predicate): &P::Attribute) -> Option<bool> {
+        use P::{AttributeValue_ as AV, Attribute_ as PA, Value_ as PV};
+        match predicate {
+            PA::Assigned(sp!(_, name), value) if name.as_str() == CfgAttribute::FEATURE => {
+                match &value.value {
+                    AV::Value(sp!(_, PV::ByteString(feature))) => {
+                        Some(self.env.flags().is_feature_enabled(self.package, *feature))
+                    }
+                    _ => {
+                        self.invalid_predicate(
+                            value.loc,
+                            "Expected the name of a feature as a string, e.g. \"name\"",
+                        );
+                        None
+                    }
+                }
+            }
+            PA::Parameterized(sp!(_, name), sp!(_, predicates)) => match name.as_str() {
+                CfgAttribute::NOT if predicates.len() == 1 => {
+                    self.eval_predicate(&predicates[0]).map(|holds| !holds)
+                }
+                CfgAttribute::ALL => predicates.iter().try_fold(true, |holds, predicate| {
+                    Some(self.eval_predicate(predicate)? && holds)
+                }),
+                CfgAttribute::ANY => predicates.iter().try_fold(false, |holds, predicate| {
+                    Some(self.eval_predicate(predicate)? || holds)
+                }),
+                _ => {
+                    self.invalid_predicate(*loc, PREDICATE_FORMS);
+                    None
+                }
+            },
+            _ => {
+                self.invalid_predicate(*loc, PREDICATE_FORMS);
+                None
+            }
+        }
+    }
+
+    fn invalid_predicate(&mut self, loc: Loc, msg: &str) {
+        let msg = format!("Invalid '{}' attribute. {}", CfgAttribute::CFG, msg);
+        self.env
+            .add_diag(diag!(Attributes::InvalidValue, (loc, msg)));
+    }
+}
+
+const PREDICATE_FORMS: &str = "Expected one of 'feature = \"<name>\"', 'not(<predicate>)', \
+                               'all(<predicate>, ...)' or 'any(<predicate>, ...)'";
+
+// This filters out all AST elements annotated with a `#[cfg(...)]` attribute whose predicate does
+// not hold, given the features enabled in the package they belong to.
+pub fn program(compilation_env: &mut CompilationEnv, prog: P::Program) -> P::Program {
+    let mut context = CfgContext {
+        env: compilation_env,
+        package: None,
+    };
+    filter_program(&mut context, prog)
+}
@@ -16,6 +16,7 @@ pub enum Tok {
     NumValue,
     NumTypedValue,
     ByteStringValue,
+    StringValue,
     Identifier,
     Exclaim,
     ExclaimEqual,
@@ -42,6 +43,7 @@ pub enum Tok {
     LessLess,
     Equal,
     EqualEqual,
+    EqualGreater,
     EqualEqualGreater,
     LessEqualEqualGreater,
     Greater,
@@ -90,6 +92,7 @@ impl fmt::Display for Tok {
             NumValue => "[Num]",
             NumTypedValue => "[NumTyped]",
             ByteStringValue => "[ByteString]",
+            StringValue => "[String]",
             Identifier => "[Identifier]",
             Exclaim => "!",
             ExclaimEqual => "!=",
@@ -116,6 +119,7 @@ impl fmt::Display for Tok {
             LessLess => "<<",
             Equal => "=",
             EqualEqual => "==",
+            EqualGreater => "=>",
             EqualEqualGreater => "==>",
             LessEqualEqualGreater => "<==>",
             Greater => ">",
@@ -164,6 +168,8 @@ pub struct Lexer<'input> {
     file_hash: FileHash,
     doc_comments: FileCommentMap,
     matched_doc_comments: MatchedFileCommentMap,
+    /// All comments, including their delimiters, if the lexer was asked to keep them
+    comments: Option<FileCommentMap>,
     prev_end: usize,
     cur_start: usize,
     cur_end: usize,
@@ -177,6 +183,7 @@ impl<'input> Lexer<'input> {
             file_hash,
             doc_comments: FileCommentMap::new(),
             matched_doc_comments: MatchedFileCommentMap::new(),
+            comments: None,
             prev_end: 0,
             cur_start: 0,
             cur_end: 0,
@@ -184,6 +191,22 @@ impl<'input> Lexer<'input> {
         }
     }
 
+    /// Creates a lexer that, in addition to documentation comments, keeps all regular comments.
+    /// These are returned by `take_comments`
+    pub fn new_keeping_comments(text: &'input str, file_hash: FileHash) -> Lexer<'input> {
+        Lexer {
+            comments: Some(FileCommentMap::new()),
+            ..Self::new(text, file_hash)
+        }
+    }
+
+    /// Returns all comments seen so far, indexed by their span, if the lexer was created with
+    /// `new_keeping_comments`. Unlike documentation comments, the text of each comment includes
+    /// its delimiters
+    pub fn take_comments(&mut self) -> Option<FileCommentMap> {
+        self.comments.as_mut().map(std::mem::take)
+    }
+
     pub fn peek(&self) -> Tok {
         self.token
     }
@@ -214,6 +237,8 @@ impl<'input> Lexer<'input> {
     /// (`/// .. <newline>` and `/** .. */`) will be not included in extracted comment string. The
     /// span in the returned map, however, covers the whole region of the comment, including the
     /// delimiters.
+    ///
+    /// If the lexer keeps comments, all outermost comments are also recorded, see `take_comments`.
     fn trim_whitespace_and_comments(
         &mut self,
         offset: usize,
@@ -268,8 +293,8 @@ impl<'input> Lexer<'input> {
                         text = &text[2..];
 
                         // If this was a documentation comment, record it in our map.
+                        let end = get_offset(text);
                         if loc.1 {
-                            let end = get_offset(text);
                             self.doc_comments.insert(
                                 (loc.0 as u32, end as u32),
                                 self.text[(loc.0 + 3)..(end - 2)].to_string(),
@@ -278,6 +303,7 @@ impl<'input> Lexer<'input> {
 
                         // If this terminated our last comment, exit the loop.
                         if locs.is_empty() {
+                            self.keep_comment(loc.0, end);
                             break;
                         }
                     } else {
@@ -304,6 +330,9 @@ impl<'input> Lexer<'input> {
                     self.doc_comments
                         .insert((start as u32, end as u32), comment.to_string());
                 }
+                let end = get_offset(text);
+                let end = start + self.text[start..end].trim_end_matches('\r').len();
+                self.keep_comment(start, end);
 
                 // Continue the loop on the following line, which may contain leading
                 // whitespace or comments of its own.
@@ -314,6 +343,15 @@ impl<'input> Lexer<'input> {
         Ok(text)
     }
 
+    fn keep_comment(&mut self, start: usize, end: usize) {
+        if let Some(comments) = &mut self.comments {
+            comments.insert(
+                (start as u32, end as u32),
+                self.text[start..end].to_string(),
+            );
+        }
+    }
+
     // Look ahead to the next token after the current one and return it, and its starting offset,
     // without advancing the state of the lexer.
     pub fn lookahead_with_start_loc(&mut self) -> Result<(Tok, usize), Box<Diagnostic>> {
@@ -477,6 +515,8 @@ fn find_token(
                 (Tok::EqualEqualGreater, 3)
             } else if text.starts_with("==") {
                 (Tok::EqualEqual, 2)
+            } else if text.starts_with("=>") {
+                (Tok::EqualGreater, 2)
             } else {
                 (Tok::Equal, 1)
             }
@@ -515,6 +555,19 @@ fn find_token(
                 (Tok::Colon, 1)
             }
         }
+        '"' => {
+            let line = &text.lines().next().unwrap()[1..];
+            match get_string_len(line) {
+                Some(last_quote) => (Tok::StringValue, 1 + last_quote + 1),
+                None => {
+                    let loc = make_loc(file_hash, start_offset, start_offset + line.len() + 1);
+                    return Err(Box::new(diag!(
+                        Syntax::InvalidString,
+                        (loc, "Missing closing quote (\") after string")
+                    )));
+                }
+            }
+        }
         '%' => (Tok::Percent, 1),
         '(' => (Tok::LParen, 1),
         ')' => (Tok::RParen, 1),
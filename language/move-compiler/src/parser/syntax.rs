@@ -15,7 +15,7 @@ use crate::{
     diagnostics::{Diagnostic, Diagnostics},
     parser::{ast::*, lexer::*},
     shared::*,
-    MatchedFileCommentMap,
+    FileCommentMap, MatchedFileCommentMap,
 };
 
 struct Context<'env, 'lexer, 'input> {
@@ -428,6 +428,7 @@ struct Modifiers {
     visibility: Option<Visibility>,
     entry: Option<Loc>,
     native: Option<Loc>,
+    inline: Option<Loc>,
 }
 
 impl Modifiers {
@@ -436,14 +437,15 @@ impl Modifiers {
             visibility: None,
             entry: None,
             native: None,
+            inline: None,
         }
     }
 }
 
-// Parse module member modifiers: visiblility and native.
+// Parse module member modifiers: visiblility, native, entry, and inline.
 // The modifiers are also used for script-functions
 //      ModuleMemberModifiers = <ModuleMemberModifier>*
-//      ModuleMemberModifier = <Visibility> | "native"
+//      ModuleMemberModifier = <Visibility> | "native" | "entry" | "inline"
 // ModuleMemberModifiers checks for uniqueness, meaning each individual ModuleMemberModifier can
 // appear only once
 fn parse_module_member_modifiers(context: &mut Context) -> Result<Modifiers, Box<Diagnostic>> {
@@ -491,6 +493,20 @@ fn parse_module_member_modifiers(context: &mut Context) -> Result<Modifiers, Box
                 }
                 mods.entry = Some(loc)
             }
+            Tok::Identifier if context.tokens.content() == INLINE_MODIFIER => {
+                let loc = current_token_loc(context.tokens);
+                context.tokens.advance()?;
+                if let Some(prev_loc) = mods.inline {
+                    let msg = format!("Duplicate '{}' modifier", INLINE_MODIFIER);
+                    let prev_msg = format!("'{}' modifier previously given here", INLINE_MODIFIER);
+                    context.env.add_diag(diag!(
+                        Declarations::DuplicateItem,
+                        (loc, msg),
+                        (prev_loc, prev_msg)
+                    ))
+                }
+                mods.inline = Some(loc)
+            }
             _ => break,
         }
     }
@@ -529,11 +545,27 @@ fn parse_visibility(context: &mut Context) -> Result<Visibility, Box<Diagnostic>
         }
     })
 }
-// Parse an attribute value. Either a value literal or a module access
+// Parse an attribute value. Either a value literal or a module access. A string literal is only
+// allowed here, and is taken as a byte string.
 //      AttributeValue =
 //          <Value>
+//          | <StringValue>
 //          | <NameAccessChain>
 fn parse_attribute_value(context: &mut Context) -> Result<AttributeValue, Box<Diagnostic>> {
+    if context.tokens.peek() == Tok::StringValue {
+        let start_loc = context.tokens.start_loc();
+        let s = context.tokens.content();
+        let text = Symbol::from(&s[1..s.len() - 1]);
+        context.tokens.advance()?;
+        let end_loc = context.tokens.previous_end_loc();
+        let v = spanned(
+            context.tokens.file_hash(),
+            start_loc,
+            end_loc,
+            Value_::ByteString(text),
+        );
+        return Ok(sp(v.loc, AttributeValue_::Value(v)));
+    }
     if let Some(v) = maybe_parse_value(context)? {
         return Ok(sp(v.loc, AttributeValue_::Value(v)));
     }
@@ -624,6 +656,49 @@ fn parse_exp_field(context: &mut Context) -> Result<(Field, Exp), Box<Diagnostic
     Ok((f, arg))
 }
 
+// An item in the braces of a pack: either a field or the base of a struct update
+enum PackItem {
+    Field(Field, Exp),
+    Base(Exp),
+}
+
+// Parse a pack field, or the base of a struct update:
+//      PackItem = <ExpField> | ".." <Exp>
+fn parse_pack_item(context: &mut Context) -> Result<PackItem, Box<Diagnostic>> {
+    if match_token(context.tokens, Tok::PeriodPeriod)? {
+        return Ok(PackItem::Base(parse_exp(context)?));
+    }
+    let (f, e) = parse_exp_field(context)?;
+    Ok(PackItem::Field(f, e))
+}
+
+// Parse the fields of a pack, where the base of a struct update can only be the last item:
+//      PackFields = "{" Comma<PackItem> "}"
+fn parse_pack_fields(
+    context: &mut Context,
+) -> Result<(Vec<(Field, Exp)>, Option<Exp>), Box<Diagnostic>> {
+    let items = parse_comma_list(
+        context,
+        Tok::LBrace,
+        Tok::RBrace,
+        parse_pack_item,
+        "a field expression",
+    )?;
+    let mut fields = vec![];
+    let mut base = None;
+    for item in items {
+        if let Some(sp!(loc, _)) = &base {
+            let msg = "The '..' base value must be the last item";
+            return Err(Box::new(diag!(Syntax::InvalidStructUpdate, (*loc, msg))));
+        }
+        match item {
+            PackItem::Field(f, e) => fields.push((f, e)),
+            PackItem::Base(e) => base = Some(e),
+        }
+    }
+    Ok((fields, base))
+}
+
 // Parse a field name optionally followed by a colon and a binding:
 //      BindField = <Field> <":" <Bind>>?
 //
@@ -644,11 +719,16 @@ fn parse_bind_field(context: &mut Context) -> Result<(Field, Bind), Box<Diagnost
 //      Bind =
 //          <Var>
 //          | <NameAccessChain> <OptionalTypeArgs> "{" Comma<BindField> "}"
+//          | <NameAccessChain> <OptionalTypeArgs> "(" Comma<Bind> ")"
 fn parse_bind(context: &mut Context) -> Result<Bind, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     if context.tokens.peek() == Tok::Identifier {
         let next_tok = context.tokens.lookahead()?;
-        if next_tok != Tok::LBrace && next_tok != Tok::Less && next_tok != Tok::ColonColon {
+        if next_tok != Tok::LBrace
+            && next_tok != Tok::Less
+            && next_tok != Tok::ColonColon
+            && next_tok != Tok::LParen
+        {
             let v = Bind_::Var(parse_var(context)?);
             let end_loc = context.tokens.previous_end_loc();
             return Ok(spanned(context.tokens.file_hash(), start_loc, end_loc, v));
@@ -659,13 +739,27 @@ fn parse_bind(context: &mut Context) -> Result<Bind, Box<Diagnostic>> {
     // it is possible that the user intention was to use a variable name.
     let ty = parse_name_access_chain(context, || "a variable or struct name")?;
     let ty_args = parse_optional_type_args(context)?;
-    let args = parse_comma_list(
-        context,
-        Tok::LBrace,
-        Tok::RBrace,
-        parse_bind_field,
-        "a field binding",
-    )?;
+    let args = if context.tokens.peek() == Tok::LParen {
+        parse_comma_list(
+            context,
+            Tok::LParen,
+            Tok::RParen,
+            parse_bind,
+            "a variable or structure binding",
+        )?
+        .into_iter()
+        .enumerate()
+        .map(|(idx, b)| (Field::positional(b.loc, idx), b))
+        .collect()
+    } else {
+        parse_comma_list(
+            context,
+            Tok::LBrace,
+            Tok::RBrace,
+            parse_bind_field,
+            "a field binding",
+        )?
+    };
     let end_loc = context.tokens.previous_end_loc();
     let unpack = Bind_::Unpack(Box::new(ty), ty_args, args);
     Ok(spanned(
@@ -703,15 +797,20 @@ fn parse_bind_list(context: &mut Context) -> Result<BindList, Box<Diagnostic>> {
 // Parse a list of bindings for lambda.
 //      LambdaBindList =
 //          "|" Comma<Bind> "|"
+//          | "||"
 fn parse_lambda_bind_list(context: &mut Context) -> Result<BindList, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
-    let b = parse_comma_list(
-        context,
-        Tok::Pipe,
-        Tok::Pipe,
-        parse_bind,
-        "a variable or structure binding",
-    )?;
+    let b = if match_token(context.tokens, Tok::PipePipe)? {
+        vec![]
+    } else {
+        parse_comma_list(
+            context,
+            Tok::Pipe,
+            Tok::Pipe,
+            parse_bind,
+            "a variable or structure binding",
+        )?
+    };
     let end_loc = context.tokens.previous_end_loc();
     Ok(spanned(context.tokens.file_hash(), start_loc, end_loc, b))
 }
@@ -955,6 +1054,17 @@ fn parse_term(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
             Exp_::Vector(vec_loc, tys_opt, args)
         }
 
+        Tok::Identifier
+            if context.tokens.content() == MATCH_IDENT
+                && context.tokens.lookahead()? == Tok::LParen =>
+        {
+            let match_exp = parse_match_exp(context)?;
+            if at_end_of_exp(context) {
+                return Ok(match_exp);
+            }
+            return parse_binop_exp(context, match_exp, /* min_prec */ 1);
+        }
+
         Tok::Identifier => parse_name_exp(context)?,
 
         Tok::NumValue => {
@@ -1023,6 +1133,12 @@ fn parse_term(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
             Exp_::Spec(spec_block)
         }
 
+        Tok::StringValue => {
+            let mut diag = unexpected_token_error(context.tokens, "an expression term");
+            diag.add_note("String literals are only allowed in attributes, use a byte string instead, e.g. b\"hello\"");
+            return Err(diag);
+        }
+
         _ => {
             return Err(unexpected_token_error(context.tokens, "an expression term"));
         }
@@ -1149,9 +1265,89 @@ fn parse_control_exp(context: &mut Context) -> Result<(Exp, bool), Box<Diagnosti
     Ok((exp, ends_in_block))
 }
 
+// Parse a match expression. Like other control expressions ending in a block, the match is not
+// continued by subsequent tokens unless they form a binary operation.
+//      MatchExp = "match" "(" <Exp> ")" "{" Comma<MatchArm> "}"
+//      MatchArm = <MatchPattern> "=>" <Exp>
+fn parse_match_exp(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
+    let start_loc = context.tokens.start_loc();
+    consume_identifier(context.tokens, MATCH_IDENT)?;
+    consume_token(context.tokens, Tok::LParen)?;
+    let subject = parse_exp(context)?;
+    consume_token(context.tokens, Tok::RParen)?;
+    let arms = parse_comma_list(
+        context,
+        Tok::LBrace,
+        Tok::RBrace,
+        parse_match_arm,
+        "a match arm",
+    )?;
+    let end_loc = context.tokens.previous_end_loc();
+    Ok(spanned(
+        context.tokens.file_hash(),
+        start_loc,
+        end_loc,
+        Exp_::Match(Box::new(subject), arms),
+    ))
+}
+
+// Parse an arm of a match expression:
+//      MatchArm = <MatchPattern> "=>" <Exp>
+fn parse_match_arm(context: &mut Context) -> Result<MatchArm, Box<Diagnostic>> {
+    let start_loc = context.tokens.start_loc();
+    let pattern = parse_match_pattern(context)?;
+    consume_token(context.tokens, Tok::EqualGreater)?;
+    let e = parse_exp(context)?;
+    let end_loc = context.tokens.previous_end_loc();
+    Ok(spanned(
+        context.tokens.file_hash(),
+        start_loc,
+        end_loc,
+        (pattern, e),
+    ))
+}
+
+// Parse a match pattern. Unlike other bindings, the field bindings of a variant are optional
+// when the variant has no fields:
+//      MatchPattern =
+//          <Var>
+//          | <NameAccessChain> <OptionalTypeArgs> ("{" Comma<BindField> "}")?
+fn parse_match_pattern(context: &mut Context) -> Result<Bind, Box<Diagnostic>> {
+    let start_loc = context.tokens.start_loc();
+    if context.tokens.peek() == Tok::Identifier {
+        let next_tok = context.tokens.lookahead()?;
+        if next_tok != Tok::LBrace && next_tok != Tok::Less && next_tok != Tok::ColonColon {
+            let v = Bind_::Var(parse_var(context)?);
+            let end_loc = context.tokens.previous_end_loc();
+            return Ok(spanned(context.tokens.file_hash(), start_loc, end_loc, v));
+        }
+    }
+    let ty = parse_name_access_chain(context, || "a variable or variant name")?;
+    let ty_args = parse_optional_type_args(context)?;
+    let args = if context.tokens.peek() == Tok::LBrace {
+        parse_comma_list(
+            context,
+            Tok::LBrace,
+            Tok::RBrace,
+            parse_bind_field,
+            "a field binding",
+        )?
+    } else {
+        vec![]
+    };
+    let end_loc = context.tokens.previous_end_loc();
+    let unpack = Bind_::Unpack(Box::new(ty), ty_args, args);
+    Ok(spanned(
+        context.tokens.file_hash(),
+        start_loc,
+        end_loc,
+        unpack,
+    ))
+}
+
 // Parse a pack, call, or other reference to a name:
 //      NameExp =
-//          <NameAccessChain> <OptionalTypeArgs> "{" Comma<ExpField> "}"
+//          <NameAccessChain> <OptionalTypeArgs> "{" Comma<PackItem> "}"
 //          | <NameAccessChain> <OptionalTypeArgs> "(" Comma<Exp> ")"
 //          | <NameAccessChain> "!" "(" Comma<Exp> ")"
 //          | <NameAccessChain> <OptionalTypeArgs>
@@ -1179,17 +1375,11 @@ fn parse_name_exp(context: &mut Context) -> Result<Exp_, Box<Diagnostic>> {
     }
 
     match context.tokens.peek() {
-        // Pack: "{" Comma<ExpField> "}"
-        Tok::LBrace => {
-            let fs = parse_comma_list(
-                context,
-                Tok::LBrace,
-                Tok::RBrace,
-                parse_exp_field,
-                "a field expression",
-            )?;
-            Ok(Exp_::Pack(n, tys, fs))
-        }
+        // Pack: "{" Comma<PackItem> "}"
+        Tok::LBrace => match parse_pack_fields(context)? {
+            (fs, None) => Ok(Exp_::Pack(n, tys, fs)),
+            (fs, Some(base)) => Ok(Exp_::PackUpdate(n, tys, fs, Box::new(base))),
+        },
 
         // Call: "(" Comma<Exp> ")"
         Tok::Exclaim | Tok::LParen => {
@@ -1266,14 +1456,14 @@ fn at_start_of_exp(context: &mut Context) -> bool {
 
 // Parse an expression:
 //      Exp =
-//            <LambdaBindList> <Exp>        spec only
+//            <LambdaBindList> <Exp>
 //          | <Quantifier>                  spec only
 //          | <BinOpExp>
 //          | <UnaryExp> "=" <Exp>
 fn parse_exp(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     let exp = match context.tokens.peek() {
-        Tok::Pipe => {
+        Tok::Pipe | Tok::PipePipe => {
             let bindings = parse_lambda_bind_list(context)?;
             let body = Box::new(parse_exp(context)?);
             Exp_::Lambda(bindings, body)
@@ -1460,9 +1650,12 @@ fn parse_unary_exp(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
     Ok(spanned(context.tokens.file_hash(), start_loc, end_loc, exp))
 }
 
-// Parse an expression term optionally followed by a chain of dot or index accesses:
+// Parse an expression term optionally followed by a chain of dot or index accesses, or of
+// method calls:
 //      DotOrIndexChain =
 //          <DotOrIndexChain> "." <Identifier>
+//          | <DotOrIndexChain> "." <PositionalField>
+//          | <DotOrIndexChain> "." <Identifier> <OptionalTypeArgs> <CallArgs>
 //          | <DotOrIndexChain> "[" <Exp> "]"                      spec only
 //          | <Term>
 fn parse_dot_or_index_chain(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
@@ -1472,8 +1665,27 @@ fn parse_dot_or_index_chain(context: &mut Context) -> Result<Exp, Box<Diagnostic
         let exp = match context.tokens.peek() {
             Tok::Period => {
                 context.tokens.advance()?;
-                let n = parse_identifier(context)?;
-                Exp_::Dot(Box::new(lhs), n)
+                if matches!(context.tokens.peek(), Tok::NumValue | Tok::NumTypedValue) {
+                    let f = parse_positional_field(context)?;
+                    Exp_::Dot(Box::new(lhs), f.0)
+                } else {
+                    let n = parse_identifier(context)?;
+                    // As for calls, a '<' directly after the name starts a list of type arguments
+                    let mut tys = None;
+                    let tys_start_loc = context.tokens.start_loc();
+                    if context.tokens.peek() == Tok::Less && n.loc.end() as usize == tys_start_loc {
+                        let loc =
+                            make_loc(context.tokens.file_hash(), tys_start_loc, tys_start_loc);
+                        tys = parse_optional_type_args(context)
+                            .map_err(|diag| add_type_args_ambiguity_label(loc, diag))?;
+                    }
+                    if tys.is_some() || context.tokens.peek() == Tok::LParen {
+                        let args = parse_call_args(context)?;
+                        Exp_::DotCall(Box::new(lhs), n, tys, args)
+                    } else {
+                        Exp_::Dot(Box::new(lhs), n)
+                    }
+                }
             }
             Tok::LBracket => {
                 context.tokens.advance()?;
@@ -1490,6 +1702,29 @@ fn parse_dot_or_index_chain(context: &mut Context) -> Result<Exp, Box<Diagnostic
     Ok(lhs)
 }
 
+// Parse the index of a positional field, as in `e.0`. Only plain decimal numbers without leading
+// zeros or type suffixes are accepted:
+//      PositionalField = <NumValue>
+fn parse_positional_field(context: &mut Context) -> Result<Field, Box<Diagnostic>> {
+    let loc = current_token_loc(context.tokens);
+    let content = context.tokens.content();
+    let idx = content
+        .parse::<usize>()
+        .ok()
+        .filter(|idx| idx.to_string() == content);
+    match idx {
+        Some(idx) => {
+            context.tokens.advance()?;
+            Ok(Field::positional(loc, idx))
+        }
+        None => Err(Box::new(diag!(
+            Syntax::UnexpectedToken,
+            (loc, format!("Invalid positional field '{}'", content)),
+            (loc, "Expected a field index such as '0' or '1'"),
+        ))),
+    }
+}
+
 // Lookahead to determine whether this is a quantifier. This matches
 //
 //      ( "exists" | "forall" | "choose" | "min" )
@@ -1671,8 +1906,10 @@ fn make_builtin_call(loc: Loc, name: Symbol, type_args: Option<Vec<Type>>, args:
 //          <NameAccessChain> ('<' Comma<Type> ">")?
 //          | "&" <Type>
 //          | "&mut" <Type>
-//          | "|" Comma<Type> "|" Type   (spec only)
+//          | "|" Comma<Type> "|" Type?
+//          | "||" Type?
 //          | "(" Comma<Type> ")"
+// The result type of a function type can be omitted when it is "()"
 fn parse_type(context: &mut Context) -> Result<Type, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     let t = match context.tokens.peek() {
@@ -1694,9 +1931,25 @@ fn parse_type(context: &mut Context) -> Result<Type, Box<Diagnostic>> {
             let t = parse_type(context)?;
             Type_::Ref(true, Box::new(t))
         }
-        Tok::Pipe => {
-            let args = parse_comma_list(context, Tok::Pipe, Tok::Pipe, parse_type, "a type")?;
-            let result = parse_type(context)?;
+        Tok::Pipe | Tok::PipePipe => {
+            let args = if match_token(context.tokens, Tok::PipePipe)? {
+                vec![]
+            } else {
+                parse_comma_list(context, Tok::Pipe, Tok::Pipe, parse_type, "a type")?
+            };
+            let result = match context.tokens.peek() {
+                Tok::Comma
+                | Tok::RParen
+                | Tok::Greater
+                | Tok::LBrace
+                | Tok::Semicolon
+                | Tok::Equal
+                | Tok::EOF => {
+                    let loc = context.tokens.previous_end_loc();
+                    spanned(context.tokens.file_hash(), loc, loc, Type_::Unit)
+                }
+                _ => parse_type(context)?,
+            };
             return Ok(spanned(
                 context.tokens.file_hash(),
                 start_loc,
@@ -1880,6 +2133,7 @@ fn parse_function_decl(
         visibility,
         mut entry,
         native,
+        inline,
     } = modifiers;
 
     if let Some(Visibility::Script(vloc)) = visibility {
@@ -1898,6 +2152,34 @@ fn parse_function_decl(
         }
     }
 
+    if let Some(inline_loc) = inline {
+        if let Some(native_loc) = native {
+            let msg = format!(
+                "Invalid function declaration. '{}' functions cannot be '{}'",
+                NATIVE_MODIFIER, INLINE_MODIFIER
+            );
+            let native_msg = format!("'{}' modifier given here", NATIVE_MODIFIER);
+            context.env.add_diag(diag!(
+                Syntax::InvalidModifier,
+                (inline_loc, msg),
+                (native_loc, native_msg)
+            ));
+        }
+        if let Some(entry_loc) = entry {
+            let msg = format!(
+                "Invalid function declaration. '{}' functions cannot be '{}', as they are \
+                 expanded at their call sites",
+                ENTRY_MODIFIER, INLINE_MODIFIER
+            );
+            let entry_msg = format!("'{}' modifier given here", ENTRY_MODIFIER);
+            context.env.add_diag(diag!(
+                Syntax::InvalidModifier,
+                (inline_loc, msg),
+                (entry_loc, entry_msg)
+            ));
+        }
+    }
+
     // "fun" <FunctionDefName>
     consume_token(context.tokens, Tok::Fun)?;
     let name = FunctionName(parse_identifier(context)?);
@@ -1970,6 +2252,7 @@ fn parse_function_decl(
         loc,
         visibility: visibility.unwrap_or(Visibility::Internal),
         entry,
+        inline,
         signature,
         acquires,
         name,
@@ -1994,6 +2277,7 @@ fn parse_parameter(context: &mut Context) -> Result<(Var, Type), Box<Diagnostic>
 //      StructDecl =
 //          "struct" <StructDefName> ("has" <Ability> (, <Ability>)+)?
 //          ("{" Comma<FieldAnnot> "}" | ";")
+//          | "struct" <StructDefName> "(" Comma<Type> ")" ("has" <Ability> (, <Ability>)+)? ";"
 //      StructDefName =
 //          <Identifier> <OptionalTypeParameters>
 fn parse_struct_decl(
@@ -2006,6 +2290,7 @@ fn parse_struct_decl(
         visibility,
         entry,
         native,
+        inline,
     } = modifiers;
     if let Some(vis) = visibility {
         let msg = format!(
@@ -2026,6 +2311,15 @@ fn parse_struct_decl(
             .env
             .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
     }
+    if let Some(loc) = inline {
+        let msg = format!(
+            "Invalid struct declaration. '{}' is used only on functions",
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
 
     consume_token(context.tokens, Tok::Struct)?;
 
@@ -2033,8 +2327,81 @@ fn parse_struct_decl(
     let name = StructName(parse_identifier(context)?);
     let type_parameters = parse_struct_type_parameters(context)?;
 
-    let abilities = if context.tokens.peek() == Tok::Identifier && context.tokens.content() == "has"
-    {
+    if native.is_none() && context.tokens.peek() == Tok::LParen {
+        return parse_positional_struct_decl(attributes, start_loc, name, type_parameters, context);
+    }
+
+    let abilities = parse_struct_abilities(context)?;
+
+    let fields = match native {
+        Some(loc) => {
+            consume_token(context.tokens, Tok::Semicolon)?;
+            StructFields::Native(loc)
+        }
+        _ => {
+            let list = parse_comma_list(
+                context,
+                Tok::LBrace,
+                Tok::RBrace,
+                parse_field_annot,
+                "a field",
+            )?;
+            StructFields::Defined(list)
+        }
+    };
+
+    let loc = make_loc(
+        context.tokens.file_hash(),
+        start_loc,
+        context.tokens.previous_end_loc(),
+    );
+    Ok(StructDefinition {
+        attributes,
+        loc,
+        abilities,
+        name,
+        type_parameters,
+        fields,
+    })
+}
+
+// Parse the rest of a struct declaration with positional fields, where the abilities follow the
+// fields:
+//      "(" Comma<Type> ")" <StructAbilities> ";"
+fn parse_positional_struct_decl(
+    attributes: Vec<Attributes>,
+    start_loc: usize,
+    name: StructName,
+    type_parameters: Vec<StructTypeParameter>,
+    context: &mut Context,
+) -> Result<StructDefinition, Box<Diagnostic>> {
+    let tys = parse_comma_list(context, Tok::LParen, Tok::RParen, parse_type, "a type")?;
+    let fields = tys
+        .into_iter()
+        .enumerate()
+        .map(|(idx, ty)| (Field::positional(ty.loc, idx), ty))
+        .collect();
+    let abilities = parse_struct_abilities(context)?;
+    consume_token(context.tokens, Tok::Semicolon)?;
+    let loc = make_loc(
+        context.tokens.file_hash(),
+        start_loc,
+        context.tokens.previous_end_loc(),
+    );
+    Ok(StructDefinition {
+        attributes,
+        loc,
+        abilities,
+        name,
+        type_parameters,
+        fields: StructFields::Defined(fields),
+    })
+}
+
+// Parse the optional abilities of a struct or enum declaration:
+//      StructAbilities = ("has" <Ability> (, <Ability>)+)?
+fn parse_struct_abilities(context: &mut Context) -> Result<Vec<Ability>, Box<Diagnostic>> {
+    if context.tokens.peek() == Tok::Identifier && context.tokens.content() == "has" {
         context.tokens.advance()?;
         parse_list(
             context,
@@ -2055,27 +2422,82 @@ fn parse_struct_decl(
                 )),
             },
             parse_ability,
-        )?
+        )
     } else {
-        vec![]
-    };
+        Ok(vec![])
+    }
+}
 
-    let fields = match native {
-        Some(loc) => {
-            consume_token(context.tokens, Tok::Semicolon)?;
-            StructFields::Native(loc)
-        }
-        _ => {
-            let list = parse_comma_list(
-                context,
-                Tok::LBrace,
-                Tok::RBrace,
-                parse_field_annot,
-                "a field",
-            )?;
-            StructFields::Defined(list)
-        }
-    };
+// Parse an enum definition:
+//      EnumDecl =
+//          "enum" <StructDefName> ("has" <Ability> (, <Ability>)+)?
+//          "{" Comma<VariantDecl> "}"
+//      VariantDecl =
+//          <DocComments> <Identifier> ("{" Comma<FieldAnnot> "}")?
+fn parse_enum_decl(
+    attributes: Vec<Attributes>,
+    start_loc: usize,
+    modifiers: Modifiers,
+    context: &mut Context,
+) -> Result<StructDefinition, Box<Diagnostic>> {
+    let Modifiers {
+        visibility,
+        entry,
+        native,
+        inline,
+    } = modifiers;
+    if let Some(vis) = visibility {
+        let msg = format!(
+            "Invalid enum declaration. Enums cannot have visibility modifiers as they are \
+             always '{}'",
+            Visibility::PUBLIC
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (vis.loc().unwrap(), msg)));
+    }
+    if let Some(loc) = entry {
+        let msg = format!(
+            "Invalid enum declaration. '{}' is used only on functions",
+            ENTRY_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
+    if let Some(loc) = inline {
+        let msg = format!(
+            "Invalid enum declaration. '{}' is used only on functions",
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
+    if let Some(loc) = native {
+        let msg = format!(
+            "Invalid enum declaration. Enums cannot be '{}'",
+            NATIVE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
+
+    consume_identifier(context.tokens, ENUM_IDENT)?;
+
+    // <StructDefName>
+    let name = StructName(parse_identifier(context)?);
+    let type_parameters = parse_struct_type_parameters(context)?;
+    let abilities = parse_struct_abilities(context)?;
+
+    let variants = parse_comma_list(
+        context,
+        Tok::LBrace,
+        Tok::RBrace,
+        parse_variant_decl,
+        "a variant",
+    )?;
 
     let loc = make_loc(
         context.tokens.file_hash(),
@@ -2088,10 +2510,31 @@ fn parse_struct_decl(
         abilities,
         name,
         type_parameters,
-        fields,
+        fields: StructFields::Variants(variants),
     })
 }
 
+// Parse a variant of an enum:
+//      VariantDecl = <DocComments> <Identifier> ("{" Comma<FieldAnnot> "}")?
+fn parse_variant_decl(
+    context: &mut Context,
+) -> Result<(VariantName, Vec<(Field, Type)>), Box<Diagnostic>> {
+    context.tokens.match_doc_comments();
+    let v = VariantName(parse_identifier(context)?);
+    let fields = if context.tokens.peek() == Tok::LBrace {
+        parse_comma_list(
+            context,
+            Tok::LBrace,
+            Tok::RBrace,
+            parse_field_annot,
+            "a field",
+        )?
+    } else {
+        vec![]
+    };
+    Ok((v, fields))
+}
+
 // Parse a field annotated with a type:
 //      FieldAnnot = <DocComments> <Field> ":" <Type>
 fn parse_field_annot(context: &mut Context) -> Result<(Field, Type), Box<Diagnostic>> {
@@ -2118,6 +2561,7 @@ fn parse_constant_decl(
         visibility,
         entry,
         native,
+        inline,
     } = modifiers;
     if let Some(vis) = visibility {
         let msg = "Invalid constant declaration. Constants cannot have visibility modifiers as \
@@ -2135,6 +2579,15 @@ fn parse_constant_decl(
             .env
             .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
     }
+    if let Some(loc) = inline {
+        let msg = format!(
+            "Invalid constant declaration. '{}' is used only on functions",
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
     if let Some(loc) = native {
         let msg = "Invalid constant declaration. 'native' constants are not supported";
         context
@@ -2401,17 +2854,23 @@ fn parse_module(
                         Tok::Struct => ModuleMember::Struct(parse_struct_decl(
                             attributes, start_loc, modifiers, context,
                         )?),
+                        Tok::Identifier if context.tokens.content() == ENUM_IDENT => {
+                            ModuleMember::Struct(parse_enum_decl(
+                                attributes, start_loc, modifiers, context,
+                            )?)
+                        }
                         _ => {
                             return Err(unexpected_token_error(
                                 context.tokens,
                                 &format!(
-                                    "a module member: '{}', '{}', '{}', '{}', '{}', or '{}'",
+                                    "a module member: '{}', '{}', '{}', '{}', '{}', '{}', or '{}'",
                                     Tok::Spec,
                                     Tok::Use,
                                     Tok::Friend,
                                     Tok::Const,
                                     Tok::Fun,
-                                    Tok::Struct
+                                    Tok::Struct,
+                                    ENUM_IDENT
                                 ),
                             ))
                         }
@@ -2479,8 +2938,18 @@ fn parse_script(
 
     context.tokens.match_doc_comments(); // match doc comments to script function
     let function_start_loc = context.tokens.start_loc();
-    let modifiers = parse_module_member_modifiers(context)?;
+    let mut modifiers = parse_module_member_modifiers(context)?;
     // don't need to check native modifier, it is checked later
+    if let Some(loc) = modifiers.inline.take() {
+        let msg = format!(
+            "Invalid '{}' function declaration. Script functions cannot be '{}'",
+            Tok::Script,
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
     let function =
         parse_function_decl(next_item_attributes, function_start_loc, modifiers, context)?;
 
@@ -3226,12 +3695,32 @@ pub fn parse_file_string(
     input: &str,
 ) -> Result<(Vec<Definition>, MatchedFileCommentMap), Diagnostics> {
     let mut tokens = Lexer::new(input, file_hash);
+    let defs = parse_tokens(env, &mut tokens)?;
+    Ok((defs, tokens.check_and_get_doc_comments(env)))
+}
+
+/// Parse the `input` string as a file of Move source code, like `parse_file_string`, but return
+/// all comments of the file, both regular and documentation comments, instead of the matched
+/// doc comments. The comments are indexed by their span and include their delimiters.
+pub fn parse_file_string_with_comments(
+    env: &mut CompilationEnv,
+    file_hash: FileHash,
+    input: &str,
+) -> Result<(Vec<Definition>, FileCommentMap), Diagnostics> {
+    let mut tokens = Lexer::new_keeping_comments(input, file_hash);
+    let defs = parse_tokens(env, &mut tokens)?;
+    // unmatched doc comments are still reported
+    tokens.check_and_get_doc_comments(env);
+    Ok((defs, tokens.take_comments().unwrap_or_default()))
+}
+
+fn parse_tokens(
+    env: &mut CompilationEnv,
+    tokens: &mut Lexer,
+) -> Result<Vec<Definition>, Diagnostics> {
     match tokens.advance() {
         Err(err) => Err(Diagnostics::from(vec![*err])),
         Ok(..) => Ok(()),
     }?;
-    match parse_file(&mut Context::new(env, &mut tokens)) {
-        Err(err) => Err(Diagnostics::from(vec![*err])),
-        Ok(def) => Ok((def, tokens.check_and_get_doc_comments(env))),
-    }
+    parse_file(&mut Context::new(env, tokens)).map_err(|err| Diagnostics::from(vec![*err]))
 }
@@ -4,7 +4,8 @@
 
 use crate::{
     command_line as cli,
-    diagnostics::{codes::Severity, Diagnostic, Diagnostics},
+    diagnostics::{codes::Severity, Diagnostic, Diagnostics, DiagnosticsFormat},
+    linters::LintLevel,
     naming::ast::ModuleDefinition,
 };
 use clap::*;
@@ -12,7 +13,7 @@ use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use petgraph::{algo::astar as petgraph_astar, graphmap::DiGraphMap};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt,
     hash::Hash,
     sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
@@ -312,10 +313,33 @@ pub struct Flags {
     )]
     shadow: bool,
 
+    /// The format used when reporting diagnostics
+    #[clap(
+        long = cli::DIAGNOSTICS_FORMAT,
+        default_value = "human",
+        possible_values = DiagnosticsFormat::variants(),
+    )]
+    diagnostics_format: DiagnosticsFormat,
+
+    /// Run the lints over the source modules
+    #[clap(
+        long = cli::LINT,
+    )]
+    lint: bool,
+
+    /// The level of each lint, by name. Lints not listed are reported as warnings
+    #[clap(skip)]
+    lint_levels: BTreeMap<String, LintLevel>,
+
     /// Internal flag used by the model builder to maintain functions which would be otherwise
     /// included only in tests, without creating the unit test code regular tests do.
     #[clap(skip)]
     keep_testing_functions: bool,
+
+    /// The features enabled in each package, by package name (`None` for files that are not part
+    /// of a package). They decide which code annotated with `#[cfg(...)]` is compiled.
+    #[clap(skip)]
+    features: BTreeMap<Option<Symbol>, BTreeSet<Symbol>>,
 }
 
 impl Flags {
@@ -326,7 +350,11 @@ impl Flags {
             shadow: false,
             flavor: "".to_string(),
             bytecode_version: None,
+            diagnostics_format: DiagnosticsFormat::Human,
+            lint: false,
+            lint_levels: BTreeMap::new(),
             keep_testing_functions: false,
+            features: BTreeMap::new(),
         }
     }
 
@@ -337,7 +365,11 @@ impl Flags {
             shadow: false,
             flavor: "".to_string(),
             bytecode_version: None,
+            diagnostics_format: DiagnosticsFormat::Human,
+            lint: false,
+            lint_levels: BTreeMap::new(),
             keep_testing_functions: false,
+            features: BTreeMap::new(),
         }
     }
 
@@ -348,7 +380,11 @@ impl Flags {
             shadow: true, // allows overlapping between sources and deps
             flavor: "".to_string(),
             bytecode_version: None,
+            diagnostics_format: DiagnosticsFormat::Human,
+            lint: false,
+            lint_levels: BTreeMap::new(),
             keep_testing_functions: false,
+            features: BTreeMap::new(),
         }
     }
 
@@ -366,6 +402,28 @@ impl Flags {
         }
     }
 
+    pub fn set_diagnostics_format(self, diagnostics_format: DiagnosticsFormat) -> Self {
+        Self {
+            diagnostics_format,
+            ..self
+        }
+    }
+
+    pub fn set_lint(self, lint: bool) -> Self {
+        Self { lint, ..self }
+    }
+
+    pub fn set_lint_levels(self, lint_levels: BTreeMap<String, LintLevel>) -> Self {
+        Self {
+            lint_levels,
+            ..self
+        }
+    }
+
+    pub fn set_features(self, features: BTreeMap<Option<Symbol>, BTreeSet<Symbol>>) -> Self {
+        Self { features, ..self }
+    }
+
     pub fn set_sources_shadow_deps(self, sources_shadow_deps: bool) -> Self {
         Self {
             shadow: sources_shadow_deps,
@@ -400,6 +458,22 @@ impl Flags {
     pub fn bytecode_version(&self) -> Option<u32> {
         self.bytecode_version
     }
+
+    pub fn diagnostics_format(&self) -> DiagnosticsFormat {
+        self.diagnostics_format
+    }
+
+    pub fn lint(&self) -> bool {
+        self.lint
+    }
+
+    pub fn lint_level(&self, lint_name: &str) -> LintLevel {
+        self.lint_levels.get(lint_name).copied().unwrap_or_default()
+    }
+
+    pub fn is_feature_enabled(&self, package: Option<Symbol>, feature: Symbol) -> bool {
+        matches!(self.features.get(&package), Some(features) if features.contains(&feature))
+    }
 }
 
 //**************************************************************************************************
@@ -428,6 +502,9 @@ pub mod known_attributes {
         Testing(TestingAttribute),
         Verification(VerificationAttribute),
         Native(NativeAttribute),
+        Lint(LintAttribute),
+        Deprecation(DeprecationAttribute),
+        Cfg(CfgAttribute),
     }
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -452,6 +529,24 @@ pub mod known_attributes {
         BytecodeInstruction,
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum LintAttribute {
+        // The listed lints are not run on the associated AST node
+        Allow,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum DeprecationAttribute {
+        // Uses of the associated item from other modules are reported, with an optional note
+        Deprecated,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum CfgAttribute {
+        // The associated AST node is only included in the compilation if its predicate holds
+        Cfg,
+    }
+
     impl fmt::Display for AttributePosition {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
@@ -482,6 +577,11 @@ pub mod known_attributes {
                 NativeAttribute::BYTECODE_INSTRUCTION => {
                     Self::Native(NativeAttribute::BytecodeInstruction)
                 }
+                LintAttribute::ALLOW => Self::Lint(LintAttribute::Allow),
+                DeprecationAttribute::DEPRECATED => {
+                    Self::Deprecation(DeprecationAttribute::Deprecated)
+                }
+                CfgAttribute::CFG => Self::Cfg(CfgAttribute::Cfg),
                 _ => return None,
             })
         }
@@ -491,6 +591,9 @@ pub mod known_attributes {
                 Self::Testing(a) => a.name(),
                 Self::Verification(a) => a.name(),
                 Self::Native(a) => a.name(),
+                Self::Lint(a) => a.name(),
+                Self::Deprecation(a) => a.name(),
+                Self::Cfg(a) => a.name(),
             }
         }
 
@@ -499,6 +602,9 @@ pub mod known_attributes {
                 Self::Testing(a) => a.expected_positions(),
                 Self::Verification(a) => a.expected_positions(),
                 Self::Native(a) => a.expected_positions(),
+                Self::Lint(a) => a.expected_positions(),
+                Self::Deprecation(a) => a.expected_positions(),
+                Self::Cfg(a) => a.expected_positions(),
             }
         }
     }
@@ -603,4 +709,90 @@ pub mod known_attributes {
             }
         }
     }
+
+    impl LintAttribute {
+        pub const ALLOW: &'static str = "allow";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                LintAttribute::Allow => Self::ALLOW,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static ALLOW_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([
+                    AttributePosition::Module,
+                    AttributePosition::Script,
+                    AttributePosition::Constant,
+                    AttributePosition::Struct,
+                    AttributePosition::Function,
+                ])
+                .collect()
+            });
+            match self {
+                LintAttribute::Allow => &ALLOW_POSITIONS,
+            }
+        }
+    }
+
+    impl DeprecationAttribute {
+        pub const DEPRECATED: &'static str = "deprecated";
+        pub const NOTE: &'static str = "note";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                DeprecationAttribute::Deprecated => Self::DEPRECATED,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static DEPRECATED_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([
+                    AttributePosition::Module,
+                    AttributePosition::Constant,
+                    AttributePosition::Struct,
+                    AttributePosition::Function,
+                ])
+                .collect()
+            });
+            match self {
+                DeprecationAttribute::Deprecated => &DEPRECATED_POSITIONS,
+            }
+        }
+    }
+
+    impl CfgAttribute {
+        pub const CFG: &'static str = "cfg";
+        pub const FEATURE: &'static str = "feature";
+        pub const NOT: &'static str = "not";
+        pub const ALL: &'static str = "all";
+        pub const ANY: &'static str = "any";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                CfgAttribute::Cfg => Self::CFG,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static CFG_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([
+                    AttributePosition::AddressBlock,
+                    AttributePosition::Module,
+                    AttributePosition::Script,
+                    AttributePosition::Use,
+                    AttributePosition::Friend,
+                    AttributePosition::Constant,
+                    AttributePosition::Struct,
+                    AttributePosition::Function,
+                    AttributePosition::Spec,
+                ])
+                .collect()
+            });
+            match self {
+                CfgAttribute::Cfg => &CFG_POSITIONS,
+            }
+        }
+    }
 }
@@ -16,18 +16,20 @@ use crate::{
     },
     naming::{
         ast::{BuiltinTypeName_, StructTypeParameter, TParam},
-        fake_natives,
+        deprecations, fake_natives,
     },
     parser::ast::{
         Ability, Ability_, BinOp, BinOp_, ConstantName, Field, FunctionName, StructName, UnaryOp,
-        UnaryOp_, Var,
+        UnaryOp_, Var, VariantName,
     },
     shared::{unique_map::UniqueMap, *},
     FullyCompiledProgram,
 };
 use move_binary_format::file_format as F;
 use move_bytecode_source_map::source_map::SourceMap;
-use move_core_types::account_address::AccountAddress as MoveAddress;
+use move_core_types::{
+    account_address::AccountAddress as MoveAddress, identifier::positional_field_name,
+};
 use move_ir_types::{ast as IR, location::*};
 use move_symbol_pool::Symbol;
 use std::{
@@ -181,6 +183,7 @@ fn module(
     >,
 ) -> Option<AnnotatedCompiledUnit> {
     let mut context = Context::new(compilation_env, Some(&ident));
+    let deprecation_metadata = deprecation_metadata(&mdef);
     let structs = mdef
         .structs
         .into_iter()
@@ -252,17 +255,20 @@ fn module(
         synthetics: vec![],
     };
     let deps: Vec<&F::CompiledModule> = vec![];
-    let (module, source_map) = match move_ir_to_bytecode::compiler::compile_module(ir_module, deps)
-    {
-        Ok(res) => res,
-        Err(e) => {
-            compilation_env.add_diag(diag!(
-                Bug::BytecodeGeneration,
-                (ident_loc, format!("IR ERROR: {}", e))
-            ));
-            return None;
-        }
-    };
+    let (mut module, source_map) =
+        match move_ir_to_bytecode::compiler::compile_module(ir_module, deps) {
+            Ok(res) => res,
+            Err(e) => {
+                compilation_env.add_diag(diag!(
+                    Bug::BytecodeGeneration,
+                    (ident_loc, format!("IR ERROR: {}", e))
+                ));
+                return None;
+            }
+        };
+    if !deprecation_metadata.is_empty() {
+        module.metadata.push(deprecation_metadata.to_metadata());
+    }
     let function_infos = module_function_infos(&module, &source_map, &collected_function_infos);
     let module = NamedCompiledModule {
         package_name: mdef.package_name,
@@ -280,6 +286,42 @@ fn module(
     }))
 }
 
+/// Records the items marked with `#[deprecated]`, so that tools working from the compiled module
+/// (e.g. the documentation generator) can mark them
+fn deprecation_metadata(mdef: &G::ModuleDefinition) -> DeprecationMetadata {
+    fn info(attributes: &Attributes) -> Option<DeprecationInfo> {
+        let deprecation = deprecations::deprecation(attributes).ok()??;
+        Some(DeprecationInfo {
+            note: deprecation.note,
+        })
+    }
+    fn members<'a>(
+        members: impl Iterator<Item = (Symbol, &'a Attributes)>,
+    ) -> BTreeMap<String, DeprecationInfo> {
+        members
+            .filter_map(|(n, attributes)| Some((n.to_string(), info(attributes)?)))
+            .collect()
+    }
+    DeprecationMetadata {
+        module: info(&mdef.attributes),
+        structs: members(
+            mdef.structs
+                .key_cloned_iter()
+                .map(|(s, sdef)| (s.value(), &sdef.attributes)),
+        ),
+        functions: members(
+            mdef.functions
+                .key_cloned_iter()
+                .map(|(f, fdef)| (f.value(), &fdef.attributes)),
+        ),
+        constants: members(
+            mdef.constants
+                .key_cloned_iter()
+                .map(|(c, cdef)| (c.value(), &cdef.attributes)),
+        ),
+    }
+}
+
 fn script(
     compilation_env: &mut CompilationEnv,
     package_name: Option<Symbol>,
@@ -518,6 +560,19 @@ fn struct_fields(
                 .collect();
             IRF::Move { fields }
         }
+        HF::Variants(variant_vec) => {
+            let variants = variant_vec
+                .into_iter()
+                .map(|(v, field_vec)| {
+                    let fields = field_vec
+                        .into_iter()
+                        .map(|(f, ty)| (field(f), base_type(context, ty)))
+                        .collect();
+                    (variant(v), fields)
+                })
+                .collect();
+            IRF::Variants { variants }
+        }
     }
 }
 
@@ -729,8 +784,18 @@ fn var(v: Var) -> IR::Var {
     sp(v.0.loc, IR::Var_(v.0.value))
 }
 
+// Positional fields are compiled to the `pos{idx}` names shared with the rest of the tooling, as
+// `0`, `1`, ... are not valid identifiers in the binary format
 fn field(f: Field) -> IR::Field {
-    sp(f.0.loc, IR::Field_(f.0.value))
+    let name = match f.0.value.parse::<usize>() {
+        Ok(idx) if f.is_positional() => positional_field_name(idx).as_str().into(),
+        _ => f.0.value,
+    };
+    sp(f.0.loc, IR::Field_(name))
+}
+
+fn variant(v: VariantName) -> IR::VariantName {
+    IR::VariantName(v.value())
 }
 
 fn struct_definition_name(
@@ -948,6 +1013,19 @@ fn lvalue(context: &mut Context, code: &mut IR::BytecodeBlock, sp!(loc, l_): H::
 
             lvalues_(context, code, field_ls.into_iter().map(|(_, l)| l));
         }
+
+        L::UnpackVariant(s, v, unpack_type, tys, field_ls) => {
+            let n = context.struct_definition_name(context.current_module().unwrap(), s);
+            let tys = base_types(context, tys);
+            let instr = match unpack_type {
+                H::UnpackType::ByValue => B::UnpackVariant(n, variant(v), tys),
+                H::UnpackType::ByImmRef => B::UnpackVariantImmRef(n, variant(v), tys),
+                H::UnpackType::ByMutRef => B::UnpackVariantMutRef(n, variant(v), tys),
+            };
+            code.push(sp(loc, instr));
+
+            lvalues_(context, code, field_ls.into_iter().map(|(_, l)| l));
+        }
     }
 }
 
@@ -1058,6 +1136,18 @@ fn exp_(context: &mut Context, code: &mut IR::BytecodeBlock, e: H::Exp) {
             code.push(sp(loc, B::Pack(n, base_types(context, tys))))
         }
 
+        E::PackVariant(s, v, tys, field_args) => {
+            for (_, _, earg) in field_args {
+                exp_(context, code, earg);
+            }
+            variant_instr(context, code, loc, B::PackVariant, s, v, tys)
+        }
+
+        E::TestVariant(s, v, tys, e) => {
+            exp(context, code, e);
+            variant_instr(context, code, loc, B::TestVariant, s, v, tys)
+        }
+
         E::Vector(_, n, bt, args) => {
             let ty = base_type(context, *bt);
             exp(context, code, args);
@@ -1112,6 +1202,21 @@ fn exp_(context: &mut Context, code: &mut IR::BytecodeBlock, e: H::Exp) {
     }
 }
 
+// Kept out of `exp_` to keep its stack frame small, as it is called recursively
+fn variant_instr(
+    context: &mut Context,
+    code: &mut IR::BytecodeBlock,
+    loc: Loc,
+    instr: impl FnOnce(IR::StructName, IR::VariantName, Vec<IR::Type>) -> IR::Bytecode_,
+    s: StructName,
+    v: VariantName,
+    tys: Vec<H::BaseType>,
+) {
+    let n = context.struct_definition_name(context.current_module().unwrap(), s);
+    let tys = base_types(context, tys);
+    code.push(sp(loc, instr(n, variant(v), tys)))
+}
+
 fn module_call(
     context: &mut Context,
     loc: Loc,
@@ -6,7 +6,8 @@ use crate::{
     expansion::ast::{Attributes, Fields, Friend, ModuleIdent, SpecId, Value, Visibility},
     naming::ast::{FunctionSignature, StructDefinition, Type, TypeName_, Type_},
     parser::ast::{
-        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, VariantName,
+        ENTRY_MODIFIER, INLINE_MODIFIER,
     },
     shared::{ast_debug::*, unique_map::UniqueMap},
 };
@@ -76,6 +77,7 @@ pub struct Function {
     pub attributes: Attributes,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: Option<Loc>,
     pub signature: FunctionSignature,
     pub acquires: BTreeMap<StructName, Loc>,
     pub body: FunctionBody,
@@ -110,6 +112,21 @@ pub enum LValue_ {
         Vec<Type>,
         Fields<(Type, LValue)>,
     ),
+    UnpackVariant(
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Vec<Type>,
+        Fields<(Type, LValue)>,
+    ),
+    BorrowUnpackVariant(
+        bool,
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Vec<Type>,
+        Fields<(Type, LValue)>,
+    ),
 }
 pub type LValue = Spanned<LValue_>;
 pub type LValueList_ = Vec<LValue>;
@@ -139,21 +156,37 @@ pub type BuiltinFunction = Spanned<BuiltinFunction_>;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum UnannotatedExp_ {
-    Unit { trailing: bool },
+    Unit {
+        trailing: bool,
+    },
     Value(Value),
-    Move { from_user: bool, var: Var },
-    Copy { from_user: bool, var: Var },
+    Move {
+        from_user: bool,
+        var: Var,
+    },
+    Copy {
+        from_user: bool,
+        var: Var,
+    },
     Use(Var),
     Constant(Option<ModuleIdent>, ConstantName),
 
     ModuleCall(Box<ModuleCall>),
+    // call of a lambda parameter, only valid in the body of an inline function
+    VarCall(Var, Box<Exp>),
     Builtin(Box<BuiltinFunction>, Box<Exp>),
     Vector(Loc, usize, Box<Type>, Box<Exp>),
 
     IfElse(Box<Exp>, Box<Exp>, Box<Exp>),
     While(Box<Exp>, Box<Exp>),
-    Loop { has_break: bool, body: Box<Exp> },
+    Loop {
+        has_break: bool,
+        body: Box<Exp>,
+    },
+    Match(Box<Exp>, Vec<MatchArm>),
     Block(Sequence),
+    // only valid as an argument to an inline function
+    Lambda(LValueList, Box<Exp>),
     Assign(LValueList, Vec<Option<Type>>, Box<Exp>),
     Mutate(Box<Exp>, Box<Exp>),
     Return(Box<Exp>),
@@ -166,6 +199,14 @@ pub enum UnannotatedExp_ {
     BinopExp(Box<Exp>, BinOp, Box<Type>, Box<Exp>),
 
     Pack(ModuleIdent, StructName, Vec<Type>, Fields<(Type, Exp)>),
+    PackVariant(
+        ModuleIdent,
+        StructName,
+        VariantName,
+        Vec<Type>,
+        Fields<(Type, Exp)>,
+    ),
+    TestVariant(ModuleIdent, StructName, VariantName, Vec<Type>, Box<Exp>),
     ExpList(Vec<ExpListItem>),
 
     Borrow(bool, Box<Exp>, Field),
@@ -180,6 +221,12 @@ pub enum UnannotatedExp_ {
     UnresolvedError,
 }
 pub type UnannotatedExp = Spanned<UnannotatedExp_>;
+
+// The pattern of an arm is either a binder, or an `UnpackVariant`/`BorrowUnpackVariant` of the
+// matched enum
+pub type MatchArm_ = (LValue, Exp);
+pub type MatchArm = Spanned<MatchArm_>;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Exp {
     pub ty: Type,
@@ -341,6 +388,7 @@ impl AstDebug for (FunctionName, &Function) {
                 attributes,
                 visibility,
                 entry,
+                inline,
                 signature,
                 acquires,
                 body,
@@ -351,6 +399,9 @@ impl AstDebug for (FunctionName, &Function) {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if inline.is_some() {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -447,6 +498,11 @@ impl AstDebug for UnannotatedExp_ {
             E::ModuleCall(mcall) => {
                 mcall.ast_debug(w);
             }
+            E::VarCall(v, rhs) => {
+                w.write(&format!("{}(", v));
+                rhs.ast_debug(w);
+                w.write(")");
+            }
             E::Builtin(bf, rhs) => {
                 bf.ast_debug(w);
                 w.write("(");
@@ -477,6 +533,30 @@ impl AstDebug for UnannotatedExp_ {
                 });
                 w.write("}");
             }
+            E::PackVariant(m, s, v, tys, fields) => {
+                w.write(&format!("{}::{}::{}", m, s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_bt_e)| {
+                    let (idx, (bt, e)) = idx_bt_e;
+                    w.write(&format!("({}#{}:", idx, f));
+                    bt.ast_debug(w);
+                    w.write("): ");
+                    e.ast_debug(w);
+                });
+                w.write("}");
+            }
+            E::TestVariant(m, s, v, tys, e) => {
+                w.write(&format!("test_variant {}::{}::{}", m, s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("(");
+                e.ast_debug(w);
+                w.write(")");
+            }
             E::IfElse(b, t, f) => {
                 w.write("if (");
                 b.ast_debug(w);
@@ -499,7 +579,25 @@ impl AstDebug for UnannotatedExp_ {
                 w.write(" ");
                 body.ast_debug(w);
             }
+            E::Match(e, arms) => {
+                w.write("match (");
+                e.ast_debug(w);
+                w.write(") ");
+                w.block(|w| {
+                    w.comma(arms, |w, sp!(_, (pat, arm))| {
+                        pat.ast_debug(w);
+                        w.write(" => ");
+                        arm.ast_debug(w);
+                    })
+                })
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
+            E::Lambda(sp!(_, bs), e) => {
+                w.write("fun ");
+                bs.ast_debug(w);
+                w.write(" ");
+                e.ast_debug(w);
+            }
             E::ExpList(es) => {
                 w.write("(");
                 w.comma(es, |w, e| e.ast_debug(w));
@@ -737,6 +835,38 @@ impl AstDebug for LValue_ {
                 });
                 w.write("}");
             }
+            L::UnpackVariant(m, s, v, tys, fields) => {
+                w.write(&format!("{}::{}::{}", m, s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_bt_a)| {
+                    let (idx, (bt, a)) = idx_bt_a;
+                    w.annotate(|w| w.write(&format!("{}#{}", idx, f)), bt);
+                    w.write(": ");
+                    a.ast_debug(w);
+                });
+                w.write("}");
+            }
+            L::BorrowUnpackVariant(mut_, m, s, v, tys, fields) => {
+                w.write("&");
+                if *mut_ {
+                    w.write("mut ");
+                }
+                w.write(&format!("{}::{}::{}", m, s, v));
+                w.write("<");
+                tys.ast_debug(w);
+                w.write(">");
+                w.write("{");
+                w.comma(fields, |w, (_, f, idx_bt_a)| {
+                    let (idx, (bt, a)) = idx_bt_a;
+                    w.annotate(|w| w.write(&format!("{}#{}", idx, f)), bt);
+                    w.write(": ");
+                    a.ast_debug(w);
+                });
+                w.write("}");
+            }
         }
     }
 }
@@ -5,15 +5,17 @@
 use crate::{
     diag,
     diagnostics::{codes::NameResolution, Diagnostic},
-    expansion::ast::{AbilitySet, ModuleIdent, Visibility},
+    expansion::ast::{AbilitySet, Address, Fields, ModuleIdent, Visibility},
     naming::ast::{
         self as N, BuiltinTypeName_, FunctionSignature, StructDefinition, StructTypeParameter,
         TParam, TParamID, TVar, Type, TypeName, TypeName_, Type_,
     },
-    parser::ast::{Ability_, ConstantName, Field, FunctionName, StructName, Var},
+    parser::ast::{Ability_, ConstantName, Field, FunctionName, StructName, Var, VariantName},
     shared::{unique_map::UniqueMap, *},
+    typing::ast as T,
     FullyCompiledProgram,
 };
+use move_core_types::account_address::AccountAddress;
 use move_ir_types::location::*;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 
@@ -40,6 +42,7 @@ pub type TParamSubst = HashMap<TParamID, Type>;
 pub struct FunctionInfo {
     pub defined_loc: Loc,
     pub visibility: Visibility,
+    pub inline: Option<Loc>,
     pub signature: FunctionSignature,
     pub acquires: BTreeMap<StructName, Loc>,
 }
@@ -70,6 +73,7 @@ pub struct Context<'env> {
 
     pub current_module: Option<ModuleIdent>,
     pub current_function: Option<FunctionName>,
+    pub current_constant: Option<ConstantName>,
     pub current_script_constants: Option<UniqueMap<ConstantName, ConstantInfo>>,
     pub return_type: Option<Type>,
     locals: UniqueMap<Var, Type>,
@@ -78,6 +82,7 @@ pub struct Context<'env> {
     pub constraints: Constraints,
 
     loop_info: LoopInfo,
+    lambda_depth: usize,
 }
 
 impl<'env> Context<'env> {
@@ -101,6 +106,7 @@ impl<'env> Context<'env> {
             let functions = mdef.functions.ref_map(|fname, fdef| FunctionInfo {
                 defined_loc: fname.loc(),
                 visibility: fdef.visibility.clone(),
+                inline: fdef.inline,
                 signature: fdef.signature.clone(),
                 acquires: fdef.acquires.clone(),
             });
@@ -117,15 +123,62 @@ impl<'env> Context<'env> {
             (mident, minfo)
         }))
         .unwrap();
+        Self::new_(env, modules)
+    }
+
+    // Context for passes over the typed program, e.g. the expansion of 'inline' functions
+    pub fn new_for_typed_program(
+        env: &'env mut CompilationEnv,
+        pre_compiled_lib: Option<&FullyCompiledProgram>,
+        prog: &T::Program,
+    ) -> Self {
+        let all_modules = prog
+            .modules
+            .key_cloned_iter()
+            .chain(pre_compiled_lib.iter().flat_map(|pre_compiled| {
+                pre_compiled
+                    .typing
+                    .modules
+                    .key_cloned_iter()
+                    .filter(|(mident, _m)| !prog.modules.contains_key(mident))
+            }));
+        let modules = UniqueMap::maybe_from_iter(all_modules.map(|(mident, mdef)| {
+            let structs = mdef.structs.clone();
+            let functions = mdef.functions.ref_map(|fname, fdef| FunctionInfo {
+                defined_loc: fname.loc(),
+                visibility: fdef.visibility.clone(),
+                inline: fdef.inline,
+                signature: fdef.signature.clone(),
+                acquires: fdef.acquires.clone(),
+            });
+            let constants = mdef.constants.ref_map(|cname, cdef| ConstantInfo {
+                defined_loc: cname.loc(),
+                signature: cdef.signature.clone(),
+            });
+            let minfo = ModuleInfo {
+                friends: mdef.friends.ref_map(|_, friend| friend.loc),
+                structs,
+                functions,
+                constants,
+            };
+            (mident, minfo)
+        }))
+        .unwrap();
+        Self::new_(env, modules)
+    }
+
+    fn new_(env: &'env mut CompilationEnv, modules: UniqueMap<ModuleIdent, ModuleInfo>) -> Self {
         Context {
             subst: Subst::empty(),
             current_module: None,
             current_function: None,
+            current_constant: None,
             current_script_constants: None,
             return_type: None,
             constraints: vec![],
             locals: UniqueMap::new(),
             loop_info: LoopInfo(LoopInfo_::NotInLoop),
+            lambda_depth: 0,
             modules,
             env,
         }
@@ -136,11 +189,16 @@ impl<'env> Context<'env> {
             matches!(&self.loop_info, LoopInfo(LoopInfo_::NotInLoop)),
             "ICE loop_info should be reset after the loop"
         );
+        assert!(
+            self.lambda_depth == 0,
+            "ICE lambda_depth should be reset after the lambda"
+        );
         self.return_type = None;
         self.locals = UniqueMap::new();
         self.subst = Subst::empty();
         self.constraints = Constraints::new();
         self.current_function = None;
+        self.current_constant = None;
     }
 
     pub fn bind_script_constants(&mut self, constants: &UniqueMap<ConstantName, N::Constant>) {
@@ -291,7 +349,7 @@ impl<'env> Context<'env> {
             .expect("ICE should have failed in naming")
     }
 
-    fn struct_definition(&self, m: &ModuleIdent, n: &StructName) -> &StructDefinition {
+    pub fn struct_definition(&self, m: &ModuleIdent, n: &StructName) -> &StructDefinition {
         let minfo = self.module_info(m);
         minfo
             .structs
@@ -315,6 +373,55 @@ impl<'env> Context<'env> {
         &self.struct_definition(m, n).type_parameters
     }
 
+    pub fn is_inline_function(&self, m: &ModuleIdent, n: &FunctionName) -> bool {
+        self.function_info(m, n).inline.is_some()
+    }
+
+    pub fn is_function_visible(&self, m: &ModuleIdent, n: &FunctionName) -> bool {
+        match self.function_info(m, n).visibility {
+            Visibility::Internal => self.is_current_module(m),
+            Visibility::Friend(_) => {
+                self.is_current_module(m) || self.current_module_is_a_friend_of(m)
+            }
+            Visibility::Public(_) => true,
+        }
+    }
+
+    pub fn is_current_function_inline(&self) -> bool {
+        match (&self.current_module, &self.current_function) {
+            (Some(m), Some(f)) => self.is_inline_function(m, f),
+            _ => false,
+        }
+    }
+
+    /// The type of the first parameter of the function `m::n`, if the function exists and has
+    /// any parameters. Used for resolving method calls
+    pub fn function_receiver_type(&self, m: &ModuleIdent, n: &FunctionName) -> Option<&Type> {
+        let finfo = self.modules.get(m)?.functions.get(n)?;
+        finfo.signature.parameters.first().map(|(_, ty)| ty)
+    }
+
+    /// The module defining the functions on vectors, i.e. `std::vector`, if it is available
+    pub fn vector_module(&self) -> Option<ModuleIdent> {
+        self.modules
+            .key_cloned_iter()
+            .map(|(m, _)| m)
+            .find(|sp!(_, m_)| {
+                // Either (a) the address is named "std" or (b) its value is 0x1
+                let is_std = match &m_.address {
+                    Address::Numerical(Some(sp!(_, n)), _)
+                    | Address::NamedUnassigned(sp!(_, n))
+                        if n.as_str() == "std" =>
+                    {
+                        true
+                    }
+                    Address::Numerical(_, sp!(_, a)) => a.into_inner() == AccountAddress::ONE,
+                    _ => false,
+                };
+                is_std && m_.module.value().as_str() == "vector"
+            })
+    }
+
     fn function_info(&self, m: &ModuleIdent, n: &FunctionName) -> &FunctionInfo {
         self.module_info(m)
             .functions
@@ -358,6 +465,26 @@ impl<'env> Context<'env> {
     }
 
     // Reset loop info and return the loop's break type, if it has one
+    /// Lambda bodies are substituted into the body of the inline function they are passed to, so
+    /// loops of the enclosing function are not visible inside of them
+    pub fn enter_lambda(&mut self) -> LoopInfo {
+        self.lambda_depth += 1;
+        std::mem::replace(&mut self.loop_info, LoopInfo(LoopInfo_::NotInLoop))
+    }
+
+    pub fn exit_lambda(&mut self, old_info: LoopInfo) {
+        assert!(
+            self.lambda_depth > 0,
+            "ICE exit_lambda called while not in a lambda"
+        );
+        self.lambda_depth -= 1;
+        self.loop_info = old_info;
+    }
+
+    pub fn in_lambda(&self) -> bool {
+        self.lambda_depth > 0
+    }
+
     pub fn exit_loop(&mut self, old_info: LoopInfo) -> Option<Type> {
         match std::mem::replace(&mut self.loop_info, old_info).0 {
             LoopInfo_::NotInLoop => panic!("ICE exit_loop called while not in a loop"),
@@ -488,6 +615,11 @@ fn error_format_impl_(b_: &Type_, subst: &Subst, nested: bool) -> String {
             format!("{}{}", n, tys_str)
         }
         Param(tp) => tp.user_specified_name.value.to_string(),
+        Fun(args, result) => format!(
+            "|{}|{}",
+            format_comma(args.iter().map(|t| error_format_nested(t, subst))),
+            error_format_nested(result, subst)
+        ),
         Ref(mut_, ty) => format!(
             "&{}{}",
             if *mut_ { "mut " } else { "" },
@@ -511,6 +643,7 @@ pub fn infer_abilities(context: &Context, subst: &Subst, ty: Type) -> AbilitySet
     match unfold_type(subst, ty).value {
         T::Unit => AbilitySet::collection(loc),
         T::Ref(_, _) => AbilitySet::references(loc),
+        T::Fun(_, _) => AbilitySet::empty(),
         T::Var(_) => unreachable!("ICE unfold_type failed, which is impossible"),
         T::UnresolvedError | T::Anything => AbilitySet::all(loc),
         T::Param(TParam { abilities, .. }) | T::Apply(Some(abilities), _, _) => abilities,
@@ -553,6 +686,7 @@ fn debug_abilities_info(context: &Context, ty: &Type) -> (Option<Loc>, AbilitySe
     let loc = ty.loc;
     match &ty.value {
         T::Unit | T::Ref(_, _) => (None, AbilitySet::references(loc), vec![]),
+        T::Fun(_, _) => (None, AbilitySet::empty(), vec![]),
         T::Var(_) => panic!("ICE call unfold_type before debug_abilities_info"),
         T::UnresolvedError | T::Anything => (None, AbilitySet::all(loc), vec![]),
         T::Param(TParam {
@@ -660,9 +794,62 @@ pub fn make_field_types(
                 (*idx, subst_tparams(tparam_subst, field_ty.clone()))
             }))
         }
+        N::StructFields::Variants(variants) => {
+            N::StructFields::Variants(variants.ref_map(|_, (tag, fields)| {
+                let fields = fields.ref_map(|_, (idx, field_ty)| {
+                    (*idx, subst_tparams(tparam_subst, field_ty.clone()))
+                });
+                (*tag, fields)
+            }))
+        }
     }
 }
 
+// ty_args should come from make_struct_type
+// Returns None if 'n' is not an enum or if 'v' is not one of its variants
+pub fn make_variant_field_types(
+    context: &mut Context,
+    loc: Loc,
+    m: &ModuleIdent,
+    n: &StructName,
+    v: &VariantName,
+    ty_args: Vec<Type>,
+) -> Option<Fields<Type>> {
+    let sdef = context.struct_definition(m, n);
+    let variants = match &sdef.fields {
+        N::StructFields::Variants(variants) => variants,
+        N::StructFields::Native(_) | N::StructFields::Defined(_) => {
+            let msg = format!(
+                "Unbound variant '{}'. '{}::{}' is a struct, not an enum",
+                v, m, n
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundVariant, (loc, msg)));
+            return None;
+        }
+    };
+    let fields = match variants.get(v) {
+        None => {
+            let msg = format!("Unbound variant '{}' in '{}::{}'", v, m, n);
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundVariant, (loc, msg)));
+            return None;
+        }
+        Some((_, fields)) => fields.clone(),
+    };
+    let tparam_subst = &make_tparam_subst(
+        context
+            .struct_definition(m, n)
+            .type_parameters
+            .iter()
+            .map(|tp| &tp.param),
+        ty_args,
+    );
+    Some(fields.map(|_, (idx, field_ty)| (idx, subst_tparams(tparam_subst, field_ty))))
+}
+
 // ty_args should come from make_struct_type
 pub fn make_field_type(
     context: &mut Context,
@@ -684,6 +871,17 @@ pub fn make_field_type(
             ));
             return context.error_type(loc);
         }
+        N::StructFields::Variants(_) => {
+            let msg = format!(
+                "Unbound field '{}' for enum '{}::{}'. The fields of an enum variant can only be \
+                 accessed with a 'match'",
+                field, m, n
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundField, (loc, msg)));
+            return context.error_type(loc);
+        }
         N::StructFields::Defined(m) => m,
     };
     match fields_map.get(field).cloned() {
@@ -1040,7 +1238,7 @@ fn solve_base_type_constraint(context: &mut Context, loc: Loc, msg: String, ty:
     let sp!(tyloc, unfolded_) = unfold_type(&context.subst, ty.clone());
     match unfolded_ {
         Var(_) => unreachable!(),
-        Unit | Ref(_, _) | Apply(_, sp!(_, Multiple(_)), _) => {
+        Unit | Ref(_, _) | Fun(_, _) | Apply(_, sp!(_, Multiple(_)), _) => {
             let tystr = error_format(ty, &context.subst);
             let tmsg = format!("Expected a single non-reference type, but found: {}", tystr);
             context.env.add_diag(diag!(
@@ -1070,7 +1268,7 @@ fn solve_single_type_constraint(context: &mut Context, loc: Loc, msg: String, ty
                 (tyloc, tmsg)
             ))
         }
-        UnresolvedError | Anything | Ref(_, _) | Param(_) | Apply(_, _, _) => (),
+        UnresolvedError | Anything | Ref(_, _) | Fun(_, _) | Param(_) | Apply(_, _, _) => (),
     }
 }
 
@@ -1092,6 +1290,18 @@ pub fn unfold_type(subst: &Subst, sp!(loc, t_): Type) -> Type {
     }
 }
 
+// Returns true if the type is known to be a vector, e.g. the type of a byte string
+pub fn is_vector_type(subst: &Subst, ty: &Type) -> bool {
+    matches!(
+        unfold_type(subst, ty.clone()).value,
+        Type_::Apply(
+            _,
+            sp!(_, TypeName_::Builtin(sp!(_, BuiltinTypeName_::Vector))),
+            _
+        )
+    )
+}
+
 // Equivelent to unfold_type, but only returns the loc.
 // The hope is to point to the last loc in a chain of type var's, giving the loc closest to the
 // actual type in the source code
@@ -1144,6 +1354,11 @@ pub fn subst_tparams(subst: &TParamSubst, sp!(loc, t_): Type) -> Type {
                 .collect();
             sp(loc, Apply(k, n, ftys))
         }
+        Fun(args, result) => {
+            let args = args.into_iter().map(|t| subst_tparams(subst, t)).collect();
+            let result = subst_tparams(subst, *result);
+            sp(loc, Fun(args, Box::new(result)))
+        }
     }
 }
 
@@ -1156,6 +1371,11 @@ pub fn ready_tvars(subst: &Subst, sp!(loc, t_): Type) -> Type {
             let tys = tys.into_iter().map(|t| ready_tvars(subst, t)).collect();
             sp(loc, Apply(k, n, tys))
         }
+        Fun(args, result) => {
+            let args = args.into_iter().map(|t| ready_tvars(subst, t)).collect();
+            let result = ready_tvars(subst, *result);
+            sp(loc, Fun(args, Box::new(result)))
+        }
         Var(i) => {
             let last_var = forward_tvar(subst, i);
             match subst.get(last_var) {
@@ -1185,6 +1405,11 @@ pub fn instantiate(context: &mut Context, sp!(loc, t_): Type) -> Type {
         Apply(abilities_opt, n, ty_args) => {
             instantiate_apply(context, loc, abilities_opt, n, ty_args)
         }
+        Fun(args, result) => {
+            let args = args.into_iter().map(|t| instantiate(context, t)).collect();
+            let result = instantiate(context, *result);
+            Fun(args, Box::new(result))
+        }
         x @ Param(_) => x,
         Var(_) => panic!("ICE instantiate type variable"),
     };
@@ -1413,6 +1638,14 @@ fn join_impl(
             let (subst, tys) = join_impl_types(subst, case, tys1, tys2)?;
             Ok((subst, sp(*loc, Apply(k2.clone(), n2.clone(), tys))))
         }
+        (sp!(_, Fun(args1, result1)), sp!(loc, Fun(args2, result2)))
+            if args1.len() == args2.len() =>
+        {
+            // parameters are contravariant
+            let (subst, args) = join_impl_types(subst, case, args2, args1)?;
+            let (subst, result) = join_impl(subst, case, result1, result2)?;
+            Ok((subst, sp(*loc, Fun(args, Box::new(result)))))
+        }
         (sp!(loc1, Var(id1)), sp!(loc2, Var(id2))) => {
             if *id1 == *id2 {
                 Ok((subst, sp(*loc2, Var(*id2))))
@@ -1559,6 +1792,10 @@ fn join_bind_tvar(subst: &mut Subst, loc: Loc, tvar: TVar, ty: Type) -> Result<b
                 .iter()
                 .rev()
                 .for_each(|inner| used_tvars(used, inner)),
+            T::Fun(args, result) => {
+                args.iter().for_each(|arg| used_tvars(used, arg));
+                used_tvars(used, result)
+            }
             T::Unit | T::Param(_) | T::Anything | T::UnresolvedError => (),
         }
     }
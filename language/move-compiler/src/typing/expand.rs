@@ -52,6 +52,10 @@ pub fn type_(context: &mut Context, ty: &mut Type) {
     match &mut ty.value {
         Anything | UnresolvedError | Param(_) | Unit => (),
         Ref(_, b) => type_(context, b),
+        Fun(args, result) => {
+            types(context, args);
+            type_(context, result)
+        }
         Var(tvar) => {
             let ty_tvar = sp(ty.loc, Var(*tvar));
             let replacement = core::unfold_type(&context.subst, ty_tvar);
@@ -230,6 +234,7 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
         | E::UnresolvedError => (),
 
         E::ModuleCall(call) => module_call(context, call),
+        E::VarCall(_, args) => exp(context, args),
         E::Builtin(b, args) => {
             builtin_function(context, b);
             exp(context, args);
@@ -249,7 +254,18 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
             exp(context, eloop);
         }
         E::Loop { body: eloop, .. } => exp(context, eloop),
+        E::Match(esubject, arms) => {
+            exp(context, esubject);
+            for sp!(_, (pat, earm)) in arms {
+                lvalue(context, pat);
+                exp(context, earm)
+            }
+        }
         E::Block(seq) => sequence(context, seq),
+        E::Lambda(binds, body) => {
+            lvalues(context, binds);
+            exp(context, body)
+        }
         E::Assign(assigns, tys, er) => {
             lvalues(context, assigns);
             expected_types(context, tys);
@@ -279,6 +295,17 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
                 exp(context, fe)
             }
         }
+        E::PackVariant(_, _, _, bs, fields) => {
+            types(context, bs);
+            for (_, _, (_, (bt, fe))) in fields.iter_mut() {
+                type_(context, bt);
+                exp(context, fe)
+            }
+        }
+        E::TestVariant(_, _, _, bs, er) => {
+            types(context, bs);
+            exp(context, er)
+        }
         E::ExpList(el) => exp_list(context, el),
         E::Cast(el, rhs_ty) | E::Annotate(el, rhs_ty) => {
             exp(context, el);
@@ -300,7 +327,10 @@ fn lvalue(context: &mut Context, b: &mut T::LValue) {
         L::Var(_, ty) => {
             type_(context, ty);
         }
-        L::BorrowUnpack(_, _, _, bts, fields) | L::Unpack(_, _, bts, fields) => {
+        L::BorrowUnpack(_, _, _, bts, fields)
+        | L::Unpack(_, _, bts, fields)
+        | L::BorrowUnpackVariant(_, _, _, _, bts, fields)
+        | L::UnpackVariant(_, _, _, bts, fields) => {
             types(context, bts);
             for (_, _, (_, (bt, innerb))) in fields.iter_mut() {
                 type_(context, bt);
@@ -127,6 +127,12 @@ fn exp(
             exp(context, annotated_acquires, seen, eloop);
         }
         E::Loop { body: eloop, .. } => exp(context, annotated_acquires, seen, eloop),
+        E::Match(esubject, arms) => {
+            exp(context, annotated_acquires, seen, esubject);
+            for sp!(_, (_, earm)) in arms {
+                exp(context, annotated_acquires, seen, earm)
+            }
+        }
         E::Block(seq) => sequence(context, annotated_acquires, seen, seq),
         E::Assign(_, _, er) => {
             exp(context, annotated_acquires, seen, er);
@@ -137,13 +143,16 @@ fn exp(
         | E::Dereference(er)
         | E::UnaryExp(_, er)
         | E::Borrow(_, er, _)
-        | E::TempBorrow(_, er) => exp(context, annotated_acquires, seen, er),
+        | E::TempBorrow(_, er)
+        | E::TestVariant(_, _, _, _, er)
+        | E::VarCall(_, er)
+        | E::Lambda(_, er) => exp(context, annotated_acquires, seen, er),
         E::Mutate(el, er) | E::BinopExp(el, _, _, er) => {
             exp(context, annotated_acquires, seen, el);
             exp(context, annotated_acquires, seen, er)
         }
 
-        E::Pack(_, _, _, fields) => {
+        E::Pack(_, _, _, fields) | E::PackVariant(_, _, _, _, fields) => {
             for (_, _, (_, (_, fe))) in fields {
                 exp(context, annotated_acquires, seen, fe)
             }
@@ -268,7 +277,7 @@ where
         T::Anything | T::UnresolvedError => {
             return None;
         }
-        T::Ref(_, _) | T::Unit => {
+        T::Ref(_, _) | T::Unit | T::Fun(_, _) => {
             // Key ability is checked by constraints, and these types do not have Key
             assert!(context.env.has_errors());
             return None;
@@ -94,6 +94,15 @@ impl<'a> Context<'a> {
                 tys.iter()
                     .for_each(|t| Self::add_tparam_edges(acc, tparam, info.clone(), t))
             }
+            Fun(args, result) => {
+                let info = EdgeInfo {
+                    edge: Edge::Nested,
+                    ..info
+                };
+                args.iter()
+                    .chain(std::iter::once(&**result))
+                    .for_each(|t| Self::add_tparam_edges(acc, tparam, info.clone(), t))
+            }
             Param(tp) => {
                 let tp_neighbors = acc.entry(tp.clone()).or_insert_with(BTreeMap::new);
                 match tp_neighbors.get(tparam) {
@@ -239,6 +248,12 @@ fn exp(context: &mut Context, e: &T::Exp) {
             exp(context, eloop);
         }
         E::Loop { body: eloop, .. } => exp(context, eloop),
+        E::Match(esubject, arms) => {
+            exp(context, esubject);
+            for sp!(_, (_, earm)) in arms {
+                exp(context, earm)
+            }
+        }
         E::Block(seq) => sequence(context, seq),
         E::Assign(_, _, er) => exp(context, er),
 
@@ -249,13 +264,16 @@ fn exp(context: &mut Context, e: &T::Exp) {
         | E::Dereference(er)
         | E::UnaryExp(_, er)
         | E::Borrow(_, er, _)
-        | E::TempBorrow(_, er) => exp(context, er),
+        | E::TempBorrow(_, er)
+        | E::TestVariant(_, _, _, _, er)
+        | E::VarCall(_, er)
+        | E::Lambda(_, er) => exp(context, er),
         E::Mutate(el, er) | E::BinopExp(el, _, _, er) => {
             exp(context, el);
             exp(context, er)
         }
 
-        E::Pack(_, _, _, fields) => {
+        E::Pack(_, _, _, fields) | E::PackVariant(_, _, _, _, fields) => {
             for (_, _, (_, (_, fe))) in fields.iter() {
                 exp(context, fe)
             }
@@ -0,0 +1,760 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Expands calls to `inline` functions at their call sites. The body of the inline function is
+//! copied into the caller, with its parameters bound to the arguments of the call, and each call
+//! of a lambda-typed parameter replaced by the body of the lambda passed for it. Inline functions
+//! are not compiled on their own, so no bytecode is generated for them.
+
+use super::core::{self, Subst, TParamSubst};
+use crate::{
+    diag,
+    expansion::ast::ModuleIdent,
+    naming::ast::{TParam, Type, TypeName_, Type_},
+    parser::ast::{FunctionName, StructName, Var},
+    shared::{unique_map::UniqueMap, CompilationEnv},
+    typing::ast as T,
+    FullyCompiledProgram,
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+//**************************************************************************************************
+// Context
+//**************************************************************************************************
+
+struct InlineFunction {
+    type_parameters: Vec<TParam>,
+    parameters: Vec<(Var, Type)>,
+    body: T::Sequence,
+}
+
+struct Context<'a, 'env> {
+    core: &'a mut core::Context<'env>,
+    inline_functions: BTreeMap<(ModuleIdent, FunctionName), InlineFunction>,
+    // The inline functions currently being expanded, used to detect recursive inline functions
+    inline_stack: Vec<(ModuleIdent, FunctionName)>,
+    counter: usize,
+}
+
+impl<'a, 'env> Context<'a, 'env> {
+    fn new(
+        core: &'a mut core::Context<'env>,
+        pre_compiled_lib: Option<&FullyCompiledProgram>,
+        modules: &UniqueMap<ModuleIdent, T::ModuleDefinition>,
+    ) -> Self {
+        let mut inline_functions = BTreeMap::new();
+        let pre_compiled_modules = pre_compiled_lib
+            .iter()
+            .flat_map(|pre_compiled| pre_compiled.typing.modules.key_cloned_iter())
+            .filter(|(mident, _)| !modules.contains_key(mident));
+        for (mident, mdef) in modules.key_cloned_iter().chain(pre_compiled_modules) {
+            for (f, fdef) in mdef.functions.key_cloned_iter() {
+                if fdef.inline.is_none() {
+                    continue;
+                }
+                if let T::FunctionBody_::Defined(seq) = &fdef.body.value {
+                    let inline_function = InlineFunction {
+                        type_parameters: fdef.signature.type_parameters.clone(),
+                        parameters: fdef.signature.parameters.clone(),
+                        body: seq.clone(),
+                    };
+                    inline_functions.insert((mident, f), inline_function);
+                }
+            }
+        }
+        Context {
+            core,
+            inline_functions,
+            inline_stack: vec![],
+            counter: 0,
+        }
+    }
+
+    fn is_inline_function(&self, m: &ModuleIdent, f: &FunctionName) -> bool {
+        self.inline_functions.contains_key(&(*m, *f))
+    }
+
+    fn fresh_var(&mut self, Var(sp!(loc, v_)): Var) -> Var {
+        self.counter += 1;
+        Var(sp(loc, format!("{}#inline{}", v_, self.counter).into()))
+    }
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub fn program(
+    compilation_env: &mut CompilationEnv,
+    pre_compiled_lib: Option<&FullyCompiledProgram>,
+    prog: &mut T::Program,
+) {
+    let mut core_context =
+        core::Context::new_for_typed_program(compilation_env, pre_compiled_lib, prog);
+    let T::Program { modules, scripts } = prog;
+    let mut context = Context::new(&mut core_context, pre_compiled_lib, modules);
+    if context.inline_functions.is_empty() {
+        return;
+    }
+    for (loc, mident_, mdef) in modules.iter_mut() {
+        let mident = sp(loc, *mident_);
+        for (_, _, fdef) in mdef.functions.iter_mut() {
+            if fdef.inline.is_none() {
+                function_body(&mut context, Some(mident), &mut fdef.body)
+            }
+        }
+    }
+    for script in scripts.values_mut() {
+        function_body(&mut context, None, &mut script.function.body)
+    }
+}
+
+fn function_body(context: &mut Context, mident: Option<ModuleIdent>, body: &mut T::FunctionBody) {
+    context.core.current_module = mident;
+    if let T::FunctionBody_::Defined(seq) = &mut body.value {
+        sequence(context, seq)
+    }
+}
+
+//**************************************************************************************************
+// Expansion of calls
+//**************************************************************************************************
+
+fn sequence(context: &mut Context, seq: &mut T::Sequence) {
+    for sp!(_, item_) in seq {
+        match item_ {
+            T::SequenceItem_::Seq(e) | T::SequenceItem_::Bind(_, _, e) => exp(context, e),
+            T::SequenceItem_::Declare(_) => (),
+        }
+    }
+}
+
+fn exp(context: &mut Context, e: &mut T::Exp) {
+    use T::UnannotatedExp_ as E;
+    match &mut e.exp.value {
+        E::ModuleCall(call) if context.is_inline_function(&call.module, &call.name) => {
+            inline_call(context, e)
+        }
+        E::VarCall(_, _) | E::Lambda(_, _) => invalid_lambda(context, e),
+
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Constant(_, _)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::Use(_)
+        | E::BorrowLocal(_, _)
+        | E::Break
+        | E::Continue
+        | E::Spec(_, _)
+        | E::UnresolvedError => (),
+
+        E::ModuleCall(call) => exp(context, &mut call.arguments),
+        E::Builtin(_, er)
+        | E::Vector(_, _, _, er)
+        | E::Loop { body: er, .. }
+        | E::Assign(_, _, er)
+        | E::Return(er)
+        | E::Abort(er)
+        | E::Dereference(er)
+        | E::UnaryExp(_, er)
+        | E::Borrow(_, er, _)
+        | E::TempBorrow(_, er)
+        | E::TestVariant(_, _, _, _, er)
+        | E::Cast(er, _)
+        | E::Annotate(er, _) => exp(context, er),
+        E::IfElse(eb, et, ef) => {
+            exp(context, eb);
+            exp(context, et);
+            exp(context, ef)
+        }
+        E::While(el, er) | E::Mutate(el, er) | E::BinopExp(el, _, _, er) => {
+            exp(context, el);
+            exp(context, er)
+        }
+        E::Match(esubject, arms) => {
+            exp(context, esubject);
+            for sp!(_, (_, earm)) in arms {
+                exp(context, earm)
+            }
+        }
+        E::Block(seq) => sequence(context, seq),
+        E::Pack(_, _, _, fields) | E::PackVariant(_, _, _, _, fields) => {
+            for (_, _, (_, (_, fe))) in fields.iter_mut() {
+                exp(context, fe)
+            }
+        }
+        E::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        exp(context, e)
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn invalid_lambda(context: &mut Context, e: &mut T::Exp) {
+    let msg = match &e.exp.value {
+        T::UnannotatedExp_::VarCall(v, _) => format!(
+            "Invalid call of '{}'. Only function-typed parameters of 'inline' functions can be \
+             called",
+            v
+        ),
+        _ => "Invalid lambda. Function-typed parameters of 'inline' functions can only be called \
+              or passed directly to other 'inline' functions"
+            .to_owned(),
+    };
+    context
+        .core
+        .env
+        .add_diag(diag!(TypeSafety::InvalidLambda, (e.exp.loc, msg)));
+    e.exp.value = T::UnannotatedExp_::UnresolvedError
+}
+
+// Lambdas are allowed directly as arguments to inline functions. Their bodies are expanded
+// before they are substituted into the body of the callee
+fn inline_call_arguments(context: &mut Context, args: &mut T::Exp) {
+    match &mut args.exp.value {
+        T::UnannotatedExp_::Lambda(_, body) => exp(context, body),
+        T::UnannotatedExp_::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(
+                        T::Exp {
+                            exp: sp!(_, T::UnannotatedExp_::Lambda(_, body)),
+                            ..
+                        },
+                        _,
+                    ) => exp(context, body),
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        exp(context, e)
+                    }
+                }
+            }
+        }
+        _ => exp(context, args),
+    }
+}
+
+fn inline_call(context: &mut Context, e: &mut T::Exp) {
+    let eloc = e.exp.loc;
+    let call = match std::mem::replace(&mut e.exp.value, T::UnannotatedExp_::UnresolvedError) {
+        T::UnannotatedExp_::ModuleCall(call) => call,
+        _ => unreachable!(),
+    };
+    let T::ModuleCall {
+        module,
+        name,
+        type_arguments,
+        mut arguments,
+        parameter_types,
+        acquires: _,
+    } = *call;
+    let key = (module, name);
+    if let Some(idx) = context.inline_stack.iter().position(|k| k == &key) {
+        let cycle = context.inline_stack[idx..]
+            .iter()
+            .map(|(m, f)| format!("'{}::{}'", m, f))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        let msg = format!(
+            "Invalid call of 'inline' function '{}::{}'. Recursive 'inline' functions cannot be \
+             expanded: {} -> '{}::{}'",
+            module, name, cycle, module, name
+        );
+        context
+            .core
+            .env
+            .add_diag(diag!(TypeSafety::InvalidInline, (eloc, msg)));
+        return;
+    }
+
+    inline_call_arguments(context, &mut arguments);
+    let args = match split_arguments(*arguments, parameter_types.len()) {
+        Some(args) => args,
+        None => {
+            assert!(context.core.env.has_errors());
+            return;
+        }
+    };
+    let finfo = &context.inline_functions[&key];
+    let tparam_subst = core::make_tparam_subst(&finfo.type_parameters, type_arguments);
+    let parameters = finfo.parameters.clone();
+    let mut body = finfo.body.clone();
+
+    let mut subst = Substitution::new(eloc, module, name, tparam_subst);
+    let mut bound_params = vec![];
+    for (((param, _), param_ty), arg) in parameters.into_iter().zip(parameter_types).zip(args) {
+        if matches!(&param_ty.value, Type_::Fun(_, _)) {
+            subst.locals.insert(param.0.value, Local::Lambda(arg));
+        } else {
+            let new_param = context.fresh_var(param);
+            subst
+                .locals
+                .insert(param.0.value, Local::Renamed(new_param));
+            bound_params.push((new_param, param_ty, arg))
+        }
+    }
+    subst.sequence(context, &mut body);
+
+    // Unused parameters are bound to '_', as unused variables in the callee would otherwise be
+    // reported at every call site
+    let mut lvalues = vec![];
+    let mut tys = vec![];
+    let mut bound_args = vec![];
+    for (new_param, param_ty, arg) in bound_params {
+        let loc = new_param.0.loc;
+        let lvalue_ = if subst.used.contains(&new_param.0.value) {
+            T::LValue_::Var(new_param, Box::new(param_ty.clone()))
+        } else {
+            T::LValue_::Ignore
+        };
+        lvalues.push(sp(loc, lvalue_));
+        tys.push(Some(param_ty));
+        bound_args.push(arg);
+    }
+    if !lvalues.is_empty() {
+        let args = make_arguments(context, eloc, bound_args);
+        let bind = T::SequenceItem_::Bind(sp(eloc, lvalues), tys, Box::new(args));
+        body.push_front(sp(eloc, bind));
+    }
+    e.exp.value = T::UnannotatedExp_::Block(body);
+
+    // expand the inline calls in the body of the callee
+    context.inline_stack.push(key);
+    exp(context, e);
+    context.inline_stack.pop();
+}
+
+fn split_arguments(arguments: T::Exp, arity: usize) -> Option<Vec<T::Exp>> {
+    use T::UnannotatedExp_ as E;
+    match (arity, arguments.exp.value) {
+        (0, E::Unit { .. }) => Some(vec![]),
+        (0, _) => None,
+        (1, arg_) => Some(vec![T::exp(arguments.ty, sp(arguments.exp.loc, arg_))]),
+        (n, E::ExpList(items)) if items.len() == n => items
+            .into_iter()
+            .map(|item| match item {
+                T::ExpListItem::Single(e, _) => Some(e),
+                T::ExpListItem::Splat(_, _, _) => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+fn make_arguments(context: &Context, loc: Loc, mut args: Vec<T::Exp>) -> T::Exp {
+    if args.len() == 1 {
+        return args.pop().unwrap();
+    }
+    let tys = args.iter().map(|e| e.ty.clone()).collect();
+    let items = args.into_iter().map(T::single_item).collect();
+    let ty = match Type_::multiple(loc, tys) {
+        sp!(tloc, Type_::Apply(None, n, tys)) => {
+            let abilities = core::infer_abilities(
+                context.core,
+                &Subst::empty(),
+                sp(tloc, Type_::Apply(None, n.clone(), tys.clone())),
+            );
+            sp(tloc, Type_::Apply(Some(abilities), n, tys))
+        }
+        _ => unreachable!(),
+    };
+    T::exp(ty, sp(loc, T::UnannotatedExp_::ExpList(items)))
+}
+
+//**************************************************************************************************
+// Substitution
+//**************************************************************************************************
+
+#[derive(Clone)]
+enum Local {
+    Renamed(Var),
+    Lambda(T::Exp),
+}
+
+// Substitutes the body of an inline function: locals are renamed, calls of lambda parameters
+// are replaced by the lambda bodies, and type parameters by the type arguments of the call
+struct Substitution {
+    call_loc: Loc,
+    module: ModuleIdent,
+    function: FunctionName,
+    tparam_subst: TParamSubst,
+    locals: BTreeMap<Symbol, Local>,
+    used: BTreeSet<Symbol>,
+}
+
+impl Substitution {
+    fn new(
+        call_loc: Loc,
+        module: ModuleIdent,
+        function: FunctionName,
+        tparam_subst: TParamSubst,
+    ) -> Self {
+        Self {
+            call_loc,
+            module,
+            function,
+            tparam_subst,
+            locals: BTreeMap::new(),
+            used: BTreeSet::new(),
+        }
+    }
+
+    fn local(&mut self, v: &Var) -> Option<Local> {
+        let local = self.locals.get(&v.0.value).cloned();
+        if let Some(Local::Renamed(new_v)) = &local {
+            self.used.insert(new_v.0.value);
+        }
+        local
+    }
+
+    //**********************************************************************************************
+    // Types
+    //**********************************************************************************************
+
+    fn type_(&self, context: &Context, ty: &mut Type) {
+        if self.tparam_subst.is_empty() {
+            return;
+        }
+        let loc = ty.loc;
+        match &mut ty.value {
+            Type_::Param(tp) => {
+                if let Some(t) = self.tparam_subst.get(&tp.id) {
+                    *ty = sp(loc, t.value.clone())
+                }
+            }
+            Type_::Ref(_, t) => self.type_(context, t),
+            Type_::Fun(args, result) => {
+                self.types(context, args);
+                self.type_(context, result)
+            }
+            Type_::Apply(abilities, n, tys) => {
+                self.types(context, tys);
+                // The abilities of the type might depend on the substituted type arguments
+                let is_primitive = matches!(&n.value, TypeName_::Builtin(_)) && tys.is_empty();
+                if !is_primitive {
+                    let unannotated = sp(loc, Type_::Apply(None, n.clone(), tys.clone()));
+                    *abilities = Some(core::infer_abilities(
+                        context.core,
+                        &Subst::empty(),
+                        unannotated,
+                    ))
+                }
+            }
+            Type_::Unit | Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => (),
+        }
+    }
+
+    fn types(&self, context: &Context, tys: &mut [Type]) {
+        for ty in tys {
+            self.type_(context, ty)
+        }
+    }
+
+    //**********************************************************************************************
+    // Access
+    //**********************************************************************************************
+
+    fn check_struct_access(
+        &self,
+        context: &mut Context,
+        loc: Loc,
+        m: &ModuleIdent,
+        s: &StructName,
+    ) {
+        if !context.core.is_current_module(m) {
+            let item = format!("uses the internals of the struct '{}::{}'", m, s);
+            self.report_access(context, loc, item)
+        }
+    }
+
+    fn check_type_access(&self, context: &mut Context, loc: Loc, ty: &Type) {
+        if let Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, s)), _) = &ty.value {
+            if !context.core.is_current_module(m) {
+                let item = format!("uses global storage operations on '{}::{}'", m, s);
+                self.report_access(context, loc, item)
+            }
+        }
+    }
+
+    fn check_call_access(
+        &self,
+        context: &mut Context,
+        loc: Loc,
+        m: &ModuleIdent,
+        f: &FunctionName,
+    ) {
+        if !context.core.is_function_visible(m, f) {
+            let item = format!("calls '{}::{}'", m, f);
+            self.report_access(context, loc, item)
+        }
+    }
+
+    fn report_access(&self, context: &mut Context, loc: Loc, item: String) {
+        let target = match &context.core.current_module {
+            Some(m) => format!("module '{}'", m),
+            None => "a script".to_owned(),
+        };
+        let msg = format!(
+            "Invalid call of 'inline' function '{}::{}'. Its body {}, which is not accessible \
+             from {}",
+            self.module, self.function, item, target
+        );
+        context.core.env.add_diag(diag!(
+            TypeSafety::Visibility,
+            (self.call_loc, msg),
+            (loc, "Used here in the 'inline' function")
+        ));
+    }
+
+    //**********************************************************************************************
+    // Expressions
+    //**********************************************************************************************
+
+    fn sequence(&mut self, context: &mut Context, seq: &mut T::Sequence) {
+        let old_locals = self.locals.clone();
+        for sp!(_, item_) in seq.iter_mut() {
+            match item_ {
+                T::SequenceItem_::Seq(e) => self.exp(context, e),
+                T::SequenceItem_::Declare(lvalues) => self.lvalues(context, true, lvalues),
+                T::SequenceItem_::Bind(lvalues, tys, e) => {
+                    self.exp(context, e);
+                    for ty in tys.iter_mut().flatten() {
+                        self.type_(context, ty)
+                    }
+                    self.lvalues(context, true, lvalues)
+                }
+            }
+        }
+        self.locals = old_locals;
+    }
+
+    fn exp(&mut self, context: &mut Context, e: &mut T::Exp) {
+        use T::UnannotatedExp_ as E;
+        self.type_(context, &mut e.ty);
+        let eloc = e.exp.loc;
+        match &mut e.exp.value {
+            E::Unit { .. } | E::Value(_) | E::Break | E::Continue | E::UnresolvedError => (),
+            E::Constant(m_opt, c) => {
+                if matches!(m_opt, Some(m) if !context.core.is_current_module(m)) {
+                    let item = format!("uses the constant '{}::{}'", m_opt.unwrap(), c);
+                    self.report_access(context, eloc, item)
+                }
+            }
+            E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) => match self.local(var) {
+                Some(Local::Lambda(lambda)) => *e = lambda,
+                Some(Local::Renamed(new_var)) => *var = new_var,
+                None => (),
+            },
+            E::BorrowLocal(_, var) => match self.local(var) {
+                Some(Local::Lambda(_)) => panic!("ICE borrow of lambda should fail in typing"),
+                Some(Local::Renamed(new_var)) => *var = new_var,
+                None => (),
+            },
+            E::ModuleCall(call) => {
+                self.check_call_access(context, eloc, &call.module, &call.name);
+                self.types(context, &mut call.type_arguments);
+                self.types(context, &mut call.parameter_types);
+                self.exp(context, &mut call.arguments)
+            }
+            E::VarCall(_, _) => self.var_call(context, e),
+            E::Builtin(b, args) => {
+                match &mut b.value {
+                    T::BuiltinFunction_::MoveTo(ty)
+                    | T::BuiltinFunction_::MoveFrom(ty)
+                    | T::BuiltinFunction_::BorrowGlobal(_, ty)
+                    | T::BuiltinFunction_::Exists(ty) => {
+                        self.type_(context, ty);
+                        self.check_type_access(context, eloc, ty)
+                    }
+                    T::BuiltinFunction_::Freeze(ty) => self.type_(context, ty),
+                    T::BuiltinFunction_::Assert(_) => (),
+                }
+                self.exp(context, args)
+            }
+            E::Vector(_, _, ty, args) => {
+                self.type_(context, ty);
+                self.exp(context, args)
+            }
+            E::IfElse(eb, et, ef) => {
+                self.exp(context, eb);
+                self.exp(context, et);
+                self.exp(context, ef)
+            }
+            E::While(el, er) | E::Mutate(el, er) => {
+                self.exp(context, el);
+                self.exp(context, er)
+            }
+            E::BinopExp(el, _, ty, er) => {
+                self.exp(context, el);
+                self.type_(context, ty);
+                self.exp(context, er)
+            }
+            E::Loop { body: er, .. }
+            | E::Return(er)
+            | E::Abort(er)
+            | E::Dereference(er)
+            | E::UnaryExp(_, er)
+            | E::TempBorrow(_, er) => self.exp(context, er),
+            E::Borrow(_, er, _) => {
+                self.exp(context, er);
+                if let Some((m, s)) = struct_name(&er.ty) {
+                    self.check_struct_access(context, eloc, &m, &s)
+                }
+            }
+            E::Match(esubject, arms) => {
+                self.exp(context, esubject);
+                for sp!(_, (pattern, earm)) in arms {
+                    let old_locals = self.locals.clone();
+                    self.lvalue(context, true, pattern);
+                    self.exp(context, earm);
+                    self.locals = old_locals;
+                }
+            }
+            E::Block(seq) => self.sequence(context, seq),
+            E::Lambda(lvalues, body) => {
+                let old_locals = self.locals.clone();
+                self.lvalues(context, true, lvalues);
+                self.exp(context, body);
+                self.locals = old_locals;
+            }
+            E::Assign(lvalues, tys, er) => {
+                self.exp(context, er);
+                for ty in tys.iter_mut().flatten() {
+                    self.type_(context, ty)
+                }
+                self.lvalues(context, false, lvalues)
+            }
+            E::Pack(m, s, tys, fields) | E::PackVariant(m, s, _, tys, fields) => {
+                self.check_struct_access(context, eloc, m, s);
+                self.types(context, tys);
+                for (_, _, (_, (ty, fe))) in fields.iter_mut() {
+                    self.type_(context, ty);
+                    self.exp(context, fe)
+                }
+            }
+            E::TestVariant(m, s, _, tys, er) => {
+                self.check_struct_access(context, eloc, m, s);
+                self.types(context, tys);
+                self.exp(context, er)
+            }
+            E::ExpList(items) => {
+                for item in items {
+                    match item {
+                        T::ExpListItem::Single(e, ty) => {
+                            self.exp(context, e);
+                            self.type_(context, ty)
+                        }
+                        T::ExpListItem::Splat(_, e, tys) => {
+                            self.exp(context, e);
+                            self.types(context, tys)
+                        }
+                    }
+                }
+            }
+            E::Cast(er, ty) | E::Annotate(er, ty) => {
+                self.exp(context, er);
+                self.type_(context, ty)
+            }
+            // Specifications are not checked for inline functions
+            E::Spec(_, _) => e.exp.value = E::Unit { trailing: false },
+        }
+    }
+
+    // A call of a lambda parameter is replaced by a block binding the arguments to the
+    // parameters of the lambda, followed by its body
+    fn var_call(&mut self, context: &mut Context, e: &mut T::Exp) {
+        let (var, args) = match &mut e.exp.value {
+            T::UnannotatedExp_::VarCall(var, args) => (var, args),
+            _ => unreachable!(),
+        };
+        self.exp(context, args);
+        match self.local(var) {
+            Some(Local::Lambda(T::Exp {
+                exp: sp!(_, T::UnannotatedExp_::Lambda(lvalues, body)),
+                ..
+            })) => {
+                let args = std::mem::replace(
+                    args.as_mut(),
+                    T::exp(
+                        sp(e.exp.loc, Type_::Unit),
+                        sp(e.exp.loc, T::UnannotatedExp_::Unit { trailing: false }),
+                    ),
+                );
+                let mut seq = VecDeque::new();
+                if !lvalues.value.is_empty() {
+                    let tys = lvalues.value.iter().map(lvalue_type).collect();
+                    let bind = T::SequenceItem_::Bind(lvalues, tys, Box::new(args));
+                    seq.push_back(sp(e.exp.loc, bind));
+                }
+                seq.push_back(sp(body.exp.loc, T::SequenceItem_::Seq(body)));
+                e.exp.value = T::UnannotatedExp_::Block(seq)
+            }
+            Some(Local::Lambda(lambda)) => {
+                // the lambda was not valid, and an error has already been reported
+                assert!(
+                    matches!(lambda.exp.value, T::UnannotatedExp_::UnresolvedError)
+                        || context.core.env.has_errors()
+                );
+                e.exp.value = T::UnannotatedExp_::UnresolvedError
+            }
+            Some(Local::Renamed(new_var)) => *var = new_var,
+            None => (),
+        }
+    }
+
+    fn lvalues(&mut self, context: &mut Context, declare: bool, lvalues: &mut T::LValueList) {
+        for lvalue in &mut lvalues.value {
+            self.lvalue(context, declare, lvalue)
+        }
+    }
+
+    fn lvalue(&mut self, context: &mut Context, declare: bool, sp!(loc, lvalue_): &mut T::LValue) {
+        use T::LValue_ as L;
+        match lvalue_ {
+            L::Ignore => (),
+            L::Var(var, ty) => {
+                self.type_(context, ty);
+                if declare {
+                    let new_var = context.fresh_var(*var);
+                    self.locals.insert(var.0.value, Local::Renamed(new_var));
+                    *var = new_var
+                } else if let Some(Local::Renamed(new_var)) = self.local(var) {
+                    *var = new_var
+                }
+            }
+            L::Unpack(m, s, tys, fields)
+            | L::BorrowUnpack(_, m, s, tys, fields)
+            | L::UnpackVariant(m, s, _, tys, fields)
+            | L::BorrowUnpackVariant(_, m, s, _, tys, fields) => {
+                self.check_struct_access(context, *loc, m, s);
+                self.types(context, tys);
+                for (_, _, (_, (ty, lvalue))) in fields.iter_mut() {
+                    self.type_(context, ty);
+                    self.lvalue(context, declare, lvalue)
+                }
+            }
+        }
+    }
+}
+
+fn struct_name(ty: &Type) -> Option<(ModuleIdent, StructName)> {
+    match &ty.value {
+        Type_::Ref(_, t) => struct_name(t),
+        Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, s)), _) => Some((*m, *s)),
+        _ => None,
+    }
+}
+
+fn lvalue_type(sp!(_, lvalue_): &T::LValue) -> Option<Type> {
+    match lvalue_ {
+        T::LValue_::Var(_, ty) => Some(*ty.clone()),
+        _ => None,
+    }
+}
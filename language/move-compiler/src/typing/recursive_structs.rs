@@ -89,6 +89,10 @@ fn struct_def(context: &mut Context, sname: StructName, sdef: &N::StructDefiniti
         N::StructFields::Defined(fields) => {
             fields.iter().for_each(|(_, _, (_, ty))| type_(context, ty))
         }
+        N::StructFields::Variants(variants) => variants
+            .iter()
+            .flat_map(|(_, _, (_, fields))| fields.iter())
+            .for_each(|(_, _, (_, ty))| type_(context, ty)),
     };
     context.current_struct = None;
 }
@@ -99,6 +103,10 @@ fn type_(context: &mut Context, sp!(loc, ty_): &N::Type) {
         Var(_) => panic!("ICE tvar in struct field type"),
         Unit | Anything | UnresolvedError | Param(_) => (),
         Ref(_, t) => type_(context, t),
+        Fun(args, result) => {
+            args.iter().for_each(|t| type_(context, t));
+            type_(context, result)
+        }
         Apply(_, sp!(_, tn_), tys) => {
             if let TypeName_::ModuleType(m, s) = tn_ {
                 context.add_usage(*loc, m, s)
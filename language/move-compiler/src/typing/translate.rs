@@ -11,7 +11,9 @@ use crate::{
     diagnostics::{codes::*, Diagnostic},
     expansion::ast::{Fields, ModuleIdent, Value_},
     naming::ast::{self as N, TParam, TParamID, Type, TypeName_, Type_},
-    parser::ast::{Ability_, BinOp_, ConstantName, Field, FunctionName, StructName, UnaryOp_, Var},
+    parser::ast::{
+        Ability_, BinOp_, ConstantName, Field, FunctionName, StructName, UnaryOp_, Var, VariantName,
+    },
     shared::{unique_map::UniqueMap, *},
     typing::ast as T,
     FullyCompiledProgram,
@@ -135,6 +137,7 @@ fn function(
         attributes,
         visibility,
         entry,
+        inline,
         mut signature,
         body: n_body,
         acquires,
@@ -168,6 +171,7 @@ fn function(
         attributes,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -233,9 +237,10 @@ fn function_body(
 // Constants
 //**************************************************************************************************
 
-fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant) -> T::Constant {
+fn constant(context: &mut Context, name: ConstantName, nconstant: N::Constant) -> T::Constant {
     assert!(context.constraints.is_empty());
     context.reset_for_module_item();
+    context.current_constant = Some(name);
 
     let N::Constant {
         attributes,
@@ -270,6 +275,7 @@ fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant)
     expand::exp(context, &mut value);
 
     check_valid_constant::exp(context, &value);
+    context.current_constant = None;
 
     T::Constant {
         attributes,
@@ -364,7 +370,9 @@ mod check_valid_constant {
             //*****************************************
             // Valid cases
             //*****************************************
-            E::Unit { .. } | E::Value(_) | E::Move { .. } | E::Copy { .. } => return,
+            E::Unit { .. } | E::Value(_) | E::Move { .. } | E::Copy { .. } | E::Constant(_, _) => {
+                return
+            }
             E::Block(seq) => {
                 sequence(context, seq);
                 return;
@@ -447,7 +455,27 @@ mod check_valid_constant {
                 }
                 "Structs are"
             }
-            E::Constant(_, _) => "Other constants are",
+            E::PackVariant(_, _, _, _, fields) => {
+                for (_, _, (_, (_, fe))) in fields {
+                    exp(context, fe)
+                }
+                "Enums are"
+            }
+            E::TestVariant(_, _, _, _, er) => {
+                exp(context, er);
+                "Enums are"
+            }
+            E::VarCall(_, er) | E::Lambda(_, er) => {
+                exp(context, er);
+                "Lambdas are"
+            }
+            E::Match(esubject, arms) => {
+                exp(context, esubject);
+                for sp!(_, (_, earm)) in arms {
+                    exp(context, earm)
+                }
+                "'match' expressions are"
+            }
         };
         context.env.add_diag(diag!(
             TypeSafety::UnsupportedConstant,
@@ -508,16 +536,25 @@ fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
     assert!(context.constraints.is_empty());
     context.reset_for_module_item();
 
-    let field_map = match &mut s.fields {
+    let (kind, mut field_maps) = match &mut s.fields {
         N::StructFields::Native(_) => return,
-        N::StructFields::Defined(m) => m,
+        N::StructFields::Defined(m) => ("struct", vec![m]),
+        N::StructFields::Variants(variants) => (
+            "enum",
+            variants
+                .iter_mut()
+                .map(|(_, _, (_, fields))| fields)
+                .collect::<Vec<_>>(),
+        ),
     };
 
     // instantiate types and check constraints
-    for (_field_loc, _field, idx_ty) in field_map.iter() {
-        let loc = idx_ty.1.loc;
-        let inst_ty = core::instantiate(context, idx_ty.1.clone());
-        context.add_base_type_constraint(loc, "Invalid field type", inst_ty.clone());
+    for field_map in &field_maps {
+        for (_field_loc, _field, idx_ty) in field_map.iter() {
+            let loc = idx_ty.1.loc;
+            let inst_ty = core::instantiate(context, idx_ty.1.clone());
+            context.add_base_type_constraint(loc, "Invalid field type", inst_ty.clone());
+        }
     }
     core::solve_constraints(context);
 
@@ -530,36 +567,42 @@ fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
             .iter()
             .map(|tp| sp(tp.param.user_specified_name.loc, Type_::Anything)),
     );
-    for (_field_loc, _field, idx_ty) in field_map.iter() {
-        let loc = idx_ty.1.loc;
-        let subst_ty = core::subst_tparams(tparam_subst, idx_ty.1.clone());
-        for declared_ability in declared_abilities {
-            let required = declared_ability.value.requires();
-            let msg = format!(
-                "Invalid field type. The struct was declared with the ability '{}' so all fields \
-                 require the ability '{}'",
-                declared_ability, required
-            );
-            context.add_ability_constraint(loc, Some(msg), subst_ty.clone(), required)
+    for field_map in &field_maps {
+        for (_field_loc, _field, idx_ty) in field_map.iter() {
+            let loc = idx_ty.1.loc;
+            let subst_ty = core::subst_tparams(tparam_subst, idx_ty.1.clone());
+            for declared_ability in declared_abilities {
+                let required = declared_ability.value.requires();
+                let msg = format!(
+                    "Invalid field type. The {} was declared with the ability '{}' so all fields \
+                     require the ability '{}'",
+                    kind, declared_ability, required
+                );
+                context.add_ability_constraint(loc, Some(msg), subst_ty.clone(), required)
+            }
         }
     }
     core::solve_constraints(context);
 
-    for (_field_loc, _field_, idx_ty) in field_map.iter_mut() {
-        expand::type_(context, &mut idx_ty.1);
+    for field_map in &mut field_maps {
+        for (_field_loc, _field_, idx_ty) in field_map.iter_mut() {
+            expand::type_(context, &mut idx_ty.1);
+        }
     }
 
-    check_type_params_usage(context, &s.type_parameters, field_map);
+    let field_tys = field_maps
+        .iter()
+        .flat_map(|field_map| field_map.iter().map(|(_, _, idx_ty)| &idx_ty.1))
+        .collect::<Vec<_>>();
+    check_type_params_usage(context, &s.type_parameters, &field_tys);
 }
 
 fn check_type_params_usage(
     context: &mut Context,
     type_parameters: &[N::StructTypeParameter],
-    field_map: &Fields<Type>,
+    field_tys: &[&Type],
 ) {
-    let has_unresolved = field_map
-        .iter()
-        .any(|(_, _, ty)| has_unresolved_error_type(&ty.1));
+    let has_unresolved = field_tys.iter().any(|ty| has_unresolved_error_type(ty));
 
     if has_unresolved {
         return;
@@ -574,10 +617,10 @@ fn check_type_params_usage(
         .filter(|ty_param| ty_param.is_phantom)
         .map(|param| param.param.id)
         .collect();
-    for (_, _, idx_ty) in field_map.iter() {
+    for ty in field_tys {
         visit_type_params(
             context,
-            &idx_ty.1,
+            ty,
             ParamPos::FIELD,
             &mut |context, loc, param, pos| {
                 let param_is_phantom = phantom_params.contains(&param.id);
@@ -642,6 +685,12 @@ fn visit_type_params(
         Type_::Ref(_, ty) => {
             visit_type_params(context, ty, ParamPos::NonPhantom(NonPhantomPos::TypeArg), f)
         }
+        // Function types cannot appear in structs, but are treated like tuples
+        Type_::Fun(args, result) => {
+            for ty in args.iter().chain(std::iter::once(&**result)) {
+                visit_type_params(context, ty, ParamPos::NonPhantom(NonPhantomPos::TypeArg), f)
+            }
+        }
         Type_::Apply(_, n, ty_args) => match &n.value {
             // Tuples cannot appear in structs, but we still report them as a non-phantom position
             // for full information.
@@ -733,6 +782,9 @@ fn has_unresolved_error_type(ty: &Type) -> bool {
         Type_::UnresolvedError => true,
         Type_::Ref(_, ty) => has_unresolved_error_type(ty),
         Type_::Apply(_, _, ty_args) => ty_args.iter().any(has_unresolved_error_type),
+        Type_::Fun(args, result) => {
+            args.iter().any(has_unresolved_error_type) || has_unresolved_error_type(result)
+        }
         Type_::Param(_) | Type_::Var(_) | Type_::Anything | Type_::Unit => false,
     }
 }
@@ -1070,6 +1122,16 @@ fn exp_(context: &mut Context, initial_ne: N::Exp) -> T::Exp {
                     let msg = || format!("Incompatible arguments to '{}'", &bop);
                     let context = &mut s.context;
                     let (ty, operand_ty) = match &bop.value {
+                        // In constants, '+' also concatenates byte strings
+                        Add if context.current_constant.is_some()
+                            && core::is_vector_type(&context.subst, &el.ty) =>
+                        {
+                            let msg = || format!("Invalid argument to '{}'", &bop);
+                            let bytes_ty = Type_::vector(bop.loc, Type_::u8(bop.loc));
+                            subtype(context, el.exp.loc, msg, el.ty.clone(), bytes_ty.clone());
+                            subtype(context, er.exp.loc, msg, er.ty.clone(), bytes_ty.clone());
+                            (bytes_ty.clone(), bytes_ty)
+                        }
                         Sub | Add | Mul | Mod | Div => {
                             context.add_numeric_constraint(
                                 el.exp.loc,
@@ -1234,9 +1296,12 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
         }
 
         NE::ModuleCall(m, f, ty_args_opt, sp!(argloc, nargs_)) => {
-            let args = exp_vec(context, nargs_);
-            module_call(context, eloc, m, f, ty_args_opt, argloc, args)
+            module_call(context, eloc, m, f, ty_args_opt, argloc, nargs_)
         }
+        NE::MethodCall(ndotted, n, ty_args_opt, sp!(argloc, nargs_)) => {
+            method_call(context, eloc, ndotted, n, ty_args_opt, argloc, nargs_)
+        }
+        NE::VarCall(var, sp!(argloc, nargs_)) => var_call(context, eloc, var, argloc, nargs_),
         NE::Builtin(b, sp!(argloc, nargs_)) => {
             let args = exp_vec(context, nargs_);
             builtin_call(context, eloc, b, argloc, args)
@@ -1267,6 +1332,7 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             );
             (ty, TE::IfElse(eb, et, ef))
         }
+        NE::Match(nsubject, narms) => match_(context, eloc, nsubject, narms),
         NE::While(nb, nloop) => {
             let eb = exp(context, nb);
             let bloc = eb.exp.loc;
@@ -1289,6 +1355,14 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             let seq = sequence(context, nseq);
             (sequence_type(&seq).clone(), TE::Block(seq))
         }
+        NE::Lambda(_, _) => {
+            let msg = "Invalid lambda. Lambdas can only be passed directly as arguments to \
+                       'inline' functions";
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidLambda, (eloc, msg)));
+            (context.error_type(eloc), TE::UnresolvedError)
+        }
 
         NE::Assign(na, nr) => {
             let er = exp(context, nr);
@@ -1314,6 +1388,7 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
         }
 
         NE::Return(nret) => {
+            check_return_context(context, eloc);
             let eret = exp(context, nret);
             let ret_ty = context.return_type.clone().unwrap();
             subtype(context, eloc, || "Invalid return", eret.ty.clone(), ret_ty);
@@ -1441,6 +1516,12 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             }
             (bt, TE::Pack(m, n, targs, tfields))
         }
+        NE::PackVariant(m, n, v, ty_args_opt, nfields) => {
+            pack_variant(context, eloc, m, n, v, ty_args_opt, nfields)
+        }
+        NE::PackUpdate(m, n, ty_args_opt, nfields, nbase) => {
+            pack_update(context, eloc, m, n, ty_args_opt, nfields, nbase)
+        }
 
         NE::Borrow(mut_, sp!(_, N::ExpDotted_::Exp(ner))) => {
             let er = exp_(context, *ner);
@@ -1536,6 +1617,296 @@ fn loop_body(
     (has_break, ty, eloop)
 }
 
+// Struct updates are typed by desugaring them into a block, once the fields of the struct are
+// known. The given fields are bound first, then the base value is unpacked, ignoring the
+// replaced fields (which thus need 'drop'), and the new value is packed:
+//      S { f: e, ..base }  ~>  { let %f = e; let S { f: _, g: %g } = base; S { f: %f, g: %g } }
+fn pack_update(
+    context: &mut Context,
+    eloc: Loc,
+    m: ModuleIdent,
+    n: StructName,
+    ty_args_opt: Option<Vec<Type>>,
+    nfields: Fields<N::Exp>,
+    nbase: Box<N::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    use N::{Exp_ as NE, LValue_ as NL, SequenceItem_ as NS};
+
+    let declared_fields = match &context.struct_definition(&m, &n).fields {
+        N::StructFields::Defined(fields) => fields.ref_map(|_, (idx, _)| *idx),
+        N::StructFields::Native(_) | N::StructFields::Variants(_) => {
+            // The pack reports the invalid usage
+            exp_(context, *nbase);
+            let e = exp_(context, sp(eloc, NE::Pack(m, n, ty_args_opt, nfields)));
+            return (e.ty, e.exp.value);
+        }
+    };
+    let temp = |f: &Field, loc: Loc| Var(sp(loc, format!("%{}", f).into()));
+
+    let mut given_fields = nfields.into_iter().collect::<Vec<_>>();
+    given_fields.sort_by_key(|(_, (idx, _))| *idx);
+    let mut seq = VecDeque::new();
+    let mut pack_fields = UniqueMap::new();
+    for (f, (idx, ne)) in given_fields {
+        let tmp = temp(&f, f.loc());
+        let bind = sp(f.loc(), vec![sp(f.loc(), NL::Var(tmp))]);
+        seq.push_back(sp(ne.loc, NS::Bind(bind, ne)));
+        pack_fields
+            .add(f, (idx, sp(f.loc(), NE::Use(tmp))))
+            .unwrap();
+    }
+
+    let bloc = nbase.loc;
+    let unpack_fields = declared_fields.map(|f, idx| {
+        let lvalue_ = match pack_fields.get_loc(&f) {
+            Some(given_loc) => sp(*given_loc, NL::Ignore),
+            None => {
+                let tmp = temp(&f, bloc);
+                pack_fields.add(f, (idx, sp(bloc, NE::Use(tmp)))).unwrap();
+                sp(bloc, NL::Var(tmp))
+            }
+        };
+        (idx, lvalue_)
+    });
+    let unpack = NL::Unpack(m, n, ty_args_opt.clone(), unpack_fields);
+    let bind = sp(bloc, vec![sp(bloc, unpack)]);
+    seq.push_back(sp(bloc, NS::Bind(bind, *nbase)));
+
+    let pack = sp(eloc, NE::Pack(m, n, ty_args_opt, pack_fields));
+    seq.push_back(sp(eloc, NS::Seq(pack)));
+    let e = exp_(context, sp(eloc, NE::Block(seq)));
+    (e.ty, e.exp.value)
+}
+
+// Kept out of `exp_inner` to keep its stack frame small, as it is called recursively
+fn pack_variant(
+    context: &mut Context,
+    eloc: Loc,
+    m: ModuleIdent,
+    n: StructName,
+    v: VariantName,
+    ty_args_opt: Option<Vec<N::Type>>,
+    nfields: Fields<N::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    let (bt, targs) = core::make_struct_type(context, eloc, &m, &n, ty_args_opt);
+    let typed_nfields = add_variant_field_types(
+        context,
+        eloc,
+        "argument",
+        &m,
+        &n,
+        &v,
+        targs.clone(),
+        nfields,
+    );
+
+    let tfields = typed_nfields.map(|f, (idx, (fty, narg))| {
+        let arg = exp_(context, narg);
+        subtype(
+            context,
+            arg.exp.loc,
+            || {
+                format!(
+                    "Invalid argument for field '{}' for '{}::{}::{}'",
+                    f, &m, &n, &v
+                )
+            },
+            arg.ty.clone(),
+            fty.clone(),
+        );
+        (idx, (fty, arg))
+    });
+    if !context.is_current_module(&m) {
+        let msg = format!(
+            "Invalid instantiation of '{}::{}::{}'.\nAll enums can only be constructed \
+             in the module in which they are declared",
+            &m, &n, &v,
+        );
+        context
+            .env
+            .add_diag(diag!(TypeSafety::Visibility, (eloc, msg)));
+    }
+    (bt, T::UnannotatedExp_::PackVariant(m, n, v, targs, tfields))
+}
+
+//**************************************************************************************************
+// Match
+//**************************************************************************************************
+
+fn match_(
+    context: &mut Context,
+    eloc: Loc,
+    nsubject: Box<N::Exp>,
+    narms: Vec<N::MatchArm>,
+) -> (Type, T::UnannotatedExp_) {
+    let esubject = exp(context, nsubject);
+    let subject_ty = esubject.ty.clone();
+    let (ref_mut, enum_ty) = match core::unfold_type(&context.subst, subject_ty.clone()).value {
+        Type_::Ref(mut_, inner) => (Some(mut_), *inner),
+        _ => (None, subject_ty.clone()),
+    };
+
+    let mut matched_enum = None;
+    let mut covered: BTreeMap<VariantName, Loc> = BTreeMap::new();
+    let mut catch_all: Option<Loc> = None;
+    let mut has_unbound_variant = false;
+    let mut result_ty: Option<Type> = None;
+    let mut tarms = vec![];
+    for sp!(aloc, (npat, narm)) in narms {
+        let old_locals = context.save_locals_scope();
+        let mut declared = UniqueMap::new();
+        let (tpat, variant_opt) =
+            match_pattern(context, ref_mut, &subject_ty, &enum_ty, &mut declared, npat);
+        let tarm = exp_(context, narm);
+        context.close_locals_scope(old_locals, declared);
+
+        let unreachable_msg = match (&variant_opt, catch_all) {
+            (_, Some(prev)) => Some((prev, "Previous pattern matches all values")),
+            (Some(MatchedVariant::Variant(_, _, v)), None) => covered
+                .get(v)
+                .map(|prev| (*prev, "Variant previously matched here")),
+            _ => None,
+        };
+        if let Some((prev, prev_msg)) = unreachable_msg {
+            context.env.add_diag(diag!(
+                UnusedItem::DeadCode,
+                (
+                    tpat.loc,
+                    "Unreachable match arm. This pattern can never be matched"
+                ),
+                (prev, prev_msg),
+            ));
+            continue;
+        }
+        match variant_opt {
+            None => catch_all = Some(tpat.loc),
+            Some(MatchedVariant::Unbound) => has_unbound_variant = true,
+            Some(MatchedVariant::Variant(m, n, v)) => {
+                covered.insert(v, tpat.loc);
+                matched_enum.get_or_insert((m, n));
+            }
+        }
+        result_ty = Some(match result_ty {
+            None => tarm.ty.clone(),
+            Some(prev_ty) => join(
+                context,
+                eloc,
+                || "Incompatible branches",
+                prev_ty,
+                tarm.ty.clone(),
+            ),
+        });
+        tarms.push(sp(aloc, (tpat, tarm)));
+    }
+
+    if catch_all.is_none() && !has_unbound_variant {
+        let missing = match &matched_enum {
+            Some((m, n)) => match &context.struct_definition(m, n).fields {
+                N::StructFields::Variants(variants) => variants
+                    .key_cloned_iter()
+                    .filter(|(v, _)| !covered.contains_key(v))
+                    .map(|(v, _)| format!("'{}'", v))
+                    .collect::<Vec<_>>(),
+                N::StructFields::Native(_) | N::StructFields::Defined(_) => vec![],
+            },
+            None => vec!["any value".to_owned()],
+        };
+        if !missing.is_empty() {
+            let msg = format!(
+                "Non-exhaustive 'match'. Missing patterns for {}",
+                format_comma(missing)
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::NonExhaustiveMatch, (eloc, msg)));
+        }
+    }
+
+    let ty = match result_ty {
+        Some(ty) => ty,
+        None => context.error_type(eloc),
+    };
+    (ty, T::UnannotatedExp_::Match(esubject, tarms))
+}
+
+#[allow(clippy::large_enum_variant)]
+enum MatchedVariant {
+    Variant(ModuleIdent, StructName, VariantName),
+    Unbound,
+}
+
+// Returns the typed pattern, and the variant it matches. Returns None for the variant if the
+// pattern matches all values
+fn match_pattern(
+    context: &mut Context,
+    ref_mut: Option<bool>,
+    subject_ty: &Type,
+    enum_ty: &Type,
+    declared: &mut UniqueMap<Var, ()>,
+    sp!(ploc, npat_): N::MatchPattern,
+) -> (T::LValue, Option<MatchedVariant>) {
+    use N::MatchPattern_ as NP;
+    use T::LValue_ as TL;
+    match npat_ {
+        NP::Binder(nl) => {
+            let tl = lvalue(context, LValueCase::Bind, declared, nl, subject_ty.clone());
+            (tl, None)
+        }
+        NP::Variant(m, n, v, ty_args_opt, fields) => {
+            let (bt, targs) = core::make_struct_type(context, ploc, &m, &n, ty_args_opt);
+            subtype(
+                context,
+                ploc,
+                || "Invalid match pattern",
+                bt,
+                enum_ty.clone(),
+            );
+            let bound = matches!(
+                &context.struct_definition(&m, &n).fields,
+                N::StructFields::Variants(variants) if variants.contains_key(&v)
+            );
+            let typed_fields = add_variant_field_types(
+                context,
+                ploc,
+                "binding",
+                &m,
+                &n,
+                &v,
+                targs.clone(),
+                fields,
+            );
+            let tfields = typed_fields.map(|f, (idx, (fty, nl))| {
+                let nl_ty = match ref_mut {
+                    None => fty.clone(),
+                    Some(mut_) => sp(f.loc(), Type_::Ref(mut_, Box::new(fty.clone()))),
+                };
+                let tl = lvalue(context, LValueCase::Bind, declared, nl, nl_ty);
+                (idx, (fty, tl))
+            });
+            if !context.is_current_module(&m) {
+                let msg = format!(
+                    "Invalid match of '{}::{}::{}'.\nAll enums can only be matched in the module \
+                     in which they are declared",
+                    &m, &n, &v,
+                );
+                context
+                    .env
+                    .add_diag(diag!(TypeSafety::Visibility, (ploc, msg)));
+            }
+            let matched = if bound {
+                MatchedVariant::Variant(m, n, v)
+            } else {
+                MatchedVariant::Unbound
+            };
+            let tl_ = match ref_mut {
+                None => TL::UnpackVariant(m, n, v, targs, tfields),
+                Some(mut_) => TL::BorrowUnpackVariant(mut_, m, n, v, targs, tfields),
+            };
+            (sp(ploc, tl_), Some(matched))
+        }
+    }
+}
+
 //**************************************************************************************************
 // Locals and LValues
 //**************************************************************************************************
@@ -1563,7 +1934,14 @@ fn lvalue_expected_types(_context: &mut Context, sp!(loc, b_): &T::LValue) -> Op
                 Ref(*mut_, Box::new(sp(loc, Apply(None, tn, tys.clone())))),
             ))
         }
-        L::Unpack(m, s, tys, _) => {
+        L::BorrowUnpackVariant(mut_, m, s, _, tys, _) => {
+            let tn = sp(loc, N::TypeName_::ModuleType(*m, *s));
+            Some(sp(
+                loc,
+                Ref(*mut_, Box::new(sp(loc, Apply(None, tn, tys.clone())))),
+            ))
+        }
+        L::Unpack(m, s, tys, _) | L::UnpackVariant(m, s, _, tys, _) => {
             let tn = sp(loc, N::TypeName_::ModuleType(*m, *s));
             Some(sp(loc, Apply(None, tn, tys.clone())))
         }
@@ -1858,7 +2236,7 @@ fn add_field_types<T>(
     fields: Fields<T>,
 ) -> Fields<(Type, T)> {
     let maybe_fields_ty = core::make_field_types(context, loc, m, n, targs);
-    let mut fields_ty = match maybe_fields_ty {
+    let fields_ty = match maybe_fields_ty {
         N::StructFields::Defined(m) => m,
         N::StructFields::Native(nloc) => {
             let msg = format!(
@@ -1873,10 +2251,88 @@ fn add_field_types<T>(
             ));
             return fields.map(|f, (idx, x)| (idx, (context.error_type(f.loc()), x)));
         }
+        N::StructFields::Variants(_) => {
+            let msg = format!(
+                "Invalid {} usage for enum '{}::{}'. Enums must be constructed/deconstructed \
+                 through one of their variants, e.g. '{}::V {{ .. }}'",
+                verb, m, n, n
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundVariant, (loc, msg)));
+            return fields.map(|f, (idx, x)| (idx, (context.error_type(f.loc()), x)));
+        }
     };
+    match_field_types(
+        context,
+        loc,
+        verb,
+        &format!("{}::{}", m, n),
+        fields_ty,
+        fields,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_variant_field_types<T>(
+    context: &mut Context,
+    loc: Loc,
+    verb: &str,
+    m: &ModuleIdent,
+    n: &StructName,
+    v: &VariantName,
+    targs: Vec<Type>,
+    fields: Fields<T>,
+) -> Fields<(Type, T)> {
+    match core::make_variant_field_types(context, loc, m, n, v, targs) {
+        None => fields.map(|f, (idx, x)| (idx, (context.error_type(f.loc()), x))),
+        Some(fields_ty) => match_field_types(
+            context,
+            loc,
+            verb,
+            &format!("{}::{}::{}", m, n, v),
+            fields_ty,
+            fields,
+        ),
+    }
+}
+
+fn match_field_types<T>(
+    context: &mut Context,
+    loc: Loc,
+    verb: &str,
+    name: &str,
+    mut fields_ty: Fields<Type>,
+    fields: Fields<T>,
+) -> Fields<(Type, T)> {
+    let given_positional = fields
+        .key_cloned_iter()
+        .next()
+        .map(|(f, _)| f.is_positional());
+    let declared_positional = fields_ty
+        .key_cloned_iter()
+        .next()
+        .map(|(f, _)| f.is_positional());
+    if let (Some(given), Some(declared)) = (given_positional, declared_positional) {
+        if given != declared {
+            let (expected, style) = if declared {
+                ("positional", "a positional struct")
+            } else {
+                ("named", "a struct with named fields")
+            };
+            let msg = format!(
+                "Expected {} {}s for '{}', as it is declared as {}",
+                expected, verb, name, style
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::UnboundField, (loc, msg)));
+            return fields.map(|f, (idx, x)| (idx, (context.error_type(f.loc()), x)));
+        }
+    }
     for (_, f_, _) in &fields_ty {
         if fields.get_(f_).is_none() {
-            let msg = format!("Missing {} for field '{}' in '{}::{}'", verb, f_, m, n);
+            let msg = format!("Missing {} for field '{}' in '{}'", verb, f_, name);
             context
                 .env
                 .add_diag(diag!(TypeSafety::TooFewArguments, (loc, msg)))
@@ -1887,7 +2343,7 @@ fn add_field_types<T>(
             None => {
                 context.env.add_diag(diag!(
                     NameResolution::UnboundField,
-                    (loc, format!("Unbound field '{}' in '{}::{}'", &f, m, n))
+                    (loc, format!("Unbound field '{}' in '{}'", &f, name))
                 ));
                 context.error_type(f.loc())
             }
@@ -2054,10 +2510,36 @@ fn module_call(
     f: FunctionName,
     ty_args_opt: Option<Vec<Type>>,
     argloc: Loc,
-    args: Vec<T::Exp>,
+    nargs: Vec<N::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    let args = exps_or_lambdas(context, nargs);
+    module_call_impl(context, loc, m, f, ty_args_opt, argloc, args)
+}
+
+// Lambdas are typed after the function type is instantiated, so that the types of their
+// parameters can be inferred from the types of the function's parameters
+fn exps_or_lambdas(context: &mut Context, nargs: Vec<N::Exp>) -> Vec<Result<T::Exp, N::Exp>> {
+    nargs
+        .into_iter()
+        .map(|narg| match narg {
+            sp!(_, N::Exp_::Lambda(_, _)) => Err(narg),
+            narg => Ok(exp_(context, narg)),
+        })
+        .collect()
+}
+
+fn module_call_impl(
+    context: &mut Context,
+    loc: Loc,
+    m: ModuleIdent,
+    f: FunctionName,
+    ty_args_opt: Option<Vec<Type>>,
+    argloc: Loc,
+    args: Vec<Result<T::Exp, N::Exp>>,
 ) -> (Type, T::UnannotatedExp_) {
     let (_, ty_args, parameters, acquires, ret_ty) =
         core::make_function_type(context, loc, &m, &f, ty_args_opt);
+    let args = lambda_args(context, &m, &f, &parameters, args);
     let (arguments, arg_tys) = call_args(
         context,
         loc,
@@ -2088,6 +2570,336 @@ fn module_call(
     (ret_ty, T::UnannotatedExp_::ModuleCall(Box::new(call)))
 }
 
+fn method_call(
+    context: &mut Context,
+    loc: Loc,
+    ndotted: N::ExpDotted,
+    n: Name,
+    ty_args_opt: Option<Vec<Type>>,
+    argloc: Loc,
+    nargs: Vec<N::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    let (edotted, receiver_ty) = exp_dotted(context, "method call", ndotted);
+    let (m, f, mut_opt) = match resolve_method(context, loc, &receiver_ty, n) {
+        Some(resolved) => resolved,
+        None => {
+            // Still type the arguments, so that any errors in them are reported
+            exps_or_lambdas(context, nargs);
+            return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError);
+        }
+    };
+    let receiver = match mut_opt {
+        Some(mut_) => exp_dotted_to_borrow(context, loc, mut_, edotted),
+        None => method_receiver_value(context, loc, edotted, receiver_ty),
+    };
+    let args = std::iter::once(Ok(receiver))
+        .chain(exps_or_lambdas(context, nargs))
+        .collect();
+    module_call_impl(context, loc, m, f, ty_args_opt, argloc, args)
+}
+
+/// Resolves the method `n` for a receiver of type `receiver_ty` to the function `n` in the module
+/// defining that type. The function's first parameter must be the receiver's type, or a
+/// reference to it. Returns the mutability of that reference, if any, i.e. how the receiver
+/// should be borrowed
+fn resolve_method(
+    context: &mut Context,
+    loc: Loc,
+    receiver_ty: &Type,
+    n: Name,
+) -> Option<(ModuleIdent, FunctionName, Option<bool>)> {
+    use TypeName_ as TN;
+    use Type_ as Ty;
+    let unfolded = core::unfold_type(&context.subst, receiver_ty.clone());
+    let (m, tn_) = match &unfolded.value {
+        Ty::UnresolvedError | Ty::Anything => return None,
+        Ty::Var(_) => {
+            let msg = format!(
+                "Invalid method call of '{}'. Could not infer the type of the receiver. Try \
+                 annotating the type of the expression",
+                n
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::UninferredType, (loc, msg)));
+            return None;
+        }
+        Ty::Apply(_, sp!(_, tn_ @ TN::ModuleType(m, _)), _) => (*m, tn_.clone()),
+        Ty::Apply(_, sp!(_, tn_ @ TN::Builtin(sp!(_, N::BuiltinTypeName_::Vector))), _) => {
+            match context.vector_module() {
+                Some(m) => (m, tn_.clone()),
+                None => {
+                    let msg = format!(
+                        "Invalid method call of '{}'. The module 'std::vector' is not available",
+                        n
+                    );
+                    context
+                        .env
+                        .add_diag(diag!(TypeSafety::InvalidMethodCall, (loc, msg)));
+                    return None;
+                }
+            }
+        }
+        _ => {
+            let msg = format!(
+                "Invalid method call of '{}'. Method calls are not supported on values of type \
+                 {}",
+                n,
+                core::error_format(&unfolded, &context.subst)
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidMethodCall, (loc, msg)));
+            return None;
+        }
+    };
+    let f = FunctionName(n);
+    let param_ty = match context.function_receiver_type(&m, &f) {
+        Some(ty) => ty.clone(),
+        None => {
+            let msg = format!(
+                "Invalid method call. No function '{}' with at least one parameter found in \
+                 module '{}'",
+                n, m
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidMethodCall, (loc, msg)));
+            return None;
+        }
+    };
+    let (mut_opt, base_ty) = match param_ty.value {
+        Ty::Ref(mut_, inner) => (Some(mut_), *inner),
+        ty_ => (None, sp(param_ty.loc, ty_)),
+    };
+    match &base_ty.value {
+        Ty::Apply(_, sp!(_, param_tn_), _) if param_tn_ == &tn_ => Some((m, f, mut_opt)),
+        _ => {
+            let msg = format!(
+                "Invalid method call of '{}::{}'. The type of its first parameter is not {} or \
+                 a reference to it",
+                m,
+                f,
+                core::error_format(&unfolded, &context.subst)
+            );
+            context.env.add_diag(diag!(
+                TypeSafety::InvalidMethodCall,
+                (loc, msg),
+                (base_ty.loc, "First parameter declared here"),
+            ));
+            None
+        }
+    }
+}
+
+/// The receiver of a method call taking its first parameter by value
+fn method_receiver_value(
+    context: &mut Context,
+    loc: Loc,
+    edotted: ExpDotted,
+    receiver_ty: Type,
+) -> T::Exp {
+    use T::UnannotatedExp_ as TE;
+    match edotted {
+        sp!(_, ExpDotted_::TmpBorrow(e, _)) => *e,
+        sp!(_, ExpDotted_::Exp(e)) => {
+            context.add_ability_constraint(
+                loc,
+                Some(format!(
+                    "Invalid implicit copy of the receiver without the '{}' ability",
+                    Ability_::COPY,
+                )),
+                receiver_ty.clone(),
+                Ability_::Copy,
+            );
+            T::exp(receiver_ty, sp(loc, TE::Dereference(e)))
+        }
+        edotted => exp_dotted_to_owned_value(context, loc, edotted, receiver_ty),
+    }
+}
+
+fn lambda_args(
+    context: &mut Context,
+    m: &ModuleIdent,
+    f: &FunctionName,
+    parameters: &[(Var, Type)],
+    args: Vec<Result<T::Exp, N::Exp>>,
+) -> Vec<T::Exp> {
+    if args.iter().all(|arg| arg.is_ok()) {
+        return args.into_iter().map(|arg| arg.unwrap()).collect();
+    }
+    // Unify the types of the other arguments first, so their information is available when
+    // typing the lambda bodies. Errors are reported when the arguments are checked later
+    for (arg, (_, param_ty)) in args.iter().zip(parameters) {
+        if let Ok(arg) = arg {
+            let subst = context.subst.clone();
+            let arg_ty = core::ready_tvars(&subst, arg.ty.clone());
+            let param_ty = core::ready_tvars(&subst, param_ty.clone());
+            if let Ok((next_subst, _)) = core::subtype(subst, &arg_ty, &param_ty) {
+                context.subst = next_subst;
+            }
+        }
+    }
+    let is_inline = context.is_inline_function(m, f);
+    args.into_iter()
+        .enumerate()
+        .map(|(idx, arg)| match arg {
+            Ok(e) => e,
+            Err(sp!(eloc, N::Exp_::Lambda(nbinds, nbody))) => {
+                let param_ty = parameters.get(idx).map(|(_, ty)| ty);
+                let expected =
+                    match param_ty.map(|ty| core::unfold_type(&context.subst, ty.clone())) {
+                        Some(sp!(_, Type_::Fun(param_tys, result_ty))) if is_inline => {
+                            Some((param_tys, *result_ty))
+                        }
+                        _ => None,
+                    };
+                match expected {
+                    Some((param_tys, result_ty)) => {
+                        lambda(context, eloc, nbinds, *nbody, param_tys, result_ty)
+                    }
+                    None => {
+                        let msg = if is_inline {
+                            format!(
+                                "Invalid lambda argument. The corresponding parameter of \
+                                 '{}::{}' does not have a function type",
+                                m, f
+                            )
+                        } else {
+                            format!(
+                                "Invalid lambda argument. '{}::{}' is not an 'inline' function. \
+                                 Lambdas can only be passed to 'inline' functions",
+                                m, f
+                            )
+                        };
+                        context
+                            .env
+                            .add_diag(diag!(TypeSafety::InvalidLambda, (eloc, msg)));
+                        T::exp(
+                            context.error_type(eloc),
+                            sp(eloc, T::UnannotatedExp_::UnresolvedError),
+                        )
+                    }
+                }
+            }
+            Err(_) => panic!("ICE expected a lambda"),
+        })
+        .collect()
+}
+
+fn lambda(
+    context: &mut Context,
+    loc: Loc,
+    nbinds: N::LValueList,
+    nbody: N::Exp,
+    param_tys: Vec<Type>,
+    result_ty: Type,
+) -> T::Exp {
+    if nbinds.value.len() != param_tys.len() {
+        let msg = format!(
+            "Invalid lambda. Expected {} parameter(s) but got {}",
+            param_tys.len(),
+            nbinds.value.len()
+        );
+        context
+            .env
+            .add_diag(diag!(TypeSafety::InvalidLambda, (nbinds.loc, msg)));
+        return T::exp(
+            context.error_type(loc),
+            sp(loc, T::UnannotatedExp_::UnresolvedError),
+        );
+    }
+    let bind_ty = Type_::multiple(nbinds.loc, param_tys.clone());
+    let old_locals = context.save_locals_scope();
+    let (declared, binds) = bind_list(context, nbinds, Some(bind_ty));
+    let old_loop_info = context.enter_lambda();
+    let body = exp_(context, nbody);
+    context.exit_lambda(old_loop_info);
+    context.close_locals_scope(old_locals, declared);
+    let body_loc = body.exp.loc;
+    let result_ty = subtype(
+        context,
+        body_loc,
+        || "Invalid lambda body",
+        body.ty.clone(),
+        result_ty,
+    );
+    let ty = sp(loc, Type_::Fun(param_tys, Box::new(result_ty)));
+    T::exp(
+        ty,
+        sp(loc, T::UnannotatedExp_::Lambda(binds, Box::new(body))),
+    )
+}
+
+fn var_call(
+    context: &mut Context,
+    loc: Loc,
+    var: Var,
+    argloc: Loc,
+    nargs: Vec<N::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    let var_ty = match context.get_local_(&var) {
+        Some(ty) => ty,
+        None => {
+            context.env.add_diag(diag!(
+                NameResolution::UnboundUnscopedName,
+                (
+                    var.loc(),
+                    format!("Unbound function '{}' in current scope", var)
+                ),
+            ));
+            return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError);
+        }
+    };
+    let args = exp_vec(context, nargs);
+    let (param_tys, result_ty) = match core::unfold_type(&context.subst, var_ty) {
+        sp!(_, Type_::Fun(param_tys, result_ty)) => (param_tys, *result_ty),
+        sp!(_, Type_::UnresolvedError) => {
+            return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError)
+        }
+        ty => {
+            let msg = format!("Invalid call of '{}'. Expected a function type", var);
+            let tmsg = format!(
+                "Found a local of type: {}",
+                core::error_format(&ty, &context.subst)
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidLambda, (loc, msg), (ty.loc, tmsg)));
+            return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError);
+        }
+    };
+    let (arguments, arg_tys) = call_args(
+        context,
+        loc,
+        || format!("Invalid call of '{}'", var),
+        param_tys.len(),
+        argloc,
+        args,
+    );
+    for (idx, (arg_ty, param_ty)) in arg_tys.into_iter().zip(param_tys).enumerate() {
+        let msg = || format!("Invalid call of '{}'. Invalid argument {}", var, idx + 1);
+        subtype(context, loc, msg, arg_ty, param_ty);
+    }
+    (result_ty, T::UnannotatedExp_::VarCall(var, arguments))
+}
+
+fn check_return_context(context: &mut Context, loc: Loc) {
+    if context.in_lambda() {
+        let msg = "Invalid 'return' in lambda. Lambda bodies are substituted into the 'inline' \
+                   function they are passed to, so 'return' is not supported";
+        context
+            .env
+            .add_diag(diag!(TypeSafety::InvalidLambda, (loc, msg)));
+    } else if context.is_current_function_inline() {
+        let msg = "Invalid 'return' in 'inline' function. 'inline' function bodies are \
+                   substituted at their call sites, so 'return' is not supported";
+        context
+            .env
+            .add_diag(diag!(TypeSafety::InvalidInline, (loc, msg)));
+    }
+}
+
 fn builtin_call(
     context: &mut Context,
     loc: Loc,
@@ -199,6 +199,7 @@ fn create_test_poison(mloc: Loc) -> P::ModuleMember {
         loc: mloc,
         visibility: P::Visibility::Internal,
         entry: None,
+        inline: None,
         acquires: vec![],
         signature,
         name: P::FunctionName(sp(mloc, "unit_test_poison".into())),
@@ -241,7 +242,11 @@ fn test_attributes(attrs: &P::Attributes) -> Vec<(Loc, known_attributes::Testing
         .filter_map(
             |attr| match KnownAttribute::resolve(attr.value.attribute_name().value)? {
                 KnownAttribute::Testing(test_attr) => Some((attr.loc, test_attr)),
-                KnownAttribute::Verification(_) | KnownAttribute::Native(_) => None,
+                KnownAttribute::Verification(_)
+                | KnownAttribute::Native(_)
+                | KnownAttribute::Lint(_)
+                | KnownAttribute::Deprecation(_)
+                | KnownAttribute::Cfg(_) => None,
             },
         )
         .collect()
@@ -0,0 +1,30 @@
+module 0x42::M {
+    enum E has drop {
+        A { x: u64, y: u64 },
+        B,
+    }
+
+    fun write_while_borrowed(e: &mut E) {
+        match (e) {
+            E::A { x, y: _ } => {
+                *e = E::B;
+                *x = 0;
+            },
+            E::B => (),
+        }
+    }
+
+    fun read_while_borrowed(e: &mut E): u64 {
+        match (e) {
+            E::A { x, y: _ } => {
+                let r: &E = e;
+                *x = 0;
+                match (r) {
+                    E::A { x: _, y } => *y,
+                    E::B => 0,
+                }
+            },
+            E::B => 0,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+module 0x42::M {
+    use std::vector;
+
+    struct R {}
+
+    fun consume(r: R) {
+        R {} = r;
+    }
+
+    fun assign_while_borrowed(v: vector<u64>) {
+        let s = 0;
+        let r = &mut s;
+        vector::for_each(v, |e| s = s + e);
+        *r = 1;
+    }
+
+    fun borrow_while_borrowed(v: vector<u64>) {
+        let s = 0;
+        let r = &mut s;
+        vector::for_each(v, |e| { let x = &mut s; *x = e; });
+        *r = 1;
+    }
+
+    fun move_in_loop(v: vector<u64>, r: R) {
+        vector::for_each(v, |_| consume(r));
+    }
+}
@@ -0,0 +1,36 @@
+module 0x2::M {
+    #[cfg(feature = "enabled")]
+    const VALUE: u64 = 1;
+
+    #[cfg(not(feature = "enabled"))]
+    const VALUE: u64 = 2;
+
+    #[cfg(feature = b"disabled")]
+    use 0x2::Missing;
+
+    #[cfg(all(feature = "enabled", not(feature = b"disabled")))]
+    public fun value(): u64 { VALUE }
+
+    #[cfg(any(feature = b"disabled", not(feature = b"enabled")))]
+    public fun value(): u64 { Missing::value() }
+
+    #[cfg(feature = b"disabled")]
+    struct Removed {}
+
+    // Removed along with its only use, so no error is reported
+    #[cfg(feature = b"disabled")]
+    fun removed(): Removed { Removed {} }
+
+    fun uses_removed(): Removed { Removed {} }
+}
+
+#[cfg(feature = b"disabled")]
+module 0x2::Removed {
+    fun f() { undefined() }
+}
+
+#[cfg(any(feature = b"disabled", feature = b"enabled"))]
+#[cfg(not(feature = b"disabled"))]
+module 0x2::Kept {
+    public fun f(): u64 { 0x2::M::value() }
+}
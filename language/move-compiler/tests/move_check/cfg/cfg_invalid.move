@@ -0,0 +1,22 @@
+module 0x2::M {
+    #[cfg]
+    fun a() {}
+
+    #[cfg(feature)]
+    fun b() {}
+
+    #[cfg(feature = 1)]
+    fun c() {}
+
+    #[cfg(unknown(feature = b"enabled"))]
+    fun d() {}
+
+    #[cfg(not(feature = b"enabled", feature = b"disabled"))]
+    fun e() {}
+
+    #[cfg(feature = b"enabled", feature = b"disabled")]
+    fun f() {}
+
+    #[cfg(feature = enabled)]
+    fun g() {}
+}
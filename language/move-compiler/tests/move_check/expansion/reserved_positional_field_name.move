@@ -0,0 +1,17 @@
+module 0x42::M {
+    struct P { pos0: u64 }
+    struct P2 { pos0: u64, pos1: bool }
+    // Only the exact sequence pos0, pos1, ... in order is reserved
+    struct Q { f: u64, pos1: bool }
+    struct R { pos1: u64, pos0: bool }
+    struct T { pos0: u64, pos2: bool }
+    struct U { pos01: u64, pos: u64, position0: u64 }
+    // Positional structs are unaffected
+    struct S(u64, bool);
+
+    enum E {
+        A { pos0: u64 },
+        B { pos1: u64 },
+        C { pos0: u64, x: u64 },
+    }
+}
@@ -0,0 +1,9 @@
+module 0x42::M {
+    enum E has drop {
+        V { f: u64, g: u64 },
+    }
+
+    fun t(e: E): E {
+        E::V { f: 0, ..e }
+    }
+}
@@ -0,0 +1,31 @@
+address 0x42 {
+module M {
+    const BASE: u64 = 100;
+    const DOUBLE: u64 = BASE * 2;
+    const SHIFTED: u64 = DOUBLE << 3;
+    const MASKED: u64 = (SHIFTED | BASE) & 0xFFFF;
+    const IS_BIG: bool = MASKED > BASE && DOUBLE != BASE;
+
+    // Constants may be declared after their uses
+    const EARLY: u8 = LATE + 1;
+    const LATE: u8 = 254;
+
+    const U256_MAX: u256 =
+        115792089237316195423570985008687907853269984665640564039457584007913129639935;
+    const U256_HALF: u256 = U256_MAX / 2;
+    const U256_SUM: u256 = U256_HALF + U256_HALF;
+    const U256_CAST: u8 = ((U256_MAX >> 248) as u8);
+
+    const WIDTHS: vector<u64> = vector[BASE, DOUBLE, (EARLY as u64)];
+    const NESTED: vector<vector<u64>> = vector[WIDTHS, vector[BASE]];
+
+    const PREFIX: vector<u8> = b"move";
+    const SUFFIX: vector<u8> = x"2d6c616e67";
+    const NAME: vector<u8> = PREFIX + b"-" + SUFFIX;
+    const NAMES: vector<vector<u8>> = vector[PREFIX, NAME];
+
+    fun values(): (u64, bool, u256, u8, vector<u64>, vector<vector<u64>>, vector<u8>, vector<vector<u8>>) {
+        (MASKED, IS_BIG, U256_SUM, U256_CAST, WIDTHS, NESTED, NAME, NAMES)
+    }
+}
+}
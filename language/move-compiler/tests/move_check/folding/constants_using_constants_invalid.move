@@ -0,0 +1,17 @@
+address 0x42 {
+module M {
+    const SELF: u64 = SELF + 1;
+
+    const A: u64 = B;
+    const B: u64 = C * 2;
+    const C: u64 = A;
+
+    const MAX: u8 = 255;
+    const OVERFLOW: u8 = MAX + 1;
+    const USES_OVERFLOW: u8 = OVERFLOW - 1;
+    const ZERO: u64 = 0;
+    const DIV_ZERO: u64 = 1 / ZERO;
+    const UNDERFLOW: u256 = (ZERO as u256) - 1;
+    const TOO_BIG: u32 = ((MAX as u64) << 32 as u32);
+}
+}
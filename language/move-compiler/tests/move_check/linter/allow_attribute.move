@@ -0,0 +1,34 @@
+#[allow(while_true)]
+module 0x42::M {
+    fun allowed_by_module() {
+        while (true) {}
+    }
+
+    #[allow(self_assignment, constant_comparison)]
+    fun allowed_by_function(x: u64): bool {
+        x = x;
+        x == x
+    }
+
+    #[allow(self_assignment)]
+    fun allows_other_lint(x: u64): bool {
+        x == x
+    }
+
+    #[allow(while_true)]
+    const C: bool = 0 <= 1;
+
+    #[allow(constant_comparison)]
+    const D: bool = 1 >= 0;
+}
+
+module 0x42::N {
+    #[allow(not_a_lint)]
+    fun unknown_lint() {}
+
+    #[allow]
+    fun missing_lints() {}
+
+    #[allow(while_true = 0)]
+    fun invalid_lint() {}
+}
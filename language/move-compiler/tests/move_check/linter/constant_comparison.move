@@ -0,0 +1,31 @@
+module 0x42::M {
+    fun t(x: u64, y: u8, z: u128, b: bool): vector<bool> {
+        vector[
+            x == x,
+            x != x,
+            x <= x,
+            x < x,
+            b == b,
+            x >= 0,
+            x < 0,
+            0 <= x,
+            0 > x,
+            y <= 255,
+            y > 255,
+            255 >= y,
+            z > 340282366920938463463374607431768211455,
+        ]
+    }
+
+    fun no_lint(x: u64, y: u8, z: u64): vector<bool> {
+        vector[
+            x == z,
+            x > 0,
+            x <= 0,
+            0 < x,
+            y < 255,
+            y >= 255,
+            x > 255,
+        ]
+    }
+}
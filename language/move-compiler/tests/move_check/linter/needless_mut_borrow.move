@@ -0,0 +1,20 @@
+module 0x42::M {
+    struct S has drop { f: u64 }
+
+    fun read(x: &u64): u64 { *x }
+
+    fun t(s: &mut S, x: u64): u64 {
+        let local = S { f: 0 };
+        let r: &u64 = &mut x;
+        let v = *r;
+        read(&mut x) + read(&mut s.f) + read(&mut local.f) + v
+    }
+
+    fun no_lint(s: &mut S, x: u64): u64 {
+        let r = &mut x;
+        *r = 1;
+        s.f = read(&x);
+        let frozen: &S = s;
+        read(&frozen.f) + read(&x)
+    }
+}
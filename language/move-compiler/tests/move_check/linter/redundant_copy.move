@@ -0,0 +1,37 @@
+module 0x42::M {
+    struct S has copy, drop { f: u64 }
+
+    fun consume(_s: S) {}
+
+    fun t(s: S, x: u64): u64 {
+        consume(copy s);
+        let y = copy x;
+        y
+    }
+
+    fun t_branch(s: S, b: bool) {
+        if (b) consume(copy s) else consume(copy s)
+    }
+
+    fun no_lint(s: S, x: u64): u64 {
+        consume(copy s);
+        consume(copy s);
+        consume(s);
+        let y = copy x;
+        y + x
+    }
+
+    fun no_lint_loop(s: S, n: u64) {
+        let i = 0;
+        while (i < n) {
+            consume(copy s);
+            i = i + 1;
+        }
+    }
+
+    fun no_lint_borrowed(x: u64): u64 {
+        let r = &x;
+        let y = copy x;
+        *r + y
+    }
+}
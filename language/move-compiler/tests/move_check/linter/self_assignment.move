@@ -0,0 +1,20 @@
+module 0x42::M {
+    struct S has copy, drop { f: u64 }
+
+    fun t(x: u64, y: u64, s: S): (u64, u64, S) {
+        x = x;
+        (x, y) = (x, y);
+        (x, y) = (y, x);
+        s = s;
+        s.f = s.f;
+        (x, y, s)
+    }
+
+    fun no_lint(x: u64, y: u64): u64 {
+        let x = x;
+        let z = x;
+        x = y;
+        y = x + z;
+        y
+    }
+}
@@ -0,0 +1,18 @@
+module 0x42::M {
+    fun t(a: u8, b: u16, c: u32, d: u64, e: u128): u128 {
+        let a = a << 8;
+        let b = b >> 16;
+        let c = c << 32;
+        let d = d >> 64;
+        let e = e << 255;
+        (a as u128) + (b as u128) + (c as u128) + (d as u128) + e
+    }
+
+    fun no_lint(a: u8, d: u64, e: u128, f: u256, n: u8): u256 {
+        let a = a << 7;
+        let d = (d >> 63) << n;
+        let e = e << 127;
+        let f = f << 255;
+        (a as u256) + (d as u256) + (e as u256) + f
+    }
+}
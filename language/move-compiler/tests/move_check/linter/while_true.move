@@ -0,0 +1,22 @@
+module 0x42::M {
+    fun t() {
+        while (true) {}
+    }
+
+    fun t_break(): u64 {
+        let i = 0;
+        while (true) {
+            if (i > 10) break;
+            i = i + 1;
+        };
+        i
+    }
+
+    fun no_lint_loop() {
+        loop {}
+    }
+
+    fun no_lint_while(b: bool) {
+        while (b) {}
+    }
+}
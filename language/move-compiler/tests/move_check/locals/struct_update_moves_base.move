@@ -0,0 +1,11 @@
+module 0x42::M {
+    struct S has drop {
+        f: u64,
+        g: u64,
+    }
+
+    fun base_is_moved(s: S): u64 {
+        let t = S { f: 0, ..s };
+        t.g + s.g
+    }
+}
@@ -0,0 +1,16 @@
+module 0x2::M {
+    #[deprecated = b"note"]
+    fun f() {}
+
+    #[deprecated(reason = b"note")]
+    fun g() {}
+
+    #[deprecated(note = 0)]
+    fun h() {}
+
+    #[deprecated(note = x"ff")]
+    fun i() {}
+
+    #[deprecated]
+    use 0x1::vector;
+}
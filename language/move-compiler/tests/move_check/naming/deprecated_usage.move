@@ -0,0 +1,43 @@
+address 0x2 {
+module X {
+    #[deprecated]
+    struct S has drop { f: u64 }
+
+    #[deprecated(note = b"use 'g' instead")]
+    public fun f(): u64 { g() }
+
+    public fun g(): u64 { 0 }
+
+    #[deprecated]
+    const C: u64 = 0;
+
+    // uses from within the module are not reported
+    public fun s(): S { S { f: f() } }
+}
+
+#[deprecated(note = b"use 'X' instead")]
+module Y {
+    struct T has drop {}
+
+    public fun t(): T { T {} }
+}
+
+module M {
+    use 0x2::X::{Self, S};
+    use 0x2::Y;
+
+    fun uses(_s: S): u64 {
+        X::f() + X::g()
+    }
+
+    fun module_uses(): Y::T {
+        Y::t()
+    }
+}
+}
+
+script {
+    fun main() {
+        0x2::X::f();
+    }
+}
@@ -2,5 +2,6 @@
 #[attr0]
 #[attr1=0, attr2=b"hello", attr3=x"0f", attr4=0x42, attr5(attr0, attr1, attr2(attr0, attr1=0))]
 #[bttr0=false, bttr1=0u8, bttr2=0u64, bttr3=0u128]
+#[cttr0="hello", cttr1="", cttr2="\"quoted\""]
 #[]
 module 0x42::M {}
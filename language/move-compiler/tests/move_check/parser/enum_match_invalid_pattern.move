@@ -0,0 +1,11 @@
+module 0x42::M {
+    enum E has drop {
+        A { x: u64 },
+    }
+
+    fun f(e: E): u64 {
+        match (e) {
+            (a, b) => 0,
+        }
+    }
+}
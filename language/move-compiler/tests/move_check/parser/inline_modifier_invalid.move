@@ -0,0 +1,11 @@
+module 0x42::M {
+    inline native fun n();
+
+    entry inline fun e() {}
+
+    inline inline fun d() {}
+
+    inline struct S {}
+
+    inline const C: u64 = 0;
+}
@@ -0,0 +1,7 @@
+module 0x42::M {
+    struct Pair(u64, bool) has drop;
+
+    fun f(p: &Pair): u64 {
+        p.00
+    }
+}
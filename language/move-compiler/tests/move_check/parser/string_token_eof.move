@@ -0,0 +1,4 @@
+module 0x42::M {
+    #[attr = "hello]
+    fun f() {}
+}
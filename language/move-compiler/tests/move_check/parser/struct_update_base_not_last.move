@@ -0,0 +1,10 @@
+module 0x42::M {
+    struct S has drop {
+        f: u64,
+        g: u64,
+    }
+
+    fun t(s: S): S {
+        S { ..s, f: 0 }
+    }
+}
@@ -0,0 +1,7 @@
+module 0x42::M {
+    const NAME: vector<u8> = b"a" + b"b";
+
+    fun concat(): vector<u8> {
+        NAME + b"c"
+    }
+}
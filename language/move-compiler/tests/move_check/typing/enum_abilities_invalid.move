@@ -0,0 +1,12 @@
+module 0x42::M {
+    struct NoCopy {}
+
+    enum E has copy, drop {
+        A { x: NoCopy },
+        B,
+    }
+
+    enum R has key {
+        A { x: u64 },
+    }
+}
@@ -0,0 +1,9 @@
+module 0x42::M {
+    enum E has drop {
+        A { x: u64 },
+    }
+
+    fun access(e: &E): u64 {
+        e.x
+    }
+}
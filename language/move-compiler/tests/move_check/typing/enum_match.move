@@ -0,0 +1,41 @@
+module 0x42::M {
+    enum E<T> has copy, drop {
+        A { x: T },
+        B { x: T, y: u64 },
+        C,
+    }
+
+    fun by_value(e: E<u64>): u64 {
+        match (e) {
+            E::A { x } => x,
+            E::B { x, y } => x + y,
+            E::C => 0,
+        }
+    }
+
+    fun by_imm_ref(e: &E<u64>): u64 {
+        match (e) {
+            E::A { x } => *x,
+            E::B { x: _, y } => *y,
+            _ => 0,
+        }
+    }
+
+    fun by_mut_ref(e: &mut E<u64>) {
+        match (e) {
+            E::A { x } => *x = 0,
+            E::B { x, y: _ } => *x = 1,
+            E::C => (),
+        }
+    }
+
+    fun pack<T>(x: T): E<T> {
+        E::A { x }
+    }
+
+    fun bind_all(e: E<u64>): E<u64> {
+        match (e) {
+            other => other,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+module 0x42::M {
+    struct S has drop { f: u64 }
+
+    enum E has drop {
+        A { x: u64 },
+        B,
+    }
+
+    enum F has drop {
+        A,
+    }
+
+    fun unbound_variant(e: E): u64 {
+        match (e) {
+            E::A { x } => x,
+            E::D => 0,
+            _ => 1,
+        }
+    }
+
+    fun other_enum(e: E): u64 {
+        match (e) {
+            F::A => 0,
+            _ => 1,
+        }
+    }
+
+    fun not_an_enum(s: S): u64 {
+        match (s) {
+            S::A => 0,
+            _ => 1,
+        }
+    }
+
+    fun incompatible_arms(e: E): u64 {
+        match (e) {
+            E::A { x } => x,
+            E::B => false,
+        }
+    }
+
+    fun missing_field(e: E): u64 {
+        match (e) {
+            E::A {} => 0,
+            E::B => 1,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+module 0x42::M {
+    enum E has drop {
+        A { x: u64 },
+        B,
+        C,
+    }
+
+    fun missing_one(e: E): u64 {
+        match (e) {
+            E::A { x } => x,
+            E::B => 0,
+        }
+    }
+
+    fun missing_two(e: &E): u64 {
+        match (e) {
+            E::A { x } => *x,
+        }
+    }
+}
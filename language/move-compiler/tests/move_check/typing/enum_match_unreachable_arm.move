@@ -0,0 +1,21 @@
+module 0x42::M {
+    enum E has drop {
+        A { x: u64 },
+        B,
+    }
+
+    fun after_catch_all(e: E): u64 {
+        match (e) {
+            _ => 0,
+            E::A { x } => x,
+        }
+    }
+
+    fun duplicate_variant(e: E): u64 {
+        match (e) {
+            E::B => 0,
+            E::A { x } => x,
+            E::B => 1,
+        }
+    }
+}
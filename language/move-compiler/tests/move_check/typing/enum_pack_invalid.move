@@ -0,0 +1,37 @@
+module 0x42::M {
+    enum E has drop {
+        A { x: u64 },
+        B,
+    }
+
+    fun wrong_field_type(): E {
+        E::A { x: false }
+    }
+
+    fun missing_field(): E {
+        E::A {}
+    }
+
+    fun extra_field(): E {
+        E::B { x: 0 }
+    }
+
+    fun unbound_variant(): E {
+        E::C
+    }
+}
+
+module 0x42::N {
+    use 0x42::M::E;
+
+    fun pack_other_module(): E {
+        E::B
+    }
+
+    fun match_other_module(e: E): u64 {
+        match (e) {
+            E::A { x } => x,
+            E::B => 0,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+module 0x42::Iter {
+    use std::vector;
+
+    struct Counter has drop { count: u64 }
+
+    public inline fun repeat(n: u64, f: |u64|) {
+        let i = 0;
+        while (i < n) {
+            f(i);
+            i = i + 1;
+        }
+    }
+
+    public inline fun apply<T, R>(x: T, f: |T|R): R {
+        f(x)
+    }
+
+    public inline fun sum_by<T>(v: &vector<T>, f: |&T|u64): u64 {
+        let sum = 0;
+        let i = 0;
+        while (i < vector::length(v)) {
+            sum = sum + f(vector::borrow(v, i));
+            i = i + 1;
+        };
+        sum
+    }
+
+    inline fun bump(c: &mut Counter) {
+        c.count = c.count + 1
+    }
+
+    public fun count(n: u64): u64 {
+        let c = Counter { count: 0 };
+        repeat(n, |_| bump(&mut c));
+        c.count
+    }
+
+    public fun total(v: vector<u64>): u64 {
+        let evens = vector::filter(v, |x| *x % 2 == 0);
+        let doubled = vector::map(evens, |x| x * 2);
+        sum_by(&doubled, |x| *x) + apply(1, |x| x + 1)
+    }
+}
+
+module 0x42::User {
+    use std::vector;
+    use 0x42::Iter;
+
+    fun nested(): u64 {
+        let result = 0;
+        Iter::repeat(3, |i| Iter::repeat(i, |j| result = result + j));
+        result
+    }
+
+    fun nested_stdlib(): bool {
+        Iter::apply(2, |x| vector::any(&vector[1, 2], |e| *e == x))
+    }
+
+    fun unit_lambda() {
+        Iter::repeat(1, |_| ());
+    }
+}
@@ -0,0 +1,25 @@
+module 0x42::M {
+    inline fun a(x: u64): u64 {
+        b(x)
+    }
+
+    inline fun b(x: u64): u64 {
+        a(x)
+    }
+
+    inline fun recursive(x: u64): u64 {
+        if (x == 0) 0 else recursive(x - 1)
+    }
+
+    inline fun stored(f: |u64|u64) {
+        let _g = f;
+    }
+
+    fun cycle(): u64 {
+        a(0) + recursive(1)
+    }
+
+    fun lambda_as_value() {
+        stored(|x| x);
+    }
+}
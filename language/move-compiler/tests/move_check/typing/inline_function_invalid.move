@@ -0,0 +1,10 @@
+module 0x42::M {
+    inline fun early_return(x: u64): u64 {
+        if (x == 0) return 1;
+        x
+    }
+
+    fun call(): u64 {
+        early_return(2)
+    }
+}
@@ -0,0 +1,62 @@
+module 0x42::M {
+    friend 0x42::F;
+
+    struct S has drop { f: u64 }
+
+    const C: u64 = 0;
+
+    fun internal(): u64 { 0 }
+
+    public(friend) fun friend_only(): u64 { 0 }
+
+    public inline fun calls_internal(): u64 {
+        internal()
+    }
+
+    public inline fun calls_friend(): u64 {
+        friend_only()
+    }
+
+    public inline fun packs(): S {
+        S { f: 0 }
+    }
+
+    public inline fun unpacks(s: S): u64 {
+        let S { f } = s;
+        f
+    }
+
+    public inline fun borrows_field(s: &S): u64 {
+        s.f
+    }
+
+    public inline fun uses_constant(): u64 {
+        C
+    }
+
+    public fun make(): S {
+        packs()
+    }
+
+    fun ok(): u64 {
+        calls_internal() + calls_friend() + uses_constant()
+    }
+}
+
+module 0x42::F {
+    use 0x42::M;
+
+    fun ok(): u64 {
+        M::calls_friend()
+    }
+}
+
+module 0x42::N {
+    use 0x42::M;
+
+    fun invalid(): u64 {
+        let s = M::make();
+        M::calls_internal() + M::calls_friend() + M::borrows_field(&s) + M::uses_constant()
+            + M::unpacks(M::packs())
+    }
+}
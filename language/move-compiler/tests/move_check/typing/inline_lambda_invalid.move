@@ -0,0 +1,51 @@
+module 0x42::M {
+    public inline fun call(f: |u64|u64): u64 {
+        f(0)
+    }
+
+    public inline fun call_value(x: u64): u64 {
+        x
+    }
+
+    public fun not_inline(x: u64): u64 {
+        x
+    }
+
+    inline fun called_value(x: u64): u64 {
+        x(1)
+    }
+
+    inline fun borrowed(f: |u64|u64) {
+        let _g = &f;
+    }
+
+    fun lambda_as_value() {
+        let _f = |x| x + 1;
+    }
+
+    fun lambda_not_inline() {
+        not_inline(|x| x);
+    }
+
+    fun lambda_non_fun_param() {
+        call_value(|x| x);
+    }
+
+    fun lambda_arity() {
+        call(|x, y| x + y);
+    }
+
+    fun lambda_type() {
+        call(|x| x == 0);
+    }
+
+    fun lambda_return() {
+        call(|x| return x);
+    }
+
+    fun lambda_break() {
+        loop {
+            call(|x| { break; x });
+        }
+    }
+}
@@ -0,0 +1,36 @@
+module 0x42::Coin {
+    struct Coin has copy, drop { value: u64 }
+    struct Wallet has drop { coin: Coin, coins: vector<Coin> }
+
+    public fun value(c: &Coin): u64 { c.value }
+    public fun set_value(c: &mut Coin, value: u64) { c.value = value }
+    public fun double(c: Coin): Coin { Coin { value: c.value * 2 } }
+    public fun convert<T: drop>(_c: &Coin, x: T): T { x }
+
+    fun calls(w: &mut Wallet, c: Coin, r: &Coin) {
+        c.set_value(1);
+        c.value();
+        c.double().value();
+        r.value();
+        r.double();
+        w.coin.set_value(r.value());
+        w.coin.double();
+        c.convert<u64>(0);
+        c.convert(false);
+        w.coins.push_back(c);
+        w.coins.length();
+        let v = vector[1, 2, 3];
+        v.push_back(4);
+        v.swap(0, 1);
+        *v.borrow_mut(0) = v.length();
+    }
+}
+
+module 0x42::User {
+    use 0x42::Coin::Coin;
+
+    fun calls(c: &mut Coin): u64 {
+        c.set_value(0);
+        c.value()
+    }
+}
@@ -0,0 +1,29 @@
+module 0x42::Coin {
+    struct Coin has drop { value: u64 }
+    struct Other has drop {}
+
+    public fun value(c: &Coin): u64 { c.value }
+    public fun set_value(c: &mut Coin, value: u64) { c.value = value }
+    public fun none() {}
+    public fun other(_o: Other) {}
+    fun private(_c: &Coin) {}
+    public fun consume(_c: Coin) {}
+
+    fun calls(c: Coin, r: &Coin, x: u64) {
+        c.missing();
+        c.none();
+        c.other();
+        x.value();
+        r.set_value(0);
+        r.consume();
+        c.value(0);
+    }
+}
+
+module 0x42::User {
+    use 0x42::Coin::Coin;
+
+    fun calls(c: &Coin) {
+        c.private();
+    }
+}
@@ -0,0 +1,43 @@
+module 0x42::M {
+    struct Coin<phantom T>(u64) has store, drop;
+    struct Pair(u64, bool) has copy, drop;
+    struct Nested(Pair, Coin<u8>) has drop;
+    struct Empty() has drop;
+
+    fun make<T>(value: u64): Coin<T> {
+        Coin(value)
+    }
+
+    fun value<T>(c: &Coin<T>): u64 {
+        c.0
+    }
+
+    fun set<T>(c: &mut Coin<T>, v: u64) {
+        c.0 = v;
+    }
+
+    fun unpack(n: Nested): (u64, bool, u64) {
+        let Nested(Pair(a, b), Coin(c)) = n;
+        (a, b, c)
+    }
+
+    fun assign(p: Pair): u64 {
+        let a;
+        let b;
+        Pair(a, b) = p;
+        if (b) a else 0
+    }
+
+    fun chain(n: &Nested): bool {
+        n.0.1 && n.1.0 == 0
+    }
+
+    fun generic(): Coin<bool> {
+        Coin<bool>(1)
+    }
+
+    fun empty(): Empty {
+        let Empty() = Empty();
+        Empty()
+    }
+}
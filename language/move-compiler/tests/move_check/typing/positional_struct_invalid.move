@@ -0,0 +1,29 @@
+module 0x42::M {
+    struct Pair(u64, bool) has drop;
+    struct Named has drop { x: u64 }
+
+    fun arity(): Pair {
+        Pair(0)
+    }
+
+    fun too_many(): Pair {
+        Pair(0, false, 1)
+    }
+
+    fun positional_for_named(): Named {
+        Named(0)
+    }
+
+    fun named_for_positional(p: Pair): u64 {
+        let Pair { x } = p;
+        x
+    }
+
+    fun bad_index(p: &Pair): u64 {
+        *&p.2
+    }
+
+    fun bad_type(): Pair {
+        Pair(false, 0)
+    }
+}
@@ -0,0 +1,55 @@
+module 0x42::M {
+    struct Config has copy, drop {
+        max: u64,
+        min: u64,
+        enabled: bool,
+        name: vector<u8>,
+    }
+
+    struct Pair<T> has drop {
+        fst: T,
+        snd: T,
+    }
+
+    struct Wrapper<T> {
+        inner: T,
+        count: u64,
+    }
+
+    fun default(): Config {
+        Config { max: 10, min: 0, enabled: false, name: b"default" }
+    }
+
+    fun enable(c: Config): Config {
+        Config { enabled: true, ..c }
+    }
+
+    fun bounds(max: u64, min: u64): Config {
+        Config { max, min, ..default() }
+    }
+
+    fun all_given(c: Config): Config {
+        Config { max: 1, min: 1, enabled: true, name: b"", ..c }
+    }
+
+    fun none_given(c: Config): Config {
+        Config { ..c }
+    }
+
+    fun nested(c: Config): Config {
+        Config { max: 5, ..Config { min: 1, ..c } }
+    }
+
+    fun generic<T: drop>(p: Pair<T>, x: T): Pair<T> {
+        Pair { snd: x, ..p }
+    }
+
+    fun annotated(p: Pair<u8>): Pair<u8> {
+        Pair<u8> { fst: 0, ..p }
+    }
+
+    // Only the replaced fields need 'drop'
+    fun bump<T>(w: Wrapper<T>): Wrapper<T> {
+        Wrapper { count: w.count + 1, ..w }
+    }
+}
@@ -0,0 +1,56 @@
+module 0x42::N {
+    struct Coin has store { value: u64 }
+    struct Other has drop { value: u64 }
+
+    public fun zero(): Coin {
+        Coin { value: 0 }
+    }
+}
+
+module 0x42::M {
+    use 0x42::N::{Self, Coin, Other};
+
+    struct NoDrop {}
+
+    struct S has drop {
+        f: u64,
+        g: u64,
+    }
+
+    struct Holder {
+        coin: Coin,
+        count: u64,
+    }
+
+    struct Box<T> {
+        value: T,
+    }
+
+    fun replaced_field_without_drop(h: Holder): Holder {
+        Holder { coin: N::zero(), ..h }
+    }
+
+    fun replaced_generic_field<T>(b: Box<T>, value: T): Box<T> {
+        Box { value, ..b }
+    }
+
+    fun wrong_base(o: Other): S {
+        S { f: 0, ..o }
+    }
+
+    fun wrong_field_type(s: S): S {
+        S { f: false, ..s }
+    }
+
+    fun unbound_field(s: S): S {
+        S { h: 0, ..s }
+    }
+
+    fun other_module(o: Other): Other {
+        Other { value: 0, ..o }
+    }
+
+    fun base_by_reference(s: &S): S {
+        S { f: 0, ..s }
+    }
+}
@@ -2,7 +2,11 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::BTreeMap, fs, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
 
 use move_command_line_common::{
     env::read_bool_env_var,
@@ -24,6 +28,15 @@ const VERIFICATION_EXT: &str = "verification";
 /// Root of tests which require to set flavor flags.
 const FLAVOR_PATH: &str = "flavors/";
 
+/// Root of tests which require the lints to be run.
+const LINTER_PATH: &str = "linter/";
+
+/// Root of tests which require features to be enabled, for `#[cfg(...)]` attributes.
+const CFG_PATH: &str = "cfg/";
+
+/// The only feature enabled in tests under `CFG_PATH`.
+const CFG_FEATURE: &str = "enabled";
+
 fn default_testing_addresses() -> BTreeMap<String, NumericalAddress> {
     let mapping = [
         ("std", "0x1"),
@@ -98,6 +111,11 @@ fn move_check_testsuite(path: &Path) -> datatest_stable::Result<()> {
                 .to_string();
             flags = flags.set_flavor(flavor)
         }
+        Some(p) if p.contains(LINTER_PATH) => flags = flags.set_lint(true),
+        Some(p) if p.contains(CFG_PATH) => {
+            let features = BTreeSet::from([CFG_FEATURE.into()]);
+            flags = flags.set_features(BTreeMap::from([(None, features)]))
+        }
         _ => {}
     };
     run_test(path, &exp_path, &out_path, flags)?;
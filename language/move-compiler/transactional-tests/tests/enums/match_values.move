@@ -0,0 +1,80 @@
+//# publish
+module 0x42::Shapes {
+    enum Shape has copy, drop {
+        Circle { radius: u64 },
+        Rect { width: u64, height: u64 },
+        Empty,
+    }
+
+    enum Box<T> has drop {
+        Full { value: T },
+        Nothing,
+    }
+
+    public fun circle(radius: u64): Shape {
+        Shape::Circle { radius }
+    }
+
+    public fun rect(width: u64, height: u64): Shape {
+        Shape::Rect { width, height }
+    }
+
+    public fun empty(): Shape {
+        Shape::Empty
+    }
+
+    public fun area(s: Shape): u64 {
+        match (s) {
+            Shape::Circle { radius } => 3 * radius * radius,
+            Shape::Rect { width, height } => width * height,
+            Shape::Empty => 0,
+        }
+    }
+
+    public fun is_empty(s: &Shape): bool {
+        match (s) {
+            Shape::Empty => true,
+            _ => false,
+        }
+    }
+
+    public fun grow(s: &mut Shape) {
+        match (s) {
+            Shape::Circle { radius } => *radius = *radius + 1,
+            Shape::Rect { width, height: _ } => *width = *width + 1,
+            Shape::Empty => (),
+        }
+    }
+
+    public fun full<T>(value: T): Box<T> {
+        Box::Full { value }
+    }
+
+    public fun get_or<T: drop>(b: Box<T>, default: T): T {
+        match (b) {
+            Box::Full { value } => value,
+            Box::Nothing => default,
+        }
+    }
+}
+
+//# run
+script {
+use 0x42::Shapes;
+fun main() {
+    assert!(Shapes::area(Shapes::circle(2)) == 12, 0);
+    assert!(Shapes::area(Shapes::rect(2, 5)) == 10, 1);
+    assert!(Shapes::area(Shapes::empty()) == 0, 2);
+    assert!(Shapes::is_empty(&Shapes::empty()), 3);
+    assert!(!Shapes::is_empty(&Shapes::circle(1)), 4);
+
+    let s = Shapes::rect(1, 1);
+    Shapes::grow(&mut s);
+    assert!(Shapes::area(s) == 2, 5);
+    let c = Shapes::circle(1);
+    Shapes::grow(&mut c);
+    assert!(Shapes::area(c) == 12, 6);
+
+    assert!(Shapes::get_or(Shapes::full(7u64), 0) == 7, 7);
+}
+}
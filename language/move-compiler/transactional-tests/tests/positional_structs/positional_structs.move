@@ -0,0 +1,47 @@
+//# publish
+module 0x42::M {
+    struct Coin<phantom T>(u64) has store, drop;
+    struct Pair(u64, bool) has copy, drop, store;
+    struct Wallet(Coin<u8>, Pair) has key;
+
+    public fun mint<T>(value: u64): Coin<T> {
+        Coin(value)
+    }
+
+    public fun value<T>(c: &Coin<T>): u64 {
+        c.0
+    }
+
+    public fun merge<T>(c: &mut Coin<T>, other: Coin<T>) {
+        let Coin(v) = other;
+        c.0 = c.0 + v;
+    }
+
+    public fun swap(p: Pair): Pair {
+        let a;
+        let b;
+        Pair(a, b) = p;
+        Pair(a + 1, !b)
+    }
+
+    public entry fun publish(s: signer) {
+        move_to(&s, Wallet(mint(3), Pair(7, false)))
+    }
+
+    public entry fun check(addr: address) acquires Wallet {
+        let w = borrow_global_mut<Wallet>(addr);
+        merge(&mut w.0, mint(4));
+        assert!(value(&w.0) == 7, 0);
+        w.1 = swap(w.1);
+        assert!(w.1.0 == 8, 1);
+        assert!(w.1.1, 2);
+    }
+}
+
+//# run 0x42::M::publish --signers 0x1
+
+//# view --address 0x1 --resource 0x42::M::Wallet
+
+//# run 0x42::M::check --args @0x1
+
+//# view --address 0x1 --resource 0x42::M::Wallet
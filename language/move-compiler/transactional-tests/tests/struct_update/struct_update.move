@@ -0,0 +1,73 @@
+//# publish
+module 0x42::M {
+    struct Config has copy, drop {
+        max: u64,
+        min: u64,
+        enabled: bool,
+        name: vector<u8>,
+    }
+
+    struct Counter has key {
+        count: u64,
+        step: u64,
+    }
+
+    public fun default(): Config {
+        Config { max: 10, min: 0, enabled: false, name: b"default" }
+    }
+
+    public fun enable(c: Config): Config {
+        Config { enabled: true, ..c }
+    }
+
+    public fun bounds(c: Config, max: u64, min: u64): Config {
+        Config { max, min, ..c }
+    }
+
+    public fun check(c: &Config, max: u64, min: u64, enabled: bool, name: vector<u8>) {
+        assert!(c.max == max, 0);
+        assert!(c.min == min, 1);
+        assert!(c.enabled == enabled, 2);
+        assert!(c.name == name, 3);
+    }
+
+    fun fail<T>(code: u64): T {
+        abort code
+    }
+
+    // The given fields are evaluated before the base
+    public fun order(): Config {
+        Config { max: fail(0), ..fail(1) }
+    }
+
+    public fun publish(account: &signer) {
+        move_to(account, Counter { count: 0, step: 2 })
+    }
+
+    public fun tick(addr: address) acquires Counter {
+        let c = move_from<Counter>(addr);
+        let count = c.count + c.step;
+        let next = Counter { count, ..c };
+        assert!(next.step == 2, 4);
+        let Counter { count: _, step: _ } = next;
+    }
+}
+
+//# run
+script {
+use 0x42::M;
+fun main() {
+    let c = M::default();
+    let e = M::enable(c);
+    M::check(&c, 10, 0, false, b"default");
+    M::check(&e, 10, 0, true, b"default");
+    let b = M::bounds(e, 100, 5);
+    M::check(&b, 100, 5, true, b"default");
+}
+}
+
+//# run 0x42::M::order
+
+//# run 0x42::M::publish --signers 0x1
+
+//# run 0x42::M::tick --args @0x1
@@ -241,6 +241,39 @@ impl fmt::Display for IdentStr {
     }
 }
 
+/// Positional fields, like the `u64` in `struct Coin(u64)`, have no name in source code. In the
+/// binary format, the field at index `i` of such a struct is named `pos{i}`.
+/// The compiler rejects named structs whose fields are exactly `pos0`, `pos1`, ... in order, so
+/// that [`is_positional_struct`] is unambiguous.
+pub const POSITIONAL_FIELD_PREFIX: &str = "pos";
+
+/// Returns the binary format name of the positional field at index `idx`.
+pub fn positional_field_name(idx: usize) -> Identifier {
+    Identifier(format!("{}{}", POSITIONAL_FIELD_PREFIX, idx).into())
+}
+
+/// Returns the index of the positional field named `name` in the binary format, or `None` if
+/// `name` is not the name of a positional field.
+pub fn positional_field_index(name: &IdentStr) -> Option<usize> {
+    let digits = name.as_str().strip_prefix(POSITIONAL_FIELD_PREFIX)?;
+    let idx = digits.parse::<usize>().ok()?;
+    // Reject non-canonical forms, e.g. `pos01`
+    (idx.to_string() == digits).then_some(idx)
+}
+
+/// Returns true if the given field names are those of a positional struct, i.e. they are
+/// `pos0`, `pos1`, ... in declaration order. Structs without fields are not positional.
+pub fn is_positional_struct<'a>(field_names: impl IntoIterator<Item = &'a IdentStr>) -> bool {
+    let mut count = 0;
+    for (idx, name) in field_names.into_iter().enumerate() {
+        if positional_field_index(name) != Some(idx) {
+            return false;
+        }
+        count += 1;
+    }
+    count > 0
+}
+
 #[cfg(any(test, feature = "fuzzing"))]
 impl Arbitrary for Identifier {
     type Parameters = ();
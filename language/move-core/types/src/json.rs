@@ -0,0 +1,562 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A human-readable encoding of Move values, guided by their type layouts, meant to be used with
+//! `serde_json`. Values are mapped as follows:
+//!   - `bool`, `u8`, `u16` and `u32` are JSON booleans and numbers;
+//!   - `u64`, `u128` and `u256` are decimal strings, as they do not fit in the numbers most JSON
+//!     implementations can represent exactly;
+//!   - `address` and `signer` are hex strings such as `"0x1"`;
+//!   - `vector<u8>` is a hex string such as `"0xcafe"`, other vectors are arrays;
+//!   - `std::string::String` and `std::ascii::String` are strings, which must only contain ASCII
+//!     characters for the latter;
+//!   - `std::option::Option<T>` is an array of the `T` it contains, if any, such as `[]` or `[5]`,
+//!     so that nested options such as `some(none)` (`[[]]`) and `none` (`[]`) stay distinct;
+//!   - other structs are objects keyed by field name, or arrays of fields if the layout does not
+//!     have field names;
+//!   - enum values are arrays of their variant tag and fields, such as `[1, [...]]`.
+//!
+//! The special cases of the standard library types only apply to layouts with types, see
+//! `MoveStructLayout::WithTypes`. Decoding also accepts numbers for `u64`, `u128` and `u256`, and
+//! arrays of numbers for `vector<u8>`.
+
+use crate::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::CORE_CODE_ADDRESS,
+    u256,
+    value::{
+        MoveEnumLayout, MoveFieldLayout, MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue,
+        MoveVariant,
+    },
+};
+use serde::{
+    de::{DeserializeSeed, Error as DeError, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    ser::{Error as SerError, SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{fmt, str::FromStr};
+
+/// A Move value along with its layout, serialized to its JSON encoding.
+pub struct JsonMoveValue<'a> {
+    value: &'a MoveValue,
+    layout: &'a MoveTypeLayout,
+}
+
+impl<'a> JsonMoveValue<'a> {
+    pub fn new(value: &'a MoveValue, layout: &'a MoveTypeLayout) -> Self {
+        Self { value, layout }
+    }
+}
+
+/// Deserializes a Move value of the given layout from its JSON encoding. Structs are decorated
+/// as described by the layout, as if deserialized with `MoveValue::simple_deserialize`.
+pub struct JsonMoveValueSeed<'a>(pub &'a MoveTypeLayout);
+
+/// The standard library types with a dedicated encoding.
+enum SpecialStruct {
+    String,
+    AsciiString,
+    Option,
+}
+
+impl SpecialStruct {
+    fn of(layout: &MoveStructLayout) -> Option<Self> {
+        let tag = match layout {
+            MoveStructLayout::WithTypes { type_, .. } => type_,
+            MoveStructLayout::Runtime(_) | MoveStructLayout::WithFields(_) => return None,
+        };
+        if tag.address != CORE_CODE_ADDRESS {
+            return None;
+        }
+        match (tag.module.as_str(), tag.name.as_str()) {
+            ("string", "String") => Some(Self::String),
+            ("ascii", "String") => Some(Self::AsciiString),
+            ("option", "Option") => Some(Self::Option),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Serialize for JsonMoveValue<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use MoveTypeLayout as L;
+        use MoveValue as V;
+
+        match (self.layout, self.value) {
+            (L::Bool, V::Bool(b)) => serializer.serialize_bool(*b),
+            (L::U8, V::U8(n)) => serializer.serialize_u8(*n),
+            (L::U16, V::U16(n)) => serializer.serialize_u16(*n),
+            (L::U32, V::U32(n)) => serializer.serialize_u32(*n),
+            (L::U64, V::U64(n)) => serializer.collect_str(n),
+            (L::U128, V::U128(n)) => serializer.collect_str(n),
+            (L::U256, V::U256(n)) => serializer.collect_str(n),
+            (L::Address, V::Address(a)) | (L::Signer, V::Signer(a)) => {
+                serializer.serialize_str(&a.to_hex_literal())
+            }
+            (L::Vector(elem), V::Vector(vals)) => {
+                if let L::U8 = &**elem {
+                    let bytes = MoveValue::vec_to_vec_u8(vals.clone()).map_err(S::Error::custom)?;
+                    return serializer.serialize_str(&format!("0x{}", hex::encode(bytes)));
+                }
+                let mut seq = serializer.serialize_seq(Some(vals.len()))?;
+                for val in vals {
+                    seq.serialize_element(&JsonMoveValue::new(val, elem))?;
+                }
+                seq.end()
+            }
+            (L::Struct(layout), V::Struct(s)) => serialize_struct(serializer, layout, s),
+            (L::Enum(layout), V::Variant(v)) => serialize_variant(serializer, layout, v),
+            (layout, value) => Err(S::Error::custom(format!(
+                "value {} does not match layout {}",
+                value, layout
+            ))),
+        }
+    }
+}
+
+fn serialize_struct<S: Serializer>(
+    serializer: S,
+    layout: &MoveStructLayout,
+    value: &MoveStruct,
+) -> Result<S::Ok, S::Error> {
+    let fields = field_values(value);
+    let layouts = field_layouts(layout);
+    if fields.len() != layouts.len() {
+        return Err(S::Error::custom(format!(
+            "struct has {} fields but its layout has {}",
+            fields.len(),
+            layouts.len()
+        )));
+    }
+    match (SpecialStruct::of(layout), fields.as_slice()) {
+        (
+            Some(kind @ (SpecialStruct::String | SpecialStruct::AsciiString)),
+            [MoveValue::Vector(bytes)],
+        ) => {
+            let bytes = MoveValue::vec_to_vec_u8(bytes.clone()).map_err(S::Error::custom)?;
+            let s = String::from_utf8(bytes).map_err(S::Error::custom)?;
+            if matches!(kind, SpecialStruct::AsciiString) && !s.is_ascii() {
+                return Err(S::Error::custom(format!("invalid ASCII string: {:?}", s)));
+            }
+            return serializer.serialize_str(&s);
+        }
+        (Some(SpecialStruct::Option), [MoveValue::Vector(vals)]) => {
+            // Not encoded as a vector, which would be a hex string for `Option<u8>`
+            return match (layouts[0], vals.len()) {
+                (MoveTypeLayout::Vector(elem), 0 | 1) => {
+                    serializer.collect_seq(vals.iter().map(|val| JsonMoveValue::new(val, elem)))
+                }
+                _ => Err(S::Error::custom("malformed option value")),
+            };
+        }
+        _ => (),
+    }
+
+    let names = match layout {
+        MoveStructLayout::Runtime(_) => None,
+        MoveStructLayout::WithFields(fields) | MoveStructLayout::WithTypes { fields, .. } => {
+            Some(fields.iter().map(|field| &field.name))
+        }
+    };
+    match names {
+        Some(names) => {
+            let mut map = serializer.serialize_map(Some(fields.len()))?;
+            for ((name, val), layout) in names.zip(fields).zip(layouts) {
+                map.serialize_entry(name.as_str(), &JsonMoveValue::new(val, layout))?;
+            }
+            map.end()
+        }
+        None => {
+            let mut seq = serializer.serialize_seq(Some(fields.len()))?;
+            for (val, layout) in fields.into_iter().zip(layouts) {
+                seq.serialize_element(&JsonMoveValue::new(val, layout))?;
+            }
+            seq.end()
+        }
+    }
+}
+
+fn field_values(value: &MoveStruct) -> Vec<&MoveValue> {
+    match value {
+        MoveStruct::Runtime(vals) => vals.iter().collect(),
+        MoveStruct::WithFields(fields) | MoveStruct::WithTypes { fields, .. } => {
+            fields.iter().map(|(_, val)| val).collect()
+        }
+    }
+}
+
+fn field_layouts(layout: &MoveStructLayout) -> Vec<&MoveTypeLayout> {
+    match layout {
+        MoveStructLayout::Runtime(layouts) => layouts.iter().collect(),
+        MoveStructLayout::WithFields(fields) | MoveStructLayout::WithTypes { fields, .. } => {
+            fields.iter().map(|field| &field.layout).collect()
+        }
+    }
+}
+
+fn serialize_variant<S: Serializer>(
+    serializer: S,
+    layout: &MoveEnumLayout,
+    value: &MoveVariant,
+) -> Result<S::Ok, S::Error> {
+    let layouts = match layout.variants().get(value.tag as usize) {
+        Some(layouts) if layouts.len() == value.fields.len() => layouts,
+        _ => return Err(S::Error::custom("variant does not match the enum layout")),
+    };
+    let mut seq = serializer.serialize_seq(Some(2))?;
+    seq.serialize_element(&value.tag)?;
+    seq.serialize_element(&JsonFields(&value.fields, layouts))?;
+    seq.end()
+}
+
+struct JsonFields<'a>(&'a [MoveValue], &'a [MoveTypeLayout]);
+
+impl<'a> Serialize for JsonFields<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for (val, layout) in self.0.iter().zip(self.1) {
+            seq.serialize_element(&JsonMoveValue::new(val, layout))?;
+        }
+        seq.end()
+    }
+}
+
+impl<'d, 'a> DeserializeSeed<'d> for JsonMoveValueSeed<'a> {
+    type Value = MoveValue;
+
+    fn deserialize<D: Deserializer<'d>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        use MoveTypeLayout as L;
+
+        match self.0 {
+            L::Bool => bool::deserialize(deserializer).map(MoveValue::Bool),
+            L::U8 | L::U16 | L::U32 | L::U64 | L::U128 | L::U256 => {
+                deserializer.deserialize_any(IntegerVisitor(self.0))
+            }
+            L::Address => deserializer
+                .deserialize_str(AddressVisitor)
+                .map(MoveValue::Address),
+            L::Signer => deserializer
+                .deserialize_str(AddressVisitor)
+                .map(MoveValue::Signer),
+            L::Vector(elem) => match &**elem {
+                L::U8 => deserializer
+                    .deserialize_any(BytesVisitor)
+                    .map(MoveValue::vector_u8),
+                _ => deserializer
+                    .deserialize_seq(VectorVisitor(elem))
+                    .map(MoveValue::Vector),
+            },
+            L::Struct(layout) => deserialize_struct(deserializer, layout).map(MoveValue::Struct),
+            L::Enum(layout) => deserializer
+                .deserialize_seq(VariantVisitor(layout))
+                .map(MoveValue::Variant),
+        }
+    }
+}
+
+fn deserialize_struct<'d, D: Deserializer<'d>>(
+    deserializer: D,
+    layout: &MoveStructLayout,
+) -> Result<MoveStruct, D::Error> {
+    let fields = match (SpecialStruct::of(layout), layout) {
+        (Some(kind @ (SpecialStruct::String | SpecialStruct::AsciiString)), _) => {
+            let s = String::deserialize(deserializer)?;
+            if matches!(kind, SpecialStruct::AsciiString) && !s.is_ascii() {
+                return Err(D::Error::custom(format!("invalid ASCII string: {:?}", s)));
+            }
+            vec![MoveValue::vector_u8(s.into_bytes())]
+        }
+        (Some(SpecialStruct::Option), _) => {
+            let elem = match field_layouts(layout).as_slice() {
+                [MoveTypeLayout::Vector(elem)] => &**elem,
+                _ => return Err(D::Error::custom("malformed option layout")),
+            };
+            let val = deserializer.deserialize_seq(OptionVisitor(elem))?;
+            vec![MoveValue::Vector(val.into_iter().collect())]
+        }
+        (None, MoveStructLayout::Runtime(layouts)) => {
+            deserializer.deserialize_seq(VectorFieldsVisitor(
+                layouts.iter().collect(),
+                "a struct given as an array of fields",
+            ))?
+        }
+        (None, MoveStructLayout::WithFields(fields))
+        | (None, MoveStructLayout::WithTypes { fields, .. }) => {
+            deserializer.deserialize_map(StructFieldsVisitor(
+                fields
+                    .iter()
+                    .map(|field| (&field.name, &field.layout))
+                    .collect(),
+            ))?
+        }
+    };
+    Ok(decorate_fields(layout, fields))
+}
+
+/// Builds a struct from the values of its fields, decorated as described by its layout.
+fn decorate_fields(layout: &MoveStructLayout, fields: Vec<MoveValue>) -> MoveStruct {
+    match layout {
+        MoveStructLayout::Runtime(_) => MoveStruct::Runtime(fields),
+        MoveStructLayout::WithFields(layouts) => {
+            MoveStruct::WithFields(name_fields(layouts, fields))
+        }
+        MoveStructLayout::WithTypes {
+            type_,
+            fields: layouts,
+        } => MoveStruct::WithTypes {
+            type_: type_.clone(),
+            fields: name_fields(layouts, fields),
+        },
+    }
+}
+
+fn name_fields(
+    layouts: &[MoveFieldLayout],
+    fields: Vec<MoveValue>,
+) -> Vec<(Identifier, MoveValue)> {
+    layouts
+        .iter()
+        .map(|field| field.name.clone())
+        .zip(fields)
+        .collect()
+}
+
+struct IntegerVisitor<'a>(&'a MoveTypeLayout);
+
+impl<'a> IntegerVisitor<'a> {
+    fn value<E: DeError>(&self, n: impl Into<u256::U256> + fmt::Display) -> Result<MoveValue, E> {
+        let display = n.to_string();
+        let n: u256::U256 = n.into();
+        let out_of_range = || E::custom(format!("{} is out of range for {}", display, self.0));
+        Ok(match self.0 {
+            MoveTypeLayout::U8 => MoveValue::U8(n.try_into().map_err(|_| out_of_range())?),
+            MoveTypeLayout::U16 => MoveValue::U16(n.try_into().map_err(|_| out_of_range())?),
+            MoveTypeLayout::U32 => MoveValue::U32(n.try_into().map_err(|_| out_of_range())?),
+            MoveTypeLayout::U64 => MoveValue::U64(n.try_into().map_err(|_| out_of_range())?),
+            MoveTypeLayout::U128 => MoveValue::U128(n.try_into().map_err(|_| out_of_range())?),
+            MoveTypeLayout::U256 => MoveValue::U256(n),
+            _ => return Err(E::custom(format!("{} is not an integer type", self.0))),
+        })
+    }
+}
+
+impl<'d, 'a> Visitor<'d> for IntegerVisitor<'a> {
+    type Value = MoveValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "a {} given as a number or a decimal string",
+            self.0
+        )
+    }
+
+    fn visit_u64<E: DeError>(self, v: u64) -> Result<Self::Value, E> {
+        self.value(v)
+    }
+
+    fn visit_u128<E: DeError>(self, v: u128) -> Result<Self::Value, E> {
+        self.value(v)
+    }
+
+    fn visit_i64<E: DeError>(self, v: i64) -> Result<Self::Value, E> {
+        match u64::try_from(v) {
+            Ok(v) => self.value(v),
+            Err(_) => Err(E::custom(format!("{} is out of range for {}", v, self.0))),
+        }
+    }
+
+    fn visit_str<E: DeError>(self, v: &str) -> Result<Self::Value, E> {
+        let n = u256::U256::from_str_radix(v, 10)
+            .map_err(|_| E::custom(format!("invalid {}: {:?}", self.0, v)))?;
+        self.value(n)
+    }
+}
+
+struct AddressVisitor;
+
+impl<'d> Visitor<'d> for AddressVisitor {
+    type Value = AccountAddress;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an address given as a hex string")
+    }
+
+    fn visit_str<E: DeError>(self, v: &str) -> Result<Self::Value, E> {
+        AccountAddress::from_str(v).map_err(|_| E::custom(format!("invalid address: {:?}", v)))
+    }
+}
+
+struct BytesVisitor;
+
+impl<'d> Visitor<'d> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a vector<u8> given as a hex string or an array of numbers")
+    }
+
+    fn visit_str<E: DeError>(self, v: &str) -> Result<Self::Value, E> {
+        hex::decode(v.strip_prefix("0x").unwrap_or(v))
+            .map_err(|_| E::custom(format!("invalid hex string: {:?}", v)))
+    }
+
+    fn visit_seq<A: SeqAccess<'d>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = vec![];
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte)
+        }
+        Ok(bytes)
+    }
+}
+
+struct VectorVisitor<'a>(&'a MoveTypeLayout);
+
+impl<'d, 'a> Visitor<'d> for VectorVisitor<'a> {
+    type Value = Vec<MoveValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "an array of {}", self.0)
+    }
+
+    fn visit_seq<A: SeqAccess<'d>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut vals = vec![];
+        while let Some(val) = seq.next_element_seed(JsonMoveValueSeed(self.0))? {
+            vals.push(val)
+        }
+        Ok(vals)
+    }
+}
+
+/// Visits a fixed number of values given as an array, such as the fields of a struct.
+struct VectorFieldsVisitor<'a>(Vec<&'a MoveTypeLayout>, &'static str);
+
+impl<'d, 'a> Visitor<'d> for VectorFieldsVisitor<'a> {
+    type Value = Vec<MoveValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{} of length {}", self.1, self.0.len())
+    }
+
+    fn visit_seq<A: SeqAccess<'d>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut vals = vec![];
+        for (i, layout) in self.0.iter().enumerate() {
+            match seq.next_element_seed(JsonMoveValueSeed(layout))? {
+                Some(val) => vals.push(val),
+                None => return Err(A::Error::invalid_length(i, &self)),
+            }
+        }
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(A::Error::invalid_length(self.0.len() + 1, &self));
+        }
+        Ok(vals)
+    }
+}
+
+struct StructFieldsVisitor<'a>(Vec<(&'a Identifier, &'a MoveTypeLayout)>);
+
+impl<'d, 'a> Visitor<'d> for StructFieldsVisitor<'a> {
+    type Value = Vec<MoveValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a struct given as an object")
+    }
+
+    fn visit_map<A: MapAccess<'d>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut vals: Vec<Option<MoveValue>> = self.0.iter().map(|_| None).collect();
+        while let Some(key) = map.next_key::<String>()? {
+            let idx = match self.0.iter().position(|(name, _)| name.as_str() == key) {
+                Some(idx) => idx,
+                None => return Err(A::Error::custom(format!("unknown field `{}`", key))),
+            };
+            if vals[idx].is_some() {
+                return Err(A::Error::custom(format!("duplicate field `{}`", key)));
+            }
+            vals[idx] = Some(map.next_value_seed(JsonMoveValueSeed(self.0[idx].1))?);
+        }
+        vals.into_iter()
+            .zip(&self.0)
+            .map(|(val, (name, _))| {
+                val.ok_or_else(|| A::Error::custom(format!("missing field `{}`", name)))
+            })
+            .collect()
+    }
+}
+
+struct OptionVisitor<'a>(&'a MoveTypeLayout);
+
+impl<'d, 'a> Visitor<'d> for OptionVisitor<'a> {
+    type Value = Option<MoveValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "an option given as an array of at most one {}",
+            self.0
+        )
+    }
+
+    fn visit_seq<A: SeqAccess<'d>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let val = seq.next_element_seed(JsonMoveValueSeed(self.0))?;
+        if val.is_some() && seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(A::Error::invalid_length(2, &self));
+        }
+        Ok(val)
+    }
+}
+
+struct VariantVisitor<'a>(&'a MoveEnumLayout);
+
+impl<'d, 'a> Visitor<'d> for VariantVisitor<'a> {
+    type Value = MoveVariant;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an enum value given as an array of its tag and fields")
+    }
+
+    fn visit_seq<A: SeqAccess<'d>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let tag: u16 = match seq.next_element()? {
+            Some(tag) => tag,
+            None => return Err(A::Error::invalid_length(0, &self)),
+        };
+        let layouts = self.variant(tag)?;
+        let fields = match seq.next_element_seed(VariantFieldsSeed(layouts))? {
+            Some(fields) => fields,
+            None => return Err(A::Error::invalid_length(1, &self)),
+        };
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(A::Error::invalid_length(3, &self));
+        }
+        Ok(MoveVariant { tag, fields })
+    }
+}
+
+impl<'a> VariantVisitor<'a> {
+    fn variant<E: DeError>(&self, tag: u16) -> Result<&'a [MoveTypeLayout], E> {
+        self.0
+            .variants()
+            .get(tag as usize)
+            .map(Vec::as_slice)
+            .ok_or_else(|| {
+                E::custom(format!(
+                    "invalid variant tag {} for enum with {} variants",
+                    tag,
+                    self.0.variants().len()
+                ))
+            })
+    }
+}
+
+struct VariantFieldsSeed<'a>(&'a [MoveTypeLayout]);
+
+impl<'d, 'a> DeserializeSeed<'d> for VariantFieldsSeed<'a> {
+    type Value = Vec<MoveValue>;
+
+    fn deserialize<D: Deserializer<'d>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(VectorFieldsVisitor(
+            self.0.iter().collect(),
+            "the fields of a variant given as an array",
+        ))
+    }
+}
@@ -10,6 +10,7 @@ pub mod effects;
 pub mod errmap;
 pub mod gas_algebra;
 pub mod identifier;
+pub mod json;
 pub mod language_storage;
 pub mod metadata;
 pub mod move_resource;
@@ -2,7 +2,10 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::identifier::{IdentStr, Identifier, ALLOWED_IDENTIFIERS, ALLOWED_NO_SELF_IDENTIFIERS};
+use crate::identifier::{
+    is_positional_struct, positional_field_index, positional_field_name, IdentStr, Identifier,
+    ALLOWED_IDENTIFIERS, ALLOWED_NO_SELF_IDENTIFIERS,
+};
 use bcs::test_helpers::assert_canonical_encode_decode;
 use once_cell::sync::Lazy;
 use proptest::prelude::*;
@@ -123,3 +126,21 @@ fn serde_serialize_no_wrapper() {
     let s = serde_json::to_string(&foobar).expect("Identifier should serialize correctly");
     assert_eq!(s, "\"foobar\"");
 }
+
+#[test]
+fn positional_field_names() {
+    let name = positional_field_name(12);
+    assert_eq!(name.as_str(), "pos12");
+    assert_eq!(positional_field_index(&name), Some(12));
+    for s in ["pos", "pos01", "pos_1", "position", "x0"] {
+        let ident = Identifier::new(s).unwrap();
+        assert_eq!(positional_field_index(&ident), None, "{}", s);
+    }
+
+    let names = (0..3).map(positional_field_name).collect::<Vec<_>>();
+    assert!(is_positional_struct(names.iter().map(|n| n.as_ident_str())));
+    assert!(!is_positional_struct(
+        names.iter().skip(1).map(|n| n.as_ident_str())
+    ));
+    assert!(!is_positional_struct(std::iter::empty()));
+}
@@ -0,0 +1,319 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    json::{JsonMoveValue, JsonMoveValueSeed},
+    language_storage::{StructTag, TypeTag},
+    u256::U256,
+    value::{MoveEnumLayout, MoveFieldLayout, MoveStructLayout, MoveTypeLayout, MoveValue},
+};
+use serde::de::DeserializeSeed;
+use serde_json::{json, Value};
+
+fn struct_tag(
+    address: AccountAddress,
+    module: &str,
+    name: &str,
+    ty_args: Vec<TypeTag>,
+) -> StructTag {
+    StructTag {
+        address,
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: ty_args,
+    }
+}
+
+fn field(name: &str, layout: MoveTypeLayout) -> MoveFieldLayout {
+    MoveFieldLayout::new(Identifier::new(name).unwrap(), layout)
+}
+
+fn string_layout() -> MoveTypeLayout {
+    MoveTypeLayout::Struct(MoveStructLayout::with_types(
+        struct_tag(AccountAddress::ONE, "string", "String", vec![]),
+        vec![field(
+            "bytes",
+            MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+        )],
+    ))
+}
+
+fn ascii_string_layout() -> MoveTypeLayout {
+    MoveTypeLayout::Struct(MoveStructLayout::with_types(
+        struct_tag(AccountAddress::ONE, "ascii", "String", vec![]),
+        vec![field(
+            "bytes",
+            MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+        )],
+    ))
+}
+
+fn option_layout(elem: MoveTypeLayout, elem_tag: TypeTag) -> MoveTypeLayout {
+    MoveTypeLayout::Struct(MoveStructLayout::with_types(
+        struct_tag(AccountAddress::ONE, "option", "Option", vec![elem_tag]),
+        vec![field("vec", MoveTypeLayout::Vector(Box::new(elem)))],
+    ))
+}
+
+fn to_json(value: &MoveValue, layout: &MoveTypeLayout) -> Value {
+    serde_json::to_value(JsonMoveValue::new(value, layout)).unwrap()
+}
+
+fn from_json(json: Value, layout: &MoveTypeLayout) -> serde_json::Result<MoveValue> {
+    JsonMoveValueSeed(layout).deserialize(json)
+}
+
+/// Checks that `bcs` is encoded as `json`, and decoded back from it.
+fn check_round_trip(bcs: Vec<u8>, layout: &MoveTypeLayout, json: Value) {
+    let value = MoveValue::simple_deserialize(&bcs, layout).unwrap();
+    assert_eq!(to_json(&value, layout), json);
+    let decoded = from_json(json, layout).unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(decoded.undecorate().simple_serialize().unwrap(), bcs);
+}
+
+#[test]
+fn primitives() {
+    use MoveTypeLayout as L;
+    check_round_trip(bcs::to_bytes(&true).unwrap(), &L::Bool, json!(true));
+    check_round_trip(bcs::to_bytes(&7u8).unwrap(), &L::U8, json!(7));
+    check_round_trip(bcs::to_bytes(&7u16).unwrap(), &L::U16, json!(7));
+    check_round_trip(bcs::to_bytes(&7u32).unwrap(), &L::U32, json!(7));
+    check_round_trip(
+        bcs::to_bytes(&u64::MAX).unwrap(),
+        &L::U64,
+        json!("18446744073709551615"),
+    );
+    check_round_trip(
+        bcs::to_bytes(&u128::MAX).unwrap(),
+        &L::U128,
+        json!("340282366920938463463374607431768211455"),
+    );
+    check_round_trip(
+        bcs::to_bytes(&(U256::from(u128::MAX) * U256::from(2u8))).unwrap(),
+        &L::U256,
+        json!("680564733841876926926749214863536422910"),
+    );
+    check_round_trip(
+        bcs::to_bytes(&AccountAddress::ONE).unwrap(),
+        &L::Address,
+        json!("0x1"),
+    );
+}
+
+#[test]
+fn vectors() {
+    use MoveTypeLayout as L;
+    check_round_trip(
+        bcs::to_bytes(&vec![0xcau8, 0xfe]).unwrap(),
+        &L::Vector(Box::new(L::U8)),
+        json!("0xcafe"),
+    );
+    check_round_trip(
+        bcs::to_bytes(&vec![1u64, 2]).unwrap(),
+        &L::Vector(Box::new(L::U64)),
+        json!(["1", "2"]),
+    );
+    check_round_trip(
+        bcs::to_bytes(&vec![vec![1u8], vec![]]).unwrap(),
+        &L::Vector(Box::new(L::Vector(Box::new(L::U8)))),
+        json!(["0x01", "0x"]),
+    );
+}
+
+#[test]
+fn structs() {
+    use MoveTypeLayout as L;
+    let fields = vec![field("f", L::U64), field("g", L::Bool)];
+    let bcs = bcs::to_bytes(&(3u64, true)).unwrap();
+    check_round_trip(
+        bcs.clone(),
+        &L::Struct(MoveStructLayout::new(vec![L::U64, L::Bool])),
+        json!(["3", true]),
+    );
+    check_round_trip(
+        bcs.clone(),
+        &L::Struct(MoveStructLayout::with_fields(fields.clone())),
+        json!({ "f": "3", "g": true }),
+    );
+    check_round_trip(
+        bcs,
+        &L::Struct(MoveStructLayout::with_types(
+            struct_tag(AccountAddress::TWO, "M", "S", vec![]),
+            fields,
+        )),
+        json!({ "f": "3", "g": true }),
+    );
+}
+
+#[test]
+fn standard_library_types() {
+    check_round_trip(
+        bcs::to_bytes("hello").unwrap(),
+        &string_layout(),
+        json!("hello"),
+    );
+    let layout = option_layout(MoveTypeLayout::U64, TypeTag::U64);
+    check_round_trip(
+        bcs::to_bytes(&Vec::<u64>::new()).unwrap(),
+        &layout,
+        json!([]),
+    );
+    check_round_trip(bcs::to_bytes(&vec![5u64]).unwrap(), &layout, json!(["5"]));
+
+    let layout = option_layout(
+        string_layout(),
+        TypeTag::Struct(Box::new(struct_tag(
+            AccountAddress::ONE,
+            "string",
+            "String",
+            vec![],
+        ))),
+    );
+    check_round_trip(bcs::to_bytes(&vec!["a"]).unwrap(), &layout, json!(["a"]));
+
+    check_round_trip(
+        bcs::to_bytes("hello").unwrap(),
+        &ascii_string_layout(),
+        json!("hello"),
+    );
+
+    // Only the standard library types are special-cased
+    let layout = MoveTypeLayout::Struct(MoveStructLayout::with_types(
+        struct_tag(AccountAddress::TWO, "string", "String", vec![]),
+        vec![field(
+            "bytes",
+            MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+        )],
+    ));
+    check_round_trip(
+        bcs::to_bytes("hi").unwrap(),
+        &layout,
+        json!({ "bytes": "0x6869" }),
+    );
+}
+
+#[test]
+fn options() {
+    // Options of bytes are not encoded like `vector<u8>`
+    let layout = option_layout(MoveTypeLayout::U8, TypeTag::U8);
+    check_round_trip(bcs::to_bytes(&vec![7u8]).unwrap(), &layout, json!([7]));
+    check_round_trip(
+        bcs::to_bytes(&Vec::<u8>::new()).unwrap(),
+        &layout,
+        json!([]),
+    );
+
+    // Nested options are all distinct
+    let layout = option_layout(
+        option_layout(MoveTypeLayout::U64, TypeTag::U64),
+        TypeTag::Struct(Box::new(struct_tag(
+            AccountAddress::ONE,
+            "option",
+            "Option",
+            vec![TypeTag::U64],
+        ))),
+    );
+    check_round_trip(
+        bcs::to_bytes(&Vec::<Vec<u64>>::new()).unwrap(),
+        &layout,
+        json!([]),
+    );
+    check_round_trip(
+        bcs::to_bytes(&vec![Vec::<u64>::new()]).unwrap(),
+        &layout,
+        json!([[]]),
+    );
+    check_round_trip(
+        bcs::to_bytes(&vec![vec![5u64]]).unwrap(),
+        &layout,
+        json!([["5"]]),
+    );
+
+    assert!(from_json(json!(null), &layout).is_err());
+    assert!(from_json(json!([[], []]), &layout).is_err());
+    assert!(from_json(json!(["5"]), &layout).is_err());
+}
+
+#[test]
+fn ascii_strings() {
+    assert!(from_json(json!("caf\u{e9}"), &ascii_string_layout()).is_err());
+    assert!(from_json(json!("caf\u{e9}"), &string_layout()).is_ok());
+
+    let value =
+        MoveValue::simple_deserialize(&bcs::to_bytes("caf\u{e9}").unwrap(), &ascii_string_layout())
+            .unwrap();
+    assert!(serde_json::to_value(JsonMoveValue::new(&value, &ascii_string_layout())).is_err());
+}
+
+#[test]
+fn enums() {
+    let layout = MoveTypeLayout::Enum(MoveEnumLayout::new(vec![
+        vec![],
+        vec![MoveTypeLayout::U64, MoveTypeLayout::Bool],
+    ]));
+    check_round_trip(
+        bcs::to_bytes(&(1u16, (9u64, false))).unwrap(),
+        &layout,
+        json!([1, ["9", false]]),
+    );
+    check_round_trip(bcs::to_bytes(&(0u16, ())).unwrap(), &layout, json!([0, []]));
+    assert!(from_json(json!([0]), &layout).is_err());
+    assert!(from_json(json!([2, []]), &layout).is_err());
+    assert!(from_json(json!([1, ["9"]]), &layout).is_err());
+}
+
+#[test]
+fn lenient_decoding() {
+    use MoveTypeLayout as L;
+    assert_eq!(from_json(json!(5), &L::U64).unwrap(), MoveValue::U64(5));
+    assert_eq!(from_json(json!("5"), &L::U8).unwrap(), MoveValue::U8(5));
+    assert_eq!(
+        from_json(json!([1, 2]), &L::Vector(Box::new(L::U8))).unwrap(),
+        MoveValue::vector_u8(vec![1, 2])
+    );
+    assert_eq!(
+        from_json(json!("cafe"), &L::Vector(Box::new(L::U8))).unwrap(),
+        MoveValue::vector_u8(vec![0xca, 0xfe])
+    );
+    assert_eq!(
+        from_json(
+            json!(format!("0x{}2", "0".repeat(AccountAddress::LENGTH * 2 - 1))),
+            &L::Address
+        )
+        .unwrap(),
+        MoveValue::Address(AccountAddress::TWO)
+    );
+}
+
+#[test]
+fn invalid_json() {
+    use MoveTypeLayout as L;
+    assert!(from_json(json!(256), &L::U8).is_err());
+    assert!(from_json(json!(-1), &L::U64).is_err());
+    assert!(from_json(json!("1.5"), &L::U64).is_err());
+    assert!(from_json(json!("0xzz"), &L::Vector(Box::new(L::U8))).is_err());
+    assert!(from_json(json!("not an address"), &L::Address).is_err());
+
+    let layout = L::Struct(MoveStructLayout::with_fields(vec![
+        field("f", L::U64),
+        field("g", L::Bool),
+    ]));
+    assert!(from_json(json!({ "f": "1" }), &layout).is_err());
+    assert!(from_json(json!({ "f": "1", "g": true, "h": 0 }), &layout).is_err());
+    assert!(from_json(json!(["1", true]), &layout).is_err());
+    let layout = L::Struct(MoveStructLayout::new(vec![L::U64]));
+    assert!(from_json(json!(["1", "2"]), &layout).is_err());
+}
+
+#[test]
+fn mismatched_value() {
+    let value = MoveValue::Bool(true);
+    assert!(serde_json::to_value(JsonMoveValue::new(&value, &MoveTypeLayout::U64)).is_err());
+    let value =
+        MoveValue::simple_deserialize(&bcs::to_bytes(&vec![0xffu8]).unwrap(), &string_layout())
+            .unwrap();
+    assert!(serde_json::to_value(JsonMoveValue::new(&value, &string_layout())).is_err());
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod identifier_test;
+mod json_test;
 mod language_storage_test;
 mod value_test;
 mod vm_status_test;
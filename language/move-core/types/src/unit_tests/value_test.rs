@@ -7,8 +7,9 @@ use crate::{
     ident_str,
     identifier::Identifier,
     language_storage::{StructTag, TypeTag},
-    value::{MoveStruct, MoveValue},
+    value::{MoveEnumLayout, MoveStruct, MoveTypeLayout, MoveValue, MoveVariant},
 };
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 #[test]
@@ -136,3 +137,69 @@ fn nested_typed_struct_deserialization() {
         })
     );
 }
+
+#[test]
+fn variant_serialization_round_trip() {
+    let layout = MoveTypeLayout::Enum(MoveEnumLayout::new(vec![
+        vec![],
+        vec![MoveTypeLayout::U64, MoveTypeLayout::Bool],
+    ]));
+    let value = MoveValue::Variant(MoveVariant::new(
+        1,
+        vec![MoveValue::U64(7), MoveValue::Bool(true)],
+    ));
+    let blob = value.simple_serialize().unwrap();
+    // the tag is serialized as a ULEB128 variant index, followed by the fields of the variant
+    assert_eq!(blob, vec![1, 7, 0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(
+        MoveValue::simple_deserialize(&blob, &layout).unwrap(),
+        value
+    );
+
+    // a tag without a matching variant is rejected
+    assert!(MoveValue::simple_deserialize(&[2], &layout).is_err());
+}
+
+#[test]
+fn variant_serialization_matches_rust_enum() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum E {
+        A,
+        B(u64, bool),
+        C { x: u8 },
+    }
+
+    let layout = MoveTypeLayout::Enum(MoveEnumLayout::new(vec![
+        vec![],
+        vec![MoveTypeLayout::U64, MoveTypeLayout::Bool],
+        vec![MoveTypeLayout::U8],
+    ]));
+    let values = [
+        (E::A, MoveVariant::new(0, vec![])),
+        (
+            E::B(7, true),
+            MoveVariant::new(1, vec![MoveValue::U64(7), MoveValue::Bool(true)]),
+        ),
+        (E::C { x: 3 }, MoveVariant::new(2, vec![MoveValue::U8(3)])),
+    ];
+    for (rust_value, variant) in values {
+        let value = MoveValue::Variant(variant);
+        let blob = bcs::to_bytes(&rust_value).unwrap();
+        assert_eq!(value.simple_serialize().unwrap(), blob);
+        assert_eq!(
+            MoveValue::simple_deserialize(&blob, &layout).unwrap(),
+            value
+        );
+        assert_eq!(bcs::from_bytes::<E>(&blob).unwrap(), rust_value);
+    }
+
+    // tags past 127 take two bytes
+    let layout = MoveTypeLayout::Enum(MoveEnumLayout::new(vec![vec![]; 200]));
+    let value = MoveValue::Variant(MoveVariant::new(150, vec![]));
+    let blob = value.simple_serialize().unwrap();
+    assert_eq!(blob, vec![0x96, 0x01]);
+    assert_eq!(
+        MoveValue::simple_deserialize(&blob, &layout).unwrap(),
+        value
+    );
+}
@@ -10,8 +10,8 @@ use crate::{
 };
 use anyhow::{anyhow, bail, Result as AResult};
 use serde::{
-    de::Error as DeError,
-    ser::{SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple},
+    de::{Error as DeError, VariantAccess},
+    ser::{SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple, SerializeTupleVariant},
     Deserialize, Serialize,
 };
 use std::{
@@ -22,6 +22,14 @@ use std::{
 /// In the `WithTypes` configuration, a Move struct gets serialized into a Serde struct with this name
 pub const MOVE_STRUCT_NAME: &str = "struct";
 
+/// A Move enum value gets serialized into a Serde enum with this name, with the variant tag as its
+/// variant index
+pub const MOVE_ENUM_NAME: &str = "enum";
+
+/// The name of the variants of a Move enum value serialized as a Serde enum. Their tag is only
+/// given by the variant index, as in BCS.
+pub const MOVE_VARIANT_NAME: &str = "variant";
+
 /// In the `WithTypes` configuration, a Move struct gets serialized into a Serde struct with this as the first field
 pub const MOVE_STRUCT_TYPE: &str = "type";
 
@@ -55,6 +63,15 @@ pub enum MoveValue {
     U16(u16),
     U32(u32),
     U256(u256::U256),
+    // NOTE: Added in bytecode version v7, do not reorder!
+    Variant(MoveVariant),
+}
+
+/// A value of an enum type: the tag of the variant, followed by the fields of that variant
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MoveVariant {
+    pub tag: u16,
+    pub fields: Vec<MoveValue>,
 }
 
 /// A layout associated with a named field
@@ -83,6 +100,10 @@ pub enum MoveStructLayout {
     },
 }
 
+/// The layout of an enum, given as the field layouts of each variant, indexed by variant tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveEnumLayout(pub Vec<Vec<MoveTypeLayout>>);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MoveTypeLayout {
     #[serde(rename(serialize = "bool", deserialize = "bool"))]
@@ -109,6 +130,10 @@ pub enum MoveTypeLayout {
     U32,
     #[serde(rename(serialize = "u256", deserialize = "u256"))]
     U256,
+
+    // NOTE: Added in bytecode version v7, do not reorder!
+    #[serde(rename(serialize = "enum", deserialize = "enum"))]
+    Enum(MoveEnumLayout),
 }
 
 impl MoveValue {
@@ -152,6 +177,7 @@ impl MoveValue {
     pub fn decorate(self, layout: &MoveTypeLayout) -> Self {
         match (self, layout) {
             (MoveValue::Struct(s), MoveTypeLayout::Struct(l)) => MoveValue::Struct(s.decorate(l)),
+            (MoveValue::Variant(v), MoveTypeLayout::Enum(l)) => MoveValue::Variant(v.decorate(l)),
             (MoveValue::Vector(vals), MoveTypeLayout::Vector(t)) => {
                 MoveValue::Vector(vals.into_iter().map(|v| v.decorate(t)).collect())
             }
@@ -162,6 +188,7 @@ impl MoveValue {
     pub fn undecorate(self) -> Self {
         match self {
             Self::Struct(s) => MoveValue::Struct(s.undecorate()),
+            Self::Variant(v) => MoveValue::Variant(v.undecorate()),
             Self::Vector(vals) => {
                 MoveValue::Vector(vals.into_iter().map(MoveValue::undecorate).collect())
             }
@@ -263,6 +290,46 @@ impl MoveStruct {
     }
 }
 
+impl MoveVariant {
+    pub fn new(tag: u16, fields: Vec<MoveValue>) -> Self {
+        Self { tag, fields }
+    }
+
+    pub fn simple_deserialize(blob: &[u8], ty: &MoveEnumLayout) -> AResult<Self> {
+        Ok(bcs::from_bytes_seed(ty, blob)?)
+    }
+
+    pub fn decorate(self, layout: &MoveEnumLayout) -> Self {
+        let Self { tag, fields } = self;
+        let fields = match layout.0.get(tag as usize) {
+            Some(layouts) => fields
+                .into_iter()
+                .zip(layouts)
+                .map(|(v, l)| v.decorate(l))
+                .collect(),
+            None => fields,
+        };
+        Self { tag, fields }
+    }
+
+    pub fn undecorate(self) -> Self {
+        Self {
+            tag: self.tag,
+            fields: self.fields.into_iter().map(MoveValue::undecorate).collect(),
+        }
+    }
+}
+
+impl MoveEnumLayout {
+    pub fn new(variants: Vec<Vec<MoveTypeLayout>>) -> Self {
+        Self(variants)
+    }
+
+    pub fn variants(&self) -> &[Vec<MoveTypeLayout>] {
+        &self.0
+    }
+}
+
 impl MoveStructLayout {
     pub fn new(types: Vec<MoveTypeLayout>) -> Self {
         Self::Runtime(types)
@@ -319,6 +386,7 @@ impl<'d> serde::de::DeserializeSeed<'d> for &MoveTypeLayout {
                 AccountAddress::deserialize(deserializer).map(MoveValue::Signer)
             }
             MoveTypeLayout::Struct(ty) => Ok(MoveValue::Struct(ty.deserialize(deserializer)?)),
+            MoveTypeLayout::Enum(ty) => Ok(MoveValue::Variant(ty.deserialize(deserializer)?)),
             MoveTypeLayout::Vector(layout) => Ok(MoveValue::Vector(
                 deserializer.deserialize_seq(VectorElementVisitor(layout))?,
             )),
@@ -395,6 +463,49 @@ impl<'d, 'a> serde::de::Visitor<'d> for StructFieldVisitor<'a> {
     }
 }
 
+struct EnumVisitor<'a>(&'a MoveEnumLayout);
+
+impl<'d, 'a> serde::de::Visitor<'d> for EnumVisitor<'a> {
+    type Value = MoveVariant;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("Enum")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::EnumAccess<'d>,
+    {
+        let (tag, variant): (u32, _) = data.variant()?;
+        let layouts = match self.0 .0.get(tag as usize) {
+            Some(layouts) => layouts,
+            None => {
+                return Err(A::Error::custom(format!(
+                    "invalid variant tag {} for enum with {} variants",
+                    tag,
+                    self.0 .0.len()
+                )))
+            }
+        };
+        let fields = variant.tuple_variant(layouts.len(), StructFieldVisitor(layouts))?;
+        Ok(MoveVariant {
+            tag: tag as u16,
+            fields,
+        })
+    }
+}
+
+impl<'d> serde::de::DeserializeSeed<'d> for &MoveEnumLayout {
+    type Value = MoveVariant;
+
+    fn deserialize<D: serde::de::Deserializer<'d>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_enum(MOVE_ENUM_NAME, &[], EnumVisitor(self))
+    }
+}
+
 impl<'d> serde::de::DeserializeSeed<'d> for &MoveFieldLayout {
     type Value = (Identifier, MoveValue);
 
@@ -443,6 +554,7 @@ impl serde::Serialize for MoveValue {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         match self {
             MoveValue::Struct(s) => s.serialize(serializer),
+            MoveValue::Variant(v) => v.serialize(serializer),
             MoveValue::Bool(b) => serializer.serialize_bool(*b),
             MoveValue::U8(i) => serializer.serialize_u8(*i),
             MoveValue::U16(i) => serializer.serialize_u16(*i),
@@ -463,6 +575,22 @@ impl serde::Serialize for MoveValue {
     }
 }
 
+impl serde::Serialize for MoveVariant {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // A variant is serialized as a Serde tuple variant, i.e. its tag followed by its fields
+        let mut t = serializer.serialize_tuple_variant(
+            MOVE_ENUM_NAME,
+            self.tag as u32,
+            MOVE_VARIANT_NAME,
+            self.fields.len(),
+        )?;
+        for v in self.fields.iter() {
+            t.serialize_field(v)?;
+        }
+        t.end()
+    }
+}
+
 struct MoveFields<'a>(&'a [(Identifier, MoveValue)]);
 
 impl<'a> serde::Serialize for MoveFields<'a> {
@@ -523,7 +651,22 @@ impl fmt::Display for MoveTypeLayout {
             Vector(typ) => write!(f, "vector<{}>", typ),
             Struct(s) => write!(f, "{}", s),
             Signer => write!(f, "signer"),
+            Enum(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl fmt::Display for MoveEnumLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> std::fmt::Result {
+        write!(f, "enum {{ ")?;
+        for (tag, layouts) in self.0.iter().enumerate() {
+            write!(f, "{}: {{ ", tag)?;
+            for (i, l) in layouts.iter().enumerate() {
+                write!(f, "{}: {}, ", i, l)?
+            }
+            write!(f, "}}, ")?;
         }
+        write!(f, "}}")
     }
 }
 
@@ -572,6 +715,9 @@ impl TryInto<TypeTag> for &MoveTypeLayout {
                 TypeTag::Vector(Box::new(inner_type.try_into()?))
             }
             MoveTypeLayout::Struct(v) => TypeTag::Struct(Box::new(v.try_into()?)),
+            MoveTypeLayout::Enum(_) => bail!(
+                "Invalid MoveTypeLayout -> TypeTag conversion--enum layouts do not carry their type"
+            ),
         })
     }
 }
@@ -605,6 +751,7 @@ impl fmt::Display for MoveValue {
             MoveValue::Signer(a) => write!(f, "signer({})", a.to_hex_literal()),
             MoveValue::Vector(v) => fmt_list(f, "vector[", v, "]"),
             MoveValue::Struct(s) => fmt::Display::fmt(s, f),
+            MoveValue::Variant(v) => fmt::Display::fmt(v, f),
         }
     }
 }
@@ -624,6 +771,12 @@ impl fmt::Display for MoveStruct {
     }
 }
 
+impl fmt::Display for MoveVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_list(f, &format!("variant#{}[", self.tag), &self.fields, "]")
+    }
+}
+
 struct DisplayFieldBinding<'a>(&'a (Identifier, MoveValue));
 
 impl fmt::Display for DisplayFieldBinding<'_> {
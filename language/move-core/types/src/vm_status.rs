@@ -630,11 +630,13 @@ pub enum StatusCode {
     MAX_FIELD_DEFINITIONS_REACHED = 1121,
     // Reserved error code for future use
     TOO_MANY_BACK_EDGES = 1122,
-    RESERVED_VERIFICATION_ERROR_1 = 1123,
-    RESERVED_VERIFICATION_ERROR_2 = 1124,
-    RESERVED_VERIFICATION_ERROR_3 = 1125,
-    RESERVED_VERIFICATION_ERROR_4 = 1126,
-    RESERVED_VERIFICATION_ERROR_5 = 1127,
+    // An enum was declared without any variants.
+    ZERO_VARIANT_ENUM = 1123,
+    // An enum was declared with the `key` ability.
+    ENUM_WITH_KEY_ABILITY = 1124,
+    TEST_VARIANT_TYPE_MISMATCH_ERROR = 1125,
+    RESERVED_VERIFICATION_ERROR_1 = 1126,
+    RESERVED_VERIFICATION_ERROR_2 = 1127,
 
     // These are errors that the VM might raise if a violation of internal
     // invariants takes place.
@@ -716,17 +718,20 @@ pub enum StatusCode {
     VM_MAX_VALUE_DEPTH_REACHED = 4025,
     VM_EXTENSION_ERROR = 4026,
     STORAGE_WRITE_LIMIT_REACHED = 4027,
+    // The values held by an execution exceed the configured memory quota.
     MEMORY_LIMIT_EXCEEDED = 4028,
     VM_MAX_TYPE_NODES_REACHED = 4029,
     EXECUTION_LIMIT_REACHED = 4030,
     IO_LIMIT_REACHED = 4031,
     STORAGE_LIMIT_REACHED = 4032,
+    // Unpacking an enum value, or a reference to one, as a variant it does not hold.
+    VARIANT_TAG_MISMATCH = 4033,
+    // A vector instruction would make a vector longer than the configured maximum.
+    VM_MAX_VECTOR_LENGTH_REACHED = 4034,
     // Reserved error code for future use
-    RESERVED_RUNTIME_ERROR_1 = 4033,
-    RESERVED_RUNTIME_ERROR_2 = 4034,
-    RESERVED_RUNTIME_ERROR_3 = 4035,
-    RESERVED_RUNTIME_ERROR_4 = 4036,
-    RESERVED_RUNTIME_ERROR_5 = 4037,
+    RESERVED_RUNTIME_ERROR_1 = 4035,
+    RESERVED_RUNTIME_ERROR_2 = 4036,
+    RESERVED_RUNTIME_ERROR_3 = 4037,
 
     // A reserved status to represent an unknown vm status.
     // this is std::u64::MAX, but we can't pattern match on that, so put the hardcoded value in
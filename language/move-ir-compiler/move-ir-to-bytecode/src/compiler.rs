@@ -7,12 +7,13 @@ use anyhow::{bail, format_err, Result};
 use move_binary_format::{
     file_format::{
         Ability, AbilitySet, Bytecode, CodeOffset, CodeUnit, CompiledModule, CompiledScript,
-        Constant, FieldDefinition, FunctionDefinition, FunctionSignature, ModuleHandle, Signature,
-        SignatureToken, StructDefinition, StructDefinitionIndex, StructFieldInformation,
-        StructHandleIndex, StructTypeParameter, TableIndex, TypeParameterIndex, TypeSignature,
-        Visibility,
+        Constant, EnumDefinition, FieldDefinition, FunctionDefinition, FunctionSignature,
+        ModuleHandle, Signature, SignatureToken, StructDefinition, StructDefinitionIndex,
+        StructFieldInformation, StructHandleIndex, StructTypeParameter, TableIndex,
+        TypeParameterIndex, TypeSignature, VariantDefinition, VariantHandleIndex,
+        VariantInstantiationHandleIndex, VariantTag, Visibility,
     },
-    file_format_common::VERSION_MAX,
+    file_format_common::{VERSION_7, VERSION_DEFAULT},
 };
 use move_bytecode_source_map::source_map::SourceMap;
 use move_core_types::value::{MoveTypeLayout, MoveValue};
@@ -376,7 +377,7 @@ pub fn compile_script<'a>(
         source_map,
     ) = context.materialize_pools();
     let script = CompiledScript {
-        version: VERSION_MAX,
+        version: VERSION_DEFAULT,
         module_handles,
         struct_handles,
         function_handles,
@@ -447,7 +448,7 @@ pub fn compile_module<'a>(
     }
 
     // Compile definitions
-    let struct_defs = compile_structs(&mut context, &self_name, module.structs)?;
+    let (struct_defs, enum_defs) = compile_structs(&mut context, &self_name, module.structs)?;
     let function_defs = compile_functions(&mut context, &self_name, module.functions)?;
 
     let (
@@ -463,12 +464,15 @@ pub fn compile_module<'a>(
             function_instantiations,
             struct_def_instantiations,
             field_instantiations,
+            enum_def_instantiations,
+            variant_handles,
+            variant_instantiation_handles,
         },
         _compiled_deps,
         source_map,
     ) = context.materialize_pools();
-    let module = CompiledModule {
-        version: VERSION_MAX,
+    let mut module = CompiledModule {
+        version: VERSION_DEFAULT,
         module_handles,
         self_module_handle_idx,
         struct_handles,
@@ -485,7 +489,14 @@ pub fn compile_module<'a>(
         metadata: vec![],
         struct_defs,
         function_defs,
+        enum_defs,
+        enum_def_instantiations,
+        variant_handles,
+        variant_instantiation_handles,
     };
+    if module.has_enums() {
+        module.version = VERSION_7;
+    }
     Ok((module, source_map))
 }
 
@@ -542,12 +553,13 @@ fn compile_explicit_dependency_declarations(
                 function_instantiations,
                 struct_def_instantiations,
                 field_instantiations,
+                ..
             },
             compiled_deps,
             _source_map,
         ) = context.materialize_pools();
         let compiled_module = CompiledModule {
-            version: VERSION_MAX,
+            version: VERSION_DEFAULT,
             module_handles,
             self_module_handle_idx,
             struct_handles,
@@ -564,6 +576,10 @@ fn compile_explicit_dependency_declarations(
             metadata: vec![],
             struct_defs: vec![],
             function_defs: vec![],
+            enum_defs: vec![],
+            enum_def_instantiations: vec![],
+            variant_handles: vec![],
+            variant_instantiation_handles: vec![],
         };
         dependencies_acc = compiled_deps;
         dependencies_acc.insert(
@@ -719,14 +735,25 @@ fn compile_structs(
     context: &mut Context,
     self_name: &ModuleName,
     structs: Vec<ast::StructDefinition>,
-) -> Result<Vec<StructDefinition>> {
+) -> Result<(Vec<StructDefinition>, Vec<EnumDefinition>)> {
     let mut struct_defs = vec![];
+    let mut enum_defs = vec![];
     for s in structs {
         let sident = QualifiedStructIdent {
             module: *self_name,
             name: s.value.name.clone(),
         };
         let sh_idx = context.struct_handle_index(sident.clone())?;
+        if let StructDefinitionFields::Variants { variants } = s.value.fields {
+            let m = type_parameter_indexes(s.value.type_formals.iter().map(|formal| &formal.1))?;
+            context.declare_enum_definition_index(s.value.name.clone())?;
+            let variants = compile_variants(context, &m, &s.value.name, variants)?;
+            enum_defs.push(EnumDefinition {
+                enum_handle: sh_idx,
+                variants,
+            });
+            continue;
+        }
         record_src_loc!(struct_decl: context, s.loc);
         record_src_loc!(struct_type_formals: context, &s.value.type_formals);
         let m = type_parameter_indexes(s.value.type_formals.iter().map(|formal| &formal.1))?;
@@ -737,7 +764,37 @@ fn compile_structs(
             field_information,
         });
     }
-    Ok(struct_defs)
+    Ok((struct_defs, enum_defs))
+}
+
+fn compile_variants(
+    context: &mut Context,
+    type_parameters: &HashMap<TypeVar_, TypeParameterIndex>,
+    enum_name: &StructName,
+    variants: Vec<(VariantName, Fields<Type>)>,
+) -> Result<Vec<VariantDefinition>> {
+    let mut decl_variants = vec![];
+    for (tag, (v, fields)) in variants.into_iter().enumerate() {
+        if tag > VariantTag::MAX as usize {
+            bail!("too many variants for enum {}", enum_name)
+        }
+        let name = context.identifier_index(v.0)?;
+        context.declare_variant(enum_name.clone(), v, tag as VariantTag);
+        let mut decl_fields = vec![];
+        for (f, ty) in fields {
+            let name = context.identifier_index(f.value.0)?;
+            let sig_token = compile_type(context, type_parameters, &ty)?;
+            decl_fields.push(FieldDefinition {
+                name,
+                signature: TypeSignature(sig_token),
+            });
+        }
+        decl_variants.push(VariantDefinition {
+            name,
+            fields: decl_fields,
+        });
+    }
+    Ok(decl_variants)
 }
 
 fn compile_fields(
@@ -763,6 +820,9 @@ fn compile_fields(
             }
             StructFieldInformation::Declared(decl_fields)
         }
+        StructDefinitionFields::Variants { .. } => {
+            bail!("ICE enums are not compiled as struct definitions")
+        }
     })
 }
 
@@ -1714,6 +1774,36 @@ fn compile_bytecode(
                 Bytecode::UnpackGeneric(si_idx)
             }
         }
+        IRBytecode_::PackVariant(n, v, tys) => {
+            match variant_handle(context, function_frame, &n, &v, &tys)? {
+                VariantHandleKind::Plain(h) => Bytecode::PackVariant(h),
+                VariantHandleKind::Generic(h) => Bytecode::PackVariantGeneric(h),
+            }
+        }
+        IRBytecode_::UnpackVariant(n, v, tys) => {
+            match variant_handle(context, function_frame, &n, &v, &tys)? {
+                VariantHandleKind::Plain(h) => Bytecode::UnpackVariant(h),
+                VariantHandleKind::Generic(h) => Bytecode::UnpackVariantGeneric(h),
+            }
+        }
+        IRBytecode_::UnpackVariantImmRef(n, v, tys) => {
+            match variant_handle(context, function_frame, &n, &v, &tys)? {
+                VariantHandleKind::Plain(h) => Bytecode::UnpackVariantImmRef(h),
+                VariantHandleKind::Generic(h) => Bytecode::UnpackVariantImmRefGeneric(h),
+            }
+        }
+        IRBytecode_::UnpackVariantMutRef(n, v, tys) => {
+            match variant_handle(context, function_frame, &n, &v, &tys)? {
+                VariantHandleKind::Plain(h) => Bytecode::UnpackVariantMutRef(h),
+                VariantHandleKind::Generic(h) => Bytecode::UnpackVariantMutRefGeneric(h),
+            }
+        }
+        IRBytecode_::TestVariant(n, v, tys) => {
+            match variant_handle(context, function_frame, &n, &v, &tys)? {
+                VariantHandleKind::Plain(h) => Bytecode::TestVariant(h),
+                VariantHandleKind::Generic(h) => Bytecode::TestVariantGeneric(h),
+            }
+        }
         IRBytecode_::ReadRef => Bytecode::ReadRef,
         IRBytecode_::WriteRef => Bytecode::WriteRef,
         IRBytecode_::FreezeRef => Bytecode::FreezeRef,
@@ -1907,6 +1997,34 @@ fn compile_bytecode(
     Ok(())
 }
 
+enum VariantHandleKind {
+    Plain(VariantHandleIndex),
+    Generic(VariantInstantiationHandleIndex),
+}
+
+fn variant_handle(
+    context: &mut Context,
+    function_frame: &FunctionFrame,
+    n: &StructName,
+    v: &VariantName,
+    tys: &[Type],
+) -> Result<VariantHandleKind> {
+    let def_idx = context.enum_definition_index(n)?;
+    let tag = context.variant_tag(n, v)?;
+    Ok(if tys.is_empty() {
+        VariantHandleKind::Plain(context.variant_handle_index(def_idx, tag)?)
+    } else {
+        let tokens = Signature(compile_types(
+            context,
+            function_frame.type_parameters(),
+            tys,
+        )?);
+        let type_actuals_id = context.signature_index(tokens)?;
+        let ei_idx = context.enum_instantiation_index(def_idx, type_actuals_id)?;
+        VariantHandleKind::Generic(context.variant_instantiation_handle_index(ei_idx, tag)?)
+    })
+}
+
 fn remap_branch_offsets(code: &mut Vec<Bytecode>, fake_to_actual: &HashMap<u16, u16>) {
     for instr in code {
         match instr {
@@ -6,12 +6,15 @@ use anyhow::{bail, format_err, Result};
 use move_binary_format::{
     access::ModuleAccess,
     file_format::{
-        AbilitySet, AddressIdentifierIndex, CodeOffset, Constant, ConstantPoolIndex, FieldHandle,
+        AbilitySet, AddressIdentifierIndex, CodeOffset, Constant, ConstantPoolIndex,
+        EnumDefInstantiation, EnumDefInstantiationIndex, EnumDefinitionIndex, FieldHandle,
         FieldHandleIndex, FieldInstantiation, FieldInstantiationIndex, FunctionDefinitionIndex,
         FunctionHandle, FunctionHandleIndex, FunctionInstantiation, FunctionInstantiationIndex,
         FunctionSignature, IdentifierIndex, ModuleHandle, ModuleHandleIndex, Signature,
         SignatureIndex, SignatureToken, StructDefInstantiation, StructDefInstantiationIndex,
         StructDefinitionIndex, StructHandle, StructHandleIndex, StructTypeParameter, TableIndex,
+        VariantHandle, VariantHandleIndex, VariantInstantiationHandle,
+        VariantInstantiationHandleIndex, VariantTag,
     },
     CompiledModule,
 };
@@ -23,7 +26,7 @@ use move_core_types::{
 use move_ir_types::{
     ast::{
         BlockLabel_, ConstantName, Field_, FunctionName, ModuleIdent, ModuleName,
-        QualifiedStructIdent, StructName,
+        QualifiedStructIdent, StructName, VariantName,
     },
     location::Loc,
 };
@@ -222,6 +225,12 @@ pub struct MaterializedPools {
     pub function_instantiations: Vec<FunctionInstantiation>,
     /// Field instantiation pool
     pub field_instantiations: Vec<FieldInstantiation>,
+    /// Enum instantiation pool
+    pub enum_def_instantiations: Vec<EnumDefInstantiation>,
+    /// Variant handle pool
+    pub variant_handles: Vec<VariantHandle>,
+    /// Variant instantiation pool
+    pub variant_instantiation_handles: Vec<VariantInstantiationHandle>,
     /// Locals signatures pool
     pub signatures: Vec<Signature>,
     /// Identifier pool
@@ -244,6 +253,8 @@ pub(crate) struct Context<'a> {
     modules: HashMap<ModuleName, (ModuleIdent, ModuleHandle)>,
     structs: HashMap<QualifiedStructIdent, StructHandle>,
     struct_defs: HashMap<StructName, TableIndex>,
+    enum_defs: HashMap<StructName, TableIndex>,
+    variants: HashMap<(StructName, VariantName), VariantTag>,
     named_constants: HashMap<ConstantName, TableIndex>,
     labels: HashMap<BlockLabel_, u16>,
 
@@ -265,6 +276,9 @@ pub(crate) struct Context<'a> {
     struct_instantiations: HashMap<StructDefInstantiation, TableIndex>,
     function_instantiations: HashMap<FunctionInstantiation, TableIndex>,
     field_instantiations: HashMap<FieldInstantiation, TableIndex>,
+    enum_instantiations: HashMap<EnumDefInstantiation, TableIndex>,
+    variant_handles: HashMap<VariantHandle, TableIndex>,
+    variant_instantiation_handles: HashMap<VariantInstantiationHandle, TableIndex>,
 
     // The current function index that we are on
     current_function_index: FunctionDefinitionIndex,
@@ -288,6 +302,8 @@ impl<'a> Context<'a> {
             modules: HashMap::new(),
             structs: HashMap::new(),
             struct_defs: HashMap::new(),
+            enum_defs: HashMap::new(),
+            variants: HashMap::new(),
             named_constants: HashMap::new(),
             labels: HashMap::new(),
             fields: HashMap::new(),
@@ -299,6 +315,9 @@ impl<'a> Context<'a> {
             struct_instantiations: HashMap::new(),
             function_instantiations: HashMap::new(),
             field_instantiations: HashMap::new(),
+            enum_instantiations: HashMap::new(),
+            variant_handles: HashMap::new(),
+            variant_instantiation_handles: HashMap::new(),
             signatures: HashMap::new(),
             identifiers: HashMap::new(),
             address_identifiers: HashMap::new(),
@@ -371,6 +390,11 @@ impl<'a> Context<'a> {
             function_instantiations: Self::materialize_map(self.function_instantiations),
             struct_def_instantiations: Self::materialize_map(self.struct_instantiations),
             field_instantiations: Self::materialize_map(self.field_instantiations),
+            enum_def_instantiations: Self::materialize_map(self.enum_instantiations),
+            variant_handles: Self::materialize_map(self.variant_handles),
+            variant_instantiation_handles: Self::materialize_map(
+                self.variant_instantiation_handles,
+            ),
         };
         (materialized_pools, self.dependencies, self.source_map)
     }
@@ -452,6 +476,48 @@ impl<'a> Context<'a> {
         )?))
     }
 
+    /// Get the enum instantiation index for the alias, adds it if missing.
+    pub fn enum_instantiation_index(
+        &mut self,
+        def: EnumDefinitionIndex,
+        type_parameters: SignatureIndex,
+    ) -> Result<EnumDefInstantiationIndex> {
+        let enum_inst = EnumDefInstantiation {
+            def,
+            type_parameters,
+        };
+        Ok(EnumDefInstantiationIndex(get_or_add_item(
+            &mut self.enum_instantiations,
+            enum_inst,
+        )?))
+    }
+
+    /// Get the variant handle index for the alias, adds it if missing.
+    pub fn variant_handle_index(
+        &mut self,
+        enum_def: EnumDefinitionIndex,
+        variant: VariantTag,
+    ) -> Result<VariantHandleIndex> {
+        let variant_handle = VariantHandle { enum_def, variant };
+        Ok(VariantHandleIndex(get_or_add_item(
+            &mut self.variant_handles,
+            variant_handle,
+        )?))
+    }
+
+    /// Get the variant instantiation handle index for the alias, adds it if missing.
+    pub fn variant_instantiation_handle_index(
+        &mut self,
+        enum_def: EnumDefInstantiationIndex,
+        variant: VariantTag,
+    ) -> Result<VariantInstantiationHandleIndex> {
+        let variant_inst = VariantInstantiationHandle { enum_def, variant };
+        Ok(VariantInstantiationHandleIndex(get_or_add_item(
+            &mut self.variant_instantiation_handles,
+            variant_inst,
+        )?))
+    }
+
     /// Get the function instantiation index for the alias, adds it if missing.
     pub fn function_instantiation_index(
         &mut self,
@@ -541,6 +607,22 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// Get the enum definition index, fails if it is not bound.
+    pub fn enum_definition_index(&self, s: &StructName) -> Result<EnumDefinitionIndex> {
+        match self.enum_defs.get(s) {
+            None => bail!("Missing enum definition for {}", s),
+            Some(idx) => Ok(EnumDefinitionIndex(*idx)),
+        }
+    }
+
+    /// Get the tag of a variant, fails if it is not bound.
+    pub fn variant_tag(&self, s: &StructName, v: &VariantName) -> Result<VariantTag> {
+        match self.variants.get(&(s.clone(), v.clone())) {
+            None => bail!("Unbound variant {}::{}", s, v),
+            Some(tag) => Ok(*tag),
+        }
+    }
+
     /// Get the signature pool index, adds it if missing.
     pub fn signature_index(&mut self, sig: Signature) -> Result<SignatureIndex> {
         Ok(SignatureIndex(get_or_add_item(&mut self.signatures, sig)?))
@@ -638,6 +720,23 @@ impl<'a> Context<'a> {
         ))
     }
 
+    /// Given an identifier, declare the enum definition index.
+    pub fn declare_enum_definition_index(&mut self, s: StructName) -> Result<EnumDefinitionIndex> {
+        let idx = self.enum_defs.len();
+        if idx > TABLE_MAX_SIZE {
+            bail!("too many enum definitions {}", s)
+        }
+        Ok(EnumDefinitionIndex(
+            *self.enum_defs.entry(s).or_insert(idx as TableIndex),
+        ))
+    }
+
+    /// Given an enum and one of its variants, declare the tag of the variant.
+    pub fn declare_variant(&mut self, s: StructName, v: VariantName, tag: VariantTag) {
+        // need to handle duplicates
+        self.variants.entry((s, v)).or_insert(tag);
+    }
+
     /// Given an identifier and a signature, creates a function handle and adds it to the pool.
     /// Finds the index for the signature, or adds it to the pool if an identical one has not yet
     /// been used.
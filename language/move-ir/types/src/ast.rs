@@ -251,6 +251,10 @@ pub type Fields<T> = Vec<(Field, T)>;
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct StructName(pub Symbol);
 
+/// Newtype for the name of an enum variant
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct VariantName(pub Symbol);
+
 /// A struct type parameter with its constraints and whether it's declared as phantom.
 pub type StructTypeParameter = (bool, TypeVar, BTreeSet<Ability>);
 
@@ -289,6 +293,10 @@ pub enum StructDefinitionFields {
     Move { fields: Fields<Type> },
     /// The struct is a type provided by the VM
     Native,
+    /// The struct is an enum, with variants in order of their tag
+    Variants {
+        variants: Vec<(VariantName, Fields<Type>)>,
+    },
 }
 
 //**************************************************************************************************
@@ -686,6 +694,11 @@ pub enum Bytecode_ {
     Call(ModuleName, FunctionName, Vec<Type>),
     Pack(StructName, Vec<Type>),
     Unpack(StructName, Vec<Type>),
+    PackVariant(StructName, VariantName, Vec<Type>),
+    UnpackVariant(StructName, VariantName, Vec<Type>),
+    UnpackVariantImmRef(StructName, VariantName, Vec<Type>),
+    UnpackVariantMutRef(StructName, VariantName, Vec<Type>),
+    TestVariant(StructName, VariantName, Vec<Type>),
     ReadRef,
     WriteRef,
     FreezeRef,
@@ -1298,6 +1311,11 @@ impl fmt::Display for StructDefinition_ {
         match &self.fields {
             StructDefinitionFields::Move { fields } => writeln!(f, "{}", format_fields(fields))?,
             StructDefinitionFields::Native => writeln!(f, "{{native}}")?,
+            StructDefinitionFields::Variants { variants } => {
+                for (v, fields) in variants {
+                    writeln!(f, "{}{}", v, format_fields(fields))?
+                }
+            }
         }
         write!(f, ")")
     }
@@ -1339,6 +1357,12 @@ impl fmt::Display for StructName {
     }
 }
 
+impl fmt::Display for VariantName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl fmt::Display for FunctionName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -1740,6 +1764,29 @@ impl fmt::Display for Bytecode_ {
             Bytecode_::Call(m, n, tys) => write!(f, "Call {}.{}{}", m, n, format_type_actuals(tys)),
             Bytecode_::Pack(n, tys) => write!(f, "Pack {}{}", n, format_type_actuals(tys)),
             Bytecode_::Unpack(n, tys) => write!(f, "Unpack {}{}", n, format_type_actuals(tys)),
+            Bytecode_::PackVariant(n, v, tys) => {
+                write!(f, "PackVariant {}::{}{}", n, v, format_type_actuals(tys))
+            }
+            Bytecode_::UnpackVariant(n, v, tys) => {
+                write!(f, "UnpackVariant {}::{}{}", n, v, format_type_actuals(tys))
+            }
+            Bytecode_::UnpackVariantImmRef(n, v, tys) => write!(
+                f,
+                "UnpackVariantImmRef {}::{}{}",
+                n,
+                v,
+                format_type_actuals(tys)
+            ),
+            Bytecode_::UnpackVariantMutRef(n, v, tys) => write!(
+                f,
+                "UnpackVariantMutRef {}::{}{}",
+                n,
+                v,
+                format_type_actuals(tys)
+            ),
+            Bytecode_::TestVariant(n, v, tys) => {
+                write!(f, "TestVariant {}::{}{}", n, v, format_type_actuals(tys))
+            }
             Bytecode_::ReadRef => write!(f, "ReadRef"),
             Bytecode_::WriteRef => write!(f, "WriteRef"),
             Bytecode_::FreezeRef => write!(f, "FreezeRef"),
@@ -1816,7 +1863,7 @@ fn format_move_value(v: &MoveValue) -> String {
                 .join(", ");
             format!("vector[{}]", items)
         }
-        MoveValue::Struct(_) | MoveValue::Signer(_) => {
+        MoveValue::Struct(_) | MoveValue::Signer(_) | MoveValue::Variant(_) => {
             panic!("Should be inexpressible as a constant")
         }
         MoveValue::U16(u) => format!("{}u16", u),
@@ -13,7 +13,7 @@ use num::{BigInt, BigUint, FromPrimitive, Zero};
 use move_compiler::{
     expansion::ast as EA, hlir::ast as HA, naming::ast as NA, parser::ast as PA, shared::Name,
 };
-use move_core_types::value::MoveValue;
+use move_core_types::{identifier::positional_field_name, value::MoveValue};
 use move_ir_types::location::Spanned;
 
 use crate::{
@@ -139,6 +139,15 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
         self.parent.parent.env.symbol_pool()
     }
 
+    /// Shortcut for making the symbol of a field, where the fields `0`, `1`, .. of positional
+    /// structs are named as in bytecode.
+    pub fn field_symbol(&self, name: &str) -> Symbol {
+        match name.parse::<usize>() {
+            Ok(idx) => self.symbol_pool().make(positional_field_name(idx).as_str()),
+            Err(_) => self.symbol_pool().make(name),
+        }
+    }
+
     /// Shortcut for translating a Move AST location into ours.
     pub fn to_loc(&self, loc: &move_ir_types::location::Loc) -> Loc {
         self.parent.parent.env.to_loc(loc)
@@ -1327,7 +1336,7 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
         // 'type var X where X has field F'. This makes unification significant more complex,
         // so lets see how far we get without this.
         let struct_ty = self.subs.specialize(struct_ty);
-        let field_name = self.symbol_pool().make(&name.value);
+        let field_name = self.field_symbol(&name.value);
         if let Type::Struct(mid, sid, targs) = &struct_ty {
             // Lookup the StructEntry in the build. It must be defined for valid
             // Type::Struct instances.
@@ -1695,7 +1704,7 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
                 fields_not_covered.extend(field_decls.keys());
                 let mut args = BTreeMap::new();
                 for (name_loc, name_, (_, exp)) in fields.iter() {
-                    let field_name = self.symbol_pool().make(name_);
+                    let field_name = self.field_symbol(name_);
                     if let Some((idx, field_ty)) = field_decls.get(&field_name) {
                         let exp = self.translate_exp(exp, &field_ty.instantiate(&instantiation));
                         fields_not_covered.remove(&field_name);
@@ -2018,7 +2027,8 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
             | (Type::Error, MoveValue::Vector(_))
             | (Type::Error, MoveValue::Struct(_))
             | (Type::Var(_), MoveValue::Vector(_))
-            | (Type::Var(_), MoveValue::Struct(_)) => {
+            | (Type::Var(_), MoveValue::Struct(_))
+            | (_, MoveValue::Variant(_)) => {
                 self.error(
                     loc,
                     &format!("Not yet supported constant value: {:?}", value),
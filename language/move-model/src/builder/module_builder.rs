@@ -14,7 +14,9 @@ use regex::Regex;
 
 use move_binary_format::{
     access::ModuleAccess,
-    file_format::{AbilitySet, Constant, FunctionDefinitionIndex, StructDefinitionIndex},
+    file_format::{
+        Ability, AbilitySet, Constant, FunctionDefinitionIndex, StructDefinitionIndex,
+    },
     views::{FunctionHandleView, StructHandleView},
     CompiledModule,
 };
@@ -40,10 +42,10 @@ use crate::{
     exp_rewriter::{ExpRewriter, ExpRewriterFunctions, RewriteTarget},
     intrinsics::process_intrinsic_declaration,
     model::{
-        AbilityConstraint, FieldId, FunId, FunctionData, FunctionVisibility, Loc, ModuleId,
-        MoveIrLoc, NamedConstantData, NamedConstantId, NodeId, QualifiedId, QualifiedInstId,
-        SchemaId, SpecFunId, SpecVarId, StructData, StructId, TypeParameter,
-        SCRIPT_BYTECODE_FUN_NAME,
+        AbilityConstraint, FieldId, FunId, FunctionData, FunctionVisibility, InlineFunctionData,
+        Loc, ModuleId, MoveIrLoc, NamedConstantData, NamedConstantId, NodeId, Parameter,
+        QualifiedId, QualifiedInstId, SchemaId, SpecFunId, SpecVarId, StructData, StructId,
+        TypeParameter, SCRIPT_BYTECODE_FUN_NAME,
     },
     options::ModelBuilderOptions,
     pragmas::{
@@ -70,6 +72,8 @@ pub(crate) struct ModuleBuilder<'env, 'translator> {
     pub spec_fun_index: usize,
     /// Translated specification variables.
     pub spec_vars: Vec<SpecVarDecl>,
+    /// Declarations of inline functions.
+    pub inline_functions: BTreeMap<FunId, InlineFunctionData>,
     /// Translated function specifications.
     pub fun_specs: BTreeMap<Symbol, Spec>,
     /// Translated struct specifications.
@@ -123,6 +127,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             spec_funs: vec![],
             spec_fun_index: 0,
             spec_vars: vec![],
+            inline_functions: BTreeMap::new(),
             fun_specs: BTreeMap::new(),
             struct_specs: BTreeMap::new(),
             module_spec: Spec::default(),
@@ -151,12 +156,26 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
     pub fn translate(
         &mut self,
         loc: Loc,
-        module_def: EA::ModuleDefinition,
+        mut module_def: EA::ModuleDefinition,
         compiled_module: CompiledModule,
         source_map: SourceMap,
         function_infos: UniqueMap<PA::FunctionName, FunctionInfo>,
     ) {
+        // Inline functions are expanded at their call sites and have no bytecode. Only their
+        // declarations are kept, while the rest of the translation ignores them.
+        let mut inline_functions = vec![];
+        module_def.functions = module_def.functions.filter_map(|name, fun_def| {
+            if fun_def.inline.is_some() {
+                inline_functions.push((name, fun_def));
+                None
+            } else {
+                Some(fun_def)
+            }
+        });
         self.decl_ana(&module_def, &compiled_module, &source_map);
+        for (name, fun_def) in &inline_functions {
+            self.decl_ana_inline_fun(name, fun_def);
+        }
         self.def_ana(&module_def, function_infos);
         self.collect_spec_block_infos(&module_def);
         let attrs = self.translate_attributes(&module_def.attributes);
@@ -476,6 +495,50 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         self.spec_funs.push(fun_decl);
     }
 
+    fn decl_ana_inline_fun(&mut self, name: &PA::FunctionName, def: &EA::Function) {
+        let name = self.symbol_pool().make(&name.0.value);
+        let mut et = ExpTranslator::new(self);
+        et.enter_scope();
+        let type_params = et
+            .analyze_and_add_type_params(def.signature.type_parameters.iter().map(|(name, _)| name))
+            .into_iter()
+            .zip(&def.signature.type_parameters)
+            .map(|((name, _), (_, abilities))| {
+                let abilities = abilities.iter().fold(AbilitySet::EMPTY, |set, ability| {
+                    set | match ability.value {
+                        PA::Ability_::Copy => Ability::Copy,
+                        PA::Ability_::Drop => Ability::Drop,
+                        PA::Ability_::Store => Ability::Store,
+                        PA::Ability_::Key => Ability::Key,
+                    }
+                });
+                TypeParameter(name, AbilityConstraint(abilities))
+            })
+            .collect();
+        et.enter_scope();
+        let params = et
+            .analyze_and_add_params(&def.signature.parameters, true)
+            .into_iter()
+            .map(|(name, ty)| Parameter(name, ty))
+            .collect();
+        let result_type = et.translate_type(&def.signature.return_type);
+        let visibility = match def.visibility {
+            EA::Visibility::Public(_) => FunctionVisibility::Public,
+            EA::Visibility::Friend(_) => FunctionVisibility::Friend,
+            EA::Visibility::Internal => FunctionVisibility::Private,
+        };
+        let loc = et.to_loc(&def.loc);
+        let data = self.parent.env.create_inline_function_data(
+            name,
+            loc,
+            visibility,
+            type_params,
+            params,
+            result_type,
+        );
+        self.inline_functions.insert(FunId::new(name), data);
+    }
+
     fn decl_ana_spec_block(&mut self, block: &EA::SpecBlock) {
         for member in &block.value.members {
             self.decl_ana_spec_block_member(member)
@@ -863,13 +926,17 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             EA::StructFields::Defined(fields) => {
                 let mut field_map = BTreeMap::new();
                 for (_name_loc, field_name_, (idx, ty)) in fields {
-                    let field_sym = et.symbol_pool().make(field_name_);
+                    let field_sym = et.field_symbol(field_name_);
                     let field_ty = et.translate_type(ty);
                     field_map.insert(field_sym, (*idx, field_ty));
                 }
                 Some(field_map)
             }
             EA::StructFields::Native(_) => None,
+            EA::StructFields::Variants(_) => {
+                et.error(&loc, "enums are not yet supported by the Move model");
+                None
+            }
         };
         self.parent
             .struct_table
@@ -3237,6 +3304,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             named_constants,
             struct_data,
             function_data,
+            std::mem::take(&mut self.inline_functions),
             std::mem::take(&mut self.spec_vars),
             std::mem::take(&mut self.spec_funs),
             std::mem::take(&mut self.module_spec),
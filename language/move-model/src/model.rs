@@ -55,7 +55,7 @@ use move_bytecode_source_map::{mapping::SourceMapping, source_map::SourceMap};
 use move_command_line_common::{address::NumericalAddress, files::FileHash};
 use move_core_types::{
     account_address::AccountAddress,
-    identifier::{IdentStr, Identifier},
+    identifier::{is_positional_struct, IdentStr, Identifier},
     language_storage,
     value::MoveValue,
 };
@@ -1088,6 +1088,7 @@ impl GlobalEnv {
         named_constants: BTreeMap<NamedConstantId, NamedConstantData>,
         mut struct_data: BTreeMap<StructId, StructData>,
         function_data: BTreeMap<FunId, FunctionData>,
+        inline_functions: BTreeMap<FunId, InlineFunctionData>,
         spec_vars: Vec<SpecVarDecl>,
         spec_funs: Vec<SpecFunDecl>,
         module_spec: Spec,
@@ -1147,6 +1148,7 @@ impl GlobalEnv {
             struct_idx_to_id,
             function_data,
             function_idx_to_id,
+            inline_functions,
             spec_vars,
             spec_funs,
             module_spec,
@@ -1175,6 +1177,26 @@ impl GlobalEnv {
         }
     }
 
+    /// Creates data for an inline function.
+    pub fn create_inline_function_data(
+        &self,
+        name: Symbol,
+        loc: Loc,
+        visibility: FunctionVisibility,
+        type_params: Vec<TypeParameter>,
+        params: Vec<Parameter>,
+        result_type: Type,
+    ) -> InlineFunctionData {
+        InlineFunctionData {
+            name,
+            loc,
+            visibility,
+            type_params,
+            params,
+            result_type,
+        }
+    }
+
     /// Creates data for a function, adding any information not contained in bytecode. This is
     /// a helper for adding a new module to the environment.
     pub fn create_function_data(
@@ -1759,6 +1781,10 @@ pub struct ModuleData {
     /// Mapping from function definition index to id in above map.
     pub function_idx_to_id: BTreeMap<FunctionDefinitionIndex, FunId>,
 
+    /// Inline function data. Inline functions have no bytecode and are not part of the
+    /// function data.
+    pub inline_functions: BTreeMap<FunId, InlineFunctionData>,
+
     /// Specification variables, in SpecVarId order.
     pub spec_vars: BTreeMap<SpecVarId, SpecVarDecl>,
 
@@ -1795,6 +1821,7 @@ impl ModuleData {
             struct_idx_to_id: BTreeMap::new(),
             function_data: BTreeMap::new(),
             function_idx_to_id: BTreeMap::new(),
+            inline_functions: BTreeMap::new(),
             // below this line is source/prover specific
             spec_vars: BTreeMap::new(),
             spec_funs: BTreeMap::new(),
@@ -2059,6 +2086,33 @@ impl<'env> ModuleEnv<'env> {
             })
     }
 
+    /// Gets an inline function in this module by name.
+    pub fn find_inline_function(&self, name: Symbol) -> Option<InlineFunctionEnv<'env>> {
+        self.data
+            .inline_functions
+            .get(&FunId(name))
+            .map(|data| InlineFunctionEnv {
+                module_env: self.clone(),
+                data,
+            })
+    }
+
+    /// Returns an iterator over the inline functions of this module.
+    pub fn get_inline_functions(&'env self) -> impl Iterator<Item = InlineFunctionEnv<'env>> {
+        self.clone().into_inline_functions()
+    }
+
+    /// Returns an iterator over the inline functions of this module.
+    pub fn into_inline_functions(self) -> impl Iterator<Item = InlineFunctionEnv<'env>> {
+        self.data
+            .inline_functions
+            .iter()
+            .map(move |(_, data)| InlineFunctionEnv {
+                module_env: self.clone(),
+                data,
+            })
+    }
+
     /// Gets a FunctionEnv in this module by name.
     pub fn find_function(&self, name: Symbol) -> Option<FunctionEnv<'env>> {
         let id = FunId(name);
@@ -2563,6 +2617,22 @@ impl<'env> StructEnv<'env> {
         }
     }
 
+    /// Returns true if this struct is declared with positional fields, as in `struct S(u64)`.
+    pub fn is_positional(&self) -> bool {
+        match &self.data.info {
+            StructInfo::Declared { def_idx, .. } => {
+                let module = &self.module_env.data.module;
+                match &module.struct_def_at(*def_idx).field_information {
+                    StructFieldInformation::Native => false,
+                    StructFieldInformation::Declared(fields) => is_positional_struct(
+                        fields.iter().map(|field| module.identifier_at(field.name)),
+                    ),
+                }
+            }
+            StructInfo::Generated { .. } => false,
+        }
+    }
+
     /// Returns true if this struct has the pragma intrinsic set to true.
     pub fn is_intrinsic(&self) -> bool {
         self.is_pragma_true(INTRINSIC_PRAGMA, || {
@@ -2951,6 +3021,91 @@ impl<'env> NamedConstantEnv<'env> {
     }
 }
 
+// =================================================================================================
+/// # Inline Function Environment
+
+/// A function declared `inline`. Inline functions are expanded at their call sites by the
+/// compiler, so they have no bytecode and only their declaration is part of the model.
+#[derive(Debug)]
+pub struct InlineFunctionData {
+    /// The name of this function
+    name: Symbol,
+
+    /// The location of this function
+    loc: Loc,
+
+    /// The visibility of this function
+    visibility: FunctionVisibility,
+
+    /// The type parameters of this function
+    type_params: Vec<TypeParameter>,
+
+    /// The parameters of this function
+    params: Vec<Parameter>,
+
+    /// The result type of this function
+    result_type: Type,
+}
+
+#[derive(Debug)]
+pub struct InlineFunctionEnv<'env> {
+    /// Reference to enclosing module.
+    pub module_env: ModuleEnv<'env>,
+
+    data: &'env InlineFunctionData,
+}
+
+impl<'env> InlineFunctionEnv<'env> {
+    /// Returns the name of this function
+    pub fn get_name(&self) -> Symbol {
+        self.data.name
+    }
+
+    /// Returns documentation associated with this function
+    pub fn get_doc(&self) -> &str {
+        self.module_env.env.get_doc(&self.data.loc)
+    }
+
+    /// Returns the location of this function
+    pub fn get_loc(&self) -> Loc {
+        self.data.loc.clone()
+    }
+
+    /// Returns the visibility of this function
+    pub fn visibility(&self) -> FunctionVisibility {
+        self.data.visibility
+    }
+
+    /// Return the visibility string for this function. Useful for formatted printing.
+    pub fn visibility_str(&self) -> &str {
+        match self.data.visibility {
+            FunctionVisibility::Public => "public ",
+            FunctionVisibility::Friend => "public(friend) ",
+            FunctionVisibility::Private => "",
+        }
+    }
+
+    /// Return whether this function can be called outside of the module
+    pub fn is_exposed(&self) -> bool {
+        self.data.visibility != FunctionVisibility::Private
+    }
+
+    /// Returns the type parameters of this function
+    pub fn get_type_parameters(&self) -> &[TypeParameter] {
+        &self.data.type_params
+    }
+
+    /// Returns the parameters of this function
+    pub fn get_parameters(&self) -> &[Parameter] {
+        &self.data.params
+    }
+
+    /// Returns the result type of this function, which is a tuple if it returns multiple values
+    pub fn get_result_type(&self) -> &Type {
+        &self.data.result_type
+    }
+}
+
 // =================================================================================================
 /// # Function Environment
 
@@ -1228,7 +1228,12 @@ impl<'a> fmt::Display for TypeDisplay<'a> {
                 f.write_str("|")?;
                 comma_list(f, ts)?;
                 f.write_str("|")?;
-                write!(f, "{}", t.display(self.context))
+                // As in Move, a function type without result omits it
+                if matches!(t.as_ref(), Tuple(ts) if ts.is_empty()) {
+                    Ok(())
+                } else {
+                    write!(f, "{}", t.display(self.context))
+                }
             }
             Struct(mid, sid, ts) => {
                 write!(f, "{}", self.struct_str(*mid, *sid))?;
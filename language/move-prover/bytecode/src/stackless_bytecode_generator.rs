@@ -90,9 +90,14 @@ impl<'a> StacklessBytecodeGenerator<'a> {
             };
         }
 
-        // Generate bytecode.
-        for (code_offset, bytecode) in original_code.iter().enumerate() {
-            self.generate_bytecode(bytecode, code_offset as CodeOffset, &label_map);
+        // Generate bytecode. Enums are not yet supported, so functions using them are reported,
+        // and translated as aborting so that the other functions can still be processed.
+        if let Some(code_offset) = original_code.iter().position(is_enum_operation) {
+            self.generate_unsupported_enum_operation(code_offset as CodeOffset);
+        } else {
+            for (code_offset, bytecode) in original_code.iter().enumerate() {
+                self.generate_bytecode(bytecode, code_offset as CodeOffset, &label_map);
+            }
         }
 
         // Eliminate fall-through for non-branching instructions
@@ -129,6 +134,21 @@ impl<'a> StacklessBytecodeGenerator<'a> {
         )
     }
 
+    /// Report the enum operation at `code_offset` as unsupported, and generate code which aborts.
+    fn generate_unsupported_enum_operation(&mut self, code_offset: CodeOffset) {
+        let attr_id = self.new_loc_attr(code_offset);
+        self.func_env.module_env.env.error(
+            &self.location_table[&attr_id],
+            "enum operations are not yet supported by the Move Prover",
+        );
+        let temp_index = self.temp_count;
+        self.local_types.push(Type::Primitive(PrimitiveType::U64));
+        self.temp_count += 1;
+        self.code
+            .push(Bytecode::Load(attr_id, temp_index, Constant::U64(0)));
+        self.code.push(Bytecode::Abort(attr_id, temp_index));
+    }
+
     /// Create a new attribute id and populate location table.
     fn new_loc_attr(&mut self, code_offset: CodeOffset) -> AttrId {
         let loc = self.func_env.get_bytecode_loc(code_offset);
@@ -1166,6 +1186,20 @@ impl<'a> StacklessBytecodeGenerator<'a> {
 
             MoveBytecode::Nop => self.code.push(Bytecode::Nop(attr_id)),
 
+            // TODO prover support for enums, functions using them are not translated
+            MoveBytecode::PackVariant(_)
+            | MoveBytecode::PackVariantGeneric(_)
+            | MoveBytecode::UnpackVariant(_)
+            | MoveBytecode::UnpackVariantGeneric(_)
+            | MoveBytecode::UnpackVariantImmRef(_)
+            | MoveBytecode::UnpackVariantImmRefGeneric(_)
+            | MoveBytecode::UnpackVariantMutRef(_)
+            | MoveBytecode::UnpackVariantMutRefGeneric(_)
+            | MoveBytecode::TestVariant(_)
+            | MoveBytecode::TestVariantGeneric(_) => {
+                unreachable!("functions with enum operations are not translated")
+            }
+
             // TODO full prover support for vector bytecode instructions
             // These should go to non-functional call operations
             MoveBytecode::VecLen(sig) => {
@@ -1371,3 +1405,19 @@ impl<'a> StacklessBytecodeGenerator<'a> {
         }
     }
 }
+
+fn is_enum_operation(bytecode: &MoveBytecode) -> bool {
+    matches!(
+        bytecode,
+        MoveBytecode::PackVariant(_)
+            | MoveBytecode::PackVariantGeneric(_)
+            | MoveBytecode::UnpackVariant(_)
+            | MoveBytecode::UnpackVariantGeneric(_)
+            | MoveBytecode::UnpackVariantImmRef(_)
+            | MoveBytecode::UnpackVariantImmRefGeneric(_)
+            | MoveBytecode::UnpackVariantMutRef(_)
+            | MoveBytecode::UnpackVariantMutRefGeneric(_)
+            | MoveBytecode::TestVariant(_)
+            | MoveBytecode::TestVariantGeneric(_)
+    )
+}
@@ -0,0 +1,23 @@
+// Enums are not yet supported by the model and the prover, so they are reported as errors.
+
+module 0x42::Enums {
+    enum Shape has drop {
+        Circle { radius: u64 },
+        Square { side: u64 },
+    }
+
+    fun circle(radius: u64): Shape {
+        Shape::Circle { radius }
+    }
+
+    fun size(s: &Shape): u64 {
+        match (s) {
+            Shape::Circle { radius } => *radius,
+            Shape::Square { side } => *side,
+        }
+    }
+
+    fun no_enum_operation(x: u64): u64 {
+        x + 1
+    }
+}
@@ -6,18 +6,21 @@
 use log::{debug, info, warn};
 
 use codespan::{ByteIndex, Span};
-use itertools::Itertools;
-use move_compiler::parser::keywords::{BUILTINS, CONTEXTUAL_KEYWORDS, KEYWORDS};
+use itertools::{Either, Itertools};
+use move_compiler::{
+    compiled_unit::{DeprecationInfo, DeprecationMetadata},
+    parser::keywords::{BUILTINS, CONTEXTUAL_KEYWORDS, KEYWORDS},
+};
 use move_model::{
     ast::{ModuleName, SpecBlockInfo, SpecBlockTarget},
     code_writer::{CodeWriter, CodeWriterLabel},
     emit, emitln,
     model::{
-        AbilitySet, FunId, FunctionEnv, GlobalEnv, Loc, ModuleEnv, ModuleId, NamedConstantEnv,
-        Parameter, QualifiedId, StructEnv, TypeParameter,
+        AbilitySet, FunId, FunctionEnv, GlobalEnv, InlineFunctionEnv, Loc, ModuleEnv, ModuleId,
+        NamedConstantEnv, Parameter, QualifiedId, StructEnv, TypeParameter,
     },
     symbol::Symbol,
-    ty::TypeDisplayContext,
+    ty::{Type, TypeDisplayContext},
 };
 use num::BigUint;
 use once_cell::sync::Lazy;
@@ -537,6 +540,8 @@ impl<'env> Docgen<'env> {
 
         self.increment_section_nest();
 
+        self.deprecation_notice(self.deprecations().module.as_ref());
+
         // Document module overview.
         self.doc_text(module_env.get_doc());
 
@@ -610,11 +615,21 @@ impl<'env> Docgen<'env> {
         let funs = module_env
             .get_functions()
             .filter(|f| self.options.include_private_fun || f.is_exposed())
-            .sorted_by(|a, b| Ord::cmp(&a.get_loc(), &b.get_loc()))
+            .map(Either::Left)
+            .chain(
+                module_env
+                    .get_inline_functions()
+                    .filter(|f| self.options.include_private_fun || f.is_exposed())
+                    .map(Either::Right),
+            )
+            .sorted_by_key(|f| f.as_ref().either(|f| f.get_loc(), |f| f.get_loc()))
             .collect_vec();
         if !funs.is_empty() {
             for f in funs {
-                self.gen_function(&spec_block_map, &f);
+                match f {
+                    Either::Left(f) => self.gen_function(&spec_block_map, &f),
+                    Either::Right(f) => self.gen_inline_function(&f),
+                }
             }
         }
 
@@ -896,12 +911,36 @@ impl<'env> Docgen<'env> {
         self.end_items();
     }
 
+    /// Returns the items of the current module marked as `#[deprecated]`, as recorded in the
+    /// metadata of the compiled module.
+    fn deprecations(&self) -> DeprecationMetadata {
+        let module_env = self.current_module.as_ref().unwrap();
+        DeprecationMetadata::from_module(module_env.get_verified_module()).unwrap_or_default()
+    }
+
+    /// Generates a notice for an item marked as `#[deprecated]`.
+    fn deprecation_notice(&self, info: Option<&DeprecationInfo>) {
+        match info {
+            None => (),
+            Some(DeprecationInfo { note: None }) => {
+                emitln!(self.writer, "> **Deprecated**");
+                emitln!(self.writer);
+            }
+            Some(DeprecationInfo { note: Some(note) }) => {
+                emitln!(self.writer, "> **Deprecated**: {}", note);
+                emitln!(self.writer);
+            }
+        }
+    }
+
     /// Generates documentation for all named constants.
     fn gen_named_constants(&self) {
         self.section_header("Constants", &self.label_for_section("Constants"));
         self.increment_section_nest();
         for const_env in self.current_module.as_ref().unwrap().get_named_constants() {
             self.label(&self.label_for_module_item(&const_env.module_env, const_env.get_name()));
+            let name = self.name_string(const_env.get_name());
+            self.deprecation_notice(self.deprecations().constants.get(name.as_str()));
             self.doc_text(const_env.get_doc());
             self.code_block(&self.named_constant_display(&const_env));
         }
@@ -917,6 +956,8 @@ impl<'env> Docgen<'env> {
             &self.label_for_module_item(&struct_env.module_env, name),
         );
         self.increment_section_nest();
+        let name = self.name_string(name);
+        self.deprecation_notice(self.deprecations().structs.get(name.as_str()));
         self.doc_text(struct_env.get_doc());
         self.code_block(&self.struct_header_display(struct_env));
 
@@ -975,6 +1016,19 @@ impl<'env> Docgen<'env> {
         let name = self.name_string(struct_env.get_name());
         let type_params = self.type_parameter_list_display(&struct_env.get_named_type_parameters());
         let ability_tokens = self.ability_tokens(struct_env.get_abilities());
+        if struct_env.is_positional() {
+            let tctx = self.type_display_context_for_struct(struct_env);
+            let field_tys = struct_env
+                .get_fields()
+                .map(|field| field.get_type().display(&tctx).to_string())
+                .join(", ");
+            let abilities = if ability_tokens.is_empty() {
+                String::new()
+            } else {
+                format!(" has {}", ability_tokens.join(", "))
+            };
+            return format!("struct {}{}({}){}", name, type_params, field_tys, abilities);
+        }
         if ability_tokens.is_empty() {
             format!("struct {}{}", name, type_params)
         } else {
@@ -990,13 +1044,15 @@ impl<'env> Docgen<'env> {
     fn gen_struct_fields(&self, struct_env: &StructEnv<'_>) {
         let tctx = self.type_display_context_for_struct(struct_env);
         self.begin_definitions();
+        let positional = struct_env.is_positional();
         for field in struct_env.get_fields() {
+            let name = if positional {
+                field.get_offset().to_string()
+            } else {
+                self.name_string(field.get_name()).to_string()
+            };
             self.definition_text(
-                &format!(
-                    "`{}: {}`",
-                    self.name_string(field.get_name()),
-                    field.get_type().display(&tctx)
-                ),
+                &format!("`{}: {}`", name, field.get_type().display(&tctx)),
                 field.get_doc(),
             );
         }
@@ -1014,6 +1070,8 @@ impl<'env> Docgen<'env> {
             );
             self.increment_section_nest();
         }
+        let name = self.name_string(name);
+        self.deprecation_notice(self.deprecations().functions.get(name.as_str()));
         self.doc_text(func_env.get_doc());
         let sig = self.function_header_display(func_env);
         self.code_block(&sig);
@@ -1059,16 +1117,69 @@ impl<'env> Docgen<'env> {
         }
     }
 
+    /// Generates documentation for an inline function. As inline functions have no bytecode,
+    /// they have no specifications nor call diagrams.
+    fn gen_inline_function(&self, func_env: &InlineFunctionEnv<'_>) {
+        let name = func_env.get_name();
+        self.section_header(
+            &format!("Function `{}`", self.name_string(name)),
+            &self.label_for_module_item(&func_env.module_env, name),
+        );
+        self.increment_section_nest();
+        let name = self.name_string(name);
+        self.deprecation_notice(self.deprecations().functions.get(name.as_str()));
+        self.doc_text(func_env.get_doc());
+        let return_types = match func_env.get_result_type() {
+            Type::Tuple(tys) => tys.clone(),
+            ty => vec![ty.clone()],
+        };
+        let sig = self.function_signature_display(
+            &format!("{}inline ", func_env.visibility_str()),
+            func_env.get_name(),
+            func_env.get_type_parameters(),
+            func_env.get_parameters(),
+            &return_types,
+        );
+        self.code_block(&sig);
+        if self.options.include_impl {
+            self.begin_collapsed("Implementation");
+            self.code_block(&self.get_source_with_indent(&func_env.get_loc()));
+            self.end_collapsed();
+        }
+        self.decrement_section_nest();
+    }
+
     /// Generates documentation for a function signature.
     fn function_header_display(&self, func_env: &FunctionEnv<'_>) -> String {
-        let name = self.name_string(func_env.get_name());
-        let tctx = &self.type_display_context_for_fun(func_env);
-        let params = func_env
-            .get_parameters()
+        let entry_str = if func_env.is_entry() && !func_env.module_env.is_script_module() {
+            "entry "
+        } else {
+            ""
+        };
+        self.function_signature_display(
+            &format!("{}{}", func_env.visibility_str(), entry_str),
+            func_env.get_name(),
+            &func_env.get_named_type_parameters(),
+            &func_env.get_parameters(),
+            &func_env.get_return_types(),
+        )
+    }
+
+    /// Generates documentation for the signature of a function with the given modifiers, such
+    /// as its visibility.
+    fn function_signature_display(
+        &self,
+        modifiers: &str,
+        name: Symbol,
+        type_params: &[TypeParameter],
+        params: &[Parameter],
+        return_types: &[Type],
+    ) -> String {
+        let tctx = &self.type_display_context_for_fun(type_params);
+        let params = params
             .iter()
             .map(|Parameter(name, ty)| format!("{}: {}", self.name_string(*name), ty.display(tctx)))
             .join(", ");
-        let return_types = func_env.get_return_types();
         let return_str = match return_types.len() {
             0 => "".to_owned(),
             1 => format!(": {}", return_types[0].display(tctx)),
@@ -1077,17 +1188,11 @@ impl<'env> Docgen<'env> {
                 return_types.iter().map(|ty| ty.display(tctx)).join(", ")
             ),
         };
-        let entry_str = if func_env.is_entry() && !func_env.module_env.is_script_module() {
-            "entry ".to_owned()
-        } else {
-            "".to_owned()
-        };
         format!(
-            "{}{}fun {}{}({}){}",
-            func_env.visibility_str(),
-            entry_str,
-            name,
-            self.type_parameter_list_display(&func_env.get_named_type_parameters()),
+            "{}fun {}{}({}){}",
+            modifiers,
+            self.name_string(name),
+            self.type_parameter_list_display(type_params),
             params,
             return_str
         )
@@ -1223,6 +1328,7 @@ impl<'env> Docgen<'env> {
         if let Some(m) = &self.current_module {
             m.get_functions()
                 .map(|f| f.get_loc())
+                .chain(m.get_inline_functions().map(|f| f.get_loc()))
                 .chain(m.get_structs().map(|s| s.get_loc()))
                 .any(|loc| {
                     let p = loc.span().start();
@@ -1311,11 +1417,10 @@ impl<'env> Docgen<'env> {
         ability_tokens
     }
 
-    /// Creates a type display context for a function.
-    fn type_display_context_for_fun(&self, func_env: &FunctionEnv<'_>) -> TypeDisplayContext<'_> {
+    /// Creates a type display context for a function with the given type parameters.
+    fn type_display_context_for_fun(&self, type_params: &[TypeParameter]) -> TypeDisplayContext<'_> {
         let type_param_names = Some(
-            func_env
-                .get_named_type_parameters()
+            type_params
                 .iter()
                 .map(|TypeParameter(name, _)| *name)
                 .collect_vec(),
@@ -1611,6 +1716,7 @@ impl<'env> Docgen<'env> {
                         .unwrap_or(false)
                     || ((is_qualified || is_followed_by_open)
                         && (module.find_function(name).is_some()
+                            || module.find_inline_function(name).is_some()
                             || module.get_spec_funs_of_name(name).next().is_some()))
                 {
                     Some(self.ref_for_module_item(module, name))
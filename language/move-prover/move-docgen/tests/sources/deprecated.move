@@ -0,0 +1,21 @@
+#[deprecated(note = b"Use `0x2::NewModule` instead.")]
+/// This module is deprecated
+module 0x2::OldModule {
+    #[deprecated]
+    /// This is a deprecated struct
+    struct S has drop { f: u64 }
+
+    #[deprecated(note = b"Use `NEW` instead.")]
+    /// This is a deprecated constant
+    const OLD: u64 = 0;
+
+    /// This is the replacement constant
+    const NEW: u64 = 1;
+
+    #[deprecated(note = b"Use `new_fun` instead.")]
+    /// This is a deprecated function
+    public fun old_fun(): u64 { OLD }
+
+    /// This is the replacement function
+    public fun new_fun(): u64 { NEW }
+}
@@ -11,5 +11,8 @@ module TestViz {
 
     /// This is a private function
     fun this_is_a_private_fun() {}
+
+    /// This is a public inline function
+    public inline fun this_is_a_public_inline_fun(f: |u64|): u64 { f(1); 1 }
 }
 }
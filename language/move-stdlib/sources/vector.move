@@ -179,6 +179,71 @@ module std::vector {
         pragma intrinsic = true;
     }
 
+    /// Apply the function `f` to each element of the vector `v`, consuming it.
+    public inline fun for_each<Element>(v: vector<Element>, f: |Element|) {
+        reverse(&mut v);
+        while (!is_empty(&v)) f(pop_back(&mut v));
+        destroy_empty(v)
+    }
+
+    /// Map the function `f` over the elements of the vector `v`, producing a new vector.
+    public inline fun map<Element, NewElement>(
+        v: vector<Element>,
+        f: |Element|NewElement
+    ): vector<NewElement> {
+        let result = empty<NewElement>();
+        for_each(v, |elem| push_back(&mut result, f(elem)));
+        result
+    }
+
+    /// Filter the vector `v` by the predicate `p`, keeping the elements for which `p` holds.
+    public inline fun filter<Element: drop>(
+        v: vector<Element>,
+        p: |&Element|bool
+    ): vector<Element> {
+        let result = empty<Element>();
+        for_each(v, |elem| {
+            if (p(&elem)) push_back(&mut result, elem);
+        });
+        result
+    }
+
+    /// Fold the function `f` over the elements of the vector `v`, starting with `init`.
+    /// For example, `fold(vector[1, 2, 3], 0, |acc, e| acc + e)` returns `6`.
+    public inline fun fold<Accumulator, Element>(
+        v: vector<Element>,
+        init: Accumulator,
+        f: |Accumulator, Element|Accumulator
+    ): Accumulator {
+        let accu = init;
+        for_each(v, |elem| accu = f(accu, elem));
+        accu
+    }
+
+    /// Return true if any element of the vector `v` satisfies the predicate `p`.
+    public inline fun any<Element>(v: &vector<Element>, p: |&Element|bool): bool {
+        let result = false;
+        let i = 0;
+        while (i < length(v)) {
+            result = p(borrow(v, i));
+            if (result) break;
+            i = i + 1
+        };
+        result
+    }
+
+    /// Return true if all elements of the vector `v` satisfy the predicate `p`.
+    public inline fun all<Element>(v: &vector<Element>, p: |&Element|bool): bool {
+        let result = true;
+        let i = 0;
+        while (i < length(v)) {
+            result = p(borrow(v, i));
+            if (!result) break;
+            i = i + 1
+        };
+        result
+    }
+
     // =================================================================
     // Module Specification
 
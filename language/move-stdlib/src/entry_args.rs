@@ -0,0 +1,50 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Standard library structs which can be passed as arguments to entry functions and scripts.
+
+use move_core_types::{account_address::AccountAddress, ident_str, value::MoveValue};
+use move_vm_runtime::config::EntryStructArgs;
+
+/// Allows `String` and `Option` arguments, for the standard library published at
+/// `move_std_addr`.
+pub fn entry_struct_args(move_std_addr: AccountAddress) -> EntryStructArgs {
+    let mut args = EntryStructArgs::new();
+    args.allow(
+        move_std_addr,
+        ident_str!("string"),
+        ident_str!("String"),
+        validate_string,
+    );
+    args.allow(
+        move_std_addr,
+        ident_str!("option"),
+        ident_str!("Option"),
+        validate_option,
+    );
+    args
+}
+
+/// The natives of `std::string` assume that the bytes of a string are valid UTF-8.
+fn validate_string(fields: &[MoveValue]) -> bool {
+    let bytes = match fields {
+        [MoveValue::Vector(bytes)] => bytes,
+        _ => return false,
+    };
+    let bytes: Option<Vec<u8>> = bytes
+        .iter()
+        .map(|byte| match byte {
+            MoveValue::U8(byte) => Some(*byte),
+            _ => None,
+        })
+        .collect();
+    match bytes {
+        Some(bytes) => std::str::from_utf8(&bytes).is_ok(),
+        None => false,
+    }
+}
+
+/// An option is a vector holding at most one element.
+fn validate_option(fields: &[MoveValue]) -> bool {
+    matches!(fields, [MoveValue::Vector(elems)] if elems.len() <= 1)
+}
@@ -10,6 +10,7 @@ use std::{collections::BTreeMap, path::PathBuf};
 
 #[cfg(test)]
 mod tests;
+pub mod entry_args;
 pub mod utils;
 
 pub mod natives;
@@ -3,10 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::natives::helpers::make_module_natives;
-use move_binary_format::errors::PartialVMResult;
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
 use move_core_types::{
     gas_algebra::{InternalGas, InternalGasPerByte, NumBytes},
-    vm_status::sub_status::NFE_BCS_SERIALIZATION_FAILURE,
+    vm_status::{sub_status::NFE_BCS_SERIALIZATION_FAILURE, StatusCode},
 };
 use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
 use move_vm_types::{
@@ -69,6 +69,11 @@ fn native_to_bytes(
             return Ok(NativeResult::err(cost, NFE_BCS_SERIALIZATION_FAILURE));
         }
     };
+    if serialized_value.len() as u64 > context.max_vector_length() {
+        return Err(PartialVMError::new(
+            StatusCode::VM_MAX_VECTOR_LENGTH_REACHED,
+        ));
+    }
     cost += gas_params.per_byte_serialized
         * std::cmp::max(
             NumBytes::new(serialized_value.len() as u64),
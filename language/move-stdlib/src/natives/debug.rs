@@ -147,7 +147,7 @@ mod testing {
     use move_core_types::{
         account_address::AccountAddress,
         language_storage::TypeTag,
-        value::{MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue},
+        value::{MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue, MoveVariant},
         vm_status::StatusCode,
     };
     use move_vm_runtime::native_functions::NativeContext;
@@ -243,7 +243,10 @@ mod testing {
     }
 
     fn is_vector_or_struct_move_value(mv: &MoveValue) -> bool {
-        matches!(mv, MoveValue::Vector(_) | MoveValue::Struct(_))
+        matches!(
+            mv,
+            MoveValue::Vector(_) | MoveValue::Struct(_) | MoveValue::Variant(_)
+        )
     }
 
     /// Prints any `Value` in a user-friendly manner.
@@ -460,6 +463,23 @@ mod testing {
                     )?;
                 }
             }
+            MoveValue::Variant(MoveVariant { tag, fields }) => {
+                // Enum layouts carry no names, so variants are printed as their tag followed by
+                // the list of their fields
+                write!(out, "variant#{} ", tag).map_err(fmt_error_to_partial_vm_error)?;
+                let is_complex_inner_type = fields.iter().any(is_vector_or_struct_move_value);
+                print_non_u8_vector(
+                    out,
+                    move_std_addr,
+                    depth,
+                    canonicalize,
+                    single_line,
+                    include_int_types,
+                    fields,
+                    print_move_value,
+                    is_complex_inner_type,
+                )?;
+            }
             MoveValue::Struct(move_struct) => match move_struct {
                 MoveStruct::WithTypes { type_, mut fields } => {
                     let type_tag = TypeTag::from(type_.clone());
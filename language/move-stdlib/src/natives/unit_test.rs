@@ -3,10 +3,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::natives::helpers::make_module_natives;
-use move_binary_format::errors::PartialVMResult;
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
 use move_core_types::{
     account_address::AccountAddress,
     gas_algebra::{InternalGas, InternalGasPerArg, NumArgs},
+    vm_status::StatusCode,
 };
 use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
 use move_vm_types::{
@@ -36,7 +37,7 @@ pub struct CreateSignersForTestingGasParameters {
 
 fn native_create_signers_for_testing(
     gas_params: &CreateSignersForTestingGasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -44,6 +45,11 @@ fn native_create_signers_for_testing(
     debug_assert!(args.len() == 1);
 
     let num_signers = pop_arg!(args, u64);
+    if num_signers > context.max_vector_length() {
+        return Err(PartialVMError::new(
+            StatusCode::VM_MAX_VECTOR_LENGTH_REACHED,
+        ));
+    }
     let signers = Value::vector_for_testing_only(
         (0..num_signers).map(|i| Value::signer(AccountAddress::new(to_le_bytes(i)))),
     );
@@ -95,7 +95,7 @@ pub struct PushBackGasParameters {
 
 pub fn native_push_back(
     gas_params: &PushBackGasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -111,6 +111,12 @@ pub fn native_push_back(
             * std::cmp::max(e.legacy_abstract_memory_size(), 1.into());
     }
 
+    if r.len(&ty_args[0])?.value_as::<u64>()? >= context.max_vector_length() {
+        return Err(PartialVMError::new(
+            StatusCode::VM_MAX_VECTOR_LENGTH_REACHED,
+        ));
+    }
+
     NativeResult::map_partial_vm_result_empty(cost, r.push_back(e, &ty_args[0]))
 }
 
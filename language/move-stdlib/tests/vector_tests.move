@@ -539,4 +539,78 @@ module std::vector_tests {
         let v = vector[7];
         V::insert(&mut v, 6, 2);
     }
+
+    #[test]
+    fun test_for_each() {
+        let v = vector[1, 2, 3];
+        let s = 0;
+        let order = vector[];
+        V::for_each(v, |e| {
+            s = s + e;
+            V::push_back(&mut order, e);
+        });
+        assert!(s == 6, 0);
+        assert!(order == vector[1, 2, 3], 1);
+    }
+
+    #[test]
+    fun test_map() {
+        let v = V::map(vector[1, 2, 3], |e| e * 2);
+        assert!(v == vector[2, 4, 6], 0);
+        let v = V::map(vector[1, 2, 3], |e| e > 1);
+        assert!(v == vector[false, true, true], 1);
+        let v = V::map(vector<u64>[], |e| e + 1);
+        assert!(V::is_empty(&v), 2);
+    }
+
+    #[test]
+    fun test_filter() {
+        let v = V::filter(vector[1, 2, 3, 4, 5], |e| *e % 2 == 1);
+        assert!(v == vector[1, 3, 5], 0);
+        let v = V::filter(vector[1, 2, 3], |_| false);
+        assert!(V::is_empty(&v), 1);
+    }
+
+    #[test]
+    fun test_fold() {
+        let s = V::fold(vector[1, 2, 3], 0, |acc, e| acc + e);
+        assert!(s == 6, 0);
+        let r = V::fold(vector[1, 2, 3], vector[], |acc, e| {
+            V::insert(&mut acc, e, 0);
+            acc
+        });
+        assert!(r == vector[3, 2, 1], 1);
+        let s = V::fold(vector<u64>[], 7, |acc, e| acc + e);
+        assert!(s == 7, 2);
+    }
+
+    #[test]
+    fun test_any_all() {
+        let v = vector[1, 2, 3];
+        assert!(V::any(&v, |e| *e == 2), 0);
+        assert!(!V::any(&v, |e| *e > 3), 1);
+        assert!(V::all(&v, |e| *e > 0), 2);
+        assert!(!V::all(&v, |e| *e < 3), 3);
+        let empty = vector<u64>[];
+        assert!(!V::any(&empty, |_| true), 4);
+        assert!(V::all(&empty, |_| false), 5);
+    }
+
+    #[test]
+    fun test_nested_lambdas() {
+        let v = vector[vector[1, 2], vector[3], vector[]];
+        let lengths = V::map(v, |inner| V::fold(inner, 0, |acc, e| acc + e));
+        assert!(lengths == vector[3, 3, 0], 0);
+    }
+
+    #[test]
+    fun test_resources() {
+        let v = vector[R {}, R {}];
+        let n = 0;
+        V::for_each(v, |r| {
+            let R {} = r;
+            n = n + 1;
+        });
+        assert!(n == 2, 0);
+    }
 }
@@ -3,7 +3,7 @@
 
 use move_binary_format::{
     file_format::{basic_test_module, basic_test_script},
-    file_format_common::VERSION_MAX,
+    file_format_common::{VERSION_6, VERSION_MAX},
 };
 use move_core_types::{account_address::AccountAddress, vm_status::StatusCode};
 use move_vm_runtime::{config::VMConfig, move_vm::MoveVM};
@@ -137,3 +137,35 @@ fn test_run_script_with_custom_max_binary_format_version() {
             .unwrap();
     }
 }
+
+#[test]
+fn test_default_binary_format_version_loads_on_version_6() {
+    let m = basic_test_module();
+    let s = basic_test_script();
+    let mut m_bytes = vec![];
+    let mut s_bytes = vec![];
+    m.serialize(&mut m_bytes).unwrap();
+    s.serialize(&mut s_bytes).unwrap();
+
+    // Binaries without enums are not emitted at a version VMs limited to VERSION_6 reject
+    let storage = InMemoryStorage::new();
+    let vm = MoveVM::new_with_config(
+        move_stdlib::natives::all_natives(
+            AccountAddress::from_hex_literal("0x1").unwrap(),
+            move_stdlib::natives::GasParameters::zeros(),
+        ),
+        VMConfig {
+            max_binary_format_version: VERSION_6,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut sess = vm.new_session(&storage);
+
+    sess.publish_module(m_bytes, *m.self_id().address(), &mut UnmeteredGasMeter)
+        .unwrap();
+
+    let args: Vec<Vec<u8>> = vec![];
+    sess.execute_script(s_bytes, vec![], args, &mut UnmeteredGasMeter)
+        .unwrap();
+}
@@ -0,0 +1,105 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{MoveStruct, MoveValue},
+    vm_status::StatusCode,
+};
+use move_stdlib::entry_args::entry_struct_args;
+use move_vm_runtime::{config::VMConfig, move_vm::MoveVM};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+const CODE: &str = r#"
+    module std::option {
+        struct Option<Element> has drop { vec: vector<Element> }
+    }
+
+    module {{ADDR}}::M {
+        use std::option::Option;
+
+        struct Foo has drop { x: u64 }
+
+        public entry fun opt(_o: Option<u64>) {}
+
+        public entry fun foo(_foo: Foo) {}
+    }
+"#;
+
+fn setup() -> (MoveVM, InMemoryStorage) {
+    let code = CODE.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    let mut storage = InMemoryStorage::new();
+    for unit in compile_units(&code).unwrap() {
+        let m = as_module(unit);
+        let mut blob = vec![];
+        m.serialize(&mut blob).unwrap();
+        storage.publish_or_overwrite_module(m.self_id(), blob);
+    }
+    let config = VMConfig {
+        entry_struct_args: Some(entry_struct_args(AccountAddress::ONE)),
+        ..Default::default()
+    };
+    let vm = MoveVM::new_with_config(vec![], config).unwrap();
+    (vm, storage)
+}
+
+/// Calls `M::function` with a single struct argument, returning the status code of the failure,
+/// if any.
+fn call(function: &str, fields: Vec<MoveValue>, bypass_visibility: bool) -> Option<StatusCode> {
+    let (vm, storage) = setup();
+    let mut session = vm.new_session(&storage);
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    let function = Identifier::new(function).unwrap();
+    let args = vec![MoveValue::Struct(MoveStruct::new(fields))
+        .simple_serialize()
+        .unwrap()];
+    let res = if bypass_visibility {
+        session.execute_function_bypass_visibility(
+            &module_id,
+            &function,
+            vec![],
+            args,
+            &mut UnmeteredGasMeter,
+        )
+    } else {
+        session.execute_entry_function(&module_id, &function, vec![], args, &mut UnmeteredGasMeter)
+    };
+    res.err().map(|err| err.major_status())
+}
+
+#[test]
+fn allowed_struct_is_validated() {
+    let option = |elems: &[u64]| {
+        vec![MoveValue::Vector(
+            elems.iter().map(|elem| MoveValue::U64(*elem)).collect(),
+        )]
+    };
+    assert_eq!(call("opt", option(&[]), false), None);
+    assert_eq!(call("opt", option(&[1]), false), None);
+    assert_eq!(
+        call("opt", option(&[1, 2]), false),
+        Some(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT)
+    );
+    // Validation also applies when bypassing visibility
+    assert_eq!(
+        call("opt", option(&[1, 2]), true),
+        Some(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT)
+    );
+}
+
+#[test]
+fn other_structs_are_rejected() {
+    let foo = vec![MoveValue::U64(1)];
+    assert_eq!(
+        call("foo", foo.clone(), false),
+        Some(StatusCode::INVALID_PARAM_TYPE_FOR_DESERIALIZATION)
+    );
+    // Functions called bypassing visibility accept any struct
+    assert_eq!(call("foo", foo, true), None);
+}
@@ -0,0 +1,249 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_binary_format::{
+    errors::VMError,
+    file_format::{Bytecode, CodeOffset},
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::InternalGas,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{
+    execution_tracer::{ExecutionTracer, GlobalAccess, TracedFunction},
+    move_vm::MoveVM,
+    native_functions::NativeFunction,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::{
+    gas::UnmeteredGasMeter, loaded_data::runtime_types::Type, natives::function::NativeResult,
+    values::Value,
+};
+use std::sync::Arc;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Enter(String),
+    Exit(String),
+    Native(String, usize),
+    Global(GlobalAccess),
+    Abort(String, u64),
+    Unwind(String, StatusCode),
+}
+
+#[derive(Default)]
+struct RecordingTracer {
+    events: Vec<Event>,
+    instructions: Vec<(String, CodeOffset, usize)>,
+}
+
+impl ExecutionTracer for RecordingTracer {
+    fn enter_function(&mut self, function: &TracedFunction) {
+        self.events.push(Event::Enter(function.name().to_string()));
+    }
+
+    fn exit_function(&mut self, function: &TracedFunction) {
+        self.events.push(Event::Exit(function.name().to_string()));
+    }
+
+    fn unwind_function(&mut self, function: &TracedFunction, error: &VMError) {
+        self.events.push(Event::Unwind(
+            function.name().to_string(),
+            error.major_status(),
+        ));
+    }
+
+    fn instruction(
+        &mut self,
+        function: &TracedFunction,
+        pc: CodeOffset,
+        _instr: &Bytecode,
+        stack: &[Value],
+    ) {
+        self.instructions
+            .push((function.name().to_string(), pc, stack.len()));
+    }
+
+    fn native_call(&mut self, function: &TracedFunction, args: &[Value]) {
+        self.events
+            .push(Event::Native(function.name().to_string(), args.len()));
+    }
+
+    fn global_access(&mut self, access: GlobalAccess, address: AccountAddress, _ty: &Type) {
+        assert_eq!(address, TEST_ADDR);
+        self.events.push(Event::Global(access));
+    }
+
+    fn abort(&mut self, function: &TracedFunction, code: u64) {
+        self.events
+            .push(Event::Abort(function.name().to_string(), code));
+    }
+}
+
+fn setup() -> (MoveVM, InMemoryStorage, ModuleId) {
+    let code = r#"
+        module {{ADDR}}::M {
+            struct R has key { v: u64 }
+
+            native fun double(x: u64): u64;
+
+            fun add_one(x: u64): u64 {
+                x + 1
+            }
+
+            public fun run(s: signer, x: u64): u64 acquires R {
+                move_to(&s, R { v: add_one(x) });
+                let exists = exists<R>(@{{ADDR}});
+                assert!(exists, 1);
+                double(borrow_global<R>(@{{ADDR}}).v)
+            }
+
+            public fun fail(x: u64) {
+                abort add_one(x)
+            }
+
+            public fun overflow(x: u64): u64 {
+                add_one(x) + 1
+            }
+        }
+    "#;
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    let mut units = compile_units(&code).unwrap();
+    let m = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+
+    let double: NativeFunction = Arc::new(|_context, _ty_args, mut args| {
+        let x = args.pop_back().unwrap().value_as::<u64>()?;
+        Ok(NativeResult::ok(
+            InternalGas::zero(),
+            vec![Value::u64(x * 2)].into(),
+        ))
+    });
+    let vm = MoveVM::new(vec![(
+        TEST_ADDR,
+        Identifier::new("M").unwrap(),
+        Identifier::new("double").unwrap(),
+        double,
+    )])
+    .unwrap();
+    (vm, storage, module_id)
+}
+
+#[test]
+fn trace_function_calls_and_global_access() {
+    let (vm, storage, module_id) = setup();
+    let mut tracer = RecordingTracer::default();
+    let mut sess = vm.new_session_with_tracer(&storage, &mut tracer);
+    let args = serialize_values(&vec![MoveValue::Signer(TEST_ADDR), MoveValue::U64(20)]);
+    let result = sess
+        .execute_function_bypass_visibility(
+            &module_id,
+            &Identifier::new("run").unwrap(),
+            vec![],
+            args,
+            &mut UnmeteredGasMeter,
+        )
+        .unwrap();
+    assert_eq!(
+        result.return_values[0].0,
+        MoveValue::U64(42).simple_serialize().unwrap()
+    );
+    drop(sess);
+
+    assert_eq!(
+        tracer.events,
+        vec![
+            Event::Enter("run".to_string()),
+            Event::Enter("add_one".to_string()),
+            Event::Exit("add_one".to_string()),
+            Event::Global(GlobalAccess::MoveTo),
+            Event::Global(GlobalAccess::Exists),
+            Event::Global(GlobalAccess::ImmBorrow),
+            Event::Native("double".to_string(), 1),
+            Event::Exit("run".to_string()),
+        ]
+    );
+
+    // Every instruction is reported, starting at the entry point with an empty operand stack.
+    assert_eq!(tracer.instructions[0], ("run".to_string(), 0, 0));
+    assert!(tracer
+        .instructions
+        .iter()
+        .any(|(name, pc, _)| name == "add_one" && *pc == 0));
+    assert!(tracer
+        .instructions
+        .iter()
+        .all(|(name, _, _)| name == "run" || name == "add_one"));
+}
+
+#[test]
+fn trace_abort() {
+    let (vm, storage, module_id) = setup();
+    let mut tracer = RecordingTracer::default();
+    let mut sess = vm.new_session_with_tracer(&storage, &mut tracer);
+    let args = serialize_values(&vec![MoveValue::U64(6)]);
+    let err = sess
+        .execute_function_bypass_visibility(
+            &module_id,
+            &Identifier::new("fail").unwrap(),
+            vec![],
+            args,
+            &mut UnmeteredGasMeter,
+        )
+        .unwrap_err();
+    assert_eq!(err.major_status(), StatusCode::ABORTED);
+    drop(sess);
+
+    assert_eq!(
+        tracer.events,
+        vec![
+            Event::Enter("fail".to_string()),
+            Event::Enter("add_one".to_string()),
+            Event::Exit("add_one".to_string()),
+            Event::Abort("fail".to_string(), 7),
+            Event::Unwind("fail".to_string(), StatusCode::ABORTED),
+        ]
+    );
+}
+
+#[test]
+fn trace_unwind() {
+    let (vm, storage, module_id) = setup();
+    let mut tracer = RecordingTracer::default();
+    let mut sess = vm.new_session_with_tracer(&storage, &mut tracer);
+    let args = serialize_values(&vec![MoveValue::U64(u64::MAX)]);
+    let err = sess
+        .execute_function_bypass_visibility(
+            &module_id,
+            &Identifier::new("overflow").unwrap(),
+            vec![],
+            args,
+            &mut UnmeteredGasMeter,
+        )
+        .unwrap_err();
+    assert_eq!(err.major_status(), StatusCode::ARITHMETIC_ERROR);
+    drop(sess);
+
+    // Every frame popped by the error is reported, innermost first.
+    assert_eq!(
+        tracer.events,
+        vec![
+            Event::Enter("overflow".to_string()),
+            Event::Enter("add_one".to_string()),
+            Event::Unwind("add_one".to_string(), StatusCode::ARITHMETIC_ERROR),
+            Event::Unwind("overflow".to_string(), StatusCode::ARITHMETIC_ERROR),
+        ]
+    );
+}
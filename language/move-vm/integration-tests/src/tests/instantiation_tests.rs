@@ -573,6 +573,10 @@ fn make_module(
         friend_decls: vec![],
         field_instantiations: vec![],
         metadata: vec![],
+        enum_defs: vec![],
+        enum_def_instantiations: vec![],
+        variant_handles: vec![],
+        variant_instantiation_handles: vec![],
     };
     // uncomment to see the module generated
     // println!("Module: {:#?}", module);
@@ -0,0 +1,161 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{config::VMConfig, move_vm::MoveVM};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+const CODE: &str = r#"
+    module std::vector {
+        #[bytecode_instruction]
+        native public fun push_back<Element>(v: &mut vector<Element>, e: Element);
+
+        #[bytecode_instruction]
+        native public fun pop_back<Element>(v: &mut vector<Element>): Element;
+    }
+
+    module {{ADDR}}::M {
+        use std::vector;
+
+        struct R has key { v: vector<u64> }
+
+        fun make(n: u64): vector<u64> {
+            let v = vector[];
+            let i = 0;
+            while (i < n) {
+                vector::push_back(&mut v, i);
+                i = i + 1;
+            };
+            v
+        }
+
+        public fun push(n: u64) {
+            make(n);
+        }
+
+        public fun churn(rounds: u64, n: u64) {
+            let v = vector[];
+            let i = 0;
+            while (i < rounds) {
+                v = make(n);
+                i = i + 1;
+            };
+            while (i > 0) {
+                vector::pop_back(&mut v);
+                vector::push_back(&mut v, i);
+                i = i - 1;
+            };
+        }
+
+        public fun store(s: signer, n: u64) {
+            move_to(&s, R { v: make(n) })
+        }
+
+        public fun take(addr: address, n: u64): (vector<u64>, R) acquires R {
+            let v = make(n);
+            (v, move_from<R>(addr))
+        }
+    }
+"#;
+
+fn setup() -> (MoveVM, InMemoryStorage) {
+    let code = CODE.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    let mut storage = InMemoryStorage::new();
+    for unit in compile_units(&code).unwrap() {
+        let m = as_module(unit);
+        let mut blob = vec![];
+        m.serialize(&mut blob).unwrap();
+        storage.publish_or_overwrite_module(m.self_id(), blob);
+    }
+    let config = VMConfig {
+        max_memory_usage: Some(1000),
+        ..Default::default()
+    };
+    let vm = MoveVM::new_with_config(vec![], config).unwrap();
+    (vm, storage)
+}
+
+/// Calls `M::function` with a quota of 1000 bytes, returning the status code of the failure, if
+/// any.
+fn call(function: &str, args: Vec<MoveValue>) -> Option<StatusCode> {
+    call_all(vec![(function, args)]).pop().unwrap()
+}
+
+/// Calls each `M::function` in turn in the same session, with a quota of 1000 bytes per call.
+fn call_all(calls: Vec<(&str, Vec<MoveValue>)>) -> Vec<Option<StatusCode>> {
+    let (vm, storage) = setup();
+    let mut session = vm.new_session(&storage);
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    calls
+        .into_iter()
+        .map(|(function, args)| {
+            session
+                .execute_function_bypass_visibility(
+                    &module_id,
+                    &Identifier::new(function).unwrap(),
+                    vec![],
+                    serialize_values(&args),
+                    &mut UnmeteredGasMeter,
+                )
+                .err()
+                .map(|err| err.major_status())
+        })
+        .collect()
+}
+
+#[test]
+fn large_vector_exceeds_quota() {
+    // The elements of a `vector<u64>` take 8 bytes each
+    assert_eq!(call("push", vec![MoveValue::U64(100)]), None);
+    assert_eq!(
+        call("push", vec![MoveValue::U64(200)]),
+        Some(StatusCode::MEMORY_LIMIT_EXCEEDED)
+    );
+}
+
+#[test]
+fn released_values_do_not_count() {
+    // Each round builds a vector of 400 bytes, dropping the one of the previous round
+    assert_eq!(
+        call("churn", vec![MoveValue::U64(50), MoveValue::U64(50)]),
+        None
+    );
+}
+
+#[test]
+fn globals_count() {
+    let signer = MoveValue::Signer(TEST_ADDR);
+    assert_eq!(
+        call("store", vec![signer.clone(), MoveValue::U64(100)]),
+        None
+    );
+    assert_eq!(
+        call("store", vec![signer, MoveValue::U64(200)]),
+        Some(StatusCode::MEMORY_LIMIT_EXCEEDED)
+    );
+}
+
+#[test]
+fn globals_cached_by_earlier_calls_count() {
+    // `R` is cached by the session after the first call, and still counts in the second one
+    let store = vec![MoveValue::Signer(TEST_ADDR), MoveValue::U64(60)];
+    let take = |n| vec![MoveValue::Address(TEST_ADDR), MoveValue::U64(n)];
+    assert_eq!(
+        call_all(vec![("store", store.clone()), ("take", take(50))]),
+        vec![None, None]
+    );
+    assert_eq!(
+        call_all(vec![("store", store), ("take", take(70))]),
+        vec![None, Some(StatusCode::MEMORY_LIMIT_EXCEEDED)]
+    );
+}
@@ -5,12 +5,19 @@
 mod bad_entry_point_tests;
 mod bad_storage_tests;
 mod binary_format_version;
+mod entry_struct_args_tests;
 mod exec_func_effects_tests;
+mod execution_tracer_tests;
 mod function_arg_tests;
 mod instantiation_tests;
 mod invariant_violation_tests;
 mod leak_tests;
 mod loader_tests;
+mod memory_quota_tests;
 mod mutated_accounts_tests;
 mod nested_loop_tests;
+mod parallel_execution_tests;
 mod return_value_tests;
+mod session_savepoint_tests;
+mod shared_module_cache_tests;
+mod vm_config_limits_tests;
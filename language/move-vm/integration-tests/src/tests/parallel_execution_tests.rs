@@ -0,0 +1,201 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+};
+use move_stdlib::natives::{all_natives, nursery_natives, GasParameters, NurseryGasParameters};
+use move_vm_parallel_executor::{
+    execute_block_sequential, EntryFunctionCall, ParallelExecutor, TransactionOutput,
+};
+use move_vm_runtime::move_vm::MoveVM;
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+const CODE: &str = r#"
+    module std::signer {
+        native public fun borrow_address(s: &signer): &address;
+
+        public fun address_of(s: &signer): address {
+            *borrow_address(s)
+        }
+    }
+
+    module std::event {
+        native public fun write_to_event_store<T: drop + store>(guid: vector<u8>, count: u64, msg: T);
+    }
+
+    module {{ADDR}}::Bank {
+        use std::event;
+        use std::signer;
+
+        struct Balance has key { value: u64 }
+
+        struct Deposit has drop, store { amount: u64 }
+
+        public entry fun open(account: signer, amount: u64) {
+            move_to(&account, Balance { value: amount })
+        }
+
+        public entry fun transfer(from: signer, to: address, amount: u64) acquires Balance {
+            let balance = borrow_global_mut<Balance>(signer::address_of(&from));
+            assert!(balance.value >= amount, 1);
+            balance.value = balance.value - amount;
+            let balance = borrow_global_mut<Balance>(to);
+            balance.value = balance.value + amount;
+            event::write_to_event_store(b"deposit", balance.value, Deposit { amount });
+        }
+
+        public entry fun close(account: signer) acquires Balance {
+            let Balance { value: _ } = move_from<Balance>(signer::address_of(&account));
+        }
+    }
+"#;
+
+const NUM_ACCOUNTS: u64 = 8;
+
+fn setup() -> (MoveVM, InMemoryStorage) {
+    let code = CODE.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    let mut storage = InMemoryStorage::new();
+    for unit in compile_units(&code).unwrap() {
+        let m = as_module(unit);
+        let mut blob = vec![];
+        m.serialize(&mut blob).unwrap();
+        storage.publish_or_overwrite_module(m.self_id(), blob);
+    }
+    let natives = all_natives(AccountAddress::ONE, GasParameters::zeros())
+        .into_iter()
+        .chain(nursery_natives(
+            AccountAddress::ONE,
+            NurseryGasParameters::zeros(),
+        ))
+        .collect::<Vec<_>>();
+    (MoveVM::new(natives).unwrap(), storage)
+}
+
+fn account(i: u64) -> AccountAddress {
+    AccountAddress::from_hex_literal(&format!("0x{:x}", 0x100 + i)).unwrap()
+}
+
+fn call(function: &str, args: Vec<MoveValue>) -> EntryFunctionCall {
+    EntryFunctionCall {
+        module: ModuleId::new(TEST_ADDR, Identifier::new("Bank").unwrap()),
+        function: Identifier::new(function).unwrap(),
+        ty_args: vec![],
+        args: serialize_values(&args),
+    }
+}
+
+fn open(i: u64, amount: u64) -> EntryFunctionCall {
+    call(
+        "open",
+        vec![MoveValue::Signer(account(i)), MoveValue::U64(amount)],
+    )
+}
+
+fn transfer(from: u64, to: u64, amount: u64) -> EntryFunctionCall {
+    call(
+        "transfer",
+        vec![
+            MoveValue::Signer(account(from)),
+            MoveValue::Address(account(to)),
+            MoveValue::U64(amount),
+        ],
+    )
+}
+
+fn close(i: u64) -> EntryFunctionCall {
+    call("close", vec![MoveValue::Signer(account(i))])
+}
+
+/// A deterministic pseudo-random block of transactions over a few accounts, so that many of them
+/// conflict.
+fn random_block(seed: u64, len: usize) -> Vec<EntryFunctionCall> {
+    let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    let mut next = move |bound: u64| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % bound
+    };
+    (0..len)
+        .map(|_| match next(10) {
+            0 => open(next(NUM_ACCOUNTS), next(100)),
+            1 => close(next(NUM_ACCOUNTS)),
+            _ => transfer(next(NUM_ACCOUNTS), next(NUM_ACCOUNTS), next(50)),
+        })
+        .collect()
+}
+
+fn initial_block() -> Vec<EntryFunctionCall> {
+    (0..NUM_ACCOUNTS).map(|i| open(i, 100)).collect()
+}
+
+/// Executes `block` after opening every account, sequentially and then in parallel on several
+/// numbers of threads, checking that the outputs are all the same.
+fn check_block(block: &[EntryFunctionCall]) -> Vec<TransactionOutput> {
+    let (vm, mut storage) = setup();
+    for output in execute_block_sequential(&vm, &storage, &initial_block(), || UnmeteredGasMeter) {
+        storage.apply(output.unwrap().0).unwrap();
+    }
+
+    let expected = execute_block_sequential(&vm, &storage, block, || UnmeteredGasMeter);
+    for concurrency in [1, 2, 4, 8] {
+        for _ in 0..3 {
+            let outputs =
+                ParallelExecutor::new(&vm, concurrency)
+                    .execute_block(&storage, block, || UnmeteredGasMeter);
+            assert_eq!(outputs, expected, "concurrency {}", concurrency);
+        }
+    }
+    expected
+}
+
+#[test]
+fn empty_block() {
+    assert!(check_block(&[]).is_empty());
+}
+
+#[test]
+fn independent_transactions() {
+    let block: Vec<_> = (0..NUM_ACCOUNTS)
+        .map(|i| transfer(i, (i + 1) % NUM_ACCOUNTS, 10))
+        .collect();
+    assert!(check_block(&block).iter().all(|output| output.is_ok()));
+}
+
+#[test]
+fn conflicting_transactions() {
+    // Every transfer depends on the previous one, and the last ones run out of funds
+    let block: Vec<_> = (0..30).map(|i| transfer(0, 1 + i % 3, 7)).collect();
+    let outputs = check_block(&block);
+    assert_eq!(outputs.iter().filter(|output| output.is_ok()).count(), 14);
+}
+
+#[test]
+fn deleted_and_recreated_resources() {
+    let block = vec![
+        transfer(1, 0, 10),
+        close(0),
+        transfer(1, 0, 10),
+        open(0, 5),
+        transfer(0, 1, 5),
+        transfer(0, 1, 1),
+    ];
+    let outputs = check_block(&block);
+    let succeeded: Vec<_> = outputs.iter().map(|output| output.is_ok()).collect();
+    assert_eq!(succeeded, vec![true, true, false, true, true, false]);
+}
+
+#[test]
+fn random_blocks() {
+    for seed in 0..20 {
+        check_block(&random_block(seed, 50));
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::Op,
+    gas_algebra::InternalGas,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{
+    move_vm::MoveVM,
+    native_extensions::{NativeContextExtensions, SnapshotExtension},
+    native_functions::NativeFunction,
+    session::Session,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::{
+    gas::UnmeteredGasMeter, loaded_data::runtime_types::Type, natives::function::NativeResult,
+    values::Value,
+};
+use std::sync::Arc;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+/// Counts the events emitted by `M::emit`.
+#[derive(Tid)]
+struct EventCounter(u64);
+
+impl SnapshotExtension for EventCounter {
+    fn snapshot(&self) -> PartialVMResult<Self> {
+        Ok(Self(self.0))
+    }
+}
+
+fn setup() -> (MoveVM, InMemoryStorage) {
+    let code = r#"
+        module {{ADDR}}::M {
+            struct R has key { v: u64 }
+
+            native fun emit(v: u64);
+
+            public fun publish(s: signer, v: u64) {
+                move_to(&s, R { v });
+                emit(v)
+            }
+
+            public fun set(v: u64) acquires R {
+                borrow_global_mut<R>(@{{ADDR}}).v = v;
+                emit(v)
+            }
+
+            public fun fail() {
+                abort 42
+            }
+        }
+    "#;
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    let mut units = compile_units(&code).unwrap();
+    let m = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(m.self_id(), blob);
+
+    let emit: NativeFunction = Arc::new(|context, _ty_args, mut args| {
+        let v = args.pop_back().unwrap().value_as::<u64>()?;
+        context.extensions_mut().get_mut::<EventCounter>().0 += 1;
+        context.save_event(vec![], v, Type::U64, Value::u64(v))?;
+        Ok(NativeResult::ok(InternalGas::zero(), vec![].into()))
+    });
+    let vm = MoveVM::new(vec![(
+        TEST_ADDR,
+        Identifier::new("M").unwrap(),
+        Identifier::new("emit").unwrap(),
+        emit,
+    )])
+    .unwrap();
+    (vm, storage)
+}
+
+fn call(session: &mut Session<InMemoryStorage>, function: &str, args: Vec<MoveValue>) -> bool {
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    session
+        .execute_function_bypass_visibility(
+            &module_id,
+            &Identifier::new(function).unwrap(),
+            vec![],
+            serialize_values(&args),
+            &mut UnmeteredGasMeter,
+        )
+        .is_ok()
+}
+
+/// Finishes `session`, returning the value of `R`, the sequence numbers of the emitted events and
+/// the count of the event counter.
+fn finish(session: Session<InMemoryStorage>) -> (Option<u64>, Vec<u64>, u64) {
+    let (change_set, events, mut extensions) = session.finish_with_extensions().unwrap();
+    let value = change_set.resources().next().map(|(_, _, op)| match op {
+        Op::New(blob) => u64::from_le_bytes(blob.try_into().unwrap()),
+        _ => panic!("R must be newly published"),
+    });
+    let seq_nums = events.iter().map(|(_, seq_num, _, _)| *seq_num).collect();
+    (value, seq_nums, extensions.remove::<EventCounter>().0)
+}
+
+fn extensions<'a>() -> NativeContextExtensions<'a> {
+    let mut extensions = NativeContextExtensions::default();
+    extensions.add_with_snapshots(EventCounter(0));
+    extensions
+}
+
+#[test]
+fn rollback_reverts_effects_since_savepoint() {
+    let (vm, storage) = setup();
+    let mut session = vm.new_session_with_extensions(&storage, extensions());
+
+    assert!(call(
+        &mut session,
+        "publish",
+        vec![MoveValue::Signer(TEST_ADDR), MoveValue::U64(1)]
+    ));
+    let savepoint = session.savepoint().unwrap();
+    assert!(call(&mut session, "set", vec![MoveValue::U64(2)]));
+    assert!(!call(&mut session, "fail", vec![]));
+    session.rollback_to(savepoint).unwrap();
+    assert!(call(&mut session, "set", vec![MoveValue::U64(3)]));
+
+    assert_eq!(finish(session), (Some(3), vec![1, 3], 2));
+}
+
+#[test]
+fn nested_savepoints() {
+    let (vm, storage) = setup();
+    let mut session = vm.new_session_with_extensions(&storage, extensions());
+
+    let outer = session.savepoint().unwrap();
+    assert!(call(
+        &mut session,
+        "publish",
+        vec![MoveValue::Signer(TEST_ADDR), MoveValue::U64(1)]
+    ));
+    let inner = session.savepoint().unwrap();
+    assert!(call(&mut session, "set", vec![MoveValue::U64(2)]));
+    let innermost = session.savepoint().unwrap();
+    assert!(call(&mut session, "set", vec![MoveValue::U64(3)]));
+
+    // Releasing keeps the effects, rolling back the outer savepoint reverts them all
+    session.release(innermost).unwrap();
+    session.rollback_to(inner).unwrap();
+    assert_eq!(session.get_data_store().events().len(), 1);
+    session.rollback_to(outer).unwrap();
+    assert!(session.get_data_store().events().is_empty());
+
+    // Releasing a savepoint discards the savepoints created after it
+    let savepoint = session.savepoint().unwrap();
+    assert!(call(
+        &mut session,
+        "publish",
+        vec![MoveValue::Signer(TEST_ADDR), MoveValue::U64(4)]
+    ));
+    let stale = session.savepoint().unwrap();
+    session.release(savepoint).unwrap();
+    assert_eq!(
+        session.rollback_to(stale).unwrap_err().major_status(),
+        StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR
+    );
+
+    assert_eq!(finish(session), (Some(4), vec![4], 1));
+}
+
+#[test]
+fn rollback_invalidates_published_modules() {
+    let (vm, storage) = setup();
+    let mut session = vm.new_session_with_extensions(&storage, extensions());
+
+    let code = format!("module 0x{}::N {{ public fun f() {{}} }}", TEST_ADDR);
+    let m = as_module(compile_units(&code).unwrap().pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+
+    let savepoint = session.savepoint().unwrap();
+    session
+        .publish_module(blob, TEST_ADDR, &mut UnmeteredGasMeter)
+        .unwrap();
+    session
+        .execute_function_bypass_visibility(
+            &m.self_id(),
+            &Identifier::new("f").unwrap(),
+            vec![],
+            Vec::<Vec<u8>>::new(),
+            &mut UnmeteredGasMeter,
+        )
+        .unwrap();
+    assert!(!vm.is_loader_cache_invalidated());
+    session.rollback_to(savepoint).unwrap();
+    assert!(vm.is_loader_cache_invalidated());
+
+    let (change_set, _, _) = session.finish_with_extensions().unwrap();
+    assert_eq!(change_set.modules().count(), 0);
+}
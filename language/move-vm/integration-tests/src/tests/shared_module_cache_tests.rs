@@ -0,0 +1,215 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_binary_format::CompiledModule;
+use move_bytecode_verifier::VerifierConfig;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    value::MoveValue,
+};
+use move_vm_runtime::{config::VMConfig, module_cache::SharedModuleCache, move_vm::MoveVM};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::{collections::BTreeSet, sync::Arc, thread};
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+const CODE: &str = r#"
+    module {{ADDR}}::A {
+        public fun a(): u64 { 1 }
+    }
+
+    module {{ADDR}}::B {
+        use {{ADDR}}::A;
+
+        public fun b(): u64 { A::a() + 1 }
+    }
+
+    module {{ADDR}}::C {
+        public fun c(): u64 { 3 }
+    }
+
+    module {{ADDR}}::D {
+        public fun d(): u64 { 4 }
+    }
+"#;
+
+const UPGRADED_A: &str = r#"
+    module {{ADDR}}::A {
+        public fun a(): u64 { 1 }
+
+        public fun a2(): u64 { 2 }
+    }
+"#;
+
+fn compile(code: &str) -> Vec<CompiledModule> {
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    compile_units(&code)
+        .unwrap()
+        .into_iter()
+        .map(as_module)
+        .collect()
+}
+
+fn serialize(module: &CompiledModule) -> Vec<u8> {
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+    blob
+}
+
+fn setup() -> InMemoryStorage {
+    let mut storage = InMemoryStorage::new();
+    for module in compile(CODE) {
+        storage.publish_or_overwrite_module(module.self_id(), serialize(&module));
+    }
+    storage
+}
+
+fn module_id(name: &str) -> ModuleId {
+    ModuleId::new(TEST_ADDR, Identifier::new(name).unwrap())
+}
+
+fn new_vm(cache: &Arc<SharedModuleCache>) -> MoveVM {
+    MoveVM::new_with_shared_cache(vec![], VMConfig::default(), cache.clone()).unwrap()
+}
+
+fn load(vm: &MoveVM, storage: &InMemoryStorage, name: &str) {
+    vm.load_module(&module_id(name), storage).unwrap();
+}
+
+fn upgrade_a(vm: &MoveVM, storage: &mut InMemoryStorage) {
+    let upgraded = compile(UPGRADED_A).pop().unwrap();
+    let mut session = vm.new_session(&*storage);
+    session
+        .publish_module(serialize(&upgraded), TEST_ADDR, &mut UnmeteredGasMeter)
+        .unwrap();
+    let (changeset, _) = session.finish().unwrap();
+    storage.apply(changeset).unwrap();
+}
+
+fn call(vm: &MoveVM, storage: &InMemoryStorage, module: &str, function: &str) -> MoveValue {
+    let mut session = vm.new_session(storage);
+    let result = session
+        .execute_function_bypass_visibility(
+            &module_id(module),
+            &Identifier::new(function).unwrap(),
+            vec![],
+            Vec::<Vec<u8>>::new(),
+            &mut UnmeteredGasMeter,
+        )
+        .unwrap();
+    let (bytes, layout) = &result.return_values[0];
+    MoveValue::simple_deserialize(bytes, layout).unwrap()
+}
+
+#[test]
+fn modules_are_shared_between_vms() {
+    let storage = setup();
+    let cache = Arc::new(SharedModuleCache::new(10));
+    load(&new_vm(&cache), &storage, "B");
+    assert!(cache.contains(&module_id("A")));
+    assert!(cache.contains(&module_id("B")));
+
+    // A VM whose verifier rejects any function only loads `B` if it skips verification
+    let strict_config = || VMConfig {
+        verifier: VerifierConfig {
+            max_function_definitions: Some(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let strict_vm = MoveVM::new_with_config(vec![], strict_config()).unwrap();
+    assert!(strict_vm.load_module(&module_id("B"), &storage).is_err());
+    let strict_vm = MoveVM::new_with_shared_cache(vec![], strict_config(), cache).unwrap();
+    load(&strict_vm, &storage, "B");
+}
+
+#[test]
+fn least_recently_used_module_is_evicted() {
+    let storage = setup();
+    let cache = Arc::new(SharedModuleCache::new(2));
+    load(&new_vm(&cache), &storage, "A");
+    load(&new_vm(&cache), &storage, "C");
+    load(&new_vm(&cache), &storage, "A");
+    load(&new_vm(&cache), &storage, "D");
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&module_id("A")));
+    assert!(!cache.contains(&module_id("C")));
+    assert!(cache.contains(&module_id("D")));
+}
+
+#[test]
+fn invalidation_drops_dependents() {
+    let storage = setup();
+    let cache = Arc::new(SharedModuleCache::new(10));
+    let vm = new_vm(&cache);
+    load(&vm, &storage, "B");
+    load(&vm, &storage, "C");
+    cache.invalidate(&module_id("A"));
+    assert!(!cache.contains(&module_id("A")));
+    assert!(!cache.contains(&module_id("B")));
+    assert!(cache.contains(&module_id("C")));
+}
+
+#[test]
+fn republishing_invalidates_module() {
+    let mut storage = setup();
+    let cache = Arc::new(SharedModuleCache::new(10));
+    let vm = new_vm(&cache);
+    load(&vm, &storage, "B");
+    load(&vm, &storage, "C");
+
+    upgrade_a(&vm, &mut storage);
+    assert!(!cache.contains(&module_id("A")));
+    assert!(!cache.contains(&module_id("B")));
+    assert!(cache.contains(&module_id("C")));
+
+    // Other VMs load the new version
+    let vm = new_vm(&cache);
+    assert_eq!(call(&vm, &storage, "A", "a2"), MoveValue::U64(2));
+    assert!(cache.contains(&module_id("A")));
+}
+
+#[test]
+fn republishing_flushes_module_and_dependents_from_loader() {
+    let mut storage = setup();
+    let vm = MoveVM::new(vec![]).unwrap();
+    load(&vm, &storage, "B");
+    load(&vm, &storage, "C");
+
+    upgrade_a(&vm, &mut storage);
+    assert!(vm.is_loader_cache_invalidated());
+    vm.flush_loader_cache_if_invalidated();
+    assert!(!vm.is_loader_cache_invalidated());
+
+    // Only `C` is still in the loader cache, `A` and `B` are loaded again
+    vm.get_and_clear_module_cache_hits();
+    load(&vm, &storage, "C");
+    load(&vm, &storage, "B");
+    assert_eq!(
+        vm.get_and_clear_module_cache_hits(),
+        BTreeSet::from([module_id("C")])
+    );
+    assert_eq!(call(&vm, &storage, "A", "a2"), MoveValue::U64(2));
+    assert_eq!(call(&vm, &storage, "B", "b"), MoveValue::U64(2));
+}
+
+#[test]
+fn concurrent_vms() {
+    let storage = setup();
+    let cache = Arc::new(SharedModuleCache::new(10));
+    let children: Vec<_> = (0..16)
+        .map(|_| {
+            let vm = new_vm(&cache);
+            let storage = storage.clone();
+            thread::spawn(move || {
+                assert_eq!(call(&vm, &storage, "B", "b"), MoveValue::U64(2));
+            })
+        })
+        .collect();
+    for child in children {
+        child.join().unwrap();
+    }
+    assert_eq!(cache.len(), 2);
+}
@@ -0,0 +1,257 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{config::VMConfig, move_vm::MoveVM};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+const CODE: &str = r#"
+    module std::vector {
+        #[bytecode_instruction]
+        native public fun push_back<Element>(v: &mut vector<Element>, e: Element);
+    }
+
+    module {{ADDR}}::M {
+        use std::vector;
+
+        struct S<T> has drop { t: T }
+
+        struct Shallow has key { v: vector<u64> }
+
+        struct Deep has key { v: vector<vector<u64>> }
+
+        public fun recurse(n: u64) {
+            if (n > 0) recurse(n - 1)
+        }
+
+        fun sum(a: u64, b: u64, c: u64, d: u64, e: u64): u64 {
+            a + b + c + d + e
+        }
+
+        public fun sum_constants(): u64 {
+            sum(1, 2, 3, 4, 5)
+        }
+
+        public fun pack_three(x: u64): vector<u64> {
+            vector[x, x, x]
+        }
+
+        public fun pack_four(x: u64): vector<u64> {
+            vector[x, x, x, x]
+        }
+
+        public fun push(n: u64) {
+            let v = vector[];
+            let i = 0;
+            while (i < n) {
+                vector::push_back(&mut v, i);
+                i = i + 1;
+            }
+        }
+
+        fun id<T>() {}
+
+        public fun instantiate_small() {
+            id<S<u64>>()
+        }
+
+        public fun instantiate_large() {
+            id<S<S<S<S<u64>>>>>()
+        }
+
+        public fun store_shallow(s: signer) {
+            move_to(&s, Shallow { v: vector[] })
+        }
+
+        public fun store_deep(s: signer) {
+            move_to(&s, Deep { v: vector[] })
+        }
+    }
+"#;
+
+const NATIVE_CODE: &str = r#"
+    module std::vector {
+        native public fun push_back<Element>(v: &mut vector<Element>, e: Element);
+    }
+
+    module std::bcs {
+        native public fun to_bytes<MoveValue>(v: &MoveValue): vector<u8>;
+    }
+
+    module {{ADDR}}::M {
+        use std::bcs;
+        use std::vector;
+
+        public fun push(n: u64) {
+            let v = vector[];
+            let i = 0;
+            while (i < n) {
+                vector::push_back(&mut v, i);
+                i = i + 1;
+            }
+        }
+
+        public fun serialize(x: u64) {
+            bcs::to_bytes(&x);
+        }
+    }
+"#;
+
+fn setup(code: &str, config: VMConfig) -> (MoveVM, InMemoryStorage) {
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    let mut storage = InMemoryStorage::new();
+    for unit in compile_units(&code).unwrap() {
+        let m = as_module(unit);
+        let mut blob = vec![];
+        m.serialize(&mut blob).unwrap();
+        storage.publish_or_overwrite_module(m.self_id(), blob);
+    }
+    let natives = move_stdlib::natives::all_natives(
+        AccountAddress::ONE,
+        move_stdlib::natives::GasParameters::zeros(),
+    );
+    let vm = MoveVM::new_with_config(natives, config).unwrap();
+    (vm, storage)
+}
+
+/// Calls `M::function` under `config`, returning the status code of the failure, if any.
+fn call(config: VMConfig, function: &str, args: Vec<MoveValue>) -> Option<StatusCode> {
+    call_in(CODE, config, function, args)
+}
+
+/// Calls `M::function` of `code` under `config`, returning the status code of the failure, if
+/// any.
+fn call_in(
+    code: &str,
+    config: VMConfig,
+    function: &str,
+    args: Vec<MoveValue>,
+) -> Option<StatusCode> {
+    let (vm, storage) = setup(code, config);
+    let mut session = vm.new_session(&storage);
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    session
+        .execute_function_bypass_visibility(
+            &module_id,
+            &Identifier::new(function).unwrap(),
+            vec![],
+            serialize_values(&args),
+            &mut UnmeteredGasMeter,
+        )
+        .err()
+        .map(|err| err.major_status())
+}
+
+#[test]
+fn call_stack_size() {
+    let config = || VMConfig {
+        max_call_stack_size: 10,
+        ..Default::default()
+    };
+    assert_eq!(call(config(), "recurse", vec![MoveValue::U64(10)]), None);
+    assert_eq!(
+        call(config(), "recurse", vec![MoveValue::U64(11)]),
+        Some(StatusCode::CALL_STACK_OVERFLOW)
+    );
+}
+
+#[test]
+fn operand_stack_size() {
+    let config = || VMConfig {
+        max_operand_stack_size: 4,
+        ..Default::default()
+    };
+    assert_eq!(
+        call(config(), "sum_constants", vec![]),
+        Some(StatusCode::EXECUTION_STACK_OVERFLOW)
+    );
+
+    let config = || VMConfig {
+        max_operand_stack_size: 5,
+        ..Default::default()
+    };
+    assert_eq!(call(config(), "sum_constants", vec![]), None);
+}
+
+#[test]
+fn vector_length() {
+    let config = || VMConfig {
+        max_vector_length: 3,
+        ..Default::default()
+    };
+    assert_eq!(call(config(), "pack_three", vec![MoveValue::U64(1)]), None);
+    assert_eq!(
+        call(config(), "pack_four", vec![MoveValue::U64(1)]),
+        Some(StatusCode::VM_MAX_VECTOR_LENGTH_REACHED)
+    );
+    assert_eq!(call(config(), "push", vec![MoveValue::U64(3)]), None);
+    assert_eq!(
+        call(config(), "push", vec![MoveValue::U64(4)]),
+        Some(StatusCode::VM_MAX_VECTOR_LENGTH_REACHED)
+    );
+}
+
+#[test]
+fn vector_length_in_natives() {
+    let config = || VMConfig {
+        max_vector_length: 3,
+        ..Default::default()
+    };
+    let call = |function, args| call_in(NATIVE_CODE, config(), function, args);
+    assert_eq!(call("push", vec![MoveValue::U64(3)]), None);
+    assert_eq!(
+        call("push", vec![MoveValue::U64(4)]),
+        Some(StatusCode::VM_MAX_VECTOR_LENGTH_REACHED)
+    );
+    // A `u64` serializes to 8 bytes
+    assert_eq!(
+        call("serialize", vec![MoveValue::U64(1)]),
+        Some(StatusCode::VM_MAX_VECTOR_LENGTH_REACHED)
+    );
+
+    let config = || VMConfig {
+        max_vector_length: 8,
+        ..Default::default()
+    };
+    assert_eq!(
+        call_in(NATIVE_CODE, config(), "serialize", vec![MoveValue::U64(1)]),
+        None
+    );
+}
+
+#[test]
+fn type_instantiation_nodes() {
+    let config = || VMConfig {
+        max_type_instantiation_nodes: 4,
+        ..Default::default()
+    };
+    assert_eq!(call(config(), "instantiate_small", vec![]), None);
+    assert_eq!(
+        call(config(), "instantiate_large", vec![]),
+        Some(StatusCode::TOO_MANY_TYPE_NODES)
+    );
+}
+
+#[test]
+fn value_depth() {
+    let config = || VMConfig {
+        max_value_depth: 3,
+        ..Default::default()
+    };
+    let signer = || vec![MoveValue::Signer(TEST_ADDR)];
+    assert_eq!(call(config(), "store_shallow", signer()), None);
+    assert_eq!(
+        call(config(), "store_deep", signer()),
+        Some(StatusCode::VM_MAX_VALUE_DEPTH_REACHED)
+    );
+}
@@ -0,0 +1,208 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    multi_version::MultiVersionMemory,
+    scheduler::{Scheduler, Task},
+    view::{write_set, MultiVersionView, SequentialView},
+    Version,
+};
+use move_binary_format::errors::VMResult;
+use move_core_types::{
+    effects::{ChangeSet, Event},
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+    resolver::MoveResolver,
+};
+use move_vm_runtime::move_vm::MoveVM;
+use move_vm_types::gas::GasMeter;
+use parking_lot::Mutex;
+use std::thread;
+
+/// A transaction calling an entry function, as executed by `Session::execute_entry_function`.
+#[derive(Debug, Clone)]
+pub struct EntryFunctionCall {
+    pub module: ModuleId,
+    pub function: Identifier,
+    pub ty_args: Vec<TypeTag>,
+    pub args: Vec<Vec<u8>>,
+}
+
+/// The effects of a transaction which executed successfully, or the error it failed with, in
+/// which case it has no effects.
+pub type TransactionOutput = VMResult<(ChangeSet, Vec<Event>)>;
+
+/// Executes blocks of transactions on several threads sharing a `MoveVM`.
+///
+/// Transactions may execute several times, against states which turn out to be inconsistent, so
+/// they should be metered: an unmetered transaction could fail to terminate on a state no
+/// sequential execution would produce. Transactions must not publish modules, as the VM caches
+/// the modules it loads regardless of whether the transaction loading them is re-executed later.
+pub struct ParallelExecutor<'a> {
+    vm: &'a MoveVM,
+    concurrency: usize,
+}
+
+impl<'a> ParallelExecutor<'a> {
+    /// Creates an executor running blocks on `concurrency` threads, or one if it is zero.
+    pub fn new(vm: &'a MoveVM, concurrency: usize) -> Self {
+        Self {
+            vm,
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    /// Executes `block` against `storage`, each transaction observing the effects of the ones
+    /// before it, and returns the output of every transaction. The outputs are the same as the
+    /// ones of `execute_block_sequential`; they are not applied to `storage`.
+    ///
+    /// `new_gas_meter` creates the gas meter of each execution of a transaction.
+    pub fn execute_block<S, G>(
+        &self,
+        storage: &S,
+        block: &[EntryFunctionCall],
+        new_gas_meter: impl Fn() -> G + Sync,
+    ) -> Vec<TransactionOutput>
+    where
+        S: MoveResolver + Sync,
+        G: GasMeter,
+    {
+        let block_executor = BlockExecutor {
+            vm: self.vm,
+            storage,
+            block,
+            new_gas_meter,
+            memory: MultiVersionMemory::new(block.len()),
+            scheduler: Scheduler::new(block.len()),
+            outputs: block.iter().map(|_| Mutex::new(None)).collect(),
+        };
+        thread::scope(|scope| {
+            for _ in 0..self.concurrency {
+                scope.spawn(|| block_executor.work());
+            }
+        });
+        block_executor
+            .outputs
+            .into_iter()
+            .map(|output| output.into_inner().expect("transaction not executed"))
+            .collect()
+    }
+}
+
+/// Executes `block` against `storage` one transaction after the other, each transaction
+/// observing the effects of the ones before it, and returns the output of every transaction.
+pub fn execute_block_sequential<S, G>(
+    vm: &MoveVM,
+    storage: &S,
+    block: &[EntryFunctionCall],
+    new_gas_meter: impl Fn() -> G,
+) -> Vec<TransactionOutput>
+where
+    S: MoveResolver,
+    G: GasMeter,
+{
+    let mut view = SequentialView::new(storage);
+    block
+        .iter()
+        .map(|txn| {
+            let output = execute_transaction(vm, &view, txn, &mut new_gas_meter());
+            if let Ok((change_set, _)) = &output {
+                view.apply(change_set);
+            }
+            output
+        })
+        .collect()
+}
+
+fn execute_transaction<S: MoveResolver>(
+    vm: &MoveVM,
+    storage: &S,
+    txn: &EntryFunctionCall,
+    gas_meter: &mut impl GasMeter,
+) -> TransactionOutput {
+    let mut session = vm.new_session(storage);
+    let args: Vec<&[u8]> = txn.args.iter().map(Vec::as_slice).collect();
+    session.execute_entry_function(
+        &txn.module,
+        &txn.function,
+        txn.ty_args.clone(),
+        args,
+        gas_meter,
+    )?;
+    session.finish()
+}
+
+/// The state of the execution of a block shared by the worker threads.
+struct BlockExecutor<'a, S, F> {
+    vm: &'a MoveVM,
+    storage: &'a S,
+    block: &'a [EntryFunctionCall],
+    new_gas_meter: F,
+    memory: MultiVersionMemory,
+    scheduler: Scheduler,
+    // The output of the last execution of each transaction.
+    outputs: Vec<Mutex<Option<TransactionOutput>>>,
+}
+
+impl<'a, S, F, G> BlockExecutor<'a, S, F>
+where
+    S: MoveResolver,
+    F: Fn() -> G,
+    G: GasMeter,
+{
+    fn work(&self) {
+        let mut task = None;
+        while !self.scheduler.done() {
+            task = match task {
+                Some(Task::Execution(version)) => self.try_execute(version),
+                Some(Task::Validation(version)) => self.validate(version),
+                None => {
+                    let task = self.scheduler.next_task();
+                    if task.is_none() {
+                        thread::yield_now();
+                    }
+                    task
+                }
+            };
+        }
+    }
+
+    fn try_execute(&self, version: Version) -> Option<Task> {
+        let (txn_idx, incarnation) = version;
+        loop {
+            let view = MultiVersionView::new(self.storage, &self.memory, txn_idx);
+            let output = execute_transaction(
+                self.vm,
+                &view,
+                &self.block[txn_idx],
+                &mut (self.new_gas_meter)(),
+            );
+            if let Some(blocking_txn_idx) = view.dependency() {
+                if self.scheduler.add_dependency(txn_idx, blocking_txn_idx) {
+                    return None;
+                }
+                // The blocking transaction was re-executed meanwhile.
+                continue;
+            }
+
+            let writes = match &output {
+                Ok((change_set, _)) => write_set(change_set),
+                Err(_) => vec![],
+            };
+            let wrote_new_location = self.memory.record(version, view.into_read_set(), writes);
+            *self.outputs[txn_idx].lock() = Some(output);
+            return self
+                .scheduler
+                .finish_execution(txn_idx, incarnation, wrote_new_location);
+        }
+    }
+
+    fn validate(&self, (txn_idx, incarnation): Version) -> Option<Task> {
+        let valid = self.memory.validate_read_set(txn_idx);
+        let aborted = !valid && self.scheduler.try_validation_abort(txn_idx, incarnation);
+        if aborted {
+            self.memory.convert_writes_to_estimates(txn_idx);
+        }
+        self.scheduler.finish_validation(txn_idx, aborted)
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optimistic parallel execution of a block of entry function calls, following Block-STM.
+//!
+//! The transactions of a block run speculatively on several threads, each one in its own
+//! `Session`. The writes of every transaction are kept in a multi-version memory, where the later
+//! transactions of the block read them, and the locations each transaction read are recorded.
+//! Once executed, a transaction is validated by checking that it would still read the same
+//! versions of these locations, and re-executed if not. The outputs are the same as those of
+//! executing the block sequentially, in order.
+
+mod executor;
+mod multi_version;
+mod scheduler;
+mod view;
+
+pub use executor::{
+    execute_block_sequential, EntryFunctionCall, ParallelExecutor, TransactionOutput,
+};
+
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, StructTag},
+};
+
+/// The index of a transaction within its block.
+pub type TxnIndex = usize;
+
+/// The number of times a transaction was re-executed.
+pub type Incarnation = usize;
+
+/// Identifies one execution of a transaction.
+pub type Version = (TxnIndex, Incarnation);
+
+/// A location of the global state read or written by transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StateKey {
+    Module(ModuleId),
+    Resource(AccountAddress, StructTag),
+}
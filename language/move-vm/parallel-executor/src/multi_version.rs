@@ -0,0 +1,126 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The multi-version memory holding the writes of the transactions of a block, as described in
+//! the Block-STM paper.
+
+use crate::{Incarnation, StateKey, TxnIndex, Version};
+use parking_lot::{Mutex, RwLock};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The value of a location, `None` standing for a deleted resource or module.
+pub(crate) type Value = Option<Vec<u8>>;
+
+/// A location read by an incarnation, along with the version it read, or `None` if the value
+/// came from the storage the block executes against.
+pub(crate) type ReadDescriptor = (StateKey, Option<Version>);
+
+enum Entry {
+    Write {
+        incarnation: Incarnation,
+        value: Value,
+    },
+    /// Marks a write of an aborted incarnation, which the next incarnation is likely to perform
+    /// again.
+    Estimate,
+}
+
+pub(crate) enum ReadResult {
+    Value(Version, Value),
+    NotFound,
+    /// The latest write is an estimate of the given transaction, which must be re-executed first.
+    Dependency(TxnIndex),
+}
+
+pub(crate) struct MultiVersionMemory {
+    data: RwLock<HashMap<StateKey, BTreeMap<TxnIndex, Entry>>>,
+    last_written_locations: Vec<Mutex<Vec<StateKey>>>,
+    last_read_set: Vec<Mutex<Vec<ReadDescriptor>>>,
+}
+
+impl MultiVersionMemory {
+    pub fn new(block_size: usize) -> Self {
+        Self {
+            data: RwLock::new(HashMap::new()),
+            last_written_locations: (0..block_size).map(|_| Mutex::new(vec![])).collect(),
+            last_read_set: (0..block_size).map(|_| Mutex::new(vec![])).collect(),
+        }
+    }
+
+    /// Returns the value of `key` written by the highest transaction below `txn_idx`.
+    pub fn read(&self, key: &StateKey, txn_idx: TxnIndex) -> ReadResult {
+        let data = self.data.read();
+        let entry = data
+            .get(key)
+            .and_then(|versions| versions.range(..txn_idx).next_back());
+        match entry {
+            None => ReadResult::NotFound,
+            Some((idx, Entry::Estimate)) => ReadResult::Dependency(*idx),
+            Some((idx, Entry::Write { incarnation, value })) => {
+                ReadResult::Value((*idx, *incarnation), value.clone())
+            }
+        }
+    }
+
+    /// Records the reads and writes of an incarnation, replacing those of the previous one.
+    /// Returns whether the incarnation wrote a location the previous one did not.
+    pub fn record(
+        &self,
+        (txn_idx, incarnation): Version,
+        read_set: Vec<ReadDescriptor>,
+        write_set: Vec<(StateKey, Value)>,
+    ) -> bool {
+        let new_locations: Vec<_> = write_set.iter().map(|(key, _)| key.clone()).collect();
+        {
+            let mut data = self.data.write();
+            for (key, value) in write_set {
+                data.entry(key)
+                    .or_default()
+                    .insert(txn_idx, Entry::Write { incarnation, value });
+            }
+        }
+        *self.last_read_set[txn_idx].lock() = read_set;
+        self.update_written_locations(txn_idx, new_locations)
+    }
+
+    fn update_written_locations(&self, txn_idx: TxnIndex, new_locations: Vec<StateKey>) -> bool {
+        let prev_locations = std::mem::replace(
+            &mut *self.last_written_locations[txn_idx].lock(),
+            new_locations.clone(),
+        );
+        let new_locations: HashSet<_> = new_locations.into_iter().collect();
+        let prev_locations: HashSet<_> = prev_locations.into_iter().collect();
+
+        let mut data = self.data.write();
+        for key in prev_locations.difference(&new_locations) {
+            if let Some(versions) = data.get_mut(key) {
+                versions.remove(&txn_idx);
+            }
+        }
+        !new_locations.is_subset(&prev_locations)
+    }
+
+    /// Replaces the writes of an aborted transaction by estimates.
+    pub fn convert_writes_to_estimates(&self, txn_idx: TxnIndex) {
+        let locations = self.last_written_locations[txn_idx].lock();
+        let mut data = self.data.write();
+        for key in locations.iter() {
+            if let Some(versions) = data.get_mut(key) {
+                versions.insert(txn_idx, Entry::Estimate);
+            }
+        }
+    }
+
+    /// Checks that every location read by the last incarnation of `txn_idx` would still be read
+    /// at the same version.
+    pub fn validate_read_set(&self, txn_idx: TxnIndex) -> bool {
+        let read_set = self.last_read_set[txn_idx].lock();
+        read_set
+            .iter()
+            .all(|(key, version)| match self.read(key, txn_idx) {
+                ReadResult::Value(current, _) => *version == Some(current),
+                ReadResult::NotFound => version.is_none(),
+                ReadResult::Dependency(_) => false,
+            })
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The collaborative scheduler of Block-STM, handing out the execution and validation tasks of a
+//! block to worker threads, lowest transaction index first.
+
+use crate::{Incarnation, TxnIndex, Version};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+pub(crate) enum Task {
+    Execution(Version),
+    Validation(Version),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    ReadyToExecute,
+    Executing,
+    Executed,
+    Aborting,
+}
+
+pub(crate) struct Scheduler {
+    block_size: usize,
+    execution_idx: AtomicUsize,
+    validation_idx: AtomicUsize,
+    // Counts the times one of the indices above was decreased, so that `check_done` does not
+    // miss a decrease happening while it runs.
+    decrease_cnt: AtomicUsize,
+    num_active_tasks: AtomicUsize,
+    done_marker: AtomicBool,
+    txn_status: Vec<Mutex<(Incarnation, Status)>>,
+    // The transactions waiting for a transaction to be re-executed.
+    txn_dependency: Vec<Mutex<Vec<TxnIndex>>>,
+}
+
+impl Scheduler {
+    pub fn new(block_size: usize) -> Self {
+        Self {
+            block_size,
+            execution_idx: AtomicUsize::new(0),
+            validation_idx: AtomicUsize::new(0),
+            decrease_cnt: AtomicUsize::new(0),
+            num_active_tasks: AtomicUsize::new(0),
+            done_marker: AtomicBool::new(block_size == 0),
+            txn_status: (0..block_size)
+                .map(|_| Mutex::new((0, Status::ReadyToExecute)))
+                .collect(),
+            txn_dependency: (0..block_size).map(|_| Mutex::new(vec![])).collect(),
+        }
+    }
+
+    /// Returns whether all transactions are executed and validated.
+    pub fn done(&self) -> bool {
+        self.done_marker.load(Ordering::SeqCst)
+    }
+
+    /// Returns the next task, validations of lower transactions taking precedence over
+    /// executions.
+    pub fn next_task(&self) -> Option<Task> {
+        if self.validation_idx.load(Ordering::SeqCst) < self.execution_idx.load(Ordering::SeqCst) {
+            self.next_version_to_validate().map(Task::Validation)
+        } else {
+            self.next_version_to_execute().map(Task::Execution)
+        }
+    }
+
+    /// Makes `txn_idx` wait for `blocking_txn_idx` to be re-executed, ending the current task.
+    /// Returns false if `blocking_txn_idx` was re-executed in the meantime, in which case
+    /// `txn_idx` should be executed again straight away.
+    pub fn add_dependency(&self, txn_idx: TxnIndex, blocking_txn_idx: TxnIndex) -> bool {
+        {
+            let mut dependents = self.txn_dependency[blocking_txn_idx].lock();
+            if self.txn_status[blocking_txn_idx].lock().1 == Status::Executed {
+                return false;
+            }
+            self.txn_status[txn_idx].lock().1 = Status::Aborting;
+            dependents.push(txn_idx);
+        }
+        self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        true
+    }
+
+    /// Completes the execution of a version, returning the validation of the same version as the
+    /// next task if it can be done straight away.
+    pub fn finish_execution(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        wrote_new_location: bool,
+    ) -> Option<Task> {
+        self.txn_status[txn_idx].lock().1 = Status::Executed;
+        let dependents = std::mem::take(&mut *self.txn_dependency[txn_idx].lock());
+        self.resume_dependencies(dependents);
+
+        if self.validation_idx.load(Ordering::SeqCst) > txn_idx {
+            // Validations of higher transactions have been handed out already: they only need to
+            // be redone if this incarnation wrote somewhere the previous one did not.
+            if wrote_new_location {
+                self.decrease_validation_idx(txn_idx);
+            } else {
+                return Some(Task::Validation((txn_idx, incarnation)));
+            }
+        }
+        self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        None
+    }
+
+    /// Aborts `incarnation` of `txn_idx` after it failed validation. Returns false if it was
+    /// aborted already by another validation.
+    pub fn try_validation_abort(&self, txn_idx: TxnIndex, incarnation: Incarnation) -> bool {
+        let mut status = self.txn_status[txn_idx].lock();
+        if *status == (incarnation, Status::Executed) {
+            status.1 = Status::Aborting;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Completes the validation of a version, returning the re-execution of the transaction as
+    /// the next task if the validation aborted it.
+    pub fn finish_validation(&self, txn_idx: TxnIndex, aborted: bool) -> Option<Task> {
+        if aborted {
+            self.set_ready_status(txn_idx);
+            self.decrease_validation_idx(txn_idx + 1);
+            if self.execution_idx.load(Ordering::SeqCst) > txn_idx {
+                if let Some(version) = self.try_incarnate(txn_idx) {
+                    return Some(Task::Execution(version));
+                }
+            }
+        }
+        self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        None
+    }
+
+    fn next_version_to_execute(&self) -> Option<Version> {
+        if self.execution_idx.load(Ordering::SeqCst) >= self.block_size {
+            self.check_done();
+            return None;
+        }
+        self.num_active_tasks.fetch_add(1, Ordering::SeqCst);
+        let idx = self.execution_idx.fetch_add(1, Ordering::SeqCst);
+        let version = self.try_incarnate(idx);
+        if version.is_none() {
+            self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        }
+        version
+    }
+
+    fn next_version_to_validate(&self) -> Option<Version> {
+        if self.validation_idx.load(Ordering::SeqCst) >= self.block_size {
+            self.check_done();
+            return None;
+        }
+        self.num_active_tasks.fetch_add(1, Ordering::SeqCst);
+        let idx = self.validation_idx.fetch_add(1, Ordering::SeqCst);
+        if idx < self.block_size {
+            let (incarnation, status) = *self.txn_status[idx].lock();
+            if status == Status::Executed {
+                return Some((idx, incarnation));
+            }
+        }
+        self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        None
+    }
+
+    fn try_incarnate(&self, txn_idx: TxnIndex) -> Option<Version> {
+        if txn_idx >= self.block_size {
+            return None;
+        }
+        let mut status = self.txn_status[txn_idx].lock();
+        if status.1 == Status::ReadyToExecute {
+            status.1 = Status::Executing;
+            Some((txn_idx, status.0))
+        } else {
+            None
+        }
+    }
+
+    fn set_ready_status(&self, txn_idx: TxnIndex) {
+        let mut status = self.txn_status[txn_idx].lock();
+        debug_assert!(status.1 == Status::Aborting);
+        *status = (status.0 + 1, Status::ReadyToExecute);
+    }
+
+    fn resume_dependencies(&self, dependents: Vec<TxnIndex>) {
+        for txn_idx in &dependents {
+            self.set_ready_status(*txn_idx);
+        }
+        if let Some(min_dependent) = dependents.into_iter().min() {
+            self.decrease_execution_idx(min_dependent);
+        }
+    }
+
+    fn decrease_execution_idx(&self, target: TxnIndex) {
+        self.execution_idx.fetch_min(target, Ordering::SeqCst);
+        self.decrease_cnt.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn decrease_validation_idx(&self, target: TxnIndex) {
+        self.validation_idx.fetch_min(target, Ordering::SeqCst);
+        self.decrease_cnt.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn check_done(&self) {
+        let observed_cnt = self.decrease_cnt.load(Ordering::SeqCst);
+        let execution_idx = self.execution_idx.load(Ordering::SeqCst);
+        let validation_idx = self.validation_idx.load(Ordering::SeqCst);
+        let num_active_tasks = self.num_active_tasks.load(Ordering::SeqCst);
+        if execution_idx.min(validation_idx) >= self.block_size
+            && num_active_tasks == 0
+            && observed_cnt == self.decrease_cnt.load(Ordering::SeqCst)
+        {
+            self.done_marker.store(true, Ordering::SeqCst);
+        }
+    }
+}
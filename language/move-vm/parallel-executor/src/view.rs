@@ -0,0 +1,163 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Storage views through which transactions of a block read the state left by the transactions
+//! before them.
+
+use crate::{
+    multi_version::{MultiVersionMemory, ReadDescriptor, ReadResult, Value},
+    StateKey, TxnIndex,
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::ChangeSet,
+    language_storage::{ModuleId, StructTag},
+    resolver::{ModuleResolver, MoveResolver, ResourceResolver},
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+/// An error reading through a `MultiVersionView`.
+#[derive(Debug)]
+pub(crate) enum ViewError<E> {
+    /// The read depends on a write of a transaction which is being re-executed.
+    Dependency,
+    Storage(E),
+}
+
+/// The view of the state a transaction of a block executes against: the writes of the lower
+/// transactions in the multi-version memory, falling back to the storage of the block. The view
+/// records the locations read, along with the versions observed.
+pub(crate) struct MultiVersionView<'a, S> {
+    base: &'a S,
+    memory: &'a MultiVersionMemory,
+    txn_idx: TxnIndex,
+    read_set: RefCell<Vec<ReadDescriptor>>,
+    dependency: Cell<Option<TxnIndex>>,
+}
+
+impl<'a, S: MoveResolver> MultiVersionView<'a, S> {
+    pub fn new(base: &'a S, memory: &'a MultiVersionMemory, txn_idx: TxnIndex) -> Self {
+        Self {
+            base,
+            memory,
+            txn_idx,
+            read_set: RefCell::new(vec![]),
+            dependency: Cell::new(None),
+        }
+    }
+
+    /// Returns the transaction whose estimated write was read, if any. The execution then failed
+    /// with a storage error and must be retried once that transaction is re-executed.
+    pub fn dependency(&self) -> Option<TxnIndex> {
+        self.dependency.get()
+    }
+
+    pub fn into_read_set(self) -> Vec<ReadDescriptor> {
+        self.read_set.into_inner()
+    }
+
+    fn read(
+        &self,
+        key: StateKey,
+        read_base: impl FnOnce(&S) -> Result<Value, S::Err>,
+    ) -> Result<Value, ViewError<S::Err>> {
+        match self.memory.read(&key, self.txn_idx) {
+            ReadResult::Value(version, value) => {
+                self.read_set.borrow_mut().push((key, Some(version)));
+                Ok(value)
+            }
+            ReadResult::NotFound => {
+                self.read_set.borrow_mut().push((key, None));
+                read_base(self.base).map_err(ViewError::Storage)
+            }
+            ReadResult::Dependency(txn_idx) => {
+                self.dependency.set(Some(txn_idx));
+                Err(ViewError::Dependency)
+            }
+        }
+    }
+}
+
+impl<'a, S: MoveResolver> ModuleResolver for MultiVersionView<'a, S> {
+    type Error = ViewError<S::Err>;
+
+    fn get_module(&self, id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.read(StateKey::Module(id.clone()), |base| base.get_module(id))
+    }
+}
+
+impl<'a, S: MoveResolver> ResourceResolver for MultiVersionView<'a, S> {
+    type Error = ViewError<S::Err>;
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.read(StateKey::Resource(*address, tag.clone()), |base| {
+            base.get_resource(address, tag)
+        })
+    }
+}
+
+/// The view of the state a transaction executes against when running a block sequentially: the
+/// writes of the transactions before it on top of the storage of the block.
+pub(crate) struct SequentialView<'a, S> {
+    base: &'a S,
+    writes: HashMap<StateKey, Value>,
+}
+
+impl<'a, S: MoveResolver> SequentialView<'a, S> {
+    pub fn new(base: &'a S) -> Self {
+        Self {
+            base,
+            writes: HashMap::new(),
+        }
+    }
+
+    pub fn apply(&mut self, change_set: &ChangeSet) {
+        self.writes.extend(write_set(change_set));
+    }
+}
+
+impl<'a, S: MoveResolver> ModuleResolver for SequentialView<'a, S> {
+    type Error = S::Err;
+
+    fn get_module(&self, id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        match self.writes.get(&StateKey::Module(id.clone())) {
+            Some(value) => Ok(value.clone()),
+            None => self.base.get_module(id),
+        }
+    }
+}
+
+impl<'a, S: MoveResolver> ResourceResolver for SequentialView<'a, S> {
+    type Error = S::Err;
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        match self.writes.get(&StateKey::Resource(*address, tag.clone())) {
+            Some(value) => Ok(value.clone()),
+            None => self.base.get_resource(address, tag),
+        }
+    }
+}
+
+/// Returns the locations written by `change_set` along with their new values.
+pub(crate) fn write_set(change_set: &ChangeSet) -> Vec<(StateKey, Value)> {
+    let modules = change_set.modules().map(|(address, name, op)| {
+        let key = StateKey::Module(ModuleId::new(address, name.clone()));
+        (key, op.ok().map(<[u8]>::to_vec))
+    });
+    let resources = change_set.resources().map(|(address, tag, op)| {
+        let key = StateKey::Resource(address, tag.clone());
+        (key, op.ok().map(<[u8]>::to_vec))
+    });
+    modules.chain(resources).collect()
+}
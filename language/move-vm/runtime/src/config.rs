@@ -3,6 +3,13 @@
 
 use move_binary_format::file_format_common::VERSION_MAX;
 use move_bytecode_verifier::VerifierConfig;
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::{IdentStr, Identifier},
+    language_storage::StructTag,
+    value::MoveValue,
+};
+use std::collections::BTreeMap;
 
 /// Dynamic config options for the Move VM.
 pub struct VMConfig {
@@ -11,6 +18,29 @@ pub struct VMConfig {
     // When this flag is set to true, MoveVM will perform type check at every instruction
     // execution to ensure that type safety cannot be violated at runtime.
     pub paranoid_type_checks: bool,
+    /// Maximal number of values on the operand stack. Exceeding it fails with
+    /// `EXECUTION_STACK_OVERFLOW`.
+    pub max_operand_stack_size: usize,
+    /// Maximal number of frames on the call stack. Exceeding it fails with
+    /// `CALL_STACK_OVERFLOW`.
+    pub max_call_stack_size: usize,
+    /// Maximal depth of a value in terms of type depth, checked whenever a value is serialized or
+    /// deserialized. Exceeding it fails with `VM_MAX_VALUE_DEPTH_REACHED`.
+    pub max_value_depth: usize,
+    /// Maximal number of type nodes when instantiating a generic type or function at runtime,
+    /// not including the field types of structs. Exceeding it fails with `TOO_MANY_TYPE_NODES`.
+    pub max_type_instantiation_nodes: usize,
+    /// Maximal length of a vector created or grown by the vector instructions, or by natives
+    /// which create or grow vectors. Exceeding it fails with `VM_MAX_VECTOR_LENGTH_REACHED`.
+    pub max_vector_length: u64,
+    /// If set, the only struct types which may appear in the parameters of entry functions and
+    /// scripts, besides primitive types, vectors and signers. Otherwise arguments of any struct
+    /// type are accepted without validation.
+    pub entry_struct_args: Option<EntryStructArgs>,
+    /// If set, the maximal abstract memory size of the values held by an execution on the operand
+    /// stack, in locals and in loaded globals. Exceeding it fails with `MEMORY_LIMIT_EXCEEDED`.
+    /// Memory usage is only tracked, and its peak reported to the gas meter, when this is set.
+    pub max_memory_usage: Option<u64>,
 }
 
 impl Default for VMConfig {
@@ -19,6 +49,50 @@ impl Default for VMConfig {
             verifier: VerifierConfig::default(),
             max_binary_format_version: VERSION_MAX,
             paranoid_type_checks: false,
+            max_operand_stack_size: 1024,
+            max_call_stack_size: 1024,
+            max_value_depth: 128,
+            max_type_instantiation_nodes: 128,
+            max_vector_length: u64::MAX,
+            entry_struct_args: None,
+            max_memory_usage: None,
         }
     }
 }
+
+/// Validates the fields of a struct passed as an argument, given in declaration order. Returns
+/// whether they form a valid value of the struct, e.g. whether the bytes of a string are UTF-8.
+pub type StructArgValidator = fn(&[MoveValue]) -> bool;
+
+/// An allow-list of struct types that may be passed as arguments to entry functions and scripts,
+/// each with a validator run when deserializing arguments of the type.
+#[derive(Clone, Default)]
+pub struct EntryStructArgs {
+    validators: BTreeMap<(AccountAddress, Identifier, Identifier), StructArgValidator>,
+}
+
+impl EntryStructArgs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows the struct `address::module::name`, for any type arguments which are allowed
+    /// themselves.
+    pub fn allow(
+        &mut self,
+        address: AccountAddress,
+        module: &IdentStr,
+        name: &IdentStr,
+        validator: StructArgValidator,
+    ) {
+        self.validators
+            .insert((address, module.to_owned(), name.to_owned()), validator);
+    }
+
+    /// The validator of the struct type `tag`, or `None` if the type is not allowed.
+    pub fn validator(&self, tag: &StructTag) -> Option<StructArgValidator> {
+        self.validators
+            .get(&(tag.address, tag.module.clone(), tag.name.clone()))
+            .copied()
+    }
+}
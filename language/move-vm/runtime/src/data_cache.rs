@@ -36,6 +36,31 @@ impl AccountDataCache {
     }
 }
 
+/// The changes made to a `TransactionDataCache` since a savepoint of its session, so they can be
+/// undone. The state a resource or module had at the savepoint is saved when it is first accessed
+/// after the savepoint, and `None` if it was not in the cache then.
+#[derive(Default)]
+struct UndoLog {
+    num_events: usize,
+    resources: BTreeMap<AccountAddress, BTreeMap<Type, Option<(MoveTypeLayout, GlobalValue)>>>,
+    modules: BTreeMap<ModuleId, Option<(Vec<u8>, bool)>>,
+}
+
+impl UndoLog {
+    /// Adds the changes of `log`, which was started after this log, to this log.
+    fn merge(&mut self, log: UndoLog) {
+        for (addr, resources) in log.resources {
+            let saved = self.resources.entry(addr).or_default();
+            for (ty, resource) in resources {
+                saved.entry(ty).or_insert(resource);
+            }
+        }
+        for (module_id, module) in log.modules {
+            self.modules.entry(module_id).or_insert(module);
+        }
+    }
+}
+
 /// Transaction data cache. Keep updates within a transaction so they can all be published at
 /// once when the transaction succeeds.
 ///
@@ -54,6 +79,8 @@ pub(crate) struct TransactionDataCache<'r, 'l, S> {
     loader: &'l Loader,
     account_map: BTreeMap<AccountAddress, AccountDataCache>,
     event_data: Vec<(Vec<u8>, u64, Type, MoveTypeLayout, Value)>,
+    // One log per open savepoint, the innermost savepoint last
+    undo_logs: Vec<UndoLog>,
 }
 
 impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
@@ -65,6 +92,7 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
             loader,
             account_map: BTreeMap::new(),
             event_data: vec![],
+            undo_logs: vec![],
         }
     }
 
@@ -137,6 +165,65 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
         Ok((change_set, events))
     }
 
+    /// Starts recording the changes to the cache, so they can be undone by `rollback`. Savepoints
+    /// nest, the new savepoint is the innermost one.
+    pub(crate) fn savepoint(&mut self) {
+        self.undo_logs.push(UndoLog {
+            num_events: self.event_data.len(),
+            ..UndoLog::default()
+        });
+    }
+
+    /// Restores the cache to the state it had at the savepoint of the given nesting depth,
+    /// dropping all resource updates, module publications and events since, along with the
+    /// savepoints created after it. Returns the modules whose publication was undone.
+    ///
+    /// Must not be called while references to global resources are alive.
+    pub(crate) fn rollback(&mut self, depth: usize) -> Vec<ModuleId> {
+        let mut module_ids = vec![];
+        while self.undo_logs.len() > depth {
+            let log = self.undo_logs.pop().unwrap();
+            for (addr, resources) in log.resources {
+                let data_map = &mut Self::get_mut_or_insert_with(&mut self.account_map, &addr, || {
+                    (addr, AccountDataCache::new())
+                })
+                .data_map;
+                for (ty, resource) in resources {
+                    match resource {
+                        Some(resource) => data_map.insert(ty, resource),
+                        None => data_map.remove(&ty),
+                    };
+                }
+            }
+            for (module_id, module) in log.modules {
+                let module_map = &mut Self::get_mut_or_insert_with(
+                    &mut self.account_map,
+                    module_id.address(),
+                    || (*module_id.address(), AccountDataCache::new()),
+                )
+                .module_map;
+                match module {
+                    Some(module) => module_map.insert(module_id.name().to_owned(), module),
+                    None => module_map.remove(module_id.name()),
+                };
+                module_ids.push(module_id);
+            }
+            self.event_data.truncate(log.num_events);
+        }
+        module_ids
+    }
+
+    /// Discards the savepoint of the given nesting depth and the savepoints created after it,
+    /// keeping all changes to the cache.
+    pub(crate) fn release(&mut self, depth: usize) {
+        while self.undo_logs.len() > depth {
+            let log = self.undo_logs.pop().unwrap();
+            if let Some(parent) = self.undo_logs.last_mut() {
+                parent.merge(log);
+            }
+        }
+    }
+
     pub(crate) fn num_mutated_accounts(&self, sender: &AccountAddress) -> u64 {
         // The sender's account will always be mutated.
         let mut total_mutated_accounts: u64 = 1;
@@ -175,6 +262,18 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
             (addr, AccountDataCache::new())
         });
 
+        // Save the resource on its first access since the last savepoint, as it may be changed
+        if let Some(log) = self.undo_logs.last_mut() {
+            let saved = log.resources.entry(addr).or_default();
+            if !saved.contains_key(ty) {
+                let resource = match account_cache.data_map.get(ty) {
+                    Some((layout, gv)) => Some((layout.clone(), gv.copy_value()?)),
+                    None => None,
+                };
+                saved.insert(ty.clone(), resource);
+            }
+        }
+
         let mut load_res = None;
         if !account_cache.data_map.contains_key(ty) {
             let ty_tag = match self.loader.type_to_type_tag(ty)? {
@@ -264,6 +363,13 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
                 (*module_id.address(), AccountDataCache::new())
             });
 
+        if let Some(log) = self.undo_logs.last_mut() {
+            if !log.modules.contains_key(module_id) {
+                let module = account_cache.module_map.get(module_id.name()).cloned();
+                log.modules.insert(module_id.clone(), module);
+            }
+        }
+
         account_cache
             .module_map
             .insert(module_id.name().to_owned(), (blob, is_republishing));
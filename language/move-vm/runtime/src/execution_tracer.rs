@@ -0,0 +1,234 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hooks for observing the execution of the interpreter.
+//!
+//! An `ExecutionTracer` can be installed on a `Session` via
+//! `MoveVM::new_session_with_tracer`. The interpreter then reports function entry and exit,
+//! every instruction executed, native calls, global storage accesses and aborts to it. All
+//! callbacks have empty default implementations so a tracer only needs to implement the events
+//! it cares about. When no tracer is installed the interpreter is instantiated with
+//! `NoopTracer`, whose callbacks compile away.
+
+use crate::loader::{Function, Loader};
+use move_binary_format::{
+    errors::VMError,
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+};
+use move_core_types::{
+    account_address::AccountAddress, language_storage::ModuleId, value::MoveValue,
+};
+use move_vm_types::{
+    loaded_data::runtime_types::Type,
+    values::{Locals, Reference, Value},
+};
+
+/// The kind of global storage operation reported to `ExecutionTracer::global_access`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlobalAccess {
+    ImmBorrow,
+    MutBorrow,
+    Exists,
+    MoveFrom,
+    MoveTo,
+}
+
+/// A view of the function currently being executed, handed to tracer callbacks.
+pub struct TracedFunction<'a> {
+    function: &'a Function,
+    ty_args: &'a [Type],
+    loader: &'a Loader,
+    locals: Option<&'a Locals>,
+}
+
+impl<'a> TracedFunction<'a> {
+    pub(crate) fn new(function: &'a Function, ty_args: &'a [Type], loader: &'a Loader) -> Self {
+        Self {
+            function,
+            ty_args,
+            loader,
+            locals: None,
+        }
+    }
+
+    pub(crate) fn with_locals(self, locals: &'a Locals) -> Self {
+        Self {
+            locals: Some(locals),
+            ..self
+        }
+    }
+
+    /// The module defining the function, or `None` if the function is a script.
+    pub fn module_id(&self) -> Option<&'a ModuleId> {
+        self.function.module_id()
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.function.name()
+    }
+
+    pub fn ty_args(&self) -> &'a [Type] {
+        self.ty_args
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.function.is_native()
+    }
+
+    /// The fully qualified name of the function, e.g. `0x1::M::f` or `Script::main`.
+    pub fn pretty_string(&self) -> String {
+        self.function.pretty_string()
+    }
+
+    /// The index of the function's definition in its module or script, as used by source maps.
+    pub fn definition_index(&self) -> FunctionDefinitionIndex {
+        self.function.index()
+    }
+
+    /// The number of locals of the function, parameters included.
+    pub fn local_count(&self) -> usize {
+        self.function.local_count()
+    }
+
+    /// The current value of local `idx`, annotated with struct and field names. References are
+    /// read through. Returns `None` if the locals of the function are not available (e.g. for
+    /// native calls) or if the local currently holds no value.
+    pub fn local_value(&self, idx: usize) -> Option<MoveValue> {
+        let mut value = self.locals?.copy_loc(idx).ok()?;
+        let mut ty = self
+            .function
+            .local_types()
+            .get(idx)?
+            .subst(self.ty_args)
+            .ok()?;
+        if let Type::Reference(inner) | Type::MutableReference(inner) = ty {
+            value = value.value_as::<Reference>().ok()?.read_ref().ok()?;
+            ty = *inner;
+        }
+        let layout = self.loader.type_to_type_layout(&ty).ok()?;
+        let annotated_layout = self.loader.type_to_fully_annotated_layout(&ty).ok()?;
+        Some(value.as_move_value(&layout).decorate(&annotated_layout))
+    }
+}
+
+/// Callbacks invoked by the interpreter while executing Move code.
+pub trait ExecutionTracer {
+    /// Called when a Move function starts executing, before its first instruction.
+    fn enter_function(&mut self, _function: &TracedFunction) {}
+
+    /// Called when a Move function returns normally, after its frame has been dropped.
+    fn exit_function(&mut self, _function: &TracedFunction) {}
+
+    /// Called instead of `exit_function` for each Move function whose execution is cut short by
+    /// `error`, innermost first. Every entered function is either exited or unwound.
+    fn unwind_function(&mut self, _function: &TracedFunction, _error: &VMError) {}
+
+    /// Called before `instr` at offset `pc` of `function` is executed. `stack` is the operand
+    /// stack shared by all frames, with the top of the stack at the end of the slice.
+    fn instruction(
+        &mut self,
+        _function: &TracedFunction,
+        _pc: CodeOffset,
+        _instr: &Bytecode,
+        _stack: &[Value],
+    ) {
+    }
+
+    /// Called before a native function is invoked with `args`.
+    fn native_call(&mut self, _function: &TracedFunction, _args: &[Value]) {}
+
+    /// Called before a global storage operation on the resource `ty` at `address`.
+    fn global_access(&mut self, _access: GlobalAccess, _address: AccountAddress, _ty: &Type) {}
+
+    /// Called when `function` aborts with `code`, either through the `Abort` instruction or
+    /// because a native function aborted.
+    fn abort(&mut self, _function: &TracedFunction, _code: u64) {}
+}
+
+/// A tracer that ignores all events. Used when a session has no tracer installed.
+pub struct NoopTracer;
+
+impl ExecutionTracer for NoopTracer {}
+
+impl<T: ExecutionTracer + ?Sized> ExecutionTracer for &mut T {
+    fn enter_function(&mut self, function: &TracedFunction) {
+        (**self).enter_function(function)
+    }
+
+    fn exit_function(&mut self, function: &TracedFunction) {
+        (**self).exit_function(function)
+    }
+
+    fn unwind_function(&mut self, function: &TracedFunction, error: &VMError) {
+        (**self).unwind_function(function, error)
+    }
+
+    fn instruction(
+        &mut self,
+        function: &TracedFunction,
+        pc: CodeOffset,
+        instr: &Bytecode,
+        stack: &[Value],
+    ) {
+        (**self).instruction(function, pc, instr, stack)
+    }
+
+    fn native_call(&mut self, function: &TracedFunction, args: &[Value]) {
+        (**self).native_call(function, args)
+    }
+
+    fn global_access(&mut self, access: GlobalAccess, address: AccountAddress, ty: &Type) {
+        (**self).global_access(access, address, ty)
+    }
+
+    fn abort(&mut self, function: &TracedFunction, code: u64) {
+        (**self).abort(function, code)
+    }
+}
+
+/// A tracer that reports every event to two tracers, the first one first, so that they can
+/// observe the same execution.
+pub struct TeeTracer<A, B>(pub A, pub B);
+
+impl<A: ExecutionTracer, B: ExecutionTracer> ExecutionTracer for TeeTracer<A, B> {
+    fn enter_function(&mut self, function: &TracedFunction) {
+        self.0.enter_function(function);
+        self.1.enter_function(function);
+    }
+
+    fn exit_function(&mut self, function: &TracedFunction) {
+        self.0.exit_function(function);
+        self.1.exit_function(function);
+    }
+
+    fn unwind_function(&mut self, function: &TracedFunction, error: &VMError) {
+        self.0.unwind_function(function, error);
+        self.1.unwind_function(function, error);
+    }
+
+    fn instruction(
+        &mut self,
+        function: &TracedFunction,
+        pc: CodeOffset,
+        instr: &Bytecode,
+        stack: &[Value],
+    ) {
+        self.0.instruction(function, pc, instr, stack);
+        self.1.instruction(function, pc, instr, stack);
+    }
+
+    fn native_call(&mut self, function: &TracedFunction, args: &[Value]) {
+        self.0.native_call(function, args);
+        self.1.native_call(function, args);
+    }
+
+    fn global_access(&mut self, access: GlobalAccess, address: AccountAddress, ty: &Type) {
+        self.0.global_access(access, address, ty);
+        self.1.global_access(access, address, ty);
+    }
+
+    fn abort(&mut self, function: &TracedFunction, code: u64) {
+        self.0.abort(function, code);
+        self.1.abort(function, code);
+    }
+}
@@ -3,9 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    execution_tracer::{ExecutionTracer, GlobalAccess, TracedFunction},
     loader::{Function, Loader, Resolver},
     native_functions::NativeContext,
-    trace,
 };
 use fail::fail_point;
 use move_binary_format::{
@@ -24,7 +24,9 @@ use move_vm_types::{
     loaded_data::runtime_types::Type,
     natives::function::NativeResult,
     values::{
-        self, GlobalValue, IntegerValue, Locals, Reference, Struct, StructRef, VMValueCast, Value,
+        self,
+        memory::{GlobalKey, MemoryTracker},
+        GlobalValue, IntegerValue, Locals, Reference, Struct, StructRef, VMValueCast, Value,
         Vector, VectorRef,
     },
     views::TypeView,
@@ -70,6 +72,8 @@ pub(crate) struct Interpreter {
     call_stack: CallStack,
     /// Whether to perform a paranoid type safety checks at runtime.
     paranoid_type_checks: bool,
+    /// The maximal length of vectors created or grown by vector instructions and natives.
+    max_vector_length: u64,
 }
 
 struct TypeWithLoader<'a, 'b> {
@@ -92,16 +96,22 @@ impl Interpreter {
         args: Vec<Value>,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: &mut (impl ExecutionTracer + ?Sized),
         extensions: &mut NativeContextExtensions,
         loader: &Loader,
     ) -> VMResult<Vec<Value>> {
+        let vm_config = loader.vm_config();
         Interpreter {
-            operand_stack: Stack::new(),
-            call_stack: CallStack::new(),
-            paranoid_type_checks: loader.vm_config().paranoid_type_checks,
+            operand_stack: Stack::new(
+                vm_config.max_operand_stack_size,
+                MemoryTracker::new(vm_config.max_memory_usage),
+            ),
+            call_stack: CallStack::new(vm_config.max_call_stack_size),
+            paranoid_type_checks: vm_config.paranoid_type_checks,
+            max_vector_length: vm_config.max_vector_length,
         }
         .execute_main(
-            loader, data_store, gas_meter, extensions, function, ty_args, args,
+            loader, data_store, gas_meter, tracer, extensions, function, ty_args, args,
         )
     }
 
@@ -116,46 +126,107 @@ impl Interpreter {
         loader: &Loader,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: &mut (impl ExecutionTracer + ?Sized),
         extensions: &mut NativeContextExtensions,
         function: Arc<Function>,
         ty_args: Vec<Type>,
         args: Vec<Value>,
     ) -> VMResult<Vec<Value>> {
+        // Reference arguments point to values held for the execution by its caller
+        if self.operand_stack.memory.is_enabled() {
+            for (ty, arg) in function.parameter_types().iter().zip(&args) {
+                if matches!(ty, Type::Reference(_) | Type::MutableReference(_)) {
+                    arg.copy_value()
+                        .and_then(|arg| arg.value_as::<Reference>())
+                        .and_then(|reference| {
+                            self.operand_stack.memory.use_value(reference.value_view())
+                        })
+                        .map_err(|e| self.set_location(e))?;
+                }
+            }
+        }
         let mut locals = Locals::new(function.local_count());
         for (i, value) in args.into_iter().enumerate() {
-            locals
-                .store_loc(i, value)
+            self.operand_stack
+                .memory
+                .use_value(&value)
+                .and_then(|()| locals.store_loc(i, value))
                 .map_err(|e| self.set_location(e))?;
         }
 
         let mut current_frame = self
             .make_new_frame(loader, function, ty_args, locals)
             .map_err(|err| self.set_location(err))?;
+        tracer.enter_function(&current_frame.traced(loader));
+        if let Err(err) = self.execute_frames(
+            loader,
+            data_store,
+            gas_meter,
+            tracer,
+            extensions,
+            &mut current_frame,
+        ) {
+            // Report the frames cut short by the error, innermost first, so that the tracer sees
+            // as many of them left as entered
+            tracer.unwind_function(&current_frame.traced(loader), &err);
+            while let Some(frame) = self.call_stack.pop() {
+                tracer.unwind_function(&frame.traced(loader), &err);
+            }
+            return Err(err);
+        }
+
+        // end of execution. `self` should no longer be used afterward
+        if let Some(peak) = self.operand_stack.memory.take_new_peak() {
+            gas_meter
+                .charge_peak_memory_usage(peak)
+                .map_err(|e| self.set_location(e))?;
+        }
+        Ok(self.operand_stack.value)
+    }
+
+    /// Executes `current_frame` and the functions it calls, until it returns. On error,
+    /// `current_frame` and the frames on the call stack are those which were executing.
+    fn execute_frames(
+        &mut self,
+        loader: &Loader,
+        data_store: &mut impl DataStore,
+        gas_meter: &mut impl GasMeter,
+        tracer: &mut (impl ExecutionTracer + ?Sized),
+        extensions: &mut NativeContextExtensions,
+        current_frame: &mut Frame,
+    ) -> VMResult<()> {
         loop {
             let resolver = current_frame.resolver(loader);
             let exit_code =
                 current_frame //self
-                    .execute_code(&resolver, &mut self, data_store, gas_meter)
-                    .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
+                    .execute_code(&resolver, self, data_store, gas_meter, tracer)
+                    .map_err(|err| self.maybe_core_dump(err, current_frame))?;
             match exit_code {
                 ExitCode::Return => {
                     let non_ref_vals = current_frame
                         .locals
                         .drop_all_values()
-                        .map(|(_idx, val)| val);
+                        .map(|(_idx, val)| val)
+                        .collect::<Vec<_>>();
+                    for val in &non_ref_vals {
+                        self.operand_stack
+                            .memory
+                            .release_value(val)
+                            .map_err(|e| self.set_location(e))?;
+                    }
 
                     // TODO: Check if the error location is set correctly.
                     gas_meter
-                        .charge_drop_frame(non_ref_vals.into_iter())
+                        .charge_drop_frame(non_ref_vals.iter())
                         .map_err(|e| self.set_location(e))?;
+                    tracer.exit_function(&current_frame.traced(loader));
 
                     if let Some(frame) = self.call_stack.pop() {
                         // Note: the caller will find the callee's return values at the top of the shared operand stack
-                        current_frame = frame;
+                        *current_frame = frame;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                     } else {
-                        // end of execution. `self` should no longer be used afterward
-                        return Ok(self.operand_stack.value);
+                        return Ok(());
                     }
                 }
                 ExitCode::Call(fh_idx) => {
@@ -189,6 +260,7 @@ impl Interpreter {
                             &resolver,
                             data_store,
                             gas_meter,
+                            tracer,
                             extensions,
                             func,
                             vec![],
@@ -199,14 +271,15 @@ impl Interpreter {
                     let frame = self
                         .make_call_frame(loader, func, vec![])
                         .map_err(|e| self.set_location(e))
-                        .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
-                    self.call_stack.push(current_frame).map_err(|frame| {
+                        .map_err(|err| self.maybe_core_dump(err, current_frame))?;
+                    let caller = std::mem::replace(current_frame, frame);
+                    if let Err(caller) = self.call_stack.push(caller) {
+                        *current_frame = caller;
                         let err = PartialVMError::new(StatusCode::CALL_STACK_OVERFLOW);
-                        let err = set_err_info!(frame, err);
-                        self.maybe_core_dump(err, &frame)
-                    })?;
-                    // Note: the caller will find the the callee's return values at the top of the shared operand stack
-                    current_frame = frame;
+                        let err = set_err_info!(current_frame, err);
+                        return Err(self.maybe_core_dump(err, current_frame));
+                    }
+                    tracer.enter_function(&current_frame.traced(loader));
                 }
                 ExitCode::CallGeneric(idx) => {
                     // TODO(Gas): We should charge gas as we do type substitution...
@@ -241,7 +314,7 @@ impl Interpreter {
 
                     if func.is_native() {
                         self.call_native(
-                            &resolver, data_store, gas_meter, extensions, func, ty_args,
+                            &resolver, data_store, gas_meter, tracer, extensions, func, ty_args,
                         )?;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                         continue;
@@ -249,13 +322,15 @@ impl Interpreter {
                     let frame = self
                         .make_call_frame(loader, func, ty_args)
                         .map_err(|e| self.set_location(e))
-                        .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
-                    self.call_stack.push(current_frame).map_err(|frame| {
+                        .map_err(|err| self.maybe_core_dump(err, current_frame))?;
+                    let caller = std::mem::replace(current_frame, frame);
+                    if let Err(caller) = self.call_stack.push(caller) {
+                        *current_frame = caller;
                         let err = PartialVMError::new(StatusCode::CALL_STACK_OVERFLOW);
-                        let err = set_err_info!(frame, err);
-                        self.maybe_core_dump(err, &frame)
-                    })?;
-                    current_frame = frame;
+                        let err = set_err_info!(current_frame, err);
+                        return Err(self.maybe_core_dump(err, current_frame));
+                    }
+                    tracer.enter_function(&current_frame.traced(loader));
                 }
             }
         }
@@ -276,7 +351,9 @@ impl Interpreter {
         let arg_count = func.arg_count();
         let is_generic = !ty_args.is_empty();
         for i in 0..arg_count {
-            locals.store_loc(arg_count - i - 1, self.operand_stack.pop()?)?;
+            let arg = self.operand_stack.pop()?;
+            self.operand_stack.memory.use_value(&arg)?;
+            locals.store_loc(arg_count - i - 1, arg)?;
 
             if self.paranoid_type_checks {
                 let ty = self.operand_stack.pop_ty()?;
@@ -333,6 +410,7 @@ impl Interpreter {
         resolver: &Resolver,
         data_store: &mut dyn DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: &mut (impl ExecutionTracer + ?Sized),
         extensions: &mut NativeContextExtensions,
         function: Arc<Function>,
         ty_args: Vec<Type>,
@@ -342,6 +420,7 @@ impl Interpreter {
             resolver,
             data_store,
             gas_meter,
+            tracer,
             extensions,
             function.clone(),
             ty_args,
@@ -363,6 +442,7 @@ impl Interpreter {
         resolver: &Resolver,
         data_store: &mut dyn DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: &mut (impl ExecutionTracer + ?Sized),
         extensions: &mut NativeContextExtensions,
         function: Arc<Function>,
         ty_args: Vec<Type>,
@@ -383,6 +463,29 @@ impl Interpreter {
             }
         }
 
+        tracer.native_call(
+            &TracedFunction::new(&function, &ty_args, resolver.loader()),
+            args.make_contiguous(),
+        );
+
+        // Natives may change the size of the values their mutable references point to, which are
+        // accounted for again once the native returns
+        let mut_refs = if self.operand_stack.memory.is_enabled() {
+            function
+                .parameter_types()
+                .iter()
+                .zip(&args)
+                .filter(|(ty, _)| matches!(ty, Type::MutableReference(_)))
+                .map(|(_, arg)| {
+                    let reference = arg.copy_value()?.value_as::<Reference>()?;
+                    let size = self.operand_stack.memory.size_of(reference.value_view());
+                    Ok((reference, size))
+                })
+                .collect::<PartialVMResult<Vec<_>>>()?
+        } else {
+            vec![]
+        };
+
         let mut native_context = NativeContext::new(
             self,
             data_store,
@@ -411,6 +514,10 @@ impl Interpreter {
             }
             NativeResult::Abort { cost, abort_code } => {
                 gas_meter.charge_native_function(cost, Option::<std::iter::Empty<&Value>>::None)?;
+                tracer.abort(
+                    &TracedFunction::new(&function, &ty_args, resolver.loader()),
+                    abort_code,
+                );
                 return Err(PartialVMError::new(StatusCode::ABORTED).with_sub_status(abort_code));
             }
             NativeResult::OutOfGas { partial_cost } => {
@@ -438,6 +545,12 @@ impl Interpreter {
                 ),
             );
         }
+        for (reference, size) in mut_refs {
+            let memory = &mut self.operand_stack.memory;
+            let new_size = memory.size_of(reference.value_view());
+            memory.resize_bytes(size, new_size)?;
+        }
+
         // Put return values on the top of the operand stack, where the caller will find them.
         // This is one of only two times the operand stack is shared across call stack frames; the other is in handling
         // the Return instruction for normal calls
@@ -524,7 +637,11 @@ impl Interpreter {
     }
 
     /// Loads a resource from the data store and return the number of bytes read from the storage.
+    ///
+    /// The resource is accounted for in `memory` on its first access by the execution, even if
+    /// it was loaded from storage by an earlier execution in the same session.
     fn load_resource<'b>(
+        memory: &mut MemoryTracker,
         gas_meter: &mut impl GasMeter,
         data_store: &'b mut impl DataStore,
         addr: AccountAddress,
@@ -548,6 +665,7 @@ impl Interpreter {
                     };
                     gas_meter.charge_load_resource(opt)?;
                 }
+                memory.use_global(GlobalKey::Resource(addr, ty.clone()), gv.view())?;
                 Ok(gv)
             }
             Err(e) => {
@@ -571,7 +689,14 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
-        let res = Self::load_resource(gas_meter, data_store, addr, ty)?.borrow_global();
+        let res = Self::load_resource(
+            &mut self.operand_stack.memory,
+            gas_meter,
+            data_store,
+            addr,
+            ty,
+        )?
+        .borrow_global();
         gas_meter.charge_borrow_global(
             is_mut,
             is_generic,
@@ -592,7 +717,13 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
-        let gv = Self::load_resource(gas_meter, data_store, addr, ty)?;
+        let gv = Self::load_resource(
+            &mut self.operand_stack.memory,
+            gas_meter,
+            data_store,
+            addr,
+            ty,
+        )?;
         let exists = gv.exists()?;
         gas_meter.charge_exists(is_generic, TypeWithLoader { ty, loader }, exists)?;
         self.operand_stack.push(Value::bool(exists))?;
@@ -609,8 +740,17 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
-        let resource = match Self::load_resource(gas_meter, data_store, addr, ty)?.move_from() {
+        let resource = match Self::load_resource(
+            &mut self.operand_stack.memory,
+            gas_meter,
+            data_store,
+            addr,
+            ty,
+        )?
+        .move_from()
+        {
             Ok(resource) => {
+                self.operand_stack.memory.release_value(&resource)?;
                 gas_meter.charge_move_from(
                     is_generic,
                     TypeWithLoader { ty, loader },
@@ -639,7 +779,14 @@ impl Interpreter {
         ty: &Type,
         resource: Value,
     ) -> PartialVMResult<()> {
-        let gv = Self::load_resource(gas_meter, data_store, addr, ty)?;
+        let gv = Self::load_resource(
+            &mut self.operand_stack.memory,
+            gas_meter,
+            data_store,
+            addr,
+            ty,
+        )?;
+        self.operand_stack.memory.use_value(&resource)?;
         // NOTE(Gas): To maintain backward compatibility, we need to charge gas after attempting
         //            the move_to operation.
         match gv.move_to(resource) {
@@ -762,7 +909,7 @@ impl Interpreter {
         loader: &Loader,
     ) -> PartialVMResult<()> {
         debug_writeln!(buf, "Call Stack:")?;
-        for (i, frame) in self.call_stack.0.iter().enumerate() {
+        for (i, frame) in self.call_stack.frames.iter().enumerate() {
             self.debug_print_frame(buf, loader, i, frame)?;
         }
         debug_writeln!(buf, "Operand Stack:")?;
@@ -784,7 +931,7 @@ impl Interpreter {
     /// of an execution.
     fn internal_state_str(&self, current_frame: &Frame) -> String {
         let mut internal_state = "Call stack:\n".to_string();
-        for (i, frame) in self.call_stack.0.iter().enumerate() {
+        for (i, frame) in self.call_stack.frames.iter().enumerate() {
             internal_state.push_str(
                 format!(
                     " frame #{}: {} [pc = {}]\n",
@@ -798,7 +945,7 @@ impl Interpreter {
         internal_state.push_str(
             format!(
                 "*frame #{}: {} [pc = {}]:\n",
-                self.call_stack.0.len(),
+                self.call_stack.frames.len(),
                 current_frame.function.pretty_string(),
                 current_frame.pc,
             )
@@ -837,6 +984,16 @@ impl Interpreter {
         self.get_stack_frames(usize::MAX)
     }
 
+    /// The memory held by the values of the execution.
+    pub(crate) fn memory_mut(&mut self) -> &mut MemoryTracker {
+        &mut self.operand_stack.memory
+    }
+
+    /// The maximal length of vectors created or grown by the execution.
+    pub(crate) fn max_vector_length(&self) -> u64 {
+        self.max_vector_length
+    }
+
     /// Get count stack frames starting from the top of the stack.
     pub(crate) fn get_stack_frames(&self, count: usize) -> ExecutionState {
         // collect frames in the reverse order as this is what is
@@ -844,7 +1001,7 @@ impl Interpreter {
         // is the last one)
         let stack_trace = self
             .call_stack
-            .0
+            .frames
             .iter()
             .rev()
             .take(count)
@@ -860,29 +1017,33 @@ impl Interpreter {
     }
 }
 
-// TODO Determine stack size limits based on gas limit
-const OPERAND_STACK_SIZE_LIMIT: usize = 1024;
-const CALL_STACK_SIZE_LIMIT: usize = 1024;
-
 /// The operand stack.
 struct Stack {
     value: Vec<Value>,
     types: Vec<Type>,
+    size_limit: usize,
+    /// The memory held by the values of the execution. Values pushed onto and popped off the
+    /// stack are accounted for here, while the interpreter accounts for values moving in and out
+    /// of locals, globals and containers.
+    memory: MemoryTracker,
 }
 
 impl Stack {
-    /// Create a new empty operand stack.
-    fn new() -> Self {
+    /// Create a new empty operand stack holding at most `size_limit` values.
+    fn new(size_limit: usize, memory: MemoryTracker) -> Self {
         Stack {
             value: vec![],
             types: vec![],
+            size_limit,
+            memory,
         }
     }
 
-    /// Push a `Value` on the stack if the max stack size has not been reached. Abort execution
-    /// otherwise.
+    /// Push a `Value` on the stack if the max stack size and memory quota have not been reached.
+    /// Abort execution otherwise.
     fn push(&mut self, value: Value) -> PartialVMResult<()> {
-        if self.value.len() < OPERAND_STACK_SIZE_LIMIT {
+        if self.value.len() < self.size_limit {
+            self.memory.use_value(&value)?;
             self.value.push(value);
             Ok(())
         } else {
@@ -892,9 +1053,12 @@ impl Stack {
 
     /// Pop a `Value` off the stack or abort execution if the stack is empty.
     fn pop(&mut self) -> PartialVMResult<Value> {
-        self.value
+        let value = self
+            .value
             .pop()
-            .ok_or_else(|| PartialVMError::new(StatusCode::EMPTY_VALUE_STACK))
+            .ok_or_else(|| PartialVMError::new(StatusCode::EMPTY_VALUE_STACK))?;
+        self.memory.release_value(&value)?;
+        Ok(value)
     }
 
     /// Pop a `Value` of a given type off the stack. Abort if the value is not of the given
@@ -914,6 +1078,9 @@ impl Stack {
             .checked_sub(n as usize)
             .ok_or_else(|| PartialVMError::new(StatusCode::EMPTY_VALUE_STACK))?;
         let args = self.value.split_off(remaining_stack_size);
+        for arg in &args {
+            self.memory.release_value(arg)?;
+        }
         Ok(args)
     }
 
@@ -928,7 +1095,7 @@ impl Stack {
     /// Push a `Value` on the stack if the max stack size has not been reached. Abort execution
     /// otherwise.
     fn push_ty(&mut self, ty: Type) -> PartialVMResult<()> {
-        if self.types.len() < OPERAND_STACK_SIZE_LIMIT {
+        if self.types.len() < self.size_limit {
             self.types.push(ty);
             Ok(())
         } else {
@@ -968,18 +1135,24 @@ impl Stack {
 
 /// A call stack.
 // #[derive(Debug)]
-struct CallStack(Vec<Frame>);
+struct CallStack {
+    frames: Vec<Frame>,
+    size_limit: usize,
+}
 
 impl CallStack {
-    /// Create a new empty call stack.
-    fn new() -> Self {
-        CallStack(vec![])
+    /// Create a new empty call stack holding at most `size_limit` frames.
+    fn new(size_limit: usize) -> Self {
+        CallStack {
+            frames: vec![],
+            size_limit,
+        }
     }
 
     /// Push a `Frame` on the call stack.
     fn push(&mut self, frame: Frame) -> ::std::result::Result<(), Frame> {
-        if self.0.len() < CALL_STACK_SIZE_LIMIT {
-            self.0.push(frame);
+        if self.frames.len() < self.size_limit {
+            self.frames.push(frame);
             Ok(())
         } else {
             Err(frame)
@@ -988,11 +1161,11 @@ impl CallStack {
 
     /// Pop a `Frame` off the call stack.
     fn pop(&mut self) -> Option<Frame> {
-        self.0.pop()
+        self.frames.pop()
     }
 
     fn current_location(&self) -> Location {
-        let location_opt = self.0.last().map(|frame| frame.location());
+        let location_opt = self.frames.last().map(|frame| frame.location());
         location_opt.unwrap_or(Location::Undefined)
     }
 }
@@ -1035,8 +1208,9 @@ impl Frame {
         interpreter: &mut Interpreter,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: &mut (impl ExecutionTracer + ?Sized),
     ) -> VMResult<ExitCode> {
-        self.execute_code_impl(resolver, interpreter, data_store, gas_meter)
+        self.execute_code_impl(resolver, interpreter, data_store, gas_meter, tracer)
             .map_err(|e| {
                 let e = if cfg!(feature = "testing") || cfg!(feature = "stacktrace") {
                     e.with_exec_state(interpreter.get_internal_state())
@@ -1109,6 +1283,16 @@ impl Frame {
             | Bytecode::PackGeneric(_)
             | Bytecode::Unpack(_)
             | Bytecode::UnpackGeneric(_)
+            | Bytecode::PackVariant(_)
+            | Bytecode::PackVariantGeneric(_)
+            | Bytecode::UnpackVariant(_)
+            | Bytecode::UnpackVariantGeneric(_)
+            | Bytecode::UnpackVariantImmRef(_)
+            | Bytecode::UnpackVariantImmRefGeneric(_)
+            | Bytecode::UnpackVariantMutRef(_)
+            | Bytecode::UnpackVariantMutRefGeneric(_)
+            | Bytecode::TestVariant(_)
+            | Bytecode::TestVariantGeneric(_)
             | Bytecode::ReadRef
             | Bytecode::WriteRef
             | Bytecode::CastU8
@@ -1350,6 +1534,85 @@ impl Frame {
                     interpreter.operand_stack.push_ty(ty.clone())?;
                 }
             }
+            Bytecode::PackVariant(idx) => {
+                let field_tys = resolver.get_variant_fields(*idx)?;
+                let enum_ty = resolver.get_variant_enum_type(*idx);
+                Self::pack_variant_type_transition(resolver, interpreter, field_tys, enum_ty)?;
+            }
+            Bytecode::PackVariantGeneric(idx) => {
+                let field_tys = resolver.instantiate_generic_variant_fields(*idx, ty_args)?;
+                let enum_ty = resolver.instantiate_generic_variant_enum_type(*idx, ty_args)?;
+                Self::pack_variant_type_transition(resolver, interpreter, field_tys, enum_ty)?;
+            }
+            Bytecode::UnpackVariant(idx) => {
+                let enum_ty = interpreter.operand_stack.pop_ty()?;
+                enum_ty.check_eq(&resolver.get_variant_enum_type(*idx))?;
+                for ty in resolver.get_variant_fields(*idx)? {
+                    interpreter.operand_stack.push_ty(ty)?;
+                }
+            }
+            Bytecode::UnpackVariantGeneric(idx) => {
+                let enum_ty = interpreter.operand_stack.pop_ty()?;
+                enum_ty
+                    .check_eq(&resolver.instantiate_generic_variant_enum_type(*idx, ty_args)?)?;
+                for ty in resolver.instantiate_generic_variant_fields(*idx, ty_args)? {
+                    interpreter.operand_stack.push_ty(ty)?;
+                }
+            }
+            Bytecode::UnpackVariantImmRef(idx) => {
+                let ref_ty = interpreter.operand_stack.pop_ty()?;
+                ref_ty.check_ref_eq(&resolver.get_variant_enum_type(*idx))?;
+                for ty in resolver.get_variant_fields(*idx)? {
+                    interpreter
+                        .operand_stack
+                        .push_ty(Type::Reference(Box::new(ty)))?;
+                }
+            }
+            Bytecode::UnpackVariantImmRefGeneric(idx) => {
+                let ref_ty = interpreter.operand_stack.pop_ty()?;
+                ref_ty.check_ref_eq(
+                    &resolver.instantiate_generic_variant_enum_type(*idx, ty_args)?,
+                )?;
+                for ty in resolver.instantiate_generic_variant_fields(*idx, ty_args)? {
+                    interpreter
+                        .operand_stack
+                        .push_ty(Type::Reference(Box::new(ty)))?;
+                }
+            }
+            Bytecode::UnpackVariantMutRef(idx) => {
+                let ref_ty = interpreter.operand_stack.pop_ty()?;
+                ref_ty.check_eq(&Type::MutableReference(Box::new(
+                    resolver.get_variant_enum_type(*idx),
+                )))?;
+                for ty in resolver.get_variant_fields(*idx)? {
+                    interpreter
+                        .operand_stack
+                        .push_ty(Type::MutableReference(Box::new(ty)))?;
+                }
+            }
+            Bytecode::UnpackVariantMutRefGeneric(idx) => {
+                let ref_ty = interpreter.operand_stack.pop_ty()?;
+                ref_ty.check_eq(&Type::MutableReference(Box::new(
+                    resolver.instantiate_generic_variant_enum_type(*idx, ty_args)?,
+                )))?;
+                for ty in resolver.instantiate_generic_variant_fields(*idx, ty_args)? {
+                    interpreter
+                        .operand_stack
+                        .push_ty(Type::MutableReference(Box::new(ty)))?;
+                }
+            }
+            Bytecode::TestVariant(idx) => {
+                let ref_ty = interpreter.operand_stack.pop_ty()?;
+                ref_ty.check_ref_eq(&resolver.get_variant_enum_type(*idx))?;
+                interpreter.operand_stack.push_ty(Type::Bool)?;
+            }
+            Bytecode::TestVariantGeneric(idx) => {
+                let ref_ty = interpreter.operand_stack.pop_ty()?;
+                ref_ty.check_ref_eq(
+                    &resolver.instantiate_generic_variant_enum_type(*idx, ty_args)?,
+                )?;
+                interpreter.operand_stack.push_ty(Type::Bool)?;
+            }
             Bytecode::ReadRef => {
                 let ref_ty = interpreter.operand_stack.pop_ty()?;
                 match ref_ty {
@@ -1648,12 +1911,34 @@ impl Frame {
         Ok(())
     }
 
+    fn pack_variant_type_transition(
+        resolver: &Resolver,
+        interpreter: &mut Interpreter,
+        field_tys: Vec<Type>,
+        enum_ty: Type,
+    ) -> PartialVMResult<()> {
+        // Enums cannot have the key ability, so the fields must simply have all the abilities
+        // of the enum.
+        let ability = resolver.loader().abilities(&enum_ty)?;
+        for (ty, expected_ty) in interpreter
+            .operand_stack
+            .popn_tys(field_tys.len() as u16)?
+            .into_iter()
+            .zip(field_tys.iter())
+        {
+            check_ability(ability.is_subset(resolver.loader().abilities(&ty)?))?;
+            ty.check_eq(expected_ty)?;
+        }
+        interpreter.operand_stack.push_ty(enum_ty)
+    }
+
     fn execute_code_impl(
         &mut self,
         resolver: &Resolver,
         interpreter: &mut Interpreter,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: &mut (impl ExecutionTracer + ?Sized),
     ) -> PartialVMResult<ExitCode> {
         use SimpleInstruction as S;
 
@@ -1669,13 +1954,11 @@ impl Frame {
         let code = self.function.code();
         loop {
             for instruction in &code[self.pc as usize..] {
-                trace!(
-                    &self.function,
-                    &self.locals,
+                tracer.instruction(
+                    &self.traced(resolver.loader()),
                     self.pc,
                     instruction,
-                    resolver,
-                    interpreter
+                    &interpreter.operand_stack.value,
                 );
 
                 fail_point!("move_vm::interpreter_loop", |_| {
@@ -1791,13 +2074,19 @@ impl Frame {
                     Bytecode::MoveLoc(idx) => {
                         let local = self.locals.move_loc(*idx as usize)?;
                         gas_meter.charge_move_loc(&local)?;
+                        interpreter.operand_stack.memory.release_value(&local)?;
 
                         interpreter.operand_stack.push(local)?;
                     }
                     Bytecode::StLoc(idx) => {
                         let value_to_store = interpreter.operand_stack.pop()?;
                         gas_meter.charge_store_loc(&value_to_store)?;
-                        self.locals.store_loc(*idx as usize, value_to_store)?;
+                        let memory = &mut interpreter.operand_stack.memory;
+                        let size = memory.size_of(&value_to_store);
+                        if let Some(old) = self.locals.replace_loc(*idx as usize, value_to_store)? {
+                            memory.release_value(&old)?;
+                        }
+                        memory.use_bytes(size)?;
                     }
                     Bytecode::Call(idx) => {
                         return Ok(ExitCode::Call(*idx));
@@ -1885,6 +2174,93 @@ impl Frame {
                             interpreter.operand_stack.push(value)?;
                         }
                     }
+                    Bytecode::PackVariant(idx) => {
+                        let (tag, field_count) = resolver.variant_tag_and_count(*idx);
+                        gas_meter.charge_pack(
+                            false,
+                            interpreter.operand_stack.last_n(field_count as usize)?,
+                        )?;
+                        let args = interpreter.operand_stack.popn(field_count)?;
+                        interpreter
+                            .operand_stack
+                            .push(Value::struct_(Struct::pack_variant(tag, args)))?;
+                    }
+                    Bytecode::PackVariantGeneric(idx) => {
+                        let (tag, field_count) = resolver.variant_instantiation_tag_and_count(*idx);
+                        gas_meter.charge_pack(
+                            true,
+                            interpreter.operand_stack.last_n(field_count as usize)?,
+                        )?;
+                        let args = interpreter.operand_stack.popn(field_count)?;
+                        interpreter
+                            .operand_stack
+                            .push(Value::struct_(Struct::pack_variant(tag, args)))?;
+                    }
+                    Bytecode::UnpackVariant(idx) => {
+                        let (tag, _) = resolver.variant_tag_and_count(*idx);
+                        let enum_ = interpreter.operand_stack.pop_as::<Struct>()?;
+
+                        gas_meter.charge_unpack(false, enum_.field_views())?;
+
+                        for value in enum_.unpack_variant(tag)? {
+                            interpreter.operand_stack.push(value)?;
+                        }
+                    }
+                    Bytecode::UnpackVariantGeneric(idx) => {
+                        let (tag, _) = resolver.variant_instantiation_tag_and_count(*idx);
+                        let enum_ = interpreter.operand_stack.pop_as::<Struct>()?;
+
+                        gas_meter.charge_unpack(true, enum_.field_views())?;
+
+                        for value in enum_.unpack_variant(tag)? {
+                            interpreter.operand_stack.push(value)?;
+                        }
+                    }
+                    Bytecode::UnpackVariantImmRef(idx) | Bytecode::UnpackVariantMutRef(idx) => {
+                        let instr = match instruction {
+                            Bytecode::UnpackVariantMutRef(_) => S::UnpackVariantMutRef,
+                            _ => S::UnpackVariantImmRef,
+                        };
+                        gas_meter.charge_simple_instr(instr)?;
+
+                        let (tag, field_count) = resolver.variant_tag_and_count(*idx);
+                        let reference = interpreter.operand_stack.pop_as::<StructRef>()?;
+                        for field_ref in reference.unpack_variant_ref(tag, field_count as usize)? {
+                            interpreter.operand_stack.push(field_ref)?;
+                        }
+                    }
+                    Bytecode::UnpackVariantImmRefGeneric(idx)
+                    | Bytecode::UnpackVariantMutRefGeneric(idx) => {
+                        let instr = match instruction {
+                            Bytecode::UnpackVariantMutRefGeneric(_) => {
+                                S::UnpackVariantMutRefGeneric
+                            }
+                            _ => S::UnpackVariantImmRefGeneric,
+                        };
+                        gas_meter.charge_simple_instr(instr)?;
+
+                        let (tag, field_count) = resolver.variant_instantiation_tag_and_count(*idx);
+                        let reference = interpreter.operand_stack.pop_as::<StructRef>()?;
+                        for field_ref in reference.unpack_variant_ref(tag, field_count as usize)? {
+                            interpreter.operand_stack.push(field_ref)?;
+                        }
+                    }
+                    Bytecode::TestVariant(idx) => {
+                        gas_meter.charge_simple_instr(S::TestVariant)?;
+                        let (tag, _) = resolver.variant_tag_and_count(*idx);
+                        let reference = interpreter.operand_stack.pop_as::<StructRef>()?;
+                        interpreter
+                            .operand_stack
+                            .push(reference.test_variant(tag)?)?;
+                    }
+                    Bytecode::TestVariantGeneric(idx) => {
+                        gas_meter.charge_simple_instr(S::TestVariantGeneric)?;
+                        let (tag, _) = resolver.variant_instantiation_tag_and_count(*idx);
+                        let reference = interpreter.operand_stack.pop_as::<StructRef>()?;
+                        interpreter
+                            .operand_stack
+                            .push(reference.test_variant(tag)?)?;
+                    }
                     Bytecode::ReadRef => {
                         let reference = interpreter.operand_stack.pop_as::<Reference>()?;
                         gas_meter.charge_read_ref(reference.value_view())?;
@@ -1895,6 +2271,10 @@ impl Frame {
                         let reference = interpreter.operand_stack.pop_as::<Reference>()?;
                         let value = interpreter.operand_stack.pop()?;
                         gas_meter.charge_write_ref(&value, reference.value_view())?;
+                        interpreter
+                            .operand_stack
+                            .memory
+                            .replace_value(reference.value_view(), &value)?;
                         reference.write_ref(value)?;
                     }
                     Bytecode::CastU8 => {
@@ -2015,6 +2395,7 @@ impl Frame {
                     Bytecode::Abort => {
                         gas_meter.charge_simple_instr(S::Abort)?;
                         let error_code = interpreter.operand_stack.pop_as::<u64>()?;
+                        tracer.abort(&self.traced(resolver.loader()), error_code);
                         let error = PartialVMError::new(StatusCode::ABORTED)
                             .with_sub_status(error_code)
                             .with_message(format!(
@@ -2044,6 +2425,12 @@ impl Frame {
                         let is_mut = matches!(instruction, Bytecode::MutBorrowGlobal(_));
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.get_struct_type(*sd_idx);
+                        let access = if is_mut {
+                            GlobalAccess::MutBorrow
+                        } else {
+                            GlobalAccess::ImmBorrow
+                        };
+                        tracer.global_access(access, addr, &ty);
                         interpreter.borrow_global(
                             is_mut,
                             false,
@@ -2059,6 +2446,12 @@ impl Frame {
                         let is_mut = matches!(instruction, Bytecode::MutBorrowGlobalGeneric(_));
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.instantiate_generic_type(*si_idx, self.ty_args())?;
+                        let access = if is_mut {
+                            GlobalAccess::MutBorrow
+                        } else {
+                            GlobalAccess::ImmBorrow
+                        };
+                        tracer.global_access(access, addr, &ty);
                         interpreter.borrow_global(
                             is_mut,
                             true,
@@ -2072,6 +2465,7 @@ impl Frame {
                     Bytecode::Exists(sd_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.get_struct_type(*sd_idx);
+                        tracer.global_access(GlobalAccess::Exists, addr, &ty);
                         interpreter.exists(
                             false,
                             resolver.loader(),
@@ -2084,6 +2478,7 @@ impl Frame {
                     Bytecode::ExistsGeneric(si_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.instantiate_generic_type(*si_idx, self.ty_args())?;
+                        tracer.global_access(GlobalAccess::Exists, addr, &ty);
                         interpreter.exists(
                             true,
                             resolver.loader(),
@@ -2096,6 +2491,7 @@ impl Frame {
                     Bytecode::MoveFrom(sd_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.get_struct_type(*sd_idx);
+                        tracer.global_access(GlobalAccess::MoveFrom, addr, &ty);
                         interpreter.move_from(
                             false,
                             resolver.loader(),
@@ -2108,6 +2504,7 @@ impl Frame {
                     Bytecode::MoveFromGeneric(si_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.instantiate_generic_type(*si_idx, self.ty_args())?;
+                        tracer.global_access(GlobalAccess::MoveFrom, addr, &ty);
                         interpreter.move_from(
                             true,
                             resolver.loader(),
@@ -2127,6 +2524,7 @@ impl Frame {
                             .value_as::<AccountAddress>()?;
                         let ty = resolver.get_struct_type(*sd_idx);
                         // REVIEW: Can we simplify Interpreter::move_to?
+                        tracer.global_access(GlobalAccess::MoveTo, addr, &ty);
                         interpreter.move_to(
                             false,
                             resolver.loader(),
@@ -2146,6 +2544,7 @@ impl Frame {
                             .read_ref()?
                             .value_as::<AccountAddress>()?;
                         let ty = resolver.instantiate_generic_type(*si_idx, self.ty_args())?;
+                        tracer.global_access(GlobalAccess::MoveTo, addr, &ty);
                         interpreter.move_to(
                             true,
                             resolver.loader(),
@@ -2171,6 +2570,11 @@ impl Frame {
                     }
                     Bytecode::VecPack(si, num) => {
                         let ty = resolver.instantiate_single_type(*si, self.ty_args())?;
+                        if *num > interpreter.max_vector_length {
+                            return Err(PartialVMError::new(
+                                StatusCode::VM_MAX_VECTOR_LENGTH_REACHED,
+                            ));
+                        }
                         gas_meter.charge_vec_pack(
                             make_ty!(&ty),
                             interpreter.operand_stack.last_n(*num as usize)?,
@@ -2210,6 +2614,15 @@ impl Frame {
                         let vec_ref = interpreter.operand_stack.pop_as::<VectorRef>()?;
                         let ty = &resolver.instantiate_single_type(*si, self.ty_args())?;
                         gas_meter.charge_vec_push_back(make_ty!(ty), &elem)?;
+                        if vec_ref.len(ty)?.value_as::<u64>()? >= interpreter.max_vector_length {
+                            return Err(PartialVMError::new(
+                                StatusCode::VM_MAX_VECTOR_LENGTH_REACHED,
+                            ));
+                        }
+                        interpreter
+                            .operand_stack
+                            .memory
+                            .use_vector_elem(ty, &elem)?;
                         vec_ref.push_back(elem, ty)?;
                     }
                     Bytecode::VecPopBack(si) => {
@@ -2217,7 +2630,12 @@ impl Frame {
                         let ty = &resolver.instantiate_single_type(*si, self.ty_args())?;
                         let res = vec_ref.pop(ty);
                         gas_meter.charge_vec_pop_back(make_ty!(ty), res.as_ref().ok())?;
-                        interpreter.operand_stack.push(res?)?;
+                        let elem = res?;
+                        interpreter
+                            .operand_stack
+                            .memory
+                            .release_vector_elem(ty, &elem)?;
+                        interpreter.operand_stack.push(elem)?;
                     }
                     Bytecode::VecUnpack(si, num) => {
                         let vec_val = interpreter.operand_stack.pop_as::<Vector>()?;
@@ -2253,6 +2671,10 @@ impl Frame {
                     interpreter.operand_stack.check_balance()?;
                 }
 
+                if let Some(peak) = interpreter.operand_stack.memory.take_new_peak() {
+                    gas_meter.charge_peak_memory_usage(peak)?;
+                }
+
                 // invariant: advance to pc +1 is iff instruction at pc executed without aborting
                 self.pc += 1;
             }
@@ -2272,6 +2694,10 @@ impl Frame {
         }
     }
 
+    fn traced<'a>(&'a self, loader: &'a Loader) -> TracedFunction<'a> {
+        TracedFunction::new(&self.function, &self.ty_args, loader).with_locals(&self.locals)
+    }
+
     fn ty_args(&self) -> &[Type] {
         &self.ty_args
     }
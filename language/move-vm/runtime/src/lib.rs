@@ -11,21 +11,17 @@
 //! soon.
 
 pub mod data_cache;
+pub mod execution_tracer;
 mod interpreter;
 mod loader;
 pub mod logging;
+pub mod module_cache;
 pub mod move_vm;
 pub mod native_extensions;
 pub mod native_functions;
 mod runtime;
 pub mod session;
-#[macro_use]
-mod tracing;
 pub mod config;
 
-// Only include debugging functionality in debug builds
-#[cfg(any(debug_assertions, feature = "debugging"))]
-mod debug;
-
 #[cfg(test)]
 mod unit_tests;
@@ -5,6 +5,7 @@
 use crate::{
     config::VMConfig,
     logging::expect_no_verification_errors,
+    module_cache::SharedModuleCache,
     native_functions::{NativeFunction, NativeFunctions, UnboxedNativeFunction},
     session::LoadedFunctionInstantiation,
 };
@@ -14,10 +15,12 @@ use move_binary_format::{
     errors::{verification_error, Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
         AbilitySet, Bytecode, CompiledModule, CompiledScript, Constant, ConstantPoolIndex,
-        FieldHandleIndex, FieldInstantiationIndex, FunctionDefinition, FunctionDefinitionIndex,
-        FunctionHandleIndex, FunctionInstantiationIndex, Signature, SignatureIndex, SignatureToken,
+        EnumDefinition, EnumDefinitionIndex, FieldHandleIndex, FieldInstantiationIndex,
+        FunctionDefinition, FunctionDefinitionIndex, FunctionHandleIndex,
+        FunctionInstantiationIndex, Signature, SignatureIndex, SignatureToken,
         StructDefInstantiationIndex, StructDefinition, StructDefinitionIndex,
-        StructFieldInformation, TableIndex, Visibility,
+        StructFieldInformation, TableIndex, VariantHandleIndex, VariantInstantiationHandleIndex,
+        VariantTag, Visibility,
     },
     IndexKind,
 };
@@ -26,12 +29,12 @@ use move_core_types::{
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, StructTag, TypeTag},
     metadata::Metadata,
-    value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout},
+    value::{MoveEnumLayout, MoveFieldLayout, MoveStructLayout, MoveTypeLayout},
     vm_status::StatusCode,
 };
 use move_vm_types::{
     data_store::DataStore,
-    loaded_data::runtime_types::{CachedStructIndex, StructType, Type},
+    loaded_data::runtime_types::{CachedStructIndex, StructType, Type, VariantType},
 };
 use parking_lot::RwLock;
 use sha3::{Digest, Sha3_256};
@@ -76,6 +79,18 @@ where
     fn get(&self, key: &K) -> Option<&Arc<V>> {
         self.id_map.get(key).and_then(|idx| self.binaries.get(*idx))
     }
+
+    // Binaries are only unmapped, their slot in the vector is not reused.
+    fn remove(&mut self, key: &K) -> Option<Arc<V>> {
+        self.id_map
+            .remove(key)
+            .map(|idx| Arc::clone(&self.binaries[idx]))
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&V) -> bool) {
+        let binaries = &self.binaries;
+        self.id_map.retain(|_, idx| keep(&binaries[*idx]));
+    }
 }
 
 // A script cache is a map from the hash value of a script and the `Script` itself.
@@ -102,6 +117,17 @@ impl ScriptCache {
         })
     }
 
+    // Remove the scripts which depend on any of the given modules.
+    fn remove_dependents(&mut self, ids: &BTreeSet<ModuleId>) {
+        self.scripts.retain(|script| {
+            !script
+                .script
+                .immediate_dependencies()
+                .iter()
+                .any(|dep| ids.contains(dep))
+        })
+    }
+
     fn insert(
         &mut self,
         hash: ScriptHash,
@@ -121,6 +147,10 @@ impl ScriptCache {
     }
 }
 
+/// The number of slots of removed modules, types and functions from which the module cache is
+/// flushed entirely if they make up most of it.
+pub(crate) const MIN_DEAD_SLOTS_TO_FLUSH: usize = 256;
+
 // A ModuleCache is the core structure in the Loader.
 // It holds all Modules, Types and Functions loaded.
 // Types and Functions are pushed globally to the ModuleCache.
@@ -129,6 +159,9 @@ pub struct ModuleCache {
     modules: BinaryCache<ModuleId, Module>,
     structs: Vec<Arc<StructType>>,
     functions: Vec<Arc<Function>>,
+    // The number of modules, types and functions which were removed but whose slots are still
+    // in the vectors above.
+    dead_slots: usize,
 }
 
 impl ModuleCache {
@@ -137,9 +170,18 @@ impl ModuleCache {
             modules: BinaryCache::new(),
             structs: vec![],
             functions: vec![],
+            dead_slots: 0,
         }
     }
 
+    // Whether most of the modules, types and functions in the cache were removed, and at least
+    // `MIN_DEAD_SLOTS_TO_FLUSH` of them, in which case the cache should be flushed entirely to
+    // reclaim their slots.
+    fn is_mostly_dead(&self) -> bool {
+        let slots = self.modules.binaries.len() + self.structs.len() + self.functions.len();
+        self.dead_slots >= MIN_DEAD_SLOTS_TO_FLUSH && self.dead_slots * 2 > slots
+    }
+
     //
     // Common "get" operations
     //
@@ -150,6 +192,40 @@ impl ModuleCache {
         self.modules.get(id).map(Arc::clone)
     }
 
+    // Remove the given modules and the cached modules depending on them, directly or
+    // transitively, and return the removed modules. Their types and functions stay in the global
+    // vectors, as everything loaded refers to those by index, but can no longer be resolved.
+    fn remove_modules_and_dependents(&mut self, ids: &BTreeSet<ModuleId>) -> Vec<Arc<Module>> {
+        let mut removed = BTreeSet::new();
+        let mut worklist: Vec<_> = ids.iter().cloned().collect();
+        while let Some(id) = worklist.pop() {
+            if !removed.insert(id.clone()) {
+                continue;
+            }
+            worklist.extend(
+                self.modules
+                    .id_map
+                    .iter()
+                    .filter(|(_, idx)| {
+                        self.modules.binaries[**idx]
+                            .module
+                            .immediate_dependencies()
+                            .contains(&id)
+                    })
+                    .map(|(dependent, _)| dependent.clone()),
+            );
+        }
+        let removed: Vec<_> = removed
+            .iter()
+            .filter_map(|id| self.modules.remove(id))
+            .collect();
+        self.dead_slots += removed
+            .iter()
+            .map(|module| 1 + module.struct_map.len() + module.function_map.len())
+            .sum::<usize>();
+        removed
+    }
+
     // Retrieve a function by index
     fn function_at(&self, idx: usize) -> Arc<Function> {
         Arc::clone(&self.functions[idx])
@@ -160,6 +236,19 @@ impl ModuleCache {
         Arc::clone(&self.structs[idx.0])
     }
 
+    // Retrieve the number of fields of a variant of an enum
+    fn variant_field_count(&self, idx: CachedStructIndex, tag: VariantTag) -> PartialVMResult<u16> {
+        self.structs[idx.0]
+            .variants
+            .as_ref()
+            .and_then(|variants| variants.get(tag as usize))
+            .map(|variant| variant.fields.len() as u16)
+            .ok_or_else(|| {
+                PartialVMError::new(StatusCode::TYPE_RESOLUTION_FAILURE)
+                    .with_message(format!("Cannot find variant {} of {:?}", tag, idx))
+            })
+    }
+
     //
     // Insertion is under lock and it's a pretty heavy operation.
     // The VM is pretty much stopped waiting for this to finish
@@ -169,7 +258,7 @@ impl ModuleCache {
         &mut self,
         natives: &NativeFunctions,
         id: ModuleId,
-        module: CompiledModule,
+        module: Arc<CompiledModule>,
     ) -> VMResult<Arc<Module>> {
         if let Some(cached) = self.module_at(&id) {
             return Ok(cached);
@@ -181,8 +270,8 @@ impl ModuleCache {
         match Module::new(module, self) {
             Ok(module) => Ok(Arc::clone(self.modules.insert(id, module))),
             Err((err, module)) => {
-                // remove all structs and functions that have been pushed
-                let strut_def_count = module.struct_defs().len();
+                // remove all structs, enums and functions that have been pushed
+                let strut_def_count = module.struct_defs().len() + module.enum_defs().len();
                 self.structs.truncate(self.structs.len() - strut_def_count);
                 let function_count = module.function_defs().len();
                 self.functions
@@ -198,6 +287,11 @@ impl ModuleCache {
             let st = self.make_struct_type(module, struct_def, StructDefinitionIndex(idx as u16));
             self.structs.push(Arc::new(st));
         }
+        // enums are cached right after the structs of the module
+        for (idx, enum_def) in module.enum_defs().iter().enumerate() {
+            let et = self.make_enum_type(module, enum_def, EnumDefinitionIndex(idx as u16));
+            self.structs.push(Arc::new(et));
+        }
         self.load_field_types(module, starting_idx).map_err(|err| {
             // clean up the structs that were cached
             self.structs.truncate(starting_idx);
@@ -253,6 +347,7 @@ impl ModuleCache {
         StructType {
             fields: vec![],
             field_names,
+            variants: None,
             abilities,
             type_parameters,
             name,
@@ -261,6 +356,42 @@ impl ModuleCache {
         }
     }
 
+    fn make_enum_type(
+        &self,
+        module: &CompiledModule,
+        enum_def: &EnumDefinition,
+        idx: EnumDefinitionIndex,
+    ) -> StructType {
+        let enum_handle = module.struct_handle_at(enum_def.enum_handle);
+        let variants = enum_def
+            .variants
+            .iter()
+            .map(|variant| VariantType {
+                name: module.identifier_at(variant.name).to_owned(),
+                fields: vec![],
+                field_names: variant
+                    .fields
+                    .iter()
+                    .map(|f| module.identifier_at(f.name).to_owned())
+                    .collect(),
+            })
+            .collect();
+        let abilities = enum_handle.abilities;
+        let name = module.identifier_at(enum_handle.name).to_owned();
+        let type_parameters = enum_handle.type_parameters.clone();
+        let module = module.self_id();
+        StructType {
+            fields: vec![],
+            field_names: vec![],
+            variants: Some(variants),
+            abilities,
+            type_parameters,
+            name,
+            module,
+            struct_def: StructDefinitionIndex(idx.0),
+        }
+    }
+
     fn load_field_types(
         &mut self,
         module: &CompiledModule,
@@ -282,6 +413,18 @@ impl ModuleCache {
 
             field_types.push(field_tys);
         }
+        let mut variant_types = vec![];
+        for enum_def in module.enum_defs() {
+            let mut variant_tys = vec![];
+            for variant in &enum_def.variants {
+                let mut field_tys = vec![];
+                for field in &variant.fields {
+                    field_tys.push(self.make_type_while_loading(module, &field.signature.0)?);
+                }
+                variant_tys.push(field_tys);
+            }
+            variant_types.push(variant_tys);
+        }
         let mut struct_idx = starting_idx;
         for fields in field_types {
             match Arc::get_mut(&mut self.structs[struct_idx]) {
@@ -300,6 +443,17 @@ impl ModuleCache {
             }
             struct_idx += 1;
         }
+        for variant_tys in variant_types {
+            // as for structs, no live reference to the `Arc` is expected and `make_mut` will not
+            // have to clone
+            let enum_type = Arc::make_mut(&mut self.structs[struct_idx]);
+            if let Some(variants) = &mut enum_type.variants {
+                for (variant, fields) in variants.iter_mut().zip(variant_tys) {
+                    variant.fields = fields;
+                }
+            }
+            struct_idx += 1;
+        }
         Ok(())
     }
 
@@ -484,8 +638,9 @@ pub(crate) struct Loader {
     //    never committed to storage by the adapter.
     //
     // The solution is to add a flag to Loader marking it as 'invalidated'. For scenario (1),
-    // the VM sets the flag itself. For scenario (2), a public API allows the adapter to set
-    // the flag.
+    // the VM records the upgraded module in `invalidated_modules` instead, so that only that
+    // module and the modules depending on it are flushed. For scenario (2), a public API allows
+    // the adapter to set the flag.
     //
     // If the cache is invalidated, it can (and must) still be used until there are no more
     // sessions alive which are derived from a VM with this loader. This is because there are
@@ -501,24 +656,36 @@ pub(crate) struct Loader {
     //   is a major execution bottleneck. We should be able to reuse a cache for the lifetime of
     //   the adapter/node, not just a VM or even session (as effectively today).
     invalidated: RwLock<bool>,
+    invalidated_modules: RwLock<BTreeSet<ModuleId>>,
 
     // Collects the cache hits on module loads. This information can be read and reset by
     // an adapter to reason about read/write conflicts of code publishing transactions and
     // other transactions.
     module_cache_hits: RwLock<BTreeSet<ModuleId>>,
 
+    // Deserialized and verified modules, possibly shared with other loaders. Unlike the code
+    // cache above, it holds no data derived from other modules, so entries can be invalidated
+    // individually.
+    shared_module_cache: Option<Arc<SharedModuleCache>>,
+
     vm_config: VMConfig,
 }
 
 impl Loader {
-    pub(crate) fn new(natives: NativeFunctions, vm_config: VMConfig) -> Self {
+    pub(crate) fn new(
+        natives: NativeFunctions,
+        vm_config: VMConfig,
+        shared_module_cache: Option<Arc<SharedModuleCache>>,
+    ) -> Self {
         Self {
             scripts: RwLock::new(ScriptCache::new()),
             module_cache: RwLock::new(ModuleCache::new()),
             type_cache: RwLock::new(TypeCache::new()),
             natives,
             invalidated: RwLock::new(false),
+            invalidated_modules: RwLock::new(BTreeSet::new()),
             module_cache_hits: RwLock::new(BTreeSet::new()),
+            shared_module_cache,
             vm_config,
         }
     }
@@ -543,22 +710,40 @@ impl Loader {
         if !visited.insert(id.clone()) {
             return;
         }
-        let deps = self
-            .module_cache
-            .read()
-            .modules
-            .get(id)
-            .unwrap()
-            .module
-            .immediate_dependencies();
+        // a module flushed from the cache since it was hit is still reported, without its
+        // dependencies
+        let deps = match self.module_cache.read().modules.get(id) {
+            Some(module) => module.module.immediate_dependencies(),
+            None => return,
+        };
         for dep in deps {
             self.transitive_dep_closure(&dep, visited)
         }
     }
 
-    /// Flush this cache if it is marked as invalidated.
+    /// Flush this cache if it is marked as invalidated. If only some modules were invalidated,
+    /// only those, the modules and scripts depending on them, and their types are flushed, unless
+    /// most of the cache is then taken by the slots of flushed modules, which are never reused.
     pub(crate) fn flush_if_invalidated(&self) {
         let mut invalidated = self.invalidated.write();
+        let invalidated_modules = std::mem::take(&mut *self.invalidated_modules.write());
+        if !*invalidated && !invalidated_modules.is_empty() {
+            let removed = {
+                let mut module_cache = self.module_cache.write();
+                let removed = module_cache.remove_modules_and_dependents(&invalidated_modules);
+                *invalidated = module_cache.is_mostly_dead();
+                removed
+            };
+            if !*invalidated {
+                let removed_ids = removed.iter().map(|module| module.id.clone()).collect();
+                self.scripts.write().remove_dependents(&removed_ids);
+                let removed_structs = removed
+                    .iter()
+                    .flat_map(|module| module.struct_map.values().copied())
+                    .collect();
+                self.type_cache.write().remove_structs(&removed_structs);
+            }
+        }
         if *invalidated {
             *self.scripts.write() = ScriptCache::new();
             *self.module_cache.write() = ModuleCache::new();
@@ -572,9 +757,26 @@ impl Loader {
         *self.invalidated.write() = true;
     }
 
+    /// Mark a republished module as invalidated. It is dropped from the shared module cache, if
+    /// any, right away, along with its dependents, and from this cache on the next flush.
+    pub(crate) fn invalidate_module(&self, id: &ModuleId) {
+        self.invalidated_modules.write().insert(id.clone());
+        if let Some(cache) = &self.shared_module_cache {
+            cache.invalidate(id);
+        }
+    }
+
     /// Check whether this cache is invalidated.
     pub(crate) fn is_invalidated(&self) -> bool {
-        *self.invalidated.read()
+        *self.invalidated.read() || !self.invalidated_modules.read().is_empty()
+    }
+
+    /// The number of modules, types and functions held by this cache, including the ones of
+    /// flushed modules whose slots are not reused.
+    #[cfg(test)]
+    pub(crate) fn cache_slots(&self) -> usize {
+        let cache = self.module_cache.read();
+        cache.modules.binaries.len() + cache.structs.len() + cache.functions.len()
     }
 
     /// Copies metadata out of a modules bytecode if available.
@@ -1023,7 +1225,7 @@ impl Loader {
         id: &ModuleId,
         data_store: &impl DataStore,
         allow_loading_failure: bool,
-    ) -> VMResult<CompiledModule> {
+    ) -> VMResult<Arc<CompiledModule>> {
         // bytes fetching, allow loading to fail if the flag is set
         let bytes = match data_store.load_module(id) {
             Ok(bytes) => bytes,
@@ -1034,6 +1236,17 @@ impl Loader {
             }
         };
 
+        // modules in the shared cache have already been deserialized and verified
+        if let Some(cached) = self
+            .shared_module_cache
+            .as_ref()
+            .and_then(|cache| cache.get(id, &bytes))
+        {
+            self.check_natives(&cached)
+                .map_err(expect_no_verification_errors)?;
+            return Ok(cached);
+        }
+
         // for bytes obtained from the data store, they should always deserialize and verify.
         // It is an invariant violation if they don't.
         let module = CompiledModule::deserialize_with_max_version(
@@ -1048,7 +1261,7 @@ impl Loader {
         })
         .map_err(expect_no_verification_errors)?;
 
-        fail::fail_point!("verifier-failpoint-2", |_| { Ok(module.clone()) });
+        fail::fail_point!("verifier-failpoint-2", |_| { Ok(Arc::new(module.clone())) });
 
         if self.vm_config.paranoid_type_checks && &module.self_id() != id {
             return Err(
@@ -1061,6 +1274,10 @@ impl Loader {
         // bytecode verifier checks that can be performed with the module itself
         move_bytecode_verifier::verify_module_with_config(&self.vm_config.verifier, &module)
             .map_err(expect_no_verification_errors)?;
+        let module = Arc::new(module);
+        if let Some(cache) = &self.shared_module_cache {
+            cache.insert(&bytes, Arc::clone(&module));
+        }
         self.check_natives(&module)
             .map_err(expect_no_verification_errors)?;
         Ok(module)
@@ -1276,13 +1493,13 @@ impl Loader {
     fn subst(&self, ty: &Type, ty_args: &[Type]) -> PartialVMResult<Type> {
         // Before instantiating the type, count the # of nodes of all type arguments plus
         // existing type instantiation.
-        // If that number is larger than MAX_TYPE_INSTANTIATION_NODES, refuse to construct this type.
+        // If that number is larger than the configured maximum, refuse to construct this type.
         // This prevents constructing larger and lager types via struct instantiation.
         if let Type::StructInstantiation(_, struct_inst) = ty {
             let mut sum_nodes: usize = 1;
             for ty in ty_args.iter().chain(struct_inst.iter()) {
                 sum_nodes = sum_nodes.saturating_add(self.count_type_nodes(ty));
-                if sum_nodes > MAX_TYPE_INSTANTIATION_NODES {
+                if sum_nodes > self.vm_config.max_type_instantiation_nodes {
                     return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
                 }
             }
@@ -1466,11 +1683,11 @@ impl<'a> Resolver<'a> {
             instantiation.push(self.subst(ty, type_params)?);
         }
         // Check if the function instantiation over all generics is larger
-        // than MAX_TYPE_INSTANTIATION_NODES.
+        // than the configured maximum.
         let mut sum_nodes: usize = 1;
         for ty in type_params.iter().chain(instantiation.iter()) {
             sum_nodes = sum_nodes.saturating_add(self.loader.count_type_nodes(ty));
-            if sum_nodes > MAX_TYPE_INSTANTIATION_NODES {
+            if sum_nodes > self.loader.vm_config.max_type_instantiation_nodes {
                 return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
             }
         }
@@ -1510,12 +1727,12 @@ impl<'a> Resolver<'a> {
 
         // Before instantiating the type, count the # of nodes of all type arguments plus
         // existing type instantiation.
-        // If that number is larger than MAX_TYPE_INSTANTIATION_NODES, refuse to construct this type.
+        // If that number is larger than the configured maximum, refuse to construct this type.
         // This prevents constructing larger and lager types via struct instantiation.
         let mut sum_nodes: usize = 1;
         for ty in ty_args.iter().chain(struct_inst.instantiation.iter()) {
             sum_nodes = sum_nodes.saturating_add(self.loader.count_type_nodes(ty));
-            if sum_nodes > MAX_TYPE_INSTANTIATION_NODES {
+            if sum_nodes > self.loader.vm_config.max_type_instantiation_nodes {
                 return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
             }
         }
@@ -1695,6 +1912,114 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    //
+    // Variants resolution
+    //
+
+    pub(crate) fn variant_tag_and_count(&self, idx: VariantHandleIndex) -> (VariantTag, u16) {
+        match &self.binary {
+            BinaryType::Module(module) => {
+                let handle = module.variant_handle_at(idx);
+                (handle.tag, handle.field_count)
+            }
+            BinaryType::Script(_) => unreachable!("Scripts cannot have variant instructions"),
+        }
+    }
+
+    pub(crate) fn variant_instantiation_tag_and_count(
+        &self,
+        idx: VariantInstantiationHandleIndex,
+    ) -> (VariantTag, u16) {
+        match &self.binary {
+            BinaryType::Module(module) => {
+                let inst = module.variant_instantiation_at(idx);
+                (inst.tag, inst.field_count)
+            }
+            BinaryType::Script(_) => unreachable!("Scripts cannot have variant instructions"),
+        }
+    }
+
+    pub(crate) fn get_variant_enum_type(&self, idx: VariantHandleIndex) -> Type {
+        match &self.binary {
+            BinaryType::Module(module) => Type::Struct(module.variant_handle_at(idx).enum_idx),
+            BinaryType::Script(_) => unreachable!("Scripts cannot have variant instructions"),
+        }
+    }
+
+    pub(crate) fn instantiate_generic_variant_enum_type(
+        &self,
+        idx: VariantInstantiationHandleIndex,
+        ty_args: &[Type],
+    ) -> PartialVMResult<Type> {
+        let variant_inst = match &self.binary {
+            BinaryType::Module(module) => module.variant_instantiation_at(idx),
+            BinaryType::Script(_) => unreachable!("Scripts cannot have variant instructions"),
+        };
+
+        // Same as for structs, refuse to construct types which are too large.
+        let mut sum_nodes: usize = 1;
+        for ty in ty_args.iter().chain(variant_inst.instantiation.iter()) {
+            sum_nodes = sum_nodes.saturating_add(self.loader.count_type_nodes(ty));
+            if sum_nodes > self.loader.vm_config.max_type_instantiation_nodes {
+                return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
+            }
+        }
+
+        Ok(Type::StructInstantiation(
+            variant_inst.enum_idx,
+            variant_inst
+                .instantiation
+                .iter()
+                .map(|ty| self.subst(ty, ty_args))
+                .collect::<PartialVMResult<_>>()?,
+        ))
+    }
+
+    fn variant_type(
+        &self,
+        enum_idx: CachedStructIndex,
+        tag: VariantTag,
+    ) -> PartialVMResult<VariantType> {
+        self.loader
+            .get_struct_type(enum_idx)
+            .and_then(|enum_type| enum_type.variants.as_ref()?.get(tag as usize).cloned())
+            .ok_or_else(|| {
+                PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                    .with_message("Enum Definition not resolved".to_string())
+            })
+    }
+
+    pub(crate) fn get_variant_fields(&self, idx: VariantHandleIndex) -> PartialVMResult<Vec<Type>> {
+        let handle = match &self.binary {
+            BinaryType::Module(module) => module.variant_handle_at(idx),
+            BinaryType::Script(_) => unreachable!("Scripts cannot have variant instructions"),
+        };
+        Ok(self.variant_type(handle.enum_idx, handle.tag)?.fields)
+    }
+
+    pub(crate) fn instantiate_generic_variant_fields(
+        &self,
+        idx: VariantInstantiationHandleIndex,
+        ty_args: &[Type],
+    ) -> PartialVMResult<Vec<Type>> {
+        let variant_inst = match &self.binary {
+            BinaryType::Module(module) => module.variant_instantiation_at(idx),
+            BinaryType::Script(_) => unreachable!("Scripts cannot have variant instructions"),
+        };
+        let variant_type = self.variant_type(variant_inst.enum_idx, variant_inst.tag)?;
+
+        let instantiation_types = variant_inst
+            .instantiation
+            .iter()
+            .map(|inst_ty| inst_ty.subst(ty_args))
+            .collect::<PartialVMResult<Vec<_>>>()?;
+        variant_type
+            .fields
+            .iter()
+            .map(|ty| ty.subst(&instantiation_types))
+            .collect::<PartialVMResult<Vec<_>>>()
+    }
+
     pub(crate) fn type_to_type_layout(&self, ty: &Type) -> PartialVMResult<MoveTypeLayout> {
         self.loader.type_to_type_layout(ty)
     }
@@ -1718,7 +2043,6 @@ impl<'a> Resolver<'a> {
 // so that any data needed for execution is immediately available
 #[derive(Debug)]
 pub(crate) struct Module {
-    #[allow(dead_code)]
     id: ModuleId,
     // primitive pools
     module: Arc<CompiledModule>,
@@ -1751,10 +2075,15 @@ pub(crate) struct Module {
     // materialized instantiations, whether partial or not
     field_instantiations: Vec<FieldInstantiation>,
 
+    // variants as a pair of index, first to the enum type, second to the variant tag
+    variant_handles: Vec<VariantHandle>,
+    // materialized instantiations, whether partial or not
+    variant_instantiations: Vec<VariantInstantiation>,
+
     // function name to index into the Loader function list.
     // This allows a direct access from function name to `Function`
     function_map: HashMap<Identifier, usize>,
-    // struct name to index into the Loader type list
+    // struct and enum name to index into the Loader type list
     // This allows a direct access from struct name to `Struct`
     struct_map: HashMap<Identifier, CachedStructIndex>,
 
@@ -1767,9 +2096,9 @@ pub(crate) struct Module {
 
 impl Module {
     fn new(
-        module: CompiledModule,
+        module: Arc<CompiledModule>,
         cache: &ModuleCache,
-    ) -> Result<Self, (PartialVMError, CompiledModule)> {
+    ) -> Result<Self, (PartialVMError, Arc<CompiledModule>)> {
         let id = module.self_id();
 
         let mut struct_refs = vec![];
@@ -1779,6 +2108,8 @@ impl Module {
         let mut function_instantiations = vec![];
         let mut field_handles = vec![];
         let mut field_instantiations: Vec<FieldInstantiation> = vec![];
+        let mut variant_handles = vec![];
+        let mut variant_instantiations = vec![];
         let mut function_map = HashMap::new();
         let mut struct_map = HashMap::new();
         let mut single_signature_token_map = BTreeMap::new();
@@ -1821,6 +2152,47 @@ impl Module {
                 struct_map.insert(name.to_owned(), idx);
             }
 
+            let mut enums = vec![];
+            for enum_def in module.enum_defs() {
+                let idx = struct_refs[enum_def.enum_handle.0 as usize];
+                enums.push(idx);
+                let name = module.identifier_at(module.struct_handle_at(enum_def.enum_handle).name);
+                struct_map.insert(name.to_owned(), idx);
+            }
+
+            let mut enum_instantiations = vec![];
+            for enum_inst in module.enum_instantiations() {
+                let mut instantiation = vec![];
+                for ty in &module.signature_at(enum_inst.type_parameters).0 {
+                    instantiation.push(cache.make_type_while_loading(&module, ty)?);
+                }
+                enum_instantiations.push((enums[enum_inst.def.0 as usize], instantiation));
+            }
+
+            for variant_handle in module.variant_handles() {
+                let enum_idx = enums[variant_handle.enum_def.0 as usize];
+                let tag = variant_handle.variant;
+                let field_count = cache.variant_field_count(enum_idx, tag)?;
+                variant_handles.push(VariantHandle {
+                    enum_idx,
+                    tag,
+                    field_count,
+                });
+            }
+
+            for variant_inst in module.variant_instantiation_handles() {
+                let (enum_idx, instantiation) =
+                    &enum_instantiations[variant_inst.enum_def.0 as usize];
+                let tag = variant_inst.variant;
+                let field_count = cache.variant_field_count(*enum_idx, tag)?;
+                variant_instantiations.push(VariantInstantiation {
+                    enum_idx: *enum_idx,
+                    tag,
+                    field_count,
+                    instantiation: instantiation.clone(),
+                });
+            }
+
             for struct_inst in module.struct_instantiations() {
                 let def = struct_inst.def.0 as usize;
                 let struct_def = &structs[def];
@@ -1942,7 +2314,7 @@ impl Module {
         match create() {
             Ok(_) => Ok(Self {
                 id,
-                module: Arc::new(module),
+                module,
                 struct_refs,
                 structs,
                 struct_instantiations,
@@ -1950,6 +2322,8 @@ impl Module {
                 function_instantiations,
                 field_handles,
                 field_instantiations,
+                variant_handles,
+                variant_instantiations,
                 function_map,
                 struct_map,
                 single_signature_token_map,
@@ -1994,6 +2368,17 @@ impl Module {
         self.field_handles[idx.0 as usize].offset
     }
 
+    fn variant_handle_at(&self, idx: VariantHandleIndex) -> &VariantHandle {
+        &self.variant_handles[idx.0 as usize]
+    }
+
+    fn variant_instantiation_at(
+        &self,
+        idx: VariantInstantiationHandleIndex,
+    ) -> &VariantInstantiation {
+        &self.variant_instantiations[idx.0 as usize]
+    }
+
     fn field_instantiation_offset(&self, idx: FieldInstantiationIndex) -> usize {
         self.field_instantiations[idx.0 as usize].offset
     }
@@ -2438,6 +2823,25 @@ struct StructInstantiation {
     instantiation: Vec<Type>,
 }
 
+// A variant handle. The tag and the field count are the only used information when
+// operating on a variant
+#[derive(Debug)]
+struct VariantHandle {
+    // `ModuelCache::structs` global table index of the enum
+    enum_idx: CachedStructIndex,
+    tag: VariantTag,
+    field_count: u16,
+}
+
+#[derive(Debug)]
+struct VariantInstantiation {
+    // `ModuelCache::structs` global table index of the enum. It is the generic type.
+    enum_idx: CachedStructIndex,
+    tag: VariantTag,
+    field_count: u16,
+    instantiation: Vec<Type>,
+}
+
 // A field handle. The offset is the only used information when operating on a field
 #[derive(Debug)]
 struct FieldHandle {
@@ -2464,6 +2868,8 @@ struct StructInfo {
     struct_tag: Option<StructTag>,
     struct_layout: Option<MoveStructLayout>,
     annotated_struct_layout: Option<MoveStructLayout>,
+    enum_layout: Option<MoveEnumLayout>,
+    annotated_enum_layout: Option<MoveEnumLayout>,
     node_count: Option<usize>,
     annotated_node_count: Option<usize>,
 }
@@ -2474,6 +2880,8 @@ impl StructInfo {
             struct_tag: None,
             struct_layout: None,
             annotated_struct_layout: None,
+            enum_layout: None,
+            annotated_enum_layout: None,
             node_count: None,
             annotated_node_count: None,
         }
@@ -2490,19 +2898,44 @@ impl TypeCache {
             structs: HashMap::new(),
         }
     }
-}
 
-/// Maximal depth of a value in terms of type depth.
-const VALUE_DEPTH_MAX: usize = 128;
+    // Remove the given structs and their instantiations, as well as the instantiations of other
+    // structs with type arguments containing any of them.
+    fn remove_structs(&mut self, removed: &BTreeSet<CachedStructIndex>) {
+        fn contains_removed(ty: &Type, removed: &BTreeSet<CachedStructIndex>) -> bool {
+            match ty {
+                Type::Struct(idx) => removed.contains(idx),
+                Type::StructInstantiation(idx, ty_args) => {
+                    removed.contains(idx) || ty_args.iter().any(|ty| contains_removed(ty, removed))
+                }
+                Type::Vector(ty) | Type::Reference(ty) | Type::MutableReference(ty) => {
+                    contains_removed(ty, removed)
+                }
+                Type::Bool
+                | Type::U8
+                | Type::U16
+                | Type::U32
+                | Type::U64
+                | Type::U128
+                | Type::U256
+                | Type::Address
+                | Type::Signer
+                | Type::TyParam(_) => false,
+            }
+        }
+
+        self.structs.retain(|idx, instantiations| {
+            instantiations
+                .retain(|ty_args, _| !ty_args.iter().any(|ty| contains_removed(ty, removed)));
+            !removed.contains(idx) && !instantiations.is_empty()
+        });
+    }
+}
 
 /// Maximal nodes which are allowed when converting to layout. This includes the the types of
 /// fields for struct types.
 const MAX_TYPE_TO_LAYOUT_NODES: usize = 256;
 
-/// Maximal nodes which are all allowed when instantiating a generic type. This does not include
-/// field types of structs.
-const MAX_TYPE_INSTANTIATION_NODES: usize = 128;
-
 impl Loader {
     fn struct_gidx_to_type_tag(
         &self,
@@ -2644,7 +3077,7 @@ impl Loader {
         if *count > MAX_TYPE_TO_LAYOUT_NODES {
             return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
         }
-        if depth > VALUE_DEPTH_MAX {
+        if depth > self.vm_config.max_value_depth {
             return Err(PartialVMError::new(StatusCode::VM_MAX_VALUE_DEPTH_REACHED));
         }
         Ok(match ty {
@@ -2692,6 +3125,14 @@ impl Loader {
                     depth + 1,
                 )?))
             }
+            Type::Struct(gidx) if self.is_enum(*gidx) => {
+                *count += 1;
+                MoveTypeLayout::Enum(self.enum_gidx_to_layout(*gidx, &[], count, depth, false)?)
+            }
+            Type::StructInstantiation(gidx, ty_args) if self.is_enum(*gidx) => {
+                *count += 1;
+                MoveTypeLayout::Enum(self.enum_gidx_to_layout(*gidx, ty_args, count, depth, false)?)
+            }
             Type::Struct(gidx) => {
                 *count += 1;
                 MoveTypeLayout::Struct(self.struct_gidx_to_type_layout(*gidx, &[], count, depth)?)
@@ -2711,6 +3152,76 @@ impl Loader {
         })
     }
 
+    fn is_enum(&self, gidx: CachedStructIndex) -> bool {
+        self.module_cache.read().struct_at(gidx).variants.is_some()
+    }
+
+    // Enum layouts carry no names, the annotated layout of an enum only differs from the runtime
+    // one in the layouts of the fields of its variants
+    fn enum_gidx_to_layout(
+        &self,
+        gidx: CachedStructIndex,
+        ty_args: &[Type],
+        count: &mut usize,
+        depth: usize,
+        annotated: bool,
+    ) -> PartialVMResult<MoveEnumLayout> {
+        if let Some(struct_map) = self.type_cache.read().structs.get(&gidx) {
+            if let Some(struct_info) = struct_map.get(ty_args) {
+                let (node_count, layout) = if annotated {
+                    (
+                        &struct_info.annotated_node_count,
+                        &struct_info.annotated_enum_layout,
+                    )
+                } else {
+                    (&struct_info.node_count, &struct_info.enum_layout)
+                };
+                if let Some(node_count) = node_count {
+                    *count += *node_count
+                }
+                if let Some(layout) = layout {
+                    return Ok(layout.clone());
+                }
+            }
+        }
+
+        let count_before = *count;
+        let enum_type = self.module_cache.read().struct_at(gidx);
+        let mut variant_layouts = vec![];
+        for variant in enum_type.variants.iter().flatten() {
+            let mut field_layouts = vec![];
+            for ty in &variant.fields {
+                let ty = self.subst(ty, ty_args)?;
+                field_layouts.push(if annotated {
+                    self.type_to_fully_annotated_layout_impl(&ty, count, depth + 1)?
+                } else {
+                    self.type_to_type_layout_impl(&ty, count, depth + 1)?
+                });
+            }
+            variant_layouts.push(field_layouts);
+        }
+        let field_node_count = *count - count_before;
+
+        let enum_layout = MoveEnumLayout::new(variant_layouts);
+
+        let mut cache = self.type_cache.write();
+        let info = cache
+            .structs
+            .entry(gidx)
+            .or_insert_with(HashMap::new)
+            .entry(ty_args.to_vec())
+            .or_insert_with(StructInfo::new);
+        if annotated {
+            info.annotated_enum_layout = Some(enum_layout.clone());
+            info.annotated_node_count = Some(field_node_count);
+        } else {
+            info.enum_layout = Some(enum_layout.clone());
+            info.node_count = Some(field_node_count);
+        }
+
+        Ok(enum_layout)
+    }
+
     fn struct_gidx_to_fully_annotated_layout(
         &self,
         gidx: CachedStructIndex,
@@ -2775,7 +3286,7 @@ impl Loader {
         if *count > MAX_TYPE_TO_LAYOUT_NODES {
             return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_NODES));
         }
-        if depth > VALUE_DEPTH_MAX {
+        if depth > self.vm_config.max_value_depth {
             return Err(PartialVMError::new(StatusCode::VM_MAX_VALUE_DEPTH_REACHED));
         }
         Ok(match ty {
@@ -2791,6 +3302,12 @@ impl Loader {
             Type::Vector(ty) => MoveTypeLayout::Vector(Box::new(
                 self.type_to_fully_annotated_layout_impl(ty, count, depth + 1)?,
             )),
+            Type::Struct(gidx) if self.is_enum(*gidx) => {
+                MoveTypeLayout::Enum(self.enum_gidx_to_layout(*gidx, &[], count, depth, true)?)
+            }
+            Type::StructInstantiation(gidx, ty_args) if self.is_enum(*gidx) => {
+                MoveTypeLayout::Enum(self.enum_gidx_to_layout(*gidx, ty_args, count, depth, true)?)
+            }
             Type::Struct(gidx) => MoveTypeLayout::Struct(
                 self.struct_gidx_to_fully_annotated_layout(*gidx, &[], count, depth)?,
             ),
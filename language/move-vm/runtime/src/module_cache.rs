@@ -0,0 +1,174 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cache of deserialized and verified modules which can be shared by `MoveVM` instances.
+
+use move_binary_format::{access::ModuleAccess, CompiledModule};
+use move_core_types::language_storage::ModuleId;
+use parking_lot::Mutex;
+use sha3::{Digest, Sha3_256};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+type ModuleHash = [u8; 32];
+
+/// A bounded cache of modules which have been deserialized and passed the bytecode verifier checks
+/// that can be performed on a module on its own, keyed by module id and the hash of the module's
+/// bytes. Loading a module whose bytes are in the cache skips deserialization and verification.
+///
+/// The cache can be shared by several `MoveVM`s (see `MoveVM::new_with_shared_cache`) and used
+/// from several threads at once. Once `capacity` modules are cached, the least recently used one
+/// is evicted to make room for a new one. VMs sharing a cache are expected to use the same
+/// verifier config and maximal binary format version, as those are not part of the key.
+///
+/// Modules are looked up by the hash of their bytes in storage, so a republished module is never
+/// served from a stale entry. `invalidate` can still be used to drop entries eagerly, e.g. when
+/// a module is upgraded, which also drops the modules depending on it.
+pub struct SharedModuleCache {
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    capacity: usize,
+    entries: HashMap<ModuleId, Entry>,
+    // Module ids by last use, from least to most recent.
+    recency: BTreeMap<u64, ModuleId>,
+    tick: u64,
+}
+
+struct Entry {
+    hash: ModuleHash,
+    module: Arc<CompiledModule>,
+    dependencies: Vec<ModuleId>,
+    last_used: u64,
+}
+
+impl SharedModuleCache {
+    /// Creates an empty cache holding at most `capacity` modules.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                capacity,
+                entries: HashMap::new(),
+                recency: BTreeMap::new(),
+                tick: 0,
+            }),
+        }
+    }
+
+    /// Returns the number of cached modules.
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether a module with the given id is cached.
+    pub fn contains(&self, id: &ModuleId) -> bool {
+        self.inner.lock().entries.contains_key(id)
+    }
+
+    /// Removes the module with the given id, if cached, as well as all cached modules depending
+    /// on it, directly or transitively.
+    pub fn invalidate(&self, id: &ModuleId) {
+        self.inner.lock().invalidate(id)
+    }
+
+    /// Removes all cached modules.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock();
+        inner.entries.clear();
+        inner.recency.clear();
+    }
+
+    /// Returns the cached module with the given id if it was deserialized from `bytes`.
+    pub(crate) fn get(&self, id: &ModuleId, bytes: &[u8]) -> Option<Arc<CompiledModule>> {
+        let hash = hash(bytes);
+        let mut inner = self.inner.lock();
+        let tick = inner.next_tick();
+        let Inner {
+            entries, recency, ..
+        } = &mut *inner;
+        let entry = entries.get_mut(id).filter(|entry| entry.hash == hash)?;
+        recency.remove(&entry.last_used);
+        recency.insert(tick, id.clone());
+        entry.last_used = tick;
+        Some(Arc::clone(&entry.module))
+    }
+
+    /// Caches `module`, deserialized from `bytes` and verified. A different version of the module
+    /// already in the cache is invalidated.
+    pub(crate) fn insert(&self, bytes: &[u8], module: Arc<CompiledModule>) {
+        let hash = hash(bytes);
+        let id = module.self_id();
+        let mut inner = self.inner.lock();
+        match inner.entries.get(&id) {
+            Some(entry) if entry.hash == hash => return,
+            Some(_) => inner.invalidate(&id),
+            None => (),
+        }
+        if inner.capacity == 0 {
+            return;
+        }
+        while inner.entries.len() >= inner.capacity {
+            inner.evict_least_recently_used();
+        }
+        let tick = inner.next_tick();
+        inner.recency.insert(tick, id.clone());
+        inner.entries.insert(
+            id,
+            Entry {
+                hash,
+                dependencies: module.immediate_dependencies(),
+                module,
+                last_used: tick,
+            },
+        );
+    }
+}
+
+impl Inner {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some(tick) = self.recency.keys().next().copied() {
+            if let Some(id) = self.recency.remove(&tick) {
+                self.entries.remove(&id);
+            }
+        }
+    }
+
+    fn invalidate(&mut self, id: &ModuleId) {
+        let mut invalidated = BTreeSet::new();
+        let mut worklist = vec![id.clone()];
+        while let Some(id) = worklist.pop() {
+            if !invalidated.insert(id.clone()) {
+                continue;
+            }
+            worklist.extend(
+                self.entries
+                    .iter()
+                    .filter(|(_, entry)| entry.dependencies.contains(&id))
+                    .map(|(dependent, _)| dependent.clone()),
+            );
+        }
+        for id in invalidated {
+            if let Some(entry) = self.entries.remove(&id) {
+                self.recency.remove(&entry.last_used);
+            }
+        }
+    }
+}
+
+fn hash(bytes: &[u8]) -> ModuleHash {
+    let mut sha3 = Sha3_256::new();
+    sha3.update(bytes);
+    sha3.finalize().into()
+}
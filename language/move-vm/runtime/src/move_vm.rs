@@ -5,7 +5,8 @@
 use std::{collections::BTreeSet, sync::Arc};
 
 use crate::{
-    config::VMConfig, data_cache::TransactionDataCache, native_extensions::NativeContextExtensions,
+    config::VMConfig, data_cache::TransactionDataCache, execution_tracer::ExecutionTracer,
+    module_cache::SharedModuleCache, native_extensions::NativeContextExtensions,
     native_functions::NativeFunction, runtime::VMRuntime, session::Session,
 };
 use move_binary_format::{
@@ -18,7 +19,7 @@ use move_core_types::{
 };
 
 pub struct MoveVM {
-    runtime: VMRuntime,
+    pub(crate) runtime: VMRuntime,
 }
 
 impl MoveVM {
@@ -33,7 +34,21 @@ impl MoveVM {
         vm_config: VMConfig,
     ) -> VMResult<Self> {
         Ok(Self {
-            runtime: VMRuntime::new(natives, vm_config)
+            runtime: VMRuntime::new(natives, vm_config, None)
+                .map_err(|err| err.finish(Location::Undefined))?,
+        })
+    }
+
+    /// Create a VM which looks up deserialized and verified modules in `module_cache` before
+    /// loading them from storage, and adds the ones it loads to it. The cache can be shared with
+    /// other VMs, including ones used concurrently from other threads.
+    pub fn new_with_shared_cache(
+        natives: impl IntoIterator<Item = (AccountAddress, Identifier, Identifier, NativeFunction)>,
+        vm_config: VMConfig,
+        module_cache: Arc<SharedModuleCache>,
+    ) -> VMResult<Self> {
+        Ok(Self {
+            runtime: VMRuntime::new(natives, vm_config, Some(module_cache))
                 .map_err(|err| err.finish(Location::Undefined))?,
         })
     }
@@ -65,6 +80,28 @@ impl MoveVM {
         self.runtime.new_session_with_extensions(remote, extensions)
     }
 
+    /// Create a new session, as in `new_session`, whose function and script executions report
+    /// to the given tracer.
+    pub fn new_session_with_tracer<'r, S: MoveResolver>(
+        &self,
+        remote: &'r S,
+        tracer: &'r mut dyn ExecutionTracer,
+    ) -> Session<'r, '_, S> {
+        self.runtime
+            .new_session_with_tracer(remote, NativeContextExtensions::default(), tracer)
+    }
+
+    /// Create a new session with both native context extensions and a tracer.
+    pub fn new_session_with_extensions_and_tracer<'r, S: MoveResolver>(
+        &self,
+        remote: &'r S,
+        extensions: NativeContextExtensions<'r>,
+        tracer: &'r mut dyn ExecutionTracer,
+    ) -> Session<'r, '_, S> {
+        self.runtime
+            .new_session_with_tracer(remote, extensions, tracer)
+    }
+
     /// Load a module into VM's code cache
     pub fn load_module<'r, S: MoveResolver>(
         &self,
@@ -96,7 +133,9 @@ impl MoveVM {
     }
 
     /// If the loader cache has been invalidated (either by the above call or by internal logic)
-    /// flush it so it is valid again. Notice that should only be called if there are no
+    /// flush it so it is valid again. When modules were upgraded, only those and the modules and
+    /// scripts depending on them are flushed, until flushed modules make up most of the cache,
+    /// which is then flushed entirely. Notice that should only be called if there are no
     /// outstanding sessions created from this VM.
     /// TODO: new loader architecture
     pub fn flush_loader_cache_if_invalidated(&self) {
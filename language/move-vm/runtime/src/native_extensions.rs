@@ -3,8 +3,29 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use better_any::{Tid, TidAble, TidExt};
+use move_binary_format::errors::PartialVMResult;
 use std::{any::TypeId, collections::HashMap};
 
+/// An extension whose state is saved and restored by session savepoints.
+pub trait SnapshotExtension: Sized {
+    /// Returns a copy of the extension which shares no mutable state with `self`. Rolling back to
+    /// a savepoint replaces the extension with the copy made when the savepoint was created.
+    fn snapshot(&self) -> PartialVMResult<Self>;
+}
+
+type Snapshotter<'a> = fn(&dyn Tid<'a>) -> PartialVMResult<Box<dyn Tid<'a>>>;
+
+fn snapshot_extension<'a, T: TidAble<'a> + SnapshotExtension>(
+    ext: &dyn Tid<'a>,
+) -> PartialVMResult<Box<dyn Tid<'a>>> {
+    Ok(Box::new(ext.downcast_ref::<T>().unwrap().snapshot()?))
+}
+
+/// The state of the snapshotting extensions at a savepoint of a session.
+pub(crate) struct ExtensionsSnapshot<'a> {
+    map: HashMap<TypeId, Box<dyn Tid<'a>>>,
+}
+
 /// A data type to represent a heterogeneous collection of extensions which are available to
 /// native functions. A value to this is passed into the session function execution.
 ///
@@ -13,9 +34,13 @@ use std::{any::TypeId, collections::HashMap};
 /// avoids that extensions need to have `'static` lifetime, which `Any` requires. In order to make a
 /// struct suitable to be a 'Tid', use `#[derive(Tid)]` in the struct declaration. (See also
 /// tests at the end of this module.)
+///
+/// Only extensions added with `add_with_snapshots` take part in session savepoints; the state of
+/// other extensions is kept when rolling back to a savepoint.
 #[derive(Default)]
 pub struct NativeContextExtensions<'a> {
     map: HashMap<TypeId, Box<dyn Tid<'a>>>,
+    snapshotters: HashMap<TypeId, Snapshotter<'a>>,
 }
 
 impl<'a> NativeContextExtensions<'a> {
@@ -26,6 +51,13 @@ impl<'a> NativeContextExtensions<'a> {
         )
     }
 
+    /// Same as `add`, but the state of the extension is also saved and restored by session
+    /// savepoints.
+    pub fn add_with_snapshots<T: TidAble<'a> + SnapshotExtension>(&mut self, ext: T) {
+        self.add(ext);
+        self.snapshotters.insert(T::id(), snapshot_extension::<T>);
+    }
+
     pub fn get<T: TidAble<'a>>(&self) -> &T {
         self.map
             .get(&T::id())
@@ -52,10 +84,30 @@ impl<'a> NativeContextExtensions<'a> {
             .expect("extension unknown")
             .downcast_box::<T>()
         {
-            Ok(val) => *val,
+            Ok(val) => {
+                self.snapshotters.remove(&T::id());
+                *val
+            }
             Err(_) => panic!("downcast error"),
         }
     }
+
+    pub(crate) fn snapshot(&self) -> PartialVMResult<ExtensionsSnapshot<'a>> {
+        let map = self
+            .snapshotters
+            .iter()
+            .map(|(id, snapshotter)| Ok((*id, snapshotter(self.map[id].as_ref())?)))
+            .collect::<PartialVMResult<_>>()?;
+        Ok(ExtensionsSnapshot { map })
+    }
+
+    pub(crate) fn restore(&mut self, snapshot: ExtensionsSnapshot<'a>) {
+        for (id, ext) in snapshot.map {
+            if self.snapshotters.contains_key(&id) {
+                self.map.insert(id, ext);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -15,8 +15,11 @@ use move_core_types::{
     vm_status::{StatusCode, StatusType},
 };
 use move_vm_types::{
-    data_store::DataStore, loaded_data::runtime_types::Type, natives::function::NativeResult,
-    values::Value,
+    data_store::DataStore,
+    loaded_data::runtime_types::Type,
+    natives::function::NativeResult,
+    values::{memory::GlobalKey, Value},
+    views::ValueView,
 };
 use std::{
     collections::{HashMap, VecDeque},
@@ -180,4 +183,34 @@ impl<'a, 'b> NativeContext<'a, 'b> {
     pub fn gas_balance(&self) -> InternalGas {
         self.gas_balance
     }
+
+    /// The maximal length of vectors the native function may create or grow. Exceeding it must
+    /// fail with `VM_MAX_VECTOR_LENGTH_REACHED`.
+    pub fn max_vector_length(&self) -> u64 {
+        self.interpreter.max_vector_length()
+    }
+
+    /// Records that a global value of a native extension, holding `val` if it exists, is being
+    /// accessed, for the memory quota of the execution. `key` identifies it among the global values
+    /// of all extensions. Only the first access is accounted for.
+    pub fn use_global_memory(
+        &mut self,
+        key: Vec<u8>,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.interpreter
+            .memory_mut()
+            .use_global(GlobalKey::Native(key), val)
+    }
+
+    /// Records that `val` moved into a global value of a native extension, where the execution
+    /// still holds it.
+    pub fn use_memory(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.interpreter.memory_mut().use_value(val)
+    }
+
+    /// Records that `val` moved out of a global value of a native extension.
+    pub fn release_memory(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.interpreter.memory_mut().release_value(val)
+    }
 }
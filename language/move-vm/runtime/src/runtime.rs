@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    config::VMConfig,
+    config::{EntryStructArgs, VMConfig},
     data_cache::TransactionDataCache,
+    execution_tracer::{ExecutionTracer, NoopTracer},
     interpreter::Interpreter,
     loader::{Function, Loader},
+    module_cache::SharedModuleCache,
     native_extensions::NativeContextExtensions,
     native_functions::{NativeFunction, NativeFunctions},
     session::{LoadedFunctionInstantiation, SerializedReturnValues, Session},
@@ -24,7 +26,7 @@ use move_core_types::{
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, TypeTag},
     resolver::MoveResolver,
-    value::MoveTypeLayout,
+    value::{MoveStruct, MoveTypeLayout, MoveValue},
     vm_status::StatusCode,
 };
 use move_vm_types::{
@@ -45,9 +47,14 @@ impl VMRuntime {
     pub(crate) fn new(
         natives: impl IntoIterator<Item = (AccountAddress, Identifier, Identifier, NativeFunction)>,
         vm_config: VMConfig,
+        shared_module_cache: Option<Arc<SharedModuleCache>>,
     ) -> PartialVMResult<Self> {
         Ok(VMRuntime {
-            loader: Loader::new(NativeFunctions::new(natives)?, vm_config),
+            loader: Loader::new(
+                NativeFunctions::new(natives)?,
+                vm_config,
+                shared_module_cache,
+            ),
         })
     }
 
@@ -64,9 +71,23 @@ impl VMRuntime {
             runtime: self,
             data_cache: TransactionDataCache::new(remote, &self.loader),
             native_extensions,
+            tracer: None,
+            savepoints: vec![],
+            next_savepoint_id: 0,
         }
     }
 
+    pub fn new_session_with_tracer<'r, S: MoveResolver>(
+        &self,
+        remote: &'r S,
+        native_extensions: NativeContextExtensions<'r>,
+        tracer: &'r mut dyn ExecutionTracer,
+    ) -> Session<'r, '_, S> {
+        let mut session = self.new_session_with_extensions(remote, native_extensions);
+        session.tracer = Some(tracer);
+        session
+    }
+
     pub(crate) fn publish_module_bundle(
         &self,
         modules: Vec<Vec<u8>>,
@@ -195,9 +216,8 @@ impl VMRuntime {
         for (module, blob) in compiled_modules.into_iter().zip(modules.into_iter()) {
             let is_republishing = data_store.exists_module(&module.self_id())?;
             if is_republishing {
-                // This is an upgrade, so invalidate the loader cache, which still contains the
-                // old module.
-                self.loader.mark_as_invalid();
+                // This is an upgrade, so invalidate the old module in the loader cache.
+                self.loader.invalidate_module(&module.self_id());
             }
             data_store.publish_module(&module.self_id(), blob, is_republishing)?;
         }
@@ -215,6 +235,12 @@ impl VMRuntime {
             }
         };
 
+        if let Some(entry_struct_args) = &self.loader.vm_config().entry_struct_args {
+            if contains_struct(&layout) {
+                self.validate_struct_args(ty, arg.borrow(), entry_struct_args)?;
+            }
+        }
+
         match Value::simple_deserialize(arg.borrow(), &layout) {
             Some(val) => Ok(val),
             None => {
@@ -226,6 +252,43 @@ impl VMRuntime {
         }
     }
 
+    /// Runs the validators of the allowed struct types on the structs in `arg`.
+    fn validate_struct_args(
+        &self,
+        ty: &Type,
+        arg: &[u8],
+        entry_struct_args: &EntryStructArgs,
+    ) -> PartialVMResult<()> {
+        let layout = self.loader.type_to_fully_annotated_layout(ty)?;
+        let value = MoveValue::simple_deserialize(arg, &layout).map_err(|_err| {
+            warn!("[VM] failed to deserialize argument");
+            PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT)
+        })?;
+        validate_struct_values(value, entry_struct_args)
+    }
+
+    /// Checks that `ty` may be used as the type of an entry function or script argument: a
+    /// primitive type, a signer, a vector or an allowed struct type, or a reference to these.
+    fn check_entry_arg_type(
+        &self,
+        ty: &Type,
+        entry_struct_args: &EntryStructArgs,
+    ) -> PartialVMResult<()> {
+        let ty = match ty {
+            Type::Reference(inner) | Type::MutableReference(inner) => inner,
+            _ => ty,
+        };
+        let tag = self.loader.type_to_type_tag(ty)?;
+        if is_valid_entry_arg_type(&tag, entry_struct_args) {
+            Ok(())
+        } else {
+            Err(
+                PartialVMError::new(StatusCode::INVALID_PARAM_TYPE_FOR_DESERIALIZATION)
+                    .with_message(format!("{} cannot be passed as an argument", tag)),
+            )
+        }
+    }
+
     fn deserialize_args(
         &self,
         arg_tys: Vec<Type>,
@@ -323,8 +386,10 @@ impl VMRuntime {
         param_types: Vec<Type>,
         return_types: Vec<Type>,
         serialized_args: Vec<impl Borrow<[u8]>>,
+        check_arg_types: bool,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: Option<&mut (dyn ExecutionTracer + '_)>,
         extensions: &mut NativeContextExtensions,
     ) -> VMResult<SerializedReturnValues> {
         let arg_types = param_types
@@ -332,6 +397,14 @@ impl VMRuntime {
             .map(|ty| ty.subst(&ty_args))
             .collect::<PartialVMResult<Vec<_>>>()
             .map_err(|err| err.finish(Location::Undefined))?;
+        if check_arg_types {
+            if let Some(entry_struct_args) = &self.loader.vm_config().entry_struct_args {
+                arg_types
+                    .iter()
+                    .try_for_each(|ty| self.check_entry_arg_type(ty, entry_struct_args))
+                    .map_err(|err| err.finish(Location::Undefined))?;
+            }
+        }
         let mut_ref_args = arg_types
             .iter()
             .enumerate()
@@ -349,15 +422,30 @@ impl VMRuntime {
             .collect::<PartialVMResult<Vec<_>>>()
             .map_err(|err| err.finish(Location::Undefined))?;
 
-        let return_values = Interpreter::entrypoint(
-            func,
-            ty_args,
-            deserialized_args,
-            data_store,
-            gas_meter,
-            extensions,
-            &self.loader,
-        )?;
+        // Dispatch on the tracer here so that, without one, the interpreter is instantiated with
+        // `NoopTracer` and all tracing hooks are compiled out.
+        let return_values = match tracer {
+            Some(tracer) => Interpreter::entrypoint(
+                func,
+                ty_args,
+                deserialized_args,
+                data_store,
+                gas_meter,
+                tracer,
+                extensions,
+                &self.loader,
+            ),
+            None => Interpreter::entrypoint(
+                func,
+                ty_args,
+                deserialized_args,
+                data_store,
+                gas_meter,
+                &mut NoopTracer,
+                extensions,
+                &self.loader,
+            ),
+        }?;
 
         let serialized_return_values = self
             .serialize_return_values(&return_types, return_values)
@@ -390,6 +478,7 @@ impl VMRuntime {
         serialized_args: Vec<impl Borrow<[u8]>>,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: Option<&mut (dyn ExecutionTracer + '_)>,
         extensions: &mut NativeContextExtensions,
         bypass_declared_entry_check: bool,
     ) -> VMResult<SerializedReturnValues> {
@@ -440,8 +529,10 @@ impl VMRuntime {
             parameters,
             return_,
             serialized_args,
+            !bypass_declared_entry_check,
             data_store,
             gas_meter,
+            tracer,
             extensions,
         )
     }
@@ -454,6 +545,7 @@ impl VMRuntime {
         serialized_args: Vec<impl Borrow<[u8]>>,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
+        tracer: Option<&mut (dyn ExecutionTracer + '_)>,
         extensions: &mut NativeContextExtensions,
     ) -> VMResult<SerializedReturnValues> {
         // load the script, perform verification
@@ -474,8 +566,10 @@ impl VMRuntime {
             parameters,
             return_,
             serialized_args,
+            true,
             data_store,
             gas_meter,
+            tracer,
             extensions,
         )
     }
@@ -484,3 +578,52 @@ impl VMRuntime {
         &self.loader
     }
 }
+
+fn contains_struct(layout: &MoveTypeLayout) -> bool {
+    match layout {
+        MoveTypeLayout::Vector(elem) => contains_struct(elem),
+        MoveTypeLayout::Struct(_) | MoveTypeLayout::Enum(_) => true,
+        _ => false,
+    }
+}
+
+fn is_valid_entry_arg_type(tag: &TypeTag, entry_struct_args: &EntryStructArgs) -> bool {
+    match tag {
+        TypeTag::Vector(elem) => is_valid_entry_arg_type(elem, entry_struct_args),
+        TypeTag::Struct(struct_tag) => {
+            entry_struct_args.validator(struct_tag).is_some()
+                && struct_tag
+                    .type_params
+                    .iter()
+                    .all(|ty| is_valid_entry_arg_type(ty, entry_struct_args))
+        }
+        _ => true,
+    }
+}
+
+fn validate_struct_values(
+    value: MoveValue,
+    entry_struct_args: &EntryStructArgs,
+) -> PartialVMResult<()> {
+    let fields = match value {
+        MoveValue::Vector(elems) => elems,
+        MoveValue::Struct(MoveStruct::WithTypes { type_, fields }) => {
+            let fields: Vec<_> = fields.into_iter().map(|(_, value)| value).collect();
+            if let Some(validator) = entry_struct_args.validator(&type_) {
+                if !validator(&fields) {
+                    return Err(
+                        PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT)
+                            .with_message(format!("invalid value of type {}", type_)),
+                    );
+                }
+            }
+            fields
+        }
+        MoveValue::Struct(value) => value.into_fields(),
+        MoveValue::Variant(variant) => variant.fields,
+        _ => return Ok(()),
+    };
+    fields
+        .into_iter()
+        .try_for_each(|value| validate_struct_values(value, entry_struct_args))
+}
@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    data_cache::TransactionDataCache, native_extensions::NativeContextExtensions,
+    data_cache::TransactionDataCache,
+    execution_tracer::ExecutionTracer,
+    native_extensions::{ExtensionsSnapshot, NativeContextExtensions},
     runtime::VMRuntime,
 };
 use move_binary_format::{
@@ -18,6 +20,7 @@ use move_core_types::{
     language_storage::{ModuleId, TypeTag},
     resolver::MoveResolver,
     value::MoveTypeLayout,
+    vm_status::StatusCode,
 };
 use move_vm_types::{
     data_store::DataStore,
@@ -30,6 +33,20 @@ pub struct Session<'r, 'l, S> {
     pub(crate) runtime: &'l VMRuntime,
     pub(crate) data_cache: TransactionDataCache<'r, 'l, S>,
     pub(crate) native_extensions: NativeContextExtensions<'r>,
+    pub(crate) tracer: Option<&'r mut dyn ExecutionTracer>,
+    pub(crate) savepoints: Vec<SessionSnapshot<'r>>,
+    pub(crate) next_savepoint_id: u64,
+}
+
+/// A point of a session its effects can be rolled back to. Created by `Session::savepoint`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Savepoint(u64);
+
+/// The state of a session at a savepoint. The changes to the data cache since are recorded by
+/// the cache itself.
+pub(crate) struct SessionSnapshot<'r> {
+    id: u64,
+    native_extensions: ExtensionsSnapshot<'r>,
 }
 
 /// Serialized return values from function/script execution
@@ -85,6 +102,7 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
             args,
             &mut self.data_cache,
             gas_meter,
+            self.tracer.as_deref_mut(),
             &mut self.native_extensions,
             bypass_declared_entry_check,
         )
@@ -107,6 +125,7 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
             args,
             &mut self.data_cache,
             gas_meter,
+            self.tracer.as_deref_mut(),
             &mut self.native_extensions,
             bypass_declared_entry_check,
         )
@@ -141,6 +160,7 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
             args,
             &mut self.data_cache,
             gas_meter,
+            self.tracer.as_deref_mut(),
             &mut self.native_extensions,
         )
     }
@@ -229,6 +249,63 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
         )
     }
 
+    /// Creates a savepoint capturing the effects of the session so far: the updated resources,
+    /// the published modules, the emitted events and the state of the native extensions added
+    /// with `NativeContextExtensions::add_with_snapshots`.
+    ///
+    /// Savepoints nest: rolling back to or releasing a savepoint also discards all savepoints
+    /// created after it.
+    pub fn savepoint(&mut self) -> VMResult<Savepoint> {
+        let native_extensions = self
+            .native_extensions
+            .snapshot()
+            .map_err(|e| e.finish(Location::Undefined))?;
+        let id = self.next_savepoint_id;
+        self.next_savepoint_id += 1;
+        self.data_cache.savepoint();
+        self.savepoints.push(SessionSnapshot {
+            id,
+            native_extensions,
+        });
+        Ok(Savepoint(id))
+    }
+
+    /// Reverts all effects of the session since `savepoint` was created, keeping the effects from
+    /// before it.
+    ///
+    /// Modules published since the savepoint are removed from the data store and invalidated in
+    /// the code cache of the VM, as republished modules are, so they are dropped from the cache
+    /// when it is next flushed.
+    pub fn rollback_to(&mut self, savepoint: Savepoint) -> VMResult<()> {
+        let idx = self.savepoint_index(&savepoint)?;
+        self.savepoints.truncate(idx + 1);
+        let snapshot = self.savepoints.pop().unwrap();
+        for module_id in self.data_cache.rollback(idx) {
+            self.runtime.loader().invalidate_module(&module_id);
+        }
+        self.native_extensions.restore(snapshot.native_extensions);
+        Ok(())
+    }
+
+    /// Discards `savepoint`, keeping all effects of the session.
+    pub fn release(&mut self, savepoint: Savepoint) -> VMResult<()> {
+        let idx = self.savepoint_index(&savepoint)?;
+        self.savepoints.truncate(idx);
+        self.data_cache.release(idx);
+        Ok(())
+    }
+
+    fn savepoint_index(&self, savepoint: &Savepoint) -> VMResult<usize> {
+        self.savepoints
+            .iter()
+            .position(|snapshot| snapshot.id == savepoint.0)
+            .ok_or_else(|| {
+                PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                    .with_message("savepoint was already rolled back to or released".to_string())
+                    .finish(Location::Undefined)
+            })
+    }
+
     pub fn num_mutated_accounts(&self, sender: &AccountAddress) -> u64 {
         self.data_cache.num_mutated_accounts(sender)
     }
@@ -0,0 +1,72 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{loader::MIN_DEAD_SLOTS_TO_FLUSH, move_vm::MoveVM};
+use move_binary_format::{errors::VMError, file_format::basic_test_module};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag},
+    resolver::{ModuleResolver, ResourceResolver},
+};
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::collections::HashMap;
+
+struct RemoteStore {
+    modules: HashMap<ModuleId, Vec<u8>>,
+}
+
+impl ModuleResolver for RemoteStore {
+    type Error = VMError;
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.modules.get(module_id).cloned())
+    }
+}
+
+impl ResourceResolver for RemoteStore {
+    type Error = VMError;
+
+    fn get_resource(
+        &self,
+        _address: &AccountAddress,
+        _tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+// `basic_test_module` under the given name, with a struct and a function
+fn make_module(name: &str) -> (ModuleId, Vec<u8>) {
+    let mut module = basic_test_module();
+    module.identifiers[0] = Identifier::new(name).unwrap();
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+    (module.self_id(), blob)
+}
+
+#[test]
+fn republishing_keeps_loader_cache_bounded() {
+    let (id, blob) = make_module("M");
+    let (other_id, other_blob) = make_module("N");
+    let storage = RemoteStore {
+        modules: HashMap::from([(id.clone(), blob.clone()), (other_id.clone(), other_blob)]),
+    };
+    let vm = MoveVM::new(vec![]).unwrap();
+    vm.load_module(&other_id, &storage).unwrap();
+
+    // Each round loads `M`, republishes it and flushes it from the cache, leaving its module,
+    // struct and function slots behind
+    for _ in 0..1000 {
+        vm.load_module(&id, &storage).unwrap();
+        let mut session = vm.new_session(&storage);
+        session
+            .publish_module(blob.clone(), *id.address(), &mut UnmeteredGasMeter)
+            .unwrap();
+        session.finish().unwrap();
+        vm.flush_loader_cache_if_invalidated();
+        assert!(vm.runtime.loader().cache_slots() <= 2 * MIN_DEAD_SLOTS_TO_FLUSH);
+    }
+
+    // `M` is loaded again after the last flush
+    vm.load_module(&id, &storage).unwrap();
+}
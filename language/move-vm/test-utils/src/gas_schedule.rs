@@ -12,7 +12,8 @@ use move_binary_format::{
     file_format::{
         Bytecode, ConstantPoolIndex, FieldHandleIndex, FieldInstantiationIndex,
         FunctionHandleIndex, FunctionInstantiationIndex, SignatureIndex,
-        StructDefInstantiationIndex, StructDefinitionIndex,
+        StructDefInstantiationIndex, StructDefinitionIndex, VariantHandleIndex,
+        VariantInstantiationHandleIndex,
     },
     file_format_common::{instruction_key, Opcodes},
 };
@@ -257,6 +258,13 @@ fn get_simple_instruction_opcode(instr: SimpleInstruction) -> Opcodes {
         CastU16 => CAST_U16,
         CastU32 => CAST_U32,
         CastU256 => CAST_U256,
+
+        UnpackVariantImmRef => UNPACK_VARIANT_IMM_REF,
+        UnpackVariantMutRef => UNPACK_VARIANT_MUT_REF,
+        UnpackVariantImmRefGeneric => UNPACK_VARIANT_IMM_REF_GENERIC,
+        UnpackVariantMutRefGeneric => UNPACK_VARIANT_MUT_REF_GENERIC,
+        TestVariant => TEST_VARIANT,
+        TestVariantGeneric => TEST_VARIANT_GENERIC,
     }
 }
 
@@ -695,6 +703,40 @@ pub fn zero_cost_instruction_table() -> Vec<(Bytecode, GasCost)> {
         (CastU16, GasCost::new(0, 0)),
         (CastU32, GasCost::new(0, 0)),
         (CastU256, GasCost::new(0, 0)),
+        (PackVariant(VariantHandleIndex::new(0)), GasCost::new(0, 0)),
+        (
+            PackVariantGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            UnpackVariant(VariantHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            UnpackVariantGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            UnpackVariantImmRef(VariantHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            UnpackVariantImmRefGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            UnpackVariantMutRef(VariantHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (
+            UnpackVariantMutRefGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
+        (TestVariant(VariantHandleIndex::new(0)), GasCost::new(0, 0)),
+        (
+            TestVariantGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(0, 0),
+        ),
     ]
 }
 
@@ -828,6 +870,40 @@ pub fn bytecode_instruction_costs() -> Vec<(Bytecode, GasCost)> {
         (CastU16, GasCost::new(2, 1)),
         (CastU32, GasCost::new(2, 1)),
         (CastU256, GasCost::new(2, 1)),
+        (PackVariant(VariantHandleIndex::new(0)), GasCost::new(2, 1)),
+        (
+            PackVariantGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(3, 1),
+        ),
+        (
+            UnpackVariant(VariantHandleIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            UnpackVariantGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(3, 1),
+        ),
+        (
+            UnpackVariantImmRef(VariantHandleIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            UnpackVariantImmRefGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(3, 1),
+        ),
+        (
+            UnpackVariantMutRef(VariantHandleIndex::new(0)),
+            GasCost::new(2, 1),
+        ),
+        (
+            UnpackVariantMutRefGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(3, 1),
+        ),
+        (TestVariant(VariantHandleIndex::new(0)), GasCost::new(2, 1)),
+        (
+            TestVariantGeneric(VariantInstantiationHandleIndex::new(0)),
+            GasCost::new(3, 1),
+        ),
     ]
 }
 
@@ -0,0 +1,48 @@
+//# publish
+module 0x42::M {
+    use std::option::{Self, Option};
+    use std::string::{Self, String};
+    use std::vector;
+
+    struct S has drop { f: u64 }
+
+    public entry fun str(s: String) {
+        assert!(string::length(&s) == 5, 0)
+    }
+
+    public entry fun opt(o: Option<u64>) {
+        assert!(option::is_some(&o), 0)
+    }
+
+    public entry fun strs(v: vector<String>) {
+        assert!(vector::length(&v) == 2, 0)
+    }
+}
+
+// strings must be valid UTF-8
+
+//# run 0x42::M::str --check-struct-args --args "hello"
+
+//# run 0x42::M::str --check-struct-args --args x"68656c6cff"
+
+//# run 0x42::M::strs --check-struct-args --args vector["a",x"ff"]
+
+// options hold at most one element
+
+//# run 0x42::M::opt --check-struct-args --args vector[5]
+
+//# run 0x42::M::opt --check-struct-args --args vector[5,6]
+
+// other structs are rejected
+
+//# run --args 0
+script {
+use 0x42::M;
+fun main(_s: M::S) {}
+}
+
+//# run --check-struct-args --args 0
+script {
+use 0x42::M;
+fun main(_s: M::S) {}
+}
@@ -4,7 +4,7 @@
 use crate::views::{TypeView, ValueView};
 use move_binary_format::errors::PartialVMResult;
 use move_core_types::{
-    gas_algebra::{InternalGas, NumArgs, NumBytes},
+    gas_algebra::{AbstractMemorySize, InternalGas, NumArgs, NumBytes},
     language_storage::ModuleId,
 };
 
@@ -65,6 +65,13 @@ pub enum SimpleInstruction {
     CastU16,
     CastU32,
     CastU256,
+
+    UnpackVariantImmRef,
+    UnpackVariantMutRef,
+    UnpackVariantImmRefGeneric,
+    UnpackVariantMutRefGeneric,
+    TestVariant,
+    TestVariantGeneric,
 }
 
 /// Trait that defines a generic gas meter interface, allowing clients of the Move VM to implement
@@ -232,6 +239,13 @@ pub trait GasMeter {
         &mut self,
         locals: impl Iterator<Item = impl ValueView>,
     ) -> PartialVMResult<()>;
+
+    /// Called whenever the peak memory held by the values of an execution grows, with the new
+    /// peak. Memory usage is only tracked when the VM is configured with a memory quota, and
+    /// does not cost anything by default.
+    fn charge_peak_memory_usage(&mut self, _peak: AbstractMemorySize) -> PartialVMResult<()> {
+        Ok(())
+    }
 }
 
 /// A dummy gas meter that does not meter anything.
@@ -17,13 +17,24 @@ pub const TYPE_DEPTH_MAX: usize = 256;
 pub struct StructType {
     pub fields: Vec<Type>,
     pub field_names: Vec<Identifier>,
+    // the variants of an enum, `None` for structs.
+    // Enums have no fields of their own, `fields` and `field_names` are empty
+    pub variants: Option<Vec<VariantType>>,
     pub abilities: AbilitySet,
     pub type_parameters: Vec<StructTypeParameter>,
     pub name: Identifier,
     pub module: ModuleId,
+    // the index of the definition in its module, an `EnumDefinitionIndex` for enums
     pub struct_def: StructDefinitionIndex,
 }
 
+#[derive(Debug, Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct VariantType {
+    pub name: Identifier,
+    pub fields: Vec<Type>,
+    pub field_names: Vec<Identifier>,
+}
+
 impl StructType {
     pub fn type_param_constraints(&self) -> impl ExactSizeIterator<Item = &AbilitySet> {
         self.type_parameters.iter().map(|param| &param.constraints)
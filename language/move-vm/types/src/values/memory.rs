@@ -0,0 +1,283 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accounting of the memory held by live values during execution.
+
+use crate::{
+    loaded_data::runtime_types::Type,
+    views::{ValueView, ValueVisitor},
+};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    account_address::AccountAddress, gas_algebra::AbstractMemorySize, u256::U256,
+    vm_status::StatusCode,
+};
+use std::collections::BTreeSet;
+
+/// Identifies a global value whose memory is accounted for by a `MemoryTracker`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GlobalKey {
+    /// The resource of the given type published under the given address.
+    Resource(AccountAddress, Type),
+    /// A global value managed by a native extension, such as a table entry. Extensions must use
+    /// keys distinct from those of any other global value.
+    Native(Vec<u8>),
+}
+
+/// Tracks the abstract memory size of the values held by an execution, such as the values on
+/// the operand stack, in locals and in globals, against an optional quota.
+///
+/// The tracker relies on its users to report values as they start and stop being held. Sizes
+/// follow `ValueView::legacy_abstract_memory_size`, except that references are free: they do not
+/// own the value they point to, which is accounted for by its owner.
+///
+/// Global values are accounted for on their first access by the execution, whether it loaded them
+/// from storage or an earlier execution in the same session did.
+///
+/// A tracker without a quota is disabled and does not compute the size of any value.
+#[derive(Debug, Clone)]
+pub struct MemoryTracker {
+    quota: Option<u64>,
+    usage: u64,
+    peak: u64,
+    peak_changed: bool,
+    accessed_globals: BTreeSet<GlobalKey>,
+}
+
+impl MemoryTracker {
+    /// Creates a tracker failing with `MEMORY_LIMIT_EXCEEDED` once the values held exceed `quota`
+    /// bytes, or a disabled one if there is no quota.
+    pub fn new(quota: Option<u64>) -> Self {
+        Self {
+            quota,
+            usage: 0,
+            peak: 0,
+            peak_changed: false,
+            accessed_globals: BTreeSet::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.quota.is_some()
+    }
+
+    /// Returns the size accounted for holding `val`, or zero if the tracker is disabled.
+    pub fn size_of(&self, val: impl ValueView) -> AbstractMemorySize {
+        if self.is_enabled() {
+            held_size(val)
+        } else {
+            AbstractMemorySize::zero()
+        }
+    }
+
+    /// Records that `val` is now being held.
+    pub fn use_value(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        let size = self.size_of(val);
+        self.use_bytes(size)
+    }
+
+    /// Records that `val` is no longer being held.
+    pub fn release_value(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        let size = self.size_of(val);
+        self.release_bytes(size)
+    }
+
+    /// Records that `old` was overwritten with `new`. Only the difference in their sizes is
+    /// accounted for, as the container of `old` may have accounted for it differently than on its
+    /// own, like vectors of primitive types do.
+    pub fn replace_value(
+        &mut self,
+        old: impl ValueView,
+        new: impl ValueView,
+    ) -> PartialVMResult<()> {
+        let old = self.size_of(old);
+        let new = self.size_of(new);
+        self.resize_bytes(old, new)
+    }
+
+    /// Records that a value held in `old` bytes now takes `new` bytes.
+    pub fn resize_bytes(
+        &mut self,
+        old: AbstractMemorySize,
+        new: AbstractMemorySize,
+    ) -> PartialVMResult<()> {
+        let (old, new): (u64, u64) = (old.into(), new.into());
+        if new >= old {
+            self.use_bytes((new - old).into())
+        } else {
+            self.release_bytes((old - new).into())
+        }
+    }
+
+    /// Records that the global value `key`, holding `val` if it exists, is being accessed. Only
+    /// its first access by the execution is accounted for.
+    pub fn use_global(
+        &mut self,
+        key: GlobalKey,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        if !self.is_enabled() || !self.accessed_globals.insert(key) {
+            return Ok(());
+        }
+        match val {
+            Some(val) => self.use_value(val),
+            None => Ok(()),
+        }
+    }
+
+    /// Records that `elem` was pushed onto a vector of `elem_ty` elements.
+    pub fn use_vector_elem(&mut self, elem_ty: &Type, elem: impl ValueView) -> PartialVMResult<()> {
+        let size = self.vector_elem_size(elem_ty, elem);
+        self.use_bytes(size)
+    }
+
+    /// Records that `elem` was popped off a vector of `elem_ty` elements.
+    pub fn release_vector_elem(
+        &mut self,
+        elem_ty: &Type,
+        elem: impl ValueView,
+    ) -> PartialVMResult<()> {
+        let size = self.vector_elem_size(elem_ty, elem);
+        self.release_bytes(size)
+    }
+
+    /// Vectors of primitive types store their elements unboxed, taking less space than the
+    /// elements on their own.
+    fn vector_elem_size(&self, elem_ty: &Type, elem: impl ValueView) -> AbstractMemorySize {
+        let size = match elem_ty {
+            Type::U8 | Type::Bool => 1,
+            Type::U16 => 2,
+            Type::U32 => 4,
+            Type::U64 => 8,
+            Type::U128 => 16,
+            Type::U256 => 32,
+            Type::Address => AccountAddress::LENGTH,
+            _ => return self.size_of(elem),
+        };
+        if self.is_enabled() {
+            AbstractMemorySize::new(size as u64)
+        } else {
+            AbstractMemorySize::zero()
+        }
+    }
+
+    /// Records that `size` more bytes are being held.
+    pub fn use_bytes(&mut self, size: AbstractMemorySize) -> PartialVMResult<()> {
+        let quota = match self.quota {
+            Some(quota) => quota,
+            None => return Ok(()),
+        };
+        self.usage = self.usage.saturating_add(size.into());
+        if self.usage > self.peak {
+            self.peak = self.usage;
+            self.peak_changed = true;
+        }
+        if self.usage > quota {
+            return Err(
+                PartialVMError::new(StatusCode::MEMORY_LIMIT_EXCEEDED).with_message(format!(
+                    "memory usage of {} bytes exceeds the quota of {} bytes",
+                    self.usage, quota
+                )),
+            );
+        }
+        Ok(())
+    }
+
+    /// Records that `size` bytes are no longer being held. Releasing more bytes than are held
+    /// means that a value was not accounted for when it started being held.
+    pub fn release_bytes(&mut self, size: AbstractMemorySize) -> PartialVMResult<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let size: u64 = size.into();
+        self.usage = self.usage.checked_sub(size).ok_or_else(|| {
+            PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR).with_message(
+                format!(
+                    "releasing {} bytes while only {} bytes are held",
+                    size, self.usage
+                ),
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Returns the number of bytes currently held.
+    pub fn usage(&self) -> AbstractMemorySize {
+        self.usage.into()
+    }
+
+    /// Returns the highest number of bytes held so far.
+    pub fn peak(&self) -> AbstractMemorySize {
+        self.peak.into()
+    }
+
+    /// Returns the peak usage if it grew since the last call.
+    pub fn take_new_peak(&mut self) -> Option<AbstractMemorySize> {
+        if std::mem::take(&mut self.peak_changed) {
+            Some(self.peak())
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the size of the memory owned by `val`, i.e. nothing for a reference.
+fn held_size(val: impl ValueView) -> AbstractMemorySize {
+    let mut is_ref = IsReference(false);
+    val.visit(&mut is_ref);
+    if is_ref.0 {
+        AbstractMemorySize::zero()
+    } else {
+        val.legacy_abstract_memory_size()
+    }
+}
+
+/// Checks whether the visited value is a reference, without traversing it.
+struct IsReference(bool);
+
+impl ValueVisitor for IsReference {
+    fn visit_u8(&mut self, _depth: usize, _val: u8) {}
+
+    fn visit_u16(&mut self, _depth: usize, _val: u16) {}
+
+    fn visit_u32(&mut self, _depth: usize, _val: u32) {}
+
+    fn visit_u64(&mut self, _depth: usize, _val: u64) {}
+
+    fn visit_u128(&mut self, _depth: usize, _val: u128) {}
+
+    fn visit_u256(&mut self, _depth: usize, _val: U256) {}
+
+    fn visit_bool(&mut self, _depth: usize, _val: bool) {}
+
+    fn visit_address(&mut self, _depth: usize, _val: AccountAddress) {}
+
+    fn visit_struct(&mut self, _depth: usize, _len: usize) -> bool {
+        false
+    }
+
+    fn visit_vec(&mut self, _depth: usize, _len: usize) -> bool {
+        false
+    }
+
+    fn visit_ref(&mut self, depth: usize, _is_global: bool) -> bool {
+        self.0 = depth == 0;
+        false
+    }
+
+    fn visit_vec_u8(&mut self, _depth: usize, _vals: &[u8]) {}
+
+    fn visit_vec_u16(&mut self, _depth: usize, _vals: &[u16]) {}
+
+    fn visit_vec_u32(&mut self, _depth: usize, _vals: &[u32]) {}
+
+    fn visit_vec_u64(&mut self, _depth: usize, _vals: &[u64]) {}
+
+    fn visit_vec_u128(&mut self, _depth: usize, _vals: &[u128]) {}
+
+    fn visit_vec_u256(&mut self, _depth: usize, _vals: &[U256]) {}
+
+    fn visit_vec_bool(&mut self, _depth: usize, _vals: &[bool]) {}
+
+    fn visit_vec_address(&mut self, _depth: usize, _vals: &[AccountAddress]) {}
+}
@@ -2,6 +2,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod memory;
 pub mod values_impl;
 
 #[cfg(test)]
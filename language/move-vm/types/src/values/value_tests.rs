@@ -4,7 +4,11 @@
 
 use crate::{loaded_data::runtime_types::Type, values::*, views::*};
 use move_binary_format::errors::*;
-use move_core_types::{account_address::AccountAddress, u256::U256};
+use move_core_types::{
+    account_address::AccountAddress,
+    u256::U256,
+    value::{MoveEnumLayout, MoveTypeLayout},
+};
 
 #[test]
 fn locals() -> PartialVMResult<()> {
@@ -88,6 +92,73 @@ fn struct_borrow_field() -> PartialVMResult<()> {
     Ok(())
 }
 
+#[test]
+fn variant_pack_and_unpack() -> PartialVMResult<()> {
+    let v = Struct::pack_variant(1, vec![Value::u64(20), Value::bool(true)]);
+    let mut locals = Locals::new(1);
+    locals.store_loc(0, Value::struct_(v))?;
+
+    {
+        let r: StructRef = locals.borrow_loc(0)?.value_as()?;
+        assert!(r.test_variant(1)?.equals(&Value::bool(true))?);
+        assert!(r.test_variant(0)?.equals(&Value::bool(false))?);
+        assert!(r.unpack_variant_ref(0, 2).is_err());
+
+        let fields = r.unpack_variant_ref(1, 2)?;
+        let f: Reference = fields.into_iter().nth(1).unwrap().value_as()?;
+        f.write_ref(Value::bool(false))?;
+    }
+
+    let v: Struct = locals.move_loc(0)?.value_as()?;
+    let unpacked: Vec<_> = v.unpack_variant(1)?.collect();
+    assert!(unpacked.len() == 2);
+    assert!(unpacked[0].equals(&Value::u64(20))?);
+    assert!(unpacked[1].equals(&Value::bool(false))?);
+
+    let v = Struct::pack_variant(0, vec![]);
+    assert_eq!(
+        v.unpack_variant(1).err().unwrap().major_status(),
+        move_core_types::vm_status::StatusCode::VARIANT_TAG_MISMATCH
+    );
+
+    Ok(())
+}
+
+#[test]
+fn variant_serialization_matches_rust_enum() -> PartialVMResult<()> {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    enum E {
+        A,
+        B(u64, bool),
+    }
+
+    let layout = MoveTypeLayout::Enum(MoveEnumLayout::new(vec![
+        vec![],
+        vec![MoveTypeLayout::U64, MoveTypeLayout::Bool],
+    ]));
+    let values = [
+        (E::A, Struct::pack_variant(0, vec![])),
+        (
+            E::B(7, true),
+            Struct::pack_variant(1, vec![Value::u64(7), Value::bool(true)]),
+        ),
+    ];
+    for (rust_value, variant) in values {
+        let value = Value::struct_(variant);
+        let blob = bcs::to_bytes(&rust_value).unwrap();
+        assert_eq!(value.simple_serialize(&layout).unwrap(), blob);
+        assert!(Value::simple_deserialize(&blob, &layout)
+            .unwrap()
+            .equals(&value)?);
+        assert_eq!(bcs::from_bytes::<E>(&blob).unwrap(), rust_value);
+    }
+
+    // a tag without a matching variant is rejected
+    assert!(Value::simple_deserialize(&[2], &layout).is_none());
+
+    Ok(())
+}
+
 #[test]
 fn struct_borrow_nested() -> PartialVMResult<()> {
     let mut locals = Locals::new(1);
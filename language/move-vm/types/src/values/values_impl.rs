@@ -8,14 +8,14 @@ use crate::{
 };
 use move_binary_format::{
     errors::*,
-    file_format::{Constant, SignatureToken},
+    file_format::{Constant, SignatureToken, VariantTag},
 };
 use move_core_types::{
     account_address::AccountAddress,
     effects::Op,
     gas_algebra::AbstractMemorySize,
     u256,
-    value::{MoveStructLayout, MoveTypeLayout},
+    value::{MoveEnumLayout, MoveStructLayout, MoveTypeLayout},
     vm_status::{sub_status::NFE_VECTOR_ERROR_BASE, StatusCode},
 };
 use std::{
@@ -1032,6 +1032,14 @@ impl Locals {
         Ok(())
     }
 
+    /// Stores `x` in the local at `idx`, returning the value previously held there, if any.
+    pub fn replace_loc(&mut self, idx: usize, x: Value) -> PartialVMResult<Option<Value>> {
+        match self.swap_loc(idx, x)? {
+            Value(ValueImpl::Invalid) => Ok(None),
+            v => Ok(Some(v)),
+        }
+    }
+
     /// Drop all Move values onto a different Vec to avoid leaking memory.
     /// References are excluded since they may point to invalid data.
     pub fn drop_all_values(&mut self) -> impl Iterator<Item = (usize, Value)> {
@@ -2416,6 +2424,76 @@ impl Struct {
     }
 }
 
+/***************************************************************************************
+ *
+ * Variant Operations
+ *
+ *   Public APIs for enum values. An enum value is represented as a struct whose first
+ *   field is the tag of its variant, followed by the fields of that variant.
+ *
+ **************************************************************************************/
+fn variant_tag(tag: Option<&ValueImpl>) -> PartialVMResult<VariantTag> {
+    match tag {
+        Some(ValueImpl::U16(tag)) => Ok(*tag),
+        v => Err(PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR)
+            .with_message(format!("expected a variant tag, got {:?}", v))),
+    }
+}
+
+fn check_variant_tag(actual: VariantTag, expected: VariantTag) -> PartialVMResult<()> {
+    if actual != expected {
+        return Err(
+            PartialVMError::new(StatusCode::VARIANT_TAG_MISMATCH).with_message(format!(
+                "expected variant {} but found variant {}",
+                expected, actual
+            )),
+        );
+    }
+    Ok(())
+}
+
+impl Struct {
+    pub fn pack_variant<I: IntoIterator<Item = Value>>(tag: VariantTag, vals: I) -> Self {
+        Self {
+            fields: std::iter::once(ValueImpl::U16(tag))
+                .chain(vals.into_iter().map(|v| v.0))
+                .collect(),
+        }
+    }
+
+    pub fn unpack_variant(self, tag: VariantTag) -> PartialVMResult<impl Iterator<Item = Value>> {
+        let mut fields = self.fields.into_iter();
+        check_variant_tag(variant_tag(fields.next().as_ref())?, tag)?;
+        Ok(fields.map(Value))
+    }
+}
+
+impl StructRef {
+    pub fn variant_tag(&self) -> PartialVMResult<VariantTag> {
+        match self.0.container() {
+            Container::Struct(r) => variant_tag(r.borrow().first()),
+            c => Err(PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR)
+                .with_message(format!("expected an enum value, got {:?}", c))),
+        }
+    }
+
+    pub fn test_variant(&self, tag: VariantTag) -> PartialVMResult<Value> {
+        Ok(Value::bool(self.variant_tag()? == tag))
+    }
+
+    pub fn unpack_variant_ref(
+        &self,
+        tag: VariantTag,
+        field_count: usize,
+    ) -> PartialVMResult<Vec<Value>> {
+        check_variant_tag(self.variant_tag()?, tag)?;
+        // the fields of the variant follow the tag
+        (1..=field_count)
+            .map(|idx| self.borrow_field(idx))
+            .collect()
+    }
+}
+
 /***************************************************************************************
  *
  * Global Value Operations
@@ -2528,6 +2606,30 @@ impl GlobalValueImpl {
         }
     }
 
+    fn copy_value(&self) -> PartialVMResult<Self> {
+        let copy_fields = |fields: &Rc<RefCell<Vec<ValueImpl>>>| {
+            Ok(Rc::new(RefCell::new(
+                fields
+                    .borrow()
+                    .iter()
+                    .map(|v| v.copy_value())
+                    .collect::<PartialVMResult<_>>()?,
+            )))
+        };
+
+        Ok(match self {
+            Self::None => Self::None,
+            Self::Deleted => Self::Deleted,
+            Self::Fresh { fields } => Self::Fresh {
+                fields: copy_fields(fields)?,
+            },
+            Self::Cached { fields, status } => Self::Cached {
+                fields: copy_fields(fields)?,
+                status: Rc::new(RefCell::new(*status.borrow())),
+            },
+        })
+    }
+
     fn is_mutated(&self) -> bool {
         match self {
             Self::None => false,
@@ -2577,6 +2679,11 @@ impl GlobalValue {
     pub fn is_mutated(&self) -> bool {
         self.0.is_mutated()
     }
+
+    /// Makes a deep copy of the slot, which shares no data with the original.
+    pub fn copy_value(&self) -> PartialVMResult<Self> {
+        Ok(Self(self.0.copy_value()?))
+    }
 }
 
 /***************************************************************************************
@@ -2906,9 +3013,10 @@ pub mod debug {
  *   is to involve an explicit representation of the type layout.
  *
  **************************************************************************************/
+use move_core_types::value::{MOVE_ENUM_NAME, MOVE_VARIANT_NAME};
 use serde::{
-    de::Error as DeError,
-    ser::{Error as SerError, SerializeSeq, SerializeTuple},
+    de::{Error as DeError, VariantAccess},
+    ser::{Error as SerError, SerializeSeq, SerializeTuple, SerializeTupleVariant},
     Deserialize,
 };
 
@@ -2971,6 +3079,14 @@ impl<'a, 'b> serde::Serialize for AnnotatedValue<'a, 'b, MoveTypeLayout, ValueIm
                 .serialize(serializer)
             }
 
+            (MoveTypeLayout::Enum(enum_layout), ValueImpl::Container(Container::Struct(r))) => {
+                (AnnotatedValue {
+                    layout: enum_layout,
+                    val: &*r.borrow(),
+                })
+                .serialize(serializer)
+            }
+
             (MoveTypeLayout::Vector(layout), ValueImpl::Container(c)) => {
                 let layout = &**layout;
                 match (layout, c) {
@@ -3051,6 +3167,42 @@ impl<'a, 'b> serde::Serialize for AnnotatedValue<'a, 'b, MoveStructLayout, Vec<V
     }
 }
 
+impl<'a, 'b> serde::Serialize for AnnotatedValue<'a, 'b, MoveEnumLayout, Vec<ValueImpl>> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (tag, values) = match self.val.split_first() {
+            Some((ValueImpl::U16(tag), values)) => (*tag, values),
+            _ => {
+                return Err(invariant_violation::<S>(format!(
+                    "cannot serialize enum value {:?} as {:?} -- missing variant tag",
+                    self.val, self.layout
+                )))
+            }
+        };
+        let fields = match self.layout.variants().get(tag as usize) {
+            Some(fields) if fields.len() == values.len() => fields,
+            _ => {
+                return Err(invariant_violation::<S>(format!(
+                    "cannot serialize enum value {:?} as {:?} -- variant mismatch",
+                    self.val, self.layout
+                )))
+            }
+        };
+        let mut t = serializer.serialize_tuple_variant(
+            MOVE_ENUM_NAME,
+            tag as u32,
+            MOVE_VARIANT_NAME,
+            values.len(),
+        )?;
+        for (field_layout, val) in fields.iter().zip(values.iter()) {
+            t.serialize_field(&AnnotatedValue {
+                layout: field_layout,
+                val,
+            })?;
+        }
+        t.end()
+    }
+}
+
 #[derive(Clone)]
 struct SeedWrapper<L> {
     layout: L,
@@ -3083,6 +3235,13 @@ impl<'d> serde::de::DeserializeSeed<'d> for SeedWrapper<&MoveTypeLayout> {
                 .deserialize(deserializer)?,
             )),
 
+            L::Enum(enum_layout) => Ok(Value::struct_(
+                SeedWrapper {
+                    layout: enum_layout,
+                }
+                .deserialize(deserializer)?,
+            )),
+
             L::Vector(layout) => {
                 let container = match &**layout {
                     L::U8 => {
@@ -3135,6 +3294,51 @@ impl<'d> serde::de::DeserializeSeed<'d> for SeedWrapper<&MoveStructLayout> {
     }
 }
 
+impl<'d> serde::de::DeserializeSeed<'d> for SeedWrapper<&MoveEnumLayout> {
+    type Value = Struct;
+
+    fn deserialize<D: serde::de::Deserializer<'d>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        let fields =
+            deserializer.deserialize_enum(MOVE_ENUM_NAME, &[], EnumVisitor(self.layout))?;
+        Ok(Struct::pack(fields))
+    }
+}
+
+struct EnumVisitor<'a>(&'a MoveEnumLayout);
+
+impl<'d, 'a> serde::de::Visitor<'d> for EnumVisitor<'a> {
+    type Value = Vec<Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("Enum")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::EnumAccess<'d>,
+    {
+        let (tag, variant): (u32, _) = data.variant()?;
+        let field_layouts = match self.0.variants().get(tag as usize) {
+            Some(field_layouts) => field_layouts,
+            None => {
+                return Err(A::Error::custom(format!(
+                    "invalid variant tag {} for enum with {} variants",
+                    tag,
+                    self.0.variants().len()
+                )))
+            }
+        };
+        let fields =
+            variant.tuple_variant(field_layouts.len(), StructFieldVisitor(field_layouts))?;
+        Ok(std::iter::once(Value::u16(tag as VariantTag))
+            .chain(fields)
+            .collect())
+    }
+}
+
 struct VectorElementVisitor<'a>(SeedWrapper<&'a MoveTypeLayout>);
 
 impl<'d, 'a> serde::de::Visitor<'d> for VectorElementVisitor<'a> {
@@ -3591,6 +3795,19 @@ pub mod prop {
                 .collect::<Vec<_>>()
                 .prop_map(move |vals| Value::struct_(Struct::pack(vals)))
                 .boxed(),
+
+            L::Enum(enum_layout) => {
+                let variants = enum_layout.variants().to_vec();
+                (0..variants.len() as u16)
+                    .prop_flat_map(move |tag| {
+                        variants[tag as usize]
+                            .iter()
+                            .map(value_strategy_with_layout)
+                            .collect::<Vec<_>>()
+                            .prop_map(move |vals| Value::struct_(Struct::pack_variant(tag, vals)))
+                    })
+                    .boxed()
+            }
         }
     }
 
@@ -3612,8 +3829,10 @@ pub mod prop {
         leaf.prop_recursive(8, 32, 2, |inner| {
             prop_oneof![
                 1 => inner.clone().prop_map(|layout| L::Vector(Box::new(layout))),
-                1 => vec(inner, 0..1).prop_map(|f_layouts| {
+                1 => vec(inner.clone(), 0..1).prop_map(|f_layouts| {
                      L::Struct(MoveStructLayout::new(f_layouts))}),
+                1 => vec(vec(inner, 0..2), 1..3).prop_map(|variants| {
+                     L::Enum(MoveEnumLayout::new(variants))}),
             ]
         })
     }
@@ -3626,7 +3845,7 @@ pub mod prop {
     }
 }
 
-use move_core_types::value::{MoveStruct, MoveValue};
+use move_core_types::value::{MoveStruct, MoveValue, MoveVariant};
 
 impl ValueImpl {
     pub fn as_move_value(&self, layout: &MoveTypeLayout) -> MoveValue {
@@ -3650,6 +3869,22 @@ impl ValueImpl {
                 MoveValue::Struct(MoveStruct::new(fields))
             }
 
+            (L::Enum(enum_layout), ValueImpl::Container(Container::Struct(r))) => {
+                let v = r.borrow();
+                let tag = match v.first() {
+                    Some(ValueImpl::U16(tag)) => *tag,
+                    v => panic!("Unexpected variant tag while converting enum: {:?}", v),
+                };
+                let mut fields = vec![];
+                for (v, field_layout) in v[1..]
+                    .iter()
+                    .zip(enum_layout.variants()[tag as usize].iter())
+                {
+                    fields.push(v.as_move_value(field_layout));
+                }
+                MoveValue::Variant(MoveVariant::new(tag, fields))
+            }
+
             (L::Vector(inner_layout), ValueImpl::Container(c)) => MoveValue::Vector(match c {
                 Container::VecU8(r) => r.borrow().iter().map(|u| MoveValue::U8(*u)).collect(),
                 Container::VecU16(r) => r.borrow().iter().map(|u| MoveValue::U16(*u)).collect(),
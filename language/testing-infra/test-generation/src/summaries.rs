@@ -617,5 +617,22 @@ pub fn instruction_summary(instruction: Bytecode, exact: bool) -> Summary {
         | Bytecode::VecPopBack(_)
         | Bytecode::VecUnpack(..)
         | Bytecode::VecSwap(_) => unimplemented!("Vector bytecode not supported yet"),
+        Bytecode::PackVariant(_)
+        | Bytecode::PackVariantGeneric(_)
+        | Bytecode::UnpackVariant(_)
+        | Bytecode::UnpackVariantGeneric(_)
+        | Bytecode::UnpackVariantImmRef(_)
+        | Bytecode::UnpackVariantImmRefGeneric(_)
+        | Bytecode::UnpackVariantMutRef(_)
+        | Bytecode::UnpackVariantMutRefGeneric(_)
+        | Bytecode::TestVariant(_)
+        | Bytecode::TestVariantGeneric(_) => Summary {
+            // Enums are not generated yet, so these instructions are never selected, and fail
+            // if applied nonetheless
+            preconditions: vec![state_never!()],
+            effects: Effects::NoTyParams(vec![Box::new(|_| {
+                Err(VMError::new("Enum bytecode not supported yet".to_string()))
+            })]),
+        },
     }
 }
@@ -0,0 +1,35 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+extern crate test_generation;
+use move_binary_format::file_format::{Bytecode, VariantHandleIndex};
+use test_generation::{
+    abstract_state::AbstractState,
+    summaries::{instruction_summary, Effects},
+};
+
+#[test]
+fn bytecode_enum_instructions_are_not_generated() {
+    let state = AbstractState::new();
+    for instruction in [
+        Bytecode::PackVariant(VariantHandleIndex::new(0)),
+        Bytecode::UnpackVariant(VariantHandleIndex::new(0)),
+        Bytecode::TestVariant(VariantHandleIndex::new(0)),
+    ] {
+        let summary = instruction_summary(instruction, false);
+        assert!(
+            summary
+                .preconditions
+                .iter()
+                .any(|precondition| !precondition(&state)),
+            "preconditions of enum instruction satisfied"
+        );
+        match summary.effects {
+            Effects::NoTyParams(effects) => assert!(
+                effects.iter().any(|effect| effect(&state).is_err()),
+                "effects of enum instruction applied"
+            ),
+            _ => panic!("unexpected effects of enum instruction"),
+        }
+    }
+}
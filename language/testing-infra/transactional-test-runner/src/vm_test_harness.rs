@@ -87,6 +87,10 @@ pub struct AdapterPublishArgs {
 pub struct AdapterExecuteArgs {
     #[clap(long)]
     pub check_runtime_types: bool,
+    /// Only accept the standard library structs `String` and `Option` as struct arguments, and
+    /// validate their values.
+    #[clap(long)]
+    pub check_struct_args: bool,
 }
 
 impl<'a> MoveTestAdapter<'a> for SimpleVMTestAdapter<'a> {
@@ -428,6 +432,9 @@ impl From<AdapterExecuteArgs> for VMConfig {
     fn from(arg: AdapterExecuteArgs) -> VMConfig {
         VMConfig {
             paranoid_type_checks: arg.check_runtime_types,
+            entry_struct_args: arg
+                .check_struct_args
+                .then(|| move_stdlib::entry_args::entry_struct_args(STD_ADDR)),
             ..Default::default()
         }
     }
@@ -1,7 +1,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use super::reroot_path;
+use super::{reroot_path, reroot_workspace_path, WorkspaceArgs};
 use clap::*;
 use move_package::{Architecture, BuildConfig};
 use std::path::PathBuf;
@@ -9,10 +9,16 @@ use std::path::PathBuf;
 /// Build the package at `path`. If no path is provided defaults to current directory.
 #[derive(Parser)]
 #[clap(name = "build")]
-pub struct Build;
+pub struct Build {
+    #[clap(flatten)]
+    pub workspace: WorkspaceArgs,
+}
 
 impl Build {
     pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
+        if self.workspace.is_set() {
+            return Self::execute_on_workspace(path, config, &self.workspace.members);
+        }
         let rerooted_path = reroot_path(path)?;
         if config.fetch_deps_only {
             let mut config = config;
@@ -39,4 +45,21 @@ impl Build {
         }
         Ok(())
     }
+
+    fn execute_on_workspace(
+        path: Option<PathBuf>,
+        config: BuildConfig,
+        members: &[String],
+    ) -> anyhow::Result<()> {
+        let rerooted_path = reroot_workspace_path(path)?;
+        if config.fetch_deps_only {
+            config.resolve_workspace(&rerooted_path, members, &mut std::io::stdout())?;
+            return Ok(());
+        }
+        if let Some(Architecture::Ethereum) = config.architecture {
+            anyhow::bail!("Workspaces can only be built for the Move architectures");
+        }
+        config.compile_workspace(&rerooted_path, members, &mut std::io::stdout())?;
+        Ok(())
+    }
 }
@@ -0,0 +1,81 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use anyhow::{bail, Context};
+use clap::*;
+use move_command_line_common::files::find_move_filenames;
+use move_formatter::{format_file, FormatConfig};
+use move_package::source_package::layout::SourcePackageLayout;
+use std::path::PathBuf;
+
+/// Format the Move source files of the package at `path`. If no path is provided defaults to
+/// current directory. The formatter is configured by a `.movefmt.toml` file in the package root, or
+/// by the `[fmt]` section of the package's manifest.
+#[derive(Parser)]
+#[clap(name = "fmt")]
+pub struct Fmt {
+    /// Check that the files are formatted, without modifying them. Exits with a non-zero status
+    /// if any file would be changed by formatting.
+    #[clap(long = "check")]
+    pub check: bool,
+}
+
+impl Fmt {
+    pub fn execute(self, path: Option<PathBuf>) -> anyhow::Result<()> {
+        let rerooted_path = reroot_path(path)?;
+        let config = FormatConfig::from_package_root(&rerooted_path)?;
+        let dirs: Vec<PathBuf> = [
+            SourcePackageLayout::Sources,
+            SourcePackageLayout::Scripts,
+            SourcePackageLayout::Examples,
+            SourcePackageLayout::Tests,
+        ]
+        .iter()
+        .map(|dir| rerooted_path.join(dir.path()))
+        .filter(|dir| dir.exists())
+        .collect();
+        let mut files = find_move_filenames(&dirs, false)?;
+        files.sort();
+
+        // Keep going after a file fails to format, so that the other files are still formatted
+        let mut unformatted = 0;
+        let mut errors = vec![];
+        for file in files {
+            match self.format(&file, &config) {
+                Ok(true) => (),
+                Ok(false) => unformatted += 1,
+                Err(err) => errors.push(err),
+            }
+        }
+
+        if !errors.is_empty() {
+            for err in &errors {
+                eprintln!("{:#}", err);
+            }
+            bail!("Failed to format {} file(s)", errors.len())
+        }
+        // Return a non-zero exit code if any file is not formatted
+        if unformatted > 0 {
+            std::process::exit(1)
+        }
+        Ok(())
+    }
+
+    /// Formats `file`, or only checks it with `--check`. Returns false if the file is left
+    /// unformatted
+    fn format(&self, file: &str, config: &FormatConfig) -> anyhow::Result<bool> {
+        let source = std::fs::read_to_string(file)
+            .with_context(|| format!("Error reading '{}'", file))?;
+        let formatted = format_file(file, &source, config)?;
+        if formatted == source {
+            return Ok(true);
+        }
+        if self.check {
+            println!("{} is not formatted", file);
+            return Ok(false);
+        }
+        std::fs::write(file, formatted).with_context(|| format!("Error writing '{}'", file))?;
+        Ok(true)
+    }
+}
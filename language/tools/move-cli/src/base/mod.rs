@@ -6,14 +6,35 @@ pub mod coverage;
 pub mod disassemble;
 pub mod docgen;
 pub mod errmap;
+pub mod fmt;
 pub mod info;
 pub mod new;
 pub mod prove;
 pub mod test;
 
+use clap::Parser;
 use move_package::source_package::layout::SourcePackageLayout;
 use std::path::PathBuf;
 
+/// Options running a command on the members of the workspace containing the package path, rather
+/// than on the package at that path.
+#[derive(Parser, Debug, Default)]
+pub struct WorkspaceArgs {
+    /// Run the command on every member of the workspace
+    #[clap(long = "workspace")]
+    pub workspace: bool,
+    /// Run the command on the given member of the workspace. Can be repeated
+    #[clap(long = "package", value_name = "MEMBER")]
+    pub members: Vec<String>,
+}
+
+impl WorkspaceArgs {
+    /// Whether the command runs on the members of a workspace
+    pub fn is_set(&self) -> bool {
+        self.workspace || !self.members.is_empty()
+    }
+}
+
 pub fn reroot_path(path: Option<PathBuf>) -> anyhow::Result<PathBuf> {
     let path = path.unwrap_or_else(|| PathBuf::from("."));
     // Always root ourselves to the package root, and then compile relative to that.
@@ -22,3 +43,12 @@ pub fn reroot_path(path: Option<PathBuf>) -> anyhow::Result<PathBuf> {
 
     Ok(PathBuf::from("."))
 }
+
+pub fn reroot_workspace_path(path: Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    let path = path.unwrap_or_else(|| PathBuf::from("."));
+    // Like `reroot_path`, but to the root of the workspace containing the path.
+    let rooted_path = SourcePackageLayout::try_find_workspace_root(&path.canonicalize()?)?;
+    std::env::set_current_dir(&rooted_path).unwrap();
+
+    Ok(PathBuf::from("."))
+}
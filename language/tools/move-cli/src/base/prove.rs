@@ -1,12 +1,13 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use super::reroot_path;
+use super::{reroot_path, reroot_workspace_path, WorkspaceArgs};
 use anyhow::bail;
 use clap::Parser;
 use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 use colored::Colorize;
-use move_package::{BuildConfig, ModelConfig};
+use move_model::model::GlobalEnv;
+use move_package::{compilation::model_builder::ModelBuilder, BuildConfig, ModelConfig};
 use move_prover::run_move_prover_with_model;
 use std::{
     io::Write,
@@ -42,37 +43,62 @@ pub struct Prove {
     /// Any options passed to the prover.
     #[clap(subcommand)]
     pub options: Option<ProverOptions>,
+    #[clap(flatten)]
+    pub workspace: WorkspaceArgs,
 }
 
 impl Prove {
     pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
-        let rerooted_path = reroot_path(path)?;
         let Self {
             target_filter,
             for_test,
             options,
+            workspace,
         } = self;
         let opts = match options {
             Some(ProverOptions::Options(opts)) => opts,
             _ => vec![],
         };
-        let mut args = vec!["package".to_string()];
-        let prover_toml = Path::new(&rerooted_path).join("Prover.toml");
-        if prover_toml.exists() {
-            args.push(format!("--config={}", prover_toml.to_string_lossy()));
-        }
-        args.extend(opts.iter().cloned());
-        let options = move_prover::cli::Options::create_from_args(&args)?;
-        if for_test {
-            options.setup_logging_for_test();
-        } else {
-            options.setup_logging();
+        if workspace.is_set() {
+            let rerooted_path = reroot_workspace_path(path)?;
+            return run_move_prover_on_workspace(
+                config,
+                &rerooted_path,
+                &workspace.members,
+                &target_filter,
+                for_test,
+                &opts,
+            );
         }
+        let rerooted_path = reroot_path(path)?;
+        let options = prover_options(&rerooted_path, &opts, for_test)?;
 
         run_move_prover(config, &rerooted_path, &target_filter, for_test, options)
     }
 }
 
+/// The options of the prover for the package at `path`, read from its `Prover.toml` if it has
+/// one, and then from `opts`.
+fn prover_options(
+    path: &Path,
+    opts: &[String],
+    for_test: bool,
+) -> anyhow::Result<move_prover::cli::Options> {
+    let mut args = vec!["package".to_string()];
+    let prover_toml = path.join("Prover.toml");
+    if prover_toml.exists() {
+        args.push(format!("--config={}", prover_toml.to_string_lossy()));
+    }
+    args.extend(opts.iter().cloned());
+    let options = move_prover::cli::Options::create_from_args(&args)?;
+    if for_test {
+        options.setup_logging_for_test();
+    } else {
+        options.setup_logging();
+    }
+    Ok(options)
+}
+
 // =================================================================================================
 // API for Rust unit tests
 
@@ -126,6 +152,7 @@ impl ProverTest {
             target_filter: None,
             for_test: true,
             options: Some(ProverOptions::Options(std::mem::take(&mut self.options))),
+            workspace: WorkspaceArgs::default(),
         };
         let res = cmd.execute(Some(pkg_path), move_package::BuildConfig::default());
         std::env::set_current_dir(saved_cd).expect("restore current directory");
@@ -159,11 +186,56 @@ pub fn run_move_prover(
     path: &Path,
     target_filter: &Option<String>,
     for_test: bool,
-    mut options: move_prover::cli::Options,
+    options: move_prover::cli::Options,
 ) -> anyhow::Result<()> {
     // Always run the prover in dev mode, so addresses get default assignments
     config.dev_mode = true;
+    check_prover_options(&options)?;
+
+    let now = Instant::now();
+    let model = config.move_model_for_package(
+        path,
+        ModelConfig {
+            all_files_as_targets: false,
+            target_filter: target_filter.clone(),
+        },
+    )?;
+    run_move_prover_on_model(&model, path, for_test, options, now)
+}
+
+/// Runs the prover on the `members` of the workspace at `path`, or on all of its members if none
+/// are given, stopping at the first one that fails. The packages of the workspace are resolved
+/// together.
+pub fn run_move_prover_on_workspace(
+    mut config: BuildConfig,
+    path: &Path,
+    members: &[String],
+    target_filter: &Option<String>,
+    for_test: bool,
+    opts: &[String],
+) -> anyhow::Result<()> {
+    config.dev_mode = true;
+    let workspace = config.resolve_workspace(path, members, &mut Vec::new())?;
+    for resolution_graph in workspace.resolved_graphs {
+        let pkg_path = resolution_graph.root_package_path.clone();
+        let options = prover_options(&pkg_path, opts, for_test)?;
+        check_prover_options(&options)?;
 
+        let now = Instant::now();
+        let model = ModelBuilder::create(
+            resolution_graph,
+            ModelConfig {
+                all_files_as_targets: false,
+                target_filter: target_filter.clone(),
+            },
+        )
+        .build_model()?;
+        run_move_prover_on_model(&model, &pkg_path, for_test, options, now)?;
+    }
+    Ok(())
+}
+
+fn check_prover_options(options: &move_prover::cli::Options) -> anyhow::Result<()> {
     if !options.move_sources.is_empty() {
         bail!(
             "move prover options must not specify sources as those are given \
@@ -183,20 +255,21 @@ pub fn run_move_prover(
                      by the package system"
         );
     }
+    Ok(())
+}
 
+fn run_move_prover_on_model(
+    model: &GlobalEnv,
+    path: &Path,
+    for_test: bool,
+    mut options: move_prover::cli::Options,
+    now: Instant,
+) -> anyhow::Result<()> {
     let mut message_writer = StandardStream::stdout(ColorChoice::Auto);
     let mut error_writer = StandardStream::stderr(ColorChoice::Auto);
     if for_test {
         options.set_quiet();
     }
-    let now = Instant::now();
-    let model = config.move_model_for_package(
-        path,
-        ModelConfig {
-            all_files_as_targets: false,
-            target_filter: target_filter.clone(),
-        },
-    )?;
     let _temp_dir_holder = if for_test {
         // Need to ensure a distinct output.bpl file for concurrent execution. In non-test
         // mode, we actually want to use the static output.bpl for debugging purposes
@@ -211,7 +284,7 @@ pub fn run_move_prover(
     } else {
         None
     };
-    let res = run_move_prover_with_model(&model, &mut error_writer, options, Some(now));
+    let res = run_move_prover_with_model(model, &mut error_writer, options, Some(now));
     if for_test {
         let basedir = path
             .file_name()
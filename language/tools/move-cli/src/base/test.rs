@@ -1,9 +1,9 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use super::reroot_path;
+use super::{reroot_path, reroot_workspace_path, WorkspaceArgs};
 use crate::NativeFunctionRecord;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::*;
 use move_command_line_common::files::{FileHash, MOVE_COVERAGE_MAP_EXTENSION};
 use move_compiler::{
@@ -12,8 +12,15 @@ use move_compiler::{
     unit_test::{plan_builder::construct_test_plan, TestPlan},
     PASS_CFGIR,
 };
-use move_coverage::coverage_map::{output_map_to_file, CoverageMap};
-use move_package::{compilation::build_plan::BuildPlan, BuildConfig};
+use move_coverage::coverage_map::output_map_to_file;
+use move_debugger::{run_debug_adapter, DebugSources};
+use move_gas_profiler::GasProfile;
+use move_package::{
+    compilation::{build_plan::BuildPlan, package_layout::CompiledPackageLayout},
+    resolution::resolution_graph::ResolvedGraph,
+    source_package::parsed_manifest::PackageName,
+    BuildConfig,
+};
 use move_unit_test::UnitTestingConfig;
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
@@ -33,6 +40,9 @@ use std::os::unix::prelude::ExitStatusExt;
 #[cfg(not(any(target_family = "windows", target_family = "unix")))]
 compile_error!("Unsupported OS, currently we only support windows and unix family");
 
+/// The directory of the build output gas profiles are written to.
+pub const GAS_PROFILES_DIR: &str = "gas_profiles";
+
 /// Run Move unit tests in this package.
 #[derive(Parser)]
 #[clap(name = "test")]
@@ -77,6 +87,19 @@ pub struct Test {
     #[clap(long = "coverage")]
     pub compute_coverage: bool,
 
+    /// Debug the single test selected by the filter, serving the Debug Adapter Protocol on stdin
+    /// and stdout.
+    #[clap(long = "debug", requires = "filter")]
+    pub debug: bool,
+
+    /// Profile the gas usage of the tests, writing a flamegraph-compatible folded stacks file and a
+    /// JSON and HTML summary for each test to `build/gas_profiles`.
+    #[clap(long = "profile-gas", conflicts_with = "debug")]
+    pub profile_gas: bool,
+
+    #[clap(flatten)]
+    pub workspace: WorkspaceArgs,
+
     /// Use the EVM-based execution backend.
     /// Does not work with --stackless.
     #[cfg(feature = "evm-backend")]
@@ -92,7 +115,11 @@ impl Test {
         natives: Vec<NativeFunctionRecord>,
         cost_table: Option<CostTable>,
     ) -> anyhow::Result<()> {
-        let rerooted_path = reroot_path(path)?;
+        let rerooted_path = if self.workspace.is_set() {
+            reroot_workspace_path(path)?
+        } else {
+            reroot_path(path)?
+        };
         let Self {
             gas_limit,
             filter,
@@ -104,6 +131,9 @@ impl Test {
             check_stackless_vm,
             verbose_mode,
             compute_coverage,
+            debug,
+            profile_gas,
+            workspace,
             #[cfg(feature = "evm-backend")]
             evm,
         } = self;
@@ -122,15 +152,50 @@ impl Test {
 
             ..UnitTestingConfig::default_with_bound(None)
         };
-        let result = run_move_unit_tests(
-            &rerooted_path,
-            config,
-            unit_test_config,
-            natives,
-            cost_table,
-            compute_coverage,
-            &mut std::io::stdout(),
-        )?;
+        if workspace.is_set() && (debug || profile_gas) {
+            bail!("--debug and --profile-gas only apply to the tests of a single package");
+        }
+        if debug {
+            return debug_move_unit_test(
+                &rerooted_path,
+                config,
+                unit_test_config,
+                natives,
+                cost_table,
+            );
+        }
+        if profile_gas {
+            return profile_move_unit_tests(
+                &rerooted_path,
+                config,
+                unit_test_config,
+                natives,
+                cost_table,
+                &mut std::io::stdout(),
+            );
+        }
+        let result = if workspace.is_set() {
+            run_workspace_unit_tests(
+                &rerooted_path,
+                &workspace.members,
+                config,
+                unit_test_config,
+                natives,
+                cost_table,
+                compute_coverage,
+                &mut std::io::stdout(),
+            )?
+        } else {
+            run_move_unit_tests(
+                &rerooted_path,
+                config,
+                unit_test_config,
+                natives,
+                cost_table,
+                compute_coverage,
+                &mut std::io::stdout(),
+            )?
+        };
 
         // Return a non-zero exit code if any test failed
         if let UnitTestResult::Failure = result {
@@ -149,20 +214,260 @@ pub enum UnitTestResult {
 
 pub fn run_move_unit_tests<W: Write + Send>(
     pkg_path: &Path,
-    mut build_config: move_package::BuildConfig,
+    build_config: move_package::BuildConfig,
     mut unit_test_config: UnitTestingConfig,
     natives: Vec<NativeFunctionRecord>,
     cost_table: Option<CostTable>,
     compute_coverage: bool,
     writer: &mut W,
 ) -> Result<UnitTestResult> {
-    let mut test_plan = None;
+    let test_plan = build_test_plan(pkg_path, build_config, &mut unit_test_config, writer)?;
+    run_test_plan(
+        pkg_path,
+        test_plan,
+        unit_test_config,
+        natives,
+        cost_table,
+        compute_coverage,
+        writer,
+    )
+}
+
+/// Runs the unit tests of the `members` of the workspace at `path` or containing it, or of all of
+/// its members if none are given. The packages of the workspace are resolved together.
+pub fn run_workspace_unit_tests<W: Write + Send>(
+    path: &Path,
+    members: &[String],
+    mut build_config: move_package::BuildConfig,
+    unit_test_config: UnitTestingConfig,
+    natives: Vec<NativeFunctionRecord>,
+    cost_table: Option<CostTable>,
+    compute_coverage: bool,
+    writer: &mut W,
+) -> Result<UnitTestResult> {
+    build_config.test_mode = true;
+    build_config.dev_mode = true;
+    let workspace = build_config.resolve_workspace(path, members, &mut Vec::new())?;
+
+    let mut result = UnitTestResult::Success;
+    for resolution_graph in workspace.resolved_graphs {
+        let pkg_path = resolution_graph.root_package_path.clone();
+        let mut unit_test_config = unit_test_config.clone();
+        let test_plan = build_test_plan_for_graph(
+            resolution_graph,
+            &workspace.members,
+            &mut unit_test_config,
+            writer,
+        )?;
+        let member_result = run_test_plan(
+            &pkg_path,
+            test_plan,
+            unit_test_config,
+            natives.clone(),
+            cost_table.clone(),
+            compute_coverage,
+            writer,
+        )?;
+        if let UnitTestResult::Failure = member_result {
+            result = UnitTestResult::Failure;
+        }
+    }
+    Ok(result)
+}
+
+/// Runs the tests of `test_plan`, computing their coverage in the package at `pkg_path` if
+/// `compute_coverage` is set.
+fn run_test_plan<W: Write + Send>(
+    pkg_path: &Path,
+    test_plan: TestPlan,
+    unit_test_config: UnitTestingConfig,
+    natives: Vec<NativeFunctionRecord>,
+    cost_table: Option<CostTable>,
+    compute_coverage: bool,
+    writer: &mut W,
+) -> Result<UnitTestResult> {
+    let no_tests = test_plan.module_tests.is_empty();
+
+    let coverage_map_path = pkg_path
+        .join(".coverage_map")
+        .with_extension(MOVE_COVERAGE_MAP_EXTENSION);
+
+    // Run the tests, tracing their coverage if needed. If any of the tests fail, then we don't
+    // produce a coverage report.
+    let (ok, coverage_map) = if compute_coverage {
+        let (_, ok, coverage_map) = unit_test_config
+            .run_and_report_unit_tests_with_coverage(test_plan, Some(natives), cost_table, writer)
+            .unwrap();
+        (ok, Some(coverage_map))
+    } else {
+        let (_, ok) = unit_test_config
+            .run_and_report_unit_tests(test_plan, Some(natives), cost_table, writer)
+            .unwrap();
+        (ok, None)
+    };
+    if !ok {
+        return Ok(UnitTestResult::Failure);
+    }
+
+    // Save the coverage map. This will be used by other commands after this.
+    if let Some(coverage_map) = coverage_map {
+        if !no_tests {
+            output_map_to_file(&coverage_map_path, &coverage_map).unwrap();
+        }
+    }
+    Ok(UnitTestResult::Success)
+}
+
+/// Runs the unit test selected by the filter of `unit_test_config` under a debug adapter serving
+/// the Debug Adapter Protocol on stdin and stdout. Build output goes to stderr.
+pub fn debug_move_unit_test(
+    pkg_path: &Path,
+    build_config: move_package::BuildConfig,
+    mut unit_test_config: UnitTestingConfig,
+    natives: Vec<NativeFunctionRecord>,
+    cost_table: Option<CostTable>,
+) -> Result<()> {
+    let test_name = match unit_test_config.filter.clone() {
+        Some(test_name) => test_name,
+        None => bail!("The test to debug must be given as <module_name>::<fn_name>"),
+    };
+    let test_plan = build_test_plan(
+        pkg_path,
+        build_config,
+        &mut unit_test_config,
+        &mut std::io::stderr(),
+    )?;
+
+    let sources = test_sources(&test_plan);
+    let stdin = std::io::stdin();
+    run_debug_adapter(
+        stdin.lock(),
+        std::io::stdout(),
+        &sources,
+        |tracer| match unit_test_config.run_unit_test_with_tracer(
+            test_plan,
+            Some(natives),
+            cost_table,
+            &test_name,
+            tracer,
+        ) {
+            Some(Ok(_)) => Ok(format!("{} returned", test_name)),
+            Some(Err(err)) => Err(format!("{} failed: {}", test_name, err)),
+            None => Err(format!("No test named {}", test_name)),
+        },
+    )
+}
+
+/// Runs the unit tests selected by the filter of `unit_test_config` under a gas profiler, and
+/// writes the gas profile of each test to the `gas_profiles` directory of the build output.
+pub fn profile_move_unit_tests<W: Write>(
+    pkg_path: &Path,
+    build_config: move_package::BuildConfig,
+    mut unit_test_config: UnitTestingConfig,
+    natives: Vec<NativeFunctionRecord>,
+    cost_table: Option<CostTable>,
+    writer: &mut W,
+) -> Result<()> {
+    let profile_dir = build_config
+        .install_dir
+        .clone()
+        .unwrap_or_else(|| pkg_path.to_path_buf())
+        .join(CompiledPackageLayout::Root.path())
+        .join(GAS_PROFILES_DIR);
+    let test_plan = build_test_plan(pkg_path, build_config, &mut unit_test_config, writer)?;
+    let sources = test_sources(&test_plan);
+
+    writeln!(writer, "Profiling Move unit tests")?;
+    for (test_name, result, profile) in
+        unit_test_config.profile_unit_tests(test_plan, Some(natives), cost_table)
+    {
+        let status = match result {
+            Ok(_) => "returned".to_string(),
+            Err(err) => format!("aborted with {:?}", err.major_status()),
+        };
+        let folded_path = write_gas_profile(&profile, &sources, &profile_dir, &test_name)?;
+        writeln!(
+            writer,
+            "{} {} using {} gas, profile written to {}",
+            test_name,
+            status,
+            profile.total_gas(),
+            folded_path.display()
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes `profile` to `dir` as `<name>.folded`, in the folded stacks format read by flamegraph
+/// tools, and as `<name>.json` and `<name>.html` summaries. Code locations are attributed to
+/// source lines using `sources`. Returns the path of the folded stacks file.
+pub fn write_gas_profile(
+    profile: &GasProfile,
+    sources: &DebugSources,
+    dir: &Path,
+    name: &str,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let summary = profile.summary(|location| {
+        let position =
+            sources.position(location.module_id.as_ref(), location.function, location.pc)?;
+        let path = sources.path(&position.file_hash)?;
+        Some(format!("{}:{}", path.display(), position.line))
+    });
+    let file_name = name.replace("::", "-");
+    let folded_path = dir.join(&file_name).with_extension("folded");
+    fs::write(&folded_path, profile.folded_stacks())?;
+    fs::write(
+        dir.join(&file_name).with_extension("json"),
+        summary.to_json(),
+    )?;
+    fs::write(
+        dir.join(&file_name).with_extension("html"),
+        summary.to_html(&format!("Gas profile of {}", name)),
+    )?;
+    Ok(folded_path)
+}
+
+/// Collects the source maps and source files of the modules in `test_plan`.
+fn test_sources(test_plan: &TestPlan) -> DebugSources {
+    let mut sources = DebugSources::new();
+    for (path, contents) in test_plan.files.values() {
+        sources.add_file(Path::new(path.as_str()), contents);
+    }
+    for (module_id, module) in &test_plan.module_info {
+        sources.add_module(module_id.clone(), module.source_map.clone());
+    }
+    sources
+}
+
+/// Compiles the package at `pkg_path` in test mode and builds the plan of its unit tests. The
+/// named addresses of the package are added to `unit_test_config`.
+fn build_test_plan<W: Write>(
+    pkg_path: &Path,
+    mut build_config: move_package::BuildConfig,
+    unit_test_config: &mut UnitTestingConfig,
+    writer: &mut W,
+) -> Result<TestPlan> {
     build_config.test_mode = true;
     build_config.dev_mode = true;
 
     // Build the resolution graph (resolution graph diagnostics are only needed for CLI commands so
     // ignore them by passing a vector as the writer)
     let resolution_graph = build_config.resolution_graph_for_package(pkg_path, &mut Vec::new())?;
+    build_test_plan_for_graph(resolution_graph, &[], unit_test_config, writer)
+}
+
+/// Compiles the root package of `resolution_graph`, resolved in test mode, and builds the plan of
+/// its unit tests. The build output of the `workspace_members` sharing its build directory is
+/// kept.
+fn build_test_plan_for_graph<W: Write>(
+    resolution_graph: ResolvedGraph,
+    workspace_members: &[PackageName],
+    unit_test_config: &mut UnitTestingConfig,
+    writer: &mut W,
+) -> Result<TestPlan> {
+    let mut test_plan = None;
+    let diagnostics_format = resolution_graph.build_options.diagnostics_format;
 
     // Note: unit_test_config.named_address_values is always set to vec![] (the default value) before
     // being passed in.
@@ -194,15 +499,19 @@ pub fn run_move_unit_tests<W: Write + Send>(
         })
         .collect();
     let root_package = resolution_graph.root_package.package.name;
-    let build_plan = BuildPlan::create(resolution_graph)?;
+    let build_plan = BuildPlan::create(resolution_graph)?
+        .sharing_build_dir_with(workspace_members.iter().copied());
     // Compile the package. We need to intercede in the compilation, process being performed by the
     // Move package system, to first grab the compilation env, construct the test plan from it, and
     // then save it, before resuming the rest of the compilation and returning the results and
     // control back to the Move package system.
     build_plan.compile_with_driver(writer, None, |compiler| {
         let (files, comments_and_compiler_res) = compiler.run::<PASS_CFGIR>().unwrap();
-        let (_, compiler) =
-            diagnostics::unwrap_or_report_diagnostics(&files, comments_and_compiler_res);
+        let (_, compiler) = diagnostics::unwrap_or_report_diagnostics_with_format(
+            &files,
+            comments_and_compiler_res,
+            diagnostics_format,
+        );
         let (mut compiler, cfgir) = compiler.into_ast();
         let compilation_env = compiler.compilation_env();
         let built_test_plan = construct_test_plan(compilation_env, Some(root_package), &cfgir);
@@ -213,57 +522,23 @@ pub fn run_move_unit_tests<W: Write + Send>(
                 Severity::Warning
             },
         ) {
-            diagnostics::report_diagnostics(&files, diags);
+            diagnostics::report_diagnostics_with_format(&files, diags, diagnostics_format);
         }
 
         let compilation_result = compiler.at_cfgir(cfgir).build();
 
-        let (units, _) = diagnostics::unwrap_or_report_diagnostics(&files, compilation_result);
+        let (units, _) = diagnostics::unwrap_or_report_diagnostics_with_format(
+            &files,
+            compilation_result,
+            diagnostics_format,
+        );
         test_plan = Some((built_test_plan, files.clone(), units.clone()));
         Ok((files, units))
     })?;
 
     let (test_plan, mut files, units) = test_plan.unwrap();
     files.extend(dep_file_map);
-    let test_plan = test_plan.unwrap();
-    let no_tests = test_plan.is_empty();
-    let test_plan = TestPlan::new(test_plan, files, units);
-
-    let trace_path = pkg_path.join(".trace");
-    let coverage_map_path = pkg_path
-        .join(".coverage_map")
-        .with_extension(MOVE_COVERAGE_MAP_EXTENSION);
-    let cleanup_trace = || {
-        if compute_coverage && trace_path.exists() {
-            std::fs::remove_file(&trace_path).unwrap();
-        }
-    };
-
-    cleanup_trace();
-
-    // If we need to compute test coverage set the VM tracking environment variable since we will
-    // need this trace to construct the coverage information.
-    if compute_coverage {
-        std::env::set_var("MOVE_VM_TRACE", &trace_path);
-    }
-
-    // Run the tests. If any of the tests fail, then we don't produce a coverage report, so cleanup
-    // the trace files.
-    if !unit_test_config
-        .run_and_report_unit_tests(test_plan, Some(natives), cost_table, writer)
-        .unwrap()
-        .1
-    {
-        cleanup_trace();
-        return Ok(UnitTestResult::Failure);
-    }
-
-    // Compute the coverage map. This will be used by other commands after this.
-    if compute_coverage && !no_tests {
-        let coverage_map = CoverageMap::from_trace_file(trace_path);
-        output_map_to_file(&coverage_map_path, &coverage_map).unwrap();
-    }
-    Ok(UnitTestResult::Success)
+    Ok(TestPlan::new(test_plan.unwrap(), files, units))
 }
 
 impl From<UnitTestResult> for ExitStatus {
@@ -4,7 +4,7 @@
 
 use base::{
     build::Build, coverage::Coverage, disassemble::Disassemble, docgen::Docgen, errmap::Errmap,
-    info::Info, new::New, prove::Prove, test::Test,
+    fmt::Fmt, info::Info, new::New, prove::Prove, test::Test,
 };
 use move_package::BuildConfig;
 
@@ -67,6 +67,7 @@ pub enum Command {
     Disassemble(Disassemble),
     Docgen(Docgen),
     Errmap(Errmap),
+    Fmt(Fmt),
     Info(Info),
     New(New),
     Prove(Prove),
@@ -78,6 +79,10 @@ pub enum Command {
         /// and script execution.
         #[clap(long, default_value = DEFAULT_STORAGE_DIR, parse(from_os_str))]
         storage_dir: PathBuf,
+        /// Record the coverage of the Move code executed by `run` commands into this file, adding
+        /// to the coverage map already stored in it.
+        #[clap(long, parse(from_os_str))]
+        coverage_map: Option<PathBuf>,
         #[clap(subcommand)]
         cmd: sandbox::cli::SandboxCommand,
     },
@@ -109,6 +114,7 @@ pub fn run_cli(
         Command::Disassemble(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Docgen(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Errmap(c) => c.execute(move_args.package_path, move_args.build_config),
+        Command::Fmt(c) => c.execute(move_args.package_path),
         Command::Info(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::New(c) => c.execute_with_defaults(move_args.package_path),
         Command::Prove(c) => c.execute(move_args.package_path, move_args.build_config),
@@ -118,12 +124,17 @@ pub fn run_cli(
             natives,
             Some(cost_table.clone()),
         ),
-        Command::Sandbox { storage_dir, cmd } => cmd.handle_command(
+        Command::Sandbox {
+            storage_dir,
+            coverage_map,
+            cmd,
+        } => cmd.handle_command(
             natives,
             cost_table,
             error_descriptions,
             &move_args,
             &storage_dir,
+            coverage_map.as_deref(),
         ),
         Command::Experimental { storage_dir, cmd } => cmd.handle_command(&move_args, &storage_dir),
     }
@@ -5,16 +5,13 @@
 use crate::{
     sandbox::{
         self,
-        utils::{on_disk_state_view::OnDiskStateView, PackageContext},
+        utils::{on_disk_state_view::OnDiskStateView, parse_argument, PackageContext},
     },
     Move, NativeFunctionRecord, DEFAULT_BUILD_DIR,
 };
 use anyhow::Result;
 use clap::Parser;
-use move_core_types::{
-    errmap::ErrorMapping, language_storage::TypeTag, parser,
-    transaction_argument::TransactionArgument,
-};
+use move_core_types::{errmap::ErrorMapping, language_storage::TypeTag, parser, value::MoveValue};
 use move_package::compilation::package_layout::CompiledPackageLayout;
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
@@ -80,16 +77,30 @@ pub enum SandboxCommand {
         /// bool literals (true, false),
         /// u64 literals (e.g., 10, 58),
         /// address literals (e.g., 0x12, 0x0000000000000000000000000000000f),
-        /// hexadecimal strings (e.g., x"0012" will parse as the vector<u8> value [00, 12]), and
-        /// ASCII strings (e.g., 'b"hi" will parse as the vector<u8> value [68, 69]).
+        /// hexadecimal strings (e.g., x"0012" will parse as the vector<u8> value [00, 12]),
+        /// ASCII strings (e.g., 'b"hi" will parse as the vector<u8> value [68, 69]),
+        /// UTF-8 strings for `String` arguments (e.g., "hi"), and
+        /// vectors (e.g., vector[1, 2] or vector[@0x1]; `Option` arguments are passed as vectors
+        /// with at most one element, e.g., vector[] or vector[5]).
         #[clap(
             long = "args",
-            parse(try_from_str = parser::parse_transaction_argument),
+            parse(try_from_str = parse_argument),
             takes_value(true),
             multiple_values(true),
             multiple_occurrences(true)
         )]
-        args: Vec<TransactionArgument>,
+        args: Vec<MoveValue>,
+        /// The arguments passed to the transaction as a JSON array, instead of `--args` (e.g.,
+        /// '["10", "0xcafe", [5]]'). Values are given in the JSON encoding of `move_core_types::json`
+        /// for their types: `u64`, `u128` and `u256` as strings, `vector<u8>` as hex strings and
+        /// `Option` as arrays with at most one element. Only supported when calling a function of a
+        /// module.
+        #[clap(
+            long = "json-args",
+            parse(try_from_str = serde_json::from_str),
+            conflicts_with = "args"
+        )]
+        json_args: Option<serde_json::Value>,
         /// Possibly-empty list of type arguments passed to the transaction (e.g., `T` in
         /// `main<T>()`). Must match the type arguments kinds expected by `script_file`.
         #[clap(
@@ -109,6 +120,15 @@ pub enum SandboxCommand {
         /// deleted resources) will NOT be committed to disk.
         #[clap(long = "dry-run", short = 'n')]
         dry_run: bool,
+        /// Debug the execution of `script_file`, serving the Debug Adapter Protocol on stdin and
+        /// stdout.
+        #[clap(long = "debug")]
+        debug: bool,
+        /// Profile the gas usage of `script_file`, writing a flamegraph-compatible folded stacks
+        /// file and a JSON and HTML summary to `build/gas_profiles`. Gas is metered with the
+        /// largest possible budget if `--gas-budget` is not given.
+        #[clap(long = "profile-gas", conflicts_with = "debug")]
+        profile_gas: bool,
     },
     /// Run expected value tests using the given batch file.
     #[clap(name = "exp-test")]
@@ -128,6 +148,11 @@ pub enum SandboxCommand {
         /// Path to a resource, events file, or module stored on disk.
         #[clap(name = "file", parse(from_os_str))]
         file: PathBuf,
+        /// Print resources as JSON, with `u64`, `u128` and `u256` values as decimal strings,
+        /// addresses and `vector<u8>` values as hex strings, `String` values as strings, and
+        /// `Option` values as `null` or the value they contain.
+        #[clap(long = "json")]
+        json: bool,
     },
     /// Delete all resources, events, and modules stored on disk under `storage-dir`.
     /// Does *not* delete anything in `src`.
@@ -202,6 +227,7 @@ impl SandboxCommand {
         error_descriptions: &ErrorMapping,
         move_args: &Move,
         storage_dir: &Path,
+        coverage_map: Option<&Path>,
     ) -> Result<()> {
         let bytecode_version = None;
         match self {
@@ -234,9 +260,12 @@ impl SandboxCommand {
                 script_name,
                 signers,
                 args,
+                json_args,
                 type_args,
                 gas_budget,
                 dry_run,
+                debug,
+                profile_gas,
             } => {
                 let context =
                     PackageContext::new(&move_args.package_path, &move_args.build_config)?;
@@ -251,11 +280,15 @@ impl SandboxCommand {
                     script_name,
                     signers,
                     args,
+                    json_args,
                     type_args.to_vec(),
                     *gas_budget,
                     bytecode_version,
                     *dry_run,
                     move_args.verbose,
+                    *debug,
+                    *profile_gas,
+                    coverage_map,
                 )
             }
             SandboxCommand::Test {
@@ -270,10 +303,10 @@ impl SandboxCommand {
                 *use_temp_dir,
                 *track_cov,
             ),
-            SandboxCommand::View { file } => {
+            SandboxCommand::View { file, json } => {
                 let state = PackageContext::new(&move_args.package_path, &move_args.build_config)?
                     .prepare_state(bytecode_version, storage_dir)?;
-                sandbox::commands::view(&state, file)
+                sandbox::commands::view(&state, file, *json)
             }
             SandboxCommand::Clean {} => {
                 // delete storage
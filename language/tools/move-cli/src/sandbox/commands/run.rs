@@ -3,26 +3,47 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    base::test::{write_gas_profile, GAS_PROFILES_DIR},
     sandbox::utils::{
         contains_module, explain_execution_effects, explain_execution_error, get_gas_status,
         is_bytecode_file, maybe_commit_effects, on_disk_state_view::OnDiskStateView,
+        MAX_GAS_BUDGET,
     },
     NativeFunctionRecord,
 };
 use anyhow::{anyhow, bail, Result};
-use move_binary_format::file_format::CompiledModule;
+use move_binary_format::{errors::VMResult, file_format::CompiledModule};
 use move_command_line_common::env::get_bytecode_version_from_env;
+use move_compiler::compiled_unit::CompiledUnitEnum;
 use move_core_types::{
     account_address::AccountAddress,
+    effects::{ChangeSet, Event},
     errmap::ErrorMapping,
     identifier::IdentStr,
-    language_storage::TypeTag,
-    transaction_argument::{convert_txn_args, TransactionArgument},
+    language_storage::{ModuleId, TypeTag},
+    resolver::MoveResolver,
     value::MoveValue,
 };
-use move_package::compilation::compiled_package::CompiledPackage;
-use move_vm_runtime::move_vm::MoveVM;
+use move_coverage::{
+    coverage_map::{output_map_to_file, CoverageMap},
+    coverage_tracer::CoverageTracer,
+};
+use move_debugger::{run_debug_adapter, DebugSources};
+use move_gas_profiler::GasProfiler;
+use move_package::compilation::{
+    compiled_package::CompiledPackage, package_layout::CompiledPackageLayout,
+};
+use move_resource_viewer::MoveValueAnnotator;
+use move_stdlib::entry_args::entry_struct_args;
+use move_symbol_pool::Symbol;
+use move_vm_runtime::{
+    config::{EntryStructArgs, VMConfig},
+    execution_tracer::{ExecutionTracer, NoopTracer, TeeTracer},
+    move_vm::MoveVM,
+    session::Session,
+};
 use move_vm_test_utils::gas_schedule::CostTable;
+use move_vm_types::gas::GasMeter;
 use std::{fs, path::Path};
 
 #[allow(clippy::too_many_arguments)]
@@ -35,12 +56,16 @@ pub fn run(
     script_path: &Path,
     script_name_opt: &Option<String>,
     signers: &[String],
-    txn_args: &[TransactionArgument],
+    txn_args: &[MoveValue],
+    json_args: &Option<serde_json::Value>,
     vm_type_args: Vec<TypeTag>,
     gas_budget: Option<u64>,
     bytecode_version: Option<u32>,
     dry_run: bool,
     verbose: bool,
+    debug: bool,
+    profile_gas: bool,
+    coverage_map: Option<&Path>,
 ) -> Result<()> {
     if !script_path.exists() {
         bail!("Script file {:?} does not exist", script_path)
@@ -73,12 +98,59 @@ move run` must be applied to a module inside `storage/`",
         .iter()
         .map(|s| AccountAddress::from_hex_literal(s))
         .collect::<Result<Vec<AccountAddress>, _>>()?;
-    // TODO: parse Value's directly instead of going through the indirection of TransactionArgument?
-    let vm_args: Vec<Vec<u8>> = convert_txn_args(txn_args);
+    let script_id = match script_name_opt {
+        Some(script_name) => {
+            // script fun. parse module, extract script ID to pass to VM
+            let module = CompiledModule::deserialize(&bytecode)
+                .map_err(|e| anyhow!("Error deserializing module: {:?}", e))?;
+            Some((module.self_id(), IdentStr::new(script_name)?))
+        }
+        None => None,
+    };
+    let vm_args: Vec<Vec<u8>> = match (json_args, &script_id) {
+        (None, _) => txn_args
+            .iter()
+            .map(|arg| {
+                arg.simple_serialize()
+                    .expect("transaction arguments must serialize")
+            })
+            .collect(),
+        (Some(json_args), Some((module_id, function))) => {
+            let json_args = json_args
+                .as_array()
+                .ok_or_else(|| anyhow!("JSON arguments must be given as an array"))?;
+            MoveValueAnnotator::new(state).function_arguments_from_json(
+                module_id,
+                function,
+                &vm_type_args,
+                json_args,
+            )?
+        }
+        (Some(_), None) => {
+            bail!("JSON arguments are only supported when calling a function of a module")
+        }
+    };
 
-    let vm = MoveVM::new(natives).unwrap();
+    // Allow the standard library structs as arguments if the package depends on it
+    let entry_struct_args = match package
+        .compiled_package_info
+        .address_alias_instantiation
+        .get(&Symbol::from("std"))
+    {
+        Some(std_addr) => entry_struct_args(*std_addr),
+        None => EntryStructArgs::new(),
+    };
+    let vm_config = VMConfig {
+        entry_struct_args: Some(entry_struct_args),
+        ..Default::default()
+    };
+    let vm = MoveVM::new_with_config(natives, vm_config).unwrap();
+    // Profiling needs metered gas, so fall back to the largest budget rather than no metering
+    let gas_budget = match gas_budget {
+        None if profile_gas => Some(MAX_GAS_BUDGET - 1),
+        gas_budget => gas_budget,
+    };
     let mut gas_status = get_gas_status(cost_table, gas_budget)?;
-    let mut session = vm.new_session(state);
 
     let script_type_parameters = vec![];
     let script_parameters = vec![];
@@ -92,29 +164,93 @@ move run` must be applied to a module inside `storage/`",
         })
         .chain(vm_args)
         .collect();
-    let res = match script_name_opt {
-        Some(script_name) => {
-            // script fun. parse module, extract script ID to pass to VM
-            let module = CompiledModule::deserialize(&bytecode)
-                .map_err(|e| anyhow!("Error deserializing module: {:?}", e))?;
-            session.execute_entry_function(
-                &module.self_id(),
-                IdentStr::new(script_name)?,
+    // Coverage is recorded alongside debugging or profiling, if requested
+    let mut coverage = match coverage_map {
+        Some(coverage_map_path) => {
+            let exec_id = execution_name(script_path, script_name_opt);
+            Some(if coverage_map_path.exists() {
+                let coverage_map = CoverageMap::from_binary_file(coverage_map_path)?;
+                CoverageTracer::with_coverage_map(exec_id, coverage_map)
+            } else {
+                CoverageTracer::new(exec_id)
+            })
+        }
+        None => None,
+    };
+    let mut noop_tracer = NoopTracer;
+    let coverage_tracer: &mut dyn ExecutionTracer = match &mut coverage {
+        Some(tracer) => tracer,
+        None => &mut noop_tracer,
+    };
+    let res = if debug {
+        let sources = debug_sources(package, script_path)?;
+        let mut res = None;
+        let stdin = std::io::stdin();
+        run_debug_adapter(stdin.lock(), std::io::stdout(), &sources, |tracer| {
+            let mut tracer = TeeTracer(tracer, coverage_tracer);
+            let outcome = execute(
+                vm.new_session_with_tracer(state, &mut tracer),
+                &script_id,
+                &bytecode,
                 vm_type_args.clone(),
                 vm_args,
                 &mut gas_status,
-            )
+            );
+            let message = match &outcome {
+                Ok(Ok(_)) => Ok("Execution finished".to_string()),
+                Ok(Err(err)) | Err(err) => Err(format!("Execution failed: {}", err)),
+            };
+            res = Some(outcome);
+            message
+        })?;
+        match res {
+            Some(res) => res,
+            // The client disconnected before execution started
+            None => return Ok(()),
         }
-        None => session.execute_script(
-            bytecode.to_vec(),
+    } else if profile_gas {
+        let profiler = GasProfiler::new();
+        let mut tracer = TeeTracer(profiler.tracer(), coverage_tracer);
+        let res = execute(
+            vm.new_session_with_tracer(state, &mut tracer),
+            &script_id,
+            &bytecode,
+            vm_type_args.clone(),
+            vm_args,
+            &mut profiler.gas_meter(gas_status),
+        );
+        let profile = profiler.into_profile();
+        let folded_path = write_gas_profile(
+            &profile,
+            &debug_sources(package, script_path)?,
+            &state
+                .build_dir()
+                .join(CompiledPackageLayout::Root.path())
+                .join(GAS_PROFILES_DIR),
+            execution_name(script_path, script_name_opt),
+        )?;
+        println!(
+            "Used {} gas, profile written to {}",
+            profile.total_gas(),
+            folded_path.display()
+        );
+        res
+    } else {
+        execute(
+            vm.new_session_with_tracer(state, coverage_tracer),
+            &script_id,
+            &bytecode,
             vm_type_args.clone(),
             vm_args,
             &mut gas_status,
-        ),
+        )
     };
+    if let (Some(coverage_map_path), Some(tracer)) = (coverage_map, coverage) {
+        output_map_to_file(coverage_map_path, &tracer.into_maps().0)?;
+    }
 
-    if let Err(err) = res {
-        explain_execution_error(
+    match res {
+        Err(err) => explain_execution_error(
             error_descriptions,
             err,
             state,
@@ -123,12 +259,67 @@ move run` must be applied to a module inside `storage/`",
             &vm_type_args,
             &signer_addresses,
             txn_args,
-        )
-    } else {
-        let (changeset, events) = session.finish().map_err(|e| e.into_vm_status())?;
-        if verbose {
-            explain_execution_effects(&changeset, &events, state)?
+        ),
+        Ok(effects) => {
+            let (changeset, events) = effects.map_err(|e| e.into_vm_status())?;
+            if verbose {
+                explain_execution_effects(&changeset, &events, state)?
+            }
+            maybe_commit_effects(!dry_run, changeset, events, state)
+        }
+    }
+}
+
+/// The name under which gas profiles and coverage of running `script_path` are recorded.
+fn execution_name<'a>(script_path: &'a Path, script_name_opt: &'a Option<String>) -> &'a str {
+    match script_name_opt {
+        Some(script_name) => script_name.as_str(),
+        None => script_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("script"),
+    }
+}
+
+/// Executes the script or script function in `session`. The outer result is the result of the
+/// execution, the inner one the result of collecting its effects.
+fn execute<S: MoveResolver>(
+    mut session: Session<S>,
+    script_id: &Option<(ModuleId, &IdentStr)>,
+    bytecode: &[u8],
+    vm_type_args: Vec<TypeTag>,
+    vm_args: Vec<Vec<u8>>,
+    gas_meter: &mut impl GasMeter,
+) -> VMResult<VMResult<(ChangeSet, Vec<Event>)>> {
+    match script_id {
+        Some((module_id, script_name)) => session
+            .execute_entry_function(module_id, script_name, vm_type_args, vm_args, gas_meter)
+            .map(|_| ()),
+        None => session
+            .execute_script(bytecode.to_vec(), vm_type_args, vm_args, gas_meter)
+            .map(|_| ()),
+    }?;
+    Ok(session.finish())
+}
+
+/// Collects the source maps and sources of `package` for debugging or profiling `script_path`.
+fn debug_sources(package: &CompiledPackage, script_path: &Path) -> Result<DebugSources> {
+    let mut sources = DebugSources::new();
+    for unit in package.all_compiled_units_with_source() {
+        let contents = fs::read_to_string(&unit.source_path)?;
+        sources.add_file(&unit.source_path, &contents);
+        if let CompiledUnitEnum::Module(module) = &unit.unit {
+            sources.add_module(module.module.self_id(), module.source_map.clone());
+        }
+    }
+    if !is_bytecode_file(script_path) {
+        let file_contents = fs::read_to_string(script_path)?;
+        if let Some(script) = package
+            .scripts()
+            .find(|unit| unit.unit.source_map().check(&file_contents))
+        {
+            sources.set_script(script.unit.source_map().clone());
         }
-        maybe_commit_effects(!dry_run, changeset, events, state)
     }
+    Ok(sources)
 }
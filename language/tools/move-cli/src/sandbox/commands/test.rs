@@ -20,6 +20,7 @@ use move_package::{
 use std::{
     collections::{BTreeMap, HashMap},
     env,
+    ffi::OsStr,
     fmt::Write as FmtWrite,
     fs::{self, File},
     io::{self, BufRead, Write},
@@ -43,18 +44,14 @@ const NO_MOVE_CLEAN: &str = "NO_MOVE_CLEAN";
 /// The filename that contains the arguments to the Move binary.
 pub const TEST_ARGS_FILENAME: &str = "args.txt";
 
-/// Name of the environment variable we need to set in order to get tracing
-/// enabled in the move VM.
-const MOVE_VM_TRACING_ENV_VAR_NAME: &str = "MOVE_VM_TRACE";
-
-/// The default file name (inside the build output dir) for the runtime to
-/// dump the execution trace to. The trace will be used by the coverage tool
-/// if --track-cov is set. If --track-cov is not set, then no trace file will
-/// be produced.
-const DEFAULT_TRACE_FILE: &str = "trace";
+/// The default file name (inside the workspace of the test) that sandbox
+/// commands record the coverage of their execution to. The coverage map will
+/// be used by the coverage tool if --track-cov is set. If --track-cov is not
+/// set, then no coverage map will be produced.
+const DEFAULT_COVERAGE_MAP_FILE: &str = "coverage_map";
 
 fn collect_coverage(
-    trace_file: &Path,
+    coverage_map_file: &Path,
     build_dir: &Path,
 ) -> anyhow::Result<ExecCoverageMapWithModules> {
     let canonical_build = build_dir.canonicalize().unwrap();
@@ -88,8 +85,8 @@ fn collect_coverage(
             .insert(module_id.name().to_owned(), (entry, module));
     }
 
-    // collect filtered trace
-    let coverage_map = CoverageMap::from_trace_file(trace_file)
+    // collect filtered coverage
+    let coverage_map = CoverageMap::from_binary_file(coverage_map_file)?
         .to_unified_exec_map()
         .into_coverage_map_with_modules(filter);
 
@@ -213,9 +210,9 @@ pub fn run_one(
     }
     let mut output = "".to_string();
 
-    // always use the absolute path for the trace file as we may change dirs in the process
-    let trace_file = if track_cov {
-        Some(wks_dir.canonicalize()?.join(DEFAULT_TRACE_FILE))
+    // always use the absolute path for the coverage map as we may change dirs in the process
+    let coverage_map_file = if track_cov {
+        Some(wks_dir.canonicalize()?.join(DEFAULT_COVERAGE_MAP_FILE))
     } else {
         None
     };
@@ -251,23 +248,21 @@ pub fn run_one(
             // allow comments in args.txt
             continue;
         }
-        let args_iter: Vec<&str> = args_line.split_whitespace().collect();
+        let mut args_iter: Vec<&OsStr> = args_line.split_whitespace().map(OsStr::new).collect();
         if args_iter.is_empty() {
             // allow blank lines in args.txt
             continue;
         }
 
-        // enable tracing in the VM by setting the env var.
-        match &trace_file {
-            None => {
-                // this check prevents cascading the coverage tracking flag.
-                // in particular, if
-                //   1. we run with move-cli test <path-to-args-A.txt> --track-cov, and
-                //   2. in this <args-A.txt>, there is another command: test <args-B.txt>
-                // then, when running <args-B.txt>, coverage will not be tracked nor printed
-                env::remove_var(MOVE_VM_TRACING_ENV_VAR_NAME);
+        // record the coverage of sandbox commands into the coverage map. Only the commands of
+        // this args.txt are instrumented, so if
+        //   1. we run with move-cli test <path-to-args-A.txt> --track-cov, and
+        //   2. in this <args-A.txt>, there is another command: test <args-B.txt>
+        // then, when running <args-B.txt>, coverage will not be tracked nor printed
+        if let Some(path) = &coverage_map_file {
+            if args_iter[0] == "sandbox" {
+                args_iter.splice(1..1, [OsStr::new("--coverage-map"), path.as_os_str()]);
             }
-            Some(path) => env::set_var(MOVE_VM_TRACING_ENV_VAR_NAME, path.as_os_str()),
         }
 
         let cmd_output = cli_command_template().args(args_iter).output()?;
@@ -277,16 +272,16 @@ pub fn run_one(
     }
 
     // collect coverage information
-    let cov_info = match &trace_file {
+    let cov_info = match &coverage_map_file {
         None => None,
-        Some(trace_path) => {
-            if trace_path.exists() {
-                Some(collect_coverage(trace_path, &build_output)?)
+        Some(coverage_map_path) => {
+            if coverage_map_path.exists() {
+                Some(collect_coverage(coverage_map_path, &build_output)?)
             } else {
                 eprintln!(
-                    "Trace file {:?} not found: coverage is only available with at least one `run` \
-                    command in the args.txt (after a `clean`, if there is one)",
-                    trace_path
+                    "Coverage map {:?} not found: coverage is only available with at least one \
+                    `sandbox run` command in the args.txt",
+                    coverage_map_path
                 );
                 None
             }
@@ -315,10 +310,10 @@ pub fn run_one(
             DEFAULT_BUILD_DIR
         );
 
-        // clean the trace file as well if it exists
-        if let Some(trace_path) = &trace_file {
-            if trace_path.exists() {
-                fs::remove_file(trace_path)?;
+        // clean the coverage map as well if it exists
+        if let Some(coverage_map_path) = &coverage_map_file {
+            if coverage_map_path.exists() {
+                fs::remove_file(coverage_map_path)?;
             }
         }
     }
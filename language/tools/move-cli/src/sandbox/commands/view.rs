@@ -8,9 +8,14 @@ use crate::sandbox::utils::{
 
 use anyhow::{bail, Result};
 use std::path::Path;
-/// Print a module or resource stored in `file`
-pub fn view(state: &OnDiskStateView, path: &Path) -> Result<()> {
-    if state.is_resource_path(path) {
+/// Print a module or resource stored in `file`, printing resources as JSON if `json` is set
+pub fn view(state: &OnDiskStateView, path: &Path, json: bool) -> Result<()> {
+    if state.is_resource_path(path) && json {
+        match state.view_resource_json(path)? {
+            Some(resource) => println!("{}", serde_json::to_string_pretty(&resource)?),
+            None => println!("Resource not found."),
+        }
+    } else if state.is_resource_path(path) {
         match state.view_resource(path)? {
             Some(resource) => println!("{}", resource),
             None => println!("Resource not found."),
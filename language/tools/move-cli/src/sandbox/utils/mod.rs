@@ -14,7 +14,10 @@ use move_binary_format::{
     normalized, IndexKind,
 };
 use move_bytecode_utils::Modules;
-use move_command_line_common::files::{FileHash, MOVE_COMPILED_EXTENSION};
+use move_command_line_common::{
+    files::{FileHash, MOVE_COMPILED_EXTENSION},
+    values::ParsedValue,
+};
 use move_compiler::{
     compiled_unit::{CompiledUnit, NamedCompiledModule},
     diagnostics::{self, report_diagnostics, Diagnostic, Diagnostics, FileName},
@@ -24,7 +27,8 @@ use move_core_types::{
     effects::{ChangeSet, Event, Op},
     errmap::ErrorMapping,
     language_storage::{ModuleId, TypeTag},
-    transaction_argument::TransactionArgument,
+    parser,
+    value::MoveValue,
     vm_status::{AbortLocation, StatusCode, VMStatus},
 };
 use move_ir_types::location::Loc;
@@ -45,12 +49,14 @@ use move_vm_test_utils::gas_schedule::{CostTable, GasStatus};
 pub use on_disk_state_view::*;
 pub use package_context::*;
 
+// TODO(Gas): This should not be hardcoded.
+/// The gas budget must be below this bound.
+pub(crate) const MAX_GAS_BUDGET: u64 = u64::MAX / 1000;
+
 pub fn get_gas_status(cost_table: &CostTable, gas_budget: Option<u64>) -> Result<GasStatus> {
     let gas_status = if let Some(gas_budget) = gas_budget {
-        // TODO(Gas): This should not be hardcoded.
-        let max_gas_budget = u64::MAX.checked_div(1000).unwrap();
-        if gas_budget >= max_gas_budget {
-            bail!("Gas budget set too high; maximum is {}", max_gas_budget)
+        if gas_budget >= MAX_GAS_BUDGET {
+            bail!("Gas budget set too high; maximum is {}", MAX_GAS_BUDGET)
         }
         GasStatus::new(cost_table, Gas::new(gas_budget))
     } else {
@@ -274,10 +280,20 @@ pub(crate) fn maybe_commit_effects(
     Ok(())
 }
 
+/// Parses an argument of `move sandbox run`: a transaction argument, a UTF-8 string (e.g. "hi",
+/// which can be passed as a `String`) or a vector (e.g. vector[1, 2]; an `Option` is passed as a
+/// vector of at most one element).
+pub fn parse_argument(s: &str) -> Result<MoveValue> {
+    match parser::parse_transaction_argument(s) {
+        Ok(arg) => Ok(arg.into()),
+        Err(_) => ParsedValue::<()>::parse(s)?.into_concrete_value(&|_| None),
+    }
+}
+
 pub(crate) fn explain_type_error(
     script_params: &[SignatureToken],
     signers: &[AccountAddress],
-    txn_args: &[TransactionArgument],
+    txn_args: &[MoveValue],
 ) {
     use SignatureToken::*;
     let expected_num_signers = script_params
@@ -461,7 +477,7 @@ pub(crate) fn explain_execution_error(
     script_parameters: &[SignatureToken],
     vm_type_args: &[TypeTag],
     signers: &[AccountAddress],
-    txn_args: &[TransactionArgument],
+    txn_args: &[MoveValue],
 ) -> Result<()> {
     use StatusCode::*;
     match error.into_vm_status() {
@@ -513,6 +529,7 @@ pub(crate) fn explain_execution_error(
                     .to_string(),
                 EXECUTION_STACK_OVERFLOW => "an execution stack overflow".to_string(),
                 CALL_STACK_OVERFLOW => "a call stack overflow".to_string(),
+                VM_MAX_VECTOR_LENGTH_REACHED => "a vector length limit violation".to_string(),
                 OUT_OF_GAS => "an out of gas error".to_string(),
                 _ => format!("a {} error", status_code.status_type()),
             };
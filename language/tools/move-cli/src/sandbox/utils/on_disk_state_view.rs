@@ -194,6 +194,29 @@ impl OnDiskStateView {
     /// Returns a deserialized representation of the resource value stored at `resource_path`.
     /// Returns Err if the path does not hold a resource value or the resource cannot be deserialized
     pub fn view_resource(&self, resource_path: &Path) -> Result<Option<AnnotatedMoveStruct>> {
+        let (tag, resource_data) = Self::get_resource(resource_path)?;
+        match resource_data {
+            Some(resource_data) => Ok(Some(
+                MoveValueAnnotator::new(self).view_resource(&tag, &resource_data)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Read the resource at `resource_path` as JSON, see `move_core_types::json`.
+    /// Returns Err if the path does not hold a resource value or the resource cannot be deserialized
+    pub fn view_resource_json(&self, resource_path: &Path) -> Result<Option<serde_json::Value>> {
+        let (tag, resource_data) = Self::get_resource(resource_path)?;
+        match resource_data {
+            Some(resource_data) => Ok(Some(
+                MoveValueAnnotator::new(self).view_resource_json(&tag, &resource_data)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the type of the resource stored at `resource_path` and its bytes, if any
+    fn get_resource(resource_path: &Path) -> Result<(StructTag, Option<Vec<u8>>)> {
         if resource_path.is_dir() {
             bail!(
                 "Bad resource path {:?}. Needed file, found directory",
@@ -205,18 +228,13 @@ impl OnDiskStateView {
                 "Bad resource path {:?}; last component must be a file",
                 resource_path
             ),
-            Some(name) => Ok({
-                let id = match parser::parse_type_tag(&name.to_string_lossy())? {
-                    TypeTag::Struct(s) => s,
+            Some(name) => {
+                let tag = match parser::parse_type_tag(&name.to_string_lossy())? {
+                    TypeTag::Struct(s) => *s,
                     t => bail!("Expected to parse struct tag, but got {}", t),
                 };
-                match Self::get_bytes(resource_path)? {
-                    Some(resource_data) => {
-                        Some(MoveValueAnnotator::new(self).view_resource(&id, &resource_data)?)
-                    }
-                    None => None,
-                }
-            }),
+                Ok((tag, Self::get_bytes(resource_path)?))
+            }
         }
     }
 
@@ -0,0 +1,5 @@
+module 0x42::m {
+public fun foo(x: u64): u64 {
+    1 + 1
+}
+}
@@ -0,0 +1,19 @@
+module 0x42::m {
+    fun self_assign(x: u64): u64 {
+        x = x;
+        x
+    }
+
+    fun spin() {
+        while (true) {}
+    }
+
+    fun shift(x: u64): u64 {
+        x << 64
+    }
+
+    #[allow(shift_overflow)]
+    fun allowed_shift(x: u8): u8 {
+        x << 8
+    }
+}
@@ -0,0 +1,31 @@
+module 0x42::m {
+    enum Shape has copy, drop {
+        Circle { radius: u64 },
+        Rectangle { width: u64, height: u64 },
+        Point,
+    }
+
+    enum Option<T> has drop {
+        None,
+        Some { value: T },
+    }
+
+    fun area(s: &Shape): u64 {
+        match (s) {
+            Shape::Circle { radius } => *radius * *radius * 3,
+            Shape::Rectangle { width, height } => *width * *height,
+            Shape::Point => 0,
+        }
+    }
+
+    fun circle(radius: u64): Shape {
+        Shape::Circle { radius }
+    }
+
+    fun get_or<T: drop>(o: Option<T>, default: T): T {
+        match (o) {
+            Option::Some { value } => value,
+            Option::None => default,
+        }
+    }
+}
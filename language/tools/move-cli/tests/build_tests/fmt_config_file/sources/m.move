@@ -0,0 +1,7 @@
+module 0x42::m {
+    /// Documentation
+    public fun f(x:u64):u64 {
+            // add one
+        x+1
+    }
+}
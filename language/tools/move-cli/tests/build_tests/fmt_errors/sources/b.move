@@ -0,0 +1,3 @@
+module 0x42::b {
+    fun g(x:u64):u64 { x+1 }
+}
@@ -0,0 +1,3 @@
+module 0x42::c {
+    fun h(: u64) {}
+}
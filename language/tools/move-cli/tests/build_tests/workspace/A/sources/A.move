@@ -0,0 +1,5 @@
+module A::A {
+    use B::B;
+
+    public fun f(): u64 { B::g() }
+}
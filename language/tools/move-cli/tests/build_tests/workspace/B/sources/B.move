@@ -0,0 +1,3 @@
+module B::B {
+    public fun g(): u64 { 0 }
+}
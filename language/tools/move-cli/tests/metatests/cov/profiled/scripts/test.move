@@ -0,0 +1,7 @@
+script {
+    use 0x42::M;
+
+    fun test() {
+        M::test();
+    }
+}
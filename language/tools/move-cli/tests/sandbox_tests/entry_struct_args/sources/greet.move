@@ -0,0 +1,9 @@
+script {
+use std::option::{Self, Option};
+use std::string::{Self, String};
+
+fun greet(name: String, times: Option<u64>) {
+    assert!(string::length(&name) == 4, 0);
+    assert!(option::destroy_with_default(times, 1) == 1, 1);
+}
+}
@@ -0,0 +1,11 @@
+script {
+    fun sum() {
+        let i = 0;
+        let s = 0;
+        while (i < 10) {
+            i = i + 1;
+            s = s + i;
+        };
+        assert!(s == 55, 0);
+    }
+}
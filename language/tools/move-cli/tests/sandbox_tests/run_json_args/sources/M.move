@@ -0,0 +1,25 @@
+module 0x42::M {
+    use std::option::Option;
+    use std::string::String;
+
+    struct R has key {
+        balance: u64,
+        total: u128,
+        name: String,
+        limit: Option<u64>,
+        bytes: vector<u8>,
+        owners: vector<address>,
+    }
+
+    public entry fun publish(
+        account: signer,
+        balance: u64,
+        total: u128,
+        name: String,
+        limit: Option<u64>,
+        bytes: vector<u8>,
+        owners: vector<address>,
+    ) {
+        move_to(&account, R { balance, total, name, limit, bytes, owners })
+    }
+}
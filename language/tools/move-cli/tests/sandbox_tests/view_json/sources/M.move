@@ -0,0 +1,33 @@
+module 0x42::M {
+    use std::option::{Self, Option};
+    use std::string::{Self, String};
+
+    struct Inner has store {
+        flag: bool,
+        small: u8,
+    }
+
+    struct R has key {
+        balance: u64,
+        total: u128,
+        name: String,
+        limit: Option<u64>,
+        none: Option<u64>,
+        bytes: vector<u8>,
+        owners: vector<address>,
+        inner: Inner,
+    }
+
+    public entry fun publish(account: signer) {
+        move_to(&account, R {
+            balance: 18446744073709551615,
+            total: 7,
+            name: string::utf8(b"move"),
+            limit: option::some(100),
+            none: option::none(),
+            bytes: x"cafe",
+            owners: vector[@0x1, @0x42],
+            inner: Inner { flag: true, small: 3 },
+        })
+    }
+}
@@ -24,12 +24,9 @@ use std::{
     version
 )]
 struct Args {
-    /// The path to the coverage map or trace file
+    /// The path to the serialized coverage map or trace map
     #[clap(long = "input-trace-path", short = 't')]
     pub input_trace_path: String,
-    /// Whether the passed-in file is a raw trace file or a serialized coverage map
-    #[clap(long = "is-raw-trace", short = 'r')]
-    pub is_raw_trace_file: bool,
     /// The path to the module binary
     #[clap(long = "module-path", short = 'b')]
     pub module_binary_path: Option<String>,
@@ -88,11 +85,7 @@ fn main() {
 
     let modules = get_modules(&args);
     if args.derive_path_coverage {
-        let trace_map = if args.is_raw_trace_file {
-            TraceMap::from_trace_file(input_trace_path)
-        } else {
-            TraceMap::from_binary_file(input_trace_path)
-        };
+        let trace_map = TraceMap::from_binary_file(input_trace_path);
         if !args.csv_output {
             format_human_summary(
                 &modules,
@@ -110,11 +103,7 @@ fn main() {
             )
         }
     } else {
-        let coverage_map = if args.is_raw_trace_file {
-            CoverageMap::from_trace_file(input_trace_path)
-        } else {
-            CoverageMap::from_binary_file(input_trace_path).unwrap()
-        };
+        let coverage_map = CoverageMap::from_binary_file(input_trace_path).unwrap();
         let unified_exec_map = coverage_map.to_unified_exec_map();
         if !args.csv_output {
             format_human_summary(
@@ -24,12 +24,9 @@ use std::{
     version
 )]
 struct Args {
-    /// The path to the coverage map or trace file
+    /// The path to the serialized coverage map or trace map
     #[clap(long = "input-trace-path", short = 't')]
     pub input_trace_path: String,
-    /// Whether the passed-in file is a raw trace file or a serialized coverage map
-    #[clap(long = "is-raw-trace", short = 'r')]
-    pub is_raw_trace_file: bool,
     /// The path to the module binary
     #[clap(long = "module-path", short = 'b')]
     pub module_binary_path: String,
@@ -44,11 +41,7 @@ struct Args {
 fn main() {
     let args = Args::parse();
     let source_map_extension = SOURCE_MAP_EXTENSION;
-    let coverage_map = if args.is_raw_trace_file {
-        CoverageMap::from_trace_file(&args.input_trace_path)
-    } else {
-        CoverageMap::from_binary_file(&args.input_trace_path).unwrap()
-    };
+    let coverage_map = CoverageMap::from_binary_file(&args.input_trace_path).unwrap();
 
     let bytecode_bytes = fs::read(&args.module_binary_path).expect("Unable to read bytecode file");
     let compiled_module =
@@ -14,25 +14,25 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fs::File,
-    io::{BufRead, BufReader, Read, Write},
+    io::{Read, Write},
     path::Path,
 };
 
 pub type FunctionCoverage = BTreeMap<u64, u64>;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoverageMap {
     pub exec_maps: BTreeMap<String, ExecCoverageMap>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleCoverageMap {
     pub module_addr: AccountAddress,
     pub module_name: Identifier,
     pub function_maps: BTreeMap<Identifier, FunctionCoverage>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecCoverageMap {
     pub exec_id: String,
     pub module_maps: BTreeMap<(AccountAddress, Identifier), ModuleCoverageMap>,
@@ -58,45 +58,6 @@ pub struct TraceMap {
 }
 
 impl CoverageMap {
-    /// Takes in a file containing a raw VM trace, and returns an updated coverage map.
-    pub fn update_coverage_from_trace_file<P: AsRef<Path> + std::fmt::Debug>(
-        mut self,
-        filename: P,
-    ) -> Self {
-        let file = File::open(&filename)
-            .unwrap_or_else(|_| panic!("Unable to open coverage trace file '{:?}'", filename));
-        for line in BufReader::new(file).lines() {
-            let line = line.unwrap();
-            let mut splits = line.split(',');
-            let exec_id = splits.next().unwrap();
-            let context = splits.next().unwrap();
-            let pc = splits.next().unwrap().parse::<u64>().unwrap();
-
-            let mut context_segs: Vec<_> = context.split("::").collect();
-            let is_script = context_segs.len() == 2;
-            if !is_script {
-                let func_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_addr =
-                    AccountAddress::from_hex_literal(context_segs.pop().unwrap()).unwrap();
-                self.insert(exec_id, module_addr, module_name, func_name, pc);
-            } else {
-                // Don't count scripts (for now)
-                assert_eq!(context_segs.pop().unwrap(), "main",);
-                assert_eq!(context_segs.pop().unwrap(), "Script",);
-            }
-        }
-        self
-    }
-
-    /// Takes in a file containing a raw VM trace, and returns a coverage map.
-    pub fn from_trace_file<P: AsRef<Path> + std::fmt::Debug>(filename: P) -> Self {
-        let empty_module_map = CoverageMap {
-            exec_maps: BTreeMap::new(),
-        };
-        empty_module_map.update_coverage_from_trace_file(filename)
-    }
-
     /// Takes in a file containing a serialized coverage map and returns a coverage map.
     pub fn from_binary_file<P: AsRef<Path> + std::fmt::Debug>(filename: P) -> Result<Self> {
         let mut bytes = Vec::new();
@@ -271,41 +232,6 @@ impl ExecCoverageMapWithModules {
 }
 
 impl TraceMap {
-    /// Takes in a file containing a raw VM trace, and returns an updated coverage map.
-    pub fn update_from_trace_file<P: AsRef<Path>>(mut self, filename: P) -> Self {
-        let file = File::open(filename).unwrap();
-        for line in BufReader::new(file).lines() {
-            let line = line.unwrap();
-            let mut splits = line.split(',');
-            let exec_id = splits.next().unwrap();
-            let context = splits.next().unwrap();
-            let pc = splits.next().unwrap().parse::<u64>().unwrap();
-
-            let mut context_segs: Vec<_> = context.split("::").collect();
-            let is_script = context_segs.len() == 2;
-            if !is_script {
-                let func_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_addr =
-                    AccountAddress::from_hex_literal(context_segs.pop().unwrap()).unwrap();
-                self.insert(exec_id, module_addr, module_name, func_name, pc);
-            } else {
-                // Don't count scripts (for now)
-                assert_eq!(context_segs.pop().unwrap(), "main",);
-                assert_eq!(context_segs.pop().unwrap(), "Script",);
-            }
-        }
-        self
-    }
-
-    // Takes in a file containing a raw VM trace, and returns a parsed trace.
-    pub fn from_trace_file<P: AsRef<Path>>(filename: P) -> Self {
-        let trace_map = TraceMap {
-            exec_maps: BTreeMap::new(),
-        };
-        trace_map.update_from_trace_file(filename)
-    }
-
     // Takes in a file containing a serialized trace and deserialize it.
     pub fn from_binary_file<P: AsRef<Path>>(filename: P) -> Self {
         let mut bytes = Vec::new();
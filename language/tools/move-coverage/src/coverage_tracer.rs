@@ -0,0 +1,108 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use crate::coverage_map::{CoverageMap, TraceMap};
+use move_binary_format::file_format::{Bytecode, CodeOffset};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use move_vm_runtime::execution_tracer::{ExecutionTracer, TracedFunction};
+use move_vm_types::values::Value;
+use std::collections::BTreeMap;
+
+/// An `ExecutionTracer` which builds a coverage map and a trace map directly from VM execution,
+/// without going through a trace file.
+pub struct CoverageTracer {
+    exec_id: String,
+    coverage_map: CoverageMap,
+    trace_map: TraceMap,
+    // The function the last instruction was executed in. Instructions mostly follow each other
+    // in the same function, so its name only needs to be turned into an identifier on calls and
+    // returns.
+    current_function: Option<(ModuleId, Identifier)>,
+}
+
+impl CoverageTracer {
+    /// Creates a tracer which records all executed instructions under `exec_id`.
+    pub fn new(exec_id: impl Into<String>) -> Self {
+        Self::with_coverage_map(
+            exec_id,
+            CoverageMap {
+                exec_maps: BTreeMap::new(),
+            },
+        )
+    }
+
+    /// Creates a tracer which adds all executed instructions to `coverage_map`, under `exec_id`.
+    pub fn with_coverage_map(exec_id: impl Into<String>, coverage_map: CoverageMap) -> Self {
+        CoverageTracer {
+            exec_id: exec_id.into(),
+            coverage_map,
+            trace_map: TraceMap {
+                exec_maps: BTreeMap::new(),
+            },
+            current_function: None,
+        }
+    }
+
+    /// Records all subsequently executed instructions under `exec_id`, so that one tracer can
+    /// collect several executions.
+    pub fn set_exec_id(&mut self, exec_id: impl Into<String>) {
+        self.exec_id = exec_id.into();
+    }
+
+    pub fn coverage_map(&self) -> &CoverageMap {
+        &self.coverage_map
+    }
+
+    pub fn trace_map(&self) -> &TraceMap {
+        &self.trace_map
+    }
+
+    pub fn into_maps(self) -> (CoverageMap, TraceMap) {
+        (self.coverage_map, self.trace_map)
+    }
+}
+
+impl ExecutionTracer for CoverageTracer {
+    fn instruction(
+        &mut self,
+        function: &TracedFunction,
+        pc: CodeOffset,
+        _instr: &Bytecode,
+        _stack: &[Value],
+    ) {
+        // Don't count scripts (for now)
+        let module_id = match function.module_id() {
+            Some(module_id) => module_id,
+            None => return,
+        };
+        let func_name = match &self.current_function {
+            Some((current_module_id, func_name))
+                if current_module_id == module_id && func_name.as_str() == function.name() =>
+            {
+                func_name.clone()
+            }
+            _ => {
+                let func_name = Identifier::new(function.name())
+                    .expect("names of loaded functions are valid identifiers");
+                self.current_function = Some((module_id.clone(), func_name.clone()));
+                func_name
+            }
+        };
+        self.coverage_map.insert(
+            &self.exec_id,
+            *module_id.address(),
+            module_id.name().to_owned(),
+            func_name.clone(),
+            pc as u64,
+        );
+        self.trace_map.insert(
+            &self.exec_id,
+            *module_id.address(),
+            module_id.name().to_owned(),
+            func_name,
+            pc as u64,
+        );
+    }
+}
@@ -0,0 +1,530 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The debug adapter: an `ExecutionTracer` which suspends the VM at breakpoints and steps, and
+//! answers the client's requests while execution is suspended.
+
+use crate::{
+    protocol::{Connection, Request},
+    sources::{DebugSources, SourcePosition},
+};
+use anyhow::Result;
+use move_binary_format::{
+    errors::VMError,
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+};
+use move_command_line_common::files::FileHash;
+use move_core_types::{
+    language_storage::ModuleId,
+    value::{MoveStruct, MoveValue},
+};
+use move_vm_runtime::execution_tracer::{ExecutionTracer, TracedFunction};
+use move_vm_types::values::Value;
+use serde_json::{json, Value as Json};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{BufRead, Write},
+    path::Path,
+};
+
+/// The id of the only thread reported to the client.
+const THREAD_ID: i64 = 1;
+/// The exception breakpoint filter which suspends execution when Move code aborts.
+const ABORT_FILTER: &str = "abort";
+
+/// Serves a debugging session over `reader` and `writer`. Once the client has finished
+/// configuring the session, `execute` is called to run the code being debugged with the given
+/// tracer installed; it returns a description of the outcome, which is an error if execution
+/// failed.
+pub fn run_debug_adapter<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    sources: &DebugSources,
+    execute: impl FnOnce(&mut dyn ExecutionTracer) -> Result<String, String>,
+) -> Result<()> {
+    let mut debugger = Debugger::new(Connection::new(reader, writer), sources);
+    if !debugger.configure()? {
+        return Ok(());
+    }
+
+    let outcome = execute(&mut debugger);
+    if let Some(err) = debugger.error.take() {
+        return Err(err);
+    }
+    if debugger.disconnected {
+        return Ok(());
+    }
+
+    let (output, exit_code) = match outcome {
+        Ok(output) => (output, 0),
+        Err(output) => (output, 1),
+    };
+    let conn = &mut debugger.conn;
+    conn.send_event(
+        "output",
+        json!({ "category": "console", "output": format!("{}\n", output) }),
+    )?;
+    conn.send_event("exited", json!({ "exitCode": exit_code }))?;
+    conn.send_event("terminated", json!({}))?;
+
+    while let Some(request) = conn.read_request()? {
+        match request.command.as_str() {
+            "disconnect" => return conn.respond(&request, json!({})),
+            "threads" => conn.respond(&request, json!({ "threads": [] }))?,
+            _ => conn.respond_error(&request, "The program has terminated")?,
+        }
+    }
+    Ok(())
+}
+
+/// How execution proceeds until it is suspended again.
+#[derive(Clone, Copy)]
+enum Mode {
+    Continue,
+    Entry,
+    StepIn {
+        depth: usize,
+        line: Option<(FileHash, usize)>,
+    },
+    StepOver {
+        depth: usize,
+        line: Option<(FileHash, usize)>,
+    },
+    StepOut {
+        depth: usize,
+    },
+}
+
+/// A Move function on the call stack.
+struct Frame {
+    name: String,
+    module_id: Option<ModuleId>,
+    function: FunctionDefinitionIndex,
+    position: Option<SourcePosition>,
+    /// The locals of the function as of the last time execution was suspended in it, or as of
+    /// the call it is currently executing.
+    locals: Vec<(String, MoveValue)>,
+}
+
+impl Frame {
+    fn line(&self) -> Option<(FileHash, usize)> {
+        self.position.map(|pos| (pos.file_hash, pos.line))
+    }
+}
+
+struct Debugger<'a, R, W> {
+    conn: Connection<R, W>,
+    sources: &'a DebugSources,
+    mode: Mode,
+    breakpoints: BTreeMap<FileHash, BTreeSet<usize>>,
+    break_on_abort: bool,
+    frames: Vec<Frame>,
+    /// Values whose children are shown by the client, indexed by variable reference minus one.
+    /// Only valid while execution is suspended.
+    variables: Vec<Vec<(String, MoveValue)>>,
+    /// Set once the client went away, after which execution runs to completion.
+    disconnected: bool,
+    /// The first I/O error encountered while execution was suspended.
+    error: Option<anyhow::Error>,
+}
+
+impl<'a, R: BufRead, W: Write> Debugger<'a, R, W> {
+    fn new(conn: Connection<R, W>, sources: &'a DebugSources) -> Self {
+        Self {
+            conn,
+            sources,
+            mode: Mode::Continue,
+            breakpoints: BTreeMap::new(),
+            break_on_abort: true,
+            frames: vec![],
+            variables: vec![],
+            disconnected: false,
+            error: None,
+        }
+    }
+
+    /// Handles requests until the client is done configuring the session. Returns `false` if
+    /// the client disconnected instead.
+    fn configure(&mut self) -> Result<bool> {
+        while let Some(request) = self.conn.read_request()? {
+            match request.command.as_str() {
+                "initialize" => {
+                    self.conn.respond(
+                        &request,
+                        json!({
+                            "supportsConfigurationDoneRequest": true,
+                            "exceptionBreakpointFilters": [{
+                                "filter": ABORT_FILTER,
+                                "label": "Aborts",
+                                "default": true,
+                            }],
+                        }),
+                    )?;
+                    self.conn.send_event("initialized", json!({}))?;
+                }
+                "launch" | "attach" => {
+                    if request.arguments["stopOnEntry"].as_bool() == Some(true) {
+                        self.mode = Mode::Entry;
+                    }
+                    self.conn.respond(&request, json!({}))?;
+                }
+                "configurationDone" => {
+                    self.conn.respond(&request, json!({}))?;
+                    return Ok(true);
+                }
+                "disconnect" => {
+                    self.conn.respond(&request, json!({}))?;
+                    return Ok(false);
+                }
+                _ => self.handle_common_request(&request)?,
+            }
+        }
+        Ok(false)
+    }
+
+    /// Handles the requests which are answered the same way whether or not execution is
+    /// suspended.
+    fn handle_common_request(&mut self, request: &Request) -> Result<()> {
+        match request.command.as_str() {
+            "setBreakpoints" => {
+                let body = self.set_breakpoints(&request.arguments);
+                self.conn.respond(request, body)
+            }
+            "setExceptionBreakpoints" => {
+                self.break_on_abort = request.arguments["filters"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .any(|filter| filter == ABORT_FILTER);
+                self.conn.respond(request, json!({}))
+            }
+            "threads" => self.conn.respond(
+                request,
+                json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+            ),
+            command => self
+                .conn
+                .respond_error(request, &format!("Unsupported request '{}'", command)),
+        }
+    }
+
+    fn set_breakpoints(&mut self, arguments: &Json) -> Json {
+        let requested: Vec<usize> = arguments["breakpoints"]
+            .as_array()
+            .map(|breakpoints| {
+                breakpoints
+                    .iter()
+                    .filter_map(|bp| bp["line"].as_u64())
+                    .map(|line| line as usize)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let file_hash = arguments["source"]["path"]
+            .as_str()
+            .and_then(|path| self.sources.file_hash(Path::new(path)));
+        let lines_with_code = file_hash
+            .map(|file_hash| self.sources.lines_with_code(&file_hash))
+            .unwrap_or_default();
+
+        let breakpoints = requested
+            .iter()
+            .map(|line| json!({ "verified": lines_with_code.contains(line), "line": line }))
+            .collect::<Vec<_>>();
+        if let Some(file_hash) = file_hash {
+            self.breakpoints.insert(
+                file_hash,
+                requested
+                    .into_iter()
+                    .filter(|line| lines_with_code.contains(line))
+                    .collect(),
+            );
+        }
+        json!({ "breakpoints": breakpoints })
+    }
+
+    /// Reports that execution is suspended and handles requests until the client resumes it.
+    fn suspend(&mut self, reason: &str, description: Option<String>) {
+        if let Err(err) = self.serve_suspended(reason, description) {
+            self.error = Some(err);
+            self.disconnected = true;
+        }
+        self.variables.clear();
+    }
+
+    fn serve_suspended(&mut self, reason: &str, description: Option<String>) -> Result<()> {
+        let mut body = json!({
+            "reason": reason,
+            "threadId": THREAD_ID,
+            "allThreadsStopped": true,
+        });
+        if let Some(description) = description {
+            body["description"] = json!(description);
+            body["text"] = body["description"].clone();
+        }
+        self.conn.send_event("stopped", body)?;
+
+        loop {
+            let request = match self.conn.read_request()? {
+                Some(request) => request,
+                None => {
+                    self.disconnected = true;
+                    return Ok(());
+                }
+            };
+            let depth = self.frames.len();
+            let line = self.frames.last().and_then(Frame::line);
+            let resume_mode = match request.command.as_str() {
+                "continue" => Some(Mode::Continue),
+                "next" => Some(Mode::StepOver { depth, line }),
+                "stepIn" => Some(Mode::StepIn { depth, line }),
+                "stepOut" => Some(Mode::StepOut { depth }),
+                _ => None,
+            };
+            if let Some(mode) = resume_mode {
+                self.mode = mode;
+                return self
+                    .conn
+                    .respond(&request, json!({ "allThreadsContinued": true }));
+            }
+
+            match request.command.as_str() {
+                "stackTrace" => {
+                    let body = self.stack_trace();
+                    self.conn.respond(&request, body)?
+                }
+                "scopes" => {
+                    let frame = request.arguments["frameId"]
+                        .as_u64()
+                        .and_then(|id| self.frames.get(id as usize));
+                    match frame {
+                        Some(frame) => {
+                            let locals = frame.locals.clone();
+                            let reference = self.add_variables(locals);
+                            self.conn.respond(
+                                &request,
+                                json!({ "scopes": [{
+                                    "name": "Locals",
+                                    "variablesReference": reference,
+                                    "expensive": false,
+                                }]}),
+                            )?
+                        }
+                        None => self.conn.respond_error(&request, "Unknown frame")?,
+                    }
+                }
+                "variables" => {
+                    let children = request.arguments["variablesReference"]
+                        .as_u64()
+                        .and_then(|reference| (reference as usize).checked_sub(1))
+                        .and_then(|idx| self.variables.get(idx))
+                        .cloned();
+                    match children {
+                        Some(children) => {
+                            let body = self.describe_variables(children);
+                            self.conn.respond(&request, body)?
+                        }
+                        None => self.conn.respond_error(&request, "Unknown variable")?,
+                    }
+                }
+                "pause" => self.conn.respond(&request, json!({}))?,
+                "evaluate" => self
+                    .conn
+                    .respond_error(&request, "Expressions cannot be evaluated")?,
+                "disconnect" => {
+                    self.disconnected = true;
+                    return self.conn.respond(&request, json!({}));
+                }
+                _ => self.handle_common_request(&request)?,
+            }
+        }
+    }
+
+    fn stack_trace(&self) -> Json {
+        let frames = self
+            .frames
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(id, frame)| {
+                let mut json = json!({
+                    "id": id,
+                    "name": frame.name,
+                    "line": 0,
+                    "column": 0,
+                });
+                if let Some(pos) = frame.position {
+                    json["line"] = json!(pos.line);
+                    json["column"] = json!(pos.column);
+                    if let Some(path) = self.sources.path(&pos.file_hash) {
+                        json["source"] = json!({
+                            "name": path.file_name().map(|name| name.to_string_lossy()),
+                            "path": path.to_string_lossy(),
+                        });
+                    }
+                }
+                json
+            })
+            .collect::<Vec<_>>();
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+
+    fn add_variables(&mut self, variables: Vec<(String, MoveValue)>) -> usize {
+        self.variables.push(variables);
+        self.variables.len()
+    }
+
+    fn describe_variables(&mut self, variables: Vec<(String, MoveValue)>) -> Json {
+        let variables = variables
+            .into_iter()
+            .map(|(name, value)| {
+                let children = children(&value);
+                let reference = if children.is_empty() {
+                    0
+                } else {
+                    self.add_variables(children)
+                };
+                json!({
+                    "name": name,
+                    "value": value.to_string(),
+                    "variablesReference": reference,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "variables": variables })
+    }
+
+    fn snapshot_locals(&self, function: &TracedFunction) -> Vec<(String, MoveValue)> {
+        (0..function.local_count())
+            .filter_map(|idx| {
+                let name = self.sources.local_name(
+                    function.module_id(),
+                    function.definition_index(),
+                    idx,
+                )?;
+                Some((name, function.local_value(idx)?))
+            })
+            .collect()
+    }
+
+    /// Whether execution should be suspended before the instruction at the current position of
+    /// the top frame, and if so, for what reason. `new_line` is set if the previous instruction
+    /// executed in the frame was on a different line.
+    fn stop_reason(&self, new_line: bool) -> Option<&'static str> {
+        let frame = self.frames.last()?;
+        let line = frame.line()?;
+        let depth = self.frames.len();
+        if new_line
+            && matches!(self.breakpoints.get(&line.0), Some(lines) if lines.contains(&line.1))
+        {
+            return Some("breakpoint");
+        }
+        match self.mode {
+            Mode::Continue => None,
+            Mode::Entry => Some("entry"),
+            Mode::StepIn {
+                depth: start_depth,
+                line: start_line,
+            } => (depth != start_depth || Some(line) != start_line).then_some("step"),
+            Mode::StepOver {
+                depth: start_depth,
+                line: start_line,
+            } => (depth < start_depth || (depth == start_depth && Some(line) != start_line))
+                .then_some("step"),
+            Mode::StepOut { depth: start_depth } => (depth < start_depth).then_some("step"),
+        }
+    }
+}
+
+impl<'a, R: BufRead, W: Write> ExecutionTracer for Debugger<'a, R, W> {
+    fn enter_function(&mut self, function: &TracedFunction) {
+        self.frames.push(Frame {
+            name: function.pretty_string(),
+            module_id: function.module_id().cloned(),
+            function: function.definition_index(),
+            position: None,
+            locals: vec![],
+        });
+    }
+
+    fn exit_function(&mut self, _function: &TracedFunction) {
+        self.frames.pop();
+    }
+
+    fn unwind_function(&mut self, _function: &TracedFunction, _error: &VMError) {
+        self.frames.pop();
+    }
+
+    fn instruction(
+        &mut self,
+        function: &TracedFunction,
+        pc: CodeOffset,
+        instr: &Bytecode,
+        _stack: &[Value],
+    ) {
+        if self.disconnected {
+            return;
+        }
+        let sources = self.sources;
+        let frame = match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let position = sources.position(frame.module_id.as_ref(), frame.function, pc);
+        let new_line =
+            position.is_some() && frame.line() != position.map(|p| (p.file_hash, p.line));
+        frame.position = position;
+
+        if let Some(reason) = self.stop_reason(new_line) {
+            let locals = self.snapshot_locals(function);
+            self.frames.last_mut().unwrap().locals = locals;
+            self.suspend(reason, None);
+        }
+        // The locals of a caller are no longer accessible once the callee runs, so keep a copy.
+        if matches!(instr, Bytecode::Call(_) | Bytecode::CallGeneric(_)) && !self.disconnected {
+            let locals = self.snapshot_locals(function);
+            self.frames.last_mut().unwrap().locals = locals;
+        }
+    }
+
+    fn abort(&mut self, function: &TracedFunction, code: u64) {
+        if self.disconnected || !self.break_on_abort {
+            return;
+        }
+        // Native functions have no frame of their own, the abort is reported in the caller.
+        if !function.is_native() {
+            let locals = self.snapshot_locals(function);
+            if let Some(frame) = self.frames.last_mut() {
+                frame.locals = locals;
+            }
+        }
+        self.suspend("exception", Some(format!("Aborted with code {}", code)));
+    }
+}
+
+/// The named children of a value shown as an expandable variable.
+fn children(value: &MoveValue) -> Vec<(String, MoveValue)> {
+    match value {
+        MoveValue::Vector(elems) => elems
+            .iter()
+            .enumerate()
+            .map(|(idx, elem)| (format!("[{}]", idx), elem.clone()))
+            .collect(),
+        MoveValue::Struct(MoveStruct::WithTypes { fields, .. })
+        | MoveValue::Struct(MoveStruct::WithFields(fields)) => fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect(),
+        MoveValue::Struct(MoveStruct::Runtime(fields)) => fields
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| (idx.to_string(), value.clone()))
+            .collect(),
+        MoveValue::Variant(variant) => variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| (idx.to_string(), value.clone()))
+            .collect(),
+        _ => vec![],
+    }
+}
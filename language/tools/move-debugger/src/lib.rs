@@ -0,0 +1,16 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A source-level debugger for Move, exposed as a Debug Adapter Protocol server.
+//!
+//! The adapter is installed as an `ExecutionTracer` on a VM session. It uses the source maps of
+//! the code being executed to map bytecode offsets to source lines, which it needs to resolve
+//! breakpoints, step through the code line by line and show the call stack. Locals are shown by
+//! their source names with their values annotated with struct and field names.
+
+pub mod adapter;
+pub mod protocol;
+pub mod sources;
+
+pub use adapter::run_debug_adapter;
+pub use sources::DebugSources;
@@ -0,0 +1,114 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Message framing for the Debug Adapter Protocol: every message is a JSON object preceded by a
+//! `Content-Length` header.
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+/// A request sent by the client.
+#[derive(Debug)]
+pub struct Request {
+    pub seq: i64,
+    pub command: String,
+    pub arguments: Value,
+}
+
+/// A bidirectional connection to a DAP client.
+pub struct Connection<R, W> {
+    reader: R,
+    writer: W,
+    seq: i64,
+}
+
+impl<R: BufRead, W: Write> Connection<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer,
+            seq: 0,
+        }
+    }
+
+    /// Reads the next request, or returns `None` once the client closed the connection.
+    pub fn read_request(&mut self) -> Result<Option<Request>> {
+        let mut content_length = None;
+        loop {
+            let mut header = String::new();
+            if self.reader.read_line(&mut header)? == 0 {
+                return Ok(None);
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                if content_length.is_some() {
+                    break;
+                }
+                continue;
+            }
+            if let Some(len) = header.strip_prefix("Content-Length:") {
+                content_length = Some(len.trim().parse::<usize>()?);
+            }
+        }
+
+        let mut content = vec![0; content_length.unwrap()];
+        self.reader.read_exact(&mut content)?;
+        let message: Value = serde_json::from_slice(&content)?;
+        if message["type"] != "request" {
+            bail!("Expected a request, got {}", message)
+        }
+        Ok(Some(Request {
+            seq: message["seq"]
+                .as_i64()
+                .ok_or_else(|| anyhow!("Request without sequence number"))?,
+            command: message["command"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Request without command"))?
+                .to_string(),
+            arguments: message["arguments"].clone(),
+        }))
+    }
+
+    pub fn respond(&mut self, request: &Request, body: Value) -> Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": true,
+            "command": request.command,
+            "body": body,
+        }))
+    }
+
+    pub fn respond_error(&mut self, request: &Request, message: &str) -> Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": false,
+            "command": request.command,
+            "message": message,
+        }))
+    }
+
+    pub fn send_event(&mut self, event: &str, body: Value) -> Result<()> {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }))
+    }
+
+    fn send(&mut self, mut message: Value) -> Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        let content = message.to_string();
+        write!(
+            self.writer,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
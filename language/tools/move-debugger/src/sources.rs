@@ -0,0 +1,165 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::{CodeOffset, FunctionDefinitionIndex};
+use move_bytecode_source_map::source_map::{FunctionSourceMap, SourceMap};
+use move_command_line_common::files::FileHash;
+use move_core_types::language_storage::ModuleId;
+use move_ir_types::location::Loc;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+/// Prefix of compiler-introduced temporaries, which are not shown to the user.
+const TEMP_PREFIX: char = '%';
+/// Separator of the suffix the compiler appends to local names to make them unique.
+const LOCAL_SUFFIX_DELIM: char = '#';
+
+/// A position in a source file. Lines and columns start at 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourcePosition {
+    pub file_hash: FileHash,
+    pub line: usize,
+    pub column: usize,
+}
+
+struct SourceFile {
+    path: PathBuf,
+    line_starts: Vec<usize>,
+}
+
+/// The source maps and source files of the code being debugged, used to translate between
+/// bytecode offsets and source positions.
+#[derive(Default)]
+pub struct DebugSources {
+    modules: BTreeMap<ModuleId, SourceMap>,
+    script: Option<SourceMap>,
+    files: HashMap<FileHash, SourceFile>,
+}
+
+impl DebugSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_module(&mut self, module_id: ModuleId, source_map: SourceMap) {
+        self.modules.insert(module_id, source_map);
+    }
+
+    pub fn set_script(&mut self, source_map: SourceMap) {
+        self.script = Some(source_map);
+    }
+
+    /// Registers a source file the source maps may refer to.
+    pub fn add_file(&mut self, path: &Path, contents: &str) {
+        let line_starts = std::iter::once(0)
+            .chain(contents.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        self.files.insert(
+            FileHash::new(contents),
+            SourceFile {
+                path: canonical_path(path),
+                line_starts,
+            },
+        );
+    }
+
+    pub fn path(&self, file_hash: &FileHash) -> Option<&Path> {
+        self.files.get(file_hash).map(|file| file.path.as_path())
+    }
+
+    /// The source file registered under `path`, if any.
+    pub fn file_hash(&self, path: &Path) -> Option<FileHash> {
+        let path = canonical_path(path);
+        self.files
+            .iter()
+            .find(|(_, file)| file.path == path)
+            .map(|(file_hash, _)| *file_hash)
+    }
+
+    /// The source position of the instruction at `pc` in the given function of `module_id`, or of
+    /// the script if `module_id` is `None`.
+    pub fn position(
+        &self,
+        module_id: Option<&ModuleId>,
+        function: FunctionDefinitionIndex,
+        pc: CodeOffset,
+    ) -> Option<SourcePosition> {
+        let loc = self
+            .function_source_map(module_id, function)?
+            .get_code_location(pc)?;
+        self.loc_position(loc)
+    }
+
+    /// The source name of local `idx` of the given function, or `None` if the local is a compiler
+    /// temporary.
+    pub fn local_name(
+        &self,
+        module_id: Option<&ModuleId>,
+        function: FunctionDefinitionIndex,
+        idx: usize,
+    ) -> Option<String> {
+        match self.function_source_map(module_id, function) {
+            Some(function_map) => {
+                let (name, _) = function_map.get_parameter_or_local_name(idx as u64)?;
+                if name.starts_with(TEMP_PREFIX) {
+                    return None;
+                }
+                Some(match name.find(LOCAL_SUFFIX_DELIM) {
+                    Some(end) => name[..end].to_string(),
+                    None => name,
+                })
+            }
+            None => Some(format!("local_{}", idx)),
+        }
+    }
+
+    /// The lines of `file_hash` which have code attached to them, i.e. the lines on which a
+    /// breakpoint can be hit.
+    pub fn lines_with_code(&self, file_hash: &FileHash) -> BTreeSet<usize> {
+        self.modules
+            .values()
+            .chain(self.script.iter())
+            .flat_map(|source_map| {
+                (0..)
+                    .map_while(|idx| {
+                        source_map
+                            .get_function_source_map(FunctionDefinitionIndex(idx))
+                            .ok()
+                    })
+                    .flat_map(|function_map| function_map.code_map.values())
+            })
+            .filter(|loc| loc.file_hash() == *file_hash)
+            .filter_map(|loc| self.loc_position(*loc))
+            .map(|position| position.line)
+            .collect()
+    }
+
+    fn function_source_map(
+        &self,
+        module_id: Option<&ModuleId>,
+        function: FunctionDefinitionIndex,
+    ) -> Option<&FunctionSourceMap> {
+        let source_map = match module_id {
+            Some(module_id) => self.modules.get(module_id)?,
+            None => self.script.as_ref()?,
+        };
+        source_map.get_function_source_map(function).ok()
+    }
+
+    fn loc_position(&self, loc: Loc) -> Option<SourcePosition> {
+        let file = self.files.get(&loc.file_hash())?;
+        let offset = loc.start() as usize;
+        let line = file.line_starts.partition_point(|start| *start <= offset);
+        Some(SourcePosition {
+            file_hash: loc.file_hash(),
+            line,
+            column: offset - file.line_starts[line - 1] + 1,
+        })
+    }
+}
+
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,255 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::{compiled_unit::AnnotatedCompiledUnit, Compiler};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+};
+use move_debugger::{run_debug_adapter, DebugSources};
+use move_vm_runtime::move_vm::MoveVM;
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, io::Cursor, path::PathBuf};
+use tempfile::TempDir;
+
+const CODE: &str = r#"module 0x42::M {
+    struct S has drop { a: u64, b: vector<u8> }
+
+    fun make(x: u64): S {
+        S { a: x, b: vector[1, 2] }
+    }
+
+    public fun run(x: u64): u64 {
+        let s = make(x);
+        let y = s.a + x;
+        y
+    }
+}
+"#;
+
+fn module_id() -> ModuleId {
+    ModuleId::new(
+        AccountAddress::from_hex_literal("0x42").unwrap(),
+        Identifier::new("M").unwrap(),
+    )
+}
+
+/// The line of `CODE` with the statement after the call to `make`.
+const BREAKPOINT_LINE: u64 = 10;
+
+struct Setup {
+    _dir: TempDir,
+    path: PathBuf,
+    storage: InMemoryStorage,
+    sources: DebugSources,
+}
+
+fn setup() -> Setup {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("M.move");
+    std::fs::write(&path, CODE).unwrap();
+
+    let (_, units) = Compiler::from_files(
+        vec![path.to_str().unwrap().to_string()],
+        vec![],
+        BTreeMap::<String, _>::new(),
+    )
+    .build_and_report()
+    .unwrap();
+    let module = match units.into_iter().next().unwrap() {
+        AnnotatedCompiledUnit::Module(module) => module.named_module,
+        AnnotatedCompiledUnit::Script(_) => panic!("expected a module"),
+    };
+
+    let module_id = module_id();
+    let mut blob = vec![];
+    module.module.serialize(&mut blob).unwrap();
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+
+    let mut sources = DebugSources::new();
+    sources.add_module(module_id, module.source_map);
+    sources.add_file(&path, CODE);
+    Setup {
+        _dir: dir,
+        path,
+        storage,
+        sources,
+    }
+}
+
+fn encode(requests: Vec<Value>) -> Vec<u8> {
+    let mut input = vec![];
+    for (seq, mut request) in requests.into_iter().enumerate() {
+        request["seq"] = json!(seq + 1);
+        request["type"] = json!("request");
+        let content = request.to_string();
+        input.extend(format!("Content-Length: {}\r\n\r\n{}", content.len(), content).bytes());
+    }
+    input
+}
+
+fn decode(mut output: &str) -> Vec<Value> {
+    let mut messages = vec![];
+    while let Some(rest) = output.strip_prefix("Content-Length: ") {
+        let (len, rest) = rest.split_once("\r\n\r\n").unwrap();
+        let len: usize = len.parse().unwrap();
+        messages.push(serde_json::from_str(&rest[..len]).unwrap());
+        output = &rest[len..];
+    }
+    assert!(output.is_empty());
+    messages
+}
+
+/// Runs `M::run(20)` under the debugger, driven by `requests`, and returns the messages sent by
+/// the debugger.
+fn debug(setup: &Setup, requests: Vec<Value>) -> Vec<Value> {
+    let vm = MoveVM::new(vec![]).unwrap();
+    let mut output = vec![];
+    run_debug_adapter(
+        Cursor::new(encode(requests)),
+        &mut output,
+        &setup.sources,
+        |tracer| {
+            let mut session = vm.new_session_with_tracer(&setup.storage, tracer);
+            session
+                .execute_function_bypass_visibility(
+                    &module_id(),
+                    &Identifier::new("run").unwrap(),
+                    vec![],
+                    serialize_values(&vec![MoveValue::U64(20)]),
+                    &mut UnmeteredGasMeter,
+                )
+                .map(|result| format!("{:?}", result.return_values))
+                .map_err(|err| err.to_string())
+        },
+    )
+    .unwrap();
+    decode(&String::from_utf8(output).unwrap())
+}
+
+fn response<'a>(messages: &'a [Value], command: &str, nth: usize) -> &'a Value {
+    let response = messages
+        .iter()
+        .filter(|msg| msg["type"] == "response" && msg["command"] == command)
+        .nth(nth)
+        .unwrap();
+    assert_eq!(response["success"], true, "{}", response);
+    &response["body"]
+}
+
+fn stopped_reasons(messages: &[Value]) -> Vec<&str> {
+    messages
+        .iter()
+        .filter(|msg| msg["type"] == "event" && msg["event"] == "stopped")
+        .map(|msg| msg["body"]["reason"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn breakpoint_and_locals() {
+    let setup = setup();
+    let messages = debug(
+        &setup,
+        vec![
+            json!({ "command": "initialize", "arguments": {} }),
+            json!({ "command": "launch", "arguments": {} }),
+            json!({ "command": "setBreakpoints", "arguments": {
+                "source": { "path": setup.path },
+                "breakpoints": [{ "line": BREAKPOINT_LINE }, { "line": 1 }],
+            }}),
+            json!({ "command": "configurationDone" }),
+            json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+            json!({ "command": "scopes", "arguments": { "frameId": 0 } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": 1 } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": 2 } }),
+            json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+            json!({ "command": "disconnect" }),
+        ],
+    );
+
+    // Only the line with code can have a breakpoint.
+    assert_eq!(
+        response(&messages, "setBreakpoints", 0)["breakpoints"],
+        json!([
+            { "verified": true, "line": BREAKPOINT_LINE },
+            { "verified": false, "line": 1 },
+        ])
+    );
+    assert_eq!(stopped_reasons(&messages), vec!["breakpoint"]);
+
+    let frames = &response(&messages, "stackTrace", 0)["stackFrames"];
+    assert_eq!(frames.as_array().unwrap().len(), 1);
+    assert_eq!(frames[0]["line"], BREAKPOINT_LINE);
+    assert_eq!(frames[0]["source"]["name"], "M.move");
+
+    let locals = &response(&messages, "variables", 0)["variables"];
+    let names: Vec<_> = locals
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|var| var["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["x", "s"]);
+    assert_eq!(locals[0]["value"], "20u64");
+    assert_eq!(locals[1]["variablesReference"], 2);
+
+    let fields = &response(&messages, "variables", 1)["variables"];
+    assert_eq!(fields[0]["name"], "a");
+    assert_eq!(fields[0]["value"], "20u64");
+    assert_eq!(fields[1]["name"], "b");
+
+    let exited = messages
+        .iter()
+        .find(|msg| msg["event"] == "exited")
+        .unwrap();
+    assert_eq!(exited["body"]["exitCode"], 0);
+}
+
+#[test]
+fn step_in_and_out() {
+    let setup = setup();
+    let messages = debug(
+        &setup,
+        vec![
+            json!({ "command": "initialize", "arguments": {} }),
+            json!({ "command": "launch", "arguments": { "stopOnEntry": true } }),
+            json!({ "command": "configurationDone" }),
+            json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+            json!({ "command": "stepIn", "arguments": { "threadId": 1 } }),
+            json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+            json!({ "command": "stepOut", "arguments": { "threadId": 1 } }),
+            json!({ "command": "next", "arguments": { "threadId": 1 } }),
+            json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+            json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+            json!({ "command": "disconnect" }),
+        ],
+    );
+    assert_eq!(
+        stopped_reasons(&messages),
+        vec!["entry", "step", "step", "step"]
+    );
+
+    let entry = &response(&messages, "stackTrace", 0)["stackFrames"];
+    assert_eq!(entry[0]["line"], BREAKPOINT_LINE - 1);
+
+    let stepped_in = &response(&messages, "stackTrace", 1)["stackFrames"];
+    let names: Vec<_> = stepped_in
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|frame| frame["name"].as_str().unwrap())
+        .collect();
+    assert!(names[0].ends_with("::M::make"), "{:?}", names);
+    assert!(names[1].ends_with("::M::run"), "{:?}", names);
+    assert_eq!(stepped_in[0]["line"], 5);
+
+    // Stepping out returns to the call, stepping over it moves on to the next line.
+    let stepped_over = &response(&messages, "stackTrace", 2)["stackFrames"];
+    assert_eq!(stepped_over.as_array().unwrap().len(), 1);
+    assert_eq!(stepped_over[0]["line"], BREAKPOINT_LINE);
+}
@@ -11,10 +11,11 @@ use move_binary_format::{
     binary_views::BinaryIndexedView,
     control_flow_graph::{ControlFlowGraph, VMControlFlowGraph},
     file_format::{
-        Ability, AbilitySet, Bytecode, CodeUnit, FieldHandleIndex, FunctionDefinition,
-        FunctionDefinitionIndex, FunctionHandle, ModuleHandle, Signature, SignatureIndex,
-        SignatureToken, StructDefinition, StructDefinitionIndex, StructFieldInformation,
-        StructTypeParameter, TableIndex, TypeSignature, Visibility,
+        Ability, AbilitySet, Bytecode, CodeUnit, EnumDefinition, EnumDefinitionIndex,
+        FieldDefinition, FieldHandleIndex, FunctionDefinition, FunctionDefinitionIndex,
+        FunctionHandle, ModuleHandle, Signature, SignatureIndex, SignatureToken, StructDefinition,
+        StructDefinitionIndex, StructFieldInformation, StructTypeParameter, TableIndex,
+        TypeSignature, VariantHandleIndex, VariantInstantiationHandleIndex, VariantTag, Visibility,
     },
 };
 use move_bytecode_source_map::{
@@ -22,7 +23,10 @@ use move_bytecode_source_map::{
     source_map::{FunctionSourceMap, SourceName},
 };
 use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule, NamedCompiledScript};
-use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
+use move_core_types::{
+    identifier::{is_positional_struct, IdentStr},
+    language_storage::ModuleId,
+};
 use move_coverage::coverage_map::{ExecCoverageMap, FunctionCoverage};
 use move_ir_types::location::Loc;
 
@@ -239,6 +243,26 @@ impl<'a> Disassembler<'a> {
         }
     }
 
+    fn get_enum_def(&self, enum_definition_index: EnumDefinitionIndex) -> Result<&EnumDefinition> {
+        if enum_definition_index.0 as usize
+            >= self
+                .source_mapper
+                .bytecode
+                .enum_defs()
+                .map_or(0, |d| d.len())
+        {
+            bail!("Invalid enum definition index supplied when marking enum")
+        }
+        match self
+            .source_mapper
+            .bytecode
+            .enum_def_at(enum_definition_index)
+        {
+            Ok(definition) => Ok(definition),
+            Err(err) => Err(Error::new(err)),
+        }
+    }
+
     //***************************************************************************
     // Code Coverage Helpers
     //***************************************************************************
@@ -309,11 +333,14 @@ impl<'a> Disassembler<'a> {
                 .get(field_handle.field as usize)
                 .ok_or_else(|| format_err!("Bad field index"))?,
         };
-        let field_name = self
-            .source_mapper
-            .bytecode
-            .identifier_at(field_def.name)
-            .to_string();
+        let field_name = if self.is_positional(struct_def) {
+            field_handle.field.to_string()
+        } else {
+            self.source_mapper
+                .bytecode
+                .identifier_at(field_def.name)
+                .to_string()
+        };
         let struct_handle = self
             .source_mapper
             .bytecode
@@ -374,6 +401,92 @@ impl<'a> Disassembler<'a> {
         Ok((name, Self::format_type_params(&type_arguments)))
     }
 
+    // Enums have no source map, so their type parameters are named like the ones of structs in
+    // a dummy source map
+    fn enum_type_parameters(&self, enum_definition: &EnumDefinition) -> Vec<SourceName> {
+        let enum_handle = self
+            .source_mapper
+            .bytecode
+            .struct_handle_at(enum_definition.enum_handle);
+        (0..enum_handle.type_parameters.len())
+            .map(|i| {
+                (
+                    format!("Ty{}", i),
+                    self.source_mapper.source_map.definition_location,
+                )
+            })
+            .collect()
+    }
+
+    fn variant_type_info(
+        &self,
+        enum_idx: EnumDefinitionIndex,
+        variant: VariantTag,
+        signature: &Signature,
+        type_param_context: &[SourceName],
+    ) -> Result<String> {
+        let enum_definition = self.get_enum_def(enum_idx)?;
+        let type_arguments = signature
+            .0
+            .iter()
+            .map(|sig_tok| self.disassemble_sig_tok(sig_tok.clone(), type_param_context))
+            .collect::<Result<Vec<String>>>()?;
+        let enum_handle = self
+            .source_mapper
+            .bytecode
+            .struct_handle_at(enum_definition.enum_handle);
+        let variant_definition = enum_definition
+            .variant(variant)
+            .ok_or_else(|| format_err!("Bad variant tag"))?;
+        Ok(format!(
+            "{}{}::{}",
+            self.source_mapper.bytecode.identifier_at(enum_handle.name),
+            Self::format_type_params(&type_arguments),
+            self.source_mapper
+                .bytecode
+                .identifier_at(variant_definition.name)
+        ))
+    }
+
+    fn variant_handle_info(
+        &self,
+        variant_idx: VariantHandleIndex,
+        type_param_context: &[SourceName],
+    ) -> Result<String> {
+        let variant_handle = self.source_mapper.bytecode.variant_handle_at(variant_idx)?;
+        self.variant_type_info(
+            variant_handle.enum_def,
+            variant_handle.variant,
+            &Signature(vec![]),
+            type_param_context,
+        )
+    }
+
+    fn variant_instantiation_handle_info(
+        &self,
+        variant_idx: VariantInstantiationHandleIndex,
+        type_param_context: &[SourceName],
+    ) -> Result<String> {
+        let variant_handle = self
+            .source_mapper
+            .bytecode
+            .variant_instantiation_handle_at(variant_idx)?;
+        let enum_inst = self
+            .source_mapper
+            .bytecode
+            .enum_instantiation_at(variant_handle.enum_def)?;
+        let type_params = self
+            .source_mapper
+            .bytecode
+            .signature_at(enum_inst.type_parameters);
+        self.variant_type_info(
+            enum_inst.def,
+            variant_handle.variant,
+            type_params,
+            type_param_context,
+        )
+    }
+
     fn name_for_parameter_or_local(
         &self,
         local_idx: usize,
@@ -922,6 +1035,75 @@ impl<'a> Disassembler<'a> {
                     Self::format_ret_type(&type_rets)
                 ))
             }
+            Bytecode::PackVariant(variant_idx) => {
+                let variant =
+                    self.variant_handle_info(*variant_idx, &function_source_map.type_parameters)?;
+                Ok(format!("PackVariant[{}]({})", variant_idx, variant))
+            }
+            Bytecode::PackVariantGeneric(variant_idx) => {
+                let variant = self.variant_instantiation_handle_info(
+                    *variant_idx,
+                    &function_source_map.type_parameters,
+                )?;
+                Ok(format!("PackVariantGeneric[{}]({})", variant_idx, variant))
+            }
+            Bytecode::UnpackVariant(variant_idx) => {
+                let variant =
+                    self.variant_handle_info(*variant_idx, &function_source_map.type_parameters)?;
+                Ok(format!("UnpackVariant[{}]({})", variant_idx, variant))
+            }
+            Bytecode::UnpackVariantGeneric(variant_idx) => {
+                let variant = self.variant_instantiation_handle_info(
+                    *variant_idx,
+                    &function_source_map.type_parameters,
+                )?;
+                Ok(format!(
+                    "UnpackVariantGeneric[{}]({})",
+                    variant_idx, variant
+                ))
+            }
+            Bytecode::UnpackVariantImmRef(variant_idx) => {
+                let variant =
+                    self.variant_handle_info(*variant_idx, &function_source_map.type_parameters)?;
+                Ok(format!("UnpackVariantImmRef[{}]({})", variant_idx, variant))
+            }
+            Bytecode::UnpackVariantImmRefGeneric(variant_idx) => {
+                let variant = self.variant_instantiation_handle_info(
+                    *variant_idx,
+                    &function_source_map.type_parameters,
+                )?;
+                Ok(format!(
+                    "UnpackVariantImmRefGeneric[{}]({})",
+                    variant_idx, variant
+                ))
+            }
+            Bytecode::UnpackVariantMutRef(variant_idx) => {
+                let variant =
+                    self.variant_handle_info(*variant_idx, &function_source_map.type_parameters)?;
+                Ok(format!("UnpackVariantMutRef[{}]({})", variant_idx, variant))
+            }
+            Bytecode::UnpackVariantMutRefGeneric(variant_idx) => {
+                let variant = self.variant_instantiation_handle_info(
+                    *variant_idx,
+                    &function_source_map.type_parameters,
+                )?;
+                Ok(format!(
+                    "UnpackVariantMutRefGeneric[{}]({})",
+                    variant_idx, variant
+                ))
+            }
+            Bytecode::TestVariant(variant_idx) => {
+                let variant =
+                    self.variant_handle_info(*variant_idx, &function_source_map.type_parameters)?;
+                Ok(format!("TestVariant[{}]({})", variant_idx, variant))
+            }
+            Bytecode::TestVariantGeneric(variant_idx) => {
+                let variant = self.variant_instantiation_handle_info(
+                    *variant_idx,
+                    &function_source_map.type_parameters,
+                )?;
+                Ok(format!("TestVariantGeneric[{}]({})", variant_idx, variant))
+            }
             // All other instructions are OK to be printed using the standard debug print.
             x => Ok(format!("{:#?}", x)),
         }
@@ -1161,6 +1343,19 @@ impl<'a> Disassembler<'a> {
         ))
     }
 
+    // Positional structs, declared as `struct S(T1, T2)`, are displayed with their field indices
+    // rather than with the `pos{idx}` names they are compiled to
+    fn is_positional(&self, struct_def: &StructDefinition) -> bool {
+        match &struct_def.field_information {
+            StructFieldInformation::Native => false,
+            StructFieldInformation::Declared(fields) => is_positional_struct(
+                fields
+                    .iter()
+                    .map(|field| self.source_mapper.bytecode.identifier_at(field.name)),
+            ),
+        }
+    }
+
     // The struct defs will filter out the structs that we print to only be the ones that are
     // defined in the module in question.
     pub fn disassemble_struct_def(&self, struct_def_idx: StructDefinitionIndex) -> Result<String> {
@@ -1215,18 +1410,33 @@ impl<'a> Disassembler<'a> {
             &struct_source_map.type_parameters,
             &struct_handle.type_parameters,
         );
-        let mut fields = match field_info {
+        let field_tys = match &field_info {
             None => vec![],
             Some(field_info) => field_info
                 .iter()
-                .map(|(name, ty)| {
-                    let ty_str =
-                        self.disassemble_sig_tok(ty.0.clone(), &struct_source_map.type_parameters)?;
-                    Ok(format!("{}: {}", name, ty_str))
+                .map(|(_, ty)| {
+                    self.disassemble_sig_tok(ty.0.clone(), &struct_source_map.type_parameters)
                 })
                 .collect::<Result<Vec<String>>>()?,
         };
 
+        if self.is_positional(struct_definition) {
+            return Ok(format!(
+                "struct {name}{ty_params}({tys}){abilities}",
+                name = name,
+                ty_params = ty_params,
+                tys = field_tys.join(", "),
+                abilities = abilities,
+            ));
+        }
+
+        let mut fields = field_info
+            .iter()
+            .flatten()
+            .zip(field_tys)
+            .map(|((name, _), ty_str)| format!("{}: {}", name, ty_str))
+            .collect::<Vec<_>>();
+
         if let Some(first_elem) = fields.first_mut() {
             first_elem.insert_str(0, "{\n\t");
         }
@@ -1245,6 +1455,89 @@ impl<'a> Disassembler<'a> {
         ))
     }
 
+    fn disassemble_fields(
+        &self,
+        fields: &[FieldDefinition],
+        type_param_context: &[SourceName],
+    ) -> Result<Vec<(&IdentStr, String)>> {
+        fields
+            .iter()
+            .map(|field_definition| {
+                let field_name = self
+                    .source_mapper
+                    .bytecode
+                    .identifier_at(field_definition.name);
+                let ty = self.disassemble_sig_tok(
+                    field_definition.signature.0.clone(),
+                    type_param_context,
+                )?;
+                Ok((field_name, ty))
+            })
+            .collect()
+    }
+
+    pub fn disassemble_enum_def(&self, enum_def_idx: EnumDefinitionIndex) -> Result<String> {
+        let enum_definition = self.get_enum_def(enum_def_idx)?;
+        let enum_handle = self
+            .source_mapper
+            .bytecode
+            .struct_handle_at(enum_definition.enum_handle);
+        let type_parameters = self.enum_type_parameters(enum_definition);
+
+        let abilities = if enum_handle.abilities == AbilitySet::EMPTY {
+            String::new()
+        } else {
+            let ability_vec: Vec<_> = enum_handle
+                .abilities
+                .into_iter()
+                .map(Self::format_ability)
+                .collect();
+            format!(" has {}", ability_vec.join(", "))
+        };
+
+        let name = self
+            .source_mapper
+            .bytecode
+            .identifier_at(enum_handle.name)
+            .to_string();
+
+        let ty_params =
+            Self::disassemble_struct_type_formals(&type_parameters, &enum_handle.type_parameters);
+
+        let variants = enum_definition
+            .variants
+            .iter()
+            .map(|variant_definition| {
+                let variant_name = self
+                    .source_mapper
+                    .bytecode
+                    .identifier_at(variant_definition.name);
+                let fields =
+                    self.disassemble_fields(&variant_definition.fields, &type_parameters)?;
+                if fields.is_empty() {
+                    return Ok(variant_name.to_string());
+                }
+                if is_positional_struct(fields.iter().map(|(name, _)| *name)) {
+                    let tys = fields.into_iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+                    return Ok(format!("{}({})", variant_name, tys.join(", ")));
+                }
+                let fields = fields
+                    .into_iter()
+                    .map(|(name, ty)| format!("{}: {}", name, ty))
+                    .collect::<Vec<_>>();
+                Ok(format!("{} {{ {} }}", variant_name, fields.join(", ")))
+            })
+            .collect::<Result<Vec<String>>>()?;
+
+        Ok(format!(
+            "enum {name}{ty_params}{abilities} {{\n\t{variants}\n}}",
+            name = name,
+            ty_params = ty_params,
+            abilities = abilities,
+            variants = variants.join(",\n\t"),
+        ))
+    }
+
     pub fn disassemble(&self) -> Result<String> {
         let name_opt = self.source_mapper.source_map.module_name_opt.as_ref();
         let name = name_opt.map(|(addr, n)| format!("{}.{}", addr.short_str_lossless(), n));
@@ -1261,13 +1554,21 @@ impl<'a> Disassembler<'a> {
             .iter()
             .filter_map(|h| self.get_import_string(h))
             .collect::<Vec<String>>();
-        let struct_defs: Vec<String> = (0..self
+        let mut struct_defs: Vec<String> = (0..self
             .source_mapper
             .bytecode
             .struct_defs()
             .map_or(0, |d| d.len()))
             .map(|i| self.disassemble_struct_def(StructDefinitionIndex(i as TableIndex)))
             .collect::<Result<Vec<String>>>()?;
+        let enum_defs: Vec<String> = (0..self
+            .source_mapper
+            .bytecode
+            .enum_defs()
+            .map_or(0, |d| d.len()))
+            .map(|i| self.disassemble_enum_def(EnumDefinitionIndex(i as TableIndex)))
+            .collect::<Result<Vec<String>>>()?;
+        struct_defs.extend(enum_defs);
 
         let function_defs: Vec<String> = match self.source_mapper.bytecode {
             BinaryIndexedView::Script(script) => {
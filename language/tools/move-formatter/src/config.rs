@@ -0,0 +1,68 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Name of the configuration file, looked up in the package root
+pub const CONFIG_FILE_NAME: &str = ".movefmt.toml";
+
+/// Name of the section of the package manifest holding the configuration
+pub const MANIFEST_SECTION: &str = "fmt";
+
+const MANIFEST_FILE_NAME: &str = "Move.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FormatConfig {
+    /// Number of spaces per indentation level
+    pub indent_width: usize,
+    /// Maximum number of consecutive blank lines kept
+    pub max_blank_lines: usize,
+    /// Maximum line width, beyond which lines are broken where possible
+    pub max_width: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            max_blank_lines: 1,
+            max_width: 100,
+        }
+    }
+}
+
+impl FormatConfig {
+    /// Reads the configuration of the package at `root`. A `.movefmt.toml` takes precedence over
+    /// the `[fmt]` section of the package's `Move.toml`. Missing values are set to their defaults
+    pub fn from_package_root(root: &Path) -> anyhow::Result<Self> {
+        let config_path = root.join(CONFIG_FILE_NAME);
+        if config_path.is_file() {
+            let contents = std::fs::read_to_string(&config_path)?;
+            return toml::from_str(&contents)
+                .with_context(|| format!("Error parsing '{}'", config_path.display()));
+        }
+
+        let manifest_path = root.join(MANIFEST_FILE_NAME);
+        if !manifest_path.is_file() {
+            return Ok(Self::default());
+        }
+        let manifest: toml::Value = std::fs::read_to_string(&manifest_path)?
+            .parse()
+            .with_context(|| format!("Error parsing '{}'", manifest_path.display()))?;
+        match manifest.get(MANIFEST_SECTION) {
+            None => Ok(Self::default()),
+            Some(section @ toml::Value::Table(_)) => {
+                section.clone().try_into().with_context(|| {
+                    format!("Error parsing '[{}]' section of manifest", MANIFEST_SECTION)
+                })
+            }
+            Some(_) => bail!(
+                "Expected a table for the '[{}]' section of manifest",
+                MANIFEST_SECTION
+            ),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A document model for pretty printing, in the style of Wadler's "A prettier printer". A
+//! document is laid out within a maximum line width by breaking the lines of its groups, from
+//! the outermost group inwards, until each line fits.
+
+/// A document to be laid out
+#[derive(Debug, Clone)]
+pub(crate) enum Doc {
+    /// Text without newlines
+    Text(String),
+    /// A comment. Line comments must be followed by a newline. The lines of a block comment after
+    /// the first one are shifted along with the first line, which started at `column` in the
+    /// source
+    Comment {
+        text: String,
+        column: usize,
+        is_line: bool,
+    },
+    /// A space, or a newline if the enclosing group is broken
+    Line,
+    /// Nothing, or a newline if the enclosing group is broken
+    SoftLine,
+    /// A newline, which breaks all enclosing groups
+    HardLine,
+    /// A newline unless at the start of a line. Breaks all enclosing groups
+    LineStart,
+    /// The first document if the enclosing group is broken, the second one otherwise
+    IfBreak(Box<Doc>, Box<Doc>),
+    /// A document whose lines are indented by one more level
+    Nest(Box<Doc>),
+    /// A document whose lines are broken together, or not at all. The flag is set if the group
+    /// must be broken, as it contains a hard line break
+    Group(Box<Doc>, bool),
+    Concat(Vec<Doc>),
+}
+
+pub(crate) fn nil() -> Doc {
+    Doc::Concat(vec![])
+}
+
+pub(crate) fn text(s: impl Into<String>) -> Doc {
+    Doc::Text(s.into())
+}
+
+pub(crate) fn concat(docs: Vec<Doc>) -> Doc {
+    Doc::Concat(docs)
+}
+
+pub(crate) fn nest(doc: Doc) -> Doc {
+    Doc::Nest(Box::new(doc))
+}
+
+pub(crate) fn group(doc: Doc) -> Doc {
+    let breaks = doc.breaks();
+    Doc::Group(Box::new(doc), breaks)
+}
+
+pub(crate) fn if_break(broken: Doc, flat: Doc) -> Doc {
+    Doc::IfBreak(Box::new(broken), Box::new(flat))
+}
+
+impl Doc {
+    /// Returns true if the document forces its enclosing groups to break
+    fn breaks(&self) -> bool {
+        match self {
+            Doc::HardLine | Doc::LineStart => true,
+            Doc::Comment { text, is_line, .. } => *is_line || text.contains('\n'),
+            Doc::Group(_, breaks) => *breaks,
+            Doc::Nest(doc) => doc.breaks(),
+            Doc::IfBreak(broken, flat) => broken.breaks() || flat.breaks(),
+            Doc::Concat(docs) => docs.iter().any(|doc| doc.breaks()),
+            Doc::Text(_) | Doc::Line | Doc::SoftLine => false,
+        }
+    }
+
+    /// Returns true if the document produces no output
+    pub(crate) fn is_empty(&self) -> bool {
+        match self {
+            Doc::Text(s) => s.is_empty(),
+            Doc::Nest(doc) | Doc::Group(doc, _) => doc.is_empty(),
+            Doc::Concat(docs) => docs.iter().all(|doc| doc.is_empty()),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+struct Renderer {
+    out: String,
+    indent_width: usize,
+    max_width: usize,
+    /// The column of the next character written
+    column: usize,
+    /// Set at the start of a line, before its indentation is written
+    at_line_start: bool,
+    /// Set after a line comment, which must be followed by a newline
+    needs_newline: bool,
+}
+
+/// Lays out `doc`, breaking lines longer than `max_width` where possible
+pub(crate) fn render(doc: &Doc, indent_width: usize, max_width: usize) -> String {
+    let mut renderer = Renderer {
+        out: String::new(),
+        indent_width,
+        max_width,
+        column: 0,
+        at_line_start: true,
+        needs_newline: false,
+    };
+    let mut stack = vec![(0, Mode::Break, doc)];
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Text(s) => renderer.text(indent, s),
+            Doc::Comment { text, column, .. } => renderer.comment(indent, text, *column, doc),
+            Doc::Line if mode == Mode::Flat => renderer.text(indent, " "),
+            Doc::SoftLine if mode == Mode::Flat => (),
+            Doc::Line | Doc::SoftLine | Doc::HardLine => renderer.new_line(indent),
+            Doc::LineStart => {
+                if !renderer.at_line_start {
+                    renderer.new_line(indent)
+                }
+            }
+            Doc::IfBreak(broken, flat) => {
+                let doc = if mode == Mode::Break { broken } else { flat };
+                stack.push((indent, mode, doc))
+            }
+            Doc::Nest(doc) => stack.push((indent + 1, mode, doc)),
+            Doc::Group(doc, breaks) => {
+                let mode = if mode == Mode::Flat
+                    || (!breaks && renderer.fits(indent, doc, &stack))
+                {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                stack.push((indent, mode, doc))
+            }
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+        }
+    }
+    renderer.out
+}
+
+impl Renderer {
+    fn text(&mut self, indent: usize, s: &str) {
+        if self.needs_newline {
+            self.new_line(indent)
+        }
+        if s.is_empty() {
+            return;
+        }
+        if self.at_line_start {
+            if s == " " {
+                return;
+            }
+            let width = indent * self.indent_width;
+            self.out.extend(std::iter::repeat(' ').take(width));
+            self.column = width;
+            self.at_line_start = false;
+        }
+        self.out.push_str(s);
+        self.column += s.len();
+    }
+
+    fn new_line(&mut self, indent: usize) {
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+        self.out.push('\n');
+        self.column = indent * self.indent_width;
+        self.at_line_start = true;
+        self.needs_newline = false;
+    }
+
+    fn comment(&mut self, indent: usize, comment: &str, old_column: usize, doc: &Doc) {
+        let mut lines = comment.lines();
+        self.text(indent, lines.next().unwrap_or("").trim_end());
+        let new_column = self.column - comment.lines().next().unwrap_or("").trim_end().len();
+        for line in lines {
+            self.out.push('\n');
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            let leading = line.len() - line.trim_start().len();
+            let strip = std::cmp::min(leading, old_column);
+            self.out.extend(std::iter::repeat(' ').take(new_column));
+            self.out.push_str(&line[strip..]);
+            self.column = new_column + line.len() - strip;
+        }
+        if let Doc::Comment { is_line: true, .. } = doc {
+            self.needs_newline = true
+        }
+    }
+
+    /// Returns true if `doc` fits on the rest of the current line when laid out flat, along with
+    /// the documents following it up to the next possible line break
+    fn fits(&self, indent: usize, doc: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+        let mut remaining = self.max_width as isize - self.column as isize;
+        if self.at_line_start {
+            remaining -= (indent * self.indent_width) as isize - self.column as isize;
+        }
+        let mut stack = vec![(Mode::Flat, doc)];
+        let mut rest = rest.iter().rev();
+        loop {
+            let (mode, doc) = match stack.pop() {
+                Some(next) => next,
+                None => match rest.next() {
+                    Some((_, mode, doc)) => (*mode, *doc),
+                    None => return true,
+                },
+            };
+            match doc {
+                Doc::Text(s) => remaining -= s.len() as isize,
+                Doc::Comment { text, .. } => {
+                    remaining -= text.lines().next().unwrap_or("").len() as isize;
+                    if doc.breaks() {
+                        return mode == Mode::Break && remaining >= 0;
+                    }
+                }
+                Doc::Line | Doc::SoftLine if mode == Mode::Break => return true,
+                Doc::Line => remaining -= 1,
+                Doc::SoftLine => (),
+                Doc::HardLine | Doc::LineStart => return mode == Mode::Break,
+                Doc::IfBreak(broken, flat) => {
+                    stack.push((mode, if mode == Mode::Break { broken } else { flat }))
+                }
+                Doc::Nest(doc) => stack.push((mode, doc)),
+                Doc::Group(doc, breaks) => {
+                    let mode = if *breaks { Mode::Break } else { mode };
+                    stack.push((mode, doc))
+                }
+                Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (mode, doc))),
+            }
+            if remaining < 0 {
+                return false;
+            }
+        }
+    }
+}
@@ -0,0 +1,1849 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prints the parsed AST of a Move source file as a document to be laid out. Comments are not
+//! part of the AST. Each comment is placed before the first node that follows it in the source,
+//! unless it ends the line of the node before it.
+
+use crate::{
+    config::FormatConfig,
+    doc::{concat, group, if_break, nest, nil, render, text, Doc},
+};
+use move_compiler::{
+    parser::{ast::*, comments::FileCommentMap},
+    shared::{Identifier, Name},
+};
+use move_ir_types::{location::*, sp};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Formats the definitions parsed from `source`, along with the comments of the file
+pub(crate) fn format(
+    source: &str,
+    defs: &[Definition],
+    comments: &FileCommentMap,
+    config: &FormatConfig,
+) -> String {
+    let mut formatter = Formatter {
+        source,
+        config,
+        comments: comments
+            .iter()
+            .map(|((start, end), text)| Comment {
+                start: *start as usize,
+                end: *end as usize,
+                text: text.clone(),
+            })
+            .collect(),
+        comment_spans: comments
+            .keys()
+            .map(|(start, end)| (*start as usize, *end as usize))
+            .collect(),
+        last_end: 0,
+    };
+    let doc = formatter.file(defs);
+    let formatted = render(&doc, config.indent_width, config.max_width);
+    let formatted = formatted.trim();
+    if formatted.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", formatted)
+    }
+}
+
+struct Comment {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+struct Formatter<'a> {
+    source: &'a str,
+    config: &'a FormatConfig,
+    /// The comments not yet placed, in source order
+    comments: VecDeque<Comment>,
+    /// The spans of all comments, by start position
+    comment_spans: BTreeMap<usize, usize>,
+    /// The end of the last node or comment placed, used for keeping blank lines
+    last_end: usize,
+}
+
+/// The precedence of expressions, which decides where parentheses are needed. Binary operators
+/// are between `OPEN` and `UNARY`, ordered as in the parser
+const PREC_OPEN: u32 = 0;
+const PREC_UNARY: u32 = 13;
+const PREC_DOT: u32 = 14;
+const PREC_TERM: u32 = 15;
+
+/// The weak keywords starting a spec block member, which a spec variable declared without
+/// `local` cannot be named as
+const SPEC_MEMBER_KEYWORDS: &[&str] = &[
+    "assert",
+    "assume",
+    "decreases",
+    "aborts_if",
+    "aborts_with",
+    "succeeds_if",
+    "modifies",
+    "emits",
+    "ensures",
+    "requires",
+    "axiom",
+    "include",
+    "apply",
+    "pragma",
+    "global",
+    "local",
+    "update",
+];
+
+/// A member of a module or script
+enum Member<'a> {
+    Use(&'a UseDecl),
+    Friend(&'a FriendDecl),
+    Constant(&'a Constant),
+    Function(&'a Function),
+    Struct(&'a StructDefinition),
+    Spec(&'a SpecBlock),
+}
+
+impl<'a> From<&'a ModuleMember> for Member<'a> {
+    fn from(member: &'a ModuleMember) -> Self {
+        match member {
+            ModuleMember::Use(u) => Member::Use(u),
+            ModuleMember::Friend(f) => Member::Friend(f),
+            ModuleMember::Constant(c) => Member::Constant(c),
+            ModuleMember::Function(f) => Member::Function(f),
+            ModuleMember::Struct(s) => Member::Struct(s),
+            ModuleMember::Spec(s) => Member::Spec(s),
+        }
+    }
+}
+
+/// An element of a block
+enum Statement<'a> {
+    Use(&'a UseDecl),
+    Item(&'a SequenceItem),
+    Result(&'a Exp),
+}
+
+/// An element of the braces of a pack
+enum PackItem<'a> {
+    Field(&'a Field, &'a Exp),
+    Base(&'a Exp),
+}
+
+fn start(loc: Loc) -> usize {
+    loc.start() as usize
+}
+
+fn end(loc: Loc) -> usize {
+    loc.end() as usize
+}
+
+fn hard_lines(n: usize) -> Doc {
+    concat(vec![Doc::HardLine; n])
+}
+
+fn attributes_start(attributes: &[Attributes], loc: Loc) -> usize {
+    attributes
+        .first()
+        .map_or(start(loc), |attrs| start(attrs.loc).min(start(loc)))
+}
+
+fn binop_precedence(op: BinOp_) -> u32 {
+    use BinOp_ as B;
+    match op {
+        B::Implies | B::Iff => 2,
+        B::Or => 3,
+        B::And => 4,
+        B::Eq | B::Neq | B::Lt | B::Gt | B::Le | B::Ge => 5,
+        B::Range => 6,
+        B::BitOr => 7,
+        B::Xor => 8,
+        B::BitAnd => 9,
+        B::Shl | B::Shr => 10,
+        B::Add | B::Sub => 11,
+        B::Mul | B::Div | B::Mod => 12,
+    }
+}
+
+fn precedence(e: &Exp) -> u32 {
+    use Exp_ as E;
+    match &e.value {
+        E::Lambda(..)
+        | E::Quant(..)
+        | E::Assign(..)
+        | E::IfElse(..)
+        | E::While(..)
+        | E::Loop(..)
+        | E::Return(..)
+        | E::Abort(..)
+        | E::Match(..) => PREC_OPEN,
+        E::BinopExp(_, op, _) => binop_precedence(op.value),
+        E::UnaryExp(..) | E::Borrow(..) | E::Dereference(..) | E::Move(..) | E::Copy(..) => {
+            PREC_UNARY
+        }
+        E::Dot(..) | E::DotCall(..) | E::Index(..) => PREC_DOT,
+        _ => PREC_TERM,
+    }
+}
+
+/// Returns the subexpression `e` starts with, if any
+fn leftmost(e: &Exp) -> Option<&Exp> {
+    use Exp_ as E;
+    match &e.value {
+        E::BinopExp(e, _, _)
+        | E::Assign(e, _)
+        | E::Dot(e, _)
+        | E::DotCall(e, _, _, _)
+        | E::Index(e, _) => Some(e),
+        _ => None,
+    }
+}
+
+/// Returns the condition and invariant of a `while` condition, if the parser attached a loop
+/// invariant to it
+fn loop_invariant(cond: &Exp) -> Option<(&Exp, &SpecBlock)> {
+    match &cond.value {
+        Exp_::Block((uses, items, _, last)) if uses.is_empty() && items.len() == 1 => {
+            match (&items[0].value, &**last) {
+                (SequenceItem_::Seq(item), Some(last)) if last.loc == cond.loc => {
+                    match &item.value {
+                        Exp_::Spec(spec) => Some((last, spec)),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+impl<'a> Formatter<'a> {
+    //**********************************************************************************************
+    // Comments and layout
+    //**********************************************************************************************
+
+    /// Returns the position of the next `c` from `pos`, skipping comments
+    fn find(&self, mut pos: usize, c: u8) -> usize {
+        let bytes = self.source.as_bytes();
+        while pos < bytes.len() {
+            if let Some(end) = self.comment_spans.get(&pos) {
+                pos = *end;
+                continue;
+            }
+            if bytes[pos] == c {
+                return pos;
+            }
+            pos += 1;
+        }
+        pos
+    }
+
+    fn starts_line(&self, pos: usize) -> bool {
+        let before = self.source[..pos].trim_end_matches([' ', '\t']);
+        before.is_empty() || before.ends_with('\n')
+    }
+
+    fn ends_line(&self, pos: usize) -> bool {
+        let after = self.source[pos..].trim_start_matches([' ', '\t', '\r']);
+        after.is_empty() || after.starts_with('\n')
+    }
+
+    /// Returns the number of blank lines to keep between `from` and `to`
+    fn blank_lines(&self, from: usize, to: usize) -> usize {
+        if from >= to {
+            return 0;
+        }
+        let newlines = self.source[from..to].matches('\n').count();
+        newlines
+            .saturating_sub(1)
+            .min(self.config.max_blank_lines)
+    }
+
+    fn comment(&self, comment: &Comment) -> Doc {
+        let line_start = self.source[..comment.start]
+            .rfind('\n')
+            .map_or(0, |pos| pos + 1);
+        Doc::Comment {
+            text: comment.text.clone(),
+            column: comment.start - line_start,
+            is_line: comment.text.starts_with("//"),
+        }
+    }
+
+    fn has_comment_before(&self, pos: usize) -> bool {
+        self.comments.front().map_or(false, |c| c.start < pos)
+    }
+
+    /// Places the comments before `pos`, which is the start of a node
+    fn leading(&mut self, pos: usize) -> Doc {
+        let mut docs = vec![];
+        let mut prev_end = None;
+        while self.has_comment_before(pos) {
+            let comment = self.comments.pop_front().unwrap();
+            if let Some(prev_end) = prev_end {
+                docs.push(hard_lines(self.blank_lines(prev_end, comment.start)));
+            }
+            if self.starts_line(comment.start) {
+                docs.push(Doc::LineStart);
+            }
+            docs.push(self.comment(&comment));
+            if comment.text.starts_with("//") || self.ends_line(comment.end) {
+                docs.push(Doc::HardLine);
+            } else {
+                docs.push(text(" "));
+            }
+            prev_end = Some(comment.end);
+            self.last_end = comment.end;
+        }
+        concat(docs)
+    }
+
+    /// Places the comments inside the node ending at `end`, and those following it on the same
+    /// line, up to `limit`
+    fn trailing(&mut self, end: usize, limit: usize) -> Doc {
+        let mut docs = vec![];
+        self.last_end = self.last_end.max(end);
+        while let Some(comment) = self.comments.front() {
+            if comment.start >= limit
+                || (comment.start >= end && self.source[end..comment.start].contains('\n'))
+            {
+                break;
+            }
+            let comment = self.comments.pop_front().unwrap();
+            docs.push(text(" "));
+            docs.push(self.comment(&comment));
+            self.last_end = self.last_end.max(comment.end);
+        }
+        concat(docs)
+    }
+
+    /// Places the comments before `pos`, which is a closing delimiter, on their own lines
+    fn closing(&mut self, pos: usize, after_items: bool) -> Doc {
+        let first = match self.comments.front() {
+            Some(comment) if comment.start < pos => comment.start,
+            _ => return nil(),
+        };
+        let blank_lines = if after_items {
+            self.blank_lines(self.last_end, first)
+        } else {
+            0
+        };
+        concat(vec![
+            Doc::LineStart,
+            hard_lines(blank_lines),
+            self.leading(pos),
+        ])
+    }
+
+    /// Lays out `items` on separate lines, keeping blank lines and comments between them.
+    /// Comments after `limit` are left for the enclosing node
+    fn lines<T>(
+        &mut self,
+        items: &[T],
+        span: impl Fn(&Self, &T) -> (usize, usize),
+        mut item: impl FnMut(&mut Self, &T) -> Doc,
+        limit: usize,
+    ) -> Doc {
+        let mut docs = vec![];
+        for (idx, it) in items.iter().enumerate() {
+            let (start, end) = span(self, it);
+            if idx > 0 {
+                let next = self
+                    .comments
+                    .front()
+                    .map_or(start, |comment| comment.start.min(start));
+                docs.push(Doc::HardLine);
+                docs.push(hard_lines(self.blank_lines(self.last_end, next)));
+            }
+            let leading = self.leading(start);
+            if !leading.is_empty() {
+                docs.push(leading);
+                docs.push(hard_lines(self.blank_lines(self.last_end, start)));
+            }
+            docs.push(item(self, it));
+            docs.push(self.trailing(end, limit));
+        }
+        concat(docs)
+    }
+
+    /// Lays out the contents of braces, opened before `open_end` and closed at `close`, on
+    /// indented lines
+    fn braced(
+        &mut self,
+        open_end: usize,
+        close: usize,
+        contents: impl FnOnce(&mut Self) -> Doc,
+    ) -> Doc {
+        let after_open = self.trailing(open_end, close);
+        let contents = contents(self);
+        let closing = self.closing(close, !contents.is_empty());
+        if after_open.is_empty() && contents.is_empty() && closing.is_empty() {
+            return text("{}");
+        }
+        concat(vec![
+            text("{"),
+            after_open,
+            nest(concat(vec![Doc::HardLine, contents, closing])),
+            Doc::LineStart,
+            text("}"),
+        ])
+    }
+
+    /// Lays out `items` separated by commas between `open` and `close`, on one line if they fit,
+    /// or one per line otherwise. `close_pos` is the position of the closing delimiter, if known
+    #[allow(clippy::too_many_arguments)]
+    fn list<T>(
+        &mut self,
+        open: &str,
+        items: &[T],
+        span: impl Fn(&Self, &T) -> (usize, usize),
+        mut item: impl FnMut(&mut Self, &T) -> Doc,
+        close: &str,
+        close_pos: Option<usize>,
+        padded: bool,
+        trailing_comma: bool,
+    ) -> Doc {
+        if items.is_empty() && !close_pos.map_or(false, |pos| self.has_comment_before(pos)) {
+            return text(format!("{}{}", open, close));
+        }
+        let mut docs = vec![];
+        for (idx, it) in items.iter().enumerate() {
+            let (start, end) = span(self, it);
+            let is_last = idx + 1 == items.len();
+            if idx > 0 {
+                docs.push(Doc::Line);
+            }
+            docs.push(self.leading(start));
+            docs.push(item(self, it));
+            if !is_last {
+                docs.push(text(","));
+            } else if trailing_comma {
+                docs.push(if_break(text(","), nil()));
+            }
+            let limit = if is_last {
+                close_pos.unwrap_or(end)
+            } else {
+                span(self, &items[idx + 1]).0
+            };
+            docs.push(self.trailing(end, limit));
+        }
+        let closing = close_pos.map_or_else(nil, |pos| self.leading(pos));
+        let edge = || if padded { Doc::Line } else { Doc::SoftLine };
+        let close_edge = if closing.is_empty() {
+            edge()
+        } else {
+            Doc::LineStart
+        };
+        group(concat(vec![
+            text(open),
+            nest(concat(vec![edge(), concat(docs), closing])),
+            close_edge,
+            text(close),
+        ]))
+    }
+
+    //**********************************************************************************************
+    // Definitions
+    //**********************************************************************************************
+
+    fn file(&mut self, defs: &[Definition]) -> Doc {
+        let defs_doc = self.lines(defs, Self::definition_span, Self::definition, usize::MAX);
+        let rest = self.closing(self.source.len(), !defs.is_empty());
+        concat(vec![defs_doc, rest])
+    }
+
+    fn definition_span(&self, def: &Definition) -> (usize, usize) {
+        match def {
+            Definition::Module(m) => self.module_span(m),
+            Definition::Address(a) => {
+                let last_end = a.modules.last().map_or(end(a.loc), |m| end(m.loc));
+                (
+                    attributes_start(&a.attributes, a.loc),
+                    self.find(last_end, b'}') + 1,
+                )
+            }
+            Definition::Script(s) => (attributes_start(&s.attributes, s.loc), end(s.loc)),
+        }
+    }
+
+    fn definition(&mut self, def: &Definition) -> Doc {
+        match def {
+            Definition::Module(m) => self.module(m),
+            Definition::Address(a) => {
+                let attributes = self.attributes(&a.attributes);
+                let addr = self.leading_name_access(&a.addr);
+                let open_end = self.find(end(a.addr.loc), b'{') + 1;
+                let close = self.definition_span(def).1 - 1;
+                let body = self.braced(open_end, close, |f| {
+                    f.lines(&a.modules, Self::module_span, Self::module, close)
+                });
+                concat(vec![attributes, text("address "), addr, text(" "), body])
+            }
+            Definition::Script(s) => {
+                let attributes = self.attributes(&s.attributes);
+                let members: Vec<_> = s
+                    .uses
+                    .iter()
+                    .map(Member::Use)
+                    .chain(s.constants.iter().map(Member::Constant))
+                    .chain(std::iter::once(Member::Function(&s.function)))
+                    .chain(s.specs.iter().map(Member::Spec))
+                    .collect();
+                let open_end = self.find(start(s.loc), b'{') + 1;
+                let close = end(s.loc) - 1;
+                let body = self.braced(open_end, close, |f| {
+                    f.lines(&members, Self::member_span, Self::member, close)
+                });
+                concat(vec![attributes, text("script "), body])
+            }
+        }
+    }
+
+    fn module_span(&self, m: &ModuleDefinition) -> (usize, usize) {
+        (attributes_start(&m.attributes, m.loc), end(m.loc))
+    }
+
+    fn module(&mut self, m: &ModuleDefinition) -> Doc {
+        let attributes = self.attributes(&m.attributes);
+        let keyword = if m.is_spec_module { "spec " } else { "module " };
+        let address = match &m.address {
+            Some(addr) => concat(vec![self.leading_name_access(addr), text("::")]),
+            None => nil(),
+        };
+        let members: Vec<Member> = m.members.iter().map(Member::from).collect();
+        let open_end = self.find(end(m.name.loc()), b'{') + 1;
+        let close = end(m.loc) - 1;
+        let body = self.braced(open_end, close, |f| {
+            f.lines(&members, Self::member_span, Self::member, close)
+        });
+        concat(vec![
+            attributes,
+            text(keyword),
+            address,
+            text(m.name.to_string()),
+            text(" "),
+            body,
+        ])
+    }
+
+    fn member_span(&self, member: &Member) -> (usize, usize) {
+        match member {
+            Member::Use(u) => self.use_span(u),
+            Member::Friend(f) => (attributes_start(&f.attributes, f.loc), end(f.loc)),
+            Member::Constant(c) => (attributes_start(&c.attributes, c.loc), end(c.loc)),
+            Member::Function(f) => (attributes_start(&f.attributes, f.loc), end(f.loc)),
+            Member::Struct(s) => (attributes_start(&s.attributes, s.loc), end(s.loc)),
+            Member::Spec(s) => (attributes_start(&s.value.attributes, s.loc), end(s.loc)),
+        }
+    }
+
+    fn member(&mut self, member: &Member) -> Doc {
+        match member {
+            Member::Use(u) => self.use_decl(u),
+            Member::Friend(f) => {
+                let attributes = self.attributes(&f.attributes);
+                let friend = self.name_access_chain(&f.friend);
+                concat(vec![attributes, text("friend "), friend, text(";")])
+            }
+            Member::Constant(c) => self.constant(c),
+            Member::Function(f) => self.function(f),
+            Member::Struct(s) => self.struct_def(s),
+            Member::Spec(s) => self.spec_block(s),
+        }
+    }
+
+    fn attributes(&mut self, attributes: &[Attributes]) -> Doc {
+        let mut docs = vec![];
+        for attrs in attributes {
+            docs.push(self.leading(start(attrs.loc)));
+            docs.push(self.list(
+                "#[",
+                &attrs.value,
+                |_, attr| (start(attr.loc), end(attr.loc)),
+                Self::attribute,
+                "]",
+                Some(end(attrs.loc) - 1),
+                false,
+                false,
+            ));
+            docs.push(self.trailing(end(attrs.loc), usize::MAX));
+            docs.push(Doc::HardLine);
+        }
+        concat(docs)
+    }
+
+    fn attribute(&mut self, attr: &Attribute) -> Doc {
+        match &attr.value {
+            Attribute_::Name(n) => text(n.to_string()),
+            Attribute_::Assigned(n, value) => {
+                let value = match &value.value {
+                    AttributeValue_::Value(v) => self.value(v),
+                    AttributeValue_::ModuleAccess(chain) => self.name_access_chain(chain),
+                };
+                concat(vec![text(format!("{} = ", n)), value])
+            }
+            Attribute_::Parameterized(n, attrs) => {
+                let args = self.list(
+                    "(",
+                    &attrs.value,
+                    |_, attr| (start(attr.loc), end(attr.loc)),
+                    Self::attribute,
+                    ")",
+                    Some(end(attrs.loc) - 1),
+                    false,
+                    true,
+                );
+                concat(vec![text(n.to_string()), args])
+            }
+        }
+    }
+
+    fn use_span(&self, u: &UseDecl) -> (usize, usize) {
+        let ident = match &u.use_ {
+            Use::Module(ident, _) | Use::Members(ident, _) => ident,
+        };
+        (
+            attributes_start(&u.attributes, ident.loc),
+            self.find(end(ident.loc), b';') + 1,
+        )
+    }
+
+    fn use_decl(&mut self, u: &UseDecl) -> Doc {
+        let attributes = self.attributes(&u.attributes);
+        let use_ = match &u.use_ {
+            Use::Module(ident, alias) => {
+                let alias = match alias {
+                    Some(alias) => text(format!(" as {}", alias)),
+                    None => nil(),
+                };
+                concat(vec![self.module_ident(ident), alias])
+            }
+            Use::Members(ident, members) => {
+                let ident = self.module_ident(ident);
+                let members = if members.len() == 1 {
+                    Self::use_member(self, &members[0])
+                } else {
+                    let close = self.find(end(ident_loc(&u.use_)), b';');
+                    let close = self.source[..close].rfind('}');
+                    self.list(
+                        "{",
+                        members,
+                        |_, (member, alias)| {
+                            (start(member.loc), end(alias.as_ref().unwrap_or(member).loc))
+                        },
+                        Self::use_member,
+                        "}",
+                        close,
+                        false,
+                        true,
+                    )
+                };
+                concat(vec![ident, text("::"), members])
+            }
+        };
+        concat(vec![attributes, text("use "), use_, text(";")])
+    }
+
+    fn use_member(&mut self, (member, alias): &(Name, Option<Name>)) -> Doc {
+        match alias {
+            Some(alias) => text(format!("{} as {}", member, alias)),
+            None => text(member.to_string()),
+        }
+    }
+
+    fn constant(&mut self, c: &Constant) -> Doc {
+        let attributes = self.attributes(&c.attributes);
+        let ty = self.type_(&c.signature);
+        let value = self.exp(&c.value);
+        concat(vec![
+            attributes,
+            text(format!("const {}: ", c.name)),
+            ty,
+            text(" = "),
+            value,
+            text(";"),
+        ])
+    }
+
+    fn struct_def(&mut self, s: &StructDefinition) -> Doc {
+        let attributes = self.attributes(&s.attributes);
+        let keyword = match &s.fields {
+            StructFields::Native(_) => "native struct ",
+            StructFields::Defined(_) => "struct ",
+            StructFields::Variants(_) => "enum ",
+        };
+        let type_parameters = self.struct_type_parameters(&s.type_parameters);
+        let abilities = if s.abilities.is_empty() {
+            nil()
+        } else {
+            let abilities: Vec<_> = s.abilities.iter().map(|a| a.to_string()).collect();
+            text(format!(" has {}", abilities.join(", ")))
+        };
+        let header = concat(vec![
+            attributes,
+            text(keyword),
+            text(s.name.to_string()),
+            type_parameters,
+        ]);
+        let close = end(s.loc) - 1;
+        match &s.fields {
+            StructFields::Native(_) => concat(vec![header, abilities, text(";")]),
+            StructFields::Defined(fields)
+                if fields.first().map_or(false, |(f, _)| f.is_positional()) =>
+            {
+                let fields = self.list(
+                    "(",
+                    fields,
+                    |_, (_, ty)| (start(ty.loc), end(ty.loc)),
+                    |f, (_, ty)| f.type_(ty),
+                    ")",
+                    None,
+                    false,
+                    true,
+                );
+                concat(vec![header, fields, abilities, text(";")])
+            }
+            StructFields::Defined(fields) => {
+                let open_end = self.find(end(s.name.loc()), b'{') + 1;
+                let body = self.braced(open_end, close, |f| {
+                    f.lines(
+                        fields,
+                        |_, (field, ty)| (start(field.loc()), end(ty.loc)),
+                        |f, field| concat(vec![f.field_decl(field), text(",")]),
+                        close,
+                    )
+                });
+                concat(vec![header, abilities, text(" "), body])
+            }
+            StructFields::Variants(variants) => {
+                let open_end = self.find(end(s.name.loc()), b'{') + 1;
+                let body = self.braced(open_end, close, |f| {
+                    f.lines(variants, Self::variant_span, Self::variant, close)
+                });
+                concat(vec![header, abilities, text(" "), body])
+            }
+        }
+    }
+
+    fn field_decl(&mut self, (field, ty): &(Field, Type)) -> Doc {
+        concat(vec![text(format!("{}: ", field)), self.type_(ty)])
+    }
+
+    fn variant_span(&self, (variant, fields): &(VariantName, Vec<(Field, Type)>)) -> (usize, usize) {
+        let end = match fields.last() {
+            Some((_, ty)) => self.find(end(ty.loc), b'}') + 1,
+            None => end(variant.loc()),
+        };
+        (start(variant.loc()), end)
+    }
+
+    fn variant(&mut self, v: &(VariantName, Vec<(Field, Type)>)) -> Doc {
+        let (variant, fields) = v;
+        let fields = if fields.is_empty() {
+            nil()
+        } else {
+            let close = self.variant_span(v).1 - 1;
+            let fields = self.list(
+                "{",
+                fields,
+                |_, (field, ty)| (start(field.loc()), end(ty.loc)),
+                Self::field_decl,
+                "}",
+                Some(close),
+                true,
+                true,
+            );
+            concat(vec![text(" "), fields])
+        };
+        concat(vec![text(variant.to_string()), fields, text(",")])
+    }
+
+    fn struct_type_parameters(&mut self, tparams: &[StructTypeParameter]) -> Doc {
+        if tparams.is_empty() {
+            return nil();
+        }
+        self.list(
+            "<",
+            tparams,
+            |_, tparam| (start(tparam.name.loc), end(tparam.name.loc)),
+            |f, tparam| {
+                let phantom = if tparam.is_phantom { "phantom " } else { "" };
+                concat(vec![
+                    text(format!("{}{}", phantom, tparam.name)),
+                    f.constraints(&tparam.constraints),
+                ])
+            },
+            ">",
+            None,
+            false,
+            false,
+        )
+    }
+
+    fn type_parameters(&mut self, tparams: &[(Name, Vec<Ability>)]) -> Doc {
+        if tparams.is_empty() {
+            return nil();
+        }
+        self.list(
+            "<",
+            tparams,
+            |_, (name, _)| (start(name.loc), end(name.loc)),
+            |f, (name, constraints)| {
+                concat(vec![text(name.to_string()), f.constraints(constraints)])
+            },
+            ">",
+            None,
+            false,
+            false,
+        )
+    }
+
+    fn constraints(&mut self, constraints: &[Ability]) -> Doc {
+        if constraints.is_empty() {
+            return nil();
+        }
+        let constraints: Vec<_> = constraints.iter().map(|a| a.to_string()).collect();
+        text(format!(": {}", constraints.join(" + ")))
+    }
+
+    fn function(&mut self, fun: &Function) -> Doc {
+        let attributes = self.attributes(&fun.attributes);
+        let mut modifiers = String::new();
+        match &fun.visibility {
+            Visibility::Internal => (),
+            vis => modifiers.push_str(&format!("{} ", vis)),
+        }
+        // `public(script)` implies `entry`, with the location of the visibility
+        let implied_entry = match &fun.visibility {
+            Visibility::Script(loc) => Some(*loc),
+            _ => None,
+        };
+        if fun.entry.is_some() && fun.entry != implied_entry {
+            modifiers.push_str("entry ");
+        }
+        if let FunctionBody_::Native = &fun.body.value {
+            modifiers.push_str("native ");
+        }
+        if fun.inline.is_some() {
+            modifiers.push_str("inline ");
+        }
+        let signature = self.signature(fun.name.loc(), &fun.signature);
+        let acquires = if fun.acquires.is_empty() {
+            nil()
+        } else {
+            let mut docs = vec![text(" acquires ")];
+            for (idx, chain) in fun.acquires.iter().enumerate() {
+                if idx > 0 {
+                    docs.push(text(", "));
+                }
+                docs.push(self.name_access_chain(chain));
+            }
+            concat(docs)
+        };
+        let body = self.function_body(&fun.body);
+        concat(vec![
+            attributes,
+            text(format!("{}fun {}", modifiers, fun.name)),
+            signature,
+            acquires,
+            body,
+        ])
+    }
+
+    fn function_body(&mut self, body: &FunctionBody) -> Doc {
+        match &body.value {
+            FunctionBody_::Native => text(";"),
+            FunctionBody_::Defined(seq) => {
+                concat(vec![text(" "), self.block(seq, body.loc, true)])
+            }
+        }
+    }
+
+    /// Lays out a function signature. An omitted unit return type is located at the name
+    fn signature(&mut self, name_loc: Loc, signature: &FunctionSignature) -> Doc {
+        let type_parameters = self.type_parameters(&signature.type_parameters);
+        let parameters = self.list(
+            "(",
+            &signature.parameters,
+            |_, (var, ty)| (start(var.loc()), end(ty.loc)),
+            |f, (var, ty)| concat(vec![text(format!("{}: ", var)), f.type_(ty)]),
+            ")",
+            None,
+            false,
+            true,
+        );
+        let return_type = match &signature.return_type {
+            sp!(loc, Type_::Unit) if *loc == name_loc => nil(),
+            ty => concat(vec![text(": "), self.type_(ty)]),
+        };
+        concat(vec![type_parameters, parameters, return_type])
+    }
+
+    //**********************************************************************************************
+    // Specification blocks
+    //**********************************************************************************************
+
+    fn spec_block(&mut self, spec: &SpecBlock) -> Doc {
+        let SpecBlock_ {
+            attributes,
+            target,
+            uses,
+            members,
+        } = &spec.value;
+        let attributes = self.attributes(attributes);
+        let target_doc = match &target.value {
+            // a member declared at the top of a module, such as an invariant
+            SpecBlockTarget_::Module if target.loc.start() == target.loc.end() => {
+                let prefix = match members.first().map(|m| &m.value) {
+                    Some(SpecBlockMember_::Function { .. }) => text("spec "),
+                    _ => nil(),
+                };
+                let member = self.lines(
+                    members,
+                    |_, member| (start(member.loc), end(member.loc)),
+                    Self::spec_member,
+                    usize::MAX,
+                );
+                return concat(vec![attributes, prefix, member]);
+            }
+            SpecBlockTarget_::Code => nil(),
+            SpecBlockTarget_::Module => text("module "),
+            SpecBlockTarget_::Member(name, signature) => {
+                let signature = match signature {
+                    Some(signature) => self.signature(name.loc, signature),
+                    None => nil(),
+                };
+                concat(vec![text(name.to_string()), signature, text(" ")])
+            }
+            SpecBlockTarget_::Schema(name, tparams) => concat(vec![
+                text(format!("schema {}", name)),
+                self.type_parameters(tparams),
+                text(" "),
+            ]),
+        };
+        let open_end = self.find(end(target.loc), b'{') + 1;
+        let close = end(spec.loc) - 1;
+        let body = self.braced(open_end, close, |f| {
+            let uses_doc = f.lines(uses, Self::use_span, Self::use_decl, close);
+            let separator = match members.first() {
+                Some(member) if !uses.is_empty() => {
+                    let next = f
+                        .comments
+                        .front()
+                        .map_or(start(member.loc), |c| c.start.min(start(member.loc)));
+                    concat(vec![Doc::HardLine, hard_lines(f.blank_lines(f.last_end, next))])
+                }
+                _ => nil(),
+            };
+            let members = f.lines(
+                members,
+                |_, member| (start(member.loc), end(member.loc)),
+                Self::spec_member,
+                close,
+            );
+            concat(vec![uses_doc, separator, members])
+        });
+        concat(vec![attributes, text("spec "), target_doc, body])
+    }
+
+    fn spec_member(&mut self, member: &SpecBlockMember) -> Doc {
+        use SpecBlockMember_ as M;
+        match &member.value {
+            M::Condition {
+                kind,
+                properties,
+                exp,
+                additional_exps,
+            } => {
+                use SpecConditionKind_ as K;
+                let keyword = match &kind.value {
+                    K::Assert => text("assert"),
+                    K::Assume => text("assume"),
+                    K::Decreases => text("decreases"),
+                    K::AbortsIf => text("aborts_if"),
+                    K::AbortsWith => text("aborts_with"),
+                    K::SucceedsIf => text("succeeds_if"),
+                    K::Modifies => text("modifies"),
+                    K::Emits => text("emits"),
+                    K::Ensures => text("ensures"),
+                    K::Requires => text("requires"),
+                    K::Invariant(tparams) => {
+                        concat(vec![text("invariant"), self.type_parameters(tparams)])
+                    }
+                    K::InvariantUpdate(tparams) => concat(vec![
+                        text("invariant"),
+                        self.type_parameters(tparams),
+                        text(" update"),
+                    ]),
+                    K::Axiom(tparams) => concat(vec![text("axiom"), self.type_parameters(tparams)]),
+                };
+                let properties = self.condition_properties(properties);
+                let exps = match &kind.value {
+                    // the expression is a placeholder
+                    K::AbortsWith | K::Modifies => self.joined(additional_exps, ", "),
+                    K::AbortsIf if !additional_exps.is_empty() => concat(vec![
+                        self.exp(exp),
+                        text(" with "),
+                        self.exp(&additional_exps[0]),
+                    ]),
+                    K::Emits => {
+                        let mut docs = vec![self.exp(exp), text(" to "), self.exp(&additional_exps[0])];
+                        if let Some(cond) = additional_exps.get(1) {
+                            docs.push(text(" if "));
+                            docs.push(self.exp(cond));
+                        }
+                        concat(docs)
+                    }
+                    _ => self.exp(exp),
+                };
+                group(concat(vec![keyword, properties, text(" "), exps, text(";")]))
+            }
+            M::Function {
+                uninterpreted,
+                name,
+                signature,
+                body,
+            } => {
+                let native = match &body.value {
+                    FunctionBody_::Native if !uninterpreted => "native ",
+                    _ => "",
+                };
+                let type_parameters = self.type_parameters(&signature.type_parameters);
+                let parameters = self.list(
+                    "(",
+                    &signature.parameters,
+                    |_, (var, ty)| (start(var.loc()), end(ty.loc)),
+                    |f, (var, ty)| concat(vec![text(format!("{}: ", var)), f.type_(ty)]),
+                    ")",
+                    None,
+                    false,
+                    true,
+                );
+                let return_type = self.type_(&signature.return_type);
+                let body = self.function_body(body);
+                concat(vec![
+                    text(format!("{}fun {}", native, name)),
+                    type_parameters,
+                    parameters,
+                    text(": "),
+                    return_type,
+                    body,
+                ])
+            }
+            M::Variable {
+                is_global,
+                name,
+                type_parameters,
+                type_,
+                init,
+            } => {
+                let keyword = if *is_global {
+                    "global "
+                } else if SPEC_MEMBER_KEYWORDS.contains(&name.value.as_str()) {
+                    "local "
+                } else {
+                    ""
+                };
+                let type_parameters = self.type_parameters(type_parameters);
+                let ty = self.type_(type_);
+                let init = match init {
+                    Some(init) => concat(vec![text(" = "), self.exp(init)]),
+                    None => nil(),
+                };
+                concat(vec![
+                    text(format!("{}{}", keyword, name)),
+                    type_parameters,
+                    text(": "),
+                    ty,
+                    init,
+                    text(";"),
+                ])
+            }
+            M::Let {
+                name,
+                post_state,
+                def,
+            } => {
+                let post = if *post_state { "post " } else { "" };
+                concat(vec![
+                    text(format!("let {}{} = ", post, name)),
+                    self.exp(def),
+                    text(";"),
+                ])
+            }
+            M::Update { lhs, rhs } => concat(vec![
+                text("update "),
+                self.operand(lhs, PREC_UNARY),
+                text(" = "),
+                self.exp(rhs),
+                text(";"),
+            ]),
+            M::Include { properties, exp } => concat(vec![
+                text("include"),
+                self.condition_properties(properties),
+                text(" "),
+                self.exp(exp),
+                text(";"),
+            ]),
+            M::Apply {
+                exp,
+                patterns,
+                exclusion_patterns,
+            } => {
+                let mut docs = vec![
+                    text("apply "),
+                    self.exp(exp),
+                    text(" to "),
+                    self.apply_patterns(patterns),
+                ];
+                if !exclusion_patterns.is_empty() {
+                    docs.push(text(" except "));
+                    docs.push(self.apply_patterns(exclusion_patterns));
+                }
+                docs.push(text(";"));
+                group(concat(docs))
+            }
+            M::Pragma { properties } => {
+                let mut docs = vec![text("pragma ")];
+                for (idx, property) in properties.iter().enumerate() {
+                    if idx > 0 {
+                        docs.push(text(", "));
+                    }
+                    docs.push(self.pragma_property(property));
+                }
+                docs.push(text(";"));
+                concat(docs)
+            }
+        }
+    }
+
+    fn condition_properties(&mut self, properties: &[PragmaProperty]) -> Doc {
+        if properties.is_empty() {
+            return nil();
+        }
+        let properties = self.list(
+            "[",
+            properties,
+            |_, property| (start(property.loc), end(property.loc)),
+            Self::pragma_property,
+            "]",
+            None,
+            false,
+            false,
+        );
+        concat(vec![text(" "), properties])
+    }
+
+    fn pragma_property(&mut self, property: &PragmaProperty) -> Doc {
+        let value = match &property.value.value {
+            None => nil(),
+            Some(PragmaValue::Literal(v)) => concat(vec![text(" = "), self.value(v)]),
+            Some(PragmaValue::Ident(chain)) => {
+                concat(vec![text(" = "), self.name_access_chain(chain)])
+            }
+        };
+        concat(vec![text(property.value.name.to_string()), value])
+    }
+
+    fn apply_patterns(&mut self, patterns: &[SpecApplyPattern]) -> Doc {
+        let mut docs = vec![];
+        for (idx, pattern) in patterns.iter().enumerate() {
+            if idx > 0 {
+                docs.push(text(","));
+                docs.push(Doc::Line);
+            }
+            let visibility = match &pattern.value.visibility {
+                Some(Visibility::Internal) => "internal ",
+                Some(_) => "public ",
+                None => "",
+            };
+            let fragments: String = pattern
+                .value
+                .name_pattern
+                .iter()
+                .map(|fragment| match &fragment.value {
+                    SpecApplyFragment_::Wildcard => "*".to_string(),
+                    SpecApplyFragment_::NamePart(n) => n.to_string(),
+                })
+                .collect();
+            docs.push(text(format!("{}{}", visibility, fragments)));
+            docs.push(self.type_parameters(&pattern.value.type_parameters));
+        }
+        nest(concat(docs))
+    }
+
+    //**********************************************************************************************
+    // Names, values and types
+    //**********************************************************************************************
+
+    /// Numerical addresses are printed as written
+    fn leading_name_access(&mut self, name: &LeadingNameAccess) -> Doc {
+        match &name.value {
+            LeadingNameAccess_::AnonymousAddress(_) => {
+                text(&self.source[start(name.loc)..end(name.loc)])
+            }
+            LeadingNameAccess_::Name(n) => text(n.to_string()),
+        }
+    }
+
+    fn module_ident(&mut self, ident: &ModuleIdent) -> Doc {
+        concat(vec![
+            self.leading_name_access(&ident.value.address),
+            text(format!("::{}", ident.value.module)),
+        ])
+    }
+
+    fn name_access_chain(&mut self, chain: &NameAccessChain) -> Doc {
+        match &chain.value {
+            NameAccessChain_::One(n) => text(n.to_string()),
+            NameAccessChain_::Two(ln, n) => concat(vec![
+                self.leading_name_access(ln),
+                text(format!("::{}", n)),
+            ]),
+            NameAccessChain_::Three(sp!(_, (ln, n2)), n3) => concat(vec![
+                self.leading_name_access(ln),
+                text(format!("::{}::{}", n2, n3)),
+            ]),
+        }
+    }
+
+    fn value(&mut self, v: &Value) -> Doc {
+        match &v.value {
+            Value_::Address(addr) => concat(vec![text("@"), self.leading_name_access(addr)]),
+            Value_::Num(num) => text(num.to_string()),
+            Value_::Bool(b) => text(b.to_string()),
+            Value_::HexString(s) => text(format!("x\"{}\"", s)),
+            Value_::ByteString(s) => text(format!("b\"{}\"", s)),
+        }
+    }
+
+    fn type_(&mut self, ty: &Type) -> Doc {
+        let leading = self.leading(start(ty.loc));
+        let ty_doc = match &ty.value {
+            Type_::Apply(chain, tys) => {
+                let chain = self.name_access_chain(chain);
+                if tys.is_empty() {
+                    chain
+                } else {
+                    concat(vec![chain, self.types("<", tys, ">")])
+                }
+            }
+            Type_::Ref(is_mut, inner) => {
+                let amp = match (is_mut, &inner.value) {
+                    (true, _) => "&mut ",
+                    // `&&` is a single token
+                    (false, Type_::Ref(..)) => "& ",
+                    (false, _) => "&",
+                };
+                concat(vec![text(amp), self.type_(inner)])
+            }
+            Type_::Fun(args, result) => {
+                let args = self.types("|", args, "|");
+                match &result.value {
+                    // an omitted unit result has an empty location
+                    Type_::Unit if result.loc.start() == result.loc.end() => args,
+                    _ => concat(vec![args, text(" "), self.type_(result)]),
+                }
+            }
+            Type_::Unit => text("()"),
+            Type_::Multiple(tys) => self.types("(", tys, ")"),
+        };
+        concat(vec![leading, ty_doc])
+    }
+
+    fn types(&mut self, open: &str, tys: &[Type], close: &str) -> Doc {
+        self.list(
+            open,
+            tys,
+            |_, ty| (start(ty.loc), end(ty.loc)),
+            Self::type_,
+            close,
+            None,
+            false,
+            false,
+        )
+    }
+
+    fn type_args(&mut self, tys: &Option<Vec<Type>>) -> Doc {
+        match tys {
+            None => nil(),
+            Some(tys) => self.types("<", tys, ">"),
+        }
+    }
+
+    //**********************************************************************************************
+    // Binds
+    //**********************************************************************************************
+
+    fn bind_list(&mut self, binds: &BindList) -> Doc {
+        if binds.value.len() == 1 {
+            return self.bind(&binds.value[0], false);
+        }
+        self.list(
+            "(",
+            &binds.value,
+            |_, b| (start(b.loc), end(b.loc)),
+            |f, b| f.bind(b, false),
+            ")",
+            Some(end(binds.loc) - 1),
+            false,
+            true,
+        )
+    }
+
+    /// Lays out a bind. The fields of a match pattern may be omitted if there are none
+    fn bind(&mut self, b: &Bind, is_pattern: bool) -> Doc {
+        let leading = self.leading(start(b.loc));
+        let bind = match &b.value {
+            Bind_::Var(v) => text(v.to_string()),
+            Bind_::Unpack(chain, tys, fields) => {
+                let name = concat(vec![self.name_access_chain(chain), self.type_args(tys)]);
+                let close = Some(end(b.loc) - 1);
+                if fields.first().map_or(false, |(f, _)| f.is_positional()) {
+                    let fields = self.list(
+                        "(",
+                        fields,
+                        |_, (_, b)| (start(b.loc), end(b.loc)),
+                        |f, (_, b)| f.bind(b, false),
+                        ")",
+                        close,
+                        false,
+                        true,
+                    );
+                    concat(vec![name, fields])
+                } else if fields.is_empty()
+                    && is_pattern
+                    && (tys.is_some() || !matches!(chain.value, NameAccessChain_::One(_)))
+                {
+                    name
+                } else {
+                    let fields = self.list(
+                        "{",
+                        fields,
+                        |_, (field, b)| (start(field.loc()), end(b.loc)),
+                        |f, (field, b)| {
+                            // a field bound to a variable of the same name has the same location
+                            if b.loc == field.loc() {
+                                text(field.to_string())
+                            } else {
+                                concat(vec![text(format!("{}: ", field)), f.bind(b, false)])
+                            }
+                        },
+                        "}",
+                        close,
+                        true,
+                        true,
+                    );
+                    concat(vec![name, text(" "), fields])
+                }
+            }
+        };
+        concat(vec![leading, bind])
+    }
+
+    //**********************************************************************************************
+    // Sequences
+    //**********************************************************************************************
+
+    /// Lays out a block. Unless `always_break` is set, a block of a single expression stays on one
+    /// line if it fits
+    fn block(&mut self, seq: &Sequence, loc: Loc, always_break: bool) -> Doc {
+        let (uses, items, _, result) = seq;
+        let open_end = start(loc) + 1;
+        let close = end(loc) - 1;
+        if let (false, true, true, Some(result)) =
+            (always_break, uses.is_empty(), items.is_empty(), &**result)
+        {
+            let result_doc = self.exp(result);
+            let trailing = self.trailing(end(result.loc), close);
+            let closing = self.closing(close, true);
+            let close_edge = if closing.is_empty() {
+                Doc::Line
+            } else {
+                Doc::LineStart
+            };
+            return group(concat(vec![
+                text("{"),
+                nest(concat(vec![Doc::Line, result_doc, trailing, closing])),
+                close_edge,
+                text("}"),
+            ]));
+        }
+        let statements: Vec<_> = uses
+            .iter()
+            .map(Statement::Use)
+            .chain(items.iter().map(Statement::Item))
+            .chain(result.iter().map(Statement::Result))
+            .collect();
+        self.braced(open_end, close, |f| {
+            f.lines(&statements, Self::statement_span, Self::statement, close)
+        })
+    }
+
+    fn statement_span(&self, statement: &Statement) -> (usize, usize) {
+        match statement {
+            Statement::Use(u) => self.use_span(u),
+            Statement::Item(item) => (start(item.loc), end(item.loc)),
+            Statement::Result(e) => (start(e.loc), end(e.loc)),
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Doc {
+        match statement {
+            Statement::Use(u) => self.use_decl(u),
+            Statement::Result(e) => self.exp(e),
+            Statement::Item(item) => match &item.value {
+                SequenceItem_::Seq(e) => concat(vec![self.exp(e), text(";")]),
+                SequenceItem_::Declare(binds, ty) => concat(vec![
+                    text("let "),
+                    self.bind_list(binds),
+                    self.type_annotation(ty),
+                    text(";"),
+                ]),
+                SequenceItem_::Bind(binds, ty, e) => concat(vec![
+                    text("let "),
+                    self.bind_list(binds),
+                    self.type_annotation(ty),
+                    text(" = "),
+                    self.exp(e),
+                    text(";"),
+                ]),
+            },
+        }
+    }
+
+    fn type_annotation(&mut self, ty: &Option<Type>) -> Doc {
+        match ty {
+            Some(ty) => concat(vec![text(": "), self.type_(ty)]),
+            None => nil(),
+        }
+    }
+
+    //**********************************************************************************************
+    // Expressions
+    //**********************************************************************************************
+
+    /// Returns true if `e` is in parentheses in the source. The parser drops them, but they are
+    /// part of the location of the expression
+    fn is_parenthesized(&self, e: &Exp) -> bool {
+        use Exp_ as E;
+        if self.source.as_bytes().get(start(e.loc)) != Some(&b'(') {
+            return false;
+        }
+        match &e.value {
+            E::Unit | E::ExpList(_) | E::Cast(..) | E::Annotate(..) => false,
+            _ => leftmost(e).map_or(true, |first| first.loc.start() > e.loc.start()),
+        }
+    }
+
+    /// Returns the precedence of `e`, as laid out
+    fn precedence(&self, e: &Exp) -> u32 {
+        if self.is_parenthesized(e) {
+            PREC_TERM
+        } else {
+            precedence(e)
+        }
+    }
+
+    /// Returns true if the leftmost term of `e` is a block. The parser stops after such a block
+    /// when it is the body of a control expression
+    fn starts_with_block(&self, e: &Exp) -> bool {
+        if self.is_parenthesized(e) {
+            return false;
+        }
+        match &e.value {
+            Exp_::Block(_) => true,
+            _ => leftmost(e).map_or(false, |first| self.starts_with_block(first)),
+        }
+    }
+
+    /// Returns true if `e` ends with an `if` without an `else`, which would take an `else`
+    /// following `e`
+    fn ends_with_open_if(&self, e: &Exp) -> bool {
+        use Exp_ as E;
+        if self.is_parenthesized(e) {
+            return false;
+        }
+        match &e.value {
+            E::IfElse(_, _, None) => true,
+            E::IfElse(_, _, Some(e))
+            | E::While(_, e)
+            | E::Loop(e)
+            | E::Return(Some(e))
+            | E::Abort(e)
+            | E::Lambda(_, e)
+            | E::Assign(_, e)
+            | E::BinopExp(_, _, e)
+            | E::Quant(_, _, _, _, e) => self.ends_with_open_if(e),
+            _ => false,
+        }
+    }
+
+    fn exp(&mut self, e: &Exp) -> Doc {
+        let leading = self.leading(start(e.loc));
+        let exp = if self.is_parenthesized(e) {
+            concat(vec![text("("), self.exp_(e), text(")")])
+        } else {
+            self.exp_(e)
+        };
+        concat(vec![leading, exp])
+    }
+
+    /// Lays out `e`, in parentheses if it binds less tightly than `min_precedence`
+    fn operand(&mut self, e: &Exp, min_precedence: u32) -> Doc {
+        if self.precedence(e) < min_precedence {
+            concat(vec![text("("), self.exp(e), text(")")])
+        } else {
+            self.exp(e)
+        }
+    }
+
+    /// Lays out the body of a control expression, in parentheses if the parser would stop after a
+    /// block at its start
+    fn control_body(&mut self, e: &Exp) -> Doc {
+        if !matches!(e.value, Exp_::Block(_)) && self.starts_with_block(e) {
+            concat(vec![text("("), self.exp(e), text(")")])
+        } else {
+            self.exp(e)
+        }
+    }
+
+    /// Lays out a branch or loop body, after its condition
+    fn branch(&mut self, e: &Exp) -> Doc {
+        if let Exp_::Block(_) = &e.value {
+            concat(vec![text(" "), self.exp(e)])
+        } else {
+            nest(concat(vec![Doc::Line, self.control_body(e)]))
+        }
+    }
+
+    fn joined(&mut self, es: &[Exp], separator: &str) -> Doc {
+        let mut docs = vec![];
+        for (idx, e) in es.iter().enumerate() {
+            if idx > 0 {
+                docs.push(text(separator));
+            }
+            docs.push(self.exp(e));
+        }
+        concat(docs)
+    }
+
+    fn args(&mut self, open: &str, args: &Spanned<Vec<Exp>>, close: &str) -> Doc {
+        self.list(
+            open,
+            &args.value,
+            |_, e| (start(e.loc), end(e.loc)),
+            Self::exp,
+            close,
+            Some(end(args.loc) - 1),
+            false,
+            true,
+        )
+    }
+
+    fn pack(
+        &mut self,
+        loc: Loc,
+        chain: &NameAccessChain,
+        tys: &Option<Vec<Type>>,
+        items: &[PackItem],
+    ) -> Doc {
+        let name = self.name_access_chain(chain);
+        let tys = self.type_args(tys);
+        let fields = self.list(
+            "{",
+            items,
+            |_, item| match item {
+                PackItem::Field(field, e) => (start(field.loc()), end(e.loc)),
+                PackItem::Base(e) => (start(e.loc) - 2, end(e.loc)),
+            },
+            |f, item| match item {
+                // a field initialized by a variable of the same name has the same location
+                PackItem::Field(field, e) if e.loc == field.loc() => text(field.to_string()),
+                PackItem::Field(field, e) => {
+                    concat(vec![text(format!("{}: ", field)), f.exp(e)])
+                }
+                PackItem::Base(e) => concat(vec![text(".."), f.exp(e)]),
+            },
+            "}",
+            Some(end(loc) - 1),
+            true,
+            true,
+        );
+        concat(vec![name, tys, text(" "), fields])
+    }
+
+    fn exp_(&mut self, e: &Exp) -> Doc {
+        use Exp_ as E;
+        match &e.value {
+            E::Value(v) => self.value(v),
+            E::Move(v) => text(format!("move {}", v)),
+            E::Copy(v) => text(format!("copy {}", v)),
+            E::Name(chain, tys) => concat(vec![self.name_access_chain(chain), self.type_args(tys)]),
+            E::Call(chain, is_macro, tys, args) => concat(vec![
+                self.name_access_chain(chain),
+                text(if *is_macro { "!" } else { "" }),
+                self.type_args(tys),
+                self.args("(", args, ")"),
+            ]),
+            E::Pack(chain, tys, fields) => {
+                let items: Vec<_> = fields.iter().map(|(f, e)| PackItem::Field(f, e)).collect();
+                self.pack(e.loc, chain, tys, &items)
+            }
+            E::PackUpdate(chain, tys, fields, base) => {
+                let items: Vec<_> = fields
+                    .iter()
+                    .map(|(f, e)| PackItem::Field(f, e))
+                    .chain(std::iter::once(PackItem::Base(base)))
+                    .collect();
+                self.pack(e.loc, chain, tys, &items)
+            }
+            E::Vector(_, tys, args) => concat(vec![
+                text("vector"),
+                self.type_args(tys),
+                self.args("[", args, "]"),
+            ]),
+            E::IfElse(cond, then, else_) => {
+                let cond = self.exp(cond);
+                let then_doc = match else_ {
+                    Some(_) if self.ends_with_open_if(then) => nest(concat(vec![
+                        Doc::Line,
+                        text("("),
+                        self.exp(then),
+                        text(")"),
+                    ])),
+                    _ => self.branch(then),
+                };
+                let mut docs = vec![text("if ("), cond, text(")"), then_doc];
+                if let Some(else_) = else_ {
+                    docs.push(match &then.value {
+                        E::Block(_) => text(" "),
+                        _ => Doc::Line,
+                    });
+                    docs.push(text("else"));
+                    docs.push(match &else_.value {
+                        E::IfElse(..) => concat(vec![text(" "), self.exp(else_)]),
+                        _ => self.branch(else_),
+                    });
+                }
+                group(concat(docs))
+            }
+            E::While(cond, body) => {
+                let (cond, invariant) = match loop_invariant(cond) {
+                    Some((cond, invariant)) => (cond, Some(invariant)),
+                    None => (cond.as_ref(), None),
+                };
+                let cond = self.exp(cond);
+                let body = self.branch(body);
+                let invariant = match invariant {
+                    Some(invariant) => concat(vec![text(" "), self.spec_block(invariant)]),
+                    None => nil(),
+                };
+                group(concat(vec![text("while ("), cond, text(")"), body, invariant]))
+            }
+            E::Loop(body) => group(concat(vec![text("loop"), self.branch(body)])),
+            E::Match(subject, arms) => {
+                let subject_doc = self.exp(subject);
+                let open_end = self.find(end(subject.loc), b'{') + 1;
+                let close = end(e.loc) - 1;
+                let arms = self.braced(open_end, close, |f| {
+                    f.lines(
+                        arms,
+                        |_, arm| (start(arm.loc), end(arm.loc)),
+                        |f, arm| {
+                            let (pattern, e) = &arm.value;
+                            concat(vec![
+                                f.bind(pattern, true),
+                                text(" => "),
+                                f.exp(e),
+                                text(","),
+                            ])
+                        },
+                        close,
+                    )
+                });
+                concat(vec![text("match ("), subject_doc, text(") "), arms])
+            }
+            E::Block(seq) => self.block(seq, e.loc, false),
+            E::Lambda(binds, body) => {
+                let binds = self.list(
+                    "|",
+                    &binds.value,
+                    |_, b| (start(b.loc), end(b.loc)),
+                    |f, b| f.bind(b, false),
+                    "|",
+                    None,
+                    false,
+                    false,
+                );
+                concat(vec![binds, text(" "), self.exp(body)])
+            }
+            E::Quant(kind, binds, triggers, cond, body) => {
+                self.quant(kind, binds, triggers, cond, body)
+            }
+            E::ExpList(es) => self.list(
+                "(",
+                es,
+                |_, e| (start(e.loc), end(e.loc)),
+                Self::exp,
+                ")",
+                Some(end(e.loc) - 1),
+                false,
+                true,
+            ),
+            E::Unit => text("()"),
+            E::Assign(lhs, rhs) => concat(vec![
+                self.operand(lhs, PREC_UNARY),
+                text(" = "),
+                self.exp(rhs),
+            ]),
+            E::Return(None) => text("return"),
+            E::Return(Some(e)) => concat(vec![text("return "), self.control_body(e)]),
+            E::Abort(e) => concat(vec![text("abort "), self.control_body(e)]),
+            E::Break => text("break"),
+            E::Continue => text("continue"),
+            E::Dereference(e) => concat(vec![text("*"), self.operand(e, PREC_UNARY)]),
+            E::UnaryExp(op, e) => concat(vec![
+                text(op.value.to_string()),
+                self.operand(e, PREC_UNARY),
+            ]),
+            E::Borrow(is_mut, inner) => {
+                let amp = match (is_mut, &inner.value) {
+                    (true, _) => "&mut ",
+                    // `&&` is a single token
+                    (false, E::Borrow(..)) => "& ",
+                    (false, _) => "&",
+                };
+                concat(vec![text(amp), self.operand(inner, PREC_UNARY)])
+            }
+            E::BinopExp(..) => self.binop(e),
+            E::Dot(e, n) => concat(vec![self.operand(e, PREC_DOT), text(format!(".{}", n))]),
+            E::DotCall(e, n, tys, args) => concat(vec![
+                self.operand(e, PREC_DOT),
+                text(format!(".{}", n)),
+                self.type_args(tys),
+                self.args("(", args, ")"),
+            ]),
+            E::Index(e, index) => concat(vec![
+                self.operand(e, PREC_DOT),
+                text("["),
+                self.exp(index),
+                text("]"),
+            ]),
+            E::Cast(e, ty) => concat(vec![
+                text("("),
+                self.exp(e),
+                text(" as "),
+                self.type_(ty),
+                text(")"),
+            ]),
+            E::Annotate(e, ty) => concat(vec![
+                text("("),
+                self.exp(e),
+                text(": "),
+                self.type_(ty),
+                text(")"),
+            ]),
+            E::Spec(spec) => self.spec_block(spec),
+            E::UnresolvedError => nil(),
+        }
+    }
+
+    /// Lays out a chain of binary operations of the same precedence, breaking after the
+    /// operators if the chain does not fit on one line
+    fn binop(&mut self, e: &Exp) -> Doc {
+        let precedence = precedence(e);
+        let mut operations = vec![];
+        let mut first = e;
+        while let Exp_::BinopExp(lhs, op, rhs) = &first.value {
+            if binop_precedence(op.value) != precedence
+                || (!std::ptr::eq(first, e) && self.is_parenthesized(first))
+            {
+                break;
+            }
+            operations.push((op, rhs));
+            first = lhs;
+        }
+        operations.reverse();
+        let first = self.operand(first, precedence);
+        let mut rest = vec![];
+        for (op, rhs) in operations {
+            if op.value == BinOp_::Range {
+                rest.push(text(".."));
+            } else {
+                rest.push(text(format!(" {}", op.value)));
+                rest.push(Doc::Line);
+            }
+            rest.push(self.operand(rhs, precedence + 1));
+        }
+        group(concat(vec![first, nest(concat(rest))]))
+    }
+
+    fn quant(
+        &mut self,
+        kind: &QuantKind,
+        binds: &BindWithRangeList,
+        triggers: &[Vec<Exp>],
+        cond: &Option<Box<Exp>>,
+        body: &Exp,
+    ) -> Doc {
+        let keyword = match kind.value {
+            QuantKind_::Forall => "forall ",
+            QuantKind_::Exists => "exists ",
+            QuantKind_::Choose => "choose ",
+            QuantKind_::ChooseMin => "choose min ",
+        };
+        let mut docs = vec![text(keyword)];
+        for (idx, bind) in binds.value.iter().enumerate() {
+            if idx > 0 {
+                docs.push(text(", "));
+            }
+            let (b, range) = &bind.value;
+            docs.push(self.bind(b, false));
+            docs.push(match &range.value {
+                // the domain of a type, as in `x: T`
+                Exp_::Call(sp!(_, NameAccessChain_::One(n)), false, Some(tys), args)
+                    if n.value.as_str() == "$spec_domain" && args.value.is_empty() =>
+                {
+                    concat(vec![text(": "), self.type_(&tys[0])])
+                }
+                _ => concat(vec![text(" in "), self.exp(range)]),
+            });
+        }
+        for trigger in triggers {
+            let trigger = self.list(
+                "{",
+                trigger,
+                |_, e| (start(e.loc), end(e.loc)),
+                Self::exp,
+                "}",
+                None,
+                false,
+                false,
+            );
+            docs.push(text(" "));
+            docs.push(trigger);
+        }
+        if let QuantKind_::Choose | QuantKind_::ChooseMin = kind.value {
+            docs.push(text(" where"));
+            docs.push(nest(concat(vec![Doc::Line, self.exp(body)])));
+            return group(concat(docs));
+        }
+        if let Some(cond) = cond {
+            docs.push(text(" where "));
+            docs.push(self.exp(cond));
+        }
+        docs.push(text(":"));
+        docs.push(nest(concat(vec![Doc::Line, self.exp(body)])));
+        group(concat(docs))
+    }
+}
+
+fn ident_loc(use_: &Use) -> Loc {
+    match use_ {
+        Use::Module(ident, _) | Use::Members(ident, _) => ident.loc,
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A formatter for Move source files.
+//!
+//! The formatter prints the AST produced by the Move parser, laying out each construct within the
+//! configured line width, and places the comments collected by the parser next to the nodes they
+//! precede or follow. Blank lines between items are kept, up to a configured maximum. Files that
+//! do not parse are not formatted.
+//!
+//! As a safeguard, the formatted output is parsed again, and formatting fails if the AST or the
+//! comments of the file changed.
+
+mod config;
+mod doc;
+mod formatter;
+
+pub use config::{FormatConfig, CONFIG_FILE_NAME, MANIFEST_SECTION};
+
+use anyhow::bail;
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    diagnostics::{codes::Severity, report_diagnostics_to_buffer, Diagnostics, FilesSourceText},
+    parser::{
+        ast::Definition,
+        comments::{verify_string, FileCommentMap},
+        syntax::parse_file_string_with_comments,
+    },
+    shared::{CompilationEnv, Flags},
+};
+use move_symbol_pool::Symbol;
+
+/// Formats the contents of the Move source file `file_name`. Fails with the rendered
+/// diagnostics if the file does not parse
+pub fn format_file(file_name: &str, source: &str, config: &FormatConfig) -> anyhow::Result<String> {
+    let file_hash = FileHash::new(source);
+    let report = |diags: Diagnostics| {
+        let mut files = FilesSourceText::new();
+        files.insert(file_hash, (Symbol::from(file_name), source.to_owned()));
+        let rendered = report_diagnostics_to_buffer(&files, diags);
+        anyhow::anyhow!(String::from_utf8_lossy(&rendered).into_owned())
+    };
+    let (defs, comments) = parse(file_hash, source).map_err(report)?;
+    let formatted = formatter::format(source, &defs, &comments, config);
+
+    // check that formatting changed nothing but the layout
+    let unchanged = match parse(FileHash::new(&formatted), &formatted) {
+        Ok((formatted_defs, formatted_comments)) => {
+            strip_locs(&format!("{:?}", defs)) == strip_locs(&format!("{:?}", formatted_defs))
+                && comment_words(&comments) == comment_words(&formatted_comments)
+        }
+        Err(_) => false,
+    };
+    if !unchanged {
+        bail!(
+            "ICE formatting '{}' changed its contents. The file was left unformatted",
+            file_name
+        )
+    }
+    Ok(formatted)
+}
+
+fn parse(
+    file_hash: FileHash,
+    source: &str,
+) -> Result<(Vec<Definition>, FileCommentMap), Diagnostics> {
+    verify_string(file_hash, source)?;
+    let mut env = CompilationEnv::new(Flags::empty());
+    let (defs, comments) = parse_file_string_with_comments(&mut env, file_hash, source)?;
+    env.check_diags_at_or_above_severity(Severity::NonblockingError)?;
+    Ok((defs, comments))
+}
+
+/// Removes the locations from the debug output of an AST, which are expected to change
+fn strip_locs(ast: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = ast;
+    while let Some(start) = rest.find("Loc {") {
+        stripped.push_str(&rest[..start]);
+        stripped.push_str("Loc");
+        rest = &rest[start..];
+        rest = &rest[rest.find('}').map_or(rest.len(), |end| end + 1)..];
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// Returns the words of the comments, whose whitespace changes when they are re-indented
+fn comment_words(comments: &FileCommentMap) -> Vec<&str> {
+    comments
+        .values()
+        .flat_map(|comment| comment.split_whitespace())
+        .collect()
+}
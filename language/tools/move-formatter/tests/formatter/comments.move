@@ -0,0 +1,21 @@
+/// A module with comments
+module 0x42::comments {
+  // a regular comment
+      /// Documentation of `S`
+  struct S has drop { f: u64 }   // trailing comment
+
+    /**
+     * A block documentation comment,
+     * shifted along with its first line
+     */
+fun f(s: S): u64 {
+    /* inline */ let x = s.f; // the field
+        // a comment before the closing brace
+    x
+  }
+
+  /* a comment
+     /* with a nested comment */
+     at the end */
+}
+// end of file
@@ -0,0 +1,39 @@
+module 0x42::indentation {
+use std::vector;
+
+
+
+    struct Pair<T: copy + drop> has copy, drop {
+    first: T,
+            second: T,
+    }
+
+    public fun f(
+    a: u64,
+            b: u64,
+    ): u64 {
+
+        let sum = a +
+        b;
+        let v = vector[
+        1,
+        2,
+        ];
+        if (sum > 10)
+        return sum;
+        if (a == 0) {
+            b
+        }
+        else {
+        a
+        };
+        while (a < b) {
+                    a = a + 1;
+        };
+        let p = Pair { first: a,
+        second: b };
+        p.first
+        + vector::length(&v)
+
+    }
+}
@@ -0,0 +1,36 @@
+module 0x42::line_width {
+    use 0x42::very_long_module_name::{first_function_with_a_long_name, second_function_with_a_long_name, Type};
+
+    struct Point(u64, u64) has copy, drop;
+
+    enum Shape has drop { Circle { center: Point, radius: u64 }, Square { corner: Point, side: u64 }, Empty }
+
+    public fun area(shape: &Shape, scale: u64, offset: u64, rounding: bool, default_value: u64): u64 {
+        match (shape) {
+            Shape::Circle { center: _, radius } => 3 * *radius * *radius * scale + offset + (if (rounding) 1 else 0),
+            Shape::Square { corner: Point(x, y), side } => *side * *side * scale + offset + *x - *x + *y - *y,
+            Shape::Empty => default_value,
+        }
+    }
+
+    public fun check(a: u64, b: u64) {
+        assert!(a > 0 && b > 0 && a + b < 1000000 && a * b < 1000000000 && a != b, 1 /* invalid arguments */);
+        if (a > b) if (b > 0) first_function_with_a_long_name(a) else second_function_with_a_long_name(b);
+        let i = 0;
+        while (i < a) {
+            i = i + 1;
+        } spec { invariant i <= a; };
+    }
+
+    inline fun apply(v: vector<u64>, f: |u64| u64): vector<u64> {
+        let result = vector[];
+        for_each_element(v, |e| vector::push_back(&mut result, f(e)));
+        result
+    }
+
+    spec fun sum_of_squares(x: num, y: num): num { x * x + y * y }
+
+    spec check {
+        aborts_if a == 0 || b == 0 || a + b >= 1000000 || a * b >= 1000000000 || a == b with 1;
+    }
+}
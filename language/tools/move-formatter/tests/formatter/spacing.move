@@ -0,0 +1,39 @@
+address 0x42 {
+module spacing {
+    use std::vector ;
+    use std::option::{Self,Option};
+
+    const ERR:u64=1;
+
+    struct R<phantom T> has key { v:vector<vector<u8>> }
+
+    #[test(account=@0x1)]
+    fun f<T:drop>(account:&signer,x:&mut u64,o:Option<T>):bool acquires R{
+        let y=*x+1;
+        *x=y<<2;
+        let z:vector<u64> =vector::empty<u64>();
+        vector::push_back(&mut z,y);
+        let b=x_is(&*x)&&!option::is_none(&o)||(y>1&&y<=10);
+        assert!(b,ERR);
+        let _ = account;
+        let w = (y as u128);
+        let _ = (w * (w - 1)) / 2 % 3;
+        let _ = b"hello" == x"0011";
+        let bits = y&7|y^3;
+        bits>=1
+    }
+
+    fun x_is(x:&u64):bool{*x>0}
+
+    spec f {
+        pragma verify=false;
+        ensures result==true;
+        aborts_if *x > 10 with ERR;
+        ensures forall i in 0..10: all(z, | e |e>i);
+    }
+
+    spec module {
+        apply Inv<T> to *<T>, set_* except f;
+    }
+}
+}
@@ -0,0 +1,5 @@
+module 0x42::syntax_error {
+    fun f() {
+        let x = ;
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::testing::{
+    add_update_baseline_fix, format_diff, read_env_update_baseline, EXP_EXT,
+};
+use move_formatter::{format_file, FormatConfig};
+use std::{fs, path::Path};
+
+fn run_test(path: &Path) -> datatest_stable::Result<()> {
+    let config = FormatConfig::default();
+    let file_name = path.to_string_lossy().to_string();
+    let source = fs::read_to_string(path)?;
+    let output = match format_file(&file_name, &source, &config) {
+        Ok(formatted) => {
+            // formatting must be idempotent
+            let reformatted = format_file(&file_name, &formatted, &config)?;
+            if formatted != reformatted {
+                return Err(format!(
+                    "Formatting is not idempotent for {}:\n{}",
+                    file_name,
+                    format_diff(&formatted, &reformatted)
+                )
+                .into());
+            }
+            formatted
+        }
+        Err(err) => format!("{:#}", err),
+    };
+
+    let exp_path = path.with_extension(EXP_EXT);
+    if read_env_update_baseline() {
+        fs::write(&exp_path, &output)?;
+        return Ok(());
+    }
+    let expected = if exp_path.is_file() {
+        fs::read_to_string(&exp_path)?
+    } else {
+        String::new()
+    };
+    if expected != output {
+        let msg = format!(
+            "Expected output differs from actual output for {}:\n{}",
+            file_name,
+            format_diff(expected, output)
+        );
+        return Err(add_update_baseline_fix(msg).into());
+    }
+    Ok(())
+}
+
+datatest_stable::harness!(run_test, "tests/formatter", r".*\.move$");
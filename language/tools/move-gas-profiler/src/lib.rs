@@ -0,0 +1,15 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Gas profiling for the Move VM.
+//!
+//! A `GasProfiler` wraps the gas meter of an execution and attributes every unit of gas charged
+//! to the Move call stack, the kind of instruction and, for global storage operations, the
+//! resource type. The resulting `GasProfile` can be written in the folded stacks format read by
+//! flamegraph tools, or summarized as JSON or HTML.
+
+pub mod profile;
+pub mod profiler;
+
+pub use profile::{GasProfile, Location, ProfileSummary};
+pub use profiler::{GasProfiler, ProfilingGasMeter, ProfilingTracer};
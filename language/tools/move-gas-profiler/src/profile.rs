@@ -0,0 +1,281 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::{CodeOffset, FunctionDefinitionIndex};
+use move_core_types::language_storage::ModuleId;
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+/// The frame name used in stacks for gas charged outside of any traced function.
+const UNKNOWN_FRAME: &str = "[unknown]";
+
+/// An instruction of a Move function or script.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    /// The module defining the function, or `None` for a script.
+    pub module_id: Option<ModuleId>,
+    pub function: FunctionDefinitionIndex,
+    pub pc: CodeOffset,
+}
+
+/// How often something was charged for, and how much gas it consumed in total.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub count: u64,
+    pub gas: u64,
+}
+
+impl Usage {
+    fn add(&mut self, gas: u64) {
+        self.count += 1;
+        self.gas += gas;
+    }
+}
+
+/// The gas consumed by an execution, broken down by call stack, instruction, storage operation
+/// and code location. Collected by a `GasProfiler`.
+#[derive(Clone, Debug, Default)]
+pub struct GasProfile {
+    total_gas: u64,
+    stacks: BTreeMap<Vec<String>, u64>,
+    instructions: BTreeMap<String, Usage>,
+    storage_operations: BTreeMap<String, Usage>,
+    locations: BTreeMap<Location, u64>,
+}
+
+impl GasProfile {
+    pub(crate) fn record(
+        &mut self,
+        stack: Vec<String>,
+        location: Option<Location>,
+        instruction: String,
+        storage_operation: Option<String>,
+        gas: u64,
+    ) {
+        self.total_gas += gas;
+        *self.stacks.entry(stack).or_default() += gas;
+        self.instructions.entry(instruction).or_default().add(gas);
+        if let Some(operation) = storage_operation {
+            self.storage_operations
+                .entry(operation)
+                .or_default()
+                .add(gas);
+        }
+        if let Some(location) = location {
+            *self.locations.entry(location).or_default() += gas;
+        }
+    }
+
+    pub fn total_gas(&self) -> u64 {
+        self.total_gas
+    }
+
+    /// The gas charged with each call stack, the outermost function first.
+    pub fn stacks(&self) -> &BTreeMap<Vec<String>, u64> {
+        &self.stacks
+    }
+
+    pub fn instructions(&self) -> &BTreeMap<String, Usage> {
+        &self.instructions
+    }
+
+    /// The gas charged for global storage operations, keyed by the operation and resource type,
+    /// e.g. `move_to<0x1::M::R>`.
+    pub fn storage_operations(&self) -> &BTreeMap<String, Usage> {
+        &self.storage_operations
+    }
+
+    pub fn locations(&self) -> &BTreeMap<Location, u64> {
+        &self.locations
+    }
+
+    /// The profile in the folded stacks format read by flamegraph tools: one line per call stack
+    /// with the frames separated by `;`, followed by the gas consumed.
+    pub fn folded_stacks(&self) -> String {
+        let mut folded = String::new();
+        for (stack, gas) in &self.stacks {
+            if *gas == 0 {
+                continue;
+            }
+            let stack = if stack.is_empty() {
+                UNKNOWN_FRAME.to_string()
+            } else {
+                stack.join(";")
+            };
+            writeln!(folded, "{} {}", stack, gas).unwrap();
+        }
+        folded
+    }
+
+    /// Summarizes the profile, sorting every table by gas consumed. `locate` maps code locations
+    /// to source lines; locations it returns `None` for are left out of the summary of lines.
+    pub fn summary(&self, locate: impl Fn(&Location) -> Option<String>) -> ProfileSummary {
+        let mut functions: BTreeMap<&str, FunctionGas> = BTreeMap::new();
+        for (stack, gas) in &self.stacks {
+            let names: BTreeSet<_> = stack.iter().map(String::as_str).collect();
+            for name in names {
+                functions
+                    .entry(name)
+                    .or_insert_with(|| FunctionGas::new(name))
+                    .total_gas += gas;
+            }
+            if let Some(name) = stack.last() {
+                functions.get_mut(name.as_str()).unwrap().self_gas += gas;
+            }
+        }
+        let mut functions: Vec<_> = functions.into_values().collect();
+        functions.sort_by_key(|function| Reverse(function.total_gas));
+
+        let mut lines: BTreeMap<String, u64> = BTreeMap::new();
+        for (location, gas) in &self.locations {
+            if let Some(line) = locate(location) {
+                *lines.entry(line).or_default() += gas;
+            }
+        }
+
+        ProfileSummary {
+            total_gas: self.total_gas,
+            functions,
+            instructions: sorted_entries(&self.instructions),
+            storage_operations: sorted_entries(&self.storage_operations),
+            lines: sorted_entries(
+                &lines
+                    .into_iter()
+                    .map(|(line, gas)| (line, Usage { count: 1, gas }))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+fn sorted_entries(usages: &BTreeMap<String, Usage>) -> Vec<Entry> {
+    let mut entries: Vec<_> = usages
+        .iter()
+        .map(|(name, usage)| Entry {
+            name: name.clone(),
+            count: usage.count,
+            gas: usage.gas,
+        })
+        .collect();
+    entries.sort_by_key(|entry| Reverse(entry.gas));
+    entries
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FunctionGas {
+    pub name: String,
+    /// The gas consumed by the function itself.
+    pub self_gas: u64,
+    /// The gas consumed by the function and the functions it called.
+    pub total_gas: u64,
+}
+
+impl FunctionGas {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            self_gas: 0,
+            total_gas: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Entry {
+    pub name: String,
+    pub count: u64,
+    pub gas: u64,
+}
+
+/// A summary of a `GasProfile`, which can be rendered as JSON or HTML.
+#[derive(Clone, Debug, Serialize)]
+pub struct ProfileSummary {
+    pub total_gas: u64,
+    pub functions: Vec<FunctionGas>,
+    pub instructions: Vec<Entry>,
+    pub storage_operations: Vec<Entry>,
+    /// The gas consumed per source line. The count of a line is always 1.
+    pub lines: Vec<Entry>,
+}
+
+impl ProfileSummary {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Renders the summary as a standalone HTML page titled `title`.
+    pub fn to_html(&self, title: &str) -> String {
+        let mut html = String::new();
+        writeln!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>",
+            escape(title)
+        )
+        .unwrap();
+        html.push_str(
+            "<style>body { font-family: sans-serif; } \
+             table { border-collapse: collapse; margin-bottom: 2em; } \
+             th, td { border: 1px solid #ccc; padding: 2px 8px; text-align: left; } \
+             td.gas { text-align: right; }</style>\n</head>\n<body>\n",
+        );
+        writeln!(html, "<h1>{}</h1>", escape(title)).unwrap();
+        writeln!(html, "<p>Total gas: {}</p>", self.total_gas).unwrap();
+
+        html.push_str("<h2>Functions</h2>\n<table>\n");
+        html.push_str("<tr><th>Function</th><th>Self gas</th><th>Total gas</th></tr>\n");
+        for function in &self.functions {
+            writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"gas\">{}</td><td class=\"gas\">{}</td></tr>",
+                escape(&function.name),
+                function.self_gas,
+                function.total_gas
+            )
+            .unwrap();
+        }
+        html.push_str("</table>\n");
+
+        write_entries(&mut html, "Instructions", "Instruction", &self.instructions);
+        write_entries(
+            &mut html,
+            "Storage operations",
+            "Operation",
+            &self.storage_operations,
+        );
+        write_entries(&mut html, "Source lines", "Line", &self.lines);
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+fn write_entries(html: &mut String, heading: &str, column: &str, entries: &[Entry]) {
+    writeln!(html, "<h2>{}</h2>\n<table>", heading).unwrap();
+    writeln!(
+        html,
+        "<tr><th>{}</th><th>Count</th><th>Gas</th></tr>",
+        column
+    )
+    .unwrap();
+    for entry in entries {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"gas\">{}</td><td class=\"gas\">{}</td></tr>",
+            escape(&entry.name),
+            entry.count,
+            entry.gas
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,484 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::profile::{GasProfile, Location};
+use move_binary_format::{
+    errors::{PartialVMResult, VMError},
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+};
+use move_core_types::{
+    gas_algebra::{AbstractMemorySize, InternalGas, NumArgs, NumBytes},
+    language_storage::ModuleId,
+};
+use move_vm_runtime::execution_tracer::{ExecutionTracer, TracedFunction};
+use move_vm_types::{
+    gas::{GasMeter, SimpleInstruction},
+    values::Value,
+    views::{TypeView, ValueView},
+};
+use std::cell::RefCell;
+
+/// A function on the call stack, as reported by the tracer.
+struct Frame {
+    name: String,
+    module_id: Option<ModuleId>,
+    function: FunctionDefinitionIndex,
+    pc: CodeOffset,
+    is_native: bool,
+}
+
+#[derive(Default)]
+struct ProfilerState {
+    frames: Vec<Frame>,
+    /// Set when the top frame returned. The frame is only popped once the gas for dropping it has
+    /// been charged, so that this gas is attributed to the returning function.
+    returned: bool,
+    profile: GasProfile,
+}
+
+impl ProfilerState {
+    fn pop_returned_frame(&mut self) {
+        if std::mem::take(&mut self.returned) {
+            self.frames.pop();
+        }
+    }
+
+    fn record(&mut self, instruction: String, storage_operation: Option<String>, gas: u64) {
+        let stack = self.frames.iter().map(|frame| frame.name.clone()).collect();
+        let location = self
+            .frames
+            .iter()
+            .rev()
+            .find(|frame| !frame.is_native)
+            .map(|frame| Location {
+                module_id: frame.module_id.clone(),
+                function: frame.function,
+                pc: frame.pc,
+            });
+        self.profile
+            .record(stack, location, instruction, storage_operation, gas);
+    }
+}
+
+/// Collects a `GasProfile` of an execution of the Move VM.
+///
+/// Gas is charged by the gas meter, while only the interpreter knows which code it is executing.
+/// A profiler therefore needs to be installed twice: `gas_meter` wraps the gas meter of the
+/// execution, and `tracer` must be installed on its session, e.g. with
+/// `MoveVM::new_session_with_tracer`. Every charge made through the gas meter is then attributed
+/// to the call stack and code offset reported by the tracer.
+#[derive(Default)]
+pub struct GasProfiler {
+    state: RefCell<ProfilerState>,
+}
+
+impl GasProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps `inner`, recording the gas it charges.
+    pub fn gas_meter<G: GasMeter>(&self, inner: G) -> ProfilingGasMeter<'_, G> {
+        ProfilingGasMeter {
+            inner,
+            profiler: self,
+        }
+    }
+
+    /// The tracer reporting the call stack of the execution to the profiler.
+    pub fn tracer(&self) -> ProfilingTracer<'_> {
+        ProfilingTracer { profiler: self }
+    }
+
+    pub fn into_profile(self) -> GasProfile {
+        self.state.into_inner().profile
+    }
+}
+
+/// The tracer half of a `GasProfiler`.
+pub struct ProfilingTracer<'a> {
+    profiler: &'a GasProfiler,
+}
+
+impl<'a> ExecutionTracer for ProfilingTracer<'a> {
+    fn enter_function(&mut self, function: &TracedFunction) {
+        let mut state = self.profiler.state.borrow_mut();
+        state.pop_returned_frame();
+        state.frames.push(Frame {
+            name: function.pretty_string(),
+            module_id: function.module_id().cloned(),
+            function: function.definition_index(),
+            pc: 0,
+            is_native: false,
+        });
+    }
+
+    fn exit_function(&mut self, _function: &TracedFunction) {
+        self.profiler.state.borrow_mut().returned = true;
+    }
+
+    fn unwind_function(&mut self, _function: &TracedFunction, _error: &VMError) {
+        let mut state = self.profiler.state.borrow_mut();
+        state.pop_returned_frame();
+        // A native which failed is still on top of the frame of its caller
+        while matches!(state.frames.last(), Some(frame) if frame.is_native) {
+            state.frames.pop();
+        }
+        state.frames.pop();
+    }
+
+    fn instruction(
+        &mut self,
+        _function: &TracedFunction,
+        pc: CodeOffset,
+        _instr: &Bytecode,
+        _stack: &[Value],
+    ) {
+        let mut state = self.profiler.state.borrow_mut();
+        state.pop_returned_frame();
+        if let Some(frame) = state.frames.last_mut() {
+            frame.pc = pc;
+        }
+    }
+
+    fn native_call(&mut self, function: &TracedFunction, _args: &[Value]) {
+        // Natives have no frame in the interpreter. Theirs is popped once their cost is charged.
+        self.profiler.state.borrow_mut().frames.push(Frame {
+            name: function.pretty_string(),
+            module_id: function.module_id().cloned(),
+            function: function.definition_index(),
+            pc: 0,
+            is_native: true,
+        });
+    }
+}
+
+/// The gas meter half of a `GasProfiler`, wrapping the gas meter which actually charges gas.
+pub struct ProfilingGasMeter<'a, G> {
+    inner: G,
+    profiler: &'a GasProfiler,
+}
+
+impl<'a, G: GasMeter> ProfilingGasMeter<'a, G> {
+    pub fn into_inner(self) -> G {
+        self.inner
+    }
+
+    /// Runs `charge` on the inner gas meter and records the gas it consumed for `instruction`.
+    fn charge(
+        &mut self,
+        instruction: &str,
+        storage_operation: Option<String>,
+        charge: impl FnOnce(&mut G) -> PartialVMResult<()>,
+    ) -> PartialVMResult<()> {
+        let balance = self.inner.balance_internal();
+        let res = charge(&mut self.inner);
+        let gas = balance
+            .checked_sub(self.inner.balance_internal())
+            .unwrap_or_else(InternalGas::zero);
+        self.profiler.state.borrow_mut().record(
+            instruction.to_string(),
+            storage_operation,
+            gas.into(),
+        );
+        res
+    }
+}
+
+fn storage_operation(operation: &str, ty: &impl TypeView) -> Option<String> {
+    Some(format!("{}<{}>", operation, ty.to_type_tag()))
+}
+
+impl<'a, G: GasMeter> GasMeter for ProfilingGasMeter<'a, G> {
+    fn balance_internal(&self) -> InternalGas {
+        self.inner.balance_internal()
+    }
+
+    fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
+        self.charge(&format!("{:?}", instr), None, |inner| {
+            inner.charge_simple_instr(instr)
+        })
+    }
+
+    fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("Pop", None, |inner| inner.charge_pop(popped_val))
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.charge("Call", None, |inner| {
+            inner.charge_call(module_id, func_name, args, num_locals)
+        })
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.charge("CallGeneric", None, |inner| {
+            inner.charge_call_generic(module_id, func_name, ty_args, args, num_locals)
+        })
+    }
+
+    fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
+        self.charge("LdConst", None, |inner| inner.charge_ld_const(size))
+    }
+
+    fn charge_ld_const_after_deserialization(
+        &mut self,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge("LdConst", None, |inner| {
+            inner.charge_ld_const_after_deserialization(val)
+        })
+    }
+
+    fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("CopyLoc", None, |inner| inner.charge_copy_loc(val))
+    }
+
+    fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("MoveLoc", None, |inner| inner.charge_move_loc(val))
+    }
+
+    fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("StLoc", None, |inner| inner.charge_store_loc(val))
+    }
+
+    fn charge_pack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let instruction = if is_generic { "PackGeneric" } else { "Pack" };
+        self.charge(instruction, None, |inner| inner.charge_pack(is_generic, args))
+    }
+
+    fn charge_unpack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let instruction = if is_generic {
+            "UnpackGeneric"
+        } else {
+            "Unpack"
+        };
+        self.charge(instruction, None, |inner| {
+            inner.charge_unpack(is_generic, args)
+        })
+    }
+
+    fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("ReadRef", None, |inner| inner.charge_read_ref(val))
+    }
+
+    fn charge_write_ref(
+        &mut self,
+        new_val: impl ValueView,
+        old_val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge("WriteRef", None, |inner| {
+            inner.charge_write_ref(new_val, old_val)
+        })
+    }
+
+    fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.charge("Eq", None, |inner| inner.charge_eq(lhs, rhs))
+    }
+
+    fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.charge("Neq", None, |inner| inner.charge_neq(lhs, rhs))
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let instruction = match (is_mut, is_generic) {
+            (false, false) => "ImmBorrowGlobal",
+            (false, true) => "ImmBorrowGlobalGeneric",
+            (true, false) => "MutBorrowGlobal",
+            (true, true) => "MutBorrowGlobalGeneric",
+        };
+        let operation = if is_mut {
+            "borrow_global_mut"
+        } else {
+            "borrow_global"
+        };
+        self.charge(instruction, storage_operation(operation, &ty), |inner| {
+            inner.charge_borrow_global(is_mut, is_generic, ty, is_success)
+        })
+    }
+
+    fn charge_exists(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        exists: bool,
+    ) -> PartialVMResult<()> {
+        let instruction = if is_generic {
+            "ExistsGeneric"
+        } else {
+            "Exists"
+        };
+        self.charge(instruction, storage_operation("exists", &ty), |inner| {
+            inner.charge_exists(is_generic, ty, exists)
+        })
+    }
+
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let instruction = if is_generic {
+            "MoveFromGeneric"
+        } else {
+            "MoveFrom"
+        };
+        self.charge(instruction, storage_operation("move_from", &ty), |inner| {
+            inner.charge_move_from(is_generic, ty, val)
+        })
+    }
+
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let instruction = if is_generic {
+            "MoveToGeneric"
+        } else {
+            "MoveTo"
+        };
+        self.charge(instruction, storage_operation("move_to", &ty), |inner| {
+            inner.charge_move_to(is_generic, ty, val, is_success)
+        })
+    }
+
+    fn charge_vec_pack<'b>(
+        &mut self,
+        ty: impl TypeView + 'b,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge("VecPack", None, |inner| inner.charge_vec_pack(ty, args))
+    }
+
+    fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.charge("VecLen", None, |inner| inner.charge_vec_len(ty))
+    }
+
+    fn charge_vec_borrow(
+        &mut self,
+        is_mut: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let instruction = if is_mut {
+            "VecMutBorrow"
+        } else {
+            "VecImmBorrow"
+        };
+        self.charge(instruction, None, |inner| {
+            inner.charge_vec_borrow(is_mut, ty, is_success)
+        })
+    }
+
+    fn charge_vec_push_back(
+        &mut self,
+        ty: impl TypeView,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge("VecPushBack", None, |inner| {
+            inner.charge_vec_push_back(ty, val)
+        })
+    }
+
+    fn charge_vec_pop_back(
+        &mut self,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge("VecPopBack", None, |inner| inner.charge_vec_pop_back(ty, val))
+    }
+
+    fn charge_vec_unpack(
+        &mut self,
+        ty: impl TypeView,
+        expect_num_elements: NumArgs,
+        elems: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge("VecUnpack", None, |inner| {
+            inner.charge_vec_unpack(ty, expect_num_elements, elems)
+        })
+    }
+
+    fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.charge("VecSwap", None, |inner| inner.charge_vec_swap(ty))
+    }
+
+    fn charge_load_resource(
+        &mut self,
+        loaded: Option<(NumBytes, impl ValueView)>,
+    ) -> PartialVMResult<()> {
+        self.charge(
+            "LoadResource",
+            Some("load_resource".to_string()),
+            |inner| inner.charge_load_resource(loaded),
+        )
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
+    ) -> PartialVMResult<()> {
+        let res = self.charge("NativeFunction", None, |inner| {
+            inner.charge_native_function(amount, ret_vals)
+        });
+        let mut state = self.profiler.state.borrow_mut();
+        if matches!(state.frames.last(), Some(frame) if frame.is_native) {
+            state.frames.pop();
+        }
+        res
+    }
+
+    fn charge_native_function_before_execution(
+        &mut self,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge("NativeFunction", None, |inner| {
+            inner.charge_native_function_before_execution(ty_args, args)
+        })
+    }
+
+    fn charge_drop_frame(
+        &mut self,
+        locals: impl Iterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let res = self.charge("Ret", None, |inner| inner.charge_drop_frame(locals));
+        self.profiler.state.borrow_mut().pop_returned_frame();
+        res
+    }
+
+    fn charge_peak_memory_usage(&mut self, peak: AbstractMemorySize) -> PartialVMResult<()> {
+        self.charge("PeakMemoryUsage", None, |inner| inner.charge_peak_memory_usage(peak))
+    }
+}
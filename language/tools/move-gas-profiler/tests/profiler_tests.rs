@@ -0,0 +1,207 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::{compiled_unit::AnnotatedCompiledUnit, Compiler};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::InternalGas,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+};
+use move_gas_profiler::{GasProfile, GasProfiler};
+use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunction};
+use move_vm_test_utils::{
+    gas_schedule::{zero_cost_schedule, Gas, GasCost, GasStatus},
+    InMemoryStorage,
+};
+use move_vm_types::{gas::GasMeter, natives::function::NativeResult, values::Value};
+use std::{collections::BTreeMap, sync::Arc};
+
+const CODE: &str = r#"
+    module 0x42::M {
+        struct R has key { v: u64 }
+
+        native fun double(x: u64): u64;
+
+        fun add_one(x: u64): u64 {
+            x + 1
+        }
+
+        public fun run(s: signer, x: u64) {
+            let v = double(add_one(x));
+            move_to(&s, R { v });
+        }
+
+        public fun overflow(x: u64): u64 {
+            add_one(x) + 1
+        }
+    }
+"#;
+
+const NATIVE_COST: u64 = 10;
+const GAS_BUDGET: u64 = 1_000_000;
+
+fn profile_run() -> (GasProfile, u64) {
+    profile_calls(&[("run", vec![MoveValue::U64(20)])])
+}
+
+/// Profiles calls of the functions of `CODE` in one session, ignoring their errors. `run` is
+/// passed a signer for 0x42 before `args`.
+fn profile_calls(calls: &[(&str, Vec<MoveValue>)]) -> (GasProfile, u64) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("M.move");
+    std::fs::write(&path, CODE).unwrap();
+    let (_, units) = Compiler::from_files(
+        vec![path.to_str().unwrap().to_string()],
+        vec![],
+        BTreeMap::<String, _>::new(),
+    )
+    .build_and_report()
+    .unwrap();
+    let module = match units.into_iter().next().unwrap() {
+        AnnotatedCompiledUnit::Module(module) => module.named_module.module,
+        AnnotatedCompiledUnit::Script(_) => panic!("expected a module"),
+    };
+
+    let addr = AccountAddress::from_hex_literal("0x42").unwrap();
+    let module_id = ModuleId::new(addr, Identifier::new("M").unwrap());
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+
+    let double: NativeFunction = Arc::new(|_context, _ty_args, mut args| {
+        let x = args.pop_back().unwrap().value_as::<u64>()?;
+        Ok(NativeResult::ok(
+            InternalGas::new(NATIVE_COST),
+            vec![Value::u64(x * 2)].into(),
+        ))
+    });
+    let vm = MoveVM::new(vec![(
+        addr,
+        Identifier::new("M").unwrap(),
+        Identifier::new("double").unwrap(),
+        double,
+    )])
+    .unwrap();
+
+    let mut cost_table = zero_cost_schedule();
+    cost_table
+        .instruction_table
+        .iter_mut()
+        .for_each(|cost| *cost = GasCost::new(1, 1));
+    let profiler = GasProfiler::new();
+    let mut tracer = profiler.tracer();
+    let mut gas_meter = profiler.gas_meter(GasStatus::new(&cost_table, Gas::new(GAS_BUDGET)));
+    let initial_balance = gas_meter.balance_internal();
+    let mut session = vm.new_session_with_tracer(&storage, &mut tracer);
+    for (function, args) in calls {
+        let mut args = args.clone();
+        if *function == "run" {
+            args.insert(0, MoveValue::Signer(addr));
+        }
+        let _ = session.execute_function_bypass_visibility(
+            &module_id,
+            &Identifier::new(*function).unwrap(),
+            vec![],
+            serialize_values(&args),
+            &mut gas_meter,
+        );
+    }
+    drop(session);
+
+    let consumed = initial_balance
+        .checked_sub(gas_meter.balance_internal())
+        .unwrap();
+    (profiler.into_profile(), consumed.into())
+}
+
+#[test]
+fn attribute_gas_to_call_stacks() {
+    let (profile, consumed) = profile_run();
+
+    // All gas charged is accounted for, and attributed to a function.
+    assert_eq!(profile.total_gas(), consumed);
+    assert_eq!(profile.stacks().values().sum::<u64>(), consumed);
+    assert!(profile.stacks().keys().all(|stack| !stack.is_empty()));
+
+    let stacks: BTreeMap<_, _> = profile
+        .stacks()
+        .iter()
+        .map(|(stack, gas)| {
+            let names: Vec<_> = stack
+                .iter()
+                .map(|name| name.rsplit("::").next().unwrap())
+                .collect();
+            (names.join(";"), *gas)
+        })
+        .collect();
+    assert!(stacks["run"] > 0);
+    assert!(stacks["run;add_one"] > 0);
+    assert!(stacks["run;double"] >= NATIVE_COST);
+
+    let folded = profile.folded_stacks();
+    assert_eq!(folded.lines().count(), stacks.len());
+    assert!(folded.lines().any(|line| {
+        let (stack, gas) = line.rsplit_once(' ').unwrap();
+        stack.contains("::M::run;") && stack.ends_with("::M::add_one") && gas.parse::<u64>().unwrap() > 0
+    }));
+}
+
+#[test]
+fn summarize_instructions_and_storage() {
+    let (profile, _) = profile_run();
+    let summary = profile.summary(|location| Some(format!("pc {}", location.pc)));
+
+    let run = summary
+        .functions
+        .iter()
+        .find(|function| function.name.ends_with("::M::run"))
+        .unwrap();
+    assert_eq!(run.total_gas, profile.total_gas());
+    assert!(run.self_gas < run.total_gas);
+
+    let move_to = &summary.storage_operations[0];
+    assert!(move_to.name.starts_with("move_to<"), "{}", move_to.name);
+    assert!(move_to.name.ends_with("::M::R>"), "{}", move_to.name);
+    assert_eq!(move_to.count, 1);
+
+    assert!(summary
+        .instructions
+        .iter()
+        .any(|instruction| instruction.name == "Add" && instruction.count == 1));
+    let line_total: u64 = summary.lines.iter().map(|line| line.gas).sum();
+    assert_eq!(line_total, profile.total_gas());
+
+    let html = summary.to_html("run");
+    assert!(html.contains("move_to&lt;"));
+    let json: serde_json::Value = serde_json::from_str(&summary.to_json()).unwrap();
+    assert_eq!(json["total_gas"], profile.total_gas());
+}
+
+#[test]
+fn unwind_frames_on_error() {
+    let (profile, consumed) = profile_calls(&[
+        ("overflow", vec![MoveValue::U64(u64::MAX)]),
+        ("run", vec![MoveValue::U64(20)]),
+    ]);
+    assert_eq!(profile.stacks().values().sum::<u64>(), consumed);
+
+    // The frames of the failed call are gone by the time the next call starts.
+    let stacks: Vec<Vec<&str>> = profile
+        .stacks()
+        .keys()
+        .map(|stack| {
+            stack
+                .iter()
+                .map(|name| name.rsplit("::").next().unwrap())
+                .collect()
+        })
+        .collect();
+    assert!(stacks.contains(&vec!["overflow", "add_one"]));
+    assert!(stacks.contains(&vec!["run", "add_one"]));
+    assert!(stacks
+        .iter()
+        .all(|stack| stack[0] == "run" || !stack.contains(&"run")));
+}
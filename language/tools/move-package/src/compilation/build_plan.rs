@@ -9,7 +9,9 @@ use crate::{
 use anyhow::Result;
 use move_compiler::{
     compiled_unit::AnnotatedCompiledUnit,
-    diagnostics::{report_diagnostics_to_color_buffer, report_warnings, FilesSourceText},
+    diagnostics::{
+        report_diagnostics_to_buffer_with_format, report_warnings_with_format, FilesSourceText,
+    },
     Compiler,
 };
 use petgraph::algo::toposort;
@@ -31,6 +33,8 @@ pub struct BuildPlan {
     root: PackageName,
     sorted_deps: Vec<PackageName>,
     resolution_graph: ResolvedGraph,
+    /// Other packages whose build output is kept, when the build directory is shared
+    sharing_build_dir: BTreeSet<PackageName>,
 }
 
 #[cfg(feature = "evm-backend")]
@@ -99,9 +103,20 @@ impl BuildPlan {
             root: resolution_graph.root_package.package.name,
             sorted_deps,
             resolution_graph,
+            sharing_build_dir: BTreeSet::new(),
         })
     }
 
+    /// Keeps the build output of `packages` when cleaning the build directory after compiling, as
+    /// the members of a workspace share theirs.
+    pub fn sharing_build_dir_with(
+        mut self,
+        packages: impl IntoIterator<Item = PackageName>,
+    ) -> Self {
+        self.sharing_build_dir.extend(packages);
+        self
+    }
+
     /// Compilation results in the process exit upon warning/failure
     pub fn compile<W: Write>(
         &self,
@@ -119,16 +134,21 @@ impl BuildPlan {
         bytecode_version: Option<u32>,
         writer: &mut W,
     ) -> Result<CompiledPackage> {
+        let diagnostics_format = self.resolution_graph.build_options.diagnostics_format;
         self.compile_with_driver(writer, bytecode_version, |compiler| {
             let (files, units_res) = compiler.build()?;
             match units_res {
                 Ok((units, warning_diags)) => {
-                    report_warnings(&files, warning_diags);
+                    report_warnings_with_format(&files, warning_diags, diagnostics_format);
                     Ok((files, units))
                 }
                 Err(error_diags) => {
                     assert!(!error_diags.is_empty());
-                    let diags_buf = report_diagnostics_to_color_buffer(&files, error_diags);
+                    let diags_buf = report_diagnostics_to_buffer_with_format(
+                        &files,
+                        error_diags,
+                        diagnostics_format,
+                    );
                     if let Err(err) = std::io::stdout().write_all(&diags_buf) {
                         anyhow::bail!("Cannot output compiler diagnostics: {}", err);
                     }
@@ -194,7 +214,11 @@ impl BuildPlan {
 
         Self::clean(
             &project_root.join(CompiledPackageLayout::Root.path()),
-            self.sorted_deps.iter().copied().collect(),
+            self.sorted_deps
+                .iter()
+                .chain(&self.sharing_build_dir)
+                .copied()
+                .collect(),
         )?;
         Ok(compiled)
     }
@@ -579,7 +579,14 @@ impl CompiledPackage {
             Flags::testing()
         } else {
             Flags::empty()
-        };
+        }
+        .set_diagnostics_format(resolution_graph.build_options.diagnostics_format)
+        .set_lint(
+            resolution_graph.build_options.lint
+                || !resolved_package.source_package.lints.is_empty(),
+        )
+        .set_lint_levels(resolved_package.source_package.lints.clone())
+        .set_features(resolution_graph.package_features());
         // Partition deps_package according whether src is available
         let (src_deps, bytecode_deps): (Vec<_>, Vec<_>) = deps_package_paths
             .clone()
@@ -8,8 +8,11 @@ use crate::{
 };
 use anyhow::Result;
 use itertools::Itertools;
-use move_compiler::shared::PackagePaths;
-use move_model::{model::GlobalEnv, options::ModelBuilderOptions, run_model_builder_with_options};
+use move_compiler::shared::{Flags, PackagePaths};
+use move_model::{
+    model::GlobalEnv, options::ModelBuilderOptions,
+    run_model_builder_with_options_and_compilation_flags,
+};
 
 #[derive(Debug, Clone)]
 pub struct ModelBuilder {
@@ -115,6 +118,11 @@ impl ModelBuilder {
             ),
         };
 
-        run_model_builder_with_options(all_targets, all_deps, ModelBuilderOptions::default())
+        run_model_builder_with_options_and_compilation_flags(
+            all_targets,
+            all_deps,
+            ModelBuilderOptions::default(),
+            Flags::verification().set_features(self.resolution_graph.package_features()),
+        )
     }
 }
@@ -9,11 +9,13 @@ pub mod package_hooks;
 pub mod resolution;
 pub mod source_package;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use clap::*;
+use move_compiler::diagnostics::DiagnosticsFormat;
 use move_core_types::account_address::AccountAddress;
 use move_model::model::GlobalEnv;
-use resolution::{dependency_graph::DependencyGraph, lock_file::LockFile};
+use move_symbol_pool::Symbol;
+use resolution::{dependency_graph::DependencyGraph, lock_file::LockFile, registry, FetchMode};
 use serde::{Deserialize, Serialize};
 use source_package::layout::SourcePackageLayout;
 use std::{
@@ -28,8 +30,11 @@ use crate::{
         build_plan::BuildPlan, compiled_package::CompiledPackage, model_builder::ModelBuilder,
     },
     package_lock::PackageLock,
-    resolution::resolution_graph::{ResolutionGraph, ResolvedGraph},
-    source_package::manifest_parser,
+    resolution::resolution_graph::{ResolutionGraph, ResolvedGraph, ResolvedWorkspace},
+    source_package::{
+        manifest_parser,
+        parsed_manifest::{PackageName, SourceManifest, Version},
+    },
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -121,8 +126,9 @@ pub struct BuildConfig {
     #[clap(name = "force-recompilation", long = "force", global = true)]
     pub force_recompilation: bool,
 
-    /// Optional location to save the lock file to, if package resolution succeeds.
-    #[clap(skip)]
+    /// Optional location to save the lock file to, if package resolution succeeds. In --locked
+    /// mode, the lock file to check instead (defaults to 'Move.lock' in the package).
+    #[clap(long = "lock-file", parse(from_os_str), global = true)]
     pub lock_file: Option<PathBuf>,
 
     /// Additional named address mapping. Useful for tools in rust
@@ -140,9 +146,46 @@ pub struct BuildConfig {
     #[clap(long = "skip-fetch-latest-git-deps", global = true)]
     pub skip_fetch_latest_git_deps: bool,
 
+    /// Require the lock file to exist and to be up to date: fail instead of updating it if the
+    /// manifest or the sources of any fetched dependency no longer match it
+    #[clap(long = "locked", global = true)]
+    pub locked: bool,
+
+    /// Never access the network: only use the dependencies already fetched to MOVE_HOME
+    #[clap(long = "offline", global = true)]
+    pub offline: bool,
+
     /// Bytecode version to compile move code
     #[clap(long = "bytecode-version", global = true)]
     pub bytecode_version: Option<u32>,
+
+    /// The format used when reporting compiler diagnostics
+    #[clap(
+        long = "diagnostics-format",
+        global = true,
+        default_value = "human",
+        possible_values = DiagnosticsFormat::variants(),
+    )]
+    pub diagnostics_format: DiagnosticsFormat,
+
+    /// Run the lints over the package's sources. The lints are also run if the package's
+    /// manifest has a '[lints]' section
+    #[clap(long = "lint", global = true)]
+    pub lint: bool,
+
+    /// Features to enable in the root package, separated by commas. Features of other packages in
+    /// the package graph can be enabled as '<package>/<feature>'
+    #[clap(long = "features", global = true, use_value_delimiter = true)]
+    pub features: Vec<String>,
+
+    /// Do not enable the 'default' feature of the root package
+    #[clap(long = "no-default-features", global = true)]
+    pub no_default_features: bool,
+
+    /// The deployment environment to build for. The addresses and dependencies declared for it in
+    /// the '[env.<name>]' section of the root package's manifest are used
+    #[clap(long = "env", global = true)]
+    pub env: Option<String>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd)]
@@ -181,6 +224,33 @@ impl BuildConfig {
         ret
     }
 
+    /// Compile the `members` of the workspace at `path` or containing it, or all of its members if
+    /// none are given (see `resolve_workspace`), returning one `CompiledPackage` per member in the
+    /// order they were selected. Members are compiled one after the other into the build directory
+    /// they share, so the dependencies they have in common are only compiled once. Like
+    /// `compile_package`, the process exits on the first member with compilation errors.
+    pub fn compile_workspace<W: Write>(
+        self,
+        path: &Path,
+        members: &[String],
+        writer: &mut W,
+    ) -> Result<Vec<CompiledPackage>> {
+        let bytecode_version = self.bytecode_version;
+        let workspace = self.resolve_workspace(path, members, writer)?;
+        let mutx = PackageLock::lock();
+        let ret = workspace
+            .resolved_graphs
+            .into_iter()
+            .map(|resolved_graph| {
+                BuildPlan::create(resolved_graph)?
+                    .sharing_build_dir_with(workspace.members.iter().copied())
+                    .compile(bytecode_version, writer)
+            })
+            .collect();
+        mutx.unlock();
+        ret
+    }
+
     #[cfg(feature = "evm-backend")]
     pub fn compile_package_evm<W: Write>(self, path: &Path, writer: &mut W) -> Result<()> {
         // resolution graph diagnostics are only needed for CLI commands so ignore them by passing a
@@ -239,26 +309,197 @@ impl BuildConfig {
 
         // This should be locked as it inspects the environment for `MOVE_HOME` which could
         // possibly be set by a different process in parallel.
-        let mut lock = LockFile::new(&path)?;
-        let manifest = manifest_parser::parse_source_manifest(toml_manifest)?;
+        let mut manifest = manifest_parser::parse_source_manifest(toml_manifest)?;
+        self.select_environment(&mut manifest)?;
+        let mut packages = vec![(manifest, PathBuf::new())];
+        let registry_versions = self.lock_dependencies(&path, &packages, writer)?;
+
+        packages[0].1 = path;
+        let resolution_graph =
+            ResolutionGraph::new_workspace(packages, self, &registry_versions, writer)?;
+        let ret = resolution_graph.resolve()?;
+
+        mutx.unlock();
+        Ok(ret)
+    }
+
+    /// Resolve the packages of the workspace at `path` or containing it together, along with the
+    /// resolution graph of each of the given `members`, or of every member if none are given. The
+    /// dependency graph of the whole workspace is written to a single lock file, and its packages
+    /// are installed in the build directory at the root of the workspace unless `install_dir` is
+    /// set.
+    pub fn resolve_workspace<W: Write>(
+        mut self,
+        path: &Path,
+        members: &[String],
+        writer: &mut W,
+    ) -> Result<ResolvedWorkspace> {
+        if self.test_mode {
+            self.dev_mode = true;
+        }
+        let path = SourcePackageLayout::try_find_workspace_root(path)?;
+        let workspace = manifest_parser::parse_workspace_manifest(
+            self.parse_toml_manifest(path.join(SourcePackageLayout::Manifest.path()))?,
+        )?;
+        if self.install_dir.is_none() {
+            self.install_dir = Some(path.clone());
+        }
 
-        let dependency_graph = DependencyGraph::new(
-            &manifest,
-            path.clone(),
-            self.skip_fetch_latest_git_deps,
+        let mut packages = Vec::new();
+        for member_path in workspace.members {
+            let mut manifest = self
+                .parse_toml_manifest(
+                    path.join(&member_path)
+                        .join(SourcePackageLayout::Manifest.path()),
+                )
+                .and_then(manifest_parser::parse_source_manifest)
+                .with_context(|| {
+                    format!(
+                        "While parsing workspace member '{}'",
+                        member_path.to_string_lossy()
+                    )
+                })?;
+            self.select_environment(&mut manifest)?;
+            if let Some((other, _)) =
+                packages
+                    .iter()
+                    .find(|(other, _): &&(SourceManifest, PathBuf)| {
+                        other.package.name == manifest.package.name
+                    })
+            {
+                bail!(
+                    "Found several workspace members named '{}'",
+                    other.package.name
+                );
+            }
+            packages.push((manifest, member_path));
+        }
+
+        let selected: Vec<PackageName> = if members.is_empty() {
+            packages.iter().map(|(pkg, _)| pkg.package.name).collect()
+        } else {
+            members
+                .iter()
+                .map(|name| PackageName::from(name.as_str()))
+                .collect()
+        };
+        for name in &selected {
+            if !packages.iter().any(|(pkg, _)| pkg.package.name == *name) {
+                bail!(
+                    "Package '{}' is not a member of the workspace at '{}'",
+                    name,
+                    path.to_string_lossy()
+                );
+            }
+        }
+
+        let mutx = PackageLock::lock();
+
+        // This should be locked as it inspects the environment for `MOVE_HOME` which could
+        // possibly be set by a different process in parallel.
+        let registry_versions = self.lock_dependencies(&path, &packages, writer)?;
+
+        let packages: Vec<_> = packages
+            .into_iter()
+            .map(|(manifest, member_path)| (manifest, path.join(member_path)))
+            .collect();
+        let all_members = packages.iter().map(|(pkg, _)| pkg.package.name).collect();
+        let resolution_graph =
+            ResolutionGraph::new_workspace(packages, self, &registry_versions, writer)?
+                .resolve()?;
+        let resolved_graphs = selected
+            .into_iter()
+            .map(|name| resolution_graph.for_package(name))
+            .collect();
+
+        mutx.unlock();
+        Ok(ResolvedWorkspace {
+            root_path: path,
+            members: all_members,
+            resolved_graphs,
+        })
+    }
+
+    /// Build the dependency graph of the `packages` at `path` (a single package, or the members
+    /// of a workspace), and write it to a lock file, returning the versions picked for packages
+    /// from registries.
+    ///
+    /// In `--locked` mode, the lock file (at `lock_file`, or in `path`) must already exist and
+    /// describe the same graph, down to the digests of fetched dependencies, and it is left
+    /// untouched.  Packages from registries are picked at the versions it records.
+    fn lock_dependencies<W: Write>(
+        &self,
+        path: &Path,
+        packages: &[(SourceManifest, PathBuf)],
+        writer: &mut W,
+    ) -> Result<BTreeMap<PackageName, Version>> {
+        let lock_path = match &self.lock_file {
+            Some(lock_path) => lock_path.clone(),
+            None => path.join("Move.lock"),
+        };
+
+        let preferred_versions = if self.locked {
+            let mut locked = std::fs::File::open(&lock_path).with_context(|| {
+                format!(
+                    "Unable to read lock file at {:?}, which is required in --locked mode",
+                    lock_path
+                )
+            })?;
+            registry::locked_versions(&mut locked)?
+        } else {
+            BTreeMap::new()
+        };
+
+        let mut lock = LockFile::new(path)?;
+        let dependency_graph = DependencyGraph::new_workspace(
+            packages,
+            path.to_path_buf(),
+            preferred_versions,
+            FetchMode::new(self),
             writer,
         )?;
 
+        let registry_versions = dependency_graph.registry_versions();
         dependency_graph.write_to_lock(&mut lock)?;
-        if let Some(lock_path) = &self.lock_file {
+        if self.locked {
+            lock.check_unchanged(&lock_path).with_context(|| {
+                format!(
+                    "The lock file at {:?} is out of date, and cannot be updated in --locked mode",
+                    lock_path
+                )
+            })?;
+        } else if let Some(lock_path) = &self.lock_file {
             lock.commit(lock_path)?;
         }
 
-        let resolution_graph = ResolutionGraph::new(manifest, path, self, writer)?;
-        let ret = resolution_graph.resolve()?;
+        Ok(registry_versions)
+    }
 
-        mutx.unlock();
-        Ok(ret)
+    /// Replace the dependencies of the root package `manifest` with the ones declared for the
+    /// environment selected with `--env`, if any. Its address assignments are applied during
+    /// resolution.
+    fn select_environment(&self, manifest: &mut SourceManifest) -> Result<()> {
+        let Some(env) = &self.env else {
+            return Ok(());
+        };
+        let environment = manifest.environment(Symbol::from(env.as_str()))?.clone();
+        for (dep_name, dep) in environment.dependencies {
+            let overridden = if manifest.dependencies.contains_key(&dep_name) {
+                &mut manifest.dependencies
+            } else if manifest.dev_dependencies.contains_key(&dep_name) {
+                &mut manifest.dev_dependencies
+            } else {
+                bail!(
+                    "Environment '{}' of package '{}' overrides dependency '{}', which is not \
+                     one of its dependencies",
+                    env,
+                    manifest.package.name,
+                    dep_name,
+                );
+            };
+            overridden.insert(dep_name, dep);
+        }
+        Ok(())
     }
 
     fn parse_toml_manifest(&self, path: PathBuf) -> Result<toml::Value> {
@@ -16,16 +16,18 @@ use crate::{
     source_package::{
         manifest_parser::parse_dependency,
         parsed_manifest::{
-            CustomDepInfo, Dependency, DependencyKind, GitInfo, NamedAddress, PackageName,
-            SourceManifest, SubstOrRename, Substitution,
+            CustomDepInfo, Dependency, DependencyKind, GitInfo, NamedAddress, PackageDigest,
+            PackageName, RegistryInfo, SourceManifest, SubstOrRename, Substitution, Version,
+            VersionReq,
         },
     },
 };
 
 use super::{
+    digest::compute_package_digest,
     download_and_update_if_remote,
     lock_file::{schema, LockFile},
-    parse_package_manifest,
+    parse_package_manifest, registry, FetchMode,
 };
 
 /// A representation of the transitive dependency graph of a Move package.  If successfully created,
@@ -35,6 +37,15 @@ use super::{
 /// - mentions each package at most once (i.e. no duplicate packages), and
 /// - contains information about the source of every package (excluding the root package).
 ///
+/// Packages from registries are picked at a single version, the newest one matching all the
+/// requirements on it.  Requirements are gathered as the graph is explored, and the graph is
+/// explored again whenever one of them rules out the version of a package that was already
+/// explored.  Versions are never revisited once ruled out, so resolution can fail even though a
+/// combination of compatible versions exists.
+///
+/// The graph of a workspace has several roots, its members, which are sourced from their paths
+/// relative to the workspace root when they are also dependencies of other members.
+///
 /// It can be built by recursively exploring a package's dependencies, fetching their sources if
 /// necessary, or by reading its serialized contents from a lock file.  Both these processes will
 /// fail if any of the criteria above cannot be met (e.g. if the graph contains a cycle, the same
@@ -45,21 +56,36 @@ use super::{
 /// and labels edges in the graph accordingly, as `DevOnly`, or `Always` dependencies.
 #[derive(Debug)]
 pub struct DependencyGraph {
-    /// Path to the root package and its name (according to its manifest)
+    /// Path to the root package and its name (according to its manifest), or to the root of a
+    /// workspace and the names of its members
     root_path: PathBuf,
-    root_package: PackageName,
+    root_packages: Vec<PackageName>,
 
     /// Transitive dependency graph, with dependency edges `P -> Q` labelled according to whether Q
     /// is always a dependency of P or only in dev-mode.
     package_graph: DiGraphMap<PackageName, DependencyMode>,
 
-    /// The dependency that each package (keyed by name) originates from.  The root package is the
-    /// only node in `package_graph` that does not have an entry in `package_table`.
+    /// The dependency that each package (keyed by name) originates from.  The root packages are
+    /// the only nodes in `package_graph` that may not have an entry in `package_table`.
     package_table: BTreeMap<PackageName, Dependency>,
 
+    /// The digests of the sources of packages that are not local to the root package (fetched
+    /// from git, by a custom hook, or read from a registry), recorded in the lock file so that
+    /// changes to them can be detected.
+    package_digests: BTreeMap<PackageName, PackageDigest>,
+
     /// Packages that are transitive dependencies regardless of mode (the transitive closure of
     /// `DependencyMode::Always` edges in `package_graph`).
     pub always_deps: BTreeSet<PackageName>,
+
+    /// The versions picked so far for packages from registries, and all the requirements on them
+    /// found while exploring the graph.
+    registry_versions: BTreeMap<PackageName, Version>,
+    registry_requirements: BTreeMap<PackageName, Vec<VersionReq>>,
+
+    /// Whether a package from a registry was explored at a version that is no longer the one
+    /// picked for it, so the graph needs to be explored again.
+    stale: bool,
 }
 
 /// Edge label indicating whether one package always depends on another, or only in dev-mode.
@@ -79,7 +105,8 @@ impl DependencyGraph {
     /// Build a graph from the transitive dependencies and dev-dependencies of `root_package`.
     ///
     /// `skip_fetch_latest_git_deps` controls whether package resolution will fetch the latest
-    /// versions of remote dependencies, even if a version already exists locally.
+    /// versions of remote dependencies, even if a version already exists locally (see
+    /// `new_workspace` for finer control).
     ///
     /// `progress_output` is an output stream that is written to while generating the graph, to
     /// provide human-readable progress updates.
@@ -89,27 +116,76 @@ impl DependencyGraph {
         skip_fetch_latest_git_deps: bool,
         progress_output: &mut Progress,
     ) -> Result<DependencyGraph> {
-        let mut graph = DependencyGraph {
-            root_path,
-            root_package: root_package.package.name,
-            package_graph: DiGraphMap::new(),
-            package_table: BTreeMap::new(),
-            always_deps: BTreeSet::new(),
+        let fetch_mode = if skip_fetch_latest_git_deps {
+            FetchMode::Missing
+        } else {
+            FetchMode::Latest
         };
 
-        graph
-            .extend_graph(
-                DependencyKind::default(),
-                root_package,
-                skip_fetch_latest_git_deps,
-                progress_output,
-            )
-            .with_context(|| {
-                format!(
-                    "Failed to resolve dependencies for package '{}'",
-                    graph.root_package
-                )
-            })?;
+        let mut graph = DependencyGraph::empty(root_path, vec![root_package.package.name]);
+        graph.explore(
+            &[(root_package, DependencyKind::default())],
+            fetch_mode,
+            progress_output,
+        )?;
+
+        graph.check_acyclic()?;
+        graph.discover_always_deps();
+
+        Ok(graph)
+    }
+
+    /// Build a graph from the transitive dependencies and dev-dependencies of the `members` of the
+    /// workspace at `root_path`, given along with their paths relative to it.
+    ///
+    /// Packages from registries are picked at the versions in `preferred_versions` (e.g. the ones
+    /// recorded in a lock file) as long as they satisfy all the requirements on them, and
+    /// `fetch_mode` controls whether remote dependencies are fetched.
+    pub fn new_workspace<Progress: Write>(
+        members: &[(SourceManifest, PathBuf)],
+        root_path: PathBuf,
+        preferred_versions: BTreeMap<PackageName, Version>,
+        fetch_mode: FetchMode,
+        progress_output: &mut Progress,
+    ) -> Result<DependencyGraph> {
+        let mut graph = DependencyGraph::empty(
+            root_path,
+            members.iter().map(|(pkg, _)| pkg.package.name).collect(),
+        );
+        graph.registry_versions = preferred_versions;
+
+        let mut roots = Vec::new();
+        let mut member_deps = BTreeMap::new();
+        for (member, member_path) in members {
+            let mut kind = DependencyKind::Local(member_path.clone());
+            kind.reroot(&DependencyKind::default())?;
+
+            roots.push((member, kind.clone()));
+            let member_dep = Dependency {
+                kind,
+                subst: None,
+                version: None,
+                digest: None,
+                features: Vec::new(),
+                default_features: true,
+            };
+            member_deps.insert(member.package.name, member_dep);
+        }
+
+        graph.explore(&roots, fetch_mode, progress_output)?;
+
+        // Members that other members depend on must be sourced from the members themselves.
+        for (member, member_dep) in member_deps {
+            match graph.package_table.get(&member) {
+                Some(dep) if dep.kind != member_dep.kind => bail!(
+                    "Conflicting dependencies found:\n{0} = {1}\n{0} = {2}\n",
+                    member,
+                    DependencyTOML(dep),
+                    DependencyTOML(&member_dep),
+                ),
+                _ => (),
+            }
+        }
 
         graph.check_acyclic()?;
         graph.discover_always_deps();
@@ -132,6 +208,7 @@ impl DependencyGraph {
     ) -> Result<DependencyGraph> {
         let mut package_graph = DiGraphMap::new();
         let mut package_table = BTreeMap::new();
+        let mut package_digests = BTreeMap::new();
 
         // Seed graph with edges from the root package
         let root = root_package.package.name;
@@ -149,6 +226,7 @@ impl DependencyGraph {
         for schema::Dependency {
             name,
             source,
+            digest,
             dependencies,
             dev_dependencies,
         } in schema::Dependencies::read(lock)?
@@ -171,6 +249,10 @@ impl DependencyGraph {
                 }
             };
 
+            if let Some(digest) = digest {
+                package_digests.insert(package, PackageDigest::from(digest));
+            }
+
             for dep in dependencies.iter().flatten() {
                 let dep = PackageName::from(dep.as_str());
                 package_graph.add_edge(package, dep, DependencyMode::Always);
@@ -182,13 +264,10 @@ impl DependencyGraph {
             }
         }
 
-        let mut graph = DependencyGraph {
-            root_path,
-            root_package: root,
-            package_graph,
-            package_table,
-            always_deps: BTreeSet::new(),
-        };
+        let mut graph = DependencyGraph::empty(root_path, vec![root]);
+        graph.package_graph = package_graph;
+        graph.package_table = package_table;
+        graph.package_digests = package_digests;
 
         graph.check_consistency()?;
         graph.check_acyclic()?;
@@ -196,6 +275,20 @@ impl DependencyGraph {
         Ok(graph)
     }
 
+    /// The versions picked for the packages from registries in the graph.
+    pub fn registry_versions(&self) -> BTreeMap<PackageName, Version> {
+        self.package_table
+            .iter()
+            .filter_map(|(pkg, dep)| match &dep.kind {
+                DependencyKind::Registry(RegistryInfo {
+                    resolved_version: Some(version),
+                    ..
+                }) => Some((*pkg, *version)),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Serialize this dependency graph into a lock file, consuming it in the process.
     ///
     /// This operation fails, writing nothing, if the graph contains a cycle, and can fail with an
@@ -207,6 +300,9 @@ impl DependencyGraph {
 
             writeln!(writer, "name = {}", str_escape(pkg.as_str())?)?;
             writeln!(writer, "source = {}", DependencyTOML(&dep))?;
+            if let Some(digest) = self.package_digests.get(&pkg) {
+                writeln!(writer, "digest = {}", str_escape(digest.as_str())?)?;
+            }
 
             let mut deps: Vec<_> = self
                 .package_graph
@@ -242,12 +338,57 @@ impl DependencyGraph {
         Ok(())
     }
 
+    fn empty(root_path: PathBuf, root_packages: Vec<PackageName>) -> DependencyGraph {
+        DependencyGraph {
+            root_path,
+            root_packages,
+            package_graph: DiGraphMap::new(),
+            package_table: BTreeMap::new(),
+            package_digests: BTreeMap::new(),
+            always_deps: BTreeSet::new(),
+            registry_versions: BTreeMap::new(),
+            registry_requirements: BTreeMap::new(),
+            stale: false,
+        }
+    }
+
+    /// Add the transitive dependencies and dev-dependencies of the `roots` to the graph, given
+    /// along with the dependency kind they are sourced from, until the versions picked for
+    /// packages from registries satisfy all the requirements on them.
+    fn explore<Progress: Write>(
+        &mut self,
+        roots: &[(&SourceManifest, DependencyKind)],
+        fetch_mode: FetchMode,
+        progress_output: &mut Progress,
+    ) -> Result<()> {
+        loop {
+            for (package, kind) in roots {
+                self.extend_graph(kind.clone(), package, fetch_mode, progress_output)
+                    .with_context(|| {
+                        format!(
+                            "Failed to resolve dependencies for package '{}'",
+                            package.package.name
+                        )
+                    })?;
+            }
+
+            if !self.stale {
+                return Ok(());
+            }
+
+            self.package_graph.clear();
+            self.package_table.clear();
+            self.package_digests.clear();
+            self.stale = false;
+        }
+    }
+
     /// Add the transitive dependencies and dev-dependencies from `package` to the dependency graph.
     fn extend_graph<Progress: Write>(
         &mut self,
         parent: DependencyKind,
         package: &SourceManifest,
-        skip_fetch_latest_git_deps: bool,
+        fetch_mode: FetchMode,
         progress_output: &mut Progress,
     ) -> Result<()> {
         let from = package.package.name;
@@ -255,7 +396,7 @@ impl DependencyGraph {
             let mut dep = dep.clone();
             dep.kind.reroot(&parent)?;
 
-            self.process_dependency(dep, *to, skip_fetch_latest_git_deps, progress_output)?;
+            self.process_dependency(dep, *to, fetch_mode, progress_output)?;
 
             self.package_graph
                 .add_edge(from, *to, DependencyMode::Always);
@@ -265,7 +406,7 @@ impl DependencyGraph {
             let mut dep = dep.clone();
             dep.kind.reroot(&parent)?;
 
-            self.process_dependency(dep, *to, skip_fetch_latest_git_deps, progress_output)?;
+            self.process_dependency(dep, *to, fetch_mode, progress_output)?;
 
             self.package_graph
                 .add_edge(from, *to, DependencyMode::DevOnly);
@@ -280,11 +421,18 @@ impl DependencyGraph {
     /// a different dependency.
     fn process_dependency<Progress: Write>(
         &mut self,
-        dep: Dependency,
+        mut dep: Dependency,
         dep_name: PackageName,
-        skip_fetch_latest_git_deps: bool,
+        fetch_mode: FetchMode,
         progress_output: &mut Progress,
     ) -> Result<()> {
+        if let DependencyKind::Registry(info) = &mut dep.kind {
+            let version = self
+                .pick_registry_version(dep_name, info, dep.version.as_ref())
+                .with_context(|| format!("Resolving the version of '{}'", dep_name))?;
+            info.resolved_version = Some(version);
+        }
+
         let dep = match self.package_table.entry(dep_name) {
             Entry::Vacant(entry) => entry.insert(dep),
 
@@ -293,6 +441,13 @@ impl DependencyGraph {
                 return Ok(());
             }
 
+            // Seeing the same package from the same registry again, but its version was picked
+            // again since it was explored: explore the graph again.
+            Entry::Occupied(entry) if same_registry_package(&entry.get().kind, &dep.kind) => {
+                self.stale = true;
+                return Ok(());
+            }
+
             // Seeing the same package again, but pointing to a different dependency: Not OK.
             Entry::Occupied(entry) => {
                 bail!(
@@ -304,11 +459,12 @@ impl DependencyGraph {
             }
         };
 
-        download_and_update_if_remote(dep_name, dep, skip_fetch_latest_git_deps, progress_output)
+        download_and_update_if_remote(dep_name, dep, fetch_mode, progress_output)
             .with_context(|| format!("Fetching '{}'", dep_name))?;
 
-        let (manifest, _) = parse_package_manifest(dep, &dep_name, self.root_path.clone())
-            .with_context(|| format!("Parsing manifest for '{}'", dep_name))?;
+        let (manifest, package_path) =
+            parse_package_manifest(dep, &dep_name, self.root_path.clone())
+                .with_context(|| format!("Parsing manifest for '{}'", dep_name))?;
 
         if dep_name != manifest.package.name {
             bail!(
@@ -319,16 +475,48 @@ impl DependencyGraph {
             )
         }
 
+        if !matches!(dep.kind, DependencyKind::Local(_)) {
+            let digest = compute_package_digest(&package_path)
+                .with_context(|| format!("Computing the digest of '{}'", dep_name))?;
+            self.package_digests.insert(dep_name, digest);
+        }
+
         let kind = dep.kind.clone();
-        self.extend_graph(kind, &manifest, skip_fetch_latest_git_deps, progress_output)
+        self.extend_graph(kind, &manifest, fetch_mode, progress_output)
             .with_context(|| format!("Resolving dependencies for package '{}'", dep_name))
     }
 
+    /// Pick a version for package `dep_name` from a registry, described by `info`, adding `req` to
+    /// the requirements on it.  Keeps the version picked so far if it satisfies all of them.
+    fn pick_registry_version(
+        &mut self,
+        dep_name: PackageName,
+        info: &RegistryInfo,
+        req: Option<&VersionReq>,
+    ) -> Result<Version> {
+        let requirements = self.registry_requirements.entry(dep_name).or_default();
+        if let Some(req) = req {
+            if !requirements.contains(req) {
+                requirements.push(req.clone());
+            }
+        }
+
+        if let Some(version) = self.registry_versions.get(&dep_name) {
+            if requirements.iter().all(|req| req.matches(version)) {
+                return Ok(*version);
+            }
+        }
+
+        let version = registry::select_version(&self.root_path, info, requirements)?;
+        self.registry_versions.insert(dep_name, version);
+        Ok(version)
+    }
+
     /// Check that every dependency in the graph, excluding the root package, is present in the
     /// package table.
     fn check_consistency(&self) -> Result<()> {
         for package in self.package_graph.nodes() {
-            if package == self.root_package {
+            if self.root_packages.contains(&package) {
                 continue;
             }
 
@@ -371,11 +559,11 @@ impl DependencyGraph {
         bail!("Found cycle between packages: {}", cycle.join(" -> "));
     }
 
-    /// Add the transitive closure of `DependencyMode::Always` edges reachable from the root package
+    /// Add the transitive closure of `DependencyMode::Always` edges reachable from the root packages
     /// to the `always_deps` set.  Assumes that if a package is already in the graph's `always_deps`
     /// set, then the sub-graph reachable from it has already been explored.
     fn discover_always_deps(&mut self) {
-        let mut frontier = vec![self.root_package];
+        let mut frontier = self.root_packages.clone();
         while let Some(package) = frontier.pop() {
             let new_frontier = self.always_deps.insert(package);
             if !new_frontier {
@@ -392,6 +580,17 @@ impl DependencyGraph {
     }
 }
 
+/// Whether `a` and `b` both point to the same package in the same registry, regardless of their
+/// version.
+fn same_registry_package(a: &DependencyKind, b: &DependencyKind) -> bool {
+    match (a, b) {
+        (DependencyKind::Registry(a), DependencyKind::Registry(b)) => {
+            a.registry == b.registry && a.package_name == b.package_name
+        }
+        _ => false,
+    }
+}
+
 impl<'a> fmt::Display for DependencyTOML<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let Dependency {
@@ -399,6 +598,7 @@ impl<'a> fmt::Display for DependencyTOML<'a> {
             subst,
             version,
             digest,
+            ..
         } = self.0;
 
         f.write_str("{ ")?;
@@ -442,10 +642,37 @@ impl<'a> fmt::Display for DependencyTOML<'a> {
                 write!(f, ", subdir = ")?;
                 f.write_str(&path_escape(subdir)?)?;
             }
+
+            DependencyKind::Registry(RegistryInfo {
+                registry,
+                package_name: _,
+                resolved_version,
+            }) => {
+                write!(f, "registry = ")?;
+                f.write_str(&path_escape(registry)?)?;
+
+                // The version picked replaces the requirement it was picked for
+                if let Some(version) = resolved_version {
+                    let req = VersionReq::exact(*version);
+                    write!(f, ", version = ")?;
+                    f.write_str(&str_escape(&req.to_string())?)?;
+                }
+            }
         }
 
-        if let Some((major, minor, bugfix)) = version {
-            write!(f, ", version = \"{}.{}.{}\"", major, minor, bugfix)?;
+        match (kind, version) {
+            (
+                DependencyKind::Registry(RegistryInfo {
+                    resolved_version: Some(_),
+                    ..
+                }),
+                _,
+            )
+            | (_, None) => (),
+            (_, Some(req)) => {
+                write!(f, ", version = ")?;
+                f.write_str(&str_escape(&req.to_string())?)?;
+            }
         }
 
         if let Some(digest) = digest {
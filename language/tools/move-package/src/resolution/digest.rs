@@ -50,3 +50,20 @@ pub fn compute_digest(paths: &[PathBuf]) -> Result<PackageDigest> {
 
     Ok(PackageDigest::from(format!("{:X}", hasher.finalize())))
 }
+
+/// The digest of the manifest of the package at `package_path` and of all of its sources, in any
+/// build mode.
+pub fn compute_package_digest(package_path: &Path) -> Result<PackageDigest> {
+    let paths: Vec<_> = [
+        SourcePackageLayout::Manifest,
+        SourcePackageLayout::Sources,
+        SourcePackageLayout::Scripts,
+        SourcePackageLayout::Examples,
+        SourcePackageLayout::Tests,
+    ]
+    .iter()
+    .map(|layout| package_path.join(layout.path()))
+    .filter(|path| path.exists())
+    .collect();
+    compute_digest(&paths)
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
+    io::{Seek, SeekFrom},
     ops::{Deref, DerefMut},
     path::Path,
 };
@@ -71,6 +73,51 @@ impl LockFile {
             .context("Committing lock file")?;
         Ok(())
     }
+
+    /// Consume the lock file, checking that it describes the same dependencies as the existing
+    /// lock file at `lock_path`, which is left untouched.
+    pub fn check_unchanged(mut self, lock_path: impl AsRef<Path>) -> Result<()> {
+        let lock_path = lock_path.as_ref();
+        let mut locked_file = File::open(lock_path)
+            .with_context(|| format!("Unable to read lock file at {:?}", lock_path))?;
+        let mut locked: BTreeMap<_, _> = schema::Dependencies::read(&mut locked_file)?
+            .into_iter()
+            .map(|dep| (dep.name.clone(), dep))
+            .collect();
+
+        self.seek(SeekFrom::Start(0))?;
+        for dep in schema::Dependencies::read(&mut *self)? {
+            let Some(expected) = locked.remove(&dep.name) else {
+                bail!("Dependency '{}' is missing from the lock file", dep.name);
+            };
+
+            if expected.source != dep.source {
+                bail!("The source of dependency '{}' has changed", dep.name);
+            }
+
+            if expected.digest != dep.digest {
+                bail!(
+                    "The digest of dependency '{}' has changed: expected {}, found {}",
+                    dep.name,
+                    expected.digest.as_deref().unwrap_or("none"),
+                    dep.digest.as_deref().unwrap_or("none"),
+                );
+            }
+
+            if expected.dependencies.unwrap_or_default() != dep.dependencies.unwrap_or_default()
+                || expected.dev_dependencies.unwrap_or_default()
+                    != dep.dev_dependencies.unwrap_or_default()
+            {
+                bail!("The dependencies of '{}' have changed", dep.name);
+            }
+        }
+
+        if let Some(name) = locked.keys().next() {
+            bail!("Dependency '{}' from the lock file is no longer used", name);
+        }
+
+        Ok(())
+    }
 }
 
 impl Deref for LockFile {
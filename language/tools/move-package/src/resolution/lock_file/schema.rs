@@ -25,7 +25,7 @@ pub struct Dependencies {
     dependencies: Option<Vec<Dependency>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Debug, PartialEq)]
 pub struct Dependency {
     /// The name of the dependency (corresponds to the key for the dependency in the source
     /// manifest).
@@ -35,6 +35,10 @@ pub struct Dependency {
     /// terms of serde-compatible structs, so it is deserialized into a generic data structure.
     pub source: Value,
 
+    /// The digest of the dependency's sources, for dependencies that are fetched rather than
+    /// found locally (see `resolution::digest::compute_package_digest`).
+    pub digest: Option<String>,
+
     pub dependencies: Option<Vec<String>>,
     #[serde(rename = "dev-dependencies")]
     pub dev_dependencies: Option<Vec<String>>,
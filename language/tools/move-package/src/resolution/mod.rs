@@ -2,10 +2,11 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
 use move_command_line_common::env::MOVE_HOME;
 use std::{
+    collections::BTreeMap,
     ffi::OsStr,
     fs,
     io::Write,
@@ -20,7 +21,7 @@ use crate::{
         manifest_parser::{parse_move_manifest_string, parse_source_manifest},
         parsed_manifest::{
             CustomDepInfo, Dependencies, Dependency, DependencyKind, GitInfo, PackageName,
-            SourceManifest,
+            RegistryInfo, SourceManifest,
         },
     },
     BuildConfig,
@@ -29,8 +30,32 @@ use crate::{
 pub mod dependency_graph;
 mod digest;
 pub mod lock_file;
+pub mod registry;
 pub mod resolution_graph;
 
+/// Whether dependencies are fetched from the network while resolving them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchMode {
+    /// Fetch the dependencies missing from `MOVE_HOME`, and the latest state of git dependencies
+    Latest,
+    /// Only fetch the dependencies missing from `MOVE_HOME`
+    Missing,
+    /// Never touch the network: all dependencies must already be in `MOVE_HOME`
+    Offline,
+}
+
+impl FetchMode {
+    pub fn new(build_options: &BuildConfig) -> Self {
+        if build_options.offline {
+            FetchMode::Offline
+        } else if build_options.skip_fetch_latest_git_deps {
+            FetchMode::Missing
+        } else {
+            FetchMode::Latest
+        }
+    }
+}
+
 pub fn download_dependency_repos<Progress: Write>(
     manifest: &SourceManifest,
     build_options: &BuildConfig,
@@ -47,10 +72,11 @@ pub fn download_dependency_repos<Progress: Write>(
     };
 
     for (dep_name, dep) in manifest.dependencies.iter().chain(additional_deps.iter()) {
+        let dep = &registry::resolve_version(dep, *dep_name, root_path, &BTreeMap::new())?;
         download_and_update_if_remote(
             *dep_name,
             dep,
-            build_options.skip_fetch_latest_git_deps,
+            FetchMode::new(build_options),
             progress_output,
         )?;
 
@@ -86,11 +112,24 @@ fn parse_package_manifest(
 fn download_and_update_if_remote<Progress: Write>(
     dep_name: PackageName,
     dep: &Dependency,
-    skip_fetch_latest_git_deps: bool,
+    fetch_mode: FetchMode,
     progress_output: &mut Progress,
 ) -> Result<()> {
     match &dep.kind {
-        DependencyKind::Local(_) => Ok(()),
+        // Registries are directories, which are read in place
+        DependencyKind::Local(_) | DependencyKind::Registry(_) => Ok(()),
+
+        kind @ DependencyKind::Custom(_) if fetch_mode == FetchMode::Offline => {
+            let path = repository_path(kind);
+            if !path.exists() {
+                bail!(
+                    "Package '{}' was not found at {:?}, and cannot be fetched while offline",
+                    dep_name,
+                    path,
+                );
+            }
+            Ok(())
+        }
 
         DependencyKind::Custom(node_info) => {
             package_hooks::resolve_custom_dependency(dep_name, node_info)
@@ -106,6 +145,15 @@ fn download_and_update_if_remote<Progress: Write>(
             let os_git_rev = OsStr::new(git_rev.as_str());
 
             if !git_path.exists() {
+                if fetch_mode == FetchMode::Offline {
+                    bail!(
+                        "Git repository of package '{}' was not found at {:?}, and cannot be \
+                         fetched while offline",
+                        dep_name,
+                        git_path,
+                    );
+                }
+
                 writeln!(
                     progress_output,
                     "{} {}",
@@ -136,7 +184,7 @@ fn download_and_update_if_remote<Progress: Write>(
                             dep_name
                         )
                     })?;
-            } else if !skip_fetch_latest_git_deps {
+            } else if fetch_mode == FetchMode::Latest {
                 // Update the git dependency
                 // Check first that it isn't a git rev (if it doesn't work, just continue with the
                 // fetch)
@@ -293,6 +341,11 @@ fn repository_path(kind: &DependencyKind) -> PathBuf {
         ]
         .iter()
         .collect(),
+
+        // Packages from a registry are found at <registry>/packages/<package>/<version>
+        DependencyKind::Registry(info @ RegistryInfo { registry, .. }) => {
+            info.package_path().unwrap_or_else(|| registry.clone())
+        }
     }
 }
 
@@ -0,0 +1,147 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Packages from registries: directories that can be hosted on a file share or mirrored locally,
+//! containing several versions of packages, laid out as follows:
+//!
+//! ```text
+//! <registry>/index/<package>.toml               the versions of <package> published
+//! <registry>/packages/<package>/<version>/      the sources of <package> at <version>
+//! ```
+//!
+//! Each index file lists the versions published, which can be yanked to prevent dependencies
+//! from picking them without removing them from the registry:
+//!
+//! ```toml
+//! [[version]]
+//! version = "1.0.0"
+//!
+//! [[version]]
+//! version = "1.1.0"
+//! yanked = true
+//! ```
+
+use std::{collections::BTreeMap, fs, io::Read, path::Path};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::source_package::{
+    manifest_parser::{parse_dependency, parse_version_str},
+    parsed_manifest::{Dependency, DependencyKind, PackageName, RegistryInfo, Version, VersionReq},
+};
+
+use super::lock_file::schema;
+
+#[derive(Deserialize)]
+struct Index {
+    #[serde(rename = "version", default)]
+    versions: Vec<IndexEntry>,
+}
+
+#[derive(Deserialize)]
+struct IndexEntry {
+    version: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+/// The versions of the package described by `info` published to its registry, in increasing
+/// order, along with whether they were yanked.  Relative registry paths are relative to
+/// `root_path`.
+pub fn published_versions(root_path: &Path, info: &RegistryInfo) -> Result<Vec<(Version, bool)>> {
+    let index_path = root_path.join(info.index_path());
+    let contents = fs::read_to_string(&index_path).with_context(|| {
+        format!(
+            "Unable to find package '{}' in registry at {:?}",
+            info.package_name, info.registry,
+        )
+    })?;
+
+    let index: Index = toml::from_str(&contents)
+        .with_context(|| format!("Malformed registry index at {:?}", index_path))?;
+
+    let mut versions = index
+        .versions
+        .into_iter()
+        .map(|entry| Ok((parse_version_str(&entry.version)?, entry.yanked)))
+        .collect::<Result<Vec<_>>>()
+        .with_context(|| format!("Malformed registry index at {:?}", index_path))?;
+    versions.sort();
+    Ok(versions)
+}
+
+/// Picks the newest version of the package described by `info` that matches all of
+/// `requirements` and was not yanked.
+pub fn select_version(
+    root_path: &Path,
+    info: &RegistryInfo,
+    requirements: &[VersionReq],
+) -> Result<Version> {
+    let published = published_versions(root_path, info)?;
+    let selected = published
+        .iter()
+        .rev()
+        .find(|(version, yanked)| !yanked && requirements.iter().all(|req| req.matches(version)));
+
+    let Some((version, _)) = selected else {
+        bail!(
+            "No version of package '{}' in registry at {:?} matches all of its requirements: {}\n\
+             Versions available: {}",
+            info.package_name,
+            info.registry,
+            requirements
+                .iter()
+                .map(|req| format!("\"{}\"", req))
+                .collect::<Vec<_>>()
+                .join(", "),
+            published
+                .iter()
+                .filter(|(_, yanked)| !yanked)
+                .map(|((major, minor, patch), _)| format!("{}.{}.{}", major, minor, patch))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    };
+
+    Ok(*version)
+}
+
+/// Returns `dep`, with the version of its package picked if it is an unresolved dependency on a
+/// registry: the one in `versions` if there is one (as chosen when resolving the dependency graph
+/// as a whole), or the newest one matching its requirement otherwise.  Relative registry paths
+/// are relative to `root_path`.
+pub(crate) fn resolve_version(
+    dep: &Dependency,
+    dep_name: PackageName,
+    root_path: &Path,
+    versions: &BTreeMap<PackageName, Version>,
+) -> Result<Dependency> {
+    let mut dep = dep.clone();
+    if let DependencyKind::Registry(info) = &mut dep.kind {
+        if info.resolved_version.is_none() {
+            let version = match versions.get(&dep_name) {
+                Some(version) => *version,
+                None => select_version(root_path, info, &Vec::from_iter(dep.version.clone()))?,
+            };
+            info.resolved_version = Some(version);
+        }
+    }
+    Ok(dep)
+}
+
+/// The versions of the packages from registries recorded in `lock`.
+pub fn locked_versions(lock: &mut impl Read) -> Result<BTreeMap<PackageName, Version>> {
+    let mut versions = BTreeMap::new();
+    for schema::Dependency { name, source, .. } in schema::Dependencies::read(lock)? {
+        let dep = parse_dependency(&name, source)
+            .with_context(|| format!("Deserializing dependency {}", name))?;
+        if let (DependencyKind::Registry(_), Some(version)) = (
+            &dep.kind,
+            dep.version.as_ref().and_then(VersionReq::as_exact),
+        ) {
+            versions.insert(PackageName::from(name.as_str()), version);
+        }
+    }
+    Ok(versions)
+}
@@ -7,8 +7,8 @@ use crate::{
     source_package::{
         layout::SourcePackageLayout,
         parsed_manifest::{
-            Dependency, FileName, NamedAddress, PackageDigest, PackageName, SourceManifest,
-            SubstOrRename,
+            Dependency, EnabledFeature, FeatureName, FileName, NamedAddress, PackageDigest,
+            PackageName, SourceManifest, SubstOrRename, Version, DEFAULT_FEATURE,
         },
     },
     BuildConfig,
@@ -31,7 +31,7 @@ use std::{
     rc::Rc,
 };
 
-use super::{download_and_update_if_remote, parse_package_manifest};
+use super::{download_and_update_if_remote, parse_package_manifest, registry, FetchMode};
 
 pub type ResolvedTable = ResolutionTable<AccountAddress>;
 pub type ResolvedPackage = ResolutionPackage<AccountAddress>;
@@ -76,6 +76,17 @@ pub struct ResolutionGraph<T> {
     pub package_table: BTreeMap<PackageName, ResolutionPackage<T>>,
 }
 
+/// The members of a workspace, resolved together (see `ResolutionGraph::new_workspace`).
+#[derive(Debug, Clone)]
+pub struct ResolvedWorkspace {
+    /// Where the workspace is located on the filesystem
+    pub root_path: PathBuf,
+    /// The names of all the members of the workspace
+    pub members: Vec<PackageName>,
+    /// The resolved graph of each of the members selected, rooted at that member
+    pub resolved_graphs: Vec<ResolvedGraph>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ResolutionPackage<T> {
     /// Pointer into the `ResolutionGraph.graph`
@@ -90,41 +101,73 @@ pub struct ResolutionPackage<T> {
     pub resolution_table: ResolutionTable<T>,
     /// The digest of the contents of all source files and manifest under the package root
     pub source_digest: PackageDigest,
+    /// The features enabled in this package, deciding which of its code annotated with
+    /// `#[cfg(...)]` is compiled
+    pub features: BTreeSet<FeatureName>,
 }
 
 impl ResolvingGraph {
     pub fn new<Progress: Write>(
         root_package: SourceManifest,
         root_package_path: PathBuf,
+        build_options: BuildConfig,
+        progress_output: &mut Progress,
+    ) -> Result<ResolvingGraph> {
+        Self::new_workspace(
+            vec![(root_package, root_package_path)],
+            build_options,
+            &BTreeMap::new(),
+            progress_output,
+        )
+    }
+
+    /// Resolves the `members` of a workspace, given along with their paths, all at once: the
+    /// packages they depend on are only resolved once and named addresses are unified across the
+    /// whole workspace. Each member is treated as a root package, and the first one is the root
+    /// of the returned graph (see `ResolvedGraph::for_package`).
+    ///
+    /// Packages from registries are resolved at the versions in `registry_versions`, as picked
+    /// for the whole dependency graph (see `DependencyGraph::registry_versions`), or at the newest
+    /// version matching the first requirement found on them if they are missing from it.
+    pub fn new_workspace<Progress: Write>(
+        members: Vec<(SourceManifest, PathBuf)>,
         mut build_options: BuildConfig,
+        registry_versions: &BTreeMap<PackageName, Version>,
         progress_output: &mut Progress,
     ) -> Result<ResolvingGraph> {
+        let (root_package, root_package_path) = match members.first() {
+            Some(root) => root.clone(),
+            None => bail!("A workspace must have at least one member"),
+        };
         if build_options.architecture.is_none() {
             if let Some(info) = &root_package.build {
                 build_options.architecture = info.architecture;
             }
         }
         let mut resolution_graph = Self {
-            root_package_path: root_package_path.clone(),
+            root_package_path,
             build_options,
-            root_package: root_package.clone(),
+            root_package,
             graph: DiGraphMap::new(),
             package_table: BTreeMap::new(),
         };
 
-        resolution_graph
-            .build_resolution_graph(
-                root_package.clone(),
-                root_package_path,
-                true,
-                progress_output,
-            )
-            .with_context(|| {
-                format!(
-                    "Unable to resolve packages for package '{}'",
-                    root_package.package.name
+        let root_packages = members.iter().map(|(pkg, _)| pkg.package.name).collect();
+        for (package, package_path) in members {
+            let package_name = package.package.name;
+            resolution_graph
+                .build_resolution_graph(
+                    package,
+                    package_path,
+                    &root_packages,
+                    registry_versions,
+                    progress_output,
                 )
-            })?;
+                .with_context(|| {
+                    format!("Unable to resolve packages for package '{}'", package_name)
+                })?;
+        }
+        resolution_graph.enable_features(&root_packages)?;
         Ok(resolution_graph)
     }
 
@@ -149,6 +192,7 @@ impl ResolvingGraph {
                     renaming,
                     resolution_table,
                     source_digest,
+                    features,
                 } = package;
 
                 let resolved_table = resolution_table
@@ -173,6 +217,7 @@ impl ResolvingGraph {
                     renaming,
                     resolution_table: resolved_table,
                     source_digest,
+                    features,
                 };
                 (name, resolved_pkg)
             })
@@ -202,10 +247,12 @@ impl ResolvingGraph {
         &mut self,
         package: SourceManifest,
         package_path: PathBuf,
-        is_root_package: bool,
+        root_packages: &BTreeSet<PackageName>,
+        registry_versions: &BTreeMap<PackageName, Version>,
         progress_output: &mut Progress,
     ) -> Result<()> {
         let package_name = package.package.name;
+        let is_root_package = root_packages.contains(&package_name);
         let package_node_id = match self.package_table.get(&package_name) {
             None => self.get_or_add_node(package_name)?,
             // Same package and we've already resolved it: OK, return early
@@ -256,7 +303,14 @@ impl ResolvingGraph {
             self.graph.add_edge(package_node_id, dep_node_id, ());
 
             let (dep_renaming, dep_resolution_table) = self
-                .process_dependency(dep_name, dep, package_path.clone(), progress_output)
+                .process_dependency(
+                    dep_name,
+                    dep,
+                    package_path.clone(),
+                    root_packages,
+                    registry_versions,
+                    progress_output,
+                )
                 .with_context(|| {
                     format!(
                         "While resolving dependency '{}' in package '{}'",
@@ -298,12 +352,116 @@ impl ResolvingGraph {
             renaming,
             resolution_table,
             source_digest,
+            features: BTreeSet::new(),
         };
 
         self.package_table.insert(package_name, resolved_package);
         Ok(())
     }
 
+    /// Enables the features requested in the `root_packages` by the build options (features given
+    /// as `<package>/<feature>` are requested in that package instead), the features that packages
+    /// request from their dependencies, and the features that all of these enable in turn.
+    fn enable_features(&mut self, root_packages: &BTreeSet<PackageName>) -> Result<()> {
+        let default = FeatureName::from(DEFAULT_FEATURE);
+
+        // Features to enable, along with the package that requested them, if any
+        let mut requested: Vec<(PackageName, FeatureName, Option<PackageName>)> = Vec::new();
+        for root in root_packages {
+            if !self.build_options.no_default_features {
+                requested.push((*root, default, None));
+            }
+        }
+        for feature in &self.build_options.features {
+            match feature.split_once('/') {
+                None => requested.extend(
+                    root_packages
+                        .iter()
+                        .map(|root| (*root, FeatureName::from(feature.as_str()), None)),
+                ),
+                Some((package, feature)) => {
+                    requested.push((PackageName::from(package), FeatureName::from(feature), None))
+                }
+            }
+        }
+        for (name, package) in &self.package_table {
+            for dep_name in self.graph.neighbors_directed(*name, Outgoing) {
+                let source_package = &package.source_package;
+                let Some(dep) = source_package
+                    .dependencies
+                    .get(&dep_name)
+                    .or_else(|| source_package.dev_dependencies.get(&dep_name))
+                else {
+                    continue;
+                };
+                if dep.default_features {
+                    requested.push((dep_name, default, Some(*name)));
+                }
+                for feature in &dep.features {
+                    requested.push((dep_name, *feature, Some(*name)));
+                }
+            }
+        }
+
+        while let Some((package_name, feature, requested_by)) = requested.pop() {
+            let requester = || match requested_by {
+                Some(name) => format!("requested by package '{}'", name),
+                None => "requested in the build options".to_string(),
+            };
+            let Some(package) = self.package_table.get_mut(&package_name) else {
+                bail!(
+                    "Unable to enable feature '{}' of package '{}' ({}), which is not part of \
+                     the package graph",
+                    feature,
+                    package_name,
+                    requester(),
+                );
+            };
+            if !package.features.insert(feature) {
+                continue;
+            }
+
+            let enabled = match package.source_package.features.get(&feature) {
+                Some(enabled) => enabled.clone(),
+                None if feature == default => continue,
+                None => bail!(
+                    "Package '{}' has no feature '{}' ({})",
+                    package_name,
+                    feature,
+                    requester(),
+                ),
+            };
+            for enabled_feature in enabled {
+                match enabled_feature {
+                    EnabledFeature::Package(feature) => {
+                        requested.push((package_name, feature, Some(package_name)))
+                    }
+                    EnabledFeature::Dependency(dep_name, dep_feature) => {
+                        let source_package = &package.source_package;
+                        if !source_package.dependencies.contains_key(&dep_name)
+                            && !source_package.dev_dependencies.contains_key(&dep_name)
+                        {
+                            bail!(
+                                "Feature '{}' of package '{}' enables feature '{}' of '{}', \
+                                 which is not one of its dependencies",
+                                feature,
+                                package_name,
+                                dep_feature,
+                                dep_name,
+                            );
+                        }
+                        // Dev-dependencies outside of dev mode are not part of the graph
+                        if self.graph.contains_edge(package_name, dep_name) {
+                            requested.push((dep_name, dep_feature, Some(package_name)))
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn unify_addresses_in_package(
         &mut self,
         package: &SourceManifest,
@@ -328,6 +486,39 @@ impl ResolvingGraph {
             }
         }
 
+        if let (Some(env), true) = (&self.build_options.env, is_root_package) {
+            let environment = package.environment(Symbol::from(env.as_str()))?;
+            // In dev mode, dev addresses take precedence over those of the environment
+            let dev_addresses = package
+                .dev_address_assignments
+                .as_ref()
+                .filter(|_| self.build_options.dev_mode);
+            for (name, addr) in &environment.addresses {
+                match resolution_table.get(name) {
+                    Some(_) if dev_addresses.map_or(false, |dev| dev.contains_key(name)) => (),
+                    Some(other) => {
+                        other.unify(Some(*addr)).with_context(|| {
+                            format!(
+                                "Unable to resolve named address '{}' in package '{}' when \
+                                 resolving dependencies for environment '{}'",
+                                name, package_name, env
+                            )
+                        })?;
+                    }
+                    None => {
+                        bail!(
+                            "Found unbound address assignment '{} = 0x{}' in environment '{}' of \
+                             root package '{}'. Environments cannot introduce new named addresses",
+                            name,
+                            addr.short_str_lossless(),
+                            env,
+                            package_name
+                        );
+                    }
+                }
+            }
+        }
+
         if self.build_options.dev_mode && is_root_package {
             let mut addr_to_name_mapping = BTreeMap::new();
             for (name, addr) in resolution_table
@@ -397,21 +588,28 @@ impl ResolvingGraph {
         dep_name_in_pkg: PackageName,
         dep: Dependency,
         root_path: PathBuf,
+        root_packages: &BTreeSet<PackageName>,
+        registry_versions: &BTreeMap<PackageName, Version>,
         progress_output: &mut Progress,
     ) -> Result<(Renaming, ResolvingTable)> {
+        let dep = registry::resolve_version(&dep, dep_name_in_pkg, &root_path, registry_versions)?;
         download_and_update_if_remote(
             dep_name_in_pkg,
             &dep,
-            self.build_options.skip_fetch_latest_git_deps,
+            FetchMode::new(&self.build_options),
             progress_output,
         )?;
         let (dep_package, dep_package_dir) =
             parse_package_manifest(&dep, &dep_name_in_pkg, root_path)
                 .with_context(|| format!("While processing dependency '{}'", dep_name_in_pkg))?;
-        self.build_resolution_graph(dep_package.clone(), dep_package_dir, false, progress_output)
-            .with_context(|| {
-                format!("Unable to resolve package dependency '{}'", dep_name_in_pkg)
-            })?;
+        self.build_resolution_graph(
+            dep_package.clone(),
+            dep_package_dir,
+            root_packages,
+            registry_versions,
+            progress_output,
+        )
+        .with_context(|| format!("Unable to resolve package dependency '{}'", dep_name_in_pkg))?;
 
         if dep_name_in_pkg != dep_package.package.name {
             bail!("Name of dependency declared in package '{}' does not match dependency's package name '{}'",
@@ -420,6 +618,20 @@ impl ResolvingGraph {
             );
         }
 
+        if let Some(req) = &dep.version {
+            let (major, minor, patch) = dep_package.package.version;
+            if !req.matches(&dep_package.package.version) {
+                bail!(
+                    "Version {}.{}.{} of dependency '{}' does not match its requirement \"{}\"",
+                    major,
+                    minor,
+                    patch,
+                    dep_name_in_pkg,
+                    req,
+                );
+            }
+        }
+
         match dep.digest {
             None => (),
             Some(fixed_digest) => {
@@ -641,6 +853,46 @@ impl ResolvedGraph {
         self.package_table.get(package_ident).unwrap()
     }
 
+    /// Returns the graph of the package `package_name` and of its transitive dependencies, rooted
+    /// at that package. This is how the members of a workspace, which are resolved together, are
+    /// each built.
+    pub fn for_package(&self, package_name: PackageName) -> ResolvedGraph {
+        let package = self.get_package(&package_name);
+        let mut packages = package.transitive_dependencies(self);
+        packages.insert(package_name);
+
+        let mut graph = DiGraphMap::new();
+        for name in &packages {
+            graph.add_node(*name);
+        }
+        for (from, to, ()) in self.graph.all_edges() {
+            if packages.contains(&from) && packages.contains(&to) {
+                graph.add_edge(from, to, ());
+            }
+        }
+
+        ResolvedGraph {
+            root_package_path: package.package_path.clone(),
+            build_options: self.build_options.clone(),
+            root_package: package.source_package.clone(),
+            graph,
+            package_table: self
+                .package_table
+                .iter()
+                .filter(|(name, _)| packages.contains(name))
+                .map(|(name, pkg)| (*name, pkg.clone()))
+                .collect(),
+        }
+    }
+
+    /// The features enabled in each package of the graph, as given to the compiler.
+    pub fn package_features(&self) -> BTreeMap<Option<Symbol>, BTreeSet<Symbol>> {
+        self.package_table
+            .iter()
+            .map(|(name, pkg)| (Some(*name), pkg.features.clone()))
+            .collect()
+    }
+
     fn print_info_dfs(&self, current_node: &PackageName, tree: &mut TreeBuilder) -> Result<()> {
         let pkg = self.package_table.get(current_node).unwrap();
 
@@ -2,10 +2,15 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::path::{Path, PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{bail, Result};
 
+use super::manifest_parser::{is_workspace_manifest, parse_move_manifest_string};
+
 /// References file for documentation generation
 pub const REFERENCE_TEMPLATE_FILENAME: &str = "references.md";
 
@@ -49,6 +54,27 @@ impl SourcePackageLayout {
         }
     }
 
+    /// Finds the closest directory among `starting_path` and its parents holding the manifest of a
+    /// workspace.
+    pub fn try_find_workspace_root(starting_path: &Path) -> Result<PathBuf> {
+        let mut current_path = starting_path.to_path_buf();
+        loop {
+            let manifest_path = current_path.join(Self::Manifest.path());
+            if manifest_path.is_file() {
+                let manifest = parse_move_manifest_string(fs::read_to_string(&manifest_path)?)?;
+                if is_workspace_manifest(&manifest) {
+                    break Ok(current_path);
+                }
+            }
+            if !current_path.pop() {
+                bail!(
+                    "Unable to find workspace manifest in '{}' or in its parents",
+                    starting_path.to_string_lossy()
+                )
+            }
+        }
+    }
+
     pub fn location_str(&self) -> &'static str {
         match self {
             Self::Sources => "sources",
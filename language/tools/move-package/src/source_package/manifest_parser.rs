@@ -4,6 +4,7 @@
 
 use crate::{package_hooks, source_package::parsed_manifest as PM, Architecture};
 use anyhow::{anyhow, bail, format_err, Context, Result};
+use move_compiler::linters::{self, LintLevel};
 use move_core_types::account_address::{AccountAddress, AccountAddressParseError};
 use move_symbol_pool::symbol::Symbol;
 use std::{
@@ -22,6 +23,12 @@ const ADDRESSES_NAME: &str = "addresses";
 const DEV_ADDRESSES_NAME: &str = "dev-addresses";
 const DEPENDENCY_NAME: &str = "dependencies";
 const DEV_DEPENDENCY_NAME: &str = "dev-dependencies";
+const LINTS_NAME: &str = "lints";
+const FEATURES_NAME: &str = "features";
+const ENVIRONMENTS_NAME: &str = "env";
+const WORKSPACE_NAME: &str = "workspace";
+// read by the formatter, see `move_formatter::FormatConfig`
+const FMT_NAME: &str = "fmt";
 
 const KNOWN_NAMES: &[&str] = &[
     PACKAGE_NAME,
@@ -30,6 +37,10 @@ const KNOWN_NAMES: &[&str] = &[
     DEV_ADDRESSES_NAME,
     DEPENDENCY_NAME,
     DEV_DEPENDENCY_NAME,
+    LINTS_NAME,
+    FEATURES_NAME,
+    ENVIRONMENTS_NAME,
+    FMT_NAME,
 ];
 
 const REQUIRED_FIELDS: &[&str] = &[PACKAGE_NAME];
@@ -50,6 +61,9 @@ pub fn parse_move_manifest_string(manifest_string: String) -> Result<TV> {
 pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
     match tval {
         TV::Table(mut table) => {
+            if table.contains_key(WORKSPACE_NAME) {
+                bail!("Expected the manifest of a package, but found the manifest of a workspace");
+            }
             check_for_required_field_names(&table, REQUIRED_FIELDS)
                 .context("Error parsing package manifest")?;
             warn_if_unknown_field_names(&table, KNOWN_NAMES);
@@ -86,6 +100,24 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
                 .transpose()
                 .context("Error parsing '[dev-dependencies]' section of manifest")?
                 .unwrap_or_default();
+            let lints = table
+                .remove(LINTS_NAME)
+                .map(parse_lints)
+                .transpose()
+                .context("Error parsing '[lints]' section of manifest")?
+                .unwrap_or_default();
+            let features = table
+                .remove(FEATURES_NAME)
+                .map(parse_features)
+                .transpose()
+                .context("Error parsing '[features]' section of manifest")?
+                .unwrap_or_default();
+            let environments = table
+                .remove(ENVIRONMENTS_NAME)
+                .map(parse_environments)
+                .transpose()
+                .context("Error parsing '[env]' section of manifest")?
+                .unwrap_or_default();
             Ok(PM::SourceManifest {
                 package,
                 addresses,
@@ -93,6 +125,9 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
                 build,
                 dependencies,
                 dev_dependencies,
+                lints,
+                features,
+                environments,
             })
         }
         x => {
@@ -105,6 +140,69 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
     }
 }
 
+/// Returns whether `tval` is the manifest of a workspace rather than that of a package.
+pub fn is_workspace_manifest(tval: &TV) -> bool {
+    tval.get(WORKSPACE_NAME).is_some()
+}
+
+pub fn parse_workspace_manifest(tval: TV) -> Result<PM::WorkspaceManifest> {
+    match tval {
+        TV::Table(mut table) => {
+            check_for_required_field_names(&table, &[WORKSPACE_NAME])
+                .context("Error parsing workspace manifest")?;
+            if table.contains_key(PACKAGE_NAME) {
+                bail!(
+                    "Error parsing workspace manifest: a workspace cannot also declare a package"
+                );
+            }
+            warn_if_unknown_field_names(&table, &[WORKSPACE_NAME]);
+            parse_workspace_info(table.remove(WORKSPACE_NAME).unwrap())
+                .context("Error parsing '[workspace]' section of manifest")
+        }
+        x => {
+            bail!(
+                "Malformed workspace manifest {}. Expected a table at top level, but encountered a {}",
+                x,
+                x.type_str()
+            )
+        }
+    }
+}
+
+fn parse_workspace_info(tval: TV) -> Result<PM::WorkspaceManifest> {
+    match tval {
+        TV::Table(mut table) => {
+            check_for_required_field_names(&table, &["members"])?;
+            warn_if_unknown_field_names(&table, &["members"]);
+            let members = table
+                .remove("members")
+                .unwrap()
+                .as_array()
+                .ok_or_else(|| format_err!("Invalid workspace members list"))?
+                .iter()
+                .map(|tval| {
+                    tval.as_str().map(PathBuf::from).ok_or_else(|| {
+                        format_err!(
+                            "Invalid workspace member '{}' of type {} found. Expected a path.",
+                            tval,
+                            tval.type_str()
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if members.is_empty() {
+                bail!("A workspace must have at least one member");
+            }
+            Ok(PM::WorkspaceManifest { members })
+        }
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
 pub fn parse_package_info(tval: TV) -> Result<PM::PackageInfo> {
     match tval {
         TV::Table(mut table) => {
@@ -256,7 +354,197 @@ pub fn parse_addresses(tval: TV) -> Result<PM::AddressDeclarations> {
     }
 }
 
+pub fn parse_lints(tval: TV) -> Result<PM::LintLevels> {
+    match tval {
+        TV::Table(table) => {
+            let mut lints = BTreeMap::new();
+            for (lint_name, entry) in table.into_iter() {
+                if !linters::is_lint_name(&lint_name) {
+                    bail!(
+                        "Unknown lint '{}'. Expected one of: {}",
+                        lint_name,
+                        linters::lint_names().collect::<Vec<_>>().join(", ")
+                    )
+                }
+                let level = match entry.as_str() {
+                    Some(level_str) => level_str.parse::<LintLevel>()?,
+                    None => bail!(
+                        "Invalid level {} for lint '{}'. Expected a string but found a {}",
+                        entry,
+                        lint_name,
+                        entry.type_str()
+                    ),
+                };
+                lints.insert(lint_name, level);
+            }
+            Ok(lints)
+        }
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
+pub fn parse_features(tval: TV) -> Result<PM::FeatureDeclarations> {
+    match tval {
+        TV::Table(table) => {
+            let mut features = BTreeMap::new();
+            for (feature_name, entry) in table.into_iter() {
+                check_feature_name(&feature_name)?;
+                let Some(enabled) = entry.as_array() else {
+                    bail!(
+                        "Invalid list of features enabled by feature '{}'. \
+                         Expected an array but found a {}",
+                        feature_name,
+                        entry.type_str()
+                    )
+                };
+                let enabled = enabled
+                    .iter()
+                    .map(parse_enabled_feature)
+                    .collect::<Result<_>>()
+                    .with_context(|| format!("Invalid feature '{}'", feature_name))?;
+                features.insert(PM::FeatureName::from(feature_name), enabled);
+            }
+            Ok(features)
+        }
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
+fn parse_enabled_feature(tval: &TV) -> Result<PM::EnabledFeature> {
+    let Some(feature) = tval.as_str() else {
+        bail!(
+            "Invalid feature {} of type {} found. Expected a string.",
+            tval,
+            tval.type_str()
+        )
+    };
+    Ok(match feature.split_once('/') {
+        None => {
+            check_feature_name(feature)?;
+            PM::EnabledFeature::Package(PM::FeatureName::from(feature))
+        }
+        Some((dep_name, feature)) => {
+            check_feature_name(feature)?;
+            PM::EnabledFeature::Dependency(
+                PM::PackageName::from(dep_name),
+                PM::FeatureName::from(feature),
+            )
+        }
+    })
+}
+
+fn parse_feature_names(tval: TV) -> Result<Vec<PM::FeatureName>> {
+    let Some(features) = tval.as_array() else {
+        bail!(
+            "Invalid list of features {}. Expected an array but found a {}",
+            tval,
+            tval.type_str()
+        )
+    };
+    features
+        .iter()
+        .map(|tval| {
+            let Some(feature) = tval.as_str() else {
+                bail!(
+                    "Invalid feature {} of type {} found. Expected a string.",
+                    tval,
+                    tval.type_str()
+                )
+            };
+            check_feature_name(feature)?;
+            Ok(PM::FeatureName::from(feature))
+        })
+        .collect()
+}
+
+fn check_feature_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        bail!(
+            "Invalid feature name '{}'. Feature names can only contain ASCII letters, digits, \
+             '_' and '-'",
+            name
+        )
+    }
+    Ok(())
+}
+
+pub fn parse_environments(tval: TV) -> Result<PM::Environments> {
+    match tval {
+        TV::Table(table) => {
+            let mut environments = BTreeMap::new();
+            for (env_name, entry) in table.into_iter() {
+                let environment = parse_environment(&env_name, entry)
+                    .with_context(|| format!("Invalid environment '{}'", env_name))?;
+                environments.insert(PM::EnvironmentName::from(env_name), environment);
+            }
+            Ok(environments)
+        }
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
+fn parse_environment(env_name: &str, tval: TV) -> Result<PM::Environment> {
+    let valid = !env_name.is_empty()
+        && env_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        bail!("Environment names can only contain ASCII letters, digits, '_' and '-'")
+    }
+    match tval {
+        TV::Table(mut table) => {
+            warn_if_unknown_field_names(&table, &[ADDRESSES_NAME, DEPENDENCY_NAME]);
+            let addresses_section =
+                format!("{}.{}.{}", ENVIRONMENTS_NAME, env_name, ADDRESSES_NAME);
+            let addresses = table
+                .remove(ADDRESSES_NAME)
+                .map(|addresses| parse_address_assignments(addresses, &addresses_section))
+                .transpose()
+                .with_context(|| format!("Error parsing '[{}]' section", addresses_section))?
+                .unwrap_or_default();
+            let dependencies = table
+                .remove(DEPENDENCY_NAME)
+                .map(parse_dependencies)
+                .transpose()
+                .with_context(|| {
+                    format!(
+                        "Error parsing '[{}.{}.{}]' section",
+                        ENVIRONMENTS_NAME, env_name, DEPENDENCY_NAME
+                    )
+                })?
+                .unwrap_or_default();
+            Ok(PM::Environment {
+                addresses,
+                dependencies,
+            })
+        }
+        x => bail!("Expected a table, but encountered a {}", x.type_str()),
+    }
+}
+
 pub fn parse_dev_addresses(tval: TV) -> Result<PM::DevAddressDeclarations> {
+    parse_address_assignments(tval, DEV_ADDRESSES_NAME)
+}
+
+/// Parse the named address assignments of the `section` of the manifest, which must all be
+/// instantiated.
+fn parse_address_assignments(tval: TV, section: &str) -> Result<PM::DevAddressDeclarations> {
     match tval {
         TV::Table(table) => {
             let mut addresses = BTreeMap::new();
@@ -266,7 +554,7 @@ pub fn parse_dev_addresses(tval: TV) -> Result<PM::DevAddressDeclarations> {
                     Some(entry_str) => {
                         if entry_str == EMPTY_ADDR_STR {
                             bail!("Found uninstantiated named address '{}'. All addresses in the '{}' field must be instantiated.",
-                            ident, DEV_ADDRESSES_NAME);
+                            ident, section);
                         } else if addresses
                             .insert(
                                 ident,
@@ -319,6 +607,9 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         "rev",
         "subdir",
         "address",
+        "registry",
+        "features",
+        "default-features",
     ];
 
     let custom_key_opt = &package_hooks::custom_dependency_key();
@@ -332,16 +623,30 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         .remove("addr_subst")
         .map(parse_substitution)
         .transpose()?;
-    let version = table.remove("version").map(parse_version).transpose()?;
+    let version = table.remove("version").map(parse_version_req).transpose()?;
     let digest = table.remove("digest").map(parse_digest).transpose()?;
+    let features = table
+        .remove("features")
+        .map(parse_feature_names)
+        .transpose()?
+        .unwrap_or_default();
+    let default_features = match table.remove("default-features") {
+        None => true,
+        Some(TV::Boolean(default_features)) => default_features,
+        Some(x) => bail!(
+            "'default-features' must be a boolean, but found a {}",
+            x.type_str()
+        ),
+    };
 
     let kind = match (
         table.remove("local"),
         table.remove("subdir"),
         table.remove("git"),
         custom_key_opt.as_ref().and_then(|k| table.remove(k)),
+        table.remove("registry"),
     ) {
-        (Some(local), subdir, None, None) => {
+        (Some(local), subdir, None, None, None) => {
             if subdir.is_some() {
                 bail!("'subdir' not supported for local dependencies");
             }
@@ -353,7 +658,7 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             PM::DependencyKind::Local(local)
         }
 
-        (None, subdir, Some(git_url), None) => {
+        (None, subdir, Some(git_url), None, None) => {
             let Some(git_rev) = table.remove("rev") else {
                 bail!("Git revision not supplied for dependency")
             };
@@ -381,7 +686,7 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             })
         }
 
-        (None, subdir, None, Some(custom_key)) => {
+        (None, subdir, None, Some(custom_key), None) => {
             let Some(package_address) = table.remove("address") else {
                 bail!("Address not supplied for 'node' dependency");
             };
@@ -412,8 +717,24 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             })
         }
 
+        (None, subdir, None, None, Some(registry)) => {
+            if subdir.is_some() {
+                bail!("'subdir' not supported for registry dependencies");
+            }
+
+            let Some(registry) = registry.as_str().map(PathBuf::from) else {
+                bail!("Registry path not a string")
+            };
+
+            PM::DependencyKind::Registry(PM::RegistryInfo {
+                registry,
+                package_name: Symbol::from(dep_name),
+                resolved_version: None,
+            })
+        }
+
         _ => {
-            let mut keys = vec!["'local'", "'git'"];
+            let mut keys = vec!["'local'", "'git'", "'registry'"];
             let quoted_custom_key = custom_key_opt.as_ref().map(|k| format!("'{}'", k));
             if let Some(k) = &quoted_custom_key {
                 keys.push(k.as_str())
@@ -430,6 +751,8 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         subst,
         version,
         digest,
+        features,
+        default_features,
     })
 }
 
@@ -466,7 +789,10 @@ fn parse_substitution(tval: TV) -> Result<PM::Substitution> {
 }
 
 fn parse_version(tval: TV) -> Result<PM::Version> {
-    let version_str = tval.as_str().unwrap();
+    parse_version_str(tval.as_str().unwrap())
+}
+
+pub fn parse_version_str(version_str: &str) -> Result<PM::Version> {
     let version_parts = version_str.split('.').collect::<Vec<_>>();
     if version_parts.len() != 3 {
         bail!(
@@ -488,6 +814,13 @@ fn parse_version(tval: TV) -> Result<PM::Version> {
     ))
 }
 
+fn parse_version_req(tval: TV) -> Result<PM::VersionReq> {
+    let req_str = tval
+        .as_str()
+        .ok_or_else(|| format_err!("Version requirement not a string"))?;
+    req_str.parse()
+}
+
 fn parse_architecture(tval: TV) -> Result<Architecture> {
     Architecture::try_parse_from_str(tval.as_str().unwrap())
 }
@@ -2,26 +2,37 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{bail, Result};
+use anyhow::{bail, format_err, Context, Result};
 
 use crate::Architecture;
+use move_compiler::linters::LintLevel;
 use move_core_types::account_address::AccountAddress;
 use move_symbol_pool::symbol::Symbol;
 use std::{
     collections::BTreeMap,
+    fmt,
     path::{Component, Path, PathBuf},
+    str::FromStr,
 };
 
 pub type NamedAddress = Symbol;
 pub type PackageName = Symbol;
 pub type FileName = Symbol;
 pub type PackageDigest = Symbol;
+pub type FeatureName = Symbol;
+pub type EnvironmentName = Symbol;
 
 pub type AddressDeclarations = BTreeMap<NamedAddress, Option<AccountAddress>>;
 pub type DevAddressDeclarations = BTreeMap<NamedAddress, AccountAddress>;
 pub type Version = (u64, u64, u64);
 pub type Dependencies = BTreeMap<PackageName, Dependency>;
 pub type Substitution = BTreeMap<NamedAddress, SubstOrRename>;
+pub type LintLevels = BTreeMap<String, LintLevel>;
+pub type FeatureDeclarations = BTreeMap<FeatureName, Vec<EnabledFeature>>;
+pub type Environments = BTreeMap<EnvironmentName, Environment>;
+
+/// The feature of a package enabled by default, unless its dependents opt out of it
+pub const DEFAULT_FEATURE: &str = "default";
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SourceManifest {
@@ -31,6 +42,27 @@ pub struct SourceManifest {
     pub build: Option<BuildInfo>,
     pub dependencies: Dependencies,
     pub dev_dependencies: Dependencies,
+    pub lints: LintLevels,
+    pub features: FeatureDeclarations,
+    pub environments: Environments,
+}
+
+/// A deployment environment of a package, declared in an `[env.<name>]` section of its manifest
+/// and selected with `--env <name>`.
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct Environment {
+    /// Assignments to the package's named addresses in this environment
+    pub addresses: DevAddressDeclarations,
+    /// Dependencies replacing the dependencies of the same name in this environment
+    pub dependencies: Dependencies,
+}
+
+/// The manifest of a workspace: a `Move.toml` with a `[workspace]` section instead of a
+/// `[package]` one, grouping packages that are resolved and built together.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WorkspaceManifest {
+    /// The paths to the member packages, relative to the workspace root
+    pub members: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -46,8 +78,20 @@ pub struct PackageInfo {
 pub struct Dependency {
     pub kind: DependencyKind,
     pub subst: Option<Substitution>,
-    pub version: Option<Version>,
+    pub version: Option<VersionReq>,
     pub digest: Option<PackageDigest>,
+    /// The features of the dependency to enable, on top of its default feature if
+    /// `default_features` is set
+    pub features: Vec<FeatureName>,
+    pub default_features: bool,
+}
+
+/// What enabling a feature of a package also enables: another one of its features, or a feature
+/// of one of its dependencies (written `<dependency>/<feature>`).
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub enum EnabledFeature {
+    Package(FeatureName),
+    Dependency(PackageName, FeatureName),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -55,6 +99,7 @@ pub enum DependencyKind {
     Local(PathBuf),
     Git(GitInfo),
     Custom(CustomDepInfo),
+    Registry(RegistryInfo),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -81,6 +126,56 @@ pub struct CustomDepInfo {
     pub subdir: PathBuf,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegistryInfo {
+    /// The directory of the registry, containing an index of the versions of each package
+    /// published to it and their sources (see `resolution::registry`)
+    pub registry: PathBuf,
+    /// The package's name (i.e. the dependency name).
+    pub package_name: PackageName,
+    /// The version of the package picked when resolving dependencies, among those matching the
+    /// dependency's version requirement
+    pub resolved_version: Option<Version>,
+}
+
+/// A requirement on the version of a dependency: versions match it if they match all of its
+/// comparators, and any version matches it if it has none (written `*`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VersionReq {
+    pub comparators: Vec<VersionComparator>,
+}
+
+/// A single comparison against a version whose minor and patch numbers may be left out, with the
+/// same meaning as in Cargo manifests, e.g. `^1.2`, `>=0.3`, `~1.2.3` or `1.*`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct VersionComparator {
+    pub op: VersionOp,
+    pub major: u64,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum VersionOp {
+    /// `=I.J.K`, `=I.J`, `=I`: exactly the version, or any version with the given prefix
+    Exact,
+    /// `>I.J.K`, `>I.J`, `>I`
+    Greater,
+    /// `>=I.J.K`, `>=I.J`, `>=I`
+    GreaterEq,
+    /// `<I.J.K`, `<I.J`, `<I`
+    Less,
+    /// `<=I.J.K`, `<=I.J`, `<=I`
+    LessEq,
+    /// `~I.J.K`: at least the version, and only patch updates
+    Tilde,
+    /// `^I.J.K`, or just `I.J.K`: at least the version, and no changes to its leftmost non-zero
+    /// number
+    Caret,
+    /// `I.*`, `I.J.*`: any version with the given prefix
+    Wildcard,
+}
+
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct BuildInfo {
     pub language_version: Option<Version>,
@@ -93,6 +188,24 @@ pub enum SubstOrRename {
     Assign(AccountAddress),
 }
 
+impl SourceManifest {
+    /// The environment named `name` in this package.
+    pub fn environment(&self, name: EnvironmentName) -> Result<&Environment> {
+        self.environments.get(&name).ok_or_else(|| {
+            format_err!(
+                "Environment '{}' is not declared in package '{}'. Expected one of: {}",
+                name,
+                self.package.name,
+                self.environments
+                    .keys()
+                    .map(|env| env.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+        })
+    }
+}
+
 impl DependencyKind {
     /// Given a dependency `self` assumed to be defined relative to a `parent` dependency which can
     /// itself be defined in terms of some grandparent dependency (not provided), update `self` to
@@ -110,6 +223,37 @@ impl DependencyKind {
             // change if referenced relative to some other URI).
             (_, DependencyKind::Git(_) | DependencyKind::Custom(_)) => return Ok(()),
 
+            // Registries are re-rooted like local dependencies, but they remain registry
+            // dependencies, and they can also be given relative to a package from a registry.
+            (_, DependencyKind::Registry(info)) if info.registry.is_absolute() => return Ok(()),
+
+            (DependencyKind::Local(parent), DependencyKind::Registry(info)) => {
+                let mut info = info.clone();
+                info.registry = normalize_path(parent.join(&info.registry), true)?;
+                *self = DependencyKind::Registry(info);
+                return Ok(());
+            }
+
+            (DependencyKind::Registry(parent), DependencyKind::Registry(info)) => {
+                let Some(parent_path) = parent.package_path() else {
+                    bail!("Unresolved version of registry package '{}'", parent.package_name);
+                };
+                let mut info = info.clone();
+                info.registry = normalize_path(parent_path.join(&info.registry), true)?;
+                *self = DependencyKind::Registry(info);
+                return Ok(());
+            }
+
+            (_, DependencyKind::Registry(info)) => bail!(
+                "Registry path must be absolute in dependencies of remote packages: {}",
+                info.registry.to_string_lossy(),
+            ),
+
+            (DependencyKind::Registry(parent), DependencyKind::Local(_)) => bail!(
+                "Local dependencies are not supported in package '{}' from a registry",
+                parent.package_name,
+            ),
+
             (DependencyKind::Local(parent), DependencyKind::Local(subdir)) => {
                 parent.push(subdir);
                 *parent = normalize_path(&parent, /* allow_cwd_parent */ true)?;
@@ -131,6 +275,214 @@ impl DependencyKind {
     }
 }
 
+impl RegistryInfo {
+    /// The path to the index of the versions of this package published to the registry.
+    pub fn index_path(&self) -> PathBuf {
+        let mut path = self.registry.join("index");
+        path.push(format!("{}.toml", self.package_name));
+        path
+    }
+
+    /// The path to the sources of the resolved version of this package in the registry, if it has
+    /// been resolved.
+    pub fn package_path(&self) -> Option<PathBuf> {
+        let (major, minor, patch) = self.resolved_version?;
+        let mut path = self.registry.join("packages");
+        path.push(self.package_name.as_str());
+        path.push(format!("{}.{}.{}", major, minor, patch));
+        Some(path)
+    }
+}
+
+impl VersionReq {
+    /// The requirement matching any version.
+    pub const STAR: VersionReq = VersionReq {
+        comparators: Vec::new(),
+    };
+
+    /// The requirement matching only `version`.
+    pub fn exact(version: Version) -> Self {
+        let (major, minor, patch) = version;
+        VersionReq {
+            comparators: vec![VersionComparator {
+                op: VersionOp::Exact,
+                major,
+                minor: Some(minor),
+                patch: Some(patch),
+            }],
+        }
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.comparators.iter().all(|cmp| cmp.matches(version))
+    }
+
+    /// The only version matching this requirement, if it is of the form `=I.J.K`.
+    pub fn as_exact(&self) -> Option<Version> {
+        match self.comparators.as_slice() {
+            [VersionComparator {
+                op: VersionOp::Exact,
+                major,
+                minor: Some(minor),
+                patch: Some(patch),
+            }] => Some((*major, *minor, *patch)),
+            _ => None,
+        }
+    }
+}
+
+impl VersionComparator {
+    pub fn matches(&self, version: &Version) -> bool {
+        use VersionOp::*;
+        let (major, minor, patch) = *version;
+        let lower = (self.major, self.minor.unwrap_or(0), self.patch.unwrap_or(0));
+        // Whether `version` starts with the numbers given in the comparator
+        let has_prefix = major == self.major
+            && self.minor.iter().all(|&m| m == minor)
+            && self.patch.iter().all(|&p| p == patch);
+
+        match self.op {
+            Exact | Wildcard => has_prefix,
+            Greater => *version > lower && !has_prefix,
+            GreaterEq => *version >= lower,
+            Less => *version < lower,
+            LessEq => *version < lower || has_prefix,
+            Tilde => {
+                *version >= lower && major == self.major && self.minor.iter().all(|&m| m == minor)
+            }
+            Caret => {
+                if *version < lower {
+                    return false;
+                }
+                match (self.major, self.minor, self.patch) {
+                    (0, Some(0), Some(_)) => has_prefix,
+                    (0, Some(m), _) => major == 0 && minor == m,
+                    (_, _, _) => major == self.major,
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s == "*" {
+            return Ok(VersionReq::STAR);
+        }
+
+        let comparators = s
+            .split(',')
+            .map(|cmp| {
+                cmp.parse()
+                    .with_context(|| format!("Invalid version requirement '{}'", s))
+            })
+            .collect::<Result<_>>()?;
+        Ok(VersionReq { comparators })
+    }
+}
+
+impl FromStr for VersionComparator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (op, rest) = [
+            (">=", VersionOp::GreaterEq),
+            ("<=", VersionOp::LessEq),
+            (">", VersionOp::Greater),
+            ("<", VersionOp::Less),
+            ("=", VersionOp::Exact),
+            ("~", VersionOp::Tilde),
+            ("^", VersionOp::Caret),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| Some((Some(op), s.strip_prefix(prefix)?)))
+        .unwrap_or((None, s));
+
+        let mut parts = rest.trim().split('.');
+        let mut numbers = Vec::new();
+        let mut wildcard = false;
+        for part in parts.by_ref() {
+            if part == "*" {
+                wildcard = true;
+                break;
+            }
+            numbers.push(
+                part.parse::<u64>()
+                    .map_err(|_| format_err!("Expected a number but found '{}'", part))?,
+            );
+        }
+
+        if parts.next().is_some() || numbers.len() > 3 {
+            bail!("Versions must be of the form <u64>[.<u64>[.<u64>]]");
+        }
+
+        let op = match (op, wildcard) {
+            (Some(_), true) => bail!("Wildcards cannot be combined with an operator"),
+            (_, true) if numbers.is_empty() => bail!("Wildcards must be used on their own"),
+            (None, true) => VersionOp::Wildcard,
+            (None, false) => VersionOp::Caret,
+            (Some(op), false) => op,
+        };
+
+        let Some(major) = numbers.first().copied() else {
+            bail!("Missing version");
+        };
+
+        Ok(VersionComparator {
+            op,
+            major,
+            minor: numbers.get(1).copied(),
+            patch: numbers.get(2).copied(),
+        })
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut comparators = self.comparators.iter();
+        let Some(first) = comparators.next() else {
+            return f.write_str("*");
+        };
+
+        write!(f, "{}", first)?;
+        for cmp in comparators {
+            write!(f, ", {}", cmp)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for VersionComparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            VersionOp::Exact => "=",
+            VersionOp::Greater => ">",
+            VersionOp::GreaterEq => ">=",
+            VersionOp::Less => "<",
+            VersionOp::LessEq => "<=",
+            VersionOp::Tilde => "~",
+            VersionOp::Caret => "^",
+            VersionOp::Wildcard => "",
+        };
+
+        write!(f, "{}{}", op, self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{}", minor)?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        if self.op == VersionOp::Wildcard {
+            f.write_str(".*")?;
+        }
+        Ok(())
+    }
+}
+
 /// Default `DependencyKind` is the one that acts as the left and right identity to
 /// `DependencyKind::rerooted` (modulo path normalization).
 impl Default for DependencyKind {
@@ -0,0 +1,164 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use move_package::{
+    compilation::{compiled_package::OnDiskCompiledPackage, package_layout::CompiledPackageLayout},
+    resolution::resolution_graph::ResolvedGraph,
+    BuildConfig,
+};
+use std::{fs, path::Path};
+use tempfile::tempdir;
+
+const ENVIRONMENTS: &str = "tests/test_sources/environments";
+
+fn resolve(pkg: &Path, config: BuildConfig) -> anyhow::Result<ResolvedGraph> {
+    config.resolution_graph_for_package(pkg, &mut Vec::new())
+}
+
+fn env(name: &str) -> BuildConfig {
+    BuildConfig {
+        env: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+/// The address that named address `A` resolves to in the root package of `graph`.
+fn address_of_a(graph: &ResolvedGraph) -> AccountAddress {
+    graph.package_table[&"Root".into()].resolution_table[&"A".into()]
+}
+
+#[test]
+fn environment_addresses() {
+    let graph = resolve(Path::new(ENVIRONMENTS), env("mainnet")).unwrap();
+    assert_eq!(
+        address_of_a(&graph),
+        AccountAddress::from_hex_literal("0x1").unwrap()
+    );
+
+    let graph = resolve(Path::new(ENVIRONMENTS), env("testnet")).unwrap();
+    assert_eq!(
+        address_of_a(&graph),
+        AccountAddress::from_hex_literal("0x2").unwrap()
+    );
+}
+
+#[test]
+fn dev_addresses_override_environment_addresses() {
+    let dev_address = AccountAddress::from_hex_literal("0x42").unwrap();
+    let graph = resolve(
+        Path::new(ENVIRONMENTS),
+        BuildConfig {
+            dev_mode: true,
+            ..env("testnet")
+        },
+    )
+    .unwrap();
+    assert_eq!(address_of_a(&graph), dev_address);
+
+    let graph = resolve(
+        Path::new(ENVIRONMENTS),
+        BuildConfig {
+            test_mode: true,
+            ..env("mainnet")
+        },
+    )
+    .unwrap();
+    assert_eq!(address_of_a(&graph), dev_address);
+}
+
+#[test]
+fn environment_dependencies() {
+    let graph = resolve(Path::new(ENVIRONMENTS), env("mainnet")).unwrap();
+    let dep = &graph.package_table[&"Dep".into()];
+    assert!(dep.package_path.ends_with("deps_only/dep"));
+
+    let graph = resolve(Path::new(ENVIRONMENTS), env("testnet")).unwrap();
+    let dep = &graph.package_table[&"Dep".into()];
+    assert!(dep.package_path.ends_with("deps_only/dep_testnet"));
+}
+
+#[test]
+fn undeclared_environment() {
+    let err = resolve(Path::new(ENVIRONMENTS), env("devnet")).unwrap_err();
+    let msg = format!("{:#}", err);
+    assert!(
+        msg.contains(
+            "Environment 'devnet' is not declared in package 'Root'. \
+             Expected one of: mainnet, testnet"
+        ),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn environment_cannot_introduce_addresses() {
+    let pkg = tempdir().unwrap();
+    fs::write(
+        pkg.path().join("Move.toml"),
+        r#"
+            [package]
+            name = "Root"
+            version = "0.0.0"
+
+            [env.testnet.addresses]
+            B = "0x2"
+        "#,
+    )
+    .unwrap();
+
+    let err = resolve(pkg.path(), env("testnet")).unwrap_err();
+    let msg = format!("{:#}", err);
+    assert!(
+        msg.contains("Found unbound address assignment 'B = 0x2' in environment 'testnet'"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn environment_cannot_override_unknown_dependency() {
+    let pkg = tempdir().unwrap();
+    fs::write(
+        pkg.path().join("Move.toml"),
+        r#"
+            [package]
+            name = "Root"
+            version = "0.0.0"
+
+            [env.testnet.dependencies]
+            Dep = { local = "../dep" }
+        "#,
+    )
+    .unwrap();
+
+    let err = resolve(pkg.path(), env("testnet")).unwrap_err();
+    let msg = format!("{:#}", err);
+    assert!(
+        msg.contains("overrides dependency 'Dep', which is not one of its dependencies"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn environment_recorded_in_build_info() {
+    let install_dir = tempdir().unwrap();
+    let config = BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..env("testnet")
+    };
+    config
+        .compile_package(Path::new(ENVIRONMENTS), &mut Vec::new())
+        .unwrap();
+
+    let build_info = install_dir
+        .path()
+        .join(CompiledPackageLayout::Root.path())
+        .join("Root")
+        .join(CompiledPackageLayout::BuildInfo.path());
+    let on_disk = OnDiskCompiledPackage::from_path(&build_info).unwrap();
+    let build_flags = on_disk.package.compiled_package_info.build_flags;
+    assert_eq!(build_flags.env.as_deref(), Some("testnet"));
+}
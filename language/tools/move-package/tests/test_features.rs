@@ -0,0 +1,81 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_package::{resolution::resolution_graph::ResolvedGraph, BuildConfig};
+use std::{collections::BTreeSet, path::Path};
+
+const FEATURES: &str = "tests/test_sources/features";
+
+fn resolve(pkg: &Path, config: BuildConfig) -> anyhow::Result<ResolvedGraph> {
+    config.resolution_graph_for_package(pkg, &mut Vec::new())
+}
+
+/// The features enabled in `package` in `graph`.
+fn features(graph: &ResolvedGraph, package: &str) -> BTreeSet<String> {
+    graph.package_table[&package.into()]
+        .features
+        .iter()
+        .map(|f| f.to_string())
+        .collect()
+}
+
+fn set(features: &[&str]) -> BTreeSet<String> {
+    features.iter().map(|f| f.to_string()).collect()
+}
+
+#[test]
+fn default_features() {
+    let graph = resolve(Path::new(FEATURES), BuildConfig::default()).unwrap();
+    assert_eq!(features(&graph, "Root"), set(&["default", "fast"]));
+    assert_eq!(features(&graph, "Dep"), set(&["extra", "logging"]));
+}
+
+#[test]
+fn no_default_features() {
+    let config = BuildConfig {
+        no_default_features: true,
+        features: vec!["slow".to_string()],
+        ..Default::default()
+    };
+    let graph = resolve(Path::new(FEATURES), config).unwrap();
+    assert_eq!(features(&graph, "Root"), set(&["slow"]));
+    assert_eq!(features(&graph, "Dep"), set(&["logging"]));
+}
+
+#[test]
+fn dependency_features_from_build_options() {
+    let config = BuildConfig {
+        no_default_features: true,
+        features: vec!["Dep/default".to_string()],
+        ..Default::default()
+    };
+    let graph = resolve(Path::new(FEATURES), config).unwrap();
+    assert_eq!(features(&graph, "Root"), set(&[]));
+    assert_eq!(features(&graph, "Dep"), set(&["big", "default", "logging"]));
+}
+
+#[test]
+fn unknown_feature() {
+    let config = BuildConfig {
+        features: vec!["fast".to_string(), "turbo".to_string()],
+        ..Default::default()
+    };
+    let err = resolve(Path::new(FEATURES), config).unwrap_err();
+    let msg = format!("{:#}", err);
+    assert!(
+        msg.contains("Package 'Root' has no feature 'turbo' (requested in the build options)"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn unknown_package() {
+    let config = BuildConfig {
+        features: vec!["Other/fast".to_string()],
+        ..Default::default()
+    };
+    let err = resolve(Path::new(FEATURES), config).unwrap_err();
+    let msg = format!("{:#}", err);
+    assert!(msg.contains("not part of the package graph"), "{}", msg);
+}
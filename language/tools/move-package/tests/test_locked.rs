@@ -0,0 +1,169 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_package::{resolution::resolution_graph::ResolvedGraph, BuildConfig};
+use std::{fs, io, path::Path};
+use tempfile::{tempdir, TempDir};
+
+const REGISTRY_DEPS: &str = "tests/test_sources/registry_deps";
+
+fn resolve(pkg: &Path, config: BuildConfig) -> anyhow::Result<ResolvedGraph> {
+    config.resolution_graph_for_package(pkg, &mut Vec::new())
+}
+
+fn locked() -> BuildConfig {
+    BuildConfig {
+        locked: true,
+        ..Default::default()
+    }
+}
+
+/// Resolve the package at `pkg`, writing its lock file.
+fn write_lock(pkg: &Path) {
+    let config = BuildConfig {
+        lock_file: Some(pkg.join("Move.lock")),
+        ..Default::default()
+    };
+    resolve(pkg, config).unwrap();
+}
+
+/// The version of Math picked in `graph`, from the directory it was read from.
+fn math_version(graph: &ResolvedGraph) -> String {
+    let math = &graph.package_table[&"Math".into()];
+    math.package_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn locked_requires_lock_file() {
+    let pkg = copy_package(REGISTRY_DEPS).unwrap();
+    assert!(!pkg.path().join("Move.lock").exists());
+
+    let err = resolve(pkg.path(), locked()).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("required in --locked mode"),
+        "{:#}",
+        err
+    );
+}
+
+#[test]
+fn locked_keeps_locked_versions() {
+    let pkg = copy_package(REGISTRY_DEPS).unwrap();
+    write_lock(pkg.path());
+    let lock = fs::read_to_string(pkg.path().join("Move.lock")).unwrap();
+
+    // Publish a newer version of Math that Token's requirement allows
+    let registry = pkg.path().join("deps_only/registry");
+    copy_dir(
+        &registry.join("packages/Math/1.0.0"),
+        &registry.join("packages/Math/1.0.5"),
+    )
+    .unwrap();
+    let index = registry.join("index/Math.toml");
+    let mut versions = fs::read_to_string(&index).unwrap();
+    versions.push_str("\n[[version]]\nversion = \"1.0.5\"\n");
+    fs::write(&index, versions).unwrap();
+
+    let graph = resolve(pkg.path(), locked()).unwrap();
+    assert_eq!(math_version(&graph), "1.0.0");
+    assert_eq!(
+        fs::read_to_string(pkg.path().join("Move.lock")).unwrap(),
+        lock
+    );
+
+    let graph = resolve(pkg.path(), BuildConfig::default()).unwrap();
+    assert_eq!(math_version(&graph), "1.0.5");
+}
+
+#[test]
+fn locked_detects_changed_sources() {
+    let pkg = copy_package(REGISTRY_DEPS).unwrap();
+    write_lock(pkg.path());
+    resolve(pkg.path(), locked()).unwrap();
+
+    let source = pkg
+        .path()
+        .join("deps_only/registry/packages/Math/1.0.0/sources/Math.move");
+    let mut contents = fs::read_to_string(&source).unwrap();
+    contents.push_str("\n// Changed after locking\n");
+    fs::write(&source, contents).unwrap();
+
+    let err = resolve(pkg.path(), locked()).unwrap_err();
+    let msg = format!("{:#}", err);
+    assert!(msg.contains("is out of date"), "{}", msg);
+    assert!(
+        msg.contains("The digest of dependency 'Math' has changed"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn locked_detects_changed_manifest() {
+    let pkg = copy_package(REGISTRY_DEPS).unwrap();
+    write_lock(pkg.path());
+
+    let manifest = pkg.path().join("Move.toml");
+    let contents = fs::read_to_string(&manifest)
+        .unwrap()
+        .replace(">=0.3, <0.5", "=0.3.0");
+    fs::write(&manifest, contents).unwrap();
+
+    let err = resolve(pkg.path(), locked()).unwrap_err();
+    let msg = format!("{:#}", err);
+    assert!(
+        msg.contains("The source of dependency 'Token' has changed"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn offline_does_not_fetch() {
+    let pkg = tempdir().unwrap();
+    fs::write(
+        pkg.path().join("Move.toml"),
+        r#"
+            [package]
+            name = "Root"
+            version = "0.0.0"
+
+            [dependencies]
+            NeverFetched = { git = "https://example.com/never-fetched.git", rev = "main" }
+        "#,
+    )
+    .unwrap();
+
+    let config = BuildConfig {
+        offline: true,
+        ..Default::default()
+    };
+    let err = resolve(pkg.path(), config).unwrap_err();
+    let msg = format!("{:#}", err);
+    assert!(msg.contains("cannot be fetched while offline"), "{}", msg);
+}
+
+/// Copy the package at `path` (along with its `deps_only` directory) to a temporary directory.
+fn copy_package(path: &str) -> io::Result<TempDir> {
+    let dir = tempdir()?;
+    copy_dir(Path::new(path), dir.path())?;
+    Ok(dir)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            fs::copy(entry.path(), dest)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_package::{
+    resolution::registry::{published_versions, select_version},
+    source_package::parsed_manifest::{PackageName, RegistryInfo, VersionReq},
+};
+use std::path::{Path, PathBuf};
+
+const REGISTRY: &str = "tests/test_sources/registry_deps/deps_only/registry";
+
+fn req(s: &str) -> VersionReq {
+    s.parse().unwrap()
+}
+
+fn math() -> RegistryInfo {
+    RegistryInfo {
+        registry: PathBuf::from(REGISTRY),
+        package_name: PackageName::from("Math"),
+        resolved_version: None,
+    }
+}
+
+#[test]
+fn version_req_matches() {
+    let cases: &[(&str, &[(u64, u64, u64)], &[(u64, u64, u64)])] = &[
+        ("*", &[(0, 0, 0), (3, 1, 4)], &[]),
+        ("1.2.3", &[(1, 2, 3), (1, 9, 0)], &[(1, 2, 2), (2, 0, 0)]),
+        ("^1.2", &[(1, 2, 0), (1, 3, 7)], &[(1, 1, 9), (2, 0, 0)]),
+        ("^0.2.3", &[(0, 2, 3), (0, 2, 9)], &[(0, 2, 2), (0, 3, 0)]),
+        ("^0.0.3", &[(0, 0, 3)], &[(0, 0, 4)]),
+        ("^0", &[(0, 0, 0), (0, 9, 9)], &[(1, 0, 0)]),
+        ("~1.2.3", &[(1, 2, 3), (1, 2, 9)], &[(1, 2, 2), (1, 3, 0)]),
+        ("~1", &[(1, 0, 0), (1, 9, 0)], &[(2, 0, 0)]),
+        ("=1.2", &[(1, 2, 0), (1, 2, 5)], &[(1, 3, 0)]),
+        (">1.2", &[(1, 3, 0)], &[(1, 2, 9)]),
+        (">1.2.3", &[(1, 2, 4)], &[(1, 2, 3)]),
+        ("<=1.2", &[(1, 2, 9), (0, 1, 0)], &[(1, 3, 0)]),
+        ("<1.2", &[(1, 1, 9)], &[(1, 2, 0)]),
+        (
+            ">=0.3, <0.5",
+            &[(0, 3, 0), (0, 4, 9)],
+            &[(0, 2, 9), (0, 5, 0)],
+        ),
+        ("1.*", &[(1, 0, 0), (1, 7, 2)], &[(2, 0, 0)]),
+        ("1.2.*", &[(1, 2, 0)], &[(1, 3, 0)]),
+    ];
+
+    for (s, matching, not_matching) in cases {
+        let r = req(s);
+        for version in *matching {
+            assert!(r.matches(version), "{} should match {:?}", s, version);
+        }
+        for version in *not_matching {
+            assert!(!r.matches(version), "{} should not match {:?}", s, version);
+        }
+    }
+}
+
+#[test]
+fn version_req_display() {
+    assert_eq!(req("1.2.3").to_string(), "^1.2.3");
+    assert_eq!(req(" >=0.3 ,<0.5").to_string(), ">=0.3, <0.5");
+    assert_eq!(req("1.*").to_string(), "1.*");
+    assert_eq!(req("*").to_string(), "*");
+    assert_eq!(VersionReq::exact((1, 0, 2)).to_string(), "=1.0.2");
+}
+
+#[test]
+fn invalid_version_reqs() {
+    for s in [
+        "",
+        "^",
+        "1.2.3.4",
+        "a.b",
+        ">=1.*",
+        "1.*.3",
+        "*, 1",
+        "1.2.3-beta",
+    ] {
+        assert!(s.parse::<VersionReq>().is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn registry_versions() {
+    let versions = published_versions(Path::new(""), &math()).unwrap();
+    assert_eq!(
+        versions,
+        vec![
+            ((1, 0, 0), false),
+            ((1, 1, 0), false),
+            ((1, 2, 0), true),
+            ((2, 0, 0), false),
+        ]
+    );
+
+    // Yanked versions are never picked
+    let select = |reqs: &[&str]| {
+        let reqs: Vec<_> = reqs.iter().map(|r| req(r)).collect();
+        select_version(Path::new(""), &math(), &reqs)
+    };
+    assert_eq!(select(&[]).unwrap(), (2, 0, 0));
+    assert_eq!(select(&["^1"]).unwrap(), (1, 1, 0));
+    assert_eq!(select(&["^1", "<1.1"]).unwrap(), (1, 0, 0));
+    assert!(select(&["=1.2.0"]).is_err());
+    assert!(select(&["^3"]).is_err());
+
+    let missing = RegistryInfo {
+        package_name: PackageName::from("Missing"),
+        ..math()
+    };
+    assert!(published_versions(Path::new(""), &missing).is_err());
+}
@@ -0,0 +1,3 @@
+module D::Dep {
+    public fun network(): u64 { 1 }
+}
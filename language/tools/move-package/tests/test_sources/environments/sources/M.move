@@ -0,0 +1,7 @@
+module A::M {
+    use D::Dep;
+
+    public fun network(): u64 {
+        Dep::network()
+    }
+}
@@ -0,0 +1,11 @@
+module B::Dep {
+    #[cfg(feature = b"extra")]
+    public fun extra(): u64 { 1 }
+
+    // Not compiled, as the root package turns off the default features of this package
+    #[cfg(feature = b"big")]
+    public fun big(): u64 { undefined() }
+
+    #[cfg(any(feature = b"logging", feature = b"big"))]
+    public fun log() { }
+}
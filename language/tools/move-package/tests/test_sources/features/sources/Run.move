@@ -0,0 +1,18 @@
+module A::Run {
+    use B::Dep;
+
+    #[cfg(feature = b"fast")]
+    public fun run(): u64 {
+        Dep::extra()
+    }
+
+    #[cfg(feature = b"slow")]
+    public fun run(): u64 {
+        Dep::big()
+    }
+
+    #[cfg(all(feature = b"fast", not(feature = b"slow")))]
+    public fun log() {
+        Dep::log()
+    }
+}
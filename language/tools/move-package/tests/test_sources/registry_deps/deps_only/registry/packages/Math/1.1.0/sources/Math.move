@@ -0,0 +1,5 @@
+module math::Math {
+    public fun double(x: u64): u64 {
+        x * 2
+    }
+}
@@ -0,0 +1,7 @@
+module token::Token {
+    use math::Math;
+
+    public fun supply(): u64 {
+        Math::double(50)
+    }
+}
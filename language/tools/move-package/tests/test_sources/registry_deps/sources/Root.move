@@ -0,0 +1,8 @@
+module root::Root {
+    use math::Math;
+    use token::Token;
+
+    public fun supply(): u64 {
+        Math::double(Token::supply())
+    }
+}
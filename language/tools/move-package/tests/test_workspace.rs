@@ -0,0 +1,179 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use move_package::{
+    source_package::{manifest_parser as MP, parsed_manifest::PackageName},
+    BuildConfig,
+};
+use std::{fs, path::Path};
+use tempfile::tempdir;
+
+const WORKSPACE: &str = "tests/workspace_test_sources";
+
+fn package_names<'a>(names: impl IntoIterator<Item = &'a PackageName>) -> Vec<&'a str> {
+    names.into_iter().map(|name| name.as_str()).collect()
+}
+
+#[test]
+fn resolve_all_members() {
+    let install_dir = tempdir().unwrap();
+    let workspace = BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    }
+    .resolve_workspace(Path::new(WORKSPACE), &[], &mut Vec::new())
+    .unwrap();
+    assert_eq!(package_names(&workspace.members), vec!["A", "B"]);
+
+    let graphs = &workspace.resolved_graphs;
+
+    let roots: Vec<_> = graphs
+        .iter()
+        .map(|graph| graph.root_package.package.name)
+        .collect();
+    assert_eq!(package_names(&roots), vec!["A", "B"]);
+
+    // Each member only sees the packages it depends on
+    assert_eq!(
+        package_names(graphs[0].package_table.keys()),
+        vec!["A", "B", "Shared"]
+    );
+    assert_eq!(
+        package_names(graphs[1].package_table.keys()),
+        vec!["B", "Shared"]
+    );
+
+    // The address left unassigned by the shared dependency is unified across the workspace
+    let shared = graphs[0]
+        .extract_named_address_mapping()
+        .find(|(name, _)| name.as_str() == "shared")
+        .unwrap()
+        .1;
+    assert_eq!(shared, AccountAddress::from_hex_literal("0x3").unwrap());
+}
+
+#[test]
+fn select_members() {
+    let config = BuildConfig {
+        install_dir: Some(tempdir().unwrap().path().to_path_buf()),
+        ..Default::default()
+    };
+
+    // The workspace is found from the directory of one of its members
+    let workspace = config
+        .clone()
+        .resolve_workspace(
+            &Path::new(WORKSPACE).join("A"),
+            &["B".to_string()],
+            &mut Vec::new(),
+        )
+        .unwrap();
+    assert_eq!(package_names(&workspace.members), vec!["A", "B"]);
+    assert_eq!(workspace.resolved_graphs.len(), 1);
+    assert_eq!(
+        workspace.resolved_graphs[0]
+            .root_package
+            .package
+            .name
+            .as_str(),
+        "B"
+    );
+
+    let err = config
+        .resolve_workspace(
+            Path::new(WORKSPACE),
+            &["Shared".to_string()],
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("not a member"), "{}", err);
+}
+
+#[test]
+fn compile_into_shared_build_directory() {
+    let install_dir = tempdir().unwrap();
+    let compiled = BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_workspace(Path::new(WORKSPACE), &[], &mut Vec::new())
+    .unwrap();
+
+    assert_eq!(compiled.len(), 2);
+
+    // Building a single member keeps the build output of the others
+    BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_workspace(Path::new(WORKSPACE), &["B".to_string()], &mut Vec::new())
+    .unwrap();
+
+    for member in ["A", "B"] {
+        assert!(install_dir
+            .path()
+            .join("build")
+            .join(member)
+            .join("bytecode_modules")
+            .join(format!("{}.mv", member))
+            .is_file());
+    }
+}
+
+#[test]
+fn single_lock_file() {
+    let install_dir = tempdir().unwrap();
+    let lock_path = install_dir.path().join("Move.lock");
+    BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        lock_file: Some(lock_path.clone()),
+        ..Default::default()
+    }
+    .resolve_workspace(Path::new(WORKSPACE), &[], &mut Vec::new())
+    .unwrap();
+
+    let lock = fs::read_to_string(lock_path).unwrap();
+    assert!(
+        lock.contains("name = \"B\"\nsource = { local = \"B\" }"),
+        "{}",
+        lock
+    );
+    assert!(
+        lock.contains("name = \"Shared\"\nsource = { local = \"deps/Shared\" }"),
+        "{}",
+        lock
+    );
+    assert!(!lock.contains("name = \"A\""), "{}", lock);
+}
+
+#[test]
+fn workspace_is_not_a_package() {
+    let err = BuildConfig::default()
+        .resolution_graph_for_package(Path::new(WORKSPACE), &mut Vec::new())
+        .unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("found the manifest of a workspace"),
+        "{:#}",
+        err
+    );
+}
+
+#[test]
+fn parse_workspace_manifests() {
+    let parse = |manifest: &str| {
+        MP::parse_workspace_manifest(MP::parse_move_manifest_string(manifest.to_string())?)
+    };
+
+    let workspace = parse("[workspace]\nmembers = [\"a\", \"b/c\"]").unwrap();
+    assert_eq!(workspace.members, vec![Path::new("a"), Path::new("b/c")]);
+
+    assert!(parse("[workspace]\nmembers = []").is_err());
+    assert!(parse("[workspace]\nmembers = [1]").is_err());
+    assert!(parse("[workspace]").is_err());
+    assert!(parse("[package]\nname = \"P\"\nversion = \"0.0.0\"").is_err());
+    assert!(
+        parse("[package]\nname = \"P\"\nversion = \"0.0.0\"\n[workspace]\nmembers = [\"a\"]")
+            .is_err()
+    );
+}
@@ -0,0 +1,8 @@
+module a::A {
+    use b::B;
+    use shared::Shared;
+
+    public fun f(): u64 {
+        B::g() + Shared::h()
+    }
+}
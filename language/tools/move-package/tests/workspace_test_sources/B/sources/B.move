@@ -0,0 +1,7 @@
+module b::B {
+    use shared::Shared;
+
+    public fun g(): u64 {
+        Shared::h()
+    }
+}
@@ -0,0 +1,5 @@
+module shared::Shared {
+    public fun h(): u64 {
+        1
+    }
+}
@@ -15,14 +15,18 @@ use move_binary_format::{
 use move_bytecode_utils::layout::TypeLayoutBuilder;
 use move_core_types::{
     account_address::AccountAddress,
-    identifier::{IdentStr, Identifier},
+    identifier::{is_positional_struct, IdentStr, Identifier},
+    json::{JsonMoveValue, JsonMoveValueSeed},
     language_storage::{ModuleId, StructTag, TypeTag},
     resolver::MoveResolver,
     u256,
     value::{MoveStruct, MoveTypeLayout, MoveValue},
     vm_status::VMStatus,
 };
-use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{
+    de::DeserializeSeed,
+    ser::{SerializeMap, SerializeSeq},
+};
 use std::{
     convert::{TryFrom, TryInto},
     fmt::{Display, Formatter},
@@ -120,22 +124,7 @@ impl<'a, T: MoveResolver + ?Sized> MoveValueAnnotator<'a, T> {
             .cache
             .resolve_function_arguments(module, function)?
             .into_iter()
-            .filter(|t| match t {
-                FatType::Signer => false,
-                FatType::Reference(inner) => !matches!(&**inner, FatType::Signer),
-                FatType::Bool
-                | FatType::U8
-                | FatType::U64
-                | FatType::U128
-                | FatType::Address
-                | FatType::Vector(_)
-                | FatType::Struct(_)
-                | FatType::MutableReference(_)
-                | FatType::TyParam(_)
-                | FatType::U16
-                | FatType::U32
-                | FatType::U256 => true,
-            })
+            .filter(|t| !is_signer(t))
             .collect();
         anyhow::ensure!(
             types.len() == args.len(),
@@ -150,6 +139,51 @@ impl<'a, T: MoveResolver + ?Sized> MoveValueAnnotator<'a, T> {
             .collect::<Result<_>>()
     }
 
+    /// Encodes the arguments of `function` given in JSON, see `move_core_types::json`, in BCS as
+    /// expected by `Session::execute_entry_function`. Signer arguments are left out of `args`.
+    pub fn function_arguments_from_json(
+        &self,
+        module: &ModuleId,
+        function: &IdentStr,
+        ty_args: &[TypeTag],
+        args: &[serde_json::Value],
+    ) -> Result<Vec<Vec<u8>>> {
+        let ty_args = ty_args
+            .iter()
+            .map(|ty| self.cache.resolve_type(ty))
+            .collect::<Result<Vec<_>>>()?;
+        let types: Vec<TypeTag> = self
+            .cache
+            .resolve_function_arguments(module, function)?
+            .into_iter()
+            .filter(|t| !is_signer(t))
+            .map(|t| t.subst(&ty_args)?.type_tag())
+            .collect::<Result<_, _>>()
+            .map_err(into_vm_status)?;
+        anyhow::ensure!(
+            types.len() == args.len(),
+            "function {}::{} takes {} arguments but {} were given",
+            module,
+            function,
+            types.len(),
+            args.len(),
+        );
+        types
+            .iter()
+            .zip(args)
+            .map(|(ty, arg)| {
+                let layout = self.get_type_layout_with_types(ty)?;
+                let value = JsonMoveValueSeed(&layout)
+                    .deserialize(arg)
+                    .map_err(|e| anyhow!("invalid argument of type {}: {}", ty, e))?;
+                value
+                    .undecorate()
+                    .simple_serialize()
+                    .ok_or_else(|| anyhow!("failed to serialize argument of type {}", ty))
+            })
+            .collect()
+    }
+
     pub fn view_resource(&self, tag: &StructTag, blob: &[u8]) -> Result<AnnotatedMoveStruct> {
         let ty = self.cache.resolve_struct(tag)?;
         let struct_def = (&ty).try_into().map_err(into_vm_status)?;
@@ -180,6 +214,18 @@ impl<'a, T: MoveResolver + ?Sized> MoveValueAnnotator<'a, T> {
         self.view_value_by_fat_type(&ty, blob)
     }
 
+    /// Returns the JSON encoding of the resource stored as `blob`, see `move_core_types::json`.
+    pub fn view_resource_json(&self, tag: &StructTag, blob: &[u8]) -> Result<serde_json::Value> {
+        self.view_value_json(&TypeTag::Struct(Box::new(tag.clone())), blob)
+    }
+
+    /// Returns the JSON encoding of the value stored as `blob`, see `move_core_types::json`.
+    pub fn view_value_json(&self, ty_tag: &TypeTag, blob: &[u8]) -> Result<serde_json::Value> {
+        let layout = self.get_type_layout_with_types(ty_tag)?;
+        let value = MoveValue::simple_deserialize(blob, &layout)?;
+        Ok(serde_json::to_value(JsonMoveValue::new(&value, &layout))?)
+    }
+
     fn view_value_by_fat_type(&self, ty: &FatType, blob: &[u8]) -> Result<AnnotatedMoveValue> {
         let layout = ty.try_into().map_err(into_vm_status)?;
         let move_value = MoveValue::simple_deserialize(blob, &layout)?;
@@ -248,7 +294,8 @@ impl<'a, T: MoveResolver + ?Sized> MoveValueAnnotator<'a, T> {
             | (MoveValue::Signer(_), _)
             | (MoveValue::U16(_), _)
             | (MoveValue::U32(_), _)
-            | (MoveValue::U256(_), _) => {
+            | (MoveValue::U256(_), _)
+            | (MoveValue::Variant(_), _) => {
                 return Err(anyhow!(
                     "Cannot annotate value {:?} with type {:?}",
                     value,
@@ -259,6 +306,27 @@ impl<'a, T: MoveResolver + ?Sized> MoveValueAnnotator<'a, T> {
     }
 }
 
+/// Returns whether a function parameter of type `ty` is a signer, which is not given as an
+/// argument.
+fn is_signer(ty: &FatType) -> bool {
+    match ty {
+        FatType::Signer => true,
+        FatType::Reference(inner) => matches!(&**inner, FatType::Signer),
+        FatType::Bool
+        | FatType::U8
+        | FatType::U64
+        | FatType::U128
+        | FatType::Address
+        | FatType::Vector(_)
+        | FatType::Struct(_)
+        | FatType::MutableReference(_)
+        | FatType::TyParam(_)
+        | FatType::U16
+        | FatType::U32
+        | FatType::U256 => false,
+    }
+}
+
 fn into_vm_status(e: PartialVMError) -> VMStatus {
     e.finish(Location::Undefined).into_vm_status()
 }
@@ -306,9 +374,15 @@ fn pretty_print_struct(
 ) -> std::fmt::Result {
     pretty_print_ability_modifiers(f, value.abilities)?;
     writeln!(f, "{} {{", value.type_)?;
-    for (field_name, v) in value.value.iter() {
+    // Fields of positional structs are labeled with their index instead of their `pos{idx}` name
+    let positional = is_positional_struct(value.value.iter().map(|(n, _)| n.as_ident_str()));
+    for (idx, (field_name, v)) in value.value.iter().enumerate() {
         write_indent(f, indent + 4)?;
-        write!(f, "{}: ", field_name)?;
+        if positional {
+            write!(f, "{}: ", idx)?;
+        } else {
+            write!(f, "{}: ", field_name)?;
+        }
         pretty_print_value(f, v, indent + 4)?;
         writeln!(f)?;
     }
@@ -9,6 +9,7 @@ pub mod test_runner;
 
 use crate::test_runner::TestRunner;
 use clap::*;
+use move_binary_format::errors::VMResult;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
     self,
@@ -18,7 +19,9 @@ use move_compiler::{
     Compiler, Flags, PASS_CFGIR,
 };
 use move_core_types::language_storage::ModuleId;
-use move_vm_runtime::native_functions::NativeFunctionTable;
+use move_coverage::coverage_map::CoverageMap;
+use move_gas_profiler::GasProfile;
+use move_vm_runtime::{execution_tracer::ExecutionTracer, native_functions::NativeFunctionTable};
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
     collections::BTreeMap,
@@ -223,6 +226,42 @@ impl UnitTestingConfig {
         cost_table: Option<CostTable>,
         writer: W,
     ) -> Result<(W, bool)> {
+        let (writer, ok, _) = self.run_and_report_unit_tests_impl(
+            test_plan,
+            native_function_table,
+            cost_table,
+            writer,
+            false,
+        )?;
+        Ok((writer, ok))
+    }
+
+    /// Like `run_and_report_unit_tests`, but also records the instructions executed by the tests
+    /// in the Move VM, and returns them as a coverage map with one execution per test.
+    pub fn run_and_report_unit_tests_with_coverage<W: Write + Send>(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        cost_table: Option<CostTable>,
+        writer: W,
+    ) -> Result<(W, bool, CoverageMap)> {
+        self.run_and_report_unit_tests_impl(
+            test_plan,
+            native_function_table,
+            cost_table,
+            writer,
+            true,
+        )
+    }
+
+    fn run_and_report_unit_tests_impl<W: Write + Send>(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        cost_table: Option<CostTable>,
+        writer: W,
+        compute_coverage: bool,
+    ) -> Result<(W, bool, CoverageMap)> {
         let shared_writer = Mutex::new(writer);
 
         if self.list {
@@ -236,7 +275,13 @@ impl UnitTestingConfig {
                     )?;
                 }
             }
-            return Ok((shared_writer.into_inner().unwrap(), true));
+            return Ok((
+                shared_writer.into_inner().unwrap(),
+                true,
+                CoverageMap {
+                    exec_maps: BTreeMap::new(),
+                },
+            ));
         }
 
         writeln!(shared_writer.lock().unwrap(), "Running Move unit tests")?;
@@ -252,6 +297,7 @@ impl UnitTestingConfig {
             cost_table,
             verify_and_create_named_address_mapping(self.named_address_values.clone()).unwrap(),
             self.report_writeset,
+            compute_coverage,
             #[cfg(feature = "evm-backend")]
             self.evm,
         )
@@ -270,9 +316,87 @@ impl UnitTestingConfig {
             test_results.report_goldens(&shared_writer)?;
         }
 
+        let coverage_map = test_results.coverage_map().clone();
         let ok = test_results.summarize(&shared_writer)?;
 
         let writer = shared_writer.into_inner().unwrap();
-        Ok((writer, ok))
+        Ok((writer, ok, coverage_map))
+    }
+
+    /// Runs the single unit test `test_name` in the Move VM, reporting its execution to `tracer`.
+    /// The test is named either `<module_name>::<fn_name>` or by its fully qualified
+    /// `<addr>::<module_name>::<fn_name>` name. Returns `None` if there is no such test.
+    pub fn run_unit_test_with_tracer(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        cost_table: Option<CostTable>,
+        test_name: &str,
+        tracer: &mut dyn ExecutionTracer,
+    ) -> Option<VMResult<Vec<Vec<u8>>>> {
+        let (module_id, function_name) = test_plan
+            .module_tests
+            .iter()
+            .flat_map(|(module_id, module_tests)| {
+                module_tests
+                    .tests
+                    .keys()
+                    .map(move |function_name| (module_id, function_name))
+            })
+            .find(|(module_id, function_name)| {
+                test_name == format!("{}::{}", module_id.name(), function_name)
+                    || test_name == format!("{}::{}", format_module_id(module_id), function_name)
+            })
+            .map(|(module_id, function_name)| (module_id.clone(), function_name.to_string()))?;
+
+        let test_runner = TestRunner::new(
+            self.gas_limit.unwrap_or(DEFAULT_EXECUTION_BOUND),
+            1,
+            false,
+            self.verbose,
+            false,
+            self.report_stacktrace_on_abort,
+            test_plan,
+            native_function_table,
+            cost_table,
+            verify_and_create_named_address_mapping(self.named_address_values.clone()).unwrap(),
+            false,
+            false,
+            #[cfg(feature = "evm-backend")]
+            false,
+        )
+        .unwrap();
+        test_runner.execute_test_with_tracer(&module_id, &function_name, tracer)
+    }
+
+    /// Runs the unit tests selected by the filter in the Move VM under a gas profiler, and
+    /// returns the fully qualified name, the result and the gas profile of each test.
+    pub fn profile_unit_tests(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        cost_table: Option<CostTable>,
+    ) -> Vec<(String, VMResult<Vec<Vec<u8>>>, GasProfile)> {
+        let mut test_runner = TestRunner::new(
+            self.gas_limit.unwrap_or(DEFAULT_EXECUTION_BOUND),
+            1,
+            false,
+            self.verbose,
+            false,
+            self.report_stacktrace_on_abort,
+            test_plan,
+            native_function_table,
+            cost_table,
+            verify_and_create_named_address_mapping(self.named_address_values.clone()).unwrap(),
+            false,
+            false,
+            #[cfg(feature = "evm-backend")]
+            false,
+        )
+        .unwrap();
+        if let Some(filter_str) = &self.filter {
+            test_runner.filter(filter_str)
+        }
+        test_runner.profile_tests()
     }
 }
@@ -15,6 +15,7 @@ use move_compiler::{
     unit_test::{ModuleTestPlan, TestName, TestPlan},
 };
 use move_core_types::{effects::ChangeSet, language_storage::ModuleId, vm_status::StatusType};
+use move_coverage::coverage_map::CoverageMap;
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
 use std::{
@@ -73,6 +74,7 @@ pub struct TestStatistics {
     passed: BTreeMap<ModuleId, BTreeSet<TestRunInfo>>,
     failed: BTreeMap<ModuleId, BTreeSet<TestFailure>>,
     output: BTreeMap<ModuleId, BTreeMap<TestName, String>>,
+    coverage_map: CoverageMap,
 }
 
 #[derive(Debug, Clone)]
@@ -396,6 +398,9 @@ impl TestStatistics {
             passed: BTreeMap::new(),
             failed: BTreeMap::new(),
             output: BTreeMap::new(),
+            coverage_map: CoverageMap {
+                exec_maps: BTreeMap::new(),
+            },
         }
     }
 
@@ -420,6 +425,11 @@ impl TestStatistics {
             .insert(test_name, output);
     }
 
+    /// Adds the executions in `coverage_map`, one per test, to the coverage of the tests.
+    pub fn test_coverage(&mut self, coverage_map: CoverageMap) {
+        self.coverage_map.exec_maps.extend(coverage_map.exec_maps);
+    }
+
     pub fn combine(mut self, other: Self) -> Self {
         for (module_id, test_result) in other.passed {
             let entry = self.passed.entry(module_id).or_default();
@@ -433,6 +443,7 @@ impl TestStatistics {
             let entry = self.output.entry(module_id).or_default();
             entry.extend(test_output.into_iter());
         }
+        self.test_coverage(other.coverage_map);
         self
     }
 }
@@ -445,6 +456,11 @@ impl TestResults {
         }
     }
 
+    /// The coverage of the tests run in the Move VM, empty unless the runner computed it.
+    pub fn coverage_map(&self) -> &CoverageMap {
+        &self.final_statistics.coverage_map
+    }
+
     pub fn report_goldens<W: Write>(&self, writer: &Mutex<W>) -> Result<()> {
         for (module_name, test_outputs) in self.final_statistics.output.iter() {
             for (test_name, write_set) in test_outputs.iter() {
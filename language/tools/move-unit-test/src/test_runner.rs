@@ -25,9 +25,12 @@ use move_core_types::{
     account_address::AccountAddress,
     effects::{ChangeSet, Op},
     identifier::IdentStr,
+    language_storage::ModuleId,
     value::serialize_values,
     vm_status::StatusCode,
 };
+use move_coverage::coverage_tracer::CoverageTracer;
+use move_gas_profiler::{GasProfile, GasProfiler};
 use move_model::{
     model::GlobalEnv, options::ModelBuilderOptions,
     run_model_builder_with_options_and_compilation_flags,
@@ -38,7 +41,9 @@ use move_stackless_bytecode_interpreter::{
     shared::bridge::{adapt_move_vm_change_set, adapt_move_vm_result},
     StacklessBytecodeInterpreter,
 };
-use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
+use move_vm_runtime::{
+    execution_tracer::ExecutionTracer, move_vm::MoveVM, native_functions::NativeFunctionTable,
+};
 use move_vm_test_utils::{
     gas_schedule::{zero_cost_schedule, CostTable, Gas, GasCost, GasStatus},
     InMemoryStorage,
@@ -70,6 +75,7 @@ pub struct SharedTestingConfig {
     check_stackless_vm: bool,
     verbose: bool,
     record_writeset: bool,
+    compute_coverage: bool,
 
     #[cfg(feature = "evm-backend")]
     evm: bool,
@@ -153,6 +159,7 @@ impl TestRunner {
         cost_table: Option<CostTable>,
         named_address_values: BTreeMap<String, NumericalAddress>,
         record_writeset: bool,
+        compute_coverage: bool,
         #[cfg(feature = "evm-backend")] evm: bool,
     ) -> Result<Self> {
         let source_files = tests
@@ -186,6 +193,7 @@ impl TestRunner {
                 verbose,
                 named_address_values,
                 record_writeset,
+                compute_coverage,
                 #[cfg(feature = "evm-backend")]
                 evm,
             },
@@ -211,6 +219,52 @@ impl TestRunner {
             })
     }
 
+    /// Executes the test `function_name` of module `module_id` in the Move VM with `tracer`
+    /// installed, and returns the result of the test function. Returns `None` if the test plan
+    /// contains no such test.
+    pub fn execute_test_with_tracer(
+        &self,
+        module_id: &ModuleId,
+        function_name: &str,
+        tracer: &mut dyn ExecutionTracer,
+    ) -> Option<VMResult<Vec<Vec<u8>>>> {
+        let test_plan = self.tests.module_tests.get(module_id)?;
+        let test_info = test_plan.tests.get(function_name)?;
+        let (_, _, exec_result, _) = self.testing_config.execute_via_move_vm(
+            test_plan,
+            function_name,
+            test_info,
+            Some(tracer),
+        );
+        Some(exec_result)
+    }
+
+    /// Executes every test of the test plan in the Move VM under a gas profiler. Returns the
+    /// fully qualified name, the result and the gas profile of each test.
+    pub fn profile_tests(&self) -> Vec<(String, VMResult<Vec<Vec<u8>>>, GasProfile)> {
+        self.tests
+            .module_tests
+            .iter()
+            .flat_map(|(module_id, test_plan)| {
+                test_plan
+                    .tests
+                    .iter()
+                    .map(move |(function_name, test_info)| {
+                        let (exec_result, profile) = self.testing_config.profile_via_move_vm(
+                            test_plan,
+                            function_name,
+                            test_info,
+                        );
+                        (
+                            format!("{}::{}", format_module_id(module_id), function_name),
+                            exec_result,
+                            profile,
+                        )
+                    })
+            })
+            .collect()
+    }
+
     pub fn filter(&mut self, test_name_slice: &str) {
         for (module_id, module_test) in self.tests.module_tests.iter_mut() {
             if module_id.name().as_str().contains(test_name_slice) {
@@ -272,21 +326,28 @@ impl<'a, 'b, W: Write> TestOutput<'a, 'b, W> {
 }
 
 impl SharedTestingConfig {
-    fn execute_via_move_vm(
-        &self,
+    fn execute_via_move_vm<'a>(
+        &'a self,
         test_plan: &ModuleTestPlan,
         function_name: &str,
         test_info: &TestCase,
+        tracer: Option<&'a mut dyn ExecutionTracer>,
     ) -> (
         VMResult<ChangeSet>,
-        VMResult<NativeContextExtensions>,
+        VMResult<NativeContextExtensions<'a>>,
         VMResult<Vec<Vec<u8>>>,
         TestRunInfo,
     ) {
         let move_vm = MoveVM::new(self.native_function_table.clone()).unwrap();
         let extensions = extensions::new_extensions();
-        let mut session =
-            move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
+        let mut session = match tracer {
+            Some(tracer) => move_vm.new_session_with_extensions_and_tracer(
+                &self.starting_storage_state,
+                extensions,
+                tracer,
+            ),
+            None => move_vm.new_session_with_extensions(&self.starting_storage_state, extensions),
+        };
         let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
         // TODO: collect VM logs if the verbose flag (i.e, `self.verbose`) is set
 
@@ -325,6 +386,43 @@ impl SharedTestingConfig {
         }
     }
 
+    fn profile_via_move_vm(
+        &self,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+    ) -> (VMResult<Vec<Vec<u8>>>, GasProfile) {
+        let profiler = GasProfiler::new();
+        let mut tracer = profiler.tracer();
+        let move_vm = MoveVM::new(self.native_function_table.clone()).unwrap();
+        let mut session = move_vm.new_session_with_extensions_and_tracer(
+            &self.starting_storage_state,
+            extensions::new_extensions(),
+            &mut tracer,
+        );
+        let mut gas_meter = profiler.gas_meter(GasStatus::new(
+            &self.cost_table,
+            Gas::new(self.execution_bound),
+        ));
+
+        let return_result = session
+            .execute_function_bypass_visibility(
+                &test_plan.module_id,
+                IdentStr::new(function_name).unwrap(),
+                vec![],
+                serialize_values(test_info.arguments.iter()),
+                &mut gas_meter,
+            )
+            .map(|res| {
+                res.return_values
+                    .into_iter()
+                    .map(|(bytes, _layout)| bytes)
+                    .collect()
+            });
+        drop(session);
+        (return_result, profiler.into_profile())
+    }
+
     fn execute_via_stackless_vm(
         &self,
         env: &GlobalEnv,
@@ -414,10 +512,27 @@ impl SharedTestingConfig {
         };
 
         let mut stats = TestStatistics::new();
+        // Records the coverage of each test of the module as a separate execution
+        let mut coverage_tracer = self
+            .compute_coverage
+            .then(|| CoverageTracer::new(String::new()));
 
         for (function_name, test_info) in &test_plan.tests {
-            let (cs_result, ext_result, exec_result, test_run_info) =
-                self.execute_via_move_vm(test_plan, function_name, test_info);
+            if let Some(tracer) = &mut coverage_tracer {
+                tracer.set_exec_id(format!(
+                    "{}::{}",
+                    format_module_id(&test_plan.module_id),
+                    function_name
+                ));
+            }
+            let (cs_result, ext_result, exec_result, test_run_info) = self.execute_via_move_vm(
+                test_plan,
+                function_name,
+                test_info,
+                coverage_tracer
+                    .as_mut()
+                    .map(|tracer| tracer as &mut dyn ExecutionTracer),
+            );
 
             if self.record_writeset {
                 stats.test_output(
@@ -590,6 +705,10 @@ impl SharedTestingConfig {
             }
         }
 
+        if let Some(tracer) = coverage_tracer {
+            let (coverage_map, _) = tracer.into_maps();
+            stats.test_coverage(coverage_map);
+        }
         stats
     }
 
@@ -0,0 +1,46 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+use move_unit_test::{self, UnitTestingConfig};
+use std::path::PathBuf;
+
+// Make sure the coverage map built by the coverage tracer records each test as its own execution,
+// including the code it runs in dependencies.
+#[test]
+fn test_coverage_map_from_tracer() {
+    let mut testing_config = UnitTestingConfig::default_with_bound(None)
+        .with_named_addresses(move_stdlib::move_stdlib_named_addresses());
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let a_path = path.join("tests/sources/A.move");
+    let b_path = path.join("tests/sources/B.move");
+    let mut deps = move_stdlib::move_stdlib_files();
+    deps.push(a_path.to_string_lossy().to_string());
+
+    testing_config.source_files = vec![b_path.to_str().unwrap().to_owned()];
+    testing_config.dep_files = deps;
+
+    let test_plan = testing_config.build_test_plan().unwrap();
+    let (_, _, coverage_map) = testing_config
+        .run_and_report_unit_tests_with_coverage(test_plan, None, None, Vec::new())
+        .unwrap();
+
+    let exec_ids: Vec<_> = coverage_map.exec_maps.keys().cloned().collect();
+    assert_eq!(
+        exec_ids,
+        vec![
+            "0x1::B::b",
+            "0x1::B::b_other",
+            "0x1::B::b_other0",
+            "0x1::B::b_other1"
+        ]
+    );
+
+    let addr = AccountAddress::from_hex_literal("0x1").unwrap();
+    let exec_map = &coverage_map.exec_maps["0x1::B::b_other0"];
+    let b_functions = &exec_map.module_maps[&(addr, Identifier::new("B").unwrap())].function_maps;
+    let a_functions = &exec_map.module_maps[&(addr, Identifier::new("A").unwrap())].function_maps;
+    assert!(!b_functions[&Identifier::new("b_other0").unwrap()].is_empty());
+    assert!(!a_functions[&Identifier::new("a_call").unwrap()].is_empty());
+    assert!(!b_functions.contains_key(&Identifier::new("b_other").unwrap()));
+}